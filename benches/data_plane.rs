@@ -0,0 +1,201 @@
+//! Data-plane hot paths against a mocked Service Bus endpoint.
+//!
+//! The client talks plain HTTP to a local wiremock server through the
+//! emulator connection-string mode, so these numbers measure our request
+//! handling rather than the broker. `peek_messages` issues a peek-lock and an
+//! abandon per message, up to 16 at a time; the 10/100/1000 groups show how
+//! that cost grows with the count.
+//!
+//! Run with `cargo bench --bench data_plane`.
+
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use tokio::runtime::Runtime;
+use wiremock::matchers::{method, path, path_regex};
+use wiremock::{Mock, MockServer, Request, Respond, ResponseTemplate};
+
+use service_bus_rest_client as client;
+
+use client::models::ServiceBusMessage;
+use client::{ConnectionConfig, DataPlaneClient};
+
+const QUEUE: &str = "bench-queue";
+const BROKER_PROPERTIES: &str = r#"{"MessageId":"bench","SequenceNumber":1,"DeliveryCount":1}"#;
+
+fn runtime() -> Runtime {
+    tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .expect("tokio runtime")
+}
+
+fn start_server(rt: &Runtime) -> MockServer {
+    rt.block_on(MockServer::builder().disable_request_recording().start())
+}
+
+fn client_for(server: &MockServer) -> DataPlaneClient {
+    let addr = server.address();
+    let conn_str = format!(
+        "Endpoint=sb://{}:{};SharedAccessKeyName=RootManageSharedAccessKey;SharedAccessKey=SAS_KEY_VALUE;UseDevelopmentEmulator=true;",
+        addr.ip(),
+        addr.port()
+    );
+    DataPlaneClient::new(
+        ConnectionConfig::from_connection_string(&conn_str).unwrap(),
+        Arc::new(reqwest::Client::new()),
+    )
+}
+
+fn message_response(status: u16) -> ResponseTemplate {
+    ResponseTemplate::new(status)
+        .insert_header("BrokerProperties", BROKER_PROPERTIES)
+        .insert_header("Content-Type", "application/json")
+        .set_body_string(r#"{"order":42}"#)
+}
+
+/// Peek-lock responses with increasing sequence numbers, so every lock
+/// counts as a distinct message.
+struct SequencedMessages {
+    next: AtomicI64,
+    lock_uri: String,
+}
+
+impl Respond for SequencedMessages {
+    fn respond(&self, _: &Request) -> ResponseTemplate {
+        let seq = self.next.fetch_add(1, Ordering::Relaxed);
+        ResponseTemplate::new(201)
+            .insert_header(
+                "BrokerProperties",
+                format!(
+                    r#"{{"MessageId":"bench-{0}","SequenceNumber":{0},"DeliveryCount":1}}"#,
+                    seq
+                )
+                .as_str(),
+            )
+            .insert_header("Content-Type", "application/json")
+            .insert_header("Location", self.lock_uri.as_str())
+            .set_body_string(r#"{"order":42}"#)
+    }
+}
+
+/// Receive-and-delete workers draining a fixed number of messages.
+fn bench_purge(c: &mut Criterion) {
+    const MESSAGES: u64 = 500;
+
+    let rt = runtime();
+    let server = start_server(&rt);
+    let dp = client_for(&server);
+    let head = format!("/{}/messages/head", QUEUE);
+
+    let mut group = c.benchmark_group("purge_concurrent");
+    group.sample_size(10);
+    group.throughput(Throughput::Elements(MESSAGES));
+    for concurrency in [1usize, 8, 32] {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(concurrency),
+            &concurrency,
+            |b, &concurrency| {
+                b.iter_custom(|iters| {
+                    rt.block_on(async {
+                        let mut total = Duration::ZERO;
+                        for _ in 0..iters {
+                            // Fresh queue of MESSAGES, then empty (204)
+                            server.reset().await;
+                            Mock::given(method("DELETE"))
+                                .and(path(head.as_str()))
+                                .respond_with(message_response(200))
+                                .up_to_n_times(MESSAGES)
+                                .with_priority(1)
+                                .mount(&server)
+                                .await;
+                            Mock::given(method("DELETE"))
+                                .and(path(head.as_str()))
+                                .respond_with(ResponseTemplate::new(204))
+                                .with_priority(2)
+                                .mount(&server)
+                                .await;
+
+                            let start = Instant::now();
+                            let purged = dp
+                                .purge_concurrent(QUEUE, concurrency, None, None)
+                                .await
+                                .unwrap();
+                            total += start.elapsed();
+                            assert_eq!(purged, MESSAGES);
+                        }
+                        total
+                    })
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+/// Per-message send latency (criterion reports the distribution).
+fn bench_send(c: &mut Criterion) {
+    let rt = runtime();
+    let server = start_server(&rt);
+    let dp = client_for(&server);
+    rt.block_on(
+        Mock::given(method("POST"))
+            .and(path(format!("/{}/messages", QUEUE)))
+            .respond_with(ResponseTemplate::new(201))
+            .mount(&server),
+    );
+
+    let message = ServiceBusMessage {
+        body: r#"{"order":42,"lines":[{"sku":"A-1","qty":3}]}"#.to_string(),
+        content_type: Some("application/json".to_string()),
+        message_id: Some("bench-message".to_string()),
+        label: Some("order-created".to_string()),
+        custom_properties: vec![("tenant".to_string(), "contoso".to_string())],
+        ..Default::default()
+    };
+
+    c.bench_function("send_message", |b| {
+        b.iter(|| rt.block_on(dp.send_message(QUEUE, &message)).unwrap())
+    });
+}
+
+/// Parallel peek-lock + abandon: two requests per peeked message.
+fn bench_peek(c: &mut Criterion) {
+    let rt = runtime();
+    let server = start_server(&rt);
+    let dp = client_for(&server);
+    let lock_uri = format!("{}/{}/messages/1/bench-lock", server.uri(), QUEUE);
+    rt.block_on(async {
+        Mock::given(method("POST"))
+            .and(path(format!("/{}/messages/head", QUEUE)))
+            .respond_with(SequencedMessages {
+                next: AtomicI64::new(1),
+                lock_uri: lock_uri.clone(),
+            })
+            .mount(&server)
+            .await;
+        Mock::given(method("PUT"))
+            .and(path_regex(format!("^/{}/messages/.+", QUEUE)))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+    });
+
+    let mut group = c.benchmark_group("peek_messages");
+    group.sample_size(10);
+    for count in [10i32, 100, 1000] {
+        group.throughput(Throughput::Elements(count as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(count), &count, |b, &count| {
+            b.iter(|| {
+                let peeked = rt.block_on(dp.peek_messages(QUEUE, count)).unwrap();
+                assert_eq!(peeked.len(), count as usize);
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_purge, bench_send, bench_peek);
+criterion_main!(benches);
@@ -0,0 +1,35 @@
+use std::path::Path;
+use std::process::Command;
+
+/// Stamp the git commit and build date into the binary for `--version`.
+fn main() {
+    let git_hash = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()
+        .filter(|out| out.status.success())
+        .and_then(|out| String::from_utf8(out.stdout).ok())
+        .map(|hash| hash.trim().to_string())
+        .filter(|hash| !hash.is_empty())
+        .unwrap_or_else(|| "unknown".to_string());
+    // SOURCE_DATE_EPOCH keeps reproducible builds reproducible
+    let build_time = std::env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|secs| secs.parse::<i64>().ok())
+        .and_then(|secs| chrono::DateTime::from_timestamp(secs, 0))
+        .unwrap_or_else(chrono::Utc::now);
+    let build_date = build_time.format("%Y-%m-%d").to_string();
+
+    println!("cargo:rustc-env=CARGO_BUILD_GIT_HASH={}", git_hash);
+    println!("cargo:rustc-env=CARGO_BUILD_DATE={}", build_date);
+    // Re-stamp when HEAD moves (new commit or checkout). Only watch paths that
+    // exist: cargo treats a missing one as always changed and would rerun
+    // this script on every build of a source tarball.
+    for path in [".git/HEAD", ".git/refs/heads"] {
+        if Path::new(path).exists() {
+            println!("cargo:rerun-if-changed={}", path);
+        }
+    }
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH");
+}
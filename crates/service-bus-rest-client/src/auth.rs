@@ -0,0 +1,493 @@
+//! Connection settings and request signing: SAS tokens from a connection
+//! string, or Azure AD bearer tokens with the `azure-ad` feature.
+
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+#[cfg(feature = "azure-ad")]
+use azure_core::credentials::TokenCredential;
+
+#[cfg(feature = "demo")]
+use super::demo::{DemoNamespace, DEMO_NAMESPACE};
+use super::error::{api_error, is_token_expiry, Result, ServiceBusError};
+use super::stats::{is_failure_status, Api, RequestStats};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// The Service Bus token audience used for Azure AD authentication.
+#[cfg(feature = "azure-ad")]
+const SERVICE_BUS_SCOPE: &str = "https://servicebus.azure.net/.default";
+
+/// Well-known connection string for the local Service Bus emulator.
+pub const EMULATOR_CONNECTION_STRING: &str = "Endpoint=sb://localhost;SharedAccessKeyName=RootManageSharedAccessKey;SharedAccessKey=SAS_KEY_VALUE;UseDevelopmentEmulator=true";
+
+/// Port the emulator listens on when the endpoint doesn't specify one.
+const EMULATOR_PORT: u16 = 5672;
+
+/// Difference between the local clock and the server's `Date` header above
+/// which a rejected SAS token is blamed on clock skew.
+pub const CLOCK_SKEW_THRESHOLD_SECS: i64 = 300;
+
+/// Seconds the server's clock (its `Date` header, RFC 1123) is ahead of
+/// `local`; negative when the local clock is ahead.
+pub fn clock_skew_secs(server_date: &str, local: DateTime<Utc>) -> Option<i64> {
+    let server = DateTime::parse_from_rfc2822(server_date.trim()).ok()?;
+    Some(server.timestamp() - local.timestamp())
+}
+
+/// `se=` of a SAS token created at local time `now` (Unix seconds): the
+/// validity counted from the server's clock, `now + offset_secs`.
+fn sas_expiry(now: i64, offset_secs: i64, validity_secs: u64) -> u64 {
+    (now + offset_secs).max(0) as u64 + validity_secs
+}
+
+/// Authentication mode — either SAS key-based or Azure AD (Microsoft Entra ID).
+#[derive(Clone)]
+pub enum AuthMode {
+    /// Shared access signature from a policy key.
+    Sas {
+        /// Policy name (`SharedAccessKeyName`).
+        shared_access_key_name: String,
+        /// Base64 policy key (`SharedAccessKey`).
+        shared_access_key: String,
+    },
+    /// Requires the `azure-ad` feature.
+    #[cfg(feature = "azure-ad")]
+    AzureAd {
+        /// Source of bearer tokens for `https://servicebus.azure.net`.
+        credential: Arc<dyn TokenCredential>,
+    },
+}
+
+impl std::fmt::Debug for AuthMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Sas {
+                shared_access_key_name,
+                ..
+            } => f
+                .debug_struct("Sas")
+                .field("shared_access_key_name", shared_access_key_name)
+                .finish(),
+            #[cfg(feature = "azure-ad")]
+            Self::AzureAd { .. } => f.write_str("AzureAd"),
+        }
+    }
+}
+
+/// Parsed components from a Service Bus connection string or Azure AD config.
+#[derive(Debug, Clone)]
+pub struct ConnectionConfig {
+    /// Host name of the namespace, e.g. `myns.servicebus.windows.net`.
+    pub namespace: String,
+    /// Base URL requests go to, e.g. `https://myns.servicebus.windows.net`.
+    pub endpoint: String,
+    /// How requests are signed.
+    pub auth_mode: AuthMode,
+    /// Set by `UseDevelopmentEmulator=true`: plain HTTP against a local emulator.
+    pub is_emulator: bool,
+    /// Set by `EntityPath=<queue or topic>`: the key only grants access to
+    /// that entity, so every token is scoped to it.
+    pub entity_path: Option<String>,
+    /// Server clock minus local clock in seconds, learned from a SAS token
+    /// rejected as expired; added to the time new tokens are based on.
+    /// Shared by every clone of the config.
+    clock_offset: Arc<AtomicI64>,
+    /// Latency and failures of recent requests, shared like `clock_offset`.
+    stats: Arc<RequestStats>,
+}
+
+impl ConnectionConfig {
+    /// Parse a standard Service Bus connection string (SAS auth).
+    ///
+    /// Expected format:
+    /// `Endpoint=sb://<namespace>.servicebus.windows.net/;SharedAccessKeyName=<name>;SharedAccessKey=<key>`
+    ///
+    /// With `UseDevelopmentEmulator=true` the endpoint is reached over plain
+    /// HTTP (default port 5672) instead of HTTPS. An `EntityPath=<name>`
+    /// component marks a key scoped to that queue or topic.
+    pub fn from_connection_string(conn_str: &str) -> Result<Self> {
+        let mut endpoint = None;
+        let mut key_name = None;
+        let mut key = None;
+        let mut is_emulator = false;
+        let mut entity_path = None;
+
+        for part in conn_str.split(';') {
+            let part = part.trim();
+            if part.is_empty() {
+                continue;
+            }
+            if let Some((k, v)) = part.split_once('=') {
+                match k.trim() {
+                    "Endpoint" => endpoint = Some(v.trim().to_string()),
+                    "SharedAccessKeyName" => key_name = Some(v.trim().to_string()),
+                    // Key value may contain '=' (base64 padding)
+                    "SharedAccessKey" => {
+                        let idx = part.find('=').unwrap();
+                        key = Some(part[idx + 1..].trim().to_string());
+                    }
+                    "UseDevelopmentEmulator" => {
+                        is_emulator = v.trim().eq_ignore_ascii_case("true");
+                    }
+                    "EntityPath" => {
+                        let v = v.trim().trim_matches('/');
+                        if !v.is_empty() {
+                            entity_path = Some(v.to_string());
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let endpoint = endpoint
+            .ok_or_else(|| ServiceBusError::InvalidConnectionString("missing Endpoint".into()))?;
+        let key_name = key_name.ok_or_else(|| {
+            ServiceBusError::InvalidConnectionString("missing SharedAccessKeyName".into())
+        })?;
+        let key = key.ok_or_else(|| {
+            ServiceBusError::InvalidConnectionString("missing SharedAccessKey".into())
+        })?;
+
+        // Extract namespace from endpoint like sb://mynamespace.servicebus.windows.net/
+        let namespace = endpoint
+            .trim_start_matches("sb://")
+            .trim_end_matches('/')
+            .to_string();
+
+        // Normalize endpoint to https:// (emulator: http://host:port)
+        let endpoint = if is_emulator {
+            if namespace.contains(':') {
+                format!("http://{}", namespace)
+            } else {
+                format!("http://{}:{}", namespace, EMULATOR_PORT)
+            }
+        } else {
+            format!("https://{}", namespace)
+        };
+
+        Ok(Self {
+            namespace,
+            endpoint,
+            auth_mode: AuthMode::Sas {
+                shared_access_key_name: key_name,
+                shared_access_key: key,
+            },
+            is_emulator,
+            entity_path,
+            clock_offset: Arc::new(AtomicI64::new(0)),
+            stats: Arc::new(RequestStats::default()),
+        })
+    }
+
+    /// The config to build clients on `namespace` with (their transport is
+    /// the namespace itself, see `ManagementClient::with_transport`). Meant
+    /// for trying the client out and for tests (`demo` feature).
+    #[cfg(feature = "demo")]
+    pub fn demo(namespace: &DemoNamespace) -> Self {
+        Self {
+            namespace: DEMO_NAMESPACE.to_string(),
+            endpoint: format!("https://{}", DEMO_NAMESPACE),
+            auth_mode: AuthMode::Sas {
+                shared_access_key_name: "RootManageSharedAccessKey".to_string(),
+                shared_access_key: "demo".to_string(),
+            },
+            is_emulator: false,
+            entity_path: None,
+            clock_offset: Arc::new(AtomicI64::new(0)),
+            stats: namespace.stats(),
+        }
+    }
+
+    /// Convert a transport error, turning "connection refused" against the
+    /// emulator into a clearer `EmulatorNotRunning`.
+    pub fn http_error(&self, err: reqwest::Error) -> ServiceBusError {
+        if self.is_emulator && err.is_connect() {
+            ServiceBusError::EmulatorNotRunning(self.endpoint.clone())
+        } else {
+            ServiceBusError::Http(err)
+        }
+    }
+
+    /// Rolling latency and error figures of the requests made with this
+    /// config (and its clones).
+    pub fn request_stats(&self) -> &RequestStats {
+        &self.stats
+    }
+
+    /// Send a request to `api`, recording its latency and outcome. An empty
+    /// receive (204) waited out its server-side timeout, so only its outcome
+    /// counts.
+    pub(crate) async fn send(
+        &self,
+        api: Api,
+        req: reqwest::RequestBuilder,
+    ) -> Result<reqwest::Response> {
+        let started = Instant::now();
+        match req.send().await {
+            Ok(resp) => {
+                let status = resp.status().as_u16();
+                let latency = (status != 204).then(|| started.elapsed());
+                self.stats.record(api, latency, is_failure_status(status));
+                Ok(resp)
+            }
+            Err(e) => {
+                self.stats.record(api, None, true);
+                Err(self.http_error(e))
+            }
+        }
+    }
+
+    /// Clock skew compensated for in SAS tokens (server minus local, seconds).
+    pub fn clock_offset_secs(&self) -> i64 {
+        self.clock_offset.load(Ordering::Relaxed)
+    }
+
+    /// Error for a failed response. A SAS token rejected as expired while
+    /// the server's `Date` is more than `CLOCK_SKEW_THRESHOLD_SECS` away from
+    /// the local clock becomes `ClockSkew`, and later tokens are offset by
+    /// the skew so the rest of the session works.
+    pub async fn response_error(&self, resp: reqwest::Response) -> ServiceBusError {
+        let status = resp.status().as_u16();
+        let server_date = resp
+            .headers()
+            .get(reqwest::header::DATE)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let body = match resp.text().await {
+            Ok(body) => body,
+            Err(e) => return self.http_error(e),
+        };
+        if status == 401 && matches!(self.auth_mode, AuthMode::Sas { .. }) && is_token_expiry(&body)
+        {
+            let skew = server_date
+                .as_deref()
+                .and_then(|date| clock_skew_secs(date, Utc::now()))
+                .filter(|skew| skew.abs() > CLOCK_SKEW_THRESHOLD_SECS);
+            if let Some(skew_secs) = skew {
+                self.clock_offset.store(skew_secs, Ordering::Relaxed);
+                return ServiceBusError::ClockSkew { skew_secs, body };
+            }
+        }
+        api_error(status, body)
+    }
+
+    /// Create a config for Azure AD (Microsoft Entra ID) authentication.
+    ///
+    /// `namespace` should be the fully-qualified namespace, e.g.
+    /// `mynamespace.servicebus.windows.net`.
+    #[cfg(feature = "azure-ad")]
+    pub fn from_azure_ad(namespace: &str, credential: Arc<dyn TokenCredential>) -> Self {
+        let namespace = namespace
+            .trim_start_matches("sb://")
+            .trim_end_matches('/')
+            .to_string();
+        let endpoint = format!("https://{}", namespace);
+        Self {
+            namespace,
+            endpoint,
+            auth_mode: AuthMode::AzureAd { credential },
+            is_emulator: false,
+            entity_path: None,
+            clock_offset: Arc::new(AtomicI64::new(0)),
+            stats: Arc::new(RequestStats::default()),
+        }
+    }
+
+    /// Generate a SAS token for the given resource URI, valid for
+    /// `validity_secs` by the server's clock.
+    fn generate_sas_token(
+        &self,
+        key_name: &str,
+        key: &str,
+        resource_uri: &str,
+        validity_secs: u64,
+    ) -> Result<String> {
+        let encoded_uri = urlencoding::encode(resource_uri).to_lowercase();
+        let expiry = sas_expiry(
+            Utc::now().timestamp(),
+            self.clock_offset_secs(),
+            validity_secs,
+        );
+        let string_to_sign = format!("{}\n{}", encoded_uri, expiry);
+
+        let mut mac = HmacSha256::new_from_slice(key.as_bytes())
+            .map_err(|e| ServiceBusError::Auth(format!("HMAC key error: {}", e)))?;
+        mac.update(string_to_sign.as_bytes());
+        let signature =
+            base64::engine::general_purpose::STANDARD.encode(mac.finalize().into_bytes());
+        let encoded_signature = urlencoding::encode(&signature);
+
+        Ok(format!(
+            "SharedAccessSignature sr={}&sig={}&se={}&skn={}",
+            encoded_uri, encoded_signature, expiry, key_name
+        ))
+    }
+
+    /// Acquire a Bearer token from Azure AD.
+    #[cfg(feature = "azure-ad")]
+    async fn get_azure_ad_token(credential: &dyn TokenCredential) -> Result<String> {
+        let token = credential
+            .get_token(&[SERVICE_BUS_SCOPE])
+            .await
+            .map_err(|e| ServiceBusError::Auth(format!("Azure AD token error: {}", e)))?;
+        Ok(format!("Bearer {}", token.token.secret()))
+    }
+
+    /// Generate an authorization header scoped to the namespace root, or to
+    /// `entity_path` for an entity-scoped key.
+    ///
+    /// For SAS: generates an HMAC-SHA256 token valid for 1 hour.
+    /// For Azure AD: acquires a Bearer token from the credential chain.
+    pub async fn namespace_token(&self) -> Result<String> {
+        match &self.auth_mode {
+            AuthMode::Sas {
+                shared_access_key_name,
+                shared_access_key,
+            } => {
+                let uri = match self.entity_path {
+                    Some(ref path) => format!("{}/{}", self.endpoint, path),
+                    None => self.endpoint.clone(),
+                };
+                self.generate_sas_token(shared_access_key_name, shared_access_key, &uri, 3600)
+            }
+            #[cfg(feature = "azure-ad")]
+            AuthMode::AzureAd { credential } => Self::get_azure_ad_token(credential.as_ref()).await,
+        }
+    }
+
+    /// Generate an authorization header scoped to a specific entity.
+    ///
+    /// For SAS: generates an HMAC-SHA256 token valid for 1 hour.
+    /// For Azure AD: acquires a Bearer token (scope is namespace-level
+    /// regardless, but the API authorization matches the entity).
+    pub async fn entity_token(&self, entity_path: &str) -> Result<String> {
+        match &self.auth_mode {
+            AuthMode::Sas {
+                shared_access_key_name,
+                shared_access_key,
+            } => {
+                let uri = format!("{}/{}", self.endpoint, entity_path);
+                self.generate_sas_token(shared_access_key_name, shared_access_key, &uri, 3600)
+            }
+            #[cfg(feature = "azure-ad")]
+            AuthMode::AzureAd { credential } => Self::get_azure_ad_token(credential.as_ref()).await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_valid_connection_string() {
+        let cs = "Endpoint=sb://myns.servicebus.windows.net/;SharedAccessKeyName=RootManageSharedAccessKey;SharedAccessKey=abc123def456==";
+        let cfg = ConnectionConfig::from_connection_string(cs).unwrap();
+        assert_eq!(cfg.namespace, "myns.servicebus.windows.net");
+        assert_eq!(cfg.endpoint, "https://myns.servicebus.windows.net");
+        assert!(matches!(
+            cfg.auth_mode,
+            AuthMode::Sas { ref shared_access_key_name, ref shared_access_key }
+            if shared_access_key_name == "RootManageSharedAccessKey"
+                && shared_access_key == "abc123def456=="
+        ));
+    }
+
+    #[test]
+    fn parse_emulator_connection_string() {
+        let cfg = ConnectionConfig::from_connection_string(EMULATOR_CONNECTION_STRING).unwrap();
+        assert!(cfg.is_emulator);
+        assert_eq!(cfg.namespace, "localhost");
+        assert_eq!(cfg.endpoint, "http://localhost:5672");
+    }
+
+    #[test]
+    fn emulator_endpoint_keeps_explicit_port() {
+        let cs = "Endpoint=sb://127.0.0.1:5300;SharedAccessKeyName=k;SharedAccessKey=v;UseDevelopmentEmulator=true";
+        let cfg = ConnectionConfig::from_connection_string(cs).unwrap();
+        assert_eq!(cfg.endpoint, "http://127.0.0.1:5300");
+    }
+
+    #[tokio::test]
+    async fn entity_path_scopes_every_token() {
+        let cs = "Endpoint=sb://myns.servicebus.windows.net/;SharedAccessKeyName=send;SharedAccessKey=dGVzdGtleQ==;EntityPath=orders";
+        let cfg = ConnectionConfig::from_connection_string(cs).unwrap();
+        assert_eq!(cfg.entity_path.as_deref(), Some("orders"));
+        let token = cfg.namespace_token().await.unwrap();
+        assert!(
+            token.contains("sr=https%3a%2f%2fmyns.servicebus.windows.net%2forders&"),
+            "{}",
+            token
+        );
+
+        let cs = "Endpoint=sb://myns.servicebus.windows.net/;SharedAccessKeyName=k;SharedAccessKey=v;EntityPath=";
+        let cfg = ConnectionConfig::from_connection_string(cs).unwrap();
+        assert_eq!(cfg.entity_path, None);
+    }
+
+    #[test]
+    fn parse_missing_endpoint() {
+        let cs = "SharedAccessKeyName=name;SharedAccessKey=key";
+        assert!(ConnectionConfig::from_connection_string(cs).is_err());
+    }
+
+    #[tokio::test]
+    async fn sas_token_format() {
+        let cs = "Endpoint=sb://myns.servicebus.windows.net/;SharedAccessKeyName=RootManageSharedAccessKey;SharedAccessKey=dGVzdGtleQ==";
+        let cfg = ConnectionConfig::from_connection_string(cs).unwrap();
+        let token = cfg.namespace_token().await.unwrap();
+        assert!(token.starts_with("SharedAccessSignature sr="));
+        assert!(token.contains("&sig="));
+        assert!(token.contains("&se="));
+        assert!(token.contains("&skn=RootManageSharedAccessKey"));
+    }
+
+    #[test]
+    fn clock_skew_is_measured_from_the_date_header() {
+        let local = DateTime::parse_from_rfc3339("2024-05-06T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(
+            clock_skew_secs("Mon, 06 May 2024 13:30:00 GMT", local),
+            Some(5400)
+        );
+        assert_eq!(
+            clock_skew_secs("Mon, 06 May 2024 11:59:30 GMT", local),
+            Some(-30)
+        );
+        assert_eq!(clock_skew_secs("yesterday", local), None);
+    }
+
+    #[test]
+    fn sas_expiry_follows_the_server_clock() {
+        assert_eq!(sas_expiry(1_000_000, 0, 3600), 1_003_600);
+        // Local clock 2h behind: tokens must expire 2h later than it thinks
+        assert_eq!(sas_expiry(1_000_000, 7200, 3600), 1_010_800);
+        assert_eq!(sas_expiry(1_000_000, -7200, 3600), 996_400);
+
+        let cs = "Endpoint=sb://myns.servicebus.windows.net/;SharedAccessKeyName=k;SharedAccessKey=dGVzdGtleQ==";
+        let cfg = ConnectionConfig::from_connection_string(cs).unwrap();
+        let shared = cfg.clone();
+        cfg.clock_offset.store(7200, Ordering::Relaxed);
+        assert_eq!(shared.clock_offset_secs(), 7200);
+        let token = shared
+            .generate_sas_token("k", "dGVzdGtleQ==", "https://myns", 3600)
+            .unwrap();
+        let se: i64 = token
+            .split("&se=")
+            .nth(1)
+            .and_then(|rest| rest.split('&').next())
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!(se >= Utc::now().timestamp() + 7200 + 3600 - 5);
+    }
+}
@@ -0,0 +1,1056 @@
+//! Sending and receiving messages over the Service Bus REST data plane.
+
+use reqwest::Client;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+
+use super::auth::ConnectionConfig;
+use super::entity_path;
+use super::error::{message_too_large, Result, ServiceBusError};
+use super::models::*;
+use super::stats::Api;
+use super::transport::{HttpTransport, Transport};
+
+/// Server-side long-poll timeouts (seconds) for lock-based receives.
+///
+/// An empty entity makes the server wait this long before answering 204, so
+/// short values keep peeks snappy while resend can afford to wait for slower
+/// partitions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LockTimeouts {
+    /// Used by peeks and session browsing.
+    pub peek_secs: u32,
+    /// Used by bulk resend and DLQ removal.
+    pub resend_secs: u32,
+}
+
+impl Default for LockTimeouts {
+    fn default() -> Self {
+        Self {
+            peek_secs: 1,
+            resend_secs: 5,
+        }
+    }
+}
+
+/// Parallel peek-lock workers used by `peek_messages`.
+const PEEK_WORKERS: usize = 16;
+
+/// What the peek workers have collected so far.
+#[derive(Default)]
+struct PeekState {
+    /// Sequence numbers already returned; a lock that expires mid-peek can
+    /// hand the same message to a second worker.
+    seen: HashSet<i64>,
+    messages: Vec<ReceivedMessage>,
+    /// Every lock taken, including duplicates, to abandon at the end.
+    lock_uris: Vec<String>,
+    /// Locks requested but not yet answered; each holds a slot in `messages`.
+    in_flight: usize,
+    /// Locks requested so far.
+    attempts: usize,
+    /// The entity ran dry or a lock failed.
+    done: bool,
+    error: Option<ServiceBusError>,
+}
+
+/// Client for Azure Service Bus data-plane operations (send, receive, peek).
+#[derive(Clone)]
+pub struct DataPlaneClient {
+    transport: Arc<dyn Transport>,
+    lock_timeouts: LockTimeouts,
+}
+
+impl DataPlaneClient {
+    /// `http` can be shared with a [`ManagementClient`](crate::ManagementClient)
+    /// for the same namespace, so both use one connection pool.
+    pub fn new(config: ConnectionConfig, http: Arc<Client>) -> Self {
+        Self::with_transport(Arc::new(HttpTransport::new(config, http)))
+    }
+
+    /// A client whose requests go to `transport` instead of over HTTP, e.g.
+    /// the in-memory `DemoNamespace` of the `demo` feature.
+    pub fn with_transport(transport: Arc<dyn Transport>) -> Self {
+        Self {
+            transport,
+            lock_timeouts: LockTimeouts::default(),
+        }
+    }
+
+    /// Override the lock-based receive timeouts (normally taken from app settings).
+    pub fn with_lock_timeouts(mut self, lock_timeouts: LockTimeouts) -> Self {
+        self.lock_timeouts = lock_timeouts;
+        self
+    }
+
+    /// The lock-based receive timeouts in use.
+    pub fn lock_timeouts(&self) -> LockTimeouts {
+        self.lock_timeouts
+    }
+
+    // ────────── Send ──────────
+
+    /// Send a message to a queue or topic.
+    pub async fn send_message(&self, entity_path: &str, message: &ServiceBusMessage) -> Result<()> {
+        self.transport.send_message(entity_path, message).await
+    }
+
+    /// Send several messages to a queue or topic with as few requests as
+    /// possible. Messages are packed into JSON batch requests of at most
+    /// `MAX_BATCH_BYTES`; a failing batch stops the send, and the batches
+    /// before it stay sent.
+    pub async fn send_batch(
+        &self,
+        entity_path: &str,
+        messages: &[ServiceBusMessage],
+    ) -> Result<()> {
+        self.transport.send_batch(entity_path, messages).await
+    }
+
+    // ────────── Peek ──────────
+
+    /// Peek messages without permanently removing them.
+    ///
+    /// Uses peek-lock + abandon under the hood because the REST API's
+    /// `PeekOnly=true` has no cursor and always returns the same first message.
+    /// Peek-lock gives us unique messages; we abandon all locks afterward so
+    /// messages remain available. Note: each peek-lock increments `DeliveryCount`.
+    ///
+    /// Up to `PEEK_WORKERS` peek-locks run at once. A worker reserves a slot
+    /// before each lock, so no more locks are in flight than messages still
+    /// missing, and peeking never locks a message it won't return. Results are
+    /// ordered by sequence number. Should expired locks keep handing out the
+    /// same messages, peeking stops after `2 * count` locks.
+    pub async fn peek_messages(
+        &self,
+        entity_path: &str,
+        count: i32,
+    ) -> Result<Vec<ReceivedMessage>> {
+        let wanted = count.max(0) as usize;
+        if wanted == 0 {
+            return Ok(Vec::new());
+        }
+        let timeout = self.lock_timeouts.peek_secs;
+        let max_locks = wanted.saturating_mul(2);
+        let state = Arc::new(tokio::sync::Mutex::new(PeekState::default()));
+
+        let handles: Vec<_> = (0..wanted.min(PEEK_WORKERS))
+            .map(|_| {
+                let dp = self.clone();
+                let path = entity_path.to_string();
+                let state = Arc::clone(&state);
+                tokio::spawn(async move {
+                    loop {
+                        {
+                            let mut state = state.lock().await;
+                            if state.done
+                                || state.messages.len() + state.in_flight >= wanted
+                                || state.attempts >= max_locks
+                            {
+                                break;
+                            }
+                            state.in_flight += 1;
+                            state.attempts += 1;
+                        }
+                        let result = dp.peek_lock(&path, timeout).await;
+                        let mut state = state.lock().await;
+                        state.in_flight -= 1;
+                        match result {
+                            Ok(Some(msg)) => {
+                                if let Some(ref uri) = msg.lock_token_uri {
+                                    state.lock_uris.push(uri.clone());
+                                }
+                                let fresh = match msg.broker_properties.sequence_number {
+                                    Some(seq) => state.seen.insert(seq),
+                                    None => true,
+                                };
+                                if fresh {
+                                    state.messages.push(msg);
+                                }
+                            }
+                            Ok(None) => state.done = true,
+                            Err(e) => {
+                                state.done = true;
+                                state.error.get_or_insert(e);
+                            }
+                        }
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            let _ = handle.await;
+        }
+
+        let PeekState {
+            mut messages,
+            lock_uris,
+            error,
+            ..
+        } = std::mem::take(&mut *state.lock().await);
+
+        // Abandon all locks — messages become available again.
+        self.abandon_all(lock_uris).await;
+        if let Some(e) = error {
+            return Err(e);
+        }
+
+        messages.sort_by_key(|m| m.broker_properties.sequence_number);
+        // Clear lock URIs from returned messages (locks are released)
+        for msg in &mut messages {
+            msg.lock_token_uri = None;
+        }
+
+        Ok(messages)
+    }
+
+    /// Abandon locks `PEEK_WORKERS` at a time. Failures are ignored: a lock
+    /// that cannot be abandoned expires on its own.
+    async fn abandon_all(&self, lock_uris: Vec<String>) {
+        for chunk in lock_uris.chunks(PEEK_WORKERS) {
+            let handles: Vec<_> = chunk
+                .iter()
+                .map(|uri| {
+                    let dp = self.clone();
+                    let uri = uri.clone();
+                    tokio::spawn(async move {
+                        let _ = dp.abandon_message(&uri).await;
+                    })
+                })
+                .collect();
+            for handle in handles {
+                let _ = handle.await;
+            }
+        }
+    }
+
+    // ────────── Receive ──────────
+
+    /// Receive and delete a message (destructive).
+    ///
+    /// Uses `timeout=1` to avoid the 60-second default server-side long-poll
+    /// when the entity is empty.
+    pub async fn receive_and_delete(&self, entity_path: &str) -> Result<Option<ReceivedMessage>> {
+        self.transport.receive_and_delete(entity_path).await
+    }
+
+    /// Peek-lock a message (non-destructive receive, requires later disposition).
+    pub async fn peek_lock(
+        &self,
+        entity_path: &str,
+        timeout_secs: u32,
+    ) -> Result<Option<ReceivedMessage>> {
+        self.transport.peek_lock(entity_path, timeout_secs).await
+    }
+
+    /// Complete a peek-locked message (removes it from the queue).
+    pub async fn complete_message(&self, lock_token_uri: &str) -> Result<()> {
+        self.transport.complete(lock_token_uri).await
+    }
+
+    /// Abandon a peek-locked message (makes it available again).
+    pub async fn abandon_message(&self, lock_token_uri: &str) -> Result<()> {
+        self.transport.abandon(lock_token_uri).await
+    }
+
+    // ────────── Session browse ──────────
+
+    /// Peek through an entity collecting messages until `max_sessions`
+    /// distinct session ids have been seen, `max_messages` have been read, the
+    /// entity runs dry or `cancel` is set.  The REST API cannot enumerate or
+    /// accept sessions, so sessions are discovered from the messages' SessionId.
+    /// All locks are abandoned before returning.  The flag is true when
+    /// browsing stopped before reaching the end of the entity.
+    pub async fn browse_sessions(
+        &self,
+        entity_path: &str,
+        max_messages: usize,
+        max_sessions: usize,
+        cancel: Option<Arc<AtomicBool>>,
+    ) -> Result<(Vec<ReceivedMessage>, bool)> {
+        let mut messages = Vec::new();
+        let mut lock_uris = Vec::new();
+        let mut sessions = HashSet::new();
+        let mut truncated = false;
+        let timeout = self.lock_timeouts.peek_secs;
+
+        let result = loop {
+            let cancelled = cancel.as_ref().is_some_and(|c| c.load(Ordering::Relaxed));
+            if cancelled || messages.len() >= max_messages {
+                truncated = true;
+                break Ok(());
+            }
+            match self.peek_lock(entity_path, timeout).await {
+                Ok(Some(msg)) => {
+                    if let Some(ref uri) = msg.lock_token_uri {
+                        lock_uris.push(uri.clone());
+                    }
+                    if let Some(ref id) = msg.broker_properties.session_id {
+                        if !sessions.contains(id) && sessions.len() >= max_sessions {
+                            truncated = true;
+                            break Ok(());
+                        }
+                        sessions.insert(id.clone());
+                    }
+                    messages.push(msg);
+                }
+                Ok(None) => break Ok(()),
+                Err(e) => break Err(e),
+            }
+        };
+
+        for uri in &lock_uris {
+            let _ = self.abandon_message(uri).await;
+        }
+        result?;
+
+        for msg in &mut messages {
+            msg.lock_token_uri = None;
+        }
+        Ok((messages, truncated))
+    }
+
+    // ────────── Single-message removal ──────────
+
+    /// Remove a specific message from the DLQ.
+    ///
+    /// Peek-locks messages one at a time, looking for one matching `target`
+    /// (sequence number plus partition identity, see [`MessageRef::matches`]).
+    /// Completes the match and abandons any non-matching messages that were locked
+    /// along the way.  Returns `true` if the message was found and removed.
+    pub async fn remove_from_dlq(&self, entity_path: &str, target: &MessageRef) -> Result<bool> {
+        let dlq_path = format!("{}/$deadletterqueue", entity_path);
+        let mut abandoned_uris: Vec<String> = Vec::new();
+        let max_attempts = 50u32;
+        let timeout = self.lock_timeouts.resend_secs;
+
+        for _ in 0..max_attempts {
+            match self.peek_lock(&dlq_path, timeout).await? {
+                Some(msg) => {
+                    let lock_uri = match msg.lock_token_uri {
+                        Some(ref uri) => uri.clone(),
+                        None => continue,
+                    };
+
+                    if target.matches(&msg) {
+                        // Found it — complete (delete from DLQ)
+                        self.complete_message(&lock_uri).await?;
+                        // Abandon everything else we locked
+                        for uri in &abandoned_uris {
+                            let _ = self.abandon_message(uri).await;
+                        }
+                        return Ok(true);
+                    } else {
+                        // Not a match — will abandon after we're done
+                        abandoned_uris.push(lock_uri);
+                    }
+                }
+                None => break,
+            }
+        }
+
+        // Didn't find it — abandon all locks
+        for uri in &abandoned_uris {
+            let _ = self.abandon_message(uri).await;
+        }
+        Ok(false)
+    }
+
+    // ────────── Purge ──────────
+
+    /// Concurrently purge all messages from an entity.
+    ///
+    /// Spawns `concurrency` parallel receive-and-delete workers that drain the
+    /// entity as fast as the broker allows.  Returns the total number of
+    /// messages deleted.  The optional `cancel` flag lets the caller abort
+    /// early; the optional `progress` callback is invoked after every message.
+    pub async fn purge_concurrent(
+        &self,
+        entity_path: &str,
+        concurrency: usize,
+        cancel: Option<Arc<AtomicBool>>,
+        progress: Option<tokio::sync::mpsc::UnboundedSender<u64>>,
+    ) -> Result<u64> {
+        let count = Arc::new(AtomicU64::new(0));
+        let done = Arc::new(AtomicBool::new(false));
+        let first_error: Arc<tokio::sync::Mutex<Option<ServiceBusError>>> =
+            Arc::new(tokio::sync::Mutex::new(None));
+
+        let mut handles = Vec::with_capacity(concurrency);
+        for _ in 0..concurrency {
+            let dp = self.clone();
+            let path = entity_path.to_string();
+            let count = Arc::clone(&count);
+            let done = Arc::clone(&done);
+            let cancel = cancel.clone();
+            let progress = progress.clone();
+            let first_error = Arc::clone(&first_error);
+
+            handles.push(tokio::spawn(async move {
+                loop {
+                    if done.load(Ordering::Relaxed) {
+                        return;
+                    }
+                    if let Some(ref c) = cancel {
+                        if c.load(Ordering::Relaxed) {
+                            return;
+                        }
+                    }
+                    match dp.receive_and_delete(&path).await {
+                        Ok(Some(_)) => {
+                            let n = count.fetch_add(1, Ordering::Relaxed) + 1;
+                            if let Some(ref tx) = progress {
+                                let _ = tx.send(n);
+                            }
+                        }
+                        Ok(None) => {
+                            done.store(true, Ordering::Relaxed);
+                            return;
+                        }
+                        Err(e) => {
+                            done.store(true, Ordering::Relaxed);
+                            let mut guard = first_error.lock().await;
+                            if guard.is_none() {
+                                *guard = Some(e);
+                            }
+                            return;
+                        }
+                    }
+                }
+            }));
+        }
+
+        for h in handles {
+            let _ = h.await;
+        }
+
+        let err = first_error.lock().await.take();
+        if let Some(e) = err {
+            return Err(e);
+        }
+
+        Ok(count.load(Ordering::Relaxed))
+    }
+}
+
+// ──────────────────────────── HTTP transport ────────────────────────────
+
+impl HttpTransport {
+    pub(crate) async fn http_send_message(
+        &self,
+        entity_path: &str,
+        message: &ServiceBusMessage,
+    ) -> Result<()> {
+        let entity_path = entity_path::to_data_plane_path(entity_path);
+        let url = format!(
+            "{}/{}/messages?api-version=2017-04",
+            self.config.endpoint, entity_path
+        );
+        let token = self.config.entity_token(&entity_path).await?;
+
+        let mut req = self.http.post(&url).header("Authorization", token).header(
+            "Content-Type",
+            message
+                .content_type
+                .as_deref()
+                .unwrap_or("application/json"),
+        );
+
+        let broker_props = broker_properties(message);
+        if !broker_props.is_empty() {
+            req = req.header(
+                "BrokerProperties",
+                serde_json::to_string(&broker_props).unwrap_or_default(),
+            );
+        }
+
+        // Custom properties as individual headers
+        for (k, v) in &message.custom_properties {
+            let (name, value) = encode_custom_property(k, v);
+            req = req.header(name, value);
+        }
+
+        let body_bytes = message.body.len();
+        let resp = self
+            .config
+            .send(Api::DataPlane, req.body(message.body.clone()))
+            .await?;
+
+        let status = resp.status().as_u16();
+        if status == 413 {
+            let body = resp.text().await.unwrap_or_default();
+            return Err(message_too_large(Some(body_bytes), &body));
+        }
+        if status >= 400 {
+            return Err(self.config.response_error(resp).await);
+        }
+        Ok(())
+    }
+
+    pub(crate) async fn http_send_batch(
+        &self,
+        entity_path: &str,
+        messages: &[ServiceBusMessage],
+    ) -> Result<()> {
+        let entity_path = entity_path::to_data_plane_path(entity_path);
+        let url = format!(
+            "{}/{}/messages?api-version=2017-04",
+            self.config.endpoint, entity_path
+        );
+
+        for batch in split_batches(messages, MAX_BATCH_BYTES) {
+            let body = batch_body(batch);
+            let body_bytes = body.len();
+            let token = self.config.entity_token(&entity_path).await?;
+            let resp = self
+                .config
+                .send(
+                    Api::DataPlane,
+                    self.http
+                        .post(&url)
+                        .header("Authorization", token)
+                        .header("Content-Type", BATCH_CONTENT_TYPE)
+                        .body(body),
+                )
+                .await?;
+
+            let status = resp.status().as_u16();
+            if status == 413 {
+                let body = resp.text().await.unwrap_or_default();
+                return Err(message_too_large(Some(body_bytes), &body));
+            }
+            if status >= 400 {
+                return Err(self.config.response_error(resp).await);
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) async fn http_receive_and_delete(
+        &self,
+        entity_path: &str,
+    ) -> Result<Option<ReceivedMessage>> {
+        let entity_path = entity_path::to_data_plane_path(entity_path);
+        let url = format!(
+            "{}/{}/messages/head?api-version=2017-04&timeout=1",
+            self.config.endpoint, entity_path
+        );
+        let token = self.config.entity_token(&entity_path).await?;
+
+        let resp = self
+            .config
+            .send(
+                Api::DataPlane,
+                self.http.delete(&url).header("Authorization", token),
+            )
+            .await?;
+
+        let status = resp.status().as_u16();
+        if status == 204 {
+            return Ok(None);
+        }
+        if status >= 400 {
+            return Err(self.config.response_error(resp).await);
+        }
+
+        let msg = parse_received_message(resp).await?;
+        Ok(Some(msg))
+    }
+
+    pub(crate) async fn http_peek_lock(
+        &self,
+        entity_path: &str,
+        timeout_secs: u32,
+    ) -> Result<Option<ReceivedMessage>> {
+        let entity_path = entity_path::to_data_plane_path(entity_path);
+        let url = format!(
+            "{}/{}/messages/head?api-version=2017-04&timeout={}",
+            self.config.endpoint, entity_path, timeout_secs
+        );
+        let token = self.config.entity_token(&entity_path).await?;
+
+        let resp = self
+            .config
+            .send(
+                Api::DataPlane,
+                self.http
+                    .post(&url)
+                    .header("Authorization", token)
+                    .header("Content-Length", "0")
+                    .body(""),
+            )
+            .await?;
+
+        let status = resp.status().as_u16();
+        if status == 204 {
+            return Ok(None);
+        }
+        if status >= 400 {
+            return Err(self.config.response_error(resp).await);
+        }
+
+        let lock_uri = resp
+            .headers()
+            .get("Location")
+            .and_then(|v| v.to_str().ok())
+            .map(|s| s.to_string());
+
+        let mut msg = parse_received_message(resp).await?;
+        msg.lock_token_uri = lock_uri;
+        Ok(Some(msg))
+    }
+
+    pub(crate) async fn http_complete(&self, lock_token_uri: &str) -> Result<()> {
+        let token = self.config.namespace_token().await?;
+
+        let resp = self
+            .config
+            .send(
+                Api::DataPlane,
+                self.http
+                    .delete(lock_token_uri)
+                    .header("Authorization", token),
+            )
+            .await?;
+
+        let status = resp.status().as_u16();
+        if status >= 400 {
+            return Err(self.config.response_error(resp).await);
+        }
+        Ok(())
+    }
+
+    pub(crate) async fn http_abandon(&self, lock_token_uri: &str) -> Result<()> {
+        let token = self.config.namespace_token().await?;
+
+        let resp = self
+            .config
+            .send(
+                Api::DataPlane,
+                self.http
+                    .put(lock_token_uri)
+                    .header("Authorization", token)
+                    .header("Content-Length", "0")
+                    .body(""),
+            )
+            .await?;
+
+        let status = resp.status().as_u16();
+        if status >= 400 {
+            return Err(self.config.response_error(resp).await);
+        }
+        Ok(())
+    }
+}
+
+// ──────────────────────────── Response parsing ────────────────────────────
+
+async fn parse_received_message(resp: reqwest::Response) -> Result<ReceivedMessage> {
+    let broker_props_str = resp
+        .headers()
+        .get("BrokerProperties")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("{}")
+        .to_string();
+
+    // Custom properties are every header that isn't broker/transport metadata
+    let custom_props: Vec<(String, String)> = resp
+        .headers()
+        .iter()
+        .filter_map(|(name, value)| decode_custom_property(name.as_str(), value.as_bytes()))
+        .collect();
+
+    let (body, raw_body) = match String::from_utf8(resp.bytes().await?.to_vec()) {
+        Ok(text) => (text, None),
+        Err(e) => (
+            String::from_utf8_lossy(e.as_bytes()).into_owned(),
+            Some(e.into_bytes()),
+        ),
+    };
+
+    let broker_properties: BrokerProperties =
+        serde_json::from_str(&broker_props_str).unwrap_or_default();
+
+    Ok(ReceivedMessage {
+        body,
+        broker_properties,
+        custom_properties: custom_props,
+        lock_token_uri: None,
+        source_entity: None,
+        raw_body,
+    })
+}
+
+// ──────────────────────────── Custom properties ────────────────────────────
+
+/// Headers the broker or the HTTP stack adds to every response. Matched by
+/// exact (lower-case) name, plus the `x-ms-` family.
+const TRANSPORT_HEADERS: &[&str] = &[
+    "brokerproperties",
+    "connection",
+    "content-encoding",
+    "content-length",
+    "content-type",
+    "date",
+    "etag",
+    "keep-alive",
+    "location",
+    "server",
+    "strict-transport-security",
+    "transfer-encoding",
+    "vary",
+];
+
+fn is_transport_header(name: &str) -> bool {
+    TRANSPORT_HEADERS.contains(&name) || name.starts_with("x-ms-")
+}
+
+/// Largest batch request body the broker accepts (the standard-tier message size).
+const MAX_BATCH_BYTES: usize = 256 * 1024;
+
+const BATCH_CONTENT_TYPE: &str = "application/vnd.microsoft.servicebus.json";
+
+/// `BrokerProperties` of a message to send, as sent in the header of the same
+/// name (or, in a batch, next to the body).
+fn broker_properties(message: &ServiceBusMessage) -> serde_json::Map<String, Value> {
+    let mut broker_props = serde_json::Map::new();
+    if let Some(ref id) = message.message_id {
+        broker_props.insert("MessageId".into(), Value::String(id.clone()));
+    }
+    if let Some(ref id) = message.correlation_id {
+        broker_props.insert("CorrelationId".into(), Value::String(id.clone()));
+    }
+    if let Some(ref id) = message.session_id {
+        broker_props.insert("SessionId".into(), Value::String(id.clone()));
+    }
+    if let Some(ref v) = message.label {
+        broker_props.insert("Label".into(), Value::String(v.clone()));
+    }
+    if let Some(ref v) = message.to {
+        broker_props.insert("To".into(), Value::String(v.clone()));
+    }
+    if let Some(ref v) = message.reply_to {
+        broker_props.insert("ReplyTo".into(), Value::String(v.clone()));
+    }
+    if let Some(ref v) = message.reply_to_session_id {
+        broker_props.insert("ReplyToSessionId".into(), Value::String(v.clone()));
+    }
+    if let Some(ref v) = message.time_to_live {
+        if let Ok(secs) = v.parse::<f64>() {
+            broker_props.insert("TimeToLive".into(), Value::from(secs));
+        }
+    }
+    if let Some(ref v) = message.scheduled_enqueue_time {
+        broker_props.insert("ScheduledEnqueueTimeUtc".into(), Value::String(v.clone()));
+    }
+    if let Some(ref v) = message.partition_key {
+        broker_props.insert("PartitionKey".into(), Value::String(v.clone()));
+    }
+    if let Some(ref v) = message.via_partition_key {
+        broker_props.insert("ViaPartitionKey".into(), Value::String(v.clone()));
+    }
+    broker_props
+}
+
+/// One element of a batch send body. The content type, a header when sending
+/// a single message, travels in `BrokerProperties` here.
+fn batch_entry(message: &ServiceBusMessage) -> Value {
+    let mut broker_props = broker_properties(message);
+    if let Some(ref v) = message.content_type {
+        broker_props.insert("ContentType".into(), Value::String(v.clone()));
+    }
+    let user_props: serde_json::Map<String, Value> = message
+        .custom_properties
+        .iter()
+        .map(|(k, v)| (k.clone(), Value::String(v.clone())))
+        .collect();
+    serde_json::json!({
+        "Body": message.body,
+        "BrokerProperties": broker_props,
+        "UserProperties": user_props,
+    })
+}
+
+/// Body of a batch send request: a JSON array with one entry per message.
+fn batch_body(messages: &[ServiceBusMessage]) -> String {
+    Value::Array(messages.iter().map(batch_entry).collect()).to_string()
+}
+
+/// Split `messages` into consecutive runs whose batch bodies fit in
+/// `max_bytes`. A message too large on its own still gets a batch of its own,
+/// so the broker can reject it with a proper error.
+fn split_batches(messages: &[ServiceBusMessage], max_bytes: usize) -> Vec<&[ServiceBusMessage]> {
+    let mut batches = Vec::new();
+    let mut start = 0;
+    // "[" + "]"
+    let mut size = 2;
+    for (i, message) in messages.iter().enumerate() {
+        // Entry plus its separating comma
+        let entry = batch_entry(message).to_string().len() + 1;
+        if i > start && size + entry > max_bytes {
+            batches.push(&messages[start..i]);
+            start = i;
+            size = 2;
+        }
+        size += entry;
+    }
+    if start < messages.len() {
+        batches.push(&messages[start..]);
+    }
+    batches
+}
+
+/// Custom property as a request header. String values are sent as JSON
+/// strings with non-ASCII escaped, so quotes, backslashes and `é` survive.
+/// Names that aren't valid header tokens (e.g. contain spaces) are
+/// percent-encoded; `decode_custom_property` reverses both.
+fn encode_custom_property(name: &str, value: &str) -> (String, String) {
+    let name = if !name.is_empty() && name.chars().all(is_token_char) {
+        name.to_string()
+    } else {
+        urlencoding::encode(name).into_owned()
+    };
+    let mut encoded = String::with_capacity(value.len() + 2);
+    encoded.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => encoded.push_str("\\\""),
+            '\\' => encoded.push_str("\\\\"),
+            ' '..='~' => encoded.push(c),
+            _ => {
+                let mut units = [0u16; 2];
+                for unit in c.encode_utf16(&mut units) {
+                    encoded.push_str(&format!("\\u{:04x}", unit));
+                }
+            }
+        }
+    }
+    encoded.push('"');
+    (name, encoded)
+}
+
+/// Read a response header back as a custom property, or `None` for
+/// broker/transport headers. Custom string values always arrive JSON-quoted,
+/// which is how a property named `Date` or `Location` is told apart from the
+/// real (unquoted) header of the same name.
+fn decode_custom_property(name: &str, raw_value: &[u8]) -> Option<(String, String)> {
+    let raw = String::from_utf8_lossy(raw_value);
+    let raw = raw.trim();
+    let json_string = serde_json::from_str::<String>(raw).ok();
+
+    let lower = name.to_ascii_lowercase();
+    if lower == "brokerproperties" || (is_transport_header(&lower) && json_string.is_none()) {
+        return None;
+    }
+
+    let name = match urlencoding::decode(name) {
+        Ok(decoded) => decoded.into_owned(),
+        Err(_) => name.to_string(),
+    };
+    Some((name, json_string.unwrap_or_else(|| raw.to_string())))
+}
+
+/// RFC 7230 `tchar`.
+fn is_token_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "!#$&'*+-.^_`|~".contains(c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(name: &str, value: &str) -> Option<(String, String)> {
+        let (header, encoded) = encode_custom_property(name, value);
+        assert!(
+            encoded.is_ascii(),
+            "header value must be ASCII: {}",
+            encoded
+        );
+        decode_custom_property(&header, encoded.as_bytes())
+    }
+
+    fn numbered_messages(n: usize) -> Vec<ServiceBusMessage> {
+        (0..n)
+            .map(|i| ServiceBusMessage {
+                body: format!("{{\"n\":{}}}", i),
+                message_id: Some(format!("msg-{}", i)),
+                custom_properties: vec![("tenant".to_string(), "contoso".to_string())],
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn batch_body_has_one_entry_per_message() {
+        let messages = numbered_messages(5);
+        let body: Value = serde_json::from_str(&batch_body(&messages)).unwrap();
+        let entries = body.as_array().unwrap();
+        assert_eq!(entries.len(), 5);
+        for (i, entry) in entries.iter().enumerate() {
+            assert_eq!(entry["Body"], format!("{{\"n\":{}}}", i));
+            assert_eq!(entry["BrokerProperties"]["MessageId"], format!("msg-{}", i));
+            assert_eq!(entry["BrokerProperties"]["ContentType"], "application/json");
+            assert_eq!(entry["UserProperties"]["tenant"], "contoso");
+        }
+    }
+
+    #[test]
+    fn batches_are_split_at_the_size_limit() {
+        let messages = numbered_messages(5);
+        assert_eq!(split_batches(&messages, MAX_BATCH_BYTES).len(), 1);
+
+        // Room for exactly two entries per batch
+        let entry = batch_entry(&messages[0]).to_string().len() + 1;
+        let batches = split_batches(&messages, 2 + 2 * entry);
+        assert_eq!(
+            batches.iter().map(|b| b.len()).collect::<Vec<_>>(),
+            vec![2, 2, 1]
+        );
+        for batch in &batches {
+            assert!(batch_body(batch).len() <= 2 + 2 * entry);
+        }
+
+        // An oversized message still goes out, alone
+        assert_eq!(split_batches(&messages[..2], 1).len(), 2);
+        assert!(split_batches(&[], MAX_BATCH_BYTES).is_empty());
+    }
+
+    #[test]
+    fn properties_named_like_http_headers_survive() {
+        for name in ["date", "server", "location", "content-type"] {
+            assert_eq!(
+                round_trip(name, "custom"),
+                Some((name.to_string(), "custom".to_string()))
+            );
+        }
+    }
+
+    #[test]
+    fn real_transport_headers_are_dropped() {
+        assert_eq!(
+            decode_custom_property("date", b"Wed, 05 Sep 2018 05:06:43 GMT"),
+            None
+        );
+        assert_eq!(
+            decode_custom_property(
+                "location",
+                b"https://ns.servicebus.windows.net/q/messages/1/abc"
+            ),
+            None
+        );
+        assert_eq!(decode_custom_property("brokerproperties", b"\"{}\""), None);
+        assert_eq!(decode_custom_property("x-ms-request-id", b"abc"), None);
+    }
+
+    #[test]
+    fn awkward_names_and_values_round_trip() {
+        assert_eq!(
+            round_trip("order id", "Zoë \"quoted\" \\ 日本 🚀"),
+            Some((
+                "order id".to_string(),
+                "Zoë \"quoted\" \\ 日本 🚀".to_string()
+            ))
+        );
+        assert_eq!(
+            round_trip("tenant", "\"already quoted\""),
+            Some(("tenant".to_string(), "\"already quoted\"".to_string()))
+        );
+    }
+
+    #[test]
+    fn non_string_values_are_kept_verbatim() {
+        assert_eq!(
+            decode_custom_property("priority", b"5"),
+            Some(("priority".to_string(), "5".to_string()))
+        );
+    }
+
+    /// Peek-lock responses handing out `sequence` in order, then 204.
+    struct ScriptedLocks {
+        sequence: Vec<i64>,
+        next: std::sync::atomic::AtomicUsize,
+        lock_uri: String,
+    }
+
+    impl wiremock::Respond for ScriptedLocks {
+        fn respond(&self, _: &wiremock::Request) -> wiremock::ResponseTemplate {
+            let i = self.next.fetch_add(1, Ordering::SeqCst);
+            let Some(seq) = self.sequence.get(i) else {
+                return wiremock::ResponseTemplate::new(204);
+            };
+            wiremock::ResponseTemplate::new(201)
+                .insert_header(
+                    "BrokerProperties",
+                    format!(r#"{{"MessageId":"m-{0}","SequenceNumber":{0}}}"#, seq).as_str(),
+                )
+                .insert_header("Location", self.lock_uri.as_str())
+                .set_body_string("{}")
+        }
+    }
+
+    /// Peek `count` from a mocked queue serving `sequence`; returns the
+    /// sequence numbers peeked and the number of locks taken.
+    async fn peek_scripted(sequence: Vec<i64>, count: i32) -> (Vec<i64>, usize) {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/orders/messages/head"))
+            .respond_with(ScriptedLocks {
+                sequence,
+                next: Default::default(),
+                lock_uri: format!("{}/orders/messages/1/lock", server.uri()),
+            })
+            .mount(&server)
+            .await;
+        Mock::given(method("PUT"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+        let conn_str = format!(
+            "Endpoint=sb://{};SharedAccessKeyName=k;SharedAccessKey=a2V5;UseDevelopmentEmulator=true",
+            server.address()
+        );
+        let dp = DataPlaneClient::new(
+            ConnectionConfig::from_connection_string(&conn_str).unwrap(),
+            Arc::new(Client::new()),
+        );
+
+        let peeked = dp.peek_messages("orders", count).await.unwrap();
+        let locks = server
+            .received_requests()
+            .await
+            .unwrap()
+            .iter()
+            .filter(|r| r.method == wiremock::http::Method::POST)
+            .count();
+        let seqs = peeked
+            .iter()
+            .filter_map(|m| m.broker_properties.sequence_number)
+            .collect();
+        (seqs, locks)
+    }
+
+    #[tokio::test]
+    async fn peek_takes_no_more_locks_than_messages_wanted() {
+        let (seqs, locks) = peek_scripted((1..=20).collect(), 5).await;
+        assert_eq!(seqs, vec![1, 2, 3, 4, 5]);
+        assert_eq!(locks, 5);
+    }
+
+    #[tokio::test]
+    async fn peek_drops_duplicates_and_orders_by_sequence_number() {
+        // An expired lock hands message 3 out twice; its slot is taken again
+        let (seqs, locks) = peek_scripted(vec![3, 1, 3, 2, 4], 3).await;
+        assert_eq!(seqs, vec![1, 2, 3]);
+        assert_eq!(locks, 4);
+
+        // The same message over and over: give up after twice the count
+        let (seqs, locks) = peek_scripted(vec![7; 50], 2).await;
+        assert_eq!(seqs, vec![7]);
+        assert_eq!(locks, 4);
+    }
+}
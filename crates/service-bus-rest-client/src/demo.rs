@@ -0,0 +1,1208 @@
+//! In-memory stand-in for a Service Bus namespace: a
+//! [`Transport`] the clients can be built on with
+//! `with_transport`, alongside a config from
+//! [`ConnectionConfig::demo`](crate::ConnectionConfig::demo).
+//!
+//! The management side answers with the ATOM XML the service would send, so
+//! everything above `ManagementClient`'s request helpers (parsing, patches,
+//! retries) runs unchanged. The data plane keeps messages per entity with
+//! peek-lock semantics. Every call waits a short, varying delay so progress
+//! bars and latency figures behave as they would against Azure.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use quick_xml::escape::escape;
+
+use super::entity_path;
+use super::error::{api_error, Result, ServiceBusError};
+use super::management::{
+    parse_queue_from_entry, parse_subscription_from_entry, parse_topic_from_entry,
+    queue_description_xml, subscription_description_xml, topic_description_xml,
+};
+use super::models::*;
+use super::stats::{Api, RequestStats};
+use super::transport::{BoxFuture, Transport};
+
+/// Host name of the demo namespace.
+pub const DEMO_NAMESPACE: &str = "contoso-demo.servicebus.windows.net";
+
+/// Forwarding hops followed on send before a message stays where it is.
+const MAX_FORWARD_HOPS: usize = 4;
+
+/// How long a peek-lock holds a message before it is available again.
+const LOCK_DURATION_SECS: i64 = 30;
+
+/// A namespace held in memory. Clients built on the same instance (behind
+/// an `Arc`) see the same entities and messages.
+#[derive(Debug)]
+pub struct DemoNamespace {
+    state: Mutex<DemoState>,
+    calls: AtomicU64,
+    simulate_latency: bool,
+    /// Shared with the config from `ConnectionConfig::demo`, so the app's
+    /// request statistics count demo calls.
+    stats: Arc<RequestStats>,
+}
+
+impl DemoNamespace {
+    /// An empty namespace.
+    pub fn new() -> Self {
+        Self {
+            state: Mutex::new(DemoState::default()),
+            calls: AtomicU64::new(0),
+            simulate_latency: true,
+            stats: Arc::new(RequestStats::default()),
+        }
+    }
+
+    /// Queues, topics and subscriptions of a small web shop, with messages
+    /// and dead-letters in most of them.
+    pub fn seeded() -> Self {
+        let namespace = Self::new();
+        namespace.lock().seed(Utc::now());
+        namespace
+    }
+
+    /// Answer at once instead of after a simulated round trip (for tests).
+    pub fn without_latency(mut self) -> Self {
+        self.simulate_latency = false;
+        self
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, DemoState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// 20–60 ms for the data plane, 40–80 ms for management, varying from
+    /// call to call.
+    fn latency(&self, api: Api) -> Duration {
+        if !self.simulate_latency {
+            return Duration::ZERO;
+        }
+        let n = self.calls.fetch_add(1, Ordering::Relaxed);
+        let base = match api {
+            Api::Management => 40,
+            Api::DataPlane => 20,
+        };
+        Duration::from_millis(base + n * 37 % 41)
+    }
+
+    pub(crate) fn stats(&self) -> Arc<RequestStats> {
+        Arc::clone(&self.stats)
+    }
+
+    /// Run `op` after a simulated round trip, recording it (and whether it
+    /// failed) in the request statistics like a real request.
+    async fn call<T>(&self, api: Api, op: impl FnOnce(&mut DemoState) -> Result<T>) -> Result<T> {
+        let latency = self.latency(api);
+        if !latency.is_zero() {
+            tokio::time::sleep(latency).await;
+        }
+        let result = op(&mut self.lock());
+        self.stats.record(api, Some(latency), result.is_err());
+        result
+    }
+}
+
+impl Transport for DemoNamespace {
+    fn get_atom<'a>(&'a self, path: &'a str) -> BoxFuture<'a, Result<String>> {
+        Box::pin(self.call(Api::Management, move |s| s.get_atom(path)))
+    }
+
+    fn put_atom<'a>(
+        &'a self,
+        path: &'a str,
+        body: &'a str,
+        if_match: bool,
+    ) -> BoxFuture<'a, Result<String>> {
+        Box::pin(self.call(Api::Management, move |s| s.put_atom(path, body, if_match)))
+    }
+
+    fn delete_entity<'a>(&'a self, path: &'a str) -> BoxFuture<'a, Result<()>> {
+        Box::pin(self.call(Api::Management, move |s| s.delete(path)))
+    }
+
+    fn ping(&self) -> BoxFuture<'_, Result<Duration>> {
+        Box::pin(async move {
+            let started = Instant::now();
+            self.call(Api::Management, |_| Ok(())).await?;
+            Ok(started.elapsed())
+        })
+    }
+
+    fn send_message<'a>(
+        &'a self,
+        entity_path: &'a str,
+        message: &'a ServiceBusMessage,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(self.call(Api::DataPlane, move |s| s.send(entity_path, message)))
+    }
+
+    fn send_batch<'a>(
+        &'a self,
+        entity_path: &'a str,
+        messages: &'a [ServiceBusMessage],
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(self.call(Api::DataPlane, move |s| {
+            messages.iter().try_for_each(|m| s.send(entity_path, m))
+        }))
+    }
+
+    fn receive_and_delete<'a>(
+        &'a self,
+        entity_path: &'a str,
+    ) -> BoxFuture<'a, Result<Option<ReceivedMessage>>> {
+        Box::pin(self.call(Api::DataPlane, move |s| s.receive_and_delete(entity_path)))
+    }
+
+    fn peek_lock<'a>(
+        &'a self,
+        entity_path: &'a str,
+        _timeout_secs: u32,
+    ) -> BoxFuture<'a, Result<Option<ReceivedMessage>>> {
+        Box::pin(self.call(Api::DataPlane, move |s| s.peek_lock(entity_path)))
+    }
+
+    fn complete<'a>(&'a self, lock_token_uri: &'a str) -> BoxFuture<'a, Result<()>> {
+        Box::pin(self.call(Api::DataPlane, move |s| s.complete(lock_token_uri)))
+    }
+
+    fn abandon<'a>(&'a self, lock_token_uri: &'a str) -> BoxFuture<'a, Result<()>> {
+        Box::pin(self.call(Api::DataPlane, move |s| s.abandon(lock_token_uri)))
+    }
+}
+
+impl Default for DemoNamespace {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Clone)]
+struct StoredMessage {
+    message: ReceivedMessage,
+    /// Scheduled messages stay invisible until then.
+    visible_from: Option<DateTime<Utc>>,
+    lock: Option<(String, DateTime<Utc>)>,
+}
+
+impl StoredMessage {
+    fn available(&self, now: DateTime<Utc>) -> bool {
+        self.visible_from.is_none_or(|at| at <= now)
+            && self.lock.as_ref().is_none_or(|(_, until)| *until <= now)
+    }
+}
+
+/// Entities are keyed by their lower-case path, since Service Bus names are
+/// case-insensitive; messages by lower-case data-plane path, sub-queue
+/// included.
+#[derive(Debug, Default)]
+pub(crate) struct DemoState {
+    queues: BTreeMap<String, QueueDescription>,
+    topics: BTreeMap<String, TopicDescription>,
+    /// By `topic/subscriptions/name`.
+    subscriptions: BTreeMap<String, SubscriptionDescription>,
+    /// Rule name and `RuleDescription` XML, by subscription key.
+    rules: BTreeMap<String, Vec<(String, String)>>,
+    messages: HashMap<String, Vec<StoredMessage>>,
+    next_sequence: i64,
+    next_lock: u64,
+}
+
+/// What a management path names.
+enum Resource<'a> {
+    Queues,
+    Topics,
+    AuthorizationRules,
+    NamespaceInfo,
+    Subscriptions(&'a str),
+    Subscription(&'a str, &'a str),
+    Rules(&'a str, &'a str),
+    Rule(&'a str, &'a str, &'a str),
+    Entity(&'a str),
+}
+
+impl<'a> Resource<'a> {
+    fn parse(path: &'a str) -> Self {
+        match path {
+            "$Resources/Queues" => return Resource::Queues,
+            "$Resources/Topics" => return Resource::Topics,
+            "$Resources/AuthorizationRules" => return Resource::AuthorizationRules,
+            "$namespaceinfo" => return Resource::NamespaceInfo,
+            _ => {}
+        }
+        if let Some(topic) = path.strip_suffix("/Subscriptions") {
+            return Resource::Subscriptions(topic);
+        }
+        match path.split_once("/Subscriptions/") {
+            Some((topic, rest)) => {
+                match (rest.strip_suffix("/Rules"), rest.split_once("/Rules/")) {
+                    (Some(sub), _) => Resource::Rules(topic, sub),
+                    (None, Some((sub, rule))) => Resource::Rule(topic, sub, rule),
+                    (None, None) => Resource::Subscription(topic, rest),
+                }
+            }
+            None => Resource::Entity(path),
+        }
+    }
+}
+
+fn subscription_key(topic: &str, sub: &str) -> String {
+    format!("{}/subscriptions/{}", topic, sub).to_lowercase()
+}
+
+fn message_key(path: &str) -> String {
+    entity_path::to_data_plane_path(path).to_lowercase()
+}
+
+/// Error as the service reports it, so callers classify it the same way.
+fn service_error(status: u16, detail: &str) -> ServiceBusError {
+    api_error(
+        status,
+        format!(
+            "<Error><Code>{}</Code><Detail>{}</Detail></Error>",
+            status,
+            escape(detail)
+        ),
+    )
+}
+
+fn disabled(path: &str) -> ServiceBusError {
+    service_error(
+        403,
+        &format!("Messaging entity '{}' is currently disabled.", path),
+    )
+}
+
+/// `Sun, 05 Sep 2026 05:06:43 GMT`, the format of `EnqueuedTimeUtc`.
+fn header_time(at: DateTime<Utc>) -> String {
+    at.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+}
+
+fn entry(title: &str, description: &str) -> String {
+    format!(
+        r#"<entry xmlns="http://www.w3.org/2005/Atom"><title type="text">{}</title><content type="application/xml">{}</content></entry>"#,
+        escape(title),
+        description
+    )
+}
+
+fn feed(entries: impl Iterator<Item = String>) -> String {
+    format!(
+        r#"<feed xmlns="http://www.w3.org/2005/Atom">{}</feed>"#,
+        entries.collect::<String>()
+    )
+}
+
+/// `description` with runtime elements added before its closing `</root>`.
+fn with_runtime(description: String, root: &str, runtime: &str) -> String {
+    let close = format!("</{}>", root);
+    match description.strip_suffix(&close) {
+        Some(open) => format!("{}{}{}", open, runtime, close),
+        None => description,
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct Counts {
+    active: usize,
+    dead_letters: usize,
+    scheduled: usize,
+    bytes: usize,
+}
+
+impl Counts {
+    fn xml(&self) -> String {
+        format!(
+            concat!(
+                "<SizeInBytes>{}</SizeInBytes><MessageCount>{}</MessageCount>",
+                r#"<CountDetails xmlns:d2p1="http://schemas.microsoft.com/netservices/2011/06/servicebus">"#,
+                "<d2p1:ActiveMessageCount>{}</d2p1:ActiveMessageCount>",
+                "<d2p1:DeadLetterMessageCount>{}</d2p1:DeadLetterMessageCount>",
+                "<d2p1:ScheduledMessageCount>{}</d2p1:ScheduledMessageCount>",
+                "<d2p1:TransferMessageCount>0</d2p1:TransferMessageCount>",
+                "<d2p1:TransferDeadLetterMessageCount>0</d2p1:TransferDeadLetterMessageCount>",
+                "</CountDetails>"
+            ),
+            self.bytes,
+            self.active + self.dead_letters + self.scheduled,
+            self.active,
+            self.dead_letters,
+            self.scheduled
+        )
+    }
+}
+
+const DEFAULT_RULE: &str = r#"<RuleDescription xmlns="http://schemas.microsoft.com/netservices/2010/10/servicebus/connect" xmlns:i="http://www.w3.org/2001/XMLSchema-instance"><Filter i:type="TrueFilter"><SqlExpression>1=1</SqlExpression><CompatibilityLevel>20</CompatibilityLevel></Filter><Action i:type="EmptyRuleAction"/><Name>$Default</Name></RuleDescription>"#;
+
+const AUTHORIZATION_RULES: &str = r#"<entry xmlns="http://www.w3.org/2005/Atom"><content type="application/xml"><ArrayOfAuthorizationRule xmlns="http://schemas.microsoft.com/netservices/2010/10/servicebus/connect" xmlns:i="http://www.w3.org/2001/XMLSchema-instance"><AuthorizationRule i:type="SharedAccessAuthorizationRule"><ClaimType>SharedAccessKey</ClaimType><ClaimValue>None</ClaimValue><Rights><AccessRights>Manage</AccessRights><AccessRights>Listen</AccessRights><AccessRights>Send</AccessRights></Rights><KeyName>RootManageSharedAccessKey</KeyName></AuthorizationRule><AuthorizationRule i:type="SharedAccessAuthorizationRule"><ClaimType>SharedAccessKey</ClaimType><ClaimValue>None</ClaimValue><Rights><AccessRights>Send</AccessRights></Rights><KeyName>web-shop-send</KeyName></AuthorizationRule></ArrayOfAuthorizationRule></content></entry>"#;
+
+impl DemoState {
+    // ────────── Management (ATOM) ──────────
+
+    pub(crate) fn get_atom(&self, path: &str) -> Result<String> {
+        let not_found = || ServiceBusError::NotFound(path.to_string());
+        match Resource::parse(path) {
+            Resource::Queues => Ok(feed(self.queues.values().map(|q| self.queue_entry(q)))),
+            Resource::Topics => Ok(feed(self.topics.values().map(|t| self.topic_entry(t)))),
+            Resource::AuthorizationRules => Ok(AUTHORIZATION_RULES.to_string()),
+            Resource::NamespaceInfo => Ok(entry(
+                DEMO_NAMESPACE,
+                r#"<NamespaceInfo xmlns="http://schemas.microsoft.com/netservices/2010/10/servicebus/connect"><Name>contoso-demo</Name><MessagingSKU>Standard</MessagingSKU><MessagingUnits>1</MessagingUnits></NamespaceInfo>"#,
+            )),
+            Resource::Subscriptions(topic) => {
+                let topic = self
+                    .topics
+                    .get(&topic.to_lowercase())
+                    .ok_or_else(not_found)?;
+                Ok(feed(
+                    self.topic_subscriptions(&topic.name)
+                        .map(|(_, s)| self.subscription_entry(s)),
+                ))
+            }
+            Resource::Subscription(topic, sub) => self
+                .subscriptions
+                .get(&subscription_key(topic, sub))
+                .map(|s| self.subscription_entry(s))
+                .ok_or_else(not_found),
+            Resource::Rules(topic, sub) => {
+                let rules = self
+                    .rules
+                    .get(&subscription_key(topic, sub))
+                    .ok_or_else(not_found)?;
+                Ok(feed(rules.iter().map(|(name, xml)| entry(name, xml))))
+            }
+            Resource::Rule(topic, sub, rule) => self
+                .rules
+                .get(&subscription_key(topic, sub))
+                .and_then(|rules| {
+                    rules
+                        .iter()
+                        .find(|(name, _)| name.eq_ignore_ascii_case(rule))
+                })
+                .map(|(name, xml)| entry(name, xml))
+                .ok_or_else(not_found),
+            Resource::Entity(name) => {
+                let key = name.to_lowercase();
+                if let Some(q) = self.queues.get(&key) {
+                    Ok(self.queue_entry(q))
+                } else if let Some(t) = self.topics.get(&key) {
+                    Ok(self.topic_entry(t))
+                } else {
+                    Err(not_found())
+                }
+            }
+        }
+    }
+
+    /// Create (or with `if_match`, replace) the entity at `path` from an
+    /// entry built by `ManagementClient`.
+    pub(crate) fn put_atom(&mut self, path: &str, body: &str, if_match: bool) -> Result<String> {
+        let check = |exists: bool| match (exists, if_match) {
+            (true, false) => Err(service_error(
+                409,
+                &format!("The messaging entity '{}' already exists.", path),
+            )),
+            (false, true) => Err(ServiceBusError::NotFound(path.to_string())),
+            _ => Ok(()),
+        };
+        match Resource::parse(path) {
+            Resource::Entity(name) => {
+                let key = name.to_lowercase();
+                let is_topic = body.contains("<TopicDescription");
+                if !is_topic && !body.contains("<QueueDescription") {
+                    return Err(service_error(400, "Unsupported entity description."));
+                }
+                // A name is either a queue or a topic, never both
+                let (same, other) = if is_topic {
+                    (
+                        self.topics.contains_key(&key),
+                        self.queues.contains_key(&key),
+                    )
+                } else {
+                    (
+                        self.queues.contains_key(&key),
+                        self.topics.contains_key(&key),
+                    )
+                };
+                check(same || other)?;
+                if other {
+                    return Err(service_error(
+                        409,
+                        &format!("'{}' is a different kind of entity.", name),
+                    ));
+                }
+                if is_topic {
+                    let desc = TopicDescription {
+                        name: name.to_string(),
+                        ..parse_topic_from_entry(body)
+                    };
+                    self.topics.insert(key, desc);
+                } else {
+                    let desc = QueueDescription {
+                        name: name.to_string(),
+                        ..parse_queue_from_entry(body)
+                    };
+                    self.queues.insert(key, desc);
+                }
+                self.get_atom(name)
+            }
+            Resource::Subscription(topic, sub) => {
+                let Some(topic) = self
+                    .topics
+                    .get(&topic.to_lowercase())
+                    .map(|t| t.name.clone())
+                else {
+                    return Err(ServiceBusError::NotFound(topic.to_string()));
+                };
+                let key = subscription_key(&topic, sub);
+                check(self.subscriptions.contains_key(&key))?;
+                let desc = SubscriptionDescription {
+                    name: sub.to_string(),
+                    ..parse_subscription_from_entry(&topic, body)
+                };
+                self.subscriptions.insert(key.clone(), desc);
+                self.rules
+                    .entry(key)
+                    .or_insert_with(|| vec![("$Default".to_string(), DEFAULT_RULE.to_string())]);
+                self.get_atom(path)
+            }
+            Resource::Rule(topic, sub, rule) => {
+                let key = subscription_key(topic, sub);
+                let Some(rules) = self.rules.get_mut(&key) else {
+                    return Err(ServiceBusError::NotFound(path.to_string()));
+                };
+                let existing = rules.iter().position(|(n, _)| n.eq_ignore_ascii_case(rule));
+                check(existing.is_some())?;
+                let description = body
+                    .find("<RuleDescription")
+                    .zip(body.rfind("</RuleDescription>"))
+                    .map(|(start, end)| body[start..end + "</RuleDescription>".len()].to_string())
+                    .ok_or_else(|| service_error(400, "Missing rule description."))?;
+                match existing {
+                    Some(i) => rules[i].1 = description,
+                    None => rules.push((rule.to_string(), description)),
+                }
+                self.get_atom(path)
+            }
+            _ => Err(service_error(
+                405,
+                "The requested HTTP operation is not supported.",
+            )),
+        }
+    }
+
+    pub(crate) fn delete(&mut self, path: &str) -> Result<()> {
+        let not_found = || ServiceBusError::NotFound(path.to_string());
+        match Resource::parse(path) {
+            Resource::Entity(name) => {
+                let key = name.to_lowercase();
+                if self.queues.remove(&key).is_some() {
+                    self.drop_messages(&key);
+                } else if self.topics.remove(&key).is_some() {
+                    let prefix = format!("{}/subscriptions/", key);
+                    let subs: Vec<String> = self
+                        .subscriptions
+                        .keys()
+                        .filter(|k| k.starts_with(&prefix))
+                        .cloned()
+                        .collect();
+                    for sub in subs {
+                        self.subscriptions.remove(&sub);
+                        self.rules.remove(&sub);
+                        self.drop_messages(&sub);
+                    }
+                } else {
+                    return Err(not_found());
+                }
+            }
+            Resource::Subscription(topic, sub) => {
+                let key = subscription_key(topic, sub);
+                self.subscriptions.remove(&key).ok_or_else(not_found)?;
+                self.rules.remove(&key);
+                self.drop_messages(&key);
+            }
+            Resource::Rule(topic, sub, rule) => {
+                let rules = self
+                    .rules
+                    .get_mut(&subscription_key(topic, sub))
+                    .ok_or_else(not_found)?;
+                let i = rules
+                    .iter()
+                    .position(|(n, _)| n.eq_ignore_ascii_case(rule))
+                    .ok_or_else(not_found)?;
+                rules.remove(i);
+            }
+            _ => return Err(not_found()),
+        }
+        Ok(())
+    }
+
+    fn drop_messages(&mut self, key: &str) {
+        self.messages.remove(key);
+        self.messages.remove(&format!("{}/$deadletterqueue", key));
+    }
+
+    fn topic_subscriptions<'s>(
+        &'s self,
+        topic: &str,
+    ) -> impl Iterator<Item = (&'s String, &'s SubscriptionDescription)> {
+        let prefix = format!("{}/subscriptions/", topic.to_lowercase());
+        self.subscriptions
+            .iter()
+            .filter(move |(k, _)| k.starts_with(&prefix))
+    }
+
+    fn counts(&self, key: &str) -> Counts {
+        let now = Utc::now();
+        let mut counts = Counts::default();
+        for stored in self.messages.get(key).into_iter().flatten() {
+            if stored.visible_from.is_some_and(|at| at > now) {
+                counts.scheduled += 1;
+            } else {
+                counts.active += 1;
+            }
+            counts.bytes += stored.message.body.len();
+        }
+        for stored in self
+            .messages
+            .get(&format!("{}/$deadletterqueue", key))
+            .into_iter()
+            .flatten()
+        {
+            counts.dead_letters += 1;
+            counts.bytes += stored.message.body.len();
+        }
+        counts
+    }
+
+    fn queue_entry(&self, queue: &QueueDescription) -> String {
+        let counts = self.counts(&queue.name.to_lowercase());
+        let description = with_runtime(
+            queue_description_xml(queue),
+            "QueueDescription",
+            &counts.xml(),
+        );
+        entry(&queue.name, &description)
+    }
+
+    fn topic_entry(&self, topic: &TopicDescription) -> String {
+        // Scheduled messages wait in the topic; size is what its subscriptions hold
+        let mut counts = Counts::default();
+        let mut subscription_count = 0;
+        for (key, _) in self.topic_subscriptions(&topic.name) {
+            let sub = self.counts(key);
+            counts.scheduled = counts.scheduled.max(sub.scheduled);
+            counts.bytes += sub.bytes;
+            subscription_count += 1;
+        }
+        let runtime = format!(
+            "{}<SubscriptionCount>{}</SubscriptionCount>",
+            Counts {
+                active: 0,
+                dead_letters: 0,
+                ..counts
+            }
+            .xml(),
+            subscription_count
+        );
+        let description = with_runtime(topic_description_xml(topic), "TopicDescription", &runtime);
+        entry(&topic.name, &description)
+    }
+
+    fn subscription_entry(&self, sub: &SubscriptionDescription) -> String {
+        let counts = self.counts(&subscription_key(&sub.topic_name, &sub.name));
+        let description = with_runtime(
+            subscription_description_xml(sub),
+            "SubscriptionDescription",
+            &counts.xml(),
+        );
+        entry(&sub.name, &description)
+    }
+
+    // ────────── Data plane ──────────
+
+    pub(crate) fn send(&mut self, path: &str, message: &ServiceBusMessage) -> Result<()> {
+        let targets = self.deliveries(&message_key(path), 0)?;
+        self.next_sequence += 1;
+        let stored = stored_message(message, self.next_sequence, Utc::now());
+        for target in targets {
+            self.messages
+                .entry(target)
+                .or_default()
+                .push(stored.clone());
+        }
+        Ok(())
+    }
+
+    /// Message lists a send to `key` lands in: the queue itself, or each
+    /// subscription of a topic, following auto-forwarding.
+    fn deliveries(&self, key: &str, hops: usize) -> Result<Vec<String>> {
+        let forward = |forward_to: &Option<String>| {
+            forward_to
+                .as_deref()
+                .map(entity_path::forward_target)
+                .filter(|t| !t.is_empty() && hops < MAX_FORWARD_HOPS)
+                .map(str::to_lowercase)
+        };
+        let send_blocked = |status: &Option<String>| {
+            matches!(status.as_deref(), Some("Disabled" | "SendDisabled"))
+        };
+
+        if let Some(queue) = self.queues.get(key) {
+            if send_blocked(&queue.status) {
+                return Err(disabled(&queue.name));
+            }
+            return match forward(&queue.forward_to) {
+                Some(target) => self.deliveries(&target, hops + 1),
+                None => Ok(vec![key.to_string()]),
+            };
+        }
+        let Some(topic) = self.topics.get(key) else {
+            return Err(ServiceBusError::NotFound(key.to_string()));
+        };
+        if send_blocked(&topic.status) {
+            return Err(disabled(&topic.name));
+        }
+        let mut targets = Vec::new();
+        for (sub_key, sub) in self.topic_subscriptions(&topic.name) {
+            match forward(&sub.forward_to) {
+                Some(target) => targets.extend(self.deliveries(&target, hops + 1)?),
+                None => targets.push(sub_key.clone()),
+            }
+        }
+        Ok(targets)
+    }
+
+    /// Message list to receive from at `path`, after checking the queue or
+    /// subscription exists and accepts receives.
+    fn receive_key(&self, path: &str) -> Result<String> {
+        let key = message_key(path);
+        let entity = entity_path::without_subqueue(&key);
+        let status = match (self.queues.get(entity), self.subscriptions.get(entity)) {
+            (Some(q), _) => &q.status,
+            (None, Some(s)) => &s.status,
+            (None, None) => return Err(ServiceBusError::NotFound(path.to_string())),
+        };
+        if key == entity && matches!(status.as_deref(), Some("Disabled" | "ReceiveDisabled")) {
+            return Err(disabled(path));
+        }
+        Ok(key)
+    }
+
+    pub(crate) fn receive_and_delete(&mut self, path: &str) -> Result<Option<ReceivedMessage>> {
+        let key = self.receive_key(path)?;
+        let now = Utc::now();
+        let Some(list) = self.messages.get_mut(&key) else {
+            return Ok(None);
+        };
+        let Some(i) = list.iter().position(|m| m.available(now)) else {
+            return Ok(None);
+        };
+        let mut message = list.remove(i).message;
+        *message.broker_properties.delivery_count.get_or_insert(0) += 1;
+        Ok(Some(message))
+    }
+
+    pub(crate) fn peek_lock(&mut self, path: &str) -> Result<Option<ReceivedMessage>> {
+        let key = self.receive_key(path)?;
+        let now = Utc::now();
+        self.next_lock += 1;
+        let lock = format!(
+            "{:08x}-0000-4000-8000-{:012x}",
+            self.next_lock, self.next_lock
+        );
+        let until = now + chrono::Duration::seconds(LOCK_DURATION_SECS);
+
+        let Some(stored) = self
+            .messages
+            .get_mut(&key)
+            .and_then(|list| list.iter_mut().find(|m| m.available(now)))
+        else {
+            return Ok(None);
+        };
+        stored.lock = Some((lock.clone(), until));
+        let props = &mut stored.message.broker_properties;
+        *props.delivery_count.get_or_insert(0) += 1;
+
+        let mut message = stored.message.clone();
+        let sequence = message
+            .broker_properties
+            .sequence_number
+            .unwrap_or_default();
+        message.broker_properties.lock_token = Some(lock.clone());
+        message.broker_properties.locked_until_utc = Some(header_time(until));
+        message.lock_token_uri = Some(format!(
+            "https://{}/{}/messages/{}/{}",
+            DEMO_NAMESPACE, key, sequence, lock
+        ));
+        Ok(Some(message))
+    }
+
+    /// The list and index of the message locked by `lock_token_uri`.
+    fn locked(&self, lock_token_uri: &str) -> Result<(String, usize)> {
+        let lock = lock_token_uri.rsplit('/').next().unwrap_or_default();
+        let now = Utc::now();
+        self.messages
+            .iter()
+            .find_map(|(key, list)| {
+                list.iter()
+                    .position(|m| {
+                        m.lock
+                            .as_ref()
+                            .is_some_and(|(token, until)| token == lock && *until > now)
+                    })
+                    .map(|i| (key.clone(), i))
+            })
+            .ok_or_else(|| {
+                service_error(
+                    410,
+                    "The lock supplied is invalid. Either the lock expired, or the message has already been removed from the queue.",
+                )
+            })
+    }
+
+    pub(crate) fn complete(&mut self, lock_token_uri: &str) -> Result<()> {
+        let (key, i) = self.locked(lock_token_uri)?;
+        if let Some(list) = self.messages.get_mut(&key) {
+            list.remove(i);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn abandon(&mut self, lock_token_uri: &str) -> Result<()> {
+        let (key, i) = self.locked(lock_token_uri)?;
+        if let Some(list) = self.messages.get_mut(&key) {
+            list[i].lock = None;
+        }
+        Ok(())
+    }
+
+    // ────────── Seed data ──────────
+
+    fn seed(&mut self, now: DateTime<Utc>) {
+        for (name, configure) in [
+            ("orders", None),
+            ("orders-retry", Some(("forward", "orders"))),
+            ("payments", Some(("session", ""))),
+            ("email-outbox", None),
+            ("audit-log", None),
+            ("legacy-import", Some(("status", "Disabled"))),
+        ] {
+            let mut queue = default_queue(name);
+            match configure {
+                Some(("forward", to)) => queue.forward_to = Some(to.to_string()),
+                Some(("session", _)) => queue.requires_session = Some(true),
+                Some(("status", status)) => queue.status = Some(status.to_string()),
+                _ => {}
+            }
+            self.queues.insert(name.to_string(), queue);
+        }
+        for (topic, subs) in [
+            (
+                "order-events",
+                &["analytics", "audit", "billing", "shipping"][..],
+            ),
+            ("inventory", &["warehouse-east", "warehouse-west"][..]),
+        ] {
+            self.topics.insert(topic.to_string(), default_topic(topic));
+            for &sub in subs {
+                let mut desc = default_subscription(topic, sub);
+                if sub == "audit" {
+                    desc.forward_to = Some("audit-log".to_string());
+                }
+                let key = subscription_key(topic, sub);
+                self.subscriptions.insert(key.clone(), desc);
+                self.rules.insert(
+                    key,
+                    vec![("$Default".to_string(), DEFAULT_RULE.to_string())],
+                );
+            }
+        }
+
+        let tenants = ["contoso", "fabrikam", "northwind"];
+        let order = |i: usize| ServiceBusMessage {
+            body: format!(
+                r#"{{"orderId":"ORD-{}","customerId":"C-{}","total":{}.{:02},"currency":"EUR","items":{}}}"#,
+                10231 + i,
+                1040 + i * 7 % 90,
+                20 + i * 13 % 180,
+                i * 29 % 100,
+                1 + i % 5
+            ),
+            label: Some("order.created".to_string()),
+            custom_properties: vec![
+                ("tenantId".to_string(), tenants[i % 3].to_string()),
+                ("eventType".to_string(), "OrderCreated".to_string()),
+                ("source".to_string(), "web-shop".to_string()),
+            ],
+            ..Default::default()
+        };
+        let payment = |i: usize| ServiceBusMessage {
+            body: format!(
+                r#"{{"paymentId":"PAY-{}","orderId":"ORD-{}","amount":{}.00,"method":"{}"}}"#,
+                7710 + i,
+                10231 + i * 3,
+                15 + i * 11 % 90,
+                ["card", "invoice", "paypal"][i % 3]
+            ),
+            label: Some("payment.requested".to_string()),
+            session_id: Some(format!("customer-{}", [1042, 2210, 3307][i % 3])),
+            custom_properties: vec![("tenantId".to_string(), tenants[i % 3].to_string())],
+            ..Default::default()
+        };
+        let email = |i: usize| ServiceBusMessage {
+            body: format!(
+                r#"{{"to":"customer{}@example.com","template":"{}","orderId":"ORD-{}"}}"#,
+                100 + i,
+                ["order-confirmation", "shipping-update", "invoice"][i % 3],
+                10231 + i
+            ),
+            label: Some("email.send".to_string()),
+            content_type: Some("application/json".to_string()),
+            ..Default::default()
+        };
+        let stock = |i: usize| ServiceBusMessage {
+            body: format!(
+                r#"{{"sku":"SKU-{}","warehouse":"{}","delta":{}}}"#,
+                4000 + i * 17,
+                ["east", "west"][i % 2],
+                i as i64 % 7 - 3
+            ),
+            label: Some("stock.changed".to_string()),
+            custom_properties: vec![("eventType".to_string(), "StockChanged".to_string())],
+            ..Default::default()
+        };
+
+        let mut seed = |path: &str, count: usize, make: &dyn Fn(usize) -> ServiceBusMessage| {
+            for i in 0..count {
+                self.next_sequence += 1;
+                let enqueued = now - chrono::Duration::minutes(((count - i) * 7) as i64);
+                let stored = stored_message(&make(i), self.next_sequence, enqueued);
+                self.messages
+                    .entry(message_key(path))
+                    .or_default()
+                    .push(stored);
+            }
+        };
+        seed("orders", 14, &order);
+        seed("payments", 9, &payment);
+        seed("email-outbox", 5, &email);
+        seed("audit-log", 20, &order);
+        seed("order-events/subscriptions/analytics", 12, &order);
+        seed("order-events/subscriptions/billing", 6, &order);
+        seed("order-events/subscriptions/shipping", 4, &order);
+        seed("inventory/subscriptions/warehouse-east", 3, &stock);
+        seed("inventory/subscriptions/warehouse-west", 5, &stock);
+
+        for (path, count, reason, description) in [
+            (
+                "orders",
+                3,
+                "MaxDeliveryCountExceeded",
+                "Message could not be consumed after 10 delivery attempts.",
+            ),
+            (
+                "payments",
+                1,
+                "PaymentProviderTimeout",
+                "Provider did not answer within 30s.",
+            ),
+            (
+                "email-outbox",
+                6,
+                "SmtpRejected",
+                "550 5.1.1 Recipient address rejected.",
+            ),
+            (
+                "order-events/subscriptions/analytics",
+                2,
+                "SchemaValidationFailed",
+                "Required property 'currency' is missing.",
+            ),
+            (
+                "inventory/subscriptions/warehouse-west",
+                1,
+                "MaxDeliveryCountExceeded",
+                "Message could not be consumed after 10 delivery attempts.",
+            ),
+        ] {
+            let dlq = format!("{}/$deadletterqueue", message_key(path));
+            for i in 0..count {
+                self.next_sequence += 1;
+                let make: &dyn Fn(usize) -> ServiceBusMessage = match path {
+                    "payments" => &payment,
+                    "email-outbox" => &email,
+                    p if p.starts_with("inventory") => &stock,
+                    _ => &order,
+                };
+                let enqueued = now - chrono::Duration::hours(2 + i as i64);
+                let mut stored = stored_message(&make(40 + i), self.next_sequence, enqueued);
+                let props = &mut stored.message.broker_properties;
+                props.delivery_count = Some(10);
+                props.dead_letter_reason = Some(reason.to_string());
+                props.dead_letter_error_description = Some(description.to_string());
+                props.dead_letter_source = Some(path.to_string());
+                self.messages.entry(dlq.clone()).or_default().push(stored);
+            }
+        }
+    }
+}
+
+fn default_queue(name: &str) -> QueueDescription {
+    QueueDescription {
+        name: name.to_string(),
+        lock_duration: Some("PT1M".to_string()),
+        max_size_in_megabytes: Some(1024),
+        requires_duplicate_detection: Some(false),
+        requires_session: Some(false),
+        default_message_time_to_live: Some("P14D".to_string()),
+        dead_lettering_on_message_expiration: Some(false),
+        max_delivery_count: Some(10),
+        enable_batched_operations: Some(true),
+        status: Some("Active".to_string()),
+        enable_partitioning: Some(false),
+        ..Default::default()
+    }
+}
+
+fn default_topic(name: &str) -> TopicDescription {
+    TopicDescription {
+        name: name.to_string(),
+        max_size_in_megabytes: Some(1024),
+        default_message_time_to_live: Some("P14D".to_string()),
+        requires_duplicate_detection: Some(false),
+        enable_batched_operations: Some(true),
+        status: Some("Active".to_string()),
+        support_ordering: Some(true),
+        enable_partitioning: Some(false),
+        ..Default::default()
+    }
+}
+
+fn default_subscription(topic: &str, name: &str) -> SubscriptionDescription {
+    SubscriptionDescription {
+        name: name.to_string(),
+        topic_name: topic.to_string(),
+        lock_duration: Some("PT1M".to_string()),
+        requires_session: Some(false),
+        default_message_time_to_live: Some("P14D".to_string()),
+        dead_lettering_on_message_expiration: Some(false),
+        max_delivery_count: Some(10),
+        enable_batched_operations: Some(true),
+        status: Some("Active".to_string()),
+        ..Default::default()
+    }
+}
+
+/// A sent message as the broker stores it, enqueued at `now` unless it is
+/// scheduled for later.
+fn stored_message(message: &ServiceBusMessage, sequence: i64, now: DateTime<Utc>) -> StoredMessage {
+    let scheduled = message
+        .scheduled_enqueue_time
+        .as_deref()
+        .and_then(|t| DateTime::parse_from_rfc2822(t).ok())
+        .map(|t| t.with_timezone(&Utc))
+        .filter(|t| *t > now);
+    let broker_properties = BrokerProperties {
+        message_id: Some(
+            message
+                .message_id
+                .clone()
+                .unwrap_or_else(|| format!("{:08x}-5eed-4000-8000-{:012x}", sequence, sequence)),
+        ),
+        correlation_id: message.correlation_id.clone(),
+        session_id: message.session_id.clone(),
+        label: message.label.clone(),
+        to: message.to.clone(),
+        reply_to: message.reply_to.clone(),
+        reply_to_session_id: message.reply_to_session_id.clone(),
+        content_type: message.content_type.clone(),
+        sequence_number: Some(sequence),
+        enqueued_time_utc: Some(header_time(scheduled.unwrap_or(now))),
+        time_to_live: message.time_to_live.as_deref().and_then(|t| t.parse().ok()),
+        delivery_count: Some(0),
+        state: Some(
+            if scheduled.is_some() {
+                "Scheduled"
+            } else {
+                "Active"
+            }
+            .to_string(),
+        ),
+        partition_key: message.partition_key.clone(),
+        via_partition_key: message.via_partition_key.clone(),
+        scheduled_enqueue_time_utc: scheduled.map(header_time),
+        size: Some(message.body.len() as i64),
+        ..Default::default()
+    };
+    StoredMessage {
+        message: ReceivedMessage {
+            body: message.body.clone(),
+            broker_properties,
+            custom_properties: message.custom_properties.clone(),
+            lock_token_uri: None,
+            source_entity: None,
+            raw_body: None,
+        },
+        visible_from: scheduled,
+        lock: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConnectionConfig, DataPlaneClient, ManagementClient};
+    use std::sync::Arc;
+
+    fn clients() -> (ManagementClient, DataPlaneClient) {
+        let namespace = Arc::new(DemoNamespace::seeded().without_latency());
+        (
+            ManagementClient::with_transport(ConnectionConfig::demo(&namespace), namespace.clone()),
+            DataPlaneClient::with_transport(namespace),
+        )
+    }
+
+    #[tokio::test]
+    async fn calls_are_counted_in_the_config_stats() {
+        let namespace = Arc::new(DemoNamespace::seeded().without_latency());
+        let config = ConnectionConfig::demo(&namespace);
+        let mgmt = ManagementClient::with_transport(config.clone(), namespace);
+        mgmt.get_queue("orders").await.unwrap();
+        assert!(mgmt.get_queue("missing").await.is_err());
+
+        let summary = config.request_stats().summary(Api::Management);
+        assert_eq!(summary.calls, 2);
+        assert_eq!(summary.failed, 1);
+    }
+
+    #[tokio::test]
+    async fn seeded_namespace_lists_entities_with_counts() {
+        let (mgmt, _) = clients();
+        let queues = mgmt.list_queues_with_counts().await.unwrap();
+        let orders = queues.iter().find(|(q, ..)| q.name == "orders").unwrap();
+        assert_eq!((orders.1, orders.2), (14, 3));
+        assert_eq!(
+            mgmt.get_queue("orders-retry")
+                .await
+                .unwrap()
+                .forward_to
+                .as_deref(),
+            Some("orders")
+        );
+
+        let subs = mgmt
+            .list_subscriptions_with_counts("order-events")
+            .await
+            .unwrap();
+        assert_eq!(subs.len(), 4);
+        let info = mgmt.get_namespace_info().await.unwrap();
+        assert_eq!(info.messaging_sku.as_deref(), Some("Standard"));
+        assert!(matches!(
+            mgmt.get_queue("missing").await,
+            Err(ServiceBusError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn subscription_routes_keep_the_requested_order() {
+        let (mgmt, _) = clients();
+        let names: Vec<String> = ["shipping", "missing", "analytics"]
+            .iter()
+            .map(|n| n.to_string())
+            .collect();
+        let routes = mgmt.list_subscription_routes("order-events", &names).await;
+        let listed: Vec<(&str, bool)> = routes
+            .iter()
+            .map(|r| (r.name.as_str(), r.rules.is_some()))
+            .collect();
+        assert_eq!(
+            listed,
+            [("shipping", true), ("missing", false), ("analytics", true)]
+        );
+    }
+
+    #[tokio::test]
+    async fn entities_can_be_created_patched_and_deleted() {
+        let (mgmt, _) = clients();
+        let desc = QueueDescription {
+            name: "invoices".to_string(),
+            max_delivery_count: Some(5),
+            ..Default::default()
+        };
+        mgmt.create_queue(&desc).await.unwrap();
+        let err = mgmt.create_queue(&desc).await.unwrap_err();
+        assert_eq!(err.kind(), crate::ErrorKind::Conflict);
+
+        let patched = mgmt
+            .patch_queue("invoices", &[("ForwardTo", "orders")])
+            .await
+            .unwrap();
+        assert_eq!(patched.forward_to.as_deref(), Some("orders"));
+        assert_eq!(patched.max_delivery_count, Some(5));
+
+        mgmt.create_subscription(&SubscriptionDescription {
+            name: "fraud".to_string(),
+            topic_name: "order-events".to_string(),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+        let rules = mgmt
+            .list_subscription_rules("order-events", "fraud")
+            .await
+            .unwrap();
+        assert_eq!(rules[0].name, "$Default");
+
+        mgmt.delete_topic("order-events").await.unwrap();
+        assert!(mgmt.list_subscriptions("order-events").await.is_err());
+        mgmt.delete_queue("invoices").await.unwrap();
+        assert!(mgmt.delete_queue("invoices").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn messages_fan_out_forward_and_settle() {
+        let (mgmt, dp) = clients();
+        let message = ServiceBusMessage {
+            body: "{}".to_string(),
+            ..Default::default()
+        };
+        // order-events/audit forwards to audit-log
+        dp.send_message("order-events", &message).await.unwrap();
+        let count = |path: &'static str| {
+            let mgmt = mgmt.clone();
+            async move { mgmt.get_queue_runtime_info(path).await.unwrap() }
+        };
+        assert_eq!(count("audit-log").await.active_message_count, 21);
+        let billing = mgmt
+            .get_subscription_runtime_info("order-events", "billing")
+            .await
+            .unwrap();
+        assert_eq!(billing.active_message_count, 7);
+        dp.send_message("orders-retry", &message).await.unwrap();
+        assert_eq!(count("orders").await.active_message_count, 15);
+
+        // Peeking abandons its locks, so nothing leaves the queue
+        let peeked = dp.peek_messages("orders", 5).await.unwrap();
+        assert_eq!(peeked.len(), 5);
+        assert_eq!(peeked[0].broker_properties.delivery_count, Some(1));
+        assert_eq!(count("orders").await.active_message_count, 15);
+
+        let dead = dp
+            .peek_messages("orders/$deadletterqueue", 1)
+            .await
+            .unwrap();
+        let target = MessageRef::from_message(&dead[0]).unwrap();
+        assert!(dp.remove_from_dlq("orders", &target).await.unwrap());
+        assert_eq!(count("orders").await.dead_letter_message_count, 2);
+
+        let purged = dp.purge_concurrent("orders", 4, None, None).await.unwrap();
+        assert_eq!(purged, 15);
+        assert!(dp.send_message("legacy-import", &message).await.is_err());
+    }
+}
@@ -0,0 +1,178 @@
+//! Helpers for entity paths such as `orders`, `events/Subscriptions/billing`
+//! and `orders/$deadletterqueue`.
+
+/// Queue or topic that messages for `entity_path` are sent to: sub-queue
+/// suffixes (`/$deadletterqueue`, `/$transfer/$deadletterqueue`) and the
+/// subscription segment are stripped.
+pub fn send_target(entity_path: &str) -> &str {
+    let entity_path = without_subqueue(entity_path);
+    subscription_separator(entity_path)
+        .map(|(idx, _)| &entity_path[..idx])
+        .unwrap_or(entity_path)
+}
+
+/// `entity_path` without a trailing dead-letter or transfer sub-queue.
+pub fn without_subqueue(entity_path: &str) -> &str {
+    ["/$transfer/$deadletterqueue", "/$deadletterqueue"]
+        .iter()
+        .find_map(|suffix| entity_path.strip_suffix(suffix))
+        .unwrap_or(entity_path)
+}
+
+/// `(topic, subscription)` of a subscription path, with either the
+/// management (`/Subscriptions/`) or data-plane (`/subscriptions/`) segment.
+/// `None` for queues, topics and incomplete paths.
+pub fn split_subscription_path(entity_path: &str) -> Option<(&str, &str)> {
+    let (idx, sep_len) = subscription_separator(entity_path)?;
+    let topic = &entity_path[..idx];
+    let subscription = &entity_path[idx + sep_len..];
+
+    if topic.is_empty() || subscription.is_empty() {
+        None
+    } else {
+        Some((topic, subscription))
+    }
+}
+
+/// The path as the data plane expects it: the subscription segment in
+/// lower case.
+pub fn to_data_plane_path(entity_path: &str) -> String {
+    entity_path.replace("/Subscriptions/", "/subscriptions/")
+}
+
+/// Entity path of a `ForwardTo` target. The management API reports it as an
+/// absolute URI (`https://ns.servicebus.windows.net/orders`); paths typed by
+/// the user are returned as-is.
+pub fn forward_target(value: &str) -> &str {
+    match value.find("://") {
+        Some(idx) => {
+            let rest = &value[idx + 3..];
+            rest.find('/').map(|slash| &rest[slash + 1..]).unwrap_or("")
+        }
+        None => value.trim_start_matches('/'),
+    }
+}
+
+/// Azure Portal URL of a queue, topic (`is_topic`) or subscription, given
+/// the ARM coordinates of its namespace.
+pub fn portal_url(
+    subscription_id: &str,
+    resource_group: &str,
+    namespace: &str,
+    entity_path: &str,
+    is_topic: bool,
+) -> String {
+    let entity = match split_subscription_path(entity_path) {
+        Some((topic, sub)) => format!("topics/{}/subscriptions/{}", topic, sub),
+        None if is_topic => format!("topics/{}", entity_path),
+        None => format!("queues/{}", entity_path),
+    };
+    format!(
+        "https://portal.azure.com/#resource/subscriptions/{}/resourceGroups/{}/providers/Microsoft.ServiceBus/namespaces/{}/{}",
+        subscription_id, resource_group, namespace, entity
+    )
+}
+
+fn subscription_separator(entity_path: &str) -> Option<(usize, usize)> {
+    entity_path
+        .find("/Subscriptions/")
+        .map(|idx| (idx, "/Subscriptions/".len()))
+        .or_else(|| {
+            entity_path
+                .find("/subscriptions/")
+                .map(|idx| (idx, "/subscriptions/".len()))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        forward_target, portal_url, send_target, split_subscription_path, to_data_plane_path,
+    };
+
+    #[test]
+    fn send_target_returns_queue_or_topic_path() {
+        assert_eq!(send_target("queue-a"), "queue-a");
+        assert_eq!(send_target("topic-a"), "topic-a");
+        assert_eq!(send_target("topic-a/Subscriptions/sub-a"), "topic-a");
+        assert_eq!(send_target("topic-a/subscriptions/sub-a"), "topic-a");
+    }
+
+    #[test]
+    fn send_target_strips_dead_letter_sub_queues() {
+        assert_eq!(send_target("queue-a/$deadletterqueue"), "queue-a");
+        assert_eq!(send_target("queue-a/$transfer/$deadletterqueue"), "queue-a");
+        assert_eq!(
+            send_target("topic-a/Subscriptions/sub-a/$deadletterqueue"),
+            "topic-a"
+        );
+    }
+
+    #[test]
+    fn split_subscription_path_handles_both_subscription_casings() {
+        assert_eq!(
+            split_subscription_path("topic-a/Subscriptions/sub-a"),
+            Some(("topic-a", "sub-a"))
+        );
+        assert_eq!(
+            split_subscription_path("topic-a/subscriptions/sub-a"),
+            Some(("topic-a", "sub-a"))
+        );
+    }
+
+    #[test]
+    fn split_subscription_path_rejects_invalid_shapes() {
+        assert_eq!(split_subscription_path("topic-a/Subscriptions/"), None);
+        assert_eq!(split_subscription_path("/Subscriptions/sub-a"), None);
+        assert_eq!(split_subscription_path("queue-a"), None);
+    }
+
+    #[test]
+    fn portal_url_points_at_the_entity_blade() {
+        let prefix = "https://portal.azure.com/#resource/subscriptions/0000-11/resourceGroups/rg-msg/providers/Microsoft.ServiceBus/namespaces/orders-ns";
+        assert_eq!(
+            portal_url(
+                "0000-11",
+                "rg-msg",
+                "orders-ns",
+                "events/Subscriptions/audit",
+                false
+            ),
+            format!("{}/topics/events/subscriptions/audit", prefix)
+        );
+        assert_eq!(
+            portal_url("0000-11", "rg-msg", "orders-ns", "events", true),
+            format!("{}/topics/events", prefix)
+        );
+        assert_eq!(
+            portal_url("0000-11", "rg-msg", "orders-ns", "orders", false),
+            format!("{}/queues/orders", prefix)
+        );
+    }
+
+    #[test]
+    fn to_data_plane_path_normalizes_subscription_segment() {
+        assert_eq!(
+            to_data_plane_path("topic-a/Subscriptions/sub-a"),
+            "topic-a/subscriptions/sub-a"
+        );
+        assert_eq!(
+            to_data_plane_path("topic-a/Subscriptions/sub-a/$deadletterqueue"),
+            "topic-a/subscriptions/sub-a/$deadletterqueue"
+        );
+        assert_eq!(to_data_plane_path("queue-a"), "queue-a");
+    }
+
+    #[test]
+    fn forward_target_strips_namespace_uri() {
+        assert_eq!(
+            forward_target("https://ns.servicebus.windows.net/orders"),
+            "orders"
+        );
+        assert_eq!(
+            forward_target("sb://ns.servicebus.windows.net/events/Subscriptions/audit"),
+            "events/Subscriptions/audit"
+        );
+        assert_eq!(forward_target("orders"), "orders");
+    }
+}
@@ -0,0 +1,547 @@
+//! The error type of every client call.
+
+use thiserror::Error;
+
+/// Why a client call failed.
+#[derive(Error, Debug)]
+pub enum ServiceBusError {
+    /// The request did not complete: DNS, TLS, connection or timeout.
+    #[error("HTTP request failed: {0}")]
+    Http(#[from] reqwest::Error),
+
+    /// A response could not be parsed.
+    #[error("XML parsing error: {0}")]
+    Xml(#[from] quick_xml::DeError),
+
+    /// No token could be created, e.g. an invalid key or a failing credential.
+    #[error("Authentication error: {0}")]
+    Auth(String),
+
+    /// Any other non-success response.
+    #[error("Service Bus returned {status}: {}", summarize_body(.body))]
+    Api {
+        /// HTTP status code.
+        status: u16,
+        /// Response body, usually an `<Error>` document.
+        body: String,
+    },
+
+    /// 401 from Service Bus: the key was rotated, the SAS policy removed, or
+    /// the identity lost its role.
+    #[error("Unauthorized: {}", summarize_body(.body))]
+    Unauthorized {
+        /// Response body.
+        body: String,
+    },
+
+    /// 401 for an expired SAS token while the server's clock is `skew_secs`
+    /// ahead of ours (negative: behind). The credentials are fine.
+    #[error("{}", clock_skew_message(*.skew_secs))]
+    ClockSkew {
+        /// Server clock minus ours, in seconds.
+        skew_secs: i64,
+        /// Response body.
+        body: String,
+    },
+
+    /// 413 on send. `size_bytes` is the body length we sent, `max_bytes` the
+    /// limit quoted by the broker (when its error text mentions one).
+    #[error("{}", too_large_message(*.size_bytes, *.max_bytes))]
+    MessageTooLarge {
+        /// Body length of the rejected request.
+        size_bytes: Option<usize>,
+        /// The broker's limit, if it quoted one.
+        max_bytes: Option<usize>,
+    },
+
+    /// The connection string is missing a part or has a malformed one.
+    #[error("Connection string is invalid: {0}")]
+    InvalidConnectionString(String),
+
+    /// Nothing answered at the emulator endpoint (`UseDevelopmentEmulator=true`).
+    #[error("Service Bus emulator is not reachable at {0} — is it running?")]
+    EmulatorNotRunning(String),
+
+    /// The entity does not exist.
+    #[error("Entity not found: {0}")]
+    NotFound(String),
+
+    /// A request the client refuses to make (e.g. an empty rule name), or a
+    /// response it cannot work with.
+    #[error("Operation failed: {0}")]
+    Operation(String),
+
+    /// Anything else.
+    #[error("{0}")]
+    Other(#[from] anyhow::Error),
+}
+
+/// Result of a client call.
+pub type Result<T> = std::result::Result<T, ServiceBusError>;
+
+/// Error for a failed Service Bus response: `Unauthorized` for 401, `Api`
+/// otherwise.
+pub fn api_error(status: u16, body: String) -> ServiceBusError {
+    if status == 401 {
+        ServiceBusError::Unauthorized { body }
+    } else {
+        ServiceBusError::Api { status, body }
+    }
+}
+
+impl ServiceBusError {
+    /// HTTP status code, when the failure came back from the service.
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            ServiceBusError::Api { status, .. } => Some(*status),
+            ServiceBusError::Unauthorized { .. } | ServiceBusError::ClockSkew { .. } => Some(401),
+            ServiceBusError::MessageTooLarge { .. } => Some(413),
+            ServiceBusError::Http(e) => e.status().map(|s| s.as_u16()),
+            _ => None,
+        }
+    }
+
+    /// Raw response body for API errors (XML or JSON, possibly multi-line).
+    pub fn body(&self) -> Option<&str> {
+        match self {
+            ServiceBusError::Api { body, .. }
+            | ServiceBusError::Unauthorized { body }
+            | ServiceBusError::ClockSkew { body, .. } => Some(body),
+            _ => None,
+        }
+    }
+
+    /// Code, detail and tracking id from the body of an API error.
+    pub fn api_detail(&self) -> Option<ApiErrorDetail> {
+        self.body().map(parse_error_body)
+    }
+
+    /// What went wrong, from the status, error code and detail text.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            ServiceBusError::NotFound(_) => ErrorKind::NotFound,
+            ServiceBusError::Auth(_)
+            | ServiceBusError::Unauthorized { .. }
+            | ServiceBusError::ClockSkew { .. } => ErrorKind::Unauthorized,
+            ServiceBusError::Api { status, body } => {
+                ErrorKind::classify(*status, &parse_error_body(body))
+            }
+            ServiceBusError::Http(e) => match e.status() {
+                Some(status) => ErrorKind::classify(status.as_u16(), &ApiErrorDetail::default()),
+                None => ErrorKind::Other,
+            },
+            _ => ErrorKind::Other,
+        }
+    }
+}
+
+/// Classification of a failed request, so callers can branch without
+/// matching on response bodies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// 404, or the entity was missing from a feed.
+    NotFound,
+    /// 409: the entity already exists or was changed concurrently.
+    Conflict,
+    /// 429, 503 or a server-busy code; worth retrying after a pause.
+    Throttled,
+    /// The peek-lock expired or the message was settled elsewhere.
+    MessageLockLost,
+    /// The entity's status does not allow the operation.
+    EntityDisabled,
+    /// The entity is full, or a namespace limit was reached.
+    QuotaExceeded,
+    /// 401/403, or no token could be obtained.
+    Unauthorized,
+    /// Anything not classified above.
+    Other,
+}
+
+impl ErrorKind {
+    /// Short lowercase name for reports, e.g. `throttled`.
+    pub fn label(self) -> &'static str {
+        match self {
+            ErrorKind::NotFound => "not found",
+            ErrorKind::Conflict => "conflict",
+            ErrorKind::Throttled => "throttled",
+            ErrorKind::MessageLockLost => "lock lost",
+            ErrorKind::EntityDisabled => "entity disabled",
+            ErrorKind::QuotaExceeded => "quota exceeded",
+            ErrorKind::Unauthorized => "unauthorized",
+            ErrorKind::Other => "other",
+        }
+    }
+
+    fn classify(status: u16, detail: &ApiErrorDetail) -> Self {
+        let text = detail.detail.to_ascii_lowercase();
+        let code = detail.code.as_deref().unwrap_or("");
+        if status == 410 || text.contains("lock supplied is invalid") || text.contains("lock lost")
+        {
+            ErrorKind::MessageLockLost
+        } else if text.contains("is currently disabled") || code == "MessagingEntityDisabled" {
+            ErrorKind::EntityDisabled
+        } else if status == 429
+            || status == 503
+            || matches!(code, "50002" | "50004" | "50009" | "ServerBusy")
+            || text.contains("throttl")
+        {
+            ErrorKind::Throttled
+        } else if status == 401 {
+            ErrorKind::Unauthorized
+        } else if status == 404 {
+            ErrorKind::NotFound
+        } else if status == 409 {
+            // A conflict detail may well mention a quota, e.g. one being
+            // changed concurrently; the status says what went wrong
+            ErrorKind::Conflict
+        } else if code == "40300" || code.contains("QuotaExceeded") || text.contains("quota") {
+            // Service Bus reports exceeded quotas as 403 with SubCode 40300
+            ErrorKind::QuotaExceeded
+        } else if status == 403 {
+            ErrorKind::Unauthorized
+        } else {
+            ErrorKind::Other
+        }
+    }
+}
+
+/// The parts of a Service Bus (`<Error><Code>/<Detail>`) or ARM JSON error
+/// body.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ApiErrorDetail {
+    /// `<Code>` (often the HTTP status, sometimes a 5-digit Service Bus
+    /// code such as `40400`) or the JSON `code`.
+    pub code: Option<String>,
+    /// Human-readable detail without the tracking suffix.
+    pub detail: String,
+    /// `TrackingId` to quote to Azure support.
+    pub tracking_id: Option<String>,
+}
+
+/// Split an error body into code, detail and tracking id. Bodies in neither
+/// format keep their first non-empty line as the detail.
+pub fn parse_error_body(body: &str) -> ApiErrorDetail {
+    let json = serde_json::from_str::<serde_json::Value>(body).ok();
+    let code = xml_element(body, "Code").or_else(|| {
+        let json = json.as_ref()?;
+        json.pointer("/error/code")
+            .or_else(|| json.get("code"))
+            .map(|c| match c {
+                serde_json::Value::String(s) => s.clone(),
+                other => other.to_string(),
+            })
+    });
+
+    let summary = summarize_body(body);
+    let (detail, tracking_id) = match summary.find("TrackingId:") {
+        Some(pos) => {
+            let id = summary[pos + "TrackingId:".len()..]
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .next()
+                .unwrap_or("")
+                .to_string();
+            let detail = summary[..pos].trim_end_matches([' ', ',']).to_string();
+            (detail, (!id.is_empty()).then_some(id))
+        }
+        None => (summary, None),
+    };
+
+    ApiErrorDetail {
+        code,
+        detail,
+        tracking_id,
+    }
+}
+
+fn xml_element(body: &str, name: &str) -> Option<String> {
+    let open = format!("<{}>", name);
+    let close = format!("</{}>", name);
+    let start = body.find(&open)? + open.len();
+    let end = body[start..].find(&close)? + start;
+    Some(body[start..end].trim().to_string()).filter(|s| !s.is_empty())
+}
+
+/// Whether a 401 body says the token expired (or isn't valid yet), which
+/// with a correct key points at the clock.
+pub(crate) fn is_token_expiry(body: &str) -> bool {
+    let text = body.to_ascii_lowercase();
+    text.contains("expiredtoken")
+        || text.contains("token is expired")
+        || text.contains("has expired")
+        || text.contains("not yet valid")
+        || text.contains("notyetvalid")
+}
+
+fn clock_skew_message(skew_secs: i64) -> String {
+    let secs = skew_secs.unsigned_abs();
+    let magnitude = match secs {
+        0..=119 => format!("{}s", secs),
+        120..=3599 => format!("{} min", secs / 60),
+        _ => format!("{}h{:02}m", secs / 3600, secs / 60 % 60),
+    };
+    let direction = if skew_secs > 0 { "behind" } else { "ahead of" };
+    format!(
+        "SAS token rejected as expired: this machine's clock is {} {} the namespace's. \
+         New tokens are adjusted for the rest of the session; fix the system clock \
+         (e.g. enable time sync) to stop this.",
+        magnitude, direction
+    )
+}
+
+fn too_large_message(size_bytes: Option<usize>, max_bytes: Option<usize>) -> String {
+    match (size_bytes, max_bytes) {
+        (Some(n), Some(m)) => format!("Message too large ({} bytes). Max is {} bytes.", n, m),
+        (Some(n), None) => format!("Message too large ({} bytes).", n),
+        (None, Some(m)) => format!("Message too large. Max is {} bytes.", m),
+        (None, None) => "Message too large.".to_string(),
+    }
+}
+
+/// Build the error for a 413 response. The broker's text usually reads
+/// "The received message (delivery-id:0, size:300047 bytes) exceeds the limit
+/// (262144 bytes) …"; the number after "limit" is taken as the maximum, and
+/// the broker's own size is used only if `sent_bytes` is unknown.
+pub fn message_too_large(sent_bytes: Option<usize>, body: &str) -> ServiceBusError {
+    let detail = summarize_body(body);
+    let lower = detail.to_ascii_lowercase();
+    let byte_counts: Vec<(usize, usize)> = lower
+        .match_indices("bytes")
+        .filter_map(|(pos, _)| {
+            let before = lower[..pos].trim_end();
+            let digits = before.trim_end_matches(|c: char| c.is_ascii_digit());
+            before[digits.len()..].parse().ok().map(|n| (pos, n))
+        })
+        .collect();
+    let limit_pos = lower.find("limit").or_else(|| lower.find("max"));
+    let max_bytes = match limit_pos {
+        Some(limit) => byte_counts.iter().find(|(pos, _)| *pos > limit),
+        None if byte_counts.len() > 1 => byte_counts.last(),
+        None => None,
+    }
+    .map(|(_, n)| *n);
+    let reported = byte_counts
+        .iter()
+        .map(|(_, n)| *n)
+        .find(|n| Some(*n) != max_bytes);
+
+    ServiceBusError::MessageTooLarge {
+        size_bytes: sent_bytes.or(reported),
+        max_bytes,
+    }
+}
+
+/// One-line summary of an error response body: the `<Detail>` element of
+/// Service Bus XML errors, the `message` of ARM JSON errors, or the first
+/// non-empty line otherwise.
+pub fn summarize_body(body: &str) -> String {
+    if let Some(start) = body.find("<Detail>") {
+        let rest = &body[start + "<Detail>".len()..];
+        if let Some(end) = rest.find("</Detail>") {
+            return rest[..end].trim().to_string();
+        }
+    }
+
+    if let Ok(json) = serde_json::from_str::<serde_json::Value>(body) {
+        let message = json
+            .pointer("/error/message")
+            .or_else(|| json.get("message"))
+            .and_then(|m| m.as_str());
+        if let Some(message) = message {
+            return message.trim().to_string();
+        }
+    }
+
+    body.lines()
+        .map(str::trim)
+        .find(|l| !l.is_empty())
+        .unwrap_or("")
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summarizes_service_bus_xml_error() {
+        let body = "<Error>\n  <Code>409</Code>\n  <Detail>Entity already exists. TrackingId:abc</Detail>\n</Error>";
+        assert_eq!(
+            summarize_body(body),
+            "Entity already exists. TrackingId:abc"
+        );
+    }
+
+    #[test]
+    fn expired_sas_tokens_read_as_clock_skew() {
+        let body = "<Error><Code>401</Code><Detail>ExpiredToken: The token is expired. Expiration time: '2024-05-06 12:00:00Z'</Detail></Error>";
+        assert!(is_token_expiry(body));
+        assert!(!is_token_expiry(
+            "<Error><Code>401</Code><Detail>InvalidSignature: The token has an invalid signature.</Detail></Error>"
+        ));
+
+        let err = ServiceBusError::ClockSkew {
+            skew_secs: 5400,
+            body: body.to_string(),
+        };
+        assert_eq!(err.status(), Some(401));
+        assert!(err.to_string().contains("clock is 1h30m behind"));
+        let ahead = clock_skew_message(-600);
+        assert!(ahead.contains("10 min ahead of"));
+    }
+
+    #[test]
+    fn summarizes_arm_json_error() {
+        let body = r#"{"error":{"code":"AuthorizationFailed","message":"No access."}}"#;
+        assert_eq!(summarize_body(body), "No access.");
+    }
+
+    #[test]
+    fn api_error_display_stays_on_one_line() {
+        let err = ServiceBusError::Api {
+            status: 400,
+            body: "\n\n  Bad request\n  more detail".to_string(),
+        };
+        assert_eq!(err.to_string(), "Service Bus returned 400: Bad request");
+        assert_eq!(err.status(), Some(400));
+        assert!(err.body().unwrap().contains("more detail"));
+    }
+
+    #[test]
+    fn message_too_large_parses_broker_limit() {
+        let body = "<Error><Code>413</Code><Detail>The received message (delivery-id:0, size:300047 bytes) exceeds the limit (262144 bytes) currently allowed on the link. TrackingId:abc</Detail></Error>";
+        let err = message_too_large(Some(300000), body);
+        assert_eq!(
+            err.to_string(),
+            "Message too large (300000 bytes). Max is 262144 bytes."
+        );
+        assert_eq!(err.status(), Some(413));
+
+        match message_too_large(None, body) {
+            ServiceBusError::MessageTooLarge {
+                size_bytes,
+                max_bytes,
+            } => {
+                assert_eq!(size_bytes, Some(300047));
+                assert_eq!(max_bytes, Some(262144));
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn message_too_large_handles_non_ascii_before_numbers() {
+        // A multi-byte character right before the digits must not split the slice
+        let body = "Nachricht (Größe≈300047 bytes) überschreitet das Limit (≤262144 bytes)";
+        match message_too_large(None, body) {
+            ServiceBusError::MessageTooLarge {
+                size_bytes,
+                max_bytes,
+            } => {
+                assert_eq!(size_bytes, Some(300047));
+                assert_eq!(max_bytes, Some(262144));
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parses_lock_lost_error() {
+        let err = ServiceBusError::Api {
+            status: 410,
+            body: "<Error><Code>410</Code><Detail>The lock supplied is invalid. Either the lock expired, or the message has already been removed from the queue. TrackingId:2b5a1c1e-7f7e-4c3c-9b0c-5d2f1e0e9a11_G3, SystemTracker:orders-ns:Queue:orders, Timestamp:2024-05-06T12:00:00</Detail></Error>".to_string(),
+        };
+        assert_eq!(err.kind(), ErrorKind::MessageLockLost);
+        let detail = err.api_detail().unwrap();
+        assert_eq!(detail.code.as_deref(), Some("410"));
+        assert_eq!(
+            detail.tracking_id.as_deref(),
+            Some("2b5a1c1e-7f7e-4c3c-9b0c-5d2f1e0e9a11_G3")
+        );
+        assert!(detail.detail.ends_with("removed from the queue."));
+    }
+
+    #[test]
+    fn classifies_captured_error_bodies() {
+        let kind = |status, body: &str| {
+            ServiceBusError::Api {
+                status,
+                body: body.to_string(),
+            }
+            .kind()
+        };
+        assert_eq!(
+            kind(404, "<Error><Code>404</Code><Detail>The messaging entity 'sb://ns.servicebus.windows.net/missing' could not be found. TrackingId:abc, SystemTracker:ns:Queue:missing</Detail></Error>"),
+            ErrorKind::NotFound
+        );
+        assert_eq!(
+            kind(409, "<Error><Code>409</Code><Detail>SubCode=40900. Conflict. You're requesting an operation that isn't allowed in the resource's current state. TrackingId:abc</Detail></Error>"),
+            ErrorKind::Conflict
+        );
+        assert_eq!(
+            kind(503, "<Error><Code>50004</Code><Detail>The server is busy. Please retry the operation. TrackingId:abc</Detail></Error>"),
+            ErrorKind::Throttled
+        );
+        assert_eq!(
+            kind(
+                429,
+                r#"{"error":{"code":"TooManyRequests","message":"Request rate is large."}}"#
+            ),
+            ErrorKind::Throttled
+        );
+        assert_eq!(
+            kind(400, "<Error><Code>400</Code><Detail>Messaging entity 'ns:Queue:orders' is currently disabled. TrackingId:abc</Detail></Error>"),
+            ErrorKind::EntityDisabled
+        );
+        assert_eq!(
+            kind(403, "<Error><Code>40300</Code><Detail>The maximum number of subscriptions (2000) for topic 'events' has been reached. TrackingId:abc</Detail></Error>"),
+            ErrorKind::QuotaExceeded
+        );
+        assert_eq!(
+            kind(401, "<Error><Code>401</Code><Detail>ExpiredToken: The token is expired. TrackingId:abc</Detail></Error>"),
+            ErrorKind::Unauthorized
+        );
+        assert_eq!(kind(500, "Internal Server Error"), ErrorKind::Other);
+        // The status decides over a detail that mentions a quota
+        assert_eq!(
+            kind(409, "<Error><Code>409</Code><Detail>Another update to the namespace quota is in progress. TrackingId:abc</Detail></Error>"),
+            ErrorKind::Conflict
+        );
+        assert_eq!(
+            kind(404, "<Error><Code>404</Code><Detail>Quota 'ns:Queue:missing' could not be found. TrackingId:abc</Detail></Error>"),
+            ErrorKind::NotFound
+        );
+        assert_eq!(
+            kind(400, "<Error><Code>400</Code><Detail>SubCode=40000. The size quota of the namespace has been exceeded. TrackingId:abc</Detail></Error>"),
+            ErrorKind::QuotaExceeded
+        );
+
+        let arm =
+            parse_error_body(r#"{"error":{"code":"AuthorizationFailed","message":"No access."}}"#);
+        assert_eq!(arm.code.as_deref(), Some("AuthorizationFailed"));
+        assert_eq!(arm.detail, "No access.");
+        assert_eq!(arm.tracking_id, None);
+    }
+
+    #[test]
+    fn unauthorized_responses_get_their_own_variant() {
+        let body = "<Error><Code>401</Code><Detail>InvalidSignature: The token has an invalid signature. TrackingId:abc</Detail></Error>";
+        let err = api_error(401, body.to_string());
+        assert!(matches!(err, ServiceBusError::Unauthorized { .. }));
+        assert_eq!(err.status(), Some(401));
+        assert_eq!(err.kind(), ErrorKind::Unauthorized);
+        assert_eq!(
+            err.to_string(),
+            "Unauthorized: InvalidSignature: The token has an invalid signature. TrackingId:abc"
+        );
+        assert!(matches!(
+            api_error(403, String::new()),
+            ServiceBusError::Api { status: 403, .. }
+        ));
+    }
+
+    #[test]
+    fn message_too_large_without_hints() {
+        let err = message_too_large(Some(10), "Request Entity Too Large");
+        assert_eq!(err.to_string(), "Message too large (10 bytes).");
+    }
+}
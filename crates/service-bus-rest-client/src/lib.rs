@@ -0,0 +1,64 @@
+//! Azure Service Bus REST client.
+//!
+//! Two clients share one [`ConnectionConfig`] and, to pool connections, one
+//! `reqwest::Client`:
+//!
+//! - [`ManagementClient`] — the ATOM management API: list, create, update
+//!   and delete queues, topics, subscriptions and rules, and read runtime
+//!   counts.
+//! - [`DataPlaneClient`] — the data-plane API: send, peek, receive, complete,
+//!   abandon and bulk purge/resend. The REST API has no dead-letter
+//!   operation.
+//!
+//! ```no_run
+//! use service_bus_rest_client::{ConnectionConfig, DataPlaneClient, ManagementClient};
+//! use std::sync::Arc;
+//!
+//! # async fn run() -> service_bus_rest_client::Result<()> {
+//! let config = ConnectionConfig::from_connection_string(
+//!     "Endpoint=sb://myns.servicebus.windows.net/;SharedAccessKeyName=RootManageSharedAccessKey;SharedAccessKey=...",
+//! )?;
+//! let http = Arc::new(reqwest::Client::new());
+//! let mgmt = ManagementClient::new(config.clone(), http.clone());
+//! for (queue, active, dead_letters) in mgmt.list_queues_with_counts().await? {
+//!     println!("{}: {} active, {} dead-lettered", queue.name, active, dead_letters);
+//! }
+//! let data = DataPlaneClient::new(config, http);
+//! for message in data.peek_messages("orders", 10).await? {
+//!     println!("{}", message.body);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! # Features
+//!
+//! - `azure-ad` — Azure AD (Microsoft Entra ID) authentication through any
+//!   `azure_core` `TokenCredential`
+//!   ([`ConnectionConfig::from_azure_ad`]), plus ARM namespace discovery and
+//!   metrics (`resource_manager`). Off by default, so SAS-only consumers
+//!   don't pull in the Azure SDK.
+//! - `demo` — an in-memory namespace with seed data and simulated latency
+//!   (`demo::DemoNamespace`, `ConnectionConfig::demo`) to run the clients
+//!   against without Azure. Off by default.
+
+#![warn(missing_docs)]
+
+pub mod auth;
+pub mod data_plane;
+#[cfg(feature = "demo")]
+pub mod demo;
+pub mod entity_path;
+pub mod error;
+pub mod management;
+pub mod models;
+#[cfg(feature = "azure-ad")]
+pub mod resource_manager;
+pub mod stats;
+pub mod transport;
+
+pub use auth::ConnectionConfig;
+pub use data_plane::{DataPlaneClient, LockTimeouts};
+pub use error::{ErrorKind, Result, ServiceBusError};
+pub use management::ManagementClient;
+pub use transport::{HttpTransport, Transport};
@@ -0,0 +1,1928 @@
+//! The ATOM management API: entities, rules, runtime counts and namespace
+//! properties.
+
+use quick_xml::escape::{escape, unescape};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use reqwest::Client;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
+
+use super::auth::ConnectionConfig;
+use super::error::{ErrorKind, Result, ServiceBusError};
+use super::models::*;
+use super::stats::Api;
+use super::transport::{HttpTransport, Transport};
+
+/// Retries of a throttled read before the error is returned.
+const THROTTLE_RETRIES: u32 = 3;
+
+/// Subscriptions whose rules are fetched at once for the routing diagram.
+const ROUTE_CONCURRENCY: usize = 8;
+
+/// 1s, 2s, 4s between throttled attempts.
+fn throttle_backoff(attempt: u32) -> Duration {
+    Duration::from_secs(1 << attempt.min(5))
+}
+
+/// Client for Azure Service Bus management-plane operations (ATOM XML feeds).
+#[derive(Clone)]
+pub struct ManagementClient {
+    config: ConnectionConfig,
+    transport: Arc<dyn Transport>,
+}
+
+// ──────────────────────────── ATOM XML building ────────────────────────────
+
+fn wrap_atom_entry(inner_xml: &str) -> String {
+    format!(
+        r#"<entry xmlns="http://www.w3.org/2005/Atom">
+  <content type="application/xml">
+    {}
+  </content>
+</entry>"#,
+        inner_xml
+    )
+}
+
+// The service validates element order against its data contract and answers
+// out-of-order elements with a bare 400, so each builder below emits fields in
+// the contract's order — not the struct's.
+
+pub(crate) fn queue_description_xml(desc: &QueueDescription) -> String {
+    let mut xml = String::from(
+        r#"<QueueDescription xmlns="http://schemas.microsoft.com/netservices/2010/10/servicebus/connect" xmlns:i="http://www.w3.org/2001/XMLSchema-instance">"#,
+    );
+    if let Some(ref v) = desc.lock_duration {
+        xml.push_str(&format!("<LockDuration>{}</LockDuration>", v));
+    }
+    if let Some(v) = desc.max_size_in_megabytes {
+        xml.push_str(&format!("<MaxSizeInMegabytes>{}</MaxSizeInMegabytes>", v));
+    }
+    if let Some(v) = desc.requires_duplicate_detection {
+        xml.push_str(&format!(
+            "<RequiresDuplicateDetection>{}</RequiresDuplicateDetection>",
+            v
+        ));
+    }
+    if let Some(v) = desc.requires_session {
+        xml.push_str(&format!("<RequiresSession>{}</RequiresSession>", v));
+    }
+    if let Some(ref v) = desc.default_message_time_to_live {
+        xml.push_str(&format!(
+            "<DefaultMessageTimeToLive>{}</DefaultMessageTimeToLive>",
+            v
+        ));
+    }
+    if let Some(v) = desc.dead_lettering_on_message_expiration {
+        xml.push_str(&format!(
+            "<DeadLetteringOnMessageExpiration>{}</DeadLetteringOnMessageExpiration>",
+            v
+        ));
+    }
+    if let Some(ref v) = desc.duplicate_detection_history_time_window {
+        xml.push_str(&format!(
+            "<DuplicateDetectionHistoryTimeWindow>{}</DuplicateDetectionHistoryTimeWindow>",
+            v
+        ));
+    }
+    if let Some(v) = desc.max_delivery_count {
+        xml.push_str(&format!("<MaxDeliveryCount>{}</MaxDeliveryCount>", v));
+    }
+    if let Some(v) = desc.enable_batched_operations {
+        xml.push_str(&format!(
+            "<EnableBatchedOperations>{}</EnableBatchedOperations>",
+            v
+        ));
+    }
+    if let Some(ref v) = desc.status {
+        xml.push_str(&format!("<Status>{}</Status>", v));
+    }
+    if let Some(ref v) = desc.forward_to {
+        xml.push_str(&format!("<ForwardTo>{}</ForwardTo>", escape(v)));
+    }
+    if let Some(ref v) = desc.user_metadata {
+        xml.push_str(&format!("<UserMetadata>{}</UserMetadata>", escape(v)));
+    }
+    if let Some(ref v) = desc.auto_delete_on_idle {
+        xml.push_str(&format!("<AutoDeleteOnIdle>{}</AutoDeleteOnIdle>", v));
+    }
+    if let Some(v) = desc.enable_partitioning {
+        xml.push_str(&format!("<EnablePartitioning>{}</EnablePartitioning>", v));
+    }
+    if let Some(ref v) = desc.forward_dead_lettered_messages_to {
+        xml.push_str(&format!(
+            "<ForwardDeadLetteredMessagesTo>{}</ForwardDeadLetteredMessagesTo>",
+            escape(v)
+        ));
+    }
+    xml.push_str("</QueueDescription>");
+    xml
+}
+
+pub(crate) fn topic_description_xml(desc: &TopicDescription) -> String {
+    let mut xml = String::from(
+        r#"<TopicDescription xmlns="http://schemas.microsoft.com/netservices/2010/10/servicebus/connect" xmlns:i="http://www.w3.org/2001/XMLSchema-instance">"#,
+    );
+    if let Some(ref v) = desc.default_message_time_to_live {
+        xml.push_str(&format!(
+            "<DefaultMessageTimeToLive>{}</DefaultMessageTimeToLive>",
+            v
+        ));
+    }
+    if let Some(v) = desc.max_size_in_megabytes {
+        xml.push_str(&format!("<MaxSizeInMegabytes>{}</MaxSizeInMegabytes>", v));
+    }
+    if let Some(v) = desc.requires_duplicate_detection {
+        xml.push_str(&format!(
+            "<RequiresDuplicateDetection>{}</RequiresDuplicateDetection>",
+            v
+        ));
+    }
+    if let Some(ref v) = desc.duplicate_detection_history_time_window {
+        xml.push_str(&format!(
+            "<DuplicateDetectionHistoryTimeWindow>{}</DuplicateDetectionHistoryTimeWindow>",
+            v
+        ));
+    }
+    if let Some(v) = desc.enable_batched_operations {
+        xml.push_str(&format!(
+            "<EnableBatchedOperations>{}</EnableBatchedOperations>",
+            v
+        ));
+    }
+    if let Some(ref v) = desc.status {
+        xml.push_str(&format!("<Status>{}</Status>", v));
+    }
+    if let Some(v) = desc.support_ordering {
+        xml.push_str(&format!("<SupportOrdering>{}</SupportOrdering>", v));
+    }
+    if let Some(ref v) = desc.auto_delete_on_idle {
+        xml.push_str(&format!("<AutoDeleteOnIdle>{}</AutoDeleteOnIdle>", v));
+    }
+    if let Some(v) = desc.enable_partitioning {
+        xml.push_str(&format!("<EnablePartitioning>{}</EnablePartitioning>", v));
+    }
+    // Unlike queues and subscriptions, a topic's metadata comes last
+    if let Some(ref v) = desc.user_metadata {
+        xml.push_str(&format!("<UserMetadata>{}</UserMetadata>", escape(v)));
+    }
+    xml.push_str("</TopicDescription>");
+    xml
+}
+
+pub(crate) fn subscription_description_xml(desc: &SubscriptionDescription) -> String {
+    let mut xml = String::from(
+        r#"<SubscriptionDescription xmlns="http://schemas.microsoft.com/netservices/2010/10/servicebus/connect" xmlns:i="http://www.w3.org/2001/XMLSchema-instance">"#,
+    );
+    if let Some(ref v) = desc.lock_duration {
+        xml.push_str(&format!("<LockDuration>{}</LockDuration>", v));
+    }
+    if let Some(v) = desc.requires_session {
+        xml.push_str(&format!("<RequiresSession>{}</RequiresSession>", v));
+    }
+    if let Some(ref v) = desc.default_message_time_to_live {
+        xml.push_str(&format!(
+            "<DefaultMessageTimeToLive>{}</DefaultMessageTimeToLive>",
+            v
+        ));
+    }
+    if let Some(v) = desc.dead_lettering_on_message_expiration {
+        xml.push_str(&format!(
+            "<DeadLetteringOnMessageExpiration>{}</DeadLetteringOnMessageExpiration>",
+            v
+        ));
+    }
+    if let Some(v) = desc.max_delivery_count {
+        xml.push_str(&format!("<MaxDeliveryCount>{}</MaxDeliveryCount>", v));
+    }
+    if let Some(v) = desc.enable_batched_operations {
+        xml.push_str(&format!(
+            "<EnableBatchedOperations>{}</EnableBatchedOperations>",
+            v
+        ));
+    }
+    if let Some(ref v) = desc.status {
+        xml.push_str(&format!("<Status>{}</Status>", v));
+    }
+    if let Some(ref v) = desc.forward_to {
+        xml.push_str(&format!("<ForwardTo>{}</ForwardTo>", escape(v)));
+    }
+    if let Some(ref v) = desc.user_metadata {
+        xml.push_str(&format!("<UserMetadata>{}</UserMetadata>", escape(v)));
+    }
+    if let Some(ref v) = desc.forward_dead_lettered_messages_to {
+        xml.push_str(&format!(
+            "<ForwardDeadLetteredMessagesTo>{}</ForwardDeadLetteredMessagesTo>",
+            escape(v)
+        ));
+    }
+    if let Some(ref v) = desc.auto_delete_on_idle {
+        xml.push_str(&format!("<AutoDeleteOnIdle>{}</AutoDeleteOnIdle>", v));
+    }
+    xml.push_str("</SubscriptionDescription>");
+    xml
+}
+
+fn to_cdata_safe(value: &str) -> String {
+    value.replace("]]>", "]]]]><![CDATA[>")
+}
+
+fn subscription_rule_sql_xml(sql_expression: &str) -> String {
+    let expr = to_cdata_safe(sql_expression);
+    format!(
+        r#"<RuleDescription xmlns="http://schemas.microsoft.com/netservices/2010/10/servicebus/connect" xmlns:i="http://www.w3.org/2001/XMLSchema-instance"><Filter i:type="SqlFilter"><SqlExpression><![CDATA[{}]]></SqlExpression></Filter><Action i:nil="true" /></RuleDescription>"#,
+        expr
+    )
+}
+
+fn rule_description_xml(desc: &RuleDescription) -> String {
+    let filter = match desc.filter {
+        RuleFilter::Sql { ref expression } => format!(
+            r#"<Filter i:type="SqlFilter"><SqlExpression><![CDATA[{}]]></SqlExpression></Filter>"#,
+            to_cdata_safe(expression)
+        ),
+        RuleFilter::Correlation(ref f) => {
+            let mut xml = String::from(r#"<Filter i:type="CorrelationFilter">"#);
+            let fields = [
+                ("CorrelationId", &f.correlation_id),
+                ("MessageId", &f.message_id),
+                ("To", &f.to),
+                ("ReplyTo", &f.reply_to),
+                ("Label", &f.label),
+                ("SessionId", &f.session_id),
+                ("ReplyToSessionId", &f.reply_to_session_id),
+                ("ContentType", &f.content_type),
+            ];
+            for (tag, value) in fields {
+                if let Some(v) = value {
+                    xml.push_str(&format!("<{}>{}</{}>", tag, escape(v), tag));
+                }
+            }
+            if !f.properties.is_empty() {
+                xml.push_str("<Properties>");
+                for (key, value) in &f.properties {
+                    xml.push_str(&format!(
+                        r#"<KeyValueOfstringanyType><Key>{}</Key><Value i:type="d6p1:string" xmlns:d6p1="http://www.w3.org/2001/XMLSchema">{}</Value></KeyValueOfstringanyType>"#,
+                        escape(key),
+                        escape(value)
+                    ));
+                }
+                xml.push_str("</Properties>");
+            }
+            xml.push_str("</Filter>");
+            xml
+        }
+    };
+    format!(
+        r#"<RuleDescription xmlns="http://schemas.microsoft.com/netservices/2010/10/servicebus/connect" xmlns:i="http://www.w3.org/2001/XMLSchema-instance">{}<Action i:nil="true" /><Name>{}</Name></RuleDescription>"#,
+        filter,
+        escape(&desc.name)
+    )
+}
+
+// ──────────────────────────── Partial updates ────────────────────────────
+
+// Settable elements in contract order, as emitted by the builders above. Used
+// to place an element a patch adds that the fetched description lacks.
+
+const QUEUE_ELEMENTS: &[&str] = &[
+    "LockDuration",
+    "MaxSizeInMegabytes",
+    "RequiresDuplicateDetection",
+    "RequiresSession",
+    "DefaultMessageTimeToLive",
+    "DeadLetteringOnMessageExpiration",
+    "DuplicateDetectionHistoryTimeWindow",
+    "MaxDeliveryCount",
+    "EnableBatchedOperations",
+    "Status",
+    "ForwardTo",
+    "UserMetadata",
+    "AutoDeleteOnIdle",
+    "EnablePartitioning",
+    "ForwardDeadLetteredMessagesTo",
+];
+
+const TOPIC_ELEMENTS: &[&str] = &[
+    "DefaultMessageTimeToLive",
+    "MaxSizeInMegabytes",
+    "RequiresDuplicateDetection",
+    "DuplicateDetectionHistoryTimeWindow",
+    "EnableBatchedOperations",
+    "Status",
+    "SupportOrdering",
+    "AutoDeleteOnIdle",
+    "EnablePartitioning",
+    "EnableSubscriptionPartitioning",
+    "EnableExpress",
+    "UserMetadata",
+];
+
+const SUBSCRIPTION_ELEMENTS: &[&str] = &[
+    "LockDuration",
+    "RequiresSession",
+    "DefaultMessageTimeToLive",
+    "DeadLetteringOnMessageExpiration",
+    "MaxDeliveryCount",
+    "EnableBatchedOperations",
+    "Status",
+    "ForwardTo",
+    "UserMetadata",
+    "ForwardDeadLetteredMessagesTo",
+    "AutoDeleteOnIdle",
+];
+
+/// Byte range of the `<tag>…</tag>` (or `<tag/>`) element in `xml`.
+fn element_span(xml: &str, tag: &str) -> Option<std::ops::Range<usize>> {
+    let open = format!("<{}", tag);
+    let mut from = 0;
+    while let Some(found) = xml[from..].find(&open) {
+        let start = from + found;
+        let after = start + open.len();
+        from = after;
+        if !matches!(xml[after..].chars().next(), Some('>' | ' ' | '/')) {
+            continue;
+        }
+        let tag_end = after + xml[after..].find('>')? + 1;
+        if xml[..tag_end].ends_with("/>") {
+            return Some(start..tag_end);
+        }
+        let close = format!("</{}>", tag);
+        let end = tag_end + xml[tag_end..].find(&close)? + close.len();
+        return Some(start..end);
+    }
+    None
+}
+
+/// Apply `patches` — element name and new text, where empty text removes the
+/// element — to the `<root>` description in an entry fetched from the service.
+/// Everything else is kept verbatim. An element the description lacks goes
+/// after the nearest one that precedes it in `order`.
+fn patch_description(
+    entry_xml: &str,
+    root: &str,
+    order: &[&str],
+    patches: &[(&str, &str)],
+) -> Result<String> {
+    let close = format!("</{}>", root);
+    let (Some(start), Some(end)) = (
+        entry_xml.find(&format!("<{}", root)),
+        entry_xml.rfind(&close),
+    ) else {
+        return Err(ServiceBusError::Operation(format!(
+            "Response has no {} to update",
+            root
+        )));
+    };
+    let mut xml = entry_xml[start..end + close.len()].to_string();
+
+    for &(tag, value) in patches {
+        let element = if value.is_empty() {
+            String::new()
+        } else {
+            format!("<{0}>{1}</{0}>", tag, escape(value))
+        };
+        if let Some(span) = element_span(&xml, tag) {
+            xml.replace_range(span, &element);
+        } else if !element.is_empty() {
+            let preceding = order.iter().take_while(|t| **t != tag);
+            let at = preceding
+                .filter_map(|t| element_span(&xml, t))
+                .map(|span| span.end)
+                .last()
+                .unwrap_or_else(|| xml.find('>').map_or(0, |i| i + 1));
+            xml.insert_str(at, &element);
+        }
+    }
+    Ok(xml)
+}
+
+/// Direct children of a description built above, as (name, unescaped text).
+fn child_elements(description_xml: &str) -> Vec<(String, String)> {
+    let mut reader = Reader::from_str(description_xml);
+    let mut out: Vec<(String, String)> = Vec::new();
+    let mut depth = 0usize;
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e)) => {
+                depth += 1;
+                if depth == 2 {
+                    let name = String::from_utf8_lossy(e.local_name().as_ref()).into_owned();
+                    out.push((name, String::new()));
+                }
+            }
+            Ok(Event::Text(t)) if depth == 2 => {
+                if let (Some(last), Ok(text)) = (out.last_mut(), t.unescape()) {
+                    last.1.push_str(&text);
+                }
+            }
+            Ok(Event::End(_)) => depth = depth.saturating_sub(1),
+            Ok(Event::Eof) | Err(_) => break,
+            _ => {}
+        }
+    }
+    out
+}
+
+/// Patches turning description `current` into `updated` (both as built above).
+fn description_patches(current: &str, updated: &str) -> Vec<(String, String)> {
+    let before = child_elements(current);
+    let after = child_elements(updated);
+    let mut patches: Vec<(String, String)> = after
+        .iter()
+        .filter(|el| !before.contains(el))
+        .cloned()
+        .collect();
+    patches.extend(
+        before
+            .into_iter()
+            .filter(|(tag, _)| !after.iter().any(|(t, _)| t == tag))
+            .map(|(tag, _)| (tag, String::new())),
+    );
+    patches
+}
+
+/// Elements that differ between two queue descriptions, for `patch_queue`.
+pub fn queue_patches(
+    current: &QueueDescription,
+    updated: &QueueDescription,
+) -> Vec<(String, String)> {
+    description_patches(
+        &queue_description_xml(current),
+        &queue_description_xml(updated),
+    )
+}
+
+/// Elements that differ between two topic descriptions, for `patch_topic`.
+pub fn topic_patches(
+    current: &TopicDescription,
+    updated: &TopicDescription,
+) -> Vec<(String, String)> {
+    description_patches(
+        &topic_description_xml(current),
+        &topic_description_xml(updated),
+    )
+}
+
+/// Elements that differ between two subscription descriptions, for
+/// `patch_subscription`.
+pub fn subscription_patches(
+    current: &SubscriptionDescription,
+    updated: &SubscriptionDescription,
+) -> Vec<(String, String)> {
+    description_patches(
+        &subscription_description_xml(current),
+        &subscription_description_xml(updated),
+    )
+}
+
+// ──────────────────────────── Implementation ────────────────────────────
+
+impl ManagementClient {
+    /// `http` can be shared with a [`DataPlaneClient`](crate::DataPlaneClient)
+    /// for the same namespace, so both use one connection pool.
+    pub fn new(config: ConnectionConfig, http: Arc<Client>) -> Self {
+        let transport = Arc::new(HttpTransport::new(config.clone(), http));
+        Self::with_transport(config, transport)
+    }
+
+    /// A client whose requests go to `transport` instead of over HTTP, e.g.
+    /// the in-memory `DemoNamespace` of the `demo` feature.
+    pub fn with_transport(config: ConnectionConfig, transport: Arc<dyn Transport>) -> Self {
+        Self { config, transport }
+    }
+
+    /// The only queue or topic an entity-scoped key (`EntityPath=`) can reach.
+    pub fn entity_path(&self) -> Option<&str> {
+        self.config.entity_path.as_deref()
+    }
+
+    /// GET with a few retries while the namespace reports it is throttling.
+    async fn get_atom(&self, path: &str) -> Result<String> {
+        let mut attempt = 0;
+        loop {
+            match self.get_atom_once(path).await {
+                Err(e) if e.kind() == ErrorKind::Throttled && attempt < THROTTLE_RETRIES => {
+                    tokio::time::sleep(throttle_backoff(attempt)).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    async fn get_atom_once(&self, path: &str) -> Result<String> {
+        self.transport.get_atom(path).await
+    }
+
+    async fn put_atom(&self, path: &str, body: &str) -> Result<String> {
+        self.send_put(path, body, false).await
+    }
+
+    /// PUT over an existing entity. `If-Match: *` turns the create into an update.
+    async fn update_atom(&self, path: &str, body: &str) -> Result<String> {
+        self.send_put(path, body, true).await
+    }
+
+    async fn send_put(&self, path: &str, body: &str, if_match: bool) -> Result<String> {
+        self.transport.put_atom(path, body, if_match).await
+    }
+
+    /// Update only the given elements of an entity. The ATOM API has no PATCH
+    /// verb, so this fetches the entity's description as the service stores
+    /// it, swaps in the patched elements and PUTs it back with `If-Match: *`.
+    /// Settings this client does not model survive the round trip.
+    async fn patch_entity(
+        &self,
+        path: &str,
+        root: &str,
+        order: &[&str],
+        patches: &[(&str, &str)],
+    ) -> Result<String> {
+        let current = self.get_atom(path).await?;
+        let inner = patch_description(&current, root, order, patches)?;
+        self.update_atom(path, &wrap_atom_entry(&inner)).await
+    }
+
+    async fn delete_entity(&self, path: &str) -> Result<()> {
+        self.transport.delete_entity(path).await
+    }
+
+    // ────────── Queues ──────────
+
+    /// List queues with (active_message_count, dead_letter_message_count) from the same feed.
+    pub async fn list_queues_with_counts(&self) -> Result<Vec<(QueueDescription, i64, i64)>> {
+        let xml = self.get_atom("$Resources/Queues").await?;
+        parse_queue_feed_with_counts(&xml)
+    }
+
+    /// The queue or topic `name`, whichever its entry describes, in one GET.
+    pub async fn get_entity(&self, name: &str) -> Result<EntityDescription> {
+        let xml = self.get_atom(name).await?;
+        parse_entity(name, &xml)
+    }
+
+    /// The settings of queue `name`.
+    pub async fn get_queue(&self, name: &str) -> Result<QueueDescription> {
+        let xml = self.get_atom(name).await?;
+        parse_single_queue(&xml)
+    }
+
+    /// Message counts of queue `name`.
+    pub async fn get_queue_runtime_info(&self, name: &str) -> Result<QueueRuntimeInfo> {
+        let xml = self.get_atom(name).await?;
+        parse_queue_runtime_info(name, &xml)
+    }
+
+    /// Create a queue; unset settings take the service defaults. Returns the
+    /// queue as created.
+    pub async fn create_queue(&self, desc: &QueueDescription) -> Result<QueueDescription> {
+        let inner = queue_description_xml(desc);
+        let body = wrap_atom_entry(&inner);
+        let xml = self.put_atom(&desc.name, &body).await?;
+        parse_single_queue(&xml)
+    }
+
+    /// Change only the named `QueueDescription` elements (see `patch_entity`).
+    pub async fn patch_queue(
+        &self,
+        name: &str,
+        patches: &[(&str, &str)],
+    ) -> Result<QueueDescription> {
+        let xml = self
+            .patch_entity(name, "QueueDescription", QUEUE_ELEMENTS, patches)
+            .await?;
+        parse_single_queue(&xml)
+    }
+
+    /// Delete a queue with all its messages.
+    pub async fn delete_queue(&self, name: &str) -> Result<()> {
+        self.delete_entity(name).await
+    }
+
+    // ────────── Topics ──────────
+
+    /// All topics of the namespace.
+    pub async fn list_topics(&self) -> Result<Vec<TopicDescription>> {
+        let xml = self.get_atom("$Resources/Topics").await?;
+        parse_topic_feed(&xml)
+    }
+
+    /// The settings of topic `name`.
+    pub async fn get_topic(&self, name: &str) -> Result<TopicDescription> {
+        let xml = self.get_atom(name).await?;
+        parse_single_topic(&xml)
+    }
+
+    /// Message counts of topic `name`.
+    pub async fn get_topic_runtime_info(&self, name: &str) -> Result<TopicRuntimeInfo> {
+        let xml = self.get_atom(name).await?;
+        parse_topic_runtime_info(name, &xml)
+    }
+
+    /// Create a topic; unset settings take the service defaults. Returns the
+    /// topic as created.
+    pub async fn create_topic(&self, desc: &TopicDescription) -> Result<TopicDescription> {
+        let inner = topic_description_xml(desc);
+        let body = wrap_atom_entry(&inner);
+        let xml = self.put_atom(&desc.name, &body).await?;
+        parse_single_topic(&xml)
+    }
+
+    /// Change only the named `TopicDescription` elements (see `patch_entity`).
+    pub async fn patch_topic(
+        &self,
+        name: &str,
+        patches: &[(&str, &str)],
+    ) -> Result<TopicDescription> {
+        let xml = self
+            .patch_entity(name, "TopicDescription", TOPIC_ELEMENTS, patches)
+            .await?;
+        parse_single_topic(&xml)
+    }
+
+    /// Delete a topic with its subscriptions and their messages.
+    pub async fn delete_topic(&self, name: &str) -> Result<()> {
+        self.delete_entity(name).await
+    }
+
+    // ────────── Subscriptions ──────────
+
+    /// All subscriptions of `topic_name`.
+    pub async fn list_subscriptions(
+        &self,
+        topic_name: &str,
+    ) -> Result<Vec<SubscriptionDescription>> {
+        let xml = self
+            .get_atom(&format!("{}/Subscriptions", topic_name))
+            .await?;
+        parse_subscription_feed(topic_name, &xml)
+    }
+
+    /// List subscriptions with (active_message_count, dead_letter_message_count) from the same feed.
+    pub async fn list_subscriptions_with_counts(
+        &self,
+        topic_name: &str,
+    ) -> Result<Vec<(SubscriptionDescription, i64, i64)>> {
+        let xml = self
+            .get_atom(&format!("{}/Subscriptions", topic_name))
+            .await?;
+        parse_subscription_feed_with_counts(topic_name, &xml)
+    }
+
+    /// The settings of a subscription.
+    pub async fn get_subscription(
+        &self,
+        topic_name: &str,
+        sub_name: &str,
+    ) -> Result<SubscriptionDescription> {
+        let xml = self
+            .get_atom(&format!("{}/Subscriptions/{}", topic_name, sub_name))
+            .await?;
+        parse_single_subscription(topic_name, sub_name, &xml)
+    }
+
+    /// Message counts of a subscription.
+    pub async fn get_subscription_runtime_info(
+        &self,
+        topic_name: &str,
+        sub_name: &str,
+    ) -> Result<SubscriptionRuntimeInfo> {
+        let xml = self
+            .get_atom(&format!("{}/Subscriptions/{}", topic_name, sub_name))
+            .await?;
+        parse_subscription_runtime_info(topic_name, sub_name, &xml)
+    }
+
+    /// Create a subscription of `desc.topic_name`. It starts with the
+    /// `$Default` rule, which lets every message through.
+    pub async fn create_subscription(
+        &self,
+        desc: &SubscriptionDescription,
+    ) -> Result<SubscriptionDescription> {
+        let inner = subscription_description_xml(desc);
+        let body = wrap_atom_entry(&inner);
+        let path = format!("{}/Subscriptions/{}", desc.topic_name, desc.name);
+        let xml = self.put_atom(&path, &body).await?;
+        parse_single_subscription(&desc.topic_name, &desc.name, &xml)
+    }
+
+    /// Change only the named `SubscriptionDescription` elements (see
+    /// `patch_entity`).
+    pub async fn patch_subscription(
+        &self,
+        topic_name: &str,
+        sub_name: &str,
+        patches: &[(&str, &str)],
+    ) -> Result<SubscriptionDescription> {
+        let path = format!("{}/Subscriptions/{}", topic_name, sub_name);
+        let xml = self
+            .patch_entity(
+                &path,
+                "SubscriptionDescription",
+                SUBSCRIPTION_ELEMENTS,
+                patches,
+            )
+            .await?;
+        parse_single_subscription(topic_name, sub_name, &xml)
+    }
+
+    /// Delete a subscription with all its messages.
+    pub async fn delete_subscription(&self, topic_name: &str, sub_name: &str) -> Result<()> {
+        self.delete_entity(&format!("{}/Subscriptions/{}", topic_name, sub_name))
+            .await
+    }
+
+    /// The rules of a subscription.
+    pub async fn list_subscription_rules(
+        &self,
+        topic_name: &str,
+        sub_name: &str,
+    ) -> Result<Vec<SubscriptionRule>> {
+        let xml = self
+            .get_atom(&format!("{}/Subscriptions/{}/Rules", topic_name, sub_name))
+            .await?;
+        parse_subscription_rule_feed(&xml)
+    }
+
+    /// Rules of each named subscription, for the topic routing diagram. A
+    /// subscription whose rules cannot be read gets `rules: None`.
+    pub async fn list_subscription_routes(
+        &self,
+        topic_name: &str,
+        sub_names: &[String],
+    ) -> Vec<SubscriptionRoute> {
+        // Up to ROUTE_CONCURRENCY requests in flight; results keep the order
+        // of `sub_names`.
+        let permits = Arc::new(Semaphore::new(ROUTE_CONCURRENCY));
+        let handles: Vec<_> = sub_names
+            .iter()
+            .map(|name| {
+                let client = self.clone();
+                let permits = permits.clone();
+                let topic_name = topic_name.to_string();
+                let name = name.clone();
+                tokio::spawn(async move {
+                    let _permit = permits.acquire_owned().await.ok()?;
+                    client
+                        .list_subscription_rules(&topic_name, &name)
+                        .await
+                        .ok()
+                })
+            })
+            .collect();
+
+        let mut routes = Vec::with_capacity(sub_names.len());
+        for (name, handle) in sub_names.iter().zip(handles) {
+            routes.push(SubscriptionRoute {
+                name: name.clone(),
+                rules: handle.await.ok().flatten(),
+            });
+        }
+        routes
+    }
+
+    /// Add a rule to a subscription. Fails with a conflict if it exists.
+    pub async fn create_rule(
+        &self,
+        topic_name: &str,
+        sub_name: &str,
+        desc: &RuleDescription,
+    ) -> Result<RuleDescription> {
+        let body = wrap_atom_entry(&rule_description_xml(desc));
+        let path = format!(
+            "{}/Subscriptions/{}/Rules/{}",
+            topic_name, sub_name, desc.name
+        );
+        let xml = self.put_atom(&path, &body).await?;
+        parse_single_rule(&desc.name, &xml)
+    }
+
+    /// Create or replace the SQL rule `rule_name` of a subscription. An
+    /// existing rule is deleted and recreated.
+    pub async fn upsert_subscription_sql_rule(
+        &self,
+        topic_name: &str,
+        sub_name: &str,
+        rule_name: &str,
+        sql_expression: &str,
+    ) -> Result<()> {
+        let trimmed_rule_name = rule_name.trim();
+        if trimmed_rule_name.is_empty() {
+            return Err(ServiceBusError::Operation(
+                "Rule name cannot be empty".to_string(),
+            ));
+        }
+
+        if sql_expression.trim().is_empty() {
+            return Err(ServiceBusError::Operation(
+                "SQL filter expression cannot be empty".to_string(),
+            ));
+        }
+
+        let body = wrap_atom_entry(&subscription_rule_sql_xml(sql_expression.trim()));
+        let path = format!(
+            "{}/Subscriptions/{}/Rules/{}",
+            topic_name, sub_name, trimmed_rule_name
+        );
+        match self.put_atom(&path, &body).await {
+            Ok(_) => {}
+            Err(e) if e.kind() == ErrorKind::Conflict => {
+                // Rules are create-only in some Service Bus API paths. If the rule
+                // already exists, replace it explicitly.
+                self.delete_entity(&path).await?;
+                self.put_atom(&path, &body).await?;
+            }
+            Err(e) => return Err(e),
+        }
+        Ok(())
+    }
+
+    // ────────── Namespace ──────────
+
+    /// Round-trip time of a minimal management request (an empty queue
+    /// listing, or a GET of the entity for entity-scoped keys). The body is not parsed; only the status is checked. Token
+    /// acquisition is not included in the measurement.
+    pub async fn ping(&self) -> Result<Duration> {
+        self.transport.ping().await
+    }
+
+    /// Tier and other properties of the namespace.
+    pub async fn get_namespace_info(&self) -> Result<NamespaceInfo> {
+        let xml = self.get_atom("$namespaceinfo").await?;
+        Ok(parse_namespace_info(&xml))
+    }
+
+    // ────────── Authorization rules (read-only) ──────────
+
+    /// List namespace-level SAS policies. Keys are never parsed.
+    pub async fn list_namespace_authorization_rules(&self) -> Result<Vec<AuthorizationRule>> {
+        let xml = self.get_atom("$Resources/AuthorizationRules").await?;
+        Ok(parse_authorization_rules(&xml))
+    }
+
+    /// List SAS policies attached to a queue or topic (read from its description).
+    pub async fn list_entity_authorization_rules(
+        &self,
+        entity_path: &str,
+    ) -> Result<Vec<AuthorizationRule>> {
+        let xml = self.get_atom(entity_path).await?;
+        Ok(parse_authorization_rules(&xml))
+    }
+}
+
+// ──────────────────────────── HTTP transport ────────────────────────────
+
+impl HttpTransport {
+    pub(crate) async fn http_get_atom(&self, path: &str) -> Result<String> {
+        let url = format!("{}/{}?api-version=2017-04", self.config.endpoint, path);
+        let token = self.config.namespace_token().await?;
+
+        let req = self
+            .http
+            .get(&url)
+            .header("Authorization", token)
+            .header("Content-Type", "application/atom+xml;charset=utf-8");
+        let resp = self.config.send(Api::Management, req).await?;
+
+        let status = resp.status().as_u16();
+        if status == 404 {
+            return Err(ServiceBusError::NotFound(path.to_string()));
+        }
+        if status >= 400 {
+            return Err(self.config.response_error(resp).await);
+        }
+
+        Ok(resp.text().await?)
+    }
+
+    pub(crate) async fn http_put_atom(
+        &self,
+        path: &str,
+        body: &str,
+        if_match: bool,
+    ) -> Result<String> {
+        let url = format!("{}/{}?api-version=2017-04", self.config.endpoint, path);
+        let token = self.config.namespace_token().await?;
+
+        let mut req = self
+            .http
+            .put(&url)
+            .header("Authorization", token)
+            .header("Content-Type", "application/atom+xml;charset=utf-8");
+        if if_match {
+            req = req.header("If-Match", "*");
+        }
+
+        let resp = self
+            .config
+            .send(Api::Management, req.body(body.to_string()))
+            .await?;
+
+        if resp.status().as_u16() >= 400 {
+            return Err(self.config.response_error(resp).await);
+        }
+        Ok(resp.text().await?)
+    }
+
+    pub(crate) async fn http_delete_entity(&self, path: &str) -> Result<()> {
+        let url = format!("{}/{}?api-version=2017-04", self.config.endpoint, path);
+        let token = self.config.namespace_token().await?;
+
+        let resp = self
+            .config
+            .send(
+                Api::Management,
+                self.http.delete(&url).header("Authorization", token),
+            )
+            .await?;
+
+        let status = resp.status().as_u16();
+        if status == 404 {
+            return Err(ServiceBusError::NotFound(path.to_string()));
+        }
+        if status >= 400 {
+            return Err(self.config.response_error(resp).await);
+        }
+        Ok(())
+    }
+
+    pub(crate) async fn http_ping(&self) -> Result<Duration> {
+        // An entity-scoped key can't list the namespace, only read its entity
+        let url = match self.config.entity_path {
+            Some(ref path) => format!("{}/{}?api-version=2017-04", self.config.endpoint, path),
+            None => format!(
+                "{}/$Resources/Queues?$top=0&api-version=2017-04",
+                self.config.endpoint
+            ),
+        };
+        let token = self.config.namespace_token().await?;
+
+        let started = Instant::now();
+        let resp = self
+            .config
+            .send(
+                Api::Management,
+                self.http.get(&url).header("Authorization", token),
+            )
+            .await?;
+        let rtt = started.elapsed();
+
+        let status = resp.status().as_u16();
+        if status >= 400 {
+            return Err(self.config.response_error(resp).await);
+        }
+        Ok(rtt)
+    }
+}
+
+// ──────────────────────────── XML Parsing helpers ────────────────────────────
+// The ATOM feed XML is complex. We do best-effort extraction using quick-xml.
+// For resilience, we parse the raw XML by searching for specific elements
+// rather than relying on strict schema compliance.
+
+/// Split an ATOM feed into its top-level `<entry>` blocks (raw XML).
+///
+/// Uses an event reader rather than string search so that `</entry>` inside
+/// comments, CDATA or escaped `UserMetadata` can't end an entry early, and
+/// nested elements are matched by depth.
+fn extract_entries(xml: &str) -> Vec<String> {
+    let mut reader = Reader::from_str(xml);
+    let mut entries = Vec::new();
+    let mut entry_start: Option<usize> = None;
+    let mut depth = 0usize;
+
+    loop {
+        let event_start = reader.buffer_position() as usize;
+        match reader.read_event() {
+            Ok(Event::Start(e)) => {
+                if entry_start.is_some() {
+                    depth += 1;
+                } else if e.local_name().as_ref() == b"entry" {
+                    entry_start = Some(event_start);
+                    depth = 1;
+                }
+            }
+            Ok(Event::End(_)) => {
+                if let Some(start) = entry_start {
+                    depth -= 1;
+                    if depth == 0 {
+                        let end = reader.buffer_position() as usize;
+                        entries.push(xml[start..end].to_string());
+                        entry_start = None;
+                    }
+                }
+            }
+            Ok(Event::Empty(e)) if entry_start.is_none() && e.local_name().as_ref() == b"entry" => {
+                let end = reader.buffer_position() as usize;
+                entries.push(xml[event_start..end].to_string());
+            }
+            // Malformed XML: keep whatever complete entries were found
+            Ok(Event::Eof) | Err(_) => break,
+            Ok(_) => {}
+        }
+    }
+    entries
+}
+
+fn extract_element(xml: &str, tag: &str) -> Option<String> {
+    let open = format!("<{}", tag);
+    let close = format!("</{}>", tag);
+    if let Some(start_pos) = xml.find(&open) {
+        // Find the end of the opening tag
+        if let Some(gt_pos) = xml[start_pos..].find('>') {
+            let content_start = start_pos + gt_pos + 1;
+            if let Some(end_pos) = xml[content_start..].find(&close) {
+                return Some(xml[content_start..content_start + end_pos].to_string());
+            }
+        }
+    }
+    None
+}
+
+fn extract_element_value(xml: &str, tag: &str) -> Option<String> {
+    let open = format!("<{}>", tag);
+    let close = format!("</{}>", tag);
+    if let Some(start_pos) = xml.find(&open) {
+        let content_start = start_pos + open.len();
+        if let Some(end_pos) = xml[content_start..].find(&close) {
+            let val = xml[content_start..content_start + end_pos]
+                .trim()
+                .to_string();
+            if val.is_empty() {
+                return None;
+            }
+            return Some(val);
+        }
+    }
+    None
+}
+
+/// Like `extract_element_value`, but decodes free text: CDATA is unwrapped
+/// and entities unescaped, so the value can be written back with `escape`.
+fn extract_text_value(xml: &str, tag: &str) -> Option<String> {
+    let raw = extract_element_value(xml, tag)?;
+    if let Some(inner) = raw
+        .strip_prefix("<![CDATA[")
+        .and_then(|s| s.strip_suffix("]]>"))
+    {
+        return Some(inner.to_string());
+    }
+    Some(unescape(&raw).map(|s| s.into_owned()).unwrap_or(raw))
+}
+
+fn extract_title(entry_xml: &str) -> String {
+    // Azure ATOM feeds use <title type="text">name</title>, so we must use
+    // extract_element (handles attributes) rather than extract_element_value
+    // (which requires an exact <title> open tag with no attributes).
+    extract_element(entry_xml, "title")
+        .map(|s| s.trim().to_string())
+        .unwrap_or_default()
+}
+
+fn parse_optional_i64(xml: &str, tag: &str) -> Option<i64> {
+    extract_element_value(xml, tag).and_then(|v| v.parse().ok())
+}
+
+fn parse_optional_i32(xml: &str, tag: &str) -> Option<i32> {
+    extract_element_value(xml, tag).and_then(|v| v.parse().ok())
+}
+
+fn parse_optional_bool(xml: &str, tag: &str) -> Option<bool> {
+    extract_element_value(xml, tag).and_then(|v| v.parse().ok())
+}
+
+/// Extract an element's text value by local name, ignoring any XML namespace prefix.
+///
+/// Azure's WCF serializer assigns auto-generated namespace prefixes (`d2p1:`, `d3p1:`, etc.)
+/// that vary depending on element nesting depth. This function finds the element regardless
+/// of which prefix is used, falling back to an unprefixed match.
+fn extract_value_any_ns(xml: &str, local_name: &str) -> Option<String> {
+    // Try unprefixed first (cheapest check)
+    if let Some(v) = extract_element_value(xml, local_name) {
+        return Some(v);
+    }
+    // Search for ":LocalName>" — any namespace prefix
+    let suffix = format!(":{}>", local_name);
+    if let Some(suffix_pos) = xml.find(&suffix) {
+        // Walk backward to find the '<' that opens this tag
+        let before = &xml[..suffix_pos];
+        if let Some(lt_pos) = before.rfind('<') {
+            let full_tag = &xml[lt_pos + 1..suffix_pos + suffix.len() - 1];
+            return extract_element_value(xml, full_tag);
+        }
+    }
+    None
+}
+
+fn parse_count_details(xml: &str) -> (i64, i64, i64, i64, i64) {
+    let cd = extract_element(xml, "CountDetails").unwrap_or_default();
+    let active = extract_value_any_ns(&cd, "ActiveMessageCount")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0);
+    let dlq = extract_value_any_ns(&cd, "DeadLetterMessageCount")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0);
+    let scheduled = extract_value_any_ns(&cd, "ScheduledMessageCount")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0);
+    let transfer = extract_value_any_ns(&cd, "TransferMessageCount")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0);
+    let transfer_dlq = extract_value_any_ns(&cd, "TransferDeadLetterMessageCount")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0);
+    (active, dlq, scheduled, transfer, transfer_dlq)
+}
+
+/// `parse_count_details`, unless CountDetails is missing or all zeros while a
+/// `MessageCount` is reported (seen on Basic tier); that total is then taken
+/// as the active count.
+fn parse_counts(xml: &str) -> (i64, i64, i64, i64, i64) {
+    let counts = parse_count_details(xml);
+    if counts == (0, 0, 0, 0, 0) {
+        if let Some(total) = parse_optional_i64(xml, "MessageCount") {
+            return (total, 0, 0, 0, 0);
+        }
+    }
+    counts
+}
+
+/// `MessageCount`, or the sum of the CountDetails when it is not reported.
+fn parse_message_count(xml: &str, counts: (i64, i64, i64, i64, i64)) -> i64 {
+    parse_optional_i64(xml, "MessageCount").unwrap_or({
+        let (active, dlq, scheduled, transfer, transfer_dlq) = counts;
+        active + dlq + scheduled + transfer + transfer_dlq
+    })
+}
+
+pub(crate) fn parse_queue_from_entry(entry_xml: &str) -> QueueDescription {
+    let name = extract_title(entry_xml);
+    QueueDescription {
+        name,
+        lock_duration: extract_element_value(entry_xml, "LockDuration"),
+        max_size_in_megabytes: parse_optional_i64(entry_xml, "MaxSizeInMegabytes"),
+        requires_duplicate_detection: parse_optional_bool(entry_xml, "RequiresDuplicateDetection"),
+        requires_session: parse_optional_bool(entry_xml, "RequiresSession"),
+        default_message_time_to_live: extract_element_value(entry_xml, "DefaultMessageTimeToLive"),
+        dead_lettering_on_message_expiration: parse_optional_bool(
+            entry_xml,
+            "DeadLetteringOnMessageExpiration",
+        ),
+        duplicate_detection_history_time_window: extract_element_value(
+            entry_xml,
+            "DuplicateDetectionHistoryTimeWindow",
+        ),
+        max_delivery_count: parse_optional_i32(entry_xml, "MaxDeliveryCount"),
+        enable_batched_operations: parse_optional_bool(entry_xml, "EnableBatchedOperations"),
+        status: extract_element_value(entry_xml, "Status"),
+        forward_to: extract_element_value(entry_xml, "ForwardTo"),
+        forward_dead_lettered_messages_to: extract_element_value(
+            entry_xml,
+            "ForwardDeadLetteredMessagesTo",
+        ),
+        auto_delete_on_idle: extract_element_value(entry_xml, "AutoDeleteOnIdle"),
+        enable_partitioning: parse_optional_bool(entry_xml, "EnablePartitioning"),
+        user_metadata: extract_text_value(entry_xml, "UserMetadata"),
+    }
+}
+
+fn parse_queue_feed_with_counts(xml: &str) -> Result<Vec<(QueueDescription, i64, i64)>> {
+    Ok(extract_entries(xml)
+        .into_iter()
+        .map(|e| {
+            let desc = parse_queue_from_entry(&e);
+            let (active, dlq, _, _, _) = parse_counts(&e);
+            (desc, active, dlq)
+        })
+        .collect())
+}
+
+fn parse_single_queue(xml: &str) -> Result<QueueDescription> {
+    Ok(parse_queue_from_entry(xml))
+}
+
+fn parse_entity(name: &str, xml: &str) -> Result<EntityDescription> {
+    if xml.contains("<TopicDescription") {
+        Ok(EntityDescription::Topic(parse_single_topic(xml)?))
+    } else if xml.contains("<QueueDescription") {
+        Ok(EntityDescription::Queue(
+            Box::new(parse_single_queue(xml)?),
+            parse_queue_runtime_info(name, xml)?,
+        ))
+    } else {
+        Err(ServiceBusError::NotFound(name.to_string()))
+    }
+}
+
+fn parse_queue_runtime_info(name: &str, xml: &str) -> Result<QueueRuntimeInfo> {
+    let counts = parse_counts(xml);
+    let (active, dlq, scheduled, transfer, transfer_dlq) = counts;
+    Ok(QueueRuntimeInfo {
+        name: name.to_string(),
+        active_message_count: active,
+        dead_letter_message_count: dlq,
+        scheduled_message_count: scheduled,
+        transfer_message_count: transfer,
+        transfer_dead_letter_message_count: transfer_dlq,
+        size_in_bytes: parse_optional_i64(xml, "SizeInBytes").unwrap_or(0),
+        created_at: extract_element_value(xml, "CreatedAt"),
+        updated_at: extract_element_value(xml, "UpdatedAt"),
+        accessed_at: extract_element_value(xml, "AccessedAt"),
+        message_count: parse_message_count(xml, counts),
+    })
+}
+
+pub(crate) fn parse_topic_from_entry(entry_xml: &str) -> TopicDescription {
+    let name = extract_title(entry_xml);
+    TopicDescription {
+        name,
+        max_size_in_megabytes: parse_optional_i64(entry_xml, "MaxSizeInMegabytes"),
+        default_message_time_to_live: extract_element_value(entry_xml, "DefaultMessageTimeToLive"),
+        requires_duplicate_detection: parse_optional_bool(entry_xml, "RequiresDuplicateDetection"),
+        duplicate_detection_history_time_window: extract_element_value(
+            entry_xml,
+            "DuplicateDetectionHistoryTimeWindow",
+        ),
+        enable_batched_operations: parse_optional_bool(entry_xml, "EnableBatchedOperations"),
+        status: extract_element_value(entry_xml, "Status"),
+        support_ordering: parse_optional_bool(entry_xml, "SupportOrdering"),
+        auto_delete_on_idle: extract_element_value(entry_xml, "AutoDeleteOnIdle"),
+        enable_partitioning: parse_optional_bool(entry_xml, "EnablePartitioning"),
+        user_metadata: extract_text_value(entry_xml, "UserMetadata"),
+    }
+}
+
+fn parse_topic_feed(xml: &str) -> Result<Vec<TopicDescription>> {
+    Ok(extract_entries(xml)
+        .into_iter()
+        .map(|e| parse_topic_from_entry(&e))
+        .collect())
+}
+
+fn parse_single_topic(xml: &str) -> Result<TopicDescription> {
+    Ok(parse_topic_from_entry(xml))
+}
+
+fn parse_topic_runtime_info(name: &str, xml: &str) -> Result<TopicRuntimeInfo> {
+    let (_, _, scheduled, _, _) = parse_count_details(xml);
+    Ok(TopicRuntimeInfo {
+        name: name.to_string(),
+        subscription_count: parse_optional_i64(xml, "SubscriptionCount").unwrap_or(0),
+        size_in_bytes: parse_optional_i64(xml, "SizeInBytes").unwrap_or(0),
+        created_at: extract_element_value(xml, "CreatedAt"),
+        updated_at: extract_element_value(xml, "UpdatedAt"),
+        accessed_at: extract_element_value(xml, "AccessedAt"),
+        scheduled_message_count: scheduled,
+        // Not in the topic entry: summed from its subscriptions by the caller
+        active_message_count: 0,
+        dead_letter_message_count: 0,
+    })
+}
+
+pub(crate) fn parse_subscription_from_entry(
+    topic_name: &str,
+    entry_xml: &str,
+) -> SubscriptionDescription {
+    let name = extract_title(entry_xml);
+    SubscriptionDescription {
+        name,
+        topic_name: topic_name.to_string(),
+        lock_duration: extract_element_value(entry_xml, "LockDuration"),
+        requires_session: parse_optional_bool(entry_xml, "RequiresSession"),
+        default_message_time_to_live: extract_element_value(entry_xml, "DefaultMessageTimeToLive"),
+        dead_lettering_on_message_expiration: parse_optional_bool(
+            entry_xml,
+            "DeadLetteringOnMessageExpiration",
+        ),
+        dead_lettering_on_filter_evaluation_exceptions: parse_optional_bool(
+            entry_xml,
+            "DeadLetteringOnFilterEvaluationExceptions",
+        ),
+        max_delivery_count: parse_optional_i32(entry_xml, "MaxDeliveryCount"),
+        enable_batched_operations: parse_optional_bool(entry_xml, "EnableBatchedOperations"),
+        status: extract_element_value(entry_xml, "Status"),
+        forward_to: extract_element_value(entry_xml, "ForwardTo"),
+        forward_dead_lettered_messages_to: extract_element_value(
+            entry_xml,
+            "ForwardDeadLetteredMessagesTo",
+        ),
+        auto_delete_on_idle: extract_element_value(entry_xml, "AutoDeleteOnIdle"),
+        user_metadata: extract_text_value(entry_xml, "UserMetadata"),
+    }
+}
+
+fn parse_subscription_feed(topic_name: &str, xml: &str) -> Result<Vec<SubscriptionDescription>> {
+    Ok(extract_entries(xml)
+        .into_iter()
+        .map(|e| parse_subscription_from_entry(topic_name, &e))
+        .collect())
+}
+
+fn parse_subscription_feed_with_counts(
+    topic_name: &str,
+    xml: &str,
+) -> Result<Vec<(SubscriptionDescription, i64, i64)>> {
+    Ok(extract_entries(xml)
+        .into_iter()
+        .map(|e| {
+            let desc = parse_subscription_from_entry(topic_name, &e);
+            let (active, dlq, _, _, _) = parse_counts(&e);
+            (desc, active, dlq)
+        })
+        .collect())
+}
+
+fn parse_single_subscription(
+    topic_name: &str,
+    sub_name: &str,
+    xml: &str,
+) -> Result<SubscriptionDescription> {
+    let mut desc = parse_subscription_from_entry(topic_name, xml);
+    if desc.name.is_empty() {
+        desc.name = sub_name.to_string();
+    }
+    Ok(desc)
+}
+
+fn parse_subscription_runtime_info(
+    topic_name: &str,
+    sub_name: &str,
+    xml: &str,
+) -> Result<SubscriptionRuntimeInfo> {
+    let counts = parse_counts(xml);
+    let (active, dlq, _, transfer, transfer_dlq) = counts;
+    Ok(SubscriptionRuntimeInfo {
+        name: sub_name.to_string(),
+        topic_name: topic_name.to_string(),
+        active_message_count: active,
+        dead_letter_message_count: dlq,
+        transfer_message_count: transfer,
+        transfer_dead_letter_message_count: transfer_dlq,
+        message_count: parse_message_count(xml, counts),
+        created_at: extract_element_value(xml, "CreatedAt"),
+        updated_at: extract_element_value(xml, "UpdatedAt"),
+        accessed_at: extract_element_value(xml, "AccessedAt"),
+    })
+}
+
+fn parse_subscription_rule_from_entry(entry_xml: &str) -> SubscriptionRule {
+    let name = extract_title(entry_xml);
+    let sql_expression = extract_value_any_ns(entry_xml, "SqlExpression")
+        .or_else(|| extract_value_any_ns(entry_xml, "Expression"))
+        .unwrap_or_else(|| "1=1".to_string());
+
+    SubscriptionRule {
+        name,
+        sql_expression,
+        filter_type: extract_filter_type(entry_xml).unwrap_or_default(),
+    }
+}
+
+/// Read the `type` attribute (`i:type`, `xsi:type`, ...) of the `<Filter>` element.
+fn extract_filter_type(entry_xml: &str) -> Option<String> {
+    let start = entry_xml.find("<Filter ")?;
+    let tag = &entry_xml[start..start + entry_xml[start..].find('>')?];
+    let attr = tag.find("type=\"")? + "type=\"".len();
+    let value = &tag[attr..attr + tag[attr..].find('"')?];
+    Some(value.to_string())
+}
+
+fn parse_single_rule(rule_name: &str, xml: &str) -> Result<RuleDescription> {
+    let mut name = extract_title(xml);
+    if name.is_empty() {
+        name = rule_name.to_string();
+    }
+    let filter_xml = extract_element(xml, "Filter").unwrap_or_default();
+    let filter = if extract_filter_type(xml).as_deref() == Some("CorrelationFilter") {
+        let field = |tag| extract_text_value(&filter_xml, tag);
+        let properties = extract_all_elements(&filter_xml, "KeyValueOfstringanyType")
+            .iter()
+            .filter_map(|kv| {
+                let key = extract_text_value(kv, "Key")?;
+                let raw = extract_element(kv, "Value").unwrap_or_default();
+                let value = unescape(&raw).map(|v| v.into_owned()).unwrap_or(raw);
+                Some((key, value))
+            })
+            .collect();
+        RuleFilter::Correlation(CorrelationFilter {
+            correlation_id: field("CorrelationId"),
+            message_id: field("MessageId"),
+            to: field("To"),
+            reply_to: field("ReplyTo"),
+            label: field("Label"),
+            session_id: field("SessionId"),
+            reply_to_session_id: field("ReplyToSessionId"),
+            content_type: field("ContentType"),
+            properties,
+        })
+    } else {
+        RuleFilter::Sql {
+            expression: extract_text_value(&filter_xml, "SqlExpression")
+                .unwrap_or_else(|| "1=1".to_string()),
+        }
+    };
+    Ok(RuleDescription { name, filter })
+}
+
+fn parse_subscription_rule_feed(xml: &str) -> Result<Vec<SubscriptionRule>> {
+    Ok(extract_entries(xml)
+        .into_iter()
+        .map(|e| parse_subscription_rule_from_entry(&e))
+        .collect())
+}
+
+/// Extract every `<tag ...>...</tag>` block. Longer tags sharing the prefix
+/// (e.g. `<AuthorizationRules>` when asked for `AuthorizationRule`) are skipped.
+fn extract_all_elements(xml: &str, tag: &str) -> Vec<String> {
+    let open = format!("<{}", tag);
+    let close = format!("</{}>", tag);
+    let mut blocks = Vec::new();
+    let mut remaining = xml;
+    while let Some(start) = remaining.find(&open) {
+        let after = &remaining[start + open.len()..];
+        if !after.starts_with(|c: char| c == '>' || c == '/' || c.is_whitespace()) {
+            remaining = after;
+            continue;
+        }
+        match remaining[start..].find(&close) {
+            Some(end) => {
+                let block_end = start + end + close.len();
+                blocks.push(remaining[start..block_end].to_string());
+                remaining = &remaining[block_end..];
+            }
+            None => break,
+        }
+    }
+    blocks
+}
+
+fn parse_authorization_rule(block: &str) -> AuthorizationRule {
+    let rights_xml = extract_element(block, "Rights").unwrap_or_default();
+    let rights = extract_all_elements(&rights_xml, "AccessRights")
+        .iter()
+        .filter_map(|r| extract_element_value(r, "AccessRights"))
+        .collect();
+
+    // PrimaryKey / SecondaryKey are intentionally ignored.
+    AuthorizationRule {
+        key_name: extract_value_any_ns(block, "KeyName").unwrap_or_default(),
+        claim_type: extract_value_any_ns(block, "ClaimType"),
+        rights,
+        created_time: extract_value_any_ns(block, "CreatedTime"),
+        modified_time: extract_value_any_ns(block, "ModifiedTime"),
+    }
+}
+
+fn parse_namespace_info(xml: &str) -> NamespaceInfo {
+    let info = extract_element(xml, "NamespaceInfo").unwrap_or_default();
+    NamespaceInfo {
+        name: extract_element_value(&info, "Name").unwrap_or_else(|| extract_title(xml)),
+        messaging_sku: extract_element_value(&info, "MessagingSKU"),
+        messaging_units: parse_optional_i64(&info, "MessagingUnits"),
+        created_at: extract_element_value(&info, "CreatedTime"),
+        modified_at: extract_element_value(&info, "ModifiedTime"),
+    }
+}
+
+fn parse_authorization_rules(xml: &str) -> Vec<AuthorizationRule> {
+    let mut blocks = extract_all_elements(xml, "AuthorizationRule");
+    if blocks.is_empty() {
+        blocks = extract_all_elements(xml, "SharedAccessAuthorizationRule");
+    }
+    blocks
+        .iter()
+        .map(|b| parse_authorization_rule(b))
+        .filter(|r| !r.key_name.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const QUEUE_WITH_AUTH_RULES: &str = r#"<entry xmlns="http://www.w3.org/2005/Atom">
+  <title type="text">orders</title>
+  <content type="application/xml">
+    <QueueDescription xmlns="http://schemas.microsoft.com/netservices/2010/10/servicebus/connect" xmlns:i="http://www.w3.org/2001/XMLSchema-instance">
+      <LockDuration>PT30S</LockDuration>
+      <AuthorizationRules>
+        <AuthorizationRule i:type="SharedAccessAuthorizationRule">
+          <ClaimType>SharedAccessKey</ClaimType>
+          <ClaimValue>None</ClaimValue>
+          <Rights>
+            <AccessRights>Listen</AccessRights>
+            <AccessRights>Send</AccessRights>
+          </Rights>
+          <CreatedTime>2024-01-02T03:04:05Z</CreatedTime>
+          <ModifiedTime>2024-02-03T04:05:06Z</ModifiedTime>
+          <KeyName>app-sender</KeyName>
+          <PrimaryKey>c2VjcmV0LXByaW1hcnk=</PrimaryKey>
+          <SecondaryKey>c2VjcmV0LXNlY29uZGFyeQ==</SecondaryKey>
+        </AuthorizationRule>
+        <AuthorizationRule i:type="SharedAccessAuthorizationRule">
+          <ClaimType>SharedAccessKey</ClaimType>
+          <Rights>
+            <AccessRights>Manage</AccessRights>
+            <AccessRights>Listen</AccessRights>
+            <AccessRights>Send</AccessRights>
+          </Rights>
+          <KeyName>ops</KeyName>
+          <PrimaryKey>b3BzLXByaW1hcnk=</PrimaryKey>
+        </AuthorizationRule>
+      </AuthorizationRules>
+      <Status>Active</Status>
+    </QueueDescription>
+  </content>
+</entry>"#;
+
+    #[test]
+    fn basic_tier_counts_come_from_count_details() {
+        // Basic tier: CountDetails with WCF-prefixed elements, no MessageCount
+        let xml = r#"<entry xmlns="http://www.w3.org/2005/Atom">
+  <title type="text">orders</title>
+  <content type="application/xml">
+    <QueueDescription xmlns="http://schemas.microsoft.com/netservices/2010/10/servicebus/connect" xmlns:i="http://www.w3.org/2001/XMLSchema-instance">
+      <LockDuration>PT1M</LockDuration>
+      <SizeInBytes>2048</SizeInBytes>
+      <CountDetails xmlns:d2p1="http://schemas.microsoft.com/netservices/2011/06/servicebus">
+        <d2p1:ActiveMessageCount>12</d2p1:ActiveMessageCount>
+        <d2p1:DeadLetterMessageCount>3</d2p1:DeadLetterMessageCount>
+        <d2p1:ScheduledMessageCount>2</d2p1:ScheduledMessageCount>
+        <d2p1:TransferMessageCount>0</d2p1:TransferMessageCount>
+        <d2p1:TransferDeadLetterMessageCount>0</d2p1:TransferDeadLetterMessageCount>
+      </CountDetails>
+    </QueueDescription>
+  </content>
+</entry>"#;
+        let rt = parse_queue_runtime_info("orders", xml).unwrap();
+        assert_eq!(rt.active_message_count, 12);
+        assert_eq!(rt.dead_letter_message_count, 3);
+        assert_eq!(rt.scheduled_message_count, 2);
+        assert_eq!(rt.message_count, 17);
+
+        // No usable CountDetails: the plain total stands in for the active count
+        let xml = r#"<entry><content><QueueDescription>
+      <MessageCount>7</MessageCount>
+    </QueueDescription></content></entry>"#;
+        let rt = parse_queue_runtime_info("orders", xml).unwrap();
+        assert_eq!(rt.active_message_count, 7);
+        assert_eq!(rt.message_count, 7);
+    }
+
+    #[test]
+    fn entity_lookup_tells_queues_from_topics() {
+        let queue = r#"<entry><title type="text">orders</title><content type="application/xml"><QueueDescription><LockDuration>PT1M</LockDuration><CountDetails xmlns:d2p1="http://schemas.microsoft.com/netservices/2011/06/servicebus"><d2p1:ActiveMessageCount>4</d2p1:ActiveMessageCount><d2p1:DeadLetterMessageCount>1</d2p1:DeadLetterMessageCount></CountDetails></QueueDescription></content></entry>"#;
+        match parse_entity("orders", queue).unwrap() {
+            EntityDescription::Queue(desc, runtime) => {
+                assert_eq!(desc.lock_duration.as_deref(), Some("PT1M"));
+                assert_eq!(runtime.active_message_count, 4);
+                assert_eq!(runtime.dead_letter_message_count, 1);
+            }
+            other => panic!("expected a queue, got {:?}", other),
+        }
+
+        let topic = r#"<entry><title type="text">events</title><content type="application/xml"><TopicDescription><SupportOrdering>true</SupportOrdering></TopicDescription></content></entry>"#;
+        let entity = parse_entity("events", topic).unwrap();
+        assert_eq!(entity.entity_type(), EntityType::Topic);
+
+        assert!(matches!(
+            parse_entity("gone", "<feed></feed>"),
+            Err(ServiceBusError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn parses_namespace_info() {
+        let xml = r#"<entry xmlns="http://www.w3.org/2005/Atom">
+  <title type="text">orders-staging</title>
+  <content type="application/xml">
+    <NamespaceInfo xmlns="http://schemas.microsoft.com/netservices/2010/10/servicebus/connect" xmlns:i="http://www.w3.org/2001/XMLSchema-instance">
+      <CreatedTime>2021-06-01T08:30:00.1Z</CreatedTime>
+      <MessagingSKU>Basic</MessagingSKU>
+      <MessagingUnits>0</MessagingUnits>
+      <ModifiedTime>2024-03-04T05:06:07Z</ModifiedTime>
+      <Name>orders-staging</Name>
+      <NamespaceType>Messaging</NamespaceType>
+    </NamespaceInfo>
+  </content>
+</entry>"#;
+        let info = parse_namespace_info(xml);
+        assert_eq!(info.name, "orders-staging");
+        assert_eq!(info.messaging_sku.as_deref(), Some("Basic"));
+        assert_eq!(info.messaging_units, Some(0));
+        assert_eq!(info.created_at.as_deref(), Some("2021-06-01T08:30:00.1Z"));
+        assert!(info.is_basic());
+    }
+
+    #[test]
+    fn parses_entity_authorization_rules() {
+        let rules = parse_authorization_rules(QUEUE_WITH_AUTH_RULES);
+        assert_eq!(rules.len(), 2);
+
+        assert_eq!(rules[0].key_name, "app-sender");
+        assert_eq!(rules[0].claim_type.as_deref(), Some("SharedAccessKey"));
+        assert_eq!(rules[0].rights, vec!["Listen", "Send"]);
+        assert_eq!(
+            rules[0].modified_time.as_deref(),
+            Some("2024-02-03T04:05:06Z")
+        );
+
+        assert_eq!(rules[1].key_name, "ops");
+        assert_eq!(rules[1].rights, vec!["Manage", "Listen", "Send"]);
+        assert_eq!(rules[1].created_time, None);
+    }
+
+    #[test]
+    fn authorization_rules_never_carry_keys() {
+        let rules = parse_authorization_rules(QUEUE_WITH_AUTH_RULES);
+        let debug = format!("{:?}", rules);
+        assert!(!debug.contains("c2VjcmV0"));
+        assert!(!debug.contains("b3Bz"));
+    }
+
+    #[test]
+    fn parses_namespace_authorization_rule_feed() {
+        let feed = r#"<feed xmlns="http://www.w3.org/2005/Atom">
+  <entry>
+    <title type="text">RootManageSharedAccessKey</title>
+    <content type="application/xml">
+      <SharedAccessAuthorizationRule xmlns="http://schemas.microsoft.com/netservices/2010/10/servicebus/connect">
+        <ClaimType>SharedAccessKey</ClaimType>
+        <Rights><AccessRights>Listen</AccessRights><AccessRights>Manage</AccessRights><AccessRights>Send</AccessRights></Rights>
+        <KeyName>RootManageSharedAccessKey</KeyName>
+        <PrimaryKey>cm9vdA==</PrimaryKey>
+      </SharedAccessAuthorizationRule>
+    </content>
+  </entry>
+</feed>"#;
+        let rules = parse_authorization_rules(feed);
+        assert_eq!(rules.len(), 1);
+        assert_eq!(rules[0].key_name, "RootManageSharedAccessKey");
+        assert_eq!(rules[0].rights, vec!["Listen", "Manage", "Send"]);
+    }
+
+    #[test]
+    fn entity_without_rules_yields_empty_list() {
+        let xml = "<entry><content><QueueDescription><Status>Active</Status></QueueDescription></content></entry>";
+        assert!(parse_authorization_rules(xml).is_empty());
+    }
+
+    #[test]
+    fn extract_entries_ignores_close_tag_in_cdata_and_comments() {
+        let feed = r#"<feed xmlns="http://www.w3.org/2005/Atom">
+  <!-- a stray </entry> in a comment -->
+  <entry>
+    <title type="text">a&amp;b</title>
+    <link rel="self" href="https://ns/a?x=1&amp;y=2"/>
+    <content><QueueDescription><UserMetadata><![CDATA[</entry> & <oops>]]></UserMetadata></QueueDescription></content>
+  </entry>
+  <entry><title type="text">second</title></entry>
+</feed>"#;
+        let entries = extract_entries(feed);
+        assert_eq!(entries.len(), 2);
+        assert!(entries[0].starts_with("<entry>"));
+        assert!(entries[0].ends_with("</entry>"));
+        assert!(entries[0].contains("CDATA[</entry> & <oops>]]"));
+        assert_eq!(
+            extract_element(&entries[1], "title"),
+            Some("second".to_string())
+        );
+    }
+
+    #[test]
+    fn extract_entries_matches_nested_entries_by_depth() {
+        let xml = "<feed><entry><content><entry><x/></entry></content></entry><entry/></feed>";
+        let entries = extract_entries(xml);
+        assert_eq!(
+            entries,
+            vec![
+                "<entry><content><entry><x/></entry></content></entry>".to_string(),
+                "<entry/>".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_rule_filter_types() {
+        let feed = r#"<feed>
+  <entry><title type="text">$Default</title><content><RuleDescription><Filter i:type="TrueFilter"><SqlExpression>1=1</SqlExpression></Filter></RuleDescription></content></entry>
+  <entry><title type="text">payments</title><content><RuleDescription><Filter i:type="SqlFilter"><SqlExpression>type='payment'</SqlExpression></Filter></RuleDescription></content></entry>
+  <entry><title type="text">corr</title><content><RuleDescription><Filter i:type="CorrelationFilter"><CorrelationId>abc</CorrelationId></Filter></RuleDescription></content></entry>
+</feed>"#;
+        let rules = parse_subscription_rule_feed(feed).unwrap();
+        let labels: Vec<String> = rules.iter().map(|r| r.filter_label()).collect();
+        assert_eq!(
+            labels,
+            vec!["TrueFilter", "SQL: type='payment'", "Correlation"]
+        );
+    }
+
+    #[test]
+    fn rule_xml_round_trips_sql_and_correlation_filters() {
+        let sql = RuleDescription {
+            name: "big".into(),
+            filter: RuleFilter::Sql {
+                expression: "amount > 100 AND region = 'eu'".into(),
+            },
+        };
+        let xml = rule_description_xml(&sql);
+        assert!(xml.contains(r#"<Filter i:type="SqlFilter">"#));
+        let entry = format!(
+            r#"<entry><title type="text">big</title><content type="application/xml">{}</content></entry>"#,
+            xml
+        );
+        assert_eq!(parse_single_rule("big", &entry).unwrap(), sql);
+
+        let correlation = RuleDescription {
+            name: "eu-orders".into(),
+            filter: RuleFilter::Correlation(CorrelationFilter {
+                correlation_id: Some("abc".into()),
+                label: Some("order & co".into()),
+                properties: vec![("region".into(), "eu".into())],
+                ..Default::default()
+            }),
+        };
+        let xml = rule_description_xml(&correlation);
+        assert_in_order(&xml, &["CorrelationId", "Label", "Properties", "Name"]);
+        // The create response may omit the title
+        let entry = format!("<entry><content>{}</content></entry>", xml);
+        assert_eq!(parse_single_rule("eu-orders", &entry).unwrap(), correlation);
+    }
+
+    /// Assert that every tag is present in `xml`, in the given order.
+    fn assert_in_order(xml: &str, tags: &[&str]) {
+        let positions: Vec<usize> = tags
+            .iter()
+            .map(|t| {
+                xml.find(&format!("<{}>", t))
+                    .unwrap_or_else(|| panic!("missing <{}> in {}", t, xml))
+            })
+            .collect();
+        assert!(
+            positions.windows(2).all(|w| w[0] < w[1]),
+            "out of order: {}",
+            xml
+        );
+    }
+
+    #[test]
+    fn topic_xml_follows_contract_order() {
+        let desc = TopicDescription {
+            name: "events".into(),
+            max_size_in_megabytes: Some(1024),
+            default_message_time_to_live: Some("P14D".into()),
+            requires_duplicate_detection: Some(true),
+            duplicate_detection_history_time_window: Some("PT10M".into()),
+            support_ordering: Some(true),
+            auto_delete_on_idle: Some("P7D".into()),
+            enable_partitioning: Some(false),
+            user_metadata: Some("owner=payments".into()),
+            ..Default::default()
+        };
+        assert_in_order(
+            &topic_description_xml(&desc),
+            &[
+                "DefaultMessageTimeToLive",
+                "MaxSizeInMegabytes",
+                "RequiresDuplicateDetection",
+                "DuplicateDetectionHistoryTimeWindow",
+                "SupportOrdering",
+                "AutoDeleteOnIdle",
+                "EnablePartitioning",
+                "UserMetadata",
+            ],
+        );
+    }
+
+    #[test]
+    fn queue_xml_follows_contract_order() {
+        let desc = QueueDescription {
+            name: "orders".into(),
+            lock_duration: Some("PT30S".into()),
+            requires_duplicate_detection: Some(true),
+            duplicate_detection_history_time_window: Some("PT10M".into()),
+            max_delivery_count: Some(10),
+            forward_to: Some("archive".into()),
+            user_metadata: Some("owner=payments".into()),
+            auto_delete_on_idle: Some("P7D".into()),
+            enable_partitioning: Some(false),
+            forward_dead_lettered_messages_to: Some("dlq-sink".into()),
+            ..Default::default()
+        };
+        assert_in_order(
+            &queue_description_xml(&desc),
+            &[
+                "LockDuration",
+                "RequiresDuplicateDetection",
+                "DuplicateDetectionHistoryTimeWindow",
+                "MaxDeliveryCount",
+                "ForwardTo",
+                "UserMetadata",
+                "AutoDeleteOnIdle",
+                "EnablePartitioning",
+                "ForwardDeadLetteredMessagesTo",
+            ],
+        );
+    }
+
+    #[test]
+    fn patch_keeps_unmodelled_elements_and_contract_order() {
+        let entry = r#"<entry><title type="text">orders</title><content type="application/xml"><QueueDescription xmlns="http://schemas.microsoft.com/netservices/2010/10/servicebus/connect"><LockDuration>PT30S</LockDuration><MaxDeliveryCount>10</MaxDeliveryCount><Status>Active</Status><CreatedAt>2024-01-01T00:00:00Z</CreatedAt><EnableExpress>true</EnableExpress><UserMetadata>old</UserMetadata></QueueDescription></content></entry>"#;
+        let xml = patch_description(
+            entry,
+            "QueueDescription",
+            QUEUE_ELEMENTS,
+            &[
+                ("MaxDeliveryCount", "5"),
+                ("ForwardTo", "archive & co"),
+                ("UserMetadata", ""),
+            ],
+        )
+        .unwrap();
+        assert!(xml.starts_with("<QueueDescription") && xml.ends_with("</QueueDescription>"));
+        assert!(xml.contains("<MaxDeliveryCount>5</MaxDeliveryCount>"));
+        assert!(xml.contains("<EnableExpress>true</EnableExpress>"));
+        assert!(!xml.contains("UserMetadata"));
+        assert_in_order(
+            &xml,
+            &[
+                "LockDuration",
+                "MaxDeliveryCount",
+                "Status",
+                "ForwardTo",
+                "CreatedAt",
+            ],
+        );
+        assert!(xml.contains("<ForwardTo>archive &amp; co</ForwardTo>"));
+    }
+
+    #[test]
+    fn topic_patch_adds_metadata_after_express() {
+        let entry = r#"<entry><content type="application/xml"><TopicDescription xmlns="http://schemas.microsoft.com/netservices/2010/10/servicebus/connect"><MaxSizeInMegabytes>1024</MaxSizeInMegabytes><Status>Active</Status><SupportOrdering>true</SupportOrdering><AutoDeleteOnIdle>P10675199DT2H48M5.4775807S</AutoDeleteOnIdle><EnablePartitioning>false</EnablePartitioning><EnableSubscriptionPartitioning>false</EnableSubscriptionPartitioning><EnableExpress>false</EnableExpress></TopicDescription></content></entry>"#;
+        let xml = patch_description(
+            entry,
+            "TopicDescription",
+            TOPIC_ELEMENTS,
+            &[("UserMetadata", "owner=payments"), ("Status", "Disabled")],
+        )
+        .unwrap();
+        assert!(xml.contains("<Status>Disabled</Status>"));
+        assert_in_order(
+            &xml,
+            &[
+                "Status",
+                "SupportOrdering",
+                "EnablePartitioning",
+                "EnableExpress",
+                "UserMetadata",
+            ],
+        );
+    }
+
+    #[test]
+    fn patches_cover_only_changed_elements() {
+        let current = SubscriptionDescription {
+            name: "audit".into(),
+            topic_name: "events".into(),
+            max_delivery_count: Some(10),
+            lock_duration: Some("PT30S".into()),
+            forward_to: Some("sink".into()),
+            ..Default::default()
+        };
+        let updated = SubscriptionDescription {
+            max_delivery_count: Some(3),
+            forward_to: None,
+            ..current.clone()
+        };
+        assert_eq!(
+            subscription_patches(&current, &updated),
+            vec![
+                ("MaxDeliveryCount".to_string(), "3".to_string()),
+                ("ForwardTo".to_string(), String::new()),
+            ]
+        );
+        assert!(subscription_patches(&current, &current).is_empty());
+    }
+
+    #[test]
+    fn user_metadata_round_trips_through_escaping() {
+        let desc = QueueDescription {
+            name: "orders".into(),
+            user_metadata: Some("team <a&b>".into()),
+            ..Default::default()
+        };
+        let xml = queue_description_xml(&desc);
+        assert!(xml.contains("<UserMetadata>team &lt;a&amp;b&gt;</UserMetadata>"));
+        let parsed = parse_queue_from_entry(&wrap_atom_entry(&xml));
+        assert_eq!(parsed.user_metadata.as_deref(), Some("team <a&b>"));
+
+        let cdata = "<UserMetadata><![CDATA[x & y]]></UserMetadata>";
+        assert_eq!(
+            extract_text_value(cdata, "UserMetadata").as_deref(),
+            Some("x & y")
+        );
+    }
+}
@@ -0,0 +1,1073 @@
+//! Entity, message and tree types shared by the clients.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+// ──────────────────────────── Entity Models ────────────────────────────
+
+/// Queue settings, as read from and written to the management API.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct QueueDescription {
+    /// Queue name.
+    pub name: String,
+    /// How long a peek-lock holds a message, as an ISO 8601 duration (`PT30S`).
+    pub lock_duration: Option<String>,
+    /// Storage quota.
+    pub max_size_in_megabytes: Option<i64>,
+    /// Drop messages whose `MessageId` was seen within the history window. Fixed at creation.
+    pub requires_duplicate_detection: Option<bool>,
+    /// Receivers must accept a session. Fixed at creation.
+    pub requires_session: Option<bool>,
+    /// TTL of messages that do not set their own, as an ISO 8601 duration.
+    pub default_message_time_to_live: Option<String>,
+    /// Move expired messages to the dead-letter queue instead of dropping them.
+    pub dead_lettering_on_message_expiration: Option<bool>,
+    /// How far back duplicate detection looks, as an ISO 8601 duration.
+    pub duplicate_detection_history_time_window: Option<String>,
+    /// Deliveries after which a message is dead-lettered.
+    pub max_delivery_count: Option<i32>,
+    /// Server-side batching of store operations.
+    pub enable_batched_operations: Option<bool>,
+    /// `Active`, `Disabled`, `SendDisabled` or `ReceiveDisabled`.
+    pub status: Option<String>,
+    /// Entity that messages are auto-forwarded to.
+    pub forward_to: Option<String>,
+    /// Entity that dead-lettered messages are auto-forwarded to.
+    pub forward_dead_lettered_messages_to: Option<String>,
+    /// Idle time after which the entity is deleted, as an ISO 8601 duration.
+    pub auto_delete_on_idle: Option<String>,
+    /// Spread the entity over several message brokers. Fixed at creation.
+    pub enable_partitioning: Option<bool>,
+    /// Free-form text stored with the entity.
+    pub user_metadata: Option<String>,
+}
+
+/// Message counts and timestamps of a queue.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct QueueRuntimeInfo {
+    /// Queue name.
+    pub name: String,
+    /// Messages ready to be received.
+    pub active_message_count: i64,
+    /// Messages in the dead-letter queue.
+    pub dead_letter_message_count: i64,
+    /// Messages waiting for their scheduled enqueue time.
+    pub scheduled_message_count: i64,
+    /// Messages waiting to be forwarded.
+    pub transfer_message_count: i64,
+    /// Messages that could not be forwarded.
+    pub transfer_dead_letter_message_count: i64,
+    /// Storage in use.
+    pub size_in_bytes: i64,
+    /// Creation time as the service reports it (ISO 8601).
+    pub created_at: Option<String>,
+    /// Last change to the description (ISO 8601).
+    pub updated_at: Option<String>,
+    /// Last send or receive (ISO 8601).
+    pub accessed_at: Option<String>,
+    /// All messages, including dead-lettered, scheduled and transfer ones.
+    pub message_count: i64,
+}
+
+/// Utilization at or above which a queue or topic counts as nearly full.
+pub const QUOTA_WARN_RATIO: f64 = 0.95;
+
+/// Size of a queue or topic against its `MaxSizeInMegabytes` quota.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SizeUsage {
+    /// Storage in use.
+    pub size_bytes: i64,
+    /// The quota in bytes.
+    pub max_bytes: i64,
+}
+
+impl SizeUsage {
+    /// `None` when the quota is unknown or not positive.
+    pub fn new(size_bytes: i64, max_size_in_megabytes: Option<i64>) -> Option<Self> {
+        let max_mb = max_size_in_megabytes.filter(|mb| *mb > 0)?;
+        Some(Self {
+            size_bytes,
+            max_bytes: max_mb.saturating_mul(1024 * 1024),
+        })
+    }
+
+    /// Fraction of the quota in use; above 1.0 when over it.
+    pub fn ratio(&self) -> f64 {
+        self.size_bytes as f64 / self.max_bytes as f64
+    }
+
+    /// At or above [`QUOTA_WARN_RATIO`].
+    pub fn is_near_quota(&self) -> bool {
+        self.ratio() >= QUOTA_WARN_RATIO
+    }
+}
+
+/// "972.8 / 1024 MB (95%)"
+impl std::fmt::Display for SizeUsage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        const MB: f64 = 1024.0 * 1024.0;
+        write!(
+            f,
+            "{:.1} / {} MB ({:.0}%)",
+            self.size_bytes as f64 / MB,
+            self.max_bytes / (1024 * 1024),
+            self.ratio() * 100.0
+        )
+    }
+}
+
+/// A queue or topic looked up by name without knowing which it is, as an
+/// entity-scoped key (`EntityPath=`) names it. A queue's entry carries its
+/// counts too.
+#[derive(Debug, Clone)]
+pub enum EntityDescription {
+    /// A queue with its counts.
+    Queue(Box<QueueDescription>, QueueRuntimeInfo),
+    /// A topic.
+    Topic(TopicDescription),
+}
+
+impl EntityDescription {
+    /// `Queue` or `Topic`.
+    pub fn entity_type(&self) -> EntityType {
+        match self {
+            EntityDescription::Queue(..) => EntityType::Queue,
+            EntityDescription::Topic(_) => EntityType::Topic,
+        }
+    }
+}
+
+/// Topic settings, as read from and written to the management API.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct TopicDescription {
+    /// Topic name.
+    pub name: String,
+    /// Storage quota.
+    pub max_size_in_megabytes: Option<i64>,
+    /// TTL of messages that do not set their own, as an ISO 8601 duration.
+    pub default_message_time_to_live: Option<String>,
+    /// Drop messages whose `MessageId` was seen within the history window. Fixed at creation.
+    pub requires_duplicate_detection: Option<bool>,
+    /// How far back duplicate detection looks, as an ISO 8601 duration.
+    pub duplicate_detection_history_time_window: Option<String>,
+    /// Server-side batching of store operations.
+    pub enable_batched_operations: Option<bool>,
+    /// `Active`, `Disabled`, `SendDisabled` or `ReceiveDisabled`.
+    pub status: Option<String>,
+    /// Deliver messages in the order they were sent.
+    pub support_ordering: Option<bool>,
+    /// Idle time after which the entity is deleted, as an ISO 8601 duration.
+    pub auto_delete_on_idle: Option<String>,
+    /// Spread the entity over several message brokers. Fixed at creation.
+    pub enable_partitioning: Option<bool>,
+    /// Free-form text stored with the entity.
+    pub user_metadata: Option<String>,
+}
+
+/// Message counts and timestamps of a topic. The counts cover messages still
+/// held by the topic; delivered copies are counted on its subscriptions.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct TopicRuntimeInfo {
+    /// Topic name.
+    pub name: String,
+    /// Subscriptions of the topic.
+    pub subscription_count: i64,
+    /// Messages ready to be received.
+    pub active_message_count: i64,
+    /// Messages in the dead-letter queue.
+    pub dead_letter_message_count: i64,
+    /// Storage in use.
+    pub size_in_bytes: i64,
+    /// Creation time as the service reports it (ISO 8601).
+    pub created_at: Option<String>,
+    /// Last change to the description (ISO 8601).
+    pub updated_at: Option<String>,
+    /// Last send or receive (ISO 8601).
+    pub accessed_at: Option<String>,
+    /// Messages waiting for their scheduled enqueue time.
+    pub scheduled_message_count: i64,
+}
+
+/// Subscription settings, as read from and written to the management API.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct SubscriptionDescription {
+    /// Subscription name, without the topic.
+    pub name: String,
+    /// Topic the subscription belongs to.
+    pub topic_name: String,
+    /// How long a peek-lock holds a message, as an ISO 8601 duration (`PT30S`).
+    pub lock_duration: Option<String>,
+    /// Receivers must accept a session. Fixed at creation.
+    pub requires_session: Option<bool>,
+    /// TTL of messages that do not set their own, as an ISO 8601 duration.
+    pub default_message_time_to_live: Option<String>,
+    /// Move expired messages to the dead-letter queue instead of dropping them.
+    pub dead_lettering_on_message_expiration: Option<bool>,
+    /// Dead-letter messages whose rule filter fails to evaluate.
+    pub dead_lettering_on_filter_evaluation_exceptions: Option<bool>,
+    /// Deliveries after which a message is dead-lettered.
+    pub max_delivery_count: Option<i32>,
+    /// Server-side batching of store operations.
+    pub enable_batched_operations: Option<bool>,
+    /// `Active`, `Disabled`, `SendDisabled` or `ReceiveDisabled`.
+    pub status: Option<String>,
+    /// Entity that messages are auto-forwarded to.
+    pub forward_to: Option<String>,
+    /// Entity that dead-lettered messages are auto-forwarded to.
+    pub forward_dead_lettered_messages_to: Option<String>,
+    /// Idle time after which the entity is deleted, as an ISO 8601 duration.
+    pub auto_delete_on_idle: Option<String>,
+    /// Free-form text stored with the entity.
+    pub user_metadata: Option<String>,
+}
+
+/// Message counts and timestamps of a subscription.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct SubscriptionRuntimeInfo {
+    /// Subscription name, without the topic.
+    pub name: String,
+    /// Topic the subscription belongs to.
+    pub topic_name: String,
+    /// Messages ready to be received.
+    pub active_message_count: i64,
+    /// Messages in the dead-letter queue.
+    pub dead_letter_message_count: i64,
+    /// Messages waiting to be forwarded.
+    pub transfer_message_count: i64,
+    /// Messages that could not be forwarded.
+    pub transfer_dead_letter_message_count: i64,
+    /// All messages, including dead-lettered, scheduled and transfer ones.
+    pub message_count: i64,
+    /// Creation time as the service reports it (ISO 8601).
+    pub created_at: Option<String>,
+    /// Last change to the description (ISO 8601).
+    pub updated_at: Option<String>,
+    /// Last send or receive (ISO 8601).
+    pub accessed_at: Option<String>,
+}
+
+/// A single field that differs between two fetches of an entity description.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldChange {
+    /// PascalCase field name, e.g. `MaxDeliveryCount`.
+    pub field: String,
+    /// Old value; `-` when unset.
+    pub before: String,
+    /// New value; `-` when unset.
+    pub after: String,
+}
+
+/// Field-level diff of two entity descriptions. Field names are reported in
+/// the PascalCase used by the management API (e.g. `MaxDeliveryCount`).
+pub fn diff_descriptions<T: Serialize + PartialEq>(before: &T, after: &T) -> Vec<FieldChange> {
+    if before == after {
+        return Vec::new();
+    }
+    let (Ok(serde_json::Value::Object(old)), Ok(serde_json::Value::Object(new))) =
+        (serde_json::to_value(before), serde_json::to_value(after))
+    else {
+        return Vec::new();
+    };
+
+    let display = |v: Option<&serde_json::Value>| match v {
+        None | Some(serde_json::Value::Null) => "-".to_string(),
+        Some(serde_json::Value::String(s)) => s.clone(),
+        Some(other) => other.to_string(),
+    };
+
+    new.iter()
+        .filter(|(key, value)| old.get(*key) != Some(*value))
+        .map(|(key, value)| FieldChange {
+            field: pascal_case(key),
+            before: display(old.get(key)),
+            after: display(Some(value)),
+        })
+        .collect()
+}
+
+fn pascal_case(snake: &str) -> String {
+    snake
+        .split('_')
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+/// Whole seconds of .NET's `TimeSpan.MaxValue`, the TTL of messages and
+/// entities that never expire.
+const TIMESPAN_MAX_SECS: u64 = 922_337_203_685;
+
+/// Parse a TimeSpan the way the management API writes it, as an ISO 8601
+/// duration (`PT30S`, `P14D`, `P10675199DT2H48M5.4775807S`). Years, months
+/// and weeks are never used there and are rejected.
+pub fn parse_timespan(value: &str) -> Option<std::time::Duration> {
+    let rest = value.trim().strip_prefix('P')?;
+    let (date, time) = rest.split_once('T').unwrap_or((rest, ""));
+    let mut secs = 0f64;
+    for (part, units) in [
+        (date, &[('D', 86_400.0)][..]),
+        (time, &[('H', 3600.0), ('M', 60.0), ('S', 1.0)][..]),
+    ] {
+        let mut number = String::new();
+        for c in part.chars() {
+            if c.is_ascii_digit() || c == '.' {
+                number.push(c);
+                continue;
+            }
+            let (_, unit) = units.iter().find(|(u, _)| *u == c)?;
+            secs += number.parse::<f64>().ok()? * unit;
+            number.clear();
+        }
+        if !number.is_empty() {
+            return None;
+        }
+    }
+    std::time::Duration::try_from_secs_f64(secs).ok()
+}
+
+/// A rule of a subscription as listed in its rules feed.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct SubscriptionRule {
+    /// Rule name; `$Default` for the rule every subscription starts with.
+    pub name: String,
+    /// The SQL filter expression; empty for other filter types.
+    pub sql_expression: String,
+    /// `i:type` of the rule's filter: `SqlFilter`, `TrueFilter`, `FalseFilter`
+    /// or `CorrelationFilter`. Empty if the feed did not say.
+    #[serde(default)]
+    pub filter_type: String,
+}
+
+impl SubscriptionRule {
+    /// Short label for the routing diagram, e.g. `SQL: type='payment'` or `TrueFilter`.
+    pub fn filter_label(&self) -> String {
+        match self.filter_type.as_str() {
+            "TrueFilter" | "FalseFilter" => self.filter_type.clone(),
+            "CorrelationFilter" => "Correlation".to_string(),
+            _ => format!("SQL: {}", self.sql_expression),
+        }
+    }
+}
+
+/// A subscription rule with its full filter, as created by
+/// `ManagementClient::create_rule`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct RuleDescription {
+    /// Rule name.
+    pub name: String,
+    /// Which messages the rule lets through.
+    pub filter: RuleFilter,
+}
+
+/// The filter of a [`RuleDescription`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum RuleFilter {
+    /// A SQL-92 style expression over message properties; `1=1` matches all.
+    Sql {
+        /// The expression, e.g. `type = 'payment'`.
+        expression: String,
+    },
+    /// Equality on system and application properties.
+    Correlation(CorrelationFilter),
+}
+
+impl Default for RuleFilter {
+    fn default() -> Self {
+        RuleFilter::Sql {
+            expression: "1=1".to_string(),
+        }
+    }
+}
+
+/// Matches when every set field equals the message's; `properties` are
+/// compared against application properties as strings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct CorrelationFilter {
+    /// Matches `CorrelationId`.
+    pub correlation_id: Option<String>,
+    /// Matches `MessageId`.
+    pub message_id: Option<String>,
+    /// Matches `To`.
+    pub to: Option<String>,
+    /// Matches `ReplyTo`.
+    pub reply_to: Option<String>,
+    /// Matches `Label` (the subject).
+    pub label: Option<String>,
+    /// Matches `SessionId`.
+    pub session_id: Option<String>,
+    /// Matches `ReplyToSessionId`.
+    pub reply_to_session_id: Option<String>,
+    /// Matches `ContentType`.
+    pub content_type: Option<String>,
+    /// Application properties that must be present with these values.
+    pub properties: Vec<(String, String)>,
+}
+
+/// A subscription of a topic together with its rules (topic routing diagram).
+#[derive(Debug, Clone, Default)]
+pub struct SubscriptionRoute {
+    /// Subscription name.
+    pub name: String,
+    /// `None` if the rules could not be read.
+    pub rules: Option<Vec<SubscriptionRule>>,
+}
+
+/// A SAS authorization rule (policy) on the namespace or an entity.
+/// Primary/secondary keys are deliberately never parsed or stored.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct AuthorizationRule {
+    /// Policy name, e.g. `RootManageSharedAccessKey`.
+    pub key_name: String,
+    /// Usually `SharedAccessKey`.
+    pub claim_type: Option<String>,
+    /// `Manage`, `Send` and/or `Listen`.
+    pub rights: Vec<String>,
+    /// Creation time (ISO 8601).
+    pub created_time: Option<String>,
+    /// Last change (ISO 8601).
+    pub modified_time: Option<String>,
+}
+
+/// Namespace properties from the `$namespaceinfo` document, readable with
+/// the connection's own credentials (no ARM access needed).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct NamespaceInfo {
+    /// Namespace name.
+    pub name: String,
+    /// "Basic", "Standard" or "Premium".
+    pub messaging_sku: Option<String>,
+    /// Premium messaging units; 0 on the other tiers.
+    pub messaging_units: Option<i64>,
+    /// Creation time (ISO 8601).
+    pub created_at: Option<String>,
+    /// Last change (ISO 8601).
+    pub modified_at: Option<String>,
+}
+
+impl NamespaceInfo {
+    /// Basic-tier namespaces have queues only: no topics or subscriptions.
+    pub fn is_basic(&self) -> bool {
+        self.messaging_sku
+            .as_deref()
+            .is_some_and(|sku| sku.eq_ignore_ascii_case("basic"))
+    }
+}
+
+// ──────────────────────────── Message Models ────────────────────────────
+
+/// A message to send. Optional properties left `None` are not sent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServiceBusMessage {
+    /// Message body, sent as is.
+    pub body: String,
+    /// MIME type of the body; `application/json` by default.
+    pub content_type: Option<String>,
+    /// Application-defined id, used by duplicate detection.
+    pub message_id: Option<String>,
+    /// Application-defined correlation id.
+    pub correlation_id: Option<String>,
+    /// Session the message belongs to; required by session-enabled entities.
+    pub session_id: Option<String>,
+    /// Application-specific subject (`Label`).
+    pub label: Option<String>,
+    /// Application-defined address.
+    pub to: Option<String>,
+    /// Entity the receiver should reply to.
+    pub reply_to: Option<String>,
+    /// Session the receiver should reply to.
+    pub reply_to_session_id: Option<String>,
+    /// TTL in seconds; the entity default applies when unset.
+    pub time_to_live: Option<String>,
+    /// When to enqueue the message, as an RFC 2822 date; sent immediately when unset.
+    pub scheduled_enqueue_time: Option<String>,
+    /// Partition of a partitioned entity.
+    pub partition_key: Option<String>,
+    /// Partition of the transfer queue when sent via another entity.
+    pub via_partition_key: Option<String>,
+    /// Application properties, sent as HTTP headers.
+    pub custom_properties: Vec<(String, String)>,
+}
+
+impl Default for ServiceBusMessage {
+    fn default() -> Self {
+        Self {
+            body: String::new(),
+            content_type: Some("application/json".to_string()),
+            message_id: None,
+            correlation_id: None,
+            session_id: None,
+            label: None,
+            to: None,
+            reply_to: None,
+            reply_to_session_id: None,
+            time_to_live: None,
+            scheduled_enqueue_time: None,
+            partition_key: None,
+            via_partition_key: None,
+            custom_properties: Vec::new(),
+        }
+    }
+}
+
+/// A message peeked or received from an entity or its dead-letter queue.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReceivedMessage {
+    /// Message body as text; see `raw_body` when it is not valid UTF-8.
+    pub body: String,
+    /// Properties set by the sender and the broker.
+    pub broker_properties: BrokerProperties,
+    /// Application properties, in the order the response listed them.
+    pub custom_properties: Vec<(String, String)>,
+    /// The lock token URI for peek-locked messages (used for complete/abandon/deadletter).
+    pub lock_token_uri: Option<String>,
+    /// The entity path this message was peeked from (without `/$deadletterqueue`).
+    /// Populated during peek so bulk-resend knows which DLQ to remove from,
+    /// especially for topic fan-out where messages come from multiple subscription DLQs.
+    #[serde(skip)]
+    pub source_entity: Option<String>,
+    /// The body bytes as received, kept only when they are not valid UTF-8
+    /// (`body` then holds a lossy decoding).
+    #[serde(skip)]
+    pub raw_body: Option<Vec<u8>>,
+}
+
+impl ReceivedMessage {
+    /// The body exactly as the broker returned it.
+    pub fn body_bytes(&self) -> &[u8] {
+        self.raw_body.as_deref().unwrap_or(self.body.as_bytes())
+    }
+
+    /// Convert to a sendable message, preserving body, metadata, routing
+    /// properties, remaining TTL and custom properties.
+    /// Drops broker-assigned fields (sequence number, enqueued time, delivery count, etc.).
+    pub fn to_sendable(&self) -> ServiceBusMessage {
+        self.to_sendable_at(Utc::now())
+    }
+
+    fn to_sendable_at(&self, now: DateTime<Utc>) -> ServiceBusMessage {
+        let props = &self.broker_properties;
+        ServiceBusMessage {
+            body: self.body.clone(),
+            content_type: props.content_type.clone(),
+            message_id: props.message_id.clone(),
+            correlation_id: props.correlation_id.clone(),
+            session_id: props.session_id.clone(),
+            label: props.label.clone(),
+            to: props.to.clone(),
+            reply_to: props.reply_to.clone(),
+            reply_to_session_id: props.reply_to_session_id.clone(),
+            time_to_live: self.remaining_ttl_secs(now).map(|secs| secs.to_string()),
+            partition_key: props.partition_key.clone(),
+            via_partition_key: props.via_partition_key.clone(),
+            custom_properties: self.custom_properties.clone(),
+            ..Default::default()
+        }
+    }
+
+    /// Whole seconds of the original TTL still left at `now`. `None` when the
+    /// message has no TTL, its enqueue time is unknown, or it has already
+    /// expired — the resent copy then falls back to the entity default.
+    pub fn remaining_ttl_secs(&self, now: DateTime<Utc>) -> Option<u64> {
+        let ttl = self.broker_properties.time_to_live?;
+        let enqueued = self.broker_properties.enqueued_time_utc.as_deref()?;
+        let enqueued = DateTime::parse_from_rfc2822(enqueued).ok()?;
+        let elapsed = (now - enqueued.with_timezone(&Utc)).num_milliseconds() as f64 / 1000.0;
+        let remaining = (ttl - elapsed.max(0.0)).floor();
+        (remaining >= 1.0).then_some(remaining as u64)
+    }
+
+    /// When the message expires: its enqueue time plus the shorter of its own
+    /// TTL and the entity's default, since the broker applies the lower one.
+    /// `None` if neither TTL is known, the enqueue time is missing, or the TTL
+    /// is `TimeSpan.MaxValue`, which means "never".
+    pub fn expires_at(&self, default_ttl: Option<std::time::Duration>) -> Option<DateTime<Utc>> {
+        let own = self
+            .broker_properties
+            .time_to_live
+            .and_then(|secs| std::time::Duration::try_from_secs_f64(secs).ok());
+        let ttl = match (own, default_ttl) {
+            (Some(own), Some(default)) => own.min(default),
+            (own, default) => own.or(default)?,
+        };
+        if ttl.as_secs() >= TIMESPAN_MAX_SECS {
+            return None;
+        }
+        let enqueued = self.broker_properties.enqueued_time_utc.as_deref()?;
+        let enqueued = DateTime::parse_from_rfc2822(enqueued).ok()?;
+        enqueued
+            .with_timezone(&Utc)
+            .checked_add_signed(chrono::Duration::from_std(ttl).ok()?)
+    }
+}
+
+/// Identifies one peeked message well enough to find it again. On partitioned
+/// entities the same SequenceNumber can occur on several partitions, so the
+/// enqueued sequence number, partition key and message id are compared too.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MessageRef {
+    /// Broker-assigned `SequenceNumber`.
+    pub sequence_number: i64,
+    /// `EnqueuedSequenceNumber`, if the broker sent one.
+    pub enqueued_sequence_number: Option<i64>,
+    /// `PartitionKey`, if set.
+    pub partition_key: Option<String>,
+    /// `MessageId`, if set.
+    pub message_id: Option<String>,
+}
+
+impl MessageRef {
+    /// `None` if the message has no sequence number (never enqueued).
+    pub fn from_message(msg: &ReceivedMessage) -> Option<Self> {
+        let props = &msg.broker_properties;
+        Some(Self {
+            sequence_number: props.sequence_number?,
+            enqueued_sequence_number: props.enqueued_sequence_number,
+            partition_key: props.partition_key.clone(),
+            message_id: props.message_id.clone(),
+        })
+    }
+
+    /// Same sequence number, and every identity field we recorded agrees.
+    pub fn matches(&self, msg: &ReceivedMessage) -> bool {
+        fn agrees<T: PartialEq>(recorded: &Option<T>, actual: &Option<T>) -> bool {
+            recorded.is_none() || recorded == actual
+        }
+        let props = &msg.broker_properties;
+        props.sequence_number == Some(self.sequence_number)
+            && agrees(
+                &self.enqueued_sequence_number,
+                &props.enqueued_sequence_number,
+            )
+            && agrees(&self.partition_key, &props.partition_key)
+            && agrees(&self.message_id, &props.message_id)
+    }
+}
+
+/// The `BrokerProperties` header of a received message.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct BrokerProperties {
+    /// Application-defined id.
+    #[serde(rename = "MessageId")]
+    pub message_id: Option<String>,
+    /// Application-defined correlation id.
+    #[serde(rename = "CorrelationId")]
+    pub correlation_id: Option<String>,
+    /// Session the message belongs to.
+    #[serde(rename = "SessionId")]
+    pub session_id: Option<String>,
+    /// Application-specific subject.
+    #[serde(rename = "Label")]
+    pub label: Option<String>,
+    /// Application-defined address.
+    #[serde(rename = "To")]
+    pub to: Option<String>,
+    /// Entity to reply to.
+    #[serde(rename = "ReplyTo")]
+    pub reply_to: Option<String>,
+    /// Session to reply to.
+    #[serde(rename = "ReplyToSessionId")]
+    pub reply_to_session_id: Option<String>,
+    /// MIME type of the body.
+    #[serde(rename = "ContentType")]
+    pub content_type: Option<String>,
+    /// Number the broker assigned on enqueue; unique per entity (per partition when partitioned).
+    #[serde(rename = "SequenceNumber")]
+    pub sequence_number: Option<i64>,
+    /// Sequence number in the entity the message was first sent to, before any forwarding.
+    #[serde(rename = "EnqueuedSequenceNumber")]
+    pub enqueued_sequence_number: Option<i64>,
+    /// When the broker accepted the message (RFC 2822).
+    #[serde(rename = "EnqueuedTimeUtc")]
+    pub enqueued_time_utc: Option<String>,
+    /// When a peek-lock on the message expires (RFC 2822).
+    #[serde(rename = "LockedUntilUtc")]
+    pub locked_until_utc: Option<String>,
+    /// Token of the peek-lock.
+    #[serde(rename = "LockToken")]
+    pub lock_token: Option<String>,
+    /// TTL in seconds.
+    #[serde(rename = "TimeToLive")]
+    pub time_to_live: Option<f64>,
+    /// Times the message has been delivered, counting this one.
+    #[serde(rename = "DeliveryCount")]
+    pub delivery_count: Option<i32>,
+    /// Entity the message was dead-lettered from, when it was forwarded first.
+    #[serde(rename = "DeadLetterSource")]
+    pub dead_letter_source: Option<String>,
+    /// Why the message was dead-lettered, e.g. `MaxDeliveryCountExceeded`.
+    #[serde(rename = "DeadLetterReason")]
+    pub dead_letter_reason: Option<String>,
+    /// Details of the dead-letter reason.
+    #[serde(rename = "DeadLetterErrorDescription")]
+    pub dead_letter_error_description: Option<String>,
+    /// `Active`, `Deferred` or `Scheduled`.
+    #[serde(rename = "State")]
+    pub state: Option<String>,
+    /// Partition of a partitioned entity.
+    #[serde(rename = "PartitionKey")]
+    pub partition_key: Option<String>,
+    /// Partition of the transfer queue when sent via another entity.
+    #[serde(rename = "ViaPartitionKey")]
+    pub via_partition_key: Option<String>,
+    /// Scheduled enqueue time (RFC 2822), if any.
+    #[serde(rename = "ScheduledEnqueueTimeUtc")]
+    pub scheduled_enqueue_time_utc: Option<String>,
+    /// Size of the message in bytes.
+    #[serde(rename = "Size")]
+    pub size: Option<i64>,
+}
+
+// ──────────────────────────── Tree / UI Models ────────────────────────────
+
+/// What a tree node stands for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EntityType {
+    /// The root of the tree.
+    Namespace,
+    /// Folder holding the queues.
+    QueueFolder,
+    /// Folder holding the topics.
+    TopicFolder,
+    /// A queue.
+    Queue,
+    /// A topic; its subscriptions sit below it.
+    Topic,
+    /// A subscription of a topic.
+    Subscription,
+    /// Folder holding a topic's subscriptions.
+    SubscriptionFolder,
+    /// Dead-letter sub-queue of a queue or subscription; its path ends in
+    /// `/$deadletterqueue`.
+    DeadLetterQueue,
+}
+
+/// A node of the namespace tree: the namespace, a folder or an entity.
+#[derive(Debug, Clone)]
+pub struct TreeNode {
+    /// Unique within the tree, stable across refreshes.
+    pub id: String,
+    /// Text shown for the node.
+    pub label: String,
+    /// What the node stands for.
+    pub entity_type: EntityType,
+    /// Entity path; empty for the namespace and folders.
+    pub path: String,
+    /// Nesting level; the namespace is 0.
+    pub depth: usize,
+    /// Whether children are shown.
+    pub expanded: bool,
+    /// Child nodes, in display order.
+    pub children: Vec<TreeNode>,
+    /// Active messages, once known.
+    pub message_count: Option<i64>,
+    /// Dead-lettered messages, once known.
+    pub dlq_count: Option<i64>,
+    /// Entity path this queue/subscription auto-forwards to.
+    pub forward_to: Option<String>,
+    /// Counts (and, for a topic, its subscriptions) are missing or stale and
+    /// will be fetched once the node scrolls into view.
+    pub counts_pending: bool,
+}
+
+impl TreeNode {
+    /// A folder node, expanded, with no entity path.
+    pub fn new_folder(id: &str, label: &str, entity_type: EntityType, depth: usize) -> Self {
+        Self {
+            id: id.to_string(),
+            label: label.to_string(),
+            entity_type,
+            path: String::new(),
+            depth,
+            expanded: true,
+            children: Vec::new(),
+            message_count: None,
+            dlq_count: None,
+            forward_to: None,
+            counts_pending: false,
+        }
+    }
+
+    /// A collapsed entity node at `path`.
+    pub fn new_entity(
+        id: &str,
+        label: &str,
+        entity_type: EntityType,
+        path: &str,
+        depth: usize,
+    ) -> Self {
+        Self {
+            id: id.to_string(),
+            label: label.to_string(),
+            entity_type,
+            path: path.to_string(),
+            depth,
+            expanded: false,
+            children: Vec::new(),
+            message_count: None,
+            dlq_count: None,
+            forward_to: None,
+            counts_pending: false,
+        }
+    }
+
+    /// Record the expanded flag of every node (visible or not), keyed by node ID.
+    pub fn collect_expand_state(&self, out: &mut HashMap<String, bool>) {
+        out.insert(self.id.clone(), self.expanded);
+        for child in &self.children {
+            child.collect_expand_state(out);
+        }
+    }
+
+    /// Flatten this tree into a displayable list of visible nodes.
+    pub fn flatten(&self) -> Vec<FlatNode> {
+        let mut result = Vec::new();
+        self.flatten_inner(&mut result);
+        result
+    }
+
+    fn flatten_inner(&self, out: &mut Vec<FlatNode>) {
+        out.push(FlatNode {
+            id: self.id.clone(),
+            label: self.label.clone(),
+            entity_type: self.entity_type.clone(),
+            path: self.path.clone(),
+            depth: self.depth,
+            expanded: self.expanded,
+            has_children: !self.children.is_empty() || self.counts_pending,
+            message_count: self.message_count,
+            dlq_count: self.dlq_count,
+            forward_to: self.forward_to.clone(),
+            counts_pending: self.counts_pending,
+        });
+        if self.expanded {
+            for child in &self.children {
+                child.flatten_inner(out);
+            }
+        }
+    }
+}
+
+/// A visible row of a flattened [`TreeNode`] tree.
+#[derive(Debug, Clone)]
+pub struct FlatNode {
+    /// Id of the node.
+    pub id: String,
+    /// Text shown for the node.
+    pub label: String,
+    /// What the node stands for.
+    pub entity_type: EntityType,
+    /// Entity path; empty for the namespace and folders.
+    pub path: String,
+    /// Nesting level; the namespace is 0.
+    pub depth: usize,
+    /// Whether the node's children follow it.
+    pub expanded: bool,
+    /// Whether the node has (or will load) children.
+    pub has_children: bool,
+    /// Active messages, once known.
+    pub message_count: Option<i64>,
+    /// Dead-lettered messages, once known.
+    pub dlq_count: Option<i64>,
+    /// Entity path this queue/subscription auto-forwards to.
+    pub forward_to: Option<String>,
+    /// Counts are missing or stale and will be fetched once the node scrolls into view.
+    pub counts_pending: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn size_usage_against_quota() {
+        let usage = SizeUsage::new(1_020_054_733, Some(1024)).unwrap();
+        assert!(usage.is_near_quota());
+        assert_eq!(usage.to_string(), "972.8 / 1024 MB (95%)");
+        assert!(!SizeUsage::new(1024, Some(1024)).unwrap().is_near_quota());
+        assert_eq!(SizeUsage::new(1024, None), None);
+        assert_eq!(SizeUsage::new(1024, Some(0)), None);
+    }
+
+    #[test]
+    fn description_diff_lists_changed_fields_in_pascal_case() {
+        let before = QueueDescription {
+            name: "orders".to_string(),
+            max_delivery_count: Some(10),
+            forward_to: Some("audit".to_string()),
+            ..Default::default()
+        };
+        assert!(diff_descriptions(&before, &before.clone()).is_empty());
+
+        let after = QueueDescription {
+            max_delivery_count: Some(5),
+            forward_to: None,
+            lock_duration: Some("PT1M".to_string()),
+            ..before.clone()
+        };
+        let mut changes = diff_descriptions(&before, &after);
+        changes.sort_by(|a, b| a.field.cmp(&b.field));
+        let change = |field: &str, before: &str, after: &str| FieldChange {
+            field: field.to_string(),
+            before: before.to_string(),
+            after: after.to_string(),
+        };
+        assert_eq!(
+            changes,
+            vec![
+                change("ForwardTo", "audit", "-"),
+                change("LockDuration", "-", "PT1M"),
+                change("MaxDeliveryCount", "10", "5"),
+            ]
+        );
+    }
+
+    fn dead_lettered_request() -> ReceivedMessage {
+        ReceivedMessage {
+            body: "{\"op\":\"ping\"}".to_string(),
+            broker_properties: BrokerProperties {
+                message_id: Some("msg-1".to_string()),
+                correlation_id: Some("corr-1".to_string()),
+                session_id: Some("session-1".to_string()),
+                label: Some("ping".to_string()),
+                to: Some("worker".to_string()),
+                reply_to: Some("replies".to_string()),
+                reply_to_session_id: Some("reply-session-1".to_string()),
+                content_type: Some("application/json".to_string()),
+                sequence_number: Some(42),
+                enqueued_time_utc: Some("Wed, 05 Sep 2018 05:06:43 GMT".to_string()),
+                time_to_live: Some(3600.0),
+                delivery_count: Some(10),
+                dead_letter_reason: Some("MaxDeliveryCountExceeded".to_string()),
+                partition_key: Some("session-1".to_string()),
+                ..Default::default()
+            },
+            custom_properties: vec![("tenant".to_string(), "contoso".to_string())],
+            lock_token_uri: None,
+            source_entity: Some("orders".to_string()),
+            raw_body: None,
+        }
+    }
+
+    #[test]
+    fn to_sendable_round_trips_all_user_properties() {
+        let msg = dead_lettered_request();
+        let enqueued = DateTime::parse_from_rfc2822("Wed, 05 Sep 2018 05:06:43 GMT")
+            .unwrap()
+            .with_timezone(&Utc);
+        let sendable = msg.to_sendable_at(enqueued + chrono::Duration::seconds(600));
+
+        assert_eq!(sendable.body, msg.body);
+        assert_eq!(sendable.content_type.as_deref(), Some("application/json"));
+        assert_eq!(sendable.message_id.as_deref(), Some("msg-1"));
+        assert_eq!(sendable.correlation_id.as_deref(), Some("corr-1"));
+        assert_eq!(sendable.session_id.as_deref(), Some("session-1"));
+        assert_eq!(sendable.label.as_deref(), Some("ping"));
+        assert_eq!(sendable.to.as_deref(), Some("worker"));
+        assert_eq!(sendable.reply_to.as_deref(), Some("replies"));
+        assert_eq!(
+            sendable.reply_to_session_id.as_deref(),
+            Some("reply-session-1")
+        );
+        assert_eq!(sendable.partition_key.as_deref(), Some("session-1"));
+        assert_eq!(sendable.time_to_live.as_deref(), Some("3000"));
+        assert_eq!(sendable.custom_properties, msg.custom_properties);
+        assert_eq!(sendable.scheduled_enqueue_time, None);
+    }
+
+    #[test]
+    fn expiry_uses_the_shorter_of_message_and_entity_ttl() {
+        let mut msg = dead_lettered_request();
+        let enqueued = DateTime::parse_from_rfc2822("Wed, 05 Sep 2018 05:06:43 GMT")
+            .unwrap()
+            .with_timezone(&Utc);
+        let hour = chrono::Duration::hours(1);
+        assert_eq!(msg.expires_at(None), Some(enqueued + hour));
+        assert_eq!(
+            msg.expires_at(parse_timespan("PT10M")),
+            Some(enqueued + chrono::Duration::minutes(10))
+        );
+        msg.broker_properties.time_to_live = None;
+        assert_eq!(
+            msg.expires_at(parse_timespan("PT1H")),
+            Some(enqueued + hour)
+        );
+        assert_eq!(msg.expires_at(None), None);
+        assert_eq!(
+            msg.expires_at(parse_timespan("P10675199DT2H48M5.4775807S")),
+            None
+        );
+
+        assert_eq!(
+            parse_timespan("P1DT2H3M4.5S"),
+            Some(std::time::Duration::from_secs_f64(93_784.5))
+        );
+        assert_eq!(
+            parse_timespan("PT30S"),
+            Some(std::time::Duration::from_secs(30))
+        );
+        assert_eq!(parse_timespan("P1Y"), None);
+        assert_eq!(parse_timespan("30S"), None);
+        assert_eq!(parse_timespan("PT30"), None);
+    }
+
+    #[test]
+    fn expired_or_unknown_ttl_is_dropped() {
+        let mut msg = dead_lettered_request();
+        assert_eq!(msg.to_sendable_at(Utc::now()).time_to_live, None);
+
+        msg.broker_properties.enqueued_time_utc = None;
+        assert_eq!(msg.remaining_ttl_secs(Utc::now()), None);
+    }
+
+    /// A peeked message as the REST API returns it: BrokerProperties header + body.
+    fn peeked(broker_properties: &str) -> ReceivedMessage {
+        ReceivedMessage {
+            body: String::new(),
+            broker_properties: serde_json::from_str(broker_properties).unwrap(),
+            custom_properties: Vec::new(),
+            lock_token_uri: None,
+            source_entity: None,
+            raw_body: None,
+        }
+    }
+
+    #[test]
+    fn message_ref_tells_partitions_apart() {
+        // Two partitions of the same DLQ handing out SequenceNumber 7
+        let a = peeked(
+            r#"{"SequenceNumber":7,"EnqueuedSequenceNumber":7,"PartitionKey":"p0","MessageId":"a"}"#,
+        );
+        let b = peeked(
+            r#"{"SequenceNumber":7,"EnqueuedSequenceNumber":7,"PartitionKey":"p1","MessageId":"b"}"#,
+        );
+        let target = MessageRef::from_message(&b).unwrap();
+        assert!(!target.matches(&a));
+        assert!(target.matches(&b));
+    }
+
+    #[test]
+    fn message_ref_uses_message_id_as_tiebreaker() {
+        let a = peeked(r#"{"SequenceNumber":7,"MessageId":"a"}"#);
+        let b = peeked(r#"{"SequenceNumber":7,"MessageId":"b"}"#);
+        let target = MessageRef::from_message(&a).unwrap();
+        assert!(target.matches(&a));
+        assert!(!target.matches(&b));
+        assert!(MessageRef::from_message(&peeked(r#"{"MessageId":"x"}"#)).is_none());
+    }
+
+    #[test]
+    fn parses_reply_to_session_id_from_broker_properties() {
+        let json = r#"{"MessageId":"m","ReplyTo":"q","ReplyToSessionId":"s","ViaPartitionKey":"p","TimeToLive":60.5}"#;
+        let props: BrokerProperties = serde_json::from_str(json).unwrap();
+        assert_eq!(props.reply_to.as_deref(), Some("q"));
+        assert_eq!(props.reply_to_session_id.as_deref(), Some("s"));
+        assert_eq!(props.via_partition_key.as_deref(), Some("p"));
+        assert_eq!(props.time_to_live, Some(60.5));
+    }
+}
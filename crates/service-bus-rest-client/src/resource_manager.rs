@@ -0,0 +1,705 @@
+//! Azure Resource Manager calls for namespace discovery and metrics
+//! (`azure-ad` feature).
+
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Azure subscription returned from ARM API.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Subscription {
+    /// Subscription GUID.
+    #[serde(rename = "subscriptionId")]
+    pub subscription_id: String,
+    /// Name shown in the portal.
+    #[serde(rename = "displayName")]
+    pub display_name: String,
+    /// `Enabled`, `Disabled`, `Warned`, …
+    pub state: String,
+}
+
+/// List wrapper for subscriptions. ARM pages lists; `nextLink` points at
+/// the next page and is absent on the last one.
+#[derive(Debug, Deserialize)]
+struct SubscriptionListResponse {
+    value: Vec<Subscription>,
+    #[serde(rename = "nextLink")]
+    next_link: Option<String>,
+}
+
+/// Azure Service Bus namespace resource.
+#[derive(Debug, Clone, Deserialize)]
+pub struct NamespaceResource {
+    /// Full ARM resource ID (`/subscriptions/…/resourceGroups/…/providers/…`).
+    #[serde(default)]
+    pub id: String,
+    /// Namespace name (the first label of its host name).
+    pub name: String,
+    /// Azure region, e.g. `westeurope`.
+    pub location: String,
+    /// Pricing tier, when ARM reports one.
+    #[serde(default)]
+    pub sku: Option<NamespaceSku>,
+    /// Endpoint, status and timestamps.
+    pub properties: NamespaceProperties,
+}
+
+impl NamespaceResource {
+    /// The SKU and timestamps the Service Bus endpoint itself does not expose.
+    pub fn detail(&self) -> NamespaceDetail {
+        let sku = self.sku.as_ref();
+        NamespaceDetail {
+            sku_name: sku.map(|s| s.name.clone()),
+            sku_tier: sku.and_then(|s| s.tier.clone()),
+            capacity: sku.and_then(|s| s.capacity),
+            created_at: self.properties.created_at.clone(),
+            updated_at: self.properties.updated_at.clone(),
+            provisioning_state: self.properties.provisioning_state.clone(),
+        }
+    }
+}
+
+/// Namespace pricing tier from ARM.
+#[derive(Debug, Clone, Deserialize)]
+pub struct NamespaceSku {
+    /// `Basic`, `Standard` or `Premium`.
+    pub name: String,
+    /// Usually the same as `name`.
+    pub tier: Option<String>,
+    /// Messaging units (Premium only).
+    pub capacity: Option<i64>,
+}
+
+/// Namespace properties from ARM.
+#[derive(Debug, Clone, Deserialize)]
+pub struct NamespaceProperties {
+    /// e.g. `https://myns.servicebus.windows.net:443/`.
+    #[serde(rename = "serviceBusEndpoint")]
+    pub service_bus_endpoint: String,
+    /// `Active`, `Disabled`, …; empty if ARM left it out.
+    #[serde(default)]
+    pub status: String,
+    /// Creation time (ISO 8601).
+    #[serde(rename = "createdAt")]
+    pub created_at: Option<String>,
+    /// Last change (ISO 8601).
+    #[serde(rename = "updatedAt")]
+    pub updated_at: Option<String>,
+    /// `Succeeded`, `Creating`, `Deleting`, …
+    #[serde(rename = "provisioningState")]
+    pub provisioning_state: Option<String>,
+}
+
+/// Namespace-level facts only ARM knows about (SKU, capacity, timestamps).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NamespaceDetail {
+    /// See [`NamespaceSku::name`].
+    pub sku_name: Option<String>,
+    /// See [`NamespaceSku::tier`].
+    pub sku_tier: Option<String>,
+    /// Messaging units (Premium only).
+    pub capacity: Option<i64>,
+    /// Creation time (ISO 8601).
+    pub created_at: Option<String>,
+    /// Last change (ISO 8601).
+    pub updated_at: Option<String>,
+    /// See [`NamespaceProperties::provisioning_state`].
+    pub provisioning_state: Option<String>,
+}
+
+/// Message totals of one entity over a metrics timespan (Azure Monitor).
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct EntityMetrics {
+    /// `IncomingMessages`; `None` when Azure Monitor had no data points.
+    pub incoming: Option<f64>,
+    /// `OutgoingMessages`; `None` when Azure Monitor had no data points.
+    pub outgoing: Option<f64>,
+}
+
+/// Azure Monitor metrics response (only the parts we read).
+#[derive(Debug, Deserialize)]
+struct MetricsResponse {
+    #[serde(default)]
+    value: Vec<Metric>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Metric {
+    name: MetricName,
+    #[serde(default)]
+    timeseries: Vec<MetricTimeseries>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MetricName {
+    value: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct MetricTimeseries {
+    #[serde(default)]
+    data: Vec<MetricValue>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MetricValue {
+    total: Option<f64>,
+}
+
+/// Sum `IncomingMessages`/`OutgoingMessages` totals across all time grains.
+/// A metric with no data points at all stays `None`.
+fn parse_entity_metrics(json: &str) -> Result<EntityMetrics, String> {
+    let parsed: MetricsResponse =
+        serde_json::from_str(json).map_err(|e| format!("Failed to parse metrics: {}", e))?;
+    let mut metrics = EntityMetrics::default();
+    for metric in parsed.value {
+        let total = metric
+            .timeseries
+            .iter()
+            .flat_map(|ts| ts.data.iter())
+            .filter_map(|point| point.total)
+            .fold(None, |sum: Option<f64>, v| Some(sum.unwrap_or(0.0) + v));
+        match metric.name.value.as_str() {
+            "IncomingMessages" => metrics.incoming = total,
+            "OutgoingMessages" => metrics.outgoing = total,
+            _ => {}
+        }
+    }
+    Ok(metrics)
+}
+
+/// List wrapper for namespaces, paged like `SubscriptionListResponse`.
+#[derive(Debug, Deserialize)]
+struct NamespaceListResponse {
+    value: Vec<NamespaceResource>,
+    #[serde(rename = "nextLink")]
+    next_link: Option<String>,
+}
+
+/// Discovered namespace with enriched metadata.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiscoveredNamespace {
+    /// Host name to connect to, e.g. `myns.servicebus.windows.net`.
+    pub fqdn: String,
+    /// Namespace name.
+    pub name: String,
+    /// Azure subscription the namespace lives in.
+    pub subscription_id: String,
+    /// Display name of that subscription.
+    pub subscription_name: String,
+    /// Full ARM resource ID; metrics are queried under it.
+    pub resource_id: String,
+    /// Parsed from the ARM resource ID; needed to re-fetch the namespace.
+    pub resource_group: Option<String>,
+    /// Azure region.
+    pub location: String,
+    /// See [`NamespaceProperties::status`].
+    pub status: String,
+    /// SKU and timestamps, when ARM returned them.
+    pub detail: Option<NamespaceDetail>,
+}
+
+/// Result of namespace discovery operation. Serializable so the app can
+/// cache it between runs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiscoveryResult {
+    /// Sorted by subscription name, then namespace name.
+    pub namespaces: Vec<DiscoveredNamespace>,
+    /// What went wrong along the way, e.g. a subscription that could not be
+    /// listed.
+    pub errors: Vec<String>,
+}
+
+/// Who a discovery ran as: the tenant and signed-in account of the ARM
+/// token. Different identities see different subscriptions, so cached
+/// results are kept per identity.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArmIdentity {
+    /// Azure AD tenant (directory) id.
+    pub tenant_id: String,
+    /// User principal name for users, application id for service
+    /// principals and managed identities.
+    pub account: String,
+}
+
+impl ArmIdentity {
+    /// Read the `tid` claim and the first of `upn`, `unique_name`,
+    /// `preferred_username`, `appid` and `oid` from a JWT access token.
+    /// The signature is not checked; the token is only used to tell
+    /// identities apart.
+    pub fn from_token(token: &str) -> Option<Self> {
+        use base64::Engine;
+
+        let payload = token.split('.').nth(1)?;
+        let bytes = base64::engine::general_purpose::URL_SAFE_NO_PAD
+            .decode(payload.trim_end_matches('='))
+            .ok()?;
+        let claims: serde_json::Value = serde_json::from_slice(&bytes).ok()?;
+        let claim = |name: &str| {
+            claims
+                .get(name)
+                .and_then(|v| v.as_str())
+                .filter(|v| !v.is_empty())
+                .map(str::to_string)
+        };
+        Some(Self {
+            tenant_id: claim("tid")?,
+            account: ["upn", "unique_name", "preferred_username", "appid", "oid"]
+                .into_iter()
+                .find_map(claim)?,
+        })
+    }
+}
+
+/// Azure Resource Manager client for discovering Service Bus namespaces.
+#[derive(Clone)]
+pub struct ResourceManagerClient {
+    http_client: reqwest::Client,
+    credential: Arc<dyn azure_core::credentials::TokenCredential>,
+}
+
+impl ResourceManagerClient {
+    /// Create a new Resource Manager client.
+    pub fn new(credential: Arc<dyn azure_core::credentials::TokenCredential>) -> Self {
+        Self {
+            http_client: reqwest::Client::new(),
+            credential,
+        }
+    }
+
+    /// Get an Azure Resource Manager bearer token.
+    async fn get_token(&self) -> Result<String, String> {
+        let token = self
+            .credential
+            .get_token(&["https://management.azure.com/.default"])
+            .await
+            .map_err(|e| format!("Failed to get ARM token: {}", e))?;
+
+        Ok(token.token.secret().to_string())
+    }
+
+    /// The tenant and account the credential signs in as.
+    pub async fn identity(&self) -> Result<ArmIdentity, String> {
+        let token = self.get_token().await?;
+        ArmIdentity::from_token(&token)
+            .ok_or_else(|| "ARM token has no tenant or account claims".to_string())
+    }
+
+    /// List all accessible Azure subscriptions, following `nextLink` across
+    /// pages.
+    pub async fn list_subscriptions(&self) -> Result<Vec<Subscription>, String> {
+        let token = self.get_token().await?;
+        let mut next_url =
+            Some("https://management.azure.com/subscriptions?api-version=2020-01-01".to_string());
+        let mut subscriptions = Vec::new();
+
+        while let Some(url) = next_url {
+            let response = self
+                .http_client
+                .get(&url)
+                .bearer_auth(&token)
+                .send()
+                .await
+                .map_err(|e| format!("Failed to list subscriptions: {}", e))?;
+
+            if !response.status().is_success() {
+                let status = response.status();
+                let body = response
+                    .text()
+                    .await
+                    .unwrap_or_else(|_| String::from("(no body)"));
+                return Err(format!("Subscription list failed ({}): {}", status, body));
+            }
+
+            let parsed: SubscriptionListResponse = response
+                .json()
+                .await
+                .map_err(|e| format!("Failed to parse subscription list: {}", e))?;
+            subscriptions.extend(parsed.value);
+            next_url = parsed.next_link.filter(|link| !link.is_empty());
+        }
+
+        // Filter only active subscriptions
+        let active: Vec<Subscription> = subscriptions
+            .into_iter()
+            .filter(|s| s.state.to_lowercase() == "enabled")
+            .collect();
+
+        Ok(active)
+    }
+
+    /// List Service Bus namespaces in a subscription, following `nextLink`
+    /// across pages.
+    pub async fn list_namespaces(
+        &self,
+        subscription_id: &str,
+    ) -> Result<Vec<NamespaceResource>, String> {
+        let token = self.get_token().await?;
+        let mut next_url = Some(format!(
+            "https://management.azure.com/subscriptions/{}/providers/Microsoft.ServiceBus/namespaces?api-version=2021-11-01",
+            subscription_id
+        ));
+        let mut namespaces = Vec::new();
+
+        while let Some(url) = next_url {
+            let response = self
+                .http_client
+                .get(&url)
+                .bearer_auth(&token)
+                .send()
+                .await
+                .map_err(|e| format!("Failed to list namespaces: {}", e))?;
+
+            if !response.status().is_success() {
+                let status = response.status();
+                let body = response
+                    .text()
+                    .await
+                    .unwrap_or_else(|_| String::from("(no body)"));
+                return Err(format!("Namespace list failed ({}): {}", status, body));
+            }
+
+            let parsed: NamespaceListResponse = response
+                .json()
+                .await
+                .map_err(|e| format!("Failed to parse namespace list: {}", e))?;
+            namespaces.extend(parsed.value);
+            next_url = parsed.next_link.filter(|link| !link.is_empty());
+        }
+
+        Ok(namespaces)
+    }
+
+    /// Fetch a single namespace's SKU, capacity and timestamps.
+    pub async fn get_namespace(
+        &self,
+        subscription_id: &str,
+        resource_group: &str,
+        namespace_name: &str,
+    ) -> Result<NamespaceDetail, String> {
+        let token = self.get_token().await?;
+        let url = format!(
+            "https://management.azure.com/subscriptions/{}/resourceGroups/{}/providers/Microsoft.ServiceBus/namespaces/{}?api-version=2021-11-01",
+            subscription_id, resource_group, namespace_name
+        );
+
+        let response = self
+            .http_client
+            .get(&url)
+            .bearer_auth(&token)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to get namespace: {}", e))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response
+                .text()
+                .await
+                .unwrap_or_else(|_| String::from("(no body)"));
+            return Err(format!("Namespace lookup failed ({}): {}", status, body));
+        }
+
+        let parsed: NamespaceResource = response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse namespace: {}", e))?;
+
+        Ok(parsed.detail())
+    }
+
+    /// Incoming/outgoing message totals of a queue or topic over the last
+    /// `timespan`, from the Azure Monitor metrics of the namespace.
+    pub async fn get_entity_metrics(
+        &self,
+        namespace_resource_id: &str,
+        entity_name: &str,
+        timespan: std::time::Duration,
+    ) -> Result<EntityMetrics, String> {
+        let token = self.get_token().await?;
+        let timespan = format!("PT{}S", timespan.as_secs());
+        let filter = format!("EntityName eq '{}'", entity_name.replace('\'', "''"));
+        let url = format!(
+            "https://management.azure.com{}/providers/microsoft.insights/metrics?api-version=2018-01-01&metricnames=IncomingMessages,OutgoingMessages&aggregation=Total&timespan={}&interval={}&$filter={}",
+            namespace_resource_id,
+            timespan,
+            timespan,
+            urlencoding::encode(&filter)
+        );
+
+        let response = self
+            .http_client
+            .get(&url)
+            .bearer_auth(&token)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to get metrics: {}", e))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response
+                .text()
+                .await
+                .unwrap_or_else(|_| String::from("(no body)"));
+            return Err(format!("Metrics lookup failed ({}): {}", status, body));
+        }
+
+        let body = response
+            .text()
+            .await
+            .map_err(|e| format!("Failed to read metrics: {}", e))?;
+        parse_entity_metrics(&body)
+    }
+
+    /// Discover all Service Bus namespaces across all subscriptions.
+    /// Returns both successful discoveries and per-subscription errors.
+    pub async fn discover_namespaces(&self) -> DiscoveryResult {
+        let mut all_namespaces = Vec::new();
+        let mut errors = Vec::new();
+
+        // Get subscriptions
+        let subscriptions = match self.list_subscriptions().await {
+            Ok(subs) => subs,
+            Err(e) => {
+                return DiscoveryResult {
+                    namespaces: Vec::new(),
+                    errors: vec![format!("Failed to list subscriptions: {}", e)],
+                };
+            }
+        };
+
+        if subscriptions.is_empty() {
+            return DiscoveryResult {
+                namespaces: Vec::new(),
+                errors: vec!["No enabled Azure subscriptions found".to_string()],
+            };
+        }
+
+        // Query each subscription in parallel
+        let mut handles = Vec::with_capacity(subscriptions.len());
+        for sub in subscriptions {
+            let client = self.clone();
+            let subscription_id = sub.subscription_id.clone();
+            let subscription_name = sub.display_name.clone();
+
+            handles.push(tokio::spawn(async move {
+                (
+                    subscription_name.clone(),
+                    subscription_id.clone(),
+                    client.list_namespaces(&subscription_id).await,
+                )
+            }));
+        }
+
+        // Collect results
+        for handle in handles {
+            match handle.await {
+                Ok((sub_name, sub_id, Ok(namespaces))) => {
+                    for ns in namespaces {
+                        // Extract FQDN from serviceBusEndpoint (e.g., "https://mynamespace.servicebus.windows.net:443/")
+                        let fqdn = extract_fqdn_from_endpoint(&ns.properties.service_bus_endpoint);
+                        let detail = ns.detail();
+
+                        all_namespaces.push(DiscoveredNamespace {
+                            fqdn,
+                            resource_group: resource_group_from_id(&ns.id),
+                            resource_id: ns.id,
+                            name: ns.name,
+                            subscription_id: sub_id.clone(),
+                            subscription_name: sub_name.clone(),
+                            location: ns.location,
+                            status: ns.properties.status,
+                            detail: Some(detail),
+                        });
+                    }
+                }
+                Ok((sub_name, _sub_id, Err(e))) => {
+                    errors.push(format!("Subscription '{}': {}", sub_name, e));
+                }
+                Err(e) => {
+                    errors.push(format!("Task join error: {}", e));
+                }
+            }
+        }
+
+        // Sort by subscription name, then namespace name
+        all_namespaces.sort_by(|a, b| {
+            a.subscription_name
+                .cmp(&b.subscription_name)
+                .then_with(|| a.name.cmp(&b.name))
+        });
+
+        DiscoveryResult {
+            namespaces: all_namespaces,
+            errors,
+        }
+    }
+}
+
+/// Extract FQDN from Azure Service Bus endpoint URL.
+/// Example: "https://mynamespace.servicebus.windows.net:443/" -> "mynamespace.servicebus.windows.net"
+fn extract_fqdn_from_endpoint(endpoint: &str) -> String {
+    let trimmed = endpoint
+        .trim_start_matches("https://")
+        .trim_start_matches("http://");
+
+    if let Some(colon_idx) = trimmed.find(':') {
+        trimmed[..colon_idx].to_string()
+    } else if let Some(slash_idx) = trimmed.find('/') {
+        trimmed[..slash_idx].to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+/// Resource group segment of an ARM resource ID.
+/// Example: "/subscriptions/s/resourceGroups/rg-app/providers/..." -> "rg-app"
+fn resource_group_from_id(id: &str) -> Option<String> {
+    let mut segments = id.split('/');
+    segments
+        .by_ref()
+        .find(|s| s.eq_ignore_ascii_case("resourceGroups"))?;
+    segments
+        .next()
+        .filter(|rg| !rg.is_empty())
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_fqdn() {
+        assert_eq!(
+            extract_fqdn_from_endpoint("https://myns.servicebus.windows.net:443/"),
+            "myns.servicebus.windows.net"
+        );
+        assert_eq!(
+            extract_fqdn_from_endpoint("https://myns.servicebus.windows.net/"),
+            "myns.servicebus.windows.net"
+        );
+        assert_eq!(
+            extract_fqdn_from_endpoint("myns.servicebus.windows.net"),
+            "myns.servicebus.windows.net"
+        );
+    }
+
+    #[test]
+    fn list_pages_carry_their_next_link() {
+        let page: SubscriptionListResponse = serde_json::from_str(
+            r#"{"value":[{"subscriptionId":"s1","displayName":"Dev","state":"Enabled"}],
+                "nextLink":"https://management.azure.com/subscriptions?api-version=2020-01-01&$skiptoken=abc"}"#,
+        )
+        .unwrap();
+        assert_eq!(page.value.len(), 1);
+        assert!(page.next_link.unwrap().ends_with("$skiptoken=abc"));
+
+        let last: NamespaceListResponse = serde_json::from_str(r#"{"value":[]}"#).unwrap();
+        assert!(last.next_link.is_none());
+    }
+
+    #[test]
+    fn test_resource_group_from_id() {
+        assert_eq!(
+            resource_group_from_id(
+                "/subscriptions/abc/resourceGroups/rg-app/providers/Microsoft.ServiceBus/namespaces/myns"
+            ),
+            Some("rg-app".to_string())
+        );
+        assert_eq!(resource_group_from_id(""), None);
+    }
+
+    #[test]
+    fn test_parse_entity_metrics() {
+        let json = r#"{
+            "cost": 59,
+            "timespan": "2024-05-06T12:00:00Z/2024-05-06T13:00:00Z",
+            "interval": "PT1H",
+            "value": [
+                {
+                    "id": "/subscriptions/abc/.../metrics/IncomingMessages",
+                    "type": "Microsoft.Insights/metrics",
+                    "name": { "value": "IncomingMessages", "localizedValue": "Incoming Messages" },
+                    "unit": "Count",
+                    "timeseries": [
+                        {
+                            "metadatavalues": [
+                                { "name": { "value": "entityname" }, "value": "orders" }
+                            ],
+                            "data": [
+                                { "timeStamp": "2024-05-06T12:00:00Z", "total": 120.0 },
+                                { "timeStamp": "2024-05-06T12:30:00Z", "total": 5.0 }
+                            ]
+                        }
+                    ]
+                },
+                {
+                    "name": { "value": "OutgoingMessages", "localizedValue": "Outgoing Messages" },
+                    "unit": "Count",
+                    "timeseries": [
+                        { "data": [ { "timeStamp": "2024-05-06T12:00:00Z" } ] }
+                    ]
+                }
+            ],
+            "namespace": "Microsoft.ServiceBus/namespaces",
+            "resourceregion": "westeurope"
+        }"#;
+        let metrics = parse_entity_metrics(json).unwrap();
+        assert_eq!(metrics.incoming, Some(125.0));
+        // A time grain without a total means no data, not zero
+        assert_eq!(metrics.outgoing, None);
+
+        let empty = parse_entity_metrics(r#"{ "value": [] }"#).unwrap();
+        assert_eq!(empty, EntityMetrics::default());
+        assert!(parse_entity_metrics("not json").is_err());
+    }
+
+    #[test]
+    fn test_namespace_detail_from_arm() {
+        let json = r#"{
+            "id": "/subscriptions/abc/resourceGroups/rg/providers/Microsoft.ServiceBus/namespaces/myns",
+            "name": "myns",
+            "location": "westeurope",
+            "sku": { "name": "Premium", "tier": "Premium", "capacity": 2 },
+            "properties": {
+                "serviceBusEndpoint": "https://myns.servicebus.windows.net:443/",
+                "status": "Active",
+                "provisioningState": "Succeeded",
+                "createdAt": "2023-01-02T03:04:05Z",
+                "updatedAt": "2024-01-02T03:04:05Z"
+            }
+        }"#;
+        let ns: NamespaceResource = serde_json::from_str(json).unwrap();
+        let detail = ns.detail();
+        assert_eq!(detail.sku_name.as_deref(), Some("Premium"));
+        assert_eq!(detail.capacity, Some(2));
+        assert_eq!(detail.provisioning_state.as_deref(), Some("Succeeded"));
+        assert_eq!(detail.created_at.as_deref(), Some("2023-01-02T03:04:05Z"));
+    }
+
+    #[test]
+    fn identity_comes_from_the_token_claims() {
+        use base64::Engine;
+        let token = |claims: &str| {
+            let encode = |part: &str| {
+                base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(part.as_bytes())
+            };
+            format!("{}.{}.sig", encode(r#"{"alg":"RS256"}"#), encode(claims))
+        };
+
+        assert_eq!(
+            ArmIdentity::from_token(&token(r#"{"tid":"t1","upn":"ada@contoso.com","oid":"o1"}"#)),
+            Some(ArmIdentity {
+                tenant_id: "t1".into(),
+                account: "ada@contoso.com".into(),
+            })
+        );
+        assert_eq!(
+            ArmIdentity::from_token(&token(r#"{"tid":"t2","appid":"app-1"}"#))
+                .map(|identity| identity.account),
+            Some("app-1".to_string())
+        );
+        assert_eq!(ArmIdentity::from_token(&token(r#"{"upn":"ada"}"#)), None);
+        assert_eq!(ArmIdentity::from_token("not-a-jwt"), None);
+    }
+}
@@ -0,0 +1,150 @@
+//! Rolling latency and error counts of recent requests, shared by every
+//! clone of a [`ConnectionConfig`](crate::ConnectionConfig).
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Requests kept per API for the rolling figures.
+pub const STATS_WINDOW: usize = 50;
+
+/// Which API a request went to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Api {
+    /// ATOM listings and entity CRUD (`ManagementClient`).
+    Management,
+    /// Message operations (`DataPlaneClient`).
+    DataPlane,
+}
+
+impl Api {
+    /// Both APIs, in display order.
+    pub const ALL: [Api; 2] = [Api::Management, Api::DataPlane];
+
+    /// Lowercase name for display, e.g. `data plane`.
+    pub fn label(self) -> &'static str {
+        match self {
+            Api::Management => "management",
+            Api::DataPlane => "data plane",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Sample {
+    /// `None` for requests whose duration says nothing about the network,
+    /// such as receives that waited out their server-side timeout.
+    latency: Option<Duration>,
+    failed: bool,
+}
+
+/// Figures over the last [`STATS_WINDOW`] requests of one API (or both).
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct StatsSummary {
+    /// Requests in the window.
+    pub calls: usize,
+    /// Of those, the ones recorded as failed.
+    pub failed: usize,
+    /// Median latency; `None` when no request had a meaningful one.
+    pub p50: Option<Duration>,
+    /// 95th percentile latency.
+    pub p95: Option<Duration>,
+}
+
+impl StatsSummary {
+    /// Share of failed calls, 0.0–1.0.
+    pub fn error_rate(&self) -> f64 {
+        if self.calls == 0 {
+            0.0
+        } else {
+            self.failed as f64 / self.calls as f64
+        }
+    }
+}
+
+/// The last [`STATS_WINDOW`] requests of each API.
+#[derive(Debug, Default)]
+pub struct RequestStats {
+    samples: Mutex<[VecDeque<Sample>; 2]>,
+}
+
+impl RequestStats {
+    /// Record one finished request. `failed` means a transport error or a
+    /// response saying the service is struggling (5xx, 408, 429), not an
+    /// ordinary 4xx such as a missing entity.
+    pub fn record(&self, api: Api, latency: Option<Duration>, failed: bool) {
+        let mut samples = self.samples.lock().unwrap_or_else(|e| e.into_inner());
+        let window = &mut samples[api as usize];
+        if window.len() == STATS_WINDOW {
+            window.pop_front();
+        }
+        window.push_back(Sample { latency, failed });
+    }
+
+    /// Figures for one API.
+    pub fn summary(&self, api: Api) -> StatsSummary {
+        let samples = self.samples.lock().unwrap_or_else(|e| e.into_inner());
+        summarize(samples[api as usize].iter())
+    }
+
+    /// Both APIs together.
+    pub fn overall(&self) -> StatsSummary {
+        let samples = self.samples.lock().unwrap_or_else(|e| e.into_inner());
+        summarize(samples.iter().flatten())
+    }
+}
+
+fn summarize<'a>(samples: impl Iterator<Item = &'a Sample>) -> StatsSummary {
+    let mut summary = StatsSummary::default();
+    let mut latencies = Vec::new();
+    for sample in samples {
+        summary.calls += 1;
+        if sample.failed {
+            summary.failed += 1;
+        }
+        latencies.extend(sample.latency);
+    }
+    latencies.sort();
+    let percentile =
+        |p: usize| (!latencies.is_empty()).then(|| latencies[(latencies.len() - 1) * p / 100]);
+    summary.p50 = percentile(50);
+    summary.p95 = percentile(95);
+    summary
+}
+
+/// Whether a response status counts against the error rate.
+pub fn is_failure_status(status: u16) -> bool {
+    status >= 500 || status == 408 || status == 429
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summaries_roll_over_the_window() {
+        let stats = RequestStats::default();
+        for ms in [100, 300, 200] {
+            stats.record(Api::Management, Some(Duration::from_millis(ms)), false);
+        }
+        stats.record(Api::DataPlane, None, true);
+
+        let mgmt = stats.summary(Api::Management);
+        assert_eq!(mgmt.calls, 3);
+        assert_eq!(mgmt.p50, Some(Duration::from_millis(200)));
+        assert_eq!(mgmt.p95, Some(Duration::from_millis(200)));
+        assert_eq!(mgmt.error_rate(), 0.0);
+
+        let data = stats.summary(Api::DataPlane);
+        assert_eq!((data.calls, data.p50), (1, None));
+        assert_eq!(data.error_rate(), 1.0);
+        assert_eq!(stats.overall().calls, 4);
+
+        for _ in 0..STATS_WINDOW {
+            stats.record(Api::Management, Some(Duration::from_millis(5)), false);
+        }
+        let mgmt = stats.summary(Api::Management);
+        assert_eq!(mgmt.calls, STATS_WINDOW);
+        assert_eq!(mgmt.p95, Some(Duration::from_millis(5)));
+    }
+}
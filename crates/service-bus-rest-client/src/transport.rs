@@ -0,0 +1,158 @@
+//! The raw requests behind both clients.
+//!
+//! [`ManagementClient`](crate::ManagementClient) and
+//! [`DataPlaneClient`](crate::DataPlaneClient) reach the namespace only
+//! through the calls in [`Transport`]; ATOM parsing, patches, retries and
+//! concurrent peeks sit above it and run the same whichever transport is
+//! plugged in. [`HttpTransport`] sends the calls to Service Bus,
+//! `DemoNamespace` (`demo` feature) answers them from memory,
+//! and tests can supply their own.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+use reqwest::Client;
+
+use super::auth::ConnectionConfig;
+use super::error::Result;
+use super::models::{ReceivedMessage, ServiceBusMessage};
+
+/// A boxed future, so that `Transport` can be used as a trait object.
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// Where the clients' requests go. Paths are relative to the namespace;
+/// data-plane paths use the lower-case `/subscriptions/` segment.
+pub trait Transport: Send + Sync {
+    /// GET an ATOM entry or feed.
+    fn get_atom<'a>(&'a self, path: &'a str) -> BoxFuture<'a, Result<String>>;
+
+    /// PUT an ATOM entry and return the stored one. `if_match` turns the
+    /// create into an update of an existing entity.
+    fn put_atom<'a>(
+        &'a self,
+        path: &'a str,
+        body: &'a str,
+        if_match: bool,
+    ) -> BoxFuture<'a, Result<String>>;
+
+    /// DELETE an entity.
+    fn delete_entity<'a>(&'a self, path: &'a str) -> BoxFuture<'a, Result<()>>;
+
+    /// Round-trip time of a minimal management request.
+    fn ping(&self) -> BoxFuture<'_, Result<Duration>>;
+
+    /// Send one message to a queue or topic.
+    fn send_message<'a>(
+        &'a self,
+        entity_path: &'a str,
+        message: &'a ServiceBusMessage,
+    ) -> BoxFuture<'a, Result<()>>;
+
+    /// Send `messages` with as few requests as possible; a failure stops
+    /// the send and what was sent before it stays sent.
+    fn send_batch<'a>(
+        &'a self,
+        entity_path: &'a str,
+        messages: &'a [ServiceBusMessage],
+    ) -> BoxFuture<'a, Result<()>>;
+
+    /// `None` when the entity is empty.
+    fn receive_and_delete<'a>(
+        &'a self,
+        entity_path: &'a str,
+    ) -> BoxFuture<'a, Result<Option<ReceivedMessage>>>;
+
+    /// Lock the next message; its `lock_token_uri` settles it. `None` when
+    /// nothing arrives within `timeout_secs`.
+    fn peek_lock<'a>(
+        &'a self,
+        entity_path: &'a str,
+        timeout_secs: u32,
+    ) -> BoxFuture<'a, Result<Option<ReceivedMessage>>>;
+
+    /// Settle a locked message, removing it from the entity.
+    fn complete<'a>(&'a self, lock_token_uri: &'a str) -> BoxFuture<'a, Result<()>>;
+
+    /// Release a lock, making the message available again.
+    fn abandon<'a>(&'a self, lock_token_uri: &'a str) -> BoxFuture<'a, Result<()>>;
+}
+
+/// Sends the calls over HTTP to the namespace of its `ConnectionConfig`.
+/// The requests themselves live next to the client that makes them, in
+/// `management.rs` and `data_plane.rs`.
+#[derive(Clone)]
+pub struct HttpTransport {
+    pub(crate) config: ConnectionConfig,
+    pub(crate) http: Arc<Client>,
+}
+
+impl HttpTransport {
+    /// `http` can be shared with other transports to pool connections.
+    pub fn new(config: ConnectionConfig, http: Arc<Client>) -> Self {
+        Self { config, http }
+    }
+}
+
+impl Transport for HttpTransport {
+    fn get_atom<'a>(&'a self, path: &'a str) -> BoxFuture<'a, Result<String>> {
+        Box::pin(self.http_get_atom(path))
+    }
+
+    fn put_atom<'a>(
+        &'a self,
+        path: &'a str,
+        body: &'a str,
+        if_match: bool,
+    ) -> BoxFuture<'a, Result<String>> {
+        Box::pin(self.http_put_atom(path, body, if_match))
+    }
+
+    fn delete_entity<'a>(&'a self, path: &'a str) -> BoxFuture<'a, Result<()>> {
+        Box::pin(self.http_delete_entity(path))
+    }
+
+    fn ping(&self) -> BoxFuture<'_, Result<Duration>> {
+        Box::pin(self.http_ping())
+    }
+
+    fn send_message<'a>(
+        &'a self,
+        entity_path: &'a str,
+        message: &'a ServiceBusMessage,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(self.http_send_message(entity_path, message))
+    }
+
+    fn send_batch<'a>(
+        &'a self,
+        entity_path: &'a str,
+        messages: &'a [ServiceBusMessage],
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(self.http_send_batch(entity_path, messages))
+    }
+
+    fn receive_and_delete<'a>(
+        &'a self,
+        entity_path: &'a str,
+    ) -> BoxFuture<'a, Result<Option<ReceivedMessage>>> {
+        Box::pin(self.http_receive_and_delete(entity_path))
+    }
+
+    fn peek_lock<'a>(
+        &'a self,
+        entity_path: &'a str,
+        timeout_secs: u32,
+    ) -> BoxFuture<'a, Result<Option<ReceivedMessage>>> {
+        Box::pin(self.http_peek_lock(entity_path, timeout_secs))
+    }
+
+    fn complete<'a>(&'a self, lock_token_uri: &'a str) -> BoxFuture<'a, Result<()>> {
+        Box::pin(self.http_complete(lock_token_uri))
+    }
+
+    fn abandon<'a>(&'a self, lock_token_uri: &'a str) -> BoxFuture<'a, Result<()>> {
+        Box::pin(self.http_abandon(lock_token_uri))
+    }
+}
@@ -1,38 +1,150 @@
+use chrono::{DateTime, Utc};
+use ratatui::text::Line;
 use ratatui::widgets::{ListState, TableState};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
 
+use crate::body_format::{available_formats, detect_body_format, BodyFormat};
+use crate::bulk_ops::{DryRunReport, PurgeCandidate, ResendReport};
+use crate::client::auth::AuthMode;
+use crate::client::demo::DemoNamespace;
+use crate::client::entity_path;
+use crate::client::management;
 use crate::client::models::*;
-use crate::client::resource_manager::{DiscoveredNamespace, DiscoveryResult};
-use crate::client::{ConnectionConfig, DataPlaneClient, ManagementClient};
-use crate::config::AppConfig;
+use crate::client::resource_manager::{
+    ArmIdentity, DiscoveredNamespace, DiscoveryResult, EntityMetrics, NamespaceDetail,
+};
+use crate::client::stats::StatsSummary;
+use crate::client::{
+    ConnectionConfig, DataPlaneClient, HttpTransport, LockTimeouts, ManagementClient, Transport,
+};
+use crate::config::{
+    AppConfig, DiscoveryCacheEntry, MessageColumn, SavedConnection, SendDefaults, SettingField,
+};
+use crate::connection_test::{ConnectionTest, TestOutcome};
+use crate::delay::{self, DelaySpec};
+use crate::error_report::ErrorReport;
+use crate::event_log::LogEvent;
+use crate::forward_check::{self, ChainProblem};
+use crate::forward_graph::{self, ForwardEdge, ForwardKind, GraphLine};
+use crate::fuzzy::{fuzzy_filter, fuzzy_score};
+use crate::jwt::decode_jwt;
+use crate::message_diff::{comparable_body, diff_lines, DiffLine};
+use crate::message_filter::{FilterPreset, FilterScope, MessageFilter};
+use crate::peek_cache::{CachedTab, PeekCache};
+use crate::session::SessionState;
+use crate::ui::theme::Theme;
+
+/// Sender handed to background tasks. Each event is stamped with the
+/// connection generation the task was started under, so results that arrive
+/// after a disconnect or connection switch can be dropped by the main loop.
+#[derive(Clone)]
+pub struct BgSender {
+    tx: mpsc::UnboundedSender<(u64, BgEvent)>,
+    generation: u64,
+}
+
+impl BgSender {
+    /// Fails only when the main loop has gone away; the event is dropped.
+    pub fn send(&self, event: BgEvent) -> Result<(), mpsc::error::SendError<()>> {
+        self.tx
+            .send((self.generation, event))
+            .map_err(|_| mpsc::error::SendError(()))
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    fn next_generation(&self) -> Self {
+        Self {
+            tx: self.tx.clone(),
+            generation: self.generation + 1,
+        }
+    }
+}
 
 /// Events sent from background tasks back to the main loop.
 pub enum BgEvent {
     Progress(String),
+    /// Progress with a running count of processed messages (feeds the rate display).
+    JobProgress {
+        processed: u64,
+        message: String,
+    },
+    /// Per-path message counts, or why a path could not be purged.
     PurgeComplete {
-        count: u64,
+        results: Vec<(String, Result<u64, String>)>,
+    },
+    /// Subscriptions of a topic about to be purged, checked for problems.
+    PurgePreflightLoaded {
+        entity_path: String,
+        is_dlq: bool,
+        candidates: Result<Vec<PurgeCandidate>, String>,
     },
     ResendComplete {
         resent: u32,
         errors: u32,
+        /// Stopped early with Esc; `resent` and `errors` cover what ran.
+        cancelled: bool,
+        /// Enqueue window and destination scheduled count of a delayed resend.
+        scheduled: Option<String>,
+        /// Messages a bulk resend of peeked messages failed to send.
+        failures: Option<ResendReport>,
     },
     BulkDeleteComplete {
         deleted: u32,
         was_dlq: bool,
     },
+    /// A dry run finished without touching any message.
+    DryRunComplete(Box<DryRunReport>),
     Cancelled {
         message: String,
     },
     Failed(String),
+    /// A client error with structured HTTP status and response body.
+    OperationFailed(ErrorReport),
 
     // Non-blocking async operation results
     TreeRefreshed {
         tree: TreeNode,
         flat_nodes: Vec<FlatNode>,
     },
+    /// A topic's subscriptions and their counts, fetched once it became visible.
+    NodeCountsLoaded {
+        topic: String,
+        subscriptions: Result<Vec<(SubscriptionDescription, i64, i64)>, String>,
+    },
     DetailLoaded(Box<DetailView>),
+    /// Loading or peeking `path` answered 404: someone deleted it.
+    EntityGone {
+        path: String,
+    },
+    NamespaceInfoLoaded(NamespaceInfo),
+    /// ARM metrics of the queue or topic `entity`; failures are not reported.
+    EntityMetricsLoaded {
+        entity: String,
+        metrics: EntityMetrics,
+    },
+    /// Management API ping (`H` or `health_check_interval_secs`). `status` is
+    /// `HEALTH_OK` or why the namespace could not be reached.
+    HealthCheckResult {
+        rtt_ms: u64,
+        status: String,
+    },
+    /// One saved connection was tested from the connection list.
+    ConnectionTested {
+        name: String,
+        outcome: TestOutcome,
+    },
+    /// Testing saved connections ended, early if `cancelled`.
+    ConnectionTestsDone {
+        cancelled: bool,
+    },
     SubscriptionFilterLoaded {
         topic_name: String,
         sub_name: String,
@@ -40,31 +152,54 @@ pub enum BgEvent {
         sql_expression: String,
     },
     PeekComplete {
+        /// Entity the peek was started for (the topic, for a topic DLQ fan-out).
+        entity: String,
         messages: Vec<ReceivedMessage>,
-        is_dlq: bool,
+        tab: MessageTab,
+    },
+    /// Active and dead-lettered messages peeked side by side (`b`); either
+    /// half may have failed on its own.
+    PeekBothComplete {
+        entity: String,
+        active: Result<Vec<ReceivedMessage>, String>,
+        dead_lettered: Result<Vec<ReceivedMessage>, String>,
     },
     SendComplete {
         status: String,
+        defaults: SendDefaults,
     },
     EntityCreated {
         status: String,
     },
+    EntityUpdated {
+        status: String,
+    },
     EntityDeleted {
         status: String,
     },
     /// Inline/modal resend completed; optionally removed DLQ source.
     ResendSendComplete {
         status: String,
-        dlq_seq_removed: Option<i64>,
+        dlq_removed: Option<MessageRef>,
         was_inline: bool,
     },
-    /// Namespace discovery completed.
+    /// A single-message operation ended (successfully, with an error, or by
+    /// panicking); its in-flight marker can be cleared.
+    MessageOpFinished {
+        target: MessageRef,
+    },
+    /// Namespace discovery completed, as `identity` when the token said
+    /// who that is; `cached_minutes` is set when the result came from the
+    /// cache.
     NamespacesDiscovered {
+        identity: Option<ArmIdentity>,
         result: DiscoveryResult,
+        cached_minutes: Option<i64>,
     },
     /// Namespace discovery failed.
     DiscoveryFailed(String),
     DestinationEntitiesLoaded {
+        connection: String,
         entities: Vec<(String, EntityType)>,
     },
     MessageCopyComplete {
@@ -73,10 +208,16 @@ pub enum BgEvent {
     SubscriptionFilterUpdated {
         status: String,
     },
+    AuthorizationRulesLoaded {
+        entity_path: String,
+        rules: Vec<AuthorizationRule>,
+    },
+    SessionsBrowsed(Box<SessionBrowse>),
+    SearchComplete(Box<SearchResults>),
 }
 
 /// Which panel is currently focused.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum FocusPanel {
     Tree,
     Detail,
@@ -90,7 +231,18 @@ pub enum ActiveModal {
     ConnectionModeSelect,
     ConnectionInput,
     ConnectionList,
+    /// Group name typed into `input_buffer`: regroups the saved connection
+    /// `connection`, or (`None`) is kept for the connection about to be added.
+    ConnectionGroupInput {
+        connection: Option<String>,
+    },
     ConnectionSwitch,
+    /// New name for the saved connection `connection`, typed into `input_buffer`.
+    ConnectionRenameInput {
+        connection: String,
+    },
+    /// Confirm removing the saved connection (the active one) from the config.
+    ConfirmDeleteConnection(String),
     AzureAdNamespaceInput,
     NamespaceDiscovery {
         state: DiscoveryState,
@@ -100,29 +252,131 @@ pub enum ActiveModal {
     CreateTopic,
     CreateSubscription,
     EditSubscriptionFilter,
+    /// Edit properties of an existing queue/topic/subscription (`path` is the entity path).
+    EditEntity {
+        entity_type: EntityType,
+        path: String,
+    },
     ConfirmDelete(String),
+    /// `dry_run` (`t`) reports what would be resent instead of resending.
     ConfirmBulkResend {
         entity_path: String,
         count: u32,
         is_topic: bool,
+        dry_run: bool,
+    },
+    /// `d` in `ConfirmBulkResend`: delay (and spread) typed into `input_buffer`.
+    BulkResendDelayInput {
+        entity_path: String,
+        count: u32,
+        is_topic: bool,
+        dry_run: bool,
     },
     ConfirmBulkDelete {
         entity_path: String,
         count: u32,
         is_dlq: bool,
         is_topic: bool,
+        dry_run: bool,
+    },
+    /// `custom` is true once the user switched from presets to typing a count.
+    PeekCountInput {
+        custom: bool,
     },
-    PeekCountInput,
     EditResend,
     ClearOptions {
         entity_path: String,
         base_entity_path: String,
         is_topic: bool,
+        dry_run: bool,
+        /// Opened from a dead-letter node: only the DLQ options are offered.
+        dlq_only: bool,
+    },
+    /// What a dry run of a purge or resend found (`dry_run_report`).
+    DryRunSummary,
+    /// Messages a bulk resend failed on (`resend_report`); `r` retries them.
+    ResendReport,
+    /// Confirm which subscriptions of topic `entity_path` to purge
+    /// (`purge_candidates`); the active messages, or the DLQs if `is_dlq`.
+    PurgePreflight {
+        entity_path: String,
+        is_dlq: bool,
     },
     Help,
     CopySelectConnection,
     CopySelectEntity,
     CopyEditMessage,
+    AuthorizationRules {
+        entity_path: String,
+    },
+    /// Full error report; `previous` is restored on close so an open form
+    /// isn't lost when its submission fails.
+    ErrorDetail {
+        previous: Box<ActiveModal>,
+    },
+    ColumnSelector,
+    DlqStats,
+    /// Sessions found by the last session browse (`session_browse`).
+    Sessions,
+    /// MessageId / CorrelationId to search the namespace for, typed into `input_buffer`.
+    MessageSearchInput,
+    /// Matches of the last namespace search (`search_results`).
+    SearchResults,
+    /// Side-by-side comparison of the two `compare_marks`.
+    CompareMessages,
+    /// Set or clear auto-forwarding on the queue/subscription in the detail
+    /// panel; the target is typed into `input_buffer`.
+    ForwardToInput {
+        entity_type: EntityType,
+        path: String,
+    },
+    /// Quit requested while a background operation runs: quit anyway, cancel
+    /// it first, or keep running.
+    ConfirmQuit,
+    /// `Y` on an entity whose namespace's ARM location is known: copy its
+    /// path or its Azure Portal URL.
+    CopyFormat {
+        path: String,
+        portal_url: String,
+    },
+    /// A draft of the form of `kind` just opened exists: resume or discard it.
+    ResumeDraft {
+        kind: DraftKind,
+    },
+    /// `n` on a topic: create another topic or a subscription under this one.
+    CreateOnTopic {
+        topic: String,
+    },
+    /// Pick a new status for the entity in the detail panel.
+    StatusSelect {
+        entity_type: EntityType,
+        path: String,
+    },
+    /// Forwarding chains among the loaded entities (`forward_graph`).
+    ForwardGraph,
+    /// Latency and error rate of recent requests, per API (`L`).
+    ApiStats,
+    /// Saved message filters (`config.filter_presets`).
+    FilterPresets,
+    /// Confirm deleting the filter preset with this name.
+    ConfirmDeleteFilterPreset(String),
+    /// Filter expression typed into `input_buffer`, matched against `scope`;
+    /// edits the preset at `preset`, or applies a new filter when `None`.
+    MessageFilterInput {
+        scope: FilterScope,
+        preset: Option<usize>,
+    },
+    /// Name typed into `input_buffer`: renames the preset at `preset`, or
+    /// saves the active filter as a new one when `None`.
+    FilterPresetNameInput {
+        preset: Option<usize>,
+    },
+    /// A filter was applied to a tab that was never peeked: peek it now?
+    ConfirmFilterPeek,
+    /// Settings editor; `editing` while a value is being typed into `input_buffer`.
+    Settings {
+        editing: bool,
+    },
 }
 
 /// State of the namespace discovery modal.
@@ -138,24 +392,548 @@ pub enum DiscoveryState {
 pub enum DetailView {
     None,
     Queue(QueueDescription, Option<QueueRuntimeInfo>),
-    Topic(TopicDescription, Option<TopicRuntimeInfo>),
+    /// Topic description, runtime info and its subscriptions' rules (routing diagram).
+    Topic(
+        TopicDescription,
+        Option<TopicRuntimeInfo>,
+        Option<Vec<SubscriptionRoute>>,
+    ),
     Subscription(SubscriptionDescription, Option<SubscriptionRuntimeInfo>),
+    /// Namespace root: name, SAS policies (`None` if they could not be read)
+    /// and ARM details (only known for namespaces found via discovery).
+    Namespace(
+        String,
+        Option<Vec<AuthorizationRule>>,
+        Option<NamespaceDetail>,
+    ),
 }
 
-/// Tab for the message panel.
+impl DetailView {
+    /// Size against quota of the queue or topic shown, with its name.
+    pub fn size_usage(&self) -> Option<(String, SizeUsage)> {
+        let (name, usage) = match self {
+            DetailView::Queue(desc, Some(rt)) => (
+                &desc.name,
+                SizeUsage::new(rt.size_in_bytes, desc.max_size_in_megabytes),
+            ),
+            DetailView::Topic(desc, Some(rt), _) => (
+                &desc.name,
+                SizeUsage::new(rt.size_in_bytes, desc.max_size_in_megabytes),
+            ),
+            _ => return None,
+        };
+        Some((name.clone(), usage?))
+    }
+
+    /// Path and default message TTL of the queue, topic or subscription shown.
+    fn default_message_ttl(&self) -> Option<(String, Duration)> {
+        let (path, ttl) = match self {
+            DetailView::Queue(desc, _) => (desc.name.clone(), &desc.default_message_time_to_live),
+            DetailView::Topic(desc, _, _) => {
+                (desc.name.clone(), &desc.default_message_time_to_live)
+            }
+            DetailView::Subscription(desc, _) => (
+                format!("{}/Subscriptions/{}", desc.topic_name, desc.name),
+                &desc.default_message_time_to_live,
+            ),
+            _ => return None,
+        };
+        Some((path, parse_timespan(ttl.as_deref()?)?))
+    }
+
+    /// Tree path of the queue, topic or subscription shown.
+    pub fn entity_path(&self) -> Option<String> {
+        match self {
+            DetailView::Queue(desc, _) => Some(desc.name.clone()),
+            DetailView::Topic(desc, _, _) => Some(desc.name.clone()),
+            DetailView::Subscription(desc, _) => {
+                Some(format!("{}/Subscriptions/{}", desc.topic_name, desc.name))
+            }
+            _ => None,
+        }
+    }
+
+    /// Active and dead-letter counts of the entity shown, once runtime info is in.
+    fn runtime_counts(&self) -> Option<(i64, i64)> {
+        match self {
+            DetailView::Queue(_, Some(rt)) => {
+                Some((rt.active_message_count, rt.dead_letter_message_count))
+            }
+            DetailView::Topic(_, Some(rt), _) => {
+                Some((rt.active_message_count, rt.dead_letter_message_count))
+            }
+            DetailView::Subscription(_, Some(rt)) => {
+                Some((rt.active_message_count, rt.dead_letter_message_count))
+            }
+            _ => None,
+        }
+    }
+
+    /// Status, session, partitioning and forwarding flags of the entity
+    /// shown, for the tree footer.
+    fn summary_flags(&self) -> Option<Vec<String>> {
+        let (status, sessions, partitioned, forward_to) = match self {
+            DetailView::Queue(desc, _) => (
+                &desc.status,
+                desc.requires_session,
+                desc.enable_partitioning,
+                forward_label(&desc.forward_to),
+            ),
+            DetailView::Topic(desc, _, _) => (&desc.status, None, desc.enable_partitioning, None),
+            DetailView::Subscription(desc, _) => (
+                &desc.status,
+                desc.requires_session,
+                None,
+                forward_label(&desc.forward_to),
+            ),
+            _ => return None,
+        };
+        let mut parts: Vec<String> = status.iter().cloned().collect();
+        if sessions == Some(true) {
+            parts.push("sessions".to_string());
+        }
+        if partitioned == Some(true) {
+            parts.push("partitioned".to_string());
+        }
+        if let Some(to) = forward_to {
+            parts.push(format!("fwd→{}", to));
+        }
+        Some(parts)
+    }
+
+    /// Forwarding targets of the queue or subscription shown (entity paths).
+    fn forward_edges(&self) -> Option<(String, Vec<(String, ForwardKind)>)> {
+        let (path, forward_to, forward_dlq_to) = match self {
+            DetailView::Queue(desc, _) => (
+                desc.name.clone(),
+                &desc.forward_to,
+                &desc.forward_dead_lettered_messages_to,
+            ),
+            DetailView::Subscription(desc, _) => (
+                format!("{}/subscriptions/{}", desc.topic_name, desc.name),
+                &desc.forward_to,
+                &desc.forward_dead_lettered_messages_to,
+            ),
+            _ => return None,
+        };
+        let targets = [
+            (forward_label(forward_to), ForwardKind::Messages),
+            (forward_label(forward_dlq_to), ForwardKind::DeadLetters),
+        ]
+        .into_iter()
+        .filter_map(|(to, kind)| to.map(|to| (to, kind)))
+        .collect();
+        Some((path, targets))
+    }
+}
+
+/// Window over which `BgJob::rate` averages progress reports.
+const JOB_RATE_WINDOW: Duration = Duration::from_secs(10);
+
+/// The background operation currently holding `bg_running`, for the status bar.
+#[derive(Debug, Clone)]
+pub struct BgJob {
+    pub operation: String,
+    pub entity_path: Option<String>,
+    pub started: Instant,
+    /// (time, processed) progress samples within `JOB_RATE_WINDOW`.
+    samples: VecDeque<(Instant, u64)>,
+}
+
+impl BgJob {
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    fn record(&mut self, processed: u64) {
+        let now = Instant::now();
+        // Counts restart when an operation moves on to the next path
+        if self
+            .samples
+            .back()
+            .is_some_and(|(_, last)| processed < *last)
+        {
+            self.samples.clear();
+        }
+        self.samples.push_back((now, processed));
+        while self
+            .samples
+            .front()
+            .is_some_and(|(t, _)| now.duration_since(*t) > JOB_RATE_WINDOW)
+        {
+            self.samples.pop_front();
+        }
+    }
+
+    /// Latest processed count the operation reported.
+    pub fn processed(&self) -> Option<u64> {
+        self.samples.back().map(|(_, n)| *n)
+    }
+
+    /// Messages per second over the sliding window, once there are two samples.
+    pub fn rate(&self) -> Option<f64> {
+        let (first_at, first) = self.samples.front()?;
+        let (last_at, last) = self.samples.back()?;
+        let secs = last_at.duration_since(*first_at).as_secs_f64();
+        (secs > 0.0).then(|| (last - first) as f64 / secs)
+    }
+}
+
+/// How long "cancel and quit" waits for background tasks to wind down.
+const QUIT_CANCEL_GRACE: Duration = Duration::from_secs(3);
+
+/// Quiet period after the last config change before it is written, so a
+/// burst of changes (e.g. toggling several columns) is saved once.
+const CONFIG_SAVE_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// How long newly alerting tree nodes flash after a refresh.
+const ALERT_FLASH: Duration = Duration::from_secs(6);
+
+/// `HealthCheck::status` of a namespace that answered.
+pub const HEALTH_OK: &str = "reachable";
+
+/// The last namespace health check of this connection.
+#[derive(Debug, Clone)]
+pub struct HealthCheck {
+    pub rtt_ms: u64,
+    pub status: String,
+}
+
+impl HealthCheck {
+    pub fn is_ok(&self) -> bool {
+        self.status == HEALTH_OK
+    }
+}
+
+/// How the recent requests of a connection are going (the header dot).
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApiHealth {
+    Good,
+    Slow,
+    Poor,
+}
+
+impl ApiHealth {
+    /// Judged on median latency and error rate of the rolling window.
+    pub fn of(summary: &StatsSummary) -> Self {
+        let p50_ms = summary.p50.map_or(0, |d| d.as_millis());
+        let error_rate = summary.error_rate();
+        if p50_ms < 500 && error_rate < 0.05 {
+            ApiHealth::Good
+        } else if p50_ms < 2000 && error_rate < 0.25 {
+            ApiHealth::Slow
+        } else {
+            ApiHealth::Poor
+        }
+    }
+}
+
+/// An entity whose DLQ count is at or above its configured threshold.
+#[derive(Debug, Clone)]
+pub struct DlqAlert {
+    pub path: String,
+    pub count: i64,
+    pub threshold: i64,
+}
+
+/// Label used for dead-lettered messages without a `DeadLetterReason`.
+pub const NO_DLQ_REASON: &str = "(no reason)";
+
+/// Lines of a selected property's value shown at once; PgUp/PgDn scroll
+/// by as many.
+pub const PROPERTY_VALUE_LINES: usize = 8;
+
+/// Label of the edit form's optional resend delay field.
+pub const DELAY_FIELD: &str = "Resend Delay (10m, 1h30m)";
+
+/// Labels of the message form fields remembered per entity as send defaults.
+pub const CONTENT_TYPE_FIELD: &str = "Content-Type";
+pub const SESSION_ID_FIELD: &str = "Session ID";
+pub const LABEL_FIELD: &str = "Label";
+pub const CUSTOM_PROPERTIES_FIELD: &str = "Custom Properties (k=v,...)";
+
+/// Offered only for session-enabled entities (and messages that carry one).
+pub const REPLY_TO_SESSION_FIELD: &str = "Reply To Session ID";
+pub const VIA_PARTITION_KEY_FIELD: &str = "Via Partition Key";
+
+/// Edit-form fields Service Bus does not let an existing entity change; they
+/// are shown for reference and skipped when moving between fields.
+const IMMUTABLE_ENTITY_FIELDS: &[&str] = &[
+    "Queue Name",
+    "Topic Name",
+    "Topic",
+    "Subscription Name",
+    "Requires Session",
+    "Enable Partitioning",
+];
+
+/// Breakdown of a peeked DLQ by dead-letter reason and source, most common first.
+#[derive(Debug, Clone, Default)]
+pub struct DlqStats {
+    pub total: usize,
+    pub reasons: Vec<(String, usize)>,
+    pub sources: Vec<(String, usize)>,
+}
+
+impl DlqStats {
+    pub fn from_messages(messages: &[ReceivedMessage]) -> Self {
+        let mut reasons: HashMap<String, usize> = HashMap::new();
+        let mut sources: HashMap<String, usize> = HashMap::new();
+        for msg in messages {
+            *reasons
+                .entry(dlq_reason_label(msg).to_string())
+                .or_default() += 1;
+            if let Some(ref source) = msg.broker_properties.dead_letter_source {
+                *sources.entry(source.clone()).or_default() += 1;
+            }
+        }
+        Self {
+            total: messages.len(),
+            reasons: sorted_by_count(reasons),
+            sources: sorted_by_count(sources),
+        }
+    }
+
+    pub fn percent(&self, count: usize) -> f64 {
+        if self.total == 0 {
+            0.0
+        } else {
+            count as f64 * 100.0 / self.total as f64
+        }
+    }
+}
+
+/// A message found by the namespace-wide search.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchHit {
+    pub entity_path: String,
+    pub is_dlq: bool,
+    pub sequence_number: Option<i64>,
+    pub message_id: Option<String>,
+    pub correlation_id: Option<String>,
+}
+
+impl SearchHit {
+    /// `Some` if the message's MessageId or CorrelationId equals `query`.
+    pub fn from_match(
+        entity_path: &str,
+        is_dlq: bool,
+        msg: &ReceivedMessage,
+        query: &str,
+    ) -> Option<Self> {
+        let props = &msg.broker_properties;
+        let matched = props.message_id.as_deref() == Some(query)
+            || props.correlation_id.as_deref() == Some(query);
+        matched.then(|| Self {
+            entity_path: entity_path.to_string(),
+            is_dlq,
+            sequence_number: props.sequence_number,
+            message_id: props.message_id.clone(),
+            correlation_id: props.correlation_id.clone(),
+        })
+    }
+}
+
+/// Outcome of a namespace search, shown in the results modal.
+#[derive(Debug, Clone, Default)]
+pub struct SearchResults {
+    pub query: String,
+    pub hits: Vec<SearchHit>,
+    pub searched: usize,
+    pub total: usize,
+    /// Entities that could not be peeked (e.g. session-enabled ones).
+    pub failed: usize,
+    pub cancelled: bool,
+}
+
+/// Most sessions a session browse collects before stopping.
+pub const MAX_BROWSED_SESSIONS: usize = 200;
+
+/// Topics listed one after another by each lazy count-loading task.
+pub const COUNT_BATCH_SIZE: usize = 4;
+
+/// Sessions found by browsing a session-enabled entity.
+#[derive(Debug, Clone, Default)]
+pub struct SessionBrowse {
+    pub entity_path: String,
+    pub messages: Vec<ReceivedMessage>,
+    /// (session id, messages seen), deepest first.
+    pub sessions: Vec<(String, usize)>,
+    /// Browsing stopped at a limit or was cancelled, so counts are lower bounds.
+    pub truncated: bool,
+}
+
+impl SessionBrowse {
+    pub fn new(entity_path: String, messages: Vec<ReceivedMessage>, truncated: bool) -> Self {
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for msg in &messages {
+            if let Some(ref id) = msg.broker_properties.session_id {
+                *counts.entry(id.clone()).or_default() += 1;
+            }
+        }
+        Self {
+            entity_path,
+            messages,
+            sessions: sorted_by_count(counts),
+            truncated,
+        }
+    }
+}
+
+fn sorted_by_count(counts: HashMap<String, usize>) -> Vec<(String, usize)> {
+    let mut sorted: Vec<(String, usize)> = counts.into_iter().collect();
+    sorted.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    sorted
+}
+
+/// One row of the grouped DLQ view: messages sharing a dead-letter reason.
+pub struct DlqGroup<'a> {
+    pub reason: String,
+    pub count: usize,
+    pub first: &'a ReceivedMessage,
+}
+
+pub fn dlq_reason_label(msg: &ReceivedMessage) -> &str {
+    msg.broker_properties
+        .dead_letter_reason
+        .as_deref()
+        .filter(|r| !r.is_empty())
+        .unwrap_or(NO_DLQ_REASON)
+}
+
+/// Custom properties in name order, ignoring case.
+pub fn sorted_properties(props: &[(String, String)]) -> Vec<&(String, String)> {
+    let mut sorted: Vec<&(String, String)> = props.iter().collect();
+    sorted.sort_by(|(a, _), (b, _)| {
+        a.to_lowercase()
+            .cmp(&b.to_lowercase())
+            .then_with(|| a.cmp(b))
+    });
+    sorted
+}
+
+/// "Peeked 40 active / 12 DLQ", with the error in place of a count for a
+/// half that failed.
+pub fn peek_both_summary(
+    active: &Result<Vec<ReceivedMessage>, String>,
+    dead_lettered: &Result<Vec<ReceivedMessage>, String>,
+) -> String {
+    let half = |result: &Result<Vec<ReceivedMessage>, String>, kind: &str| match result {
+        Ok(messages) => format!("{} {}", messages.len(), kind),
+        Err(e) => format!("{} failed: {}", kind, e),
+    };
+    format!(
+        "Peeked {} / {}",
+        half(active, "active"),
+        half(dead_lettered, "DLQ")
+    )
+}
+
+/// Tab for the message panel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum MessageTab {
     Messages,
     DeadLetter,
+    /// `$transfer/$deadletterqueue`: messages that failed to auto-forward.
+    Transferred,
+}
+
+/// Client configuration for a saved connection. Azure AD connections get a
+/// fresh default credential.
+pub fn saved_connection_config(conn: &SavedConnection) -> Result<ConnectionConfig, String> {
+    if conn.is_azure_ad() {
+        let ns = conn
+            .namespace
+            .as_ref()
+            .ok_or_else(|| "No namespace configured for Azure AD connection".to_string())?;
+        let cred = azure_identity::DefaultAzureCredential::new()
+            .map_err(|e| format!("Azure AD credential error: {}", e))?;
+        Ok(ConnectionConfig::from_azure_ad(ns, cred))
+    } else if let Some(ref cs) = conn.connection_string {
+        ConnectionConfig::from_connection_string(cs)
+            .map_err(|e| format!("Connection string parse error: {}", e))
+    } else {
+        Err("No connection string configured".to_string())
+    }
+}
+
+/// Message form whose unsent contents are kept as a draft when it is closed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DraftKind {
+    /// The send-message form.
+    Send,
+    /// Inline edit & resend of a peeked message.
+    Edit,
+}
+
+/// Field values of a closed, unsent form and what it was open for.
+#[derive(Debug, Clone)]
+pub struct FormDraft {
+    pub connection: Option<String>,
+    pub entity: String,
+    /// Message ID of the message being edited (`Edit` drafts only).
+    pub message_id: Option<String>,
+    pub fields: Vec<(String, String)>,
+}
+
+/// When a message tab was peeked; `cached` once it has been restored from
+/// the peek cache.
+#[derive(Debug, Clone, Copy)]
+pub struct PeekStamp {
+    pub at: Instant,
+    pub cached: bool,
+}
+
+/// `95s` → `1m`, rounded down to the largest whole unit.
+pub fn short_age(age: Duration) -> String {
+    let secs = age.as_secs();
+    match secs {
+        0..=59 => format!("{}s", secs),
+        60..=3599 => format!("{}m", secs / 60),
+        _ => format!("{}h", secs / 3600),
+    }
 }
 
 /// Central application state.
 pub struct App {
     pub running: bool,
     pub config: AppConfig,
+    /// Why the config file could not be loaded (defaults are in use).
+    pub config_load_error: Option<String>,
+    /// `--demo`: connected to an in-memory namespace; the config and session
+    /// are never written.
+    pub demo: bool,
+    /// Time of the last config change not yet handed to a save task.
+    config_save_at: Option<Instant>,
     pub connection_name: Option<String>,
+    /// Tier and name of the connected namespace, fetched once on connect.
+    pub namespace_info: Option<NamespaceInfo>,
+    /// Last-hour message totals of a queue or topic (by name), from ARM
+    /// metrics; only fetched for namespaces found via discovery.
+    pub entity_metrics: Option<(String, EntityMetrics)>,
+    pub health_check: Option<HealthCheck>,
+    pub health_check_in_flight: bool,
+    /// The running check was started with `H` and reports even when healthy.
+    pub health_check_manual: bool,
+    /// Group chosen in the new-connection flow, applied once it is saved.
+    pub pending_connection_group: Option<String>,
+    /// Saved connection whose credentials are being re-entered after a 401;
+    /// a connection string typed in the connect flow replaces its own.
+    pub reauth_connection: Option<String>,
+    /// Session from the last exit, held until the tree has loaded and its
+    /// selection can be restored.
+    pub session_restore: Option<SessionState>,
+    /// Last test of each saved connection (by name) this session.
+    pub connection_tests: HashMap<String, ConnectionTest>,
+    /// Saved connections whose test is still running.
+    pub connections_testing: HashSet<String>,
+    /// The connection list shows only connections whose last test failed.
+    pub connection_failures_only: bool,
 
     // Clients
+    /// HTTP client (and connection pool) shared by every management and
+    /// data-plane client the app creates.
+    pub http: Arc<reqwest::Client>,
     pub management: Option<ManagementClient>,
     pub data_plane: Option<DataPlaneClient>,
     pub connection_config: Option<ConnectionConfig>,
@@ -164,23 +942,84 @@ pub struct App {
     pub tree: Option<TreeNode>,
     pub flat_nodes: Vec<FlatNode>,
     pub tree_selected: usize,
+    /// Expanded flag per node ID, carried across refreshes so manual
+    /// collapses survive the tree being rebuilt.
+    pub expand_state: HashMap<String, bool>,
+    /// Rows the tree panel showed at the last draw; bounds which topics get
+    /// their subscriptions and counts loaded.
+    pub tree_viewport_height: usize,
+    /// Topics whose subscription listing is being fetched.
+    pub counts_in_flight: HashSet<String>,
+    /// Entities that answered 404 since the tree was last loaded; struck
+    /// through in the tree until a refresh drops them.
+    pub stale_entities: HashSet<String>,
 
     // Detail
     pub detail_view: DetailView,
+    /// Entities over their DLQ threshold, worst (largest DLQ) first.
+    pub dlq_alerts: Vec<DlqAlert>,
+    alert_flash_until: Option<Instant>,
+    /// Highlighted subscription in the topic routing diagram (`R` jumps to it).
+    pub route_selected: usize,
+    /// Show the whole `UserMetadata` value in the detail panel (`U`), not one line.
+    pub user_metadata_expanded: bool,
 
     // Messages
     pub message_tab: MessageTab,
     pub messages: Vec<ReceivedMessage>,
     pub dlq_messages: Vec<ReceivedMessage>,
+    pub transferred_messages: Vec<ReceivedMessage>,
+    /// Entity the message lists above were peeked from.
+    pub messages_entity: Option<String>,
+    /// When each tab of the message panel was peeked, and whether it came
+    /// back from `peek_cache`.
+    pub tab_peeks: HashMap<MessageTab, PeekStamp>,
+    /// Peek results of entities that are not shown right now.
+    pub peek_cache: PeekCache,
     pub message_selected: usize,
+    /// When set, the DLQ tab only lists messages with this dead-letter reason.
+    pub dlq_reason_filter: Option<String>,
+    /// Quick filter over every tab (`F`); kept across entities.
+    pub message_filter: Option<MessageFilter>,
+    /// Index into `config.filter_presets` in the preset picker.
+    pub filter_preset_selected: usize,
+    /// Active messages are ordered by expiry, soonest first (`O` toggles).
+    pub sort_by_expiry: bool,
+    pub dlq_stats_selected: usize,
+    /// DLQ tab shows one row per dead-letter reason; Enter expands a group
+    /// (via `dlq_reason_filter`), Esc returns to the summary.
+    pub dlq_group_view: bool,
+    pub dlq_group_selected: usize,
+
+    /// Details loaded this session, by entity path; feeds the forwarding graph.
+    pub detail_cache: HashMap<String, DetailView>,
+    /// Last known size against quota of queues and topics, by name.
+    pub entity_sizes: HashMap<String, SizeUsage>,
+    /// Entities the user chose to send to despite a nearly-full warning.
+    pub quota_acknowledged: HashSet<String>,
+    /// `source→target` forwarding chains the user chose to save despite a
+    /// loop or missing entity.
+    pub forward_acknowledged: HashSet<String>,
+    /// Rows of the forwarding graph modal (`ForwardGraph`).
+    pub forward_graph: Vec<GraphLine>,
+    pub forward_graph_selected: usize,
     pub selected_message_detail: Option<ReceivedMessage>,
+    /// j/k walk the open message's custom properties instead of scrolling
+    /// the body, and the selected value is shown in full (`v`).
+    pub properties_focus: bool,
+    pub property_selected: usize,
+    /// First line shown of the selected property's value (PgUp/PgDn);
+    /// clamped when drawn.
+    pub property_value_scroll: u16,
     pub detail_editing: bool,
-    /// If the message being edited came from DLQ, this holds its sequence number
-    /// so we can remove it after successful resend.
-    pub edit_source_dlq_seq: Option<i64>,
+    /// If the message being edited came from DLQ, this identifies it so we can
+    /// remove it after successful resend.
+    pub edit_source_dlq_ref: Option<MessageRef>,
 
     // UI state
     pub focus: FocusPanel,
+    /// Vim-style count typed before a tree or message key (`10j`).
+    pub pending_count: Option<usize>,
     pub modal: ActiveModal,
     pub status_message: String,
     pub status_is_error: bool,
@@ -191,22 +1030,66 @@ pub struct App {
     pub input_fields: Vec<(String, String)>, // (label, value) for multi-field forms
     pub input_field_index: usize,
     pub form_cursor: usize, // cursor position within the active form field
-    pub body_scroll: u16,   // vertical scroll offset for body editor
+    /// Body validation/format error shown in the form's hint line until the next edit.
+    pub form_error: Option<String>,
+    /// The open form was submitted and its request is still in flight;
+    /// further submits are ignored until it completes or fails.
+    pub form_submitting: bool,
+    /// Field values the open message form started with, to tell edits apart.
+    pub form_initial: Vec<String>,
+    /// Esc was pressed once on a form with an edited body.
+    pub form_discard_armed: bool,
+    /// Delay applied to every message of the next bulk DLQ resend.
+    pub bulk_resend_delay: Option<DelaySpec>,
+    /// Last closed, unsent form per kind, offered again on reopen.
+    pub form_drafts: HashMap<DraftKind, FormDraft>,
+    pub body_scroll: u16, // vertical scroll offset for body editor
 
     // Pending peek count from the peek-count input modal
     pub pending_peek_count: Option<i32>,
     pub peek_dlq: bool,
+    /// The pending peek fills both the Messages and Dead-letter tabs (`b`).
+    pub peek_both: bool,
+    /// Explanation shown when the requested peek count was clamped to `peek_max`.
+    pub peek_clamp_note: Option<String>,
 
     // Namespace discovery state
     pub discovered_namespaces: Vec<DiscoveredNamespace>,
     pub discovery_warnings: Vec<String>,
+    /// Age in minutes of the discovery shown, when it came from the cache.
+    pub discovery_cached_minutes: Option<i64>,
+    /// The running discovery was asked for with `Ctrl+R` and must not be
+    /// answered from the cache.
+    pub discovery_skip_cache: bool,
     pub namespace_list_state: usize,
+    /// Typed filter over the discovered namespaces.
+    pub namespace_search: String,
 
     // Background task channel for long-running operations
-    pub bg_tx: mpsc::UnboundedSender<BgEvent>,
-    pub bg_rx: mpsc::UnboundedReceiver<BgEvent>,
+    pub bg_tx: BgSender,
+    pub bg_rx: mpsc::UnboundedReceiver<(u64, BgEvent)>,
     pub bg_running: bool,
+    pub bg_job: Option<BgJob>,
+    /// Messages expected to be deleted by the running purge (from the detail
+    /// panel's runtime counts). `Some` shows the status-bar gauge.
+    pub purge_total: Option<u64>,
+    pub purge_done: u64,
+    /// Subscription paths of a topic purge awaiting confirmation (`PurgePreflight`).
+    pub purge_candidates: Vec<PurgeCandidate>,
+    pub purge_candidate_selected: usize,
+    /// Paths confirmed in `PurgePreflight`, taken by the purge when it starts.
+    pub purge_paths: Option<Vec<String>>,
+    /// Result of the last dry run, shown in `DryRunSummary`.
+    pub dry_run_report: Option<DryRunReport>,
+    /// Failures of the last bulk resend, shown in `ResendReport`. Kept after
+    /// the modal closes so `Ctrl+E` can show it again.
+    pub resend_report: Option<ResendReport>,
+    pub resend_report_scroll: u16,
     pub bg_cancel: Arc<AtomicBool>,
+    /// Every token handed out that may still belong to a running task.
+    bg_cancel_tokens: Vec<Arc<AtomicBool>>,
+    /// Set by "cancel and quit": exit once the operation stops or this passes.
+    pub quit_deadline: Option<Instant>,
 
     // Loading indicator
     pub loading: bool,
@@ -216,6 +1099,20 @@ pub struct App {
     pub message_table_state: TableState,
     /// Scroll offset for the read-only message body detail view.
     pub detail_body_scroll: u16,
+    /// Hex + ASCII lines of the open message's body, built on first draw
+    /// and dropped when another message is opened.
+    pub detail_hex_dump: Option<Vec<Line<'static>>>,
+    /// Wrap the body in the message detail view; when off, `h`/`l` scroll sideways.
+    pub body_wrap: bool,
+    pub body_scroll_horizontal: u16,
+    /// Format picked with `M` for the open message body; `None` = detected.
+    pub body_view_format: Option<BodyFormat>,
+
+    pub theme: Theme,
+    /// `--no-color` was passed; keeps the monochrome theme whatever the setting says.
+    pub no_color_flag: bool,
+    /// Structured stderr event log (`--log-format json`), when enabled.
+    pub event_log: Option<mpsc::UnboundedSender<LogEvent>>,
 
     // Copy operation state
     pub copy_source_message: Option<ReceivedMessage>,
@@ -223,35 +1120,146 @@ pub struct App {
     pub copy_dest_connection_name: Option<String>,
     pub copy_dest_connection_config: Option<ConnectionConfig>,
     pub copy_dest_entities: Vec<(String, EntityType)>,
+    /// Type-to-filter query of the destination entity picker.
+    pub copy_entity_filter: String,
+    /// Index into `copy_filtered_entities()`.
     pub copy_entity_selected: usize,
     pub copy_connection_list_state: ListState,
     pub copy_entity_list_state: ListState,
     pub copy_destination_entity: Option<String>,
+    /// Queue/topic names per saved connection name, shared by every destination
+    /// picker. Filled from the tree on connect and by destination lookups.
+    pub entity_cache: HashMap<String, Vec<(String, EntityType)>>,
+
+    /// SAS policies shown in the read-only authorization rules modal.
+    pub authorization_rules: Vec<AuthorizationRule>,
+
+    /// Full failure shown in the error modal, and its scroll offset.
+    pub error_report: Option<ErrorReport>,
+    pub error_scroll: u16,
+
+    /// Working copy for the column selector: every column with its enabled flag,
+    /// in display order.
+    pub column_editor: Vec<(MessageColumn, bool)>,
+    pub column_editor_selected: usize,
+
+    /// Highlighted option of the status picker (index into `status_options`).
+    pub status_selected: usize,
+
+    pub session_browse: Option<SessionBrowse>,
+    pub session_selected: usize,
+
+    pub search_results: Option<SearchResults>,
+    pub search_selected: usize,
+
+    /// Messages marked for comparison (at most two), with the tab each came from.
+    pub compare_marks: Vec<(ReceivedMessage, MessageTab)>,
+    pub compare_scroll: u16,
+    /// Body diff of the two marks, computed when the compare modal opens.
+    pub compare_body_diff: Vec<DiffLine>,
+    /// Tab shown before a dead-letter node switched to the DLQ tab; restored
+    /// once the selection leaves the node.
+    pub tab_before_dead_letter: Option<MessageTab>,
+
+    /// Messages with a single-message operation (e.g. DLQ removal after a
+    /// resend) still running. Further operations on them are refused.
+    pub message_ops_in_flight: Vec<MessageRef>,
+
+    /// Highlighted row of the settings editor (index into `SettingField::ALL`).
+    pub settings_selected: usize,
+    /// Why the last value typed into the settings editor was rejected.
+    pub settings_error: Option<String>,
+
+    /// Fields that changed on the last reload of the selected entity, and when.
+    pub detail_changes: Vec<FieldChange>,
+    pub detail_changes_at: Option<Instant>,
+}
+
+/// How long the "changed fields" highlight stays in the detail panel.
+const DETAIL_CHANGES_TTL: Duration = Duration::from_secs(10);
+
+/// Statuses the management API accepts for an entity type. Topics have no
+/// receive side, so they cannot be `ReceiveDisabled`.
+pub fn status_options(entity_type: &EntityType) -> &'static [&'static str] {
+    match entity_type {
+        EntityType::Topic => &["Active", "Disabled", "SendDisabled"],
+        _ => &["Active", "Disabled", "ReceiveDisabled", "SendDisabled"],
+    }
 }
 
 impl App {
     pub fn new() -> Self {
-        let config = AppConfig::load();
+        let (config, config_load_error) = AppConfig::load();
+        Self::with_config(config, config_load_error)
+    }
+
+    /// App state around an already loaded config.
+    pub fn with_config(config: AppConfig, config_load_error: Option<String>) -> Self {
+        let body_wrap = config.settings.body_wrap;
         let (bg_tx, bg_rx) = mpsc::unbounded_channel();
         Self {
             running: true,
             config,
+            config_load_error,
+            demo: false,
+            config_save_at: None,
             connection_name: None,
+            namespace_info: None,
+            entity_metrics: None,
+            health_check: None,
+            health_check_in_flight: false,
+            health_check_manual: false,
+            pending_connection_group: None,
+            reauth_connection: None,
+            session_restore: None,
+            connection_tests: HashMap::new(),
+            connections_testing: HashSet::new(),
+            connection_failures_only: false,
+            http: Arc::new(reqwest::Client::new()),
             management: None,
             data_plane: None,
             connection_config: None,
             tree: None,
             flat_nodes: Vec::new(),
             tree_selected: 0,
+            expand_state: HashMap::new(),
+            tree_viewport_height: 0,
+            counts_in_flight: HashSet::new(),
+            stale_entities: HashSet::new(),
             detail_view: DetailView::None,
+            dlq_alerts: Vec::new(),
+            alert_flash_until: None,
+            route_selected: 0,
+            user_metadata_expanded: false,
             message_tab: MessageTab::Messages,
             messages: Vec::new(),
             dlq_messages: Vec::new(),
+            transferred_messages: Vec::new(),
+            messages_entity: None,
+            tab_peeks: HashMap::new(),
+            peek_cache: PeekCache::default(),
             message_selected: 0,
+            dlq_reason_filter: None,
+            message_filter: None,
+            filter_preset_selected: 0,
+            sort_by_expiry: false,
+            dlq_stats_selected: 0,
+            dlq_group_view: false,
+            dlq_group_selected: 0,
+            detail_cache: HashMap::new(),
+            entity_sizes: HashMap::new(),
+            quota_acknowledged: HashSet::new(),
+            forward_acknowledged: HashSet::new(),
+            forward_graph: Vec::new(),
+            forward_graph_selected: 0,
             selected_message_detail: None,
+            properties_focus: false,
+            property_selected: 0,
+            property_value_scroll: 0,
             detail_editing: false,
-            edit_source_dlq_seq: None,
+            edit_source_dlq_ref: None,
             focus: FocusPanel::Tree,
+            pending_count: None,
             modal: ActiveModal::None,
             status_message: String::from("Press 'c' to connect, '?' for help"),
             status_is_error: false,
@@ -260,29 +1268,82 @@ impl App {
             input_fields: Vec::new(),
             input_field_index: 0,
             form_cursor: 0,
+            form_error: None,
+            form_submitting: false,
+            form_initial: Vec::new(),
+            form_discard_armed: false,
+            form_drafts: HashMap::new(),
+            bulk_resend_delay: None,
             body_scroll: 0,
             pending_peek_count: None,
             peek_dlq: false,
+            peek_both: false,
+            peek_clamp_note: None,
             discovered_namespaces: Vec::new(),
             discovery_warnings: Vec::new(),
+            discovery_cached_minutes: None,
+            discovery_skip_cache: false,
             namespace_list_state: 0,
-            bg_tx,
+            namespace_search: String::new(),
+            bg_tx: BgSender {
+                tx: bg_tx,
+                generation: 0,
+            },
             bg_rx,
             bg_running: false,
+            bg_job: None,
+            purge_total: None,
+            purge_done: 0,
+            purge_candidates: Vec::new(),
+            purge_candidate_selected: 0,
+            purge_paths: None,
+            dry_run_report: None,
+            resend_report: None,
+            resend_report_scroll: 0,
             bg_cancel: Arc::new(AtomicBool::new(false)),
+            bg_cancel_tokens: Vec::new(),
+            quit_deadline: None,
             loading: false,
             tree_list_state: ListState::default(),
             message_table_state: TableState::default(),
             detail_body_scroll: 0,
+            detail_hex_dump: None,
+            body_wrap,
+            body_scroll_horizontal: 0,
+            body_view_format: None,
+            theme: Theme::Color,
+            no_color_flag: false,
+            event_log: None,
             copy_source_message: None,
             copy_source_entity: None,
             copy_dest_connection_name: None,
             copy_dest_connection_config: None,
             copy_dest_entities: Vec::new(),
+            copy_entity_filter: String::new(),
             copy_entity_selected: 0,
             copy_connection_list_state: ListState::default(),
             copy_entity_list_state: ListState::default(),
             copy_destination_entity: None,
+            entity_cache: HashMap::new(),
+            authorization_rules: Vec::new(),
+            error_report: None,
+            error_scroll: 0,
+            column_editor: Vec::new(),
+            column_editor_selected: 0,
+            status_selected: 0,
+            session_browse: None,
+            session_selected: 0,
+            search_results: None,
+            search_selected: 0,
+            compare_marks: Vec::new(),
+            message_ops_in_flight: Vec::new(),
+            compare_scroll: 0,
+            compare_body_diff: Vec::new(),
+            tab_before_dead_letter: None,
+            settings_selected: 0,
+            settings_error: None,
+            detail_changes: Vec::new(),
+            detail_changes_at: None,
         }
     }
 
@@ -296,136 +1357,2409 @@ impl App {
         self.status_is_error = true;
     }
 
-    /// Signal the running background task to stop.
-    pub fn cancel_bg(&self) {
-        self.bg_cancel.store(true, Ordering::Relaxed);
+    /// Show a failure: summary in the status bar, plus the error modal when
+    /// the full text is too long or multi-line to read there.
+    pub fn report_error(&mut self, report: ErrorReport) {
+        self.set_error(report.status_line());
+        if report.needs_modal() {
+            let previous = match std::mem::replace(&mut self.modal, ActiveModal::None) {
+                ActiveModal::ErrorDetail { previous } => previous,
+                other => Box::new(other),
+            };
+            self.error_scroll = 0;
+            self.error_report = Some(report);
+            self.modal = ActiveModal::ErrorDetail { previous };
+        }
     }
 
-    /// Create a fresh cancellation token for a new background task.
-    pub fn new_cancel_token(&mut self) -> Arc<AtomicBool> {
-        let token = Arc::new(AtomicBool::new(false));
-        self.bg_cancel = Arc::clone(&token);
-        token
+    /// Replace the detail view, diffing against the previous description when
+    /// the same entity was reloaded (manual refresh, post-update reload).
+    pub fn apply_detail(&mut self, mut detail: DetailView) {
+        let changes = match (&self.detail_view, &detail) {
+            (DetailView::Queue(old, _), DetailView::Queue(new, _)) if old.name == new.name => {
+                Some(diff_descriptions(old, new))
+            }
+            (DetailView::Topic(old, ..), DetailView::Topic(new, ..)) if old.name == new.name => {
+                Some(diff_descriptions(old, new))
+            }
+            (DetailView::Subscription(old, _), DetailView::Subscription(new, _))
+                if old.topic_name == new.topic_name && old.name == new.name =>
+            {
+                Some(diff_descriptions(old, new))
+            }
+            _ => None,
+        };
+        // Routes are only missing when the subscriptions could not be listed,
+        // leaving the runtime counts at zero; the tree may know better.
+        if let DetailView::Topic(desc, Some(rt), None) = &mut detail {
+            if let Some((active, dlq)) = self.topic_counts(&desc.name) {
+                rt.active_message_count = active;
+                rt.dead_letter_message_count = dlq;
+            }
+        }
+
+        if let Some((path, _)) = detail.forward_edges() {
+            self.detail_cache.insert(path, detail.clone());
+        }
+        if let Some((name, usage)) = detail.size_usage() {
+            self.entity_sizes.insert(name, usage);
+        }
+
+        match changes {
+            Some(changes) if !changes.is_empty() => {
+                self.detail_changes = changes;
+                self.detail_changes_at = Some(Instant::now());
+            }
+            // Same entity, nothing new: let any current highlight expire on its own
+            Some(_) => {}
+            None => {
+                self.dismiss_detail_changes();
+                self.route_selected = 0;
+            }
+        }
+        self.detail_view = detail;
     }
 
-    /// Connect to a Service Bus namespace using a SAS connection string.
-    pub fn connect(&mut self, connection_string: &str) -> crate::client::Result<()> {
-        let cfg = ConnectionConfig::from_connection_string(connection_string)?;
-        self.management = Some(ManagementClient::new(cfg.clone()));
-        self.data_plane = Some(DataPlaneClient::new(cfg.clone()));
-        self.connection_config = Some(cfg);
-        Ok(())
+    /// Changed fields to highlight, empty once the highlight has expired.
+    pub fn visible_detail_changes(&self) -> &[FieldChange] {
+        match self.detail_changes_at {
+            Some(at) if at.elapsed() < DETAIL_CHANGES_TTL => &self.detail_changes,
+            _ => &[],
+        }
     }
 
-    /// Connect to a Service Bus namespace using Azure AD (Microsoft Entra ID).
-    pub fn connect_azure_ad(&mut self, namespace: &str) -> crate::client::Result<()> {
-        let credential = azure_identity::DefaultAzureCredential::new().map_err(|e| {
-            crate::client::ServiceBusError::Auth(format!("Azure AD credential error: {}", e))
-        })?;
-        let cfg = ConnectionConfig::from_azure_ad(namespace, credential);
-        self.management = Some(ManagementClient::new(cfg.clone()));
-        self.data_plane = Some(DataPlaneClient::new(cfg.clone()));
-        self.connection_config = Some(cfg);
-        Ok(())
+    pub fn dismiss_detail_changes(&mut self) {
+        self.detail_changes.clear();
+        self.detail_changes_at = None;
     }
 
-    /// Disconnect from the current Service Bus namespace and reset all state.
-    pub fn disconnect(&mut self) {
-        // Cancel any running background operations
-        self.cancel_bg();
+    /// Messages listed in the active tab, after the DLQ reason filter.
+    pub fn visible_messages(&self) -> Vec<&ReceivedMessage> {
+        let mut messages = self.tab_messages();
+        if self.sort_by_expiry && self.message_tab == MessageTab::Messages {
+            let default_ttl = self.messages_default_ttl();
+            messages.sort_by_cached_key(|m| {
+                let expires = m.expires_at(default_ttl);
+                (expires.is_none(), expires)
+            });
+        }
+        messages
+    }
 
-        // Clear connection state
-        self.management = None;
-        self.data_plane = None;
-        self.connection_config = None;
-        self.connection_name = None;
+    /// Whether the current tab holds no messages at all, before any filter.
+    pub fn tab_messages_empty(&self) -> bool {
+        match self.message_tab {
+            MessageTab::Messages => self.messages.is_empty(),
+            MessageTab::DeadLetter => self.dlq_messages.is_empty(),
+            MessageTab::Transferred => self.transferred_messages.is_empty(),
+        }
+    }
 
-        // Clear tree state
-        self.tree = None;
+    /// Messages of the current tab in peek order, after the reason and
+    /// message filters.
+    fn tab_messages(&self) -> Vec<&ReceivedMessage> {
+        let messages: Vec<&ReceivedMessage> = match self.message_tab {
+            MessageTab::Messages => self.messages.iter().collect(),
+            MessageTab::DeadLetter => self
+                .dlq_messages
+                .iter()
+                .filter(|m| match self.dlq_reason_filter {
+                    Some(ref reason) => dlq_reason_label(m) == reason,
+                    None => true,
+                })
+                .collect(),
+            MessageTab::Transferred => self.transferred_messages.iter().collect(),
+        };
+        match self.message_filter {
+            Some(ref filter) => messages.into_iter().filter(|m| filter.matches(m)).collect(),
+            None => messages,
+        }
+    }
+
+    /// Default message TTL of the entity whose messages are shown, from its
+    /// loaded detail. Messages without a TTL of their own expire after it.
+    pub fn messages_default_ttl(&self) -> Option<Duration> {
+        let entity = self.messages_entity.as_deref()?;
+        std::iter::once(&self.detail_view)
+            .chain(self.detail_cache.values())
+            .filter_map(DetailView::default_message_ttl)
+            .find(|(path, _)| path.eq_ignore_ascii_case(entity))
+            .map(|(_, ttl)| ttl)
+    }
+
+    /// When `msg` on the current tab expires. Dead-lettered messages don't.
+    pub fn message_expiry(&self, msg: &ReceivedMessage) -> Option<DateTime<Utc>> {
+        if self.message_tab != MessageTab::Messages {
+            return None;
+        }
+        msg.expires_at(self.messages_default_ttl())
+    }
+
+    /// Order active messages by expiry, soonest first, or back to peek order.
+    pub fn toggle_expiry_sort(&mut self) {
+        self.sort_by_expiry = !self.sort_by_expiry;
+        self.message_selected = 0;
+        self.set_status(if self.sort_by_expiry {
+            "Sorted by expiry, soonest first"
+        } else {
+            "Sorted in peek order"
+        });
+    }
+
+    /// Peeked DLQ messages grouped by reason, in the same order as `DlqStats`.
+    pub fn dlq_groups(&self) -> Vec<DlqGroup<'_>> {
+        DlqStats::from_messages(&self.dlq_messages)
+            .reasons
+            .into_iter()
+            .filter_map(|(reason, count)| {
+                let first = self
+                    .dlq_messages
+                    .iter()
+                    .find(|m| dlq_reason_label(m) == reason)?;
+                Some(DlqGroup {
+                    reason,
+                    count,
+                    first,
+                })
+            })
+            .collect()
+    }
+
+    /// Grouped DLQ view showing the per-reason summary (no group expanded).
+    pub fn in_dlq_group_summary(&self) -> bool {
+        self.dlq_group_view
+            && self.message_tab == MessageTab::DeadLetter
+            && self.dlq_reason_filter.is_none()
+    }
+
+    /// The message under the cursor in the messages table.
+    pub fn selected_message(&self) -> Option<&ReceivedMessage> {
+        self.visible_messages().get(self.message_selected).copied()
+    }
+
+    /// Whether a single-message operation on `msg` is still running.
+    pub fn is_message_op_in_flight(&self, msg: &ReceivedMessage) -> bool {
+        self.message_ops_in_flight.iter().any(|r| r.matches(msg))
+    }
+
+    pub fn begin_message_op(&mut self, target: &MessageRef) {
+        if !self.message_ops_in_flight.contains(target) {
+            self.message_ops_in_flight.push(target.clone());
+        }
+    }
+
+    pub fn finish_message_op(&mut self, target: &MessageRef) {
+        self.message_ops_in_flight.retain(|r| r != target);
+    }
+
+    /// Whether `msg` on the current tab is marked for comparison.
+    pub fn is_compare_marked(&self, msg: &ReceivedMessage) -> bool {
+        self.compare_marks.iter().any(|(marked, tab)| {
+            *tab == self.message_tab
+                && MessageRef::from_message(marked).is_some_and(|r| r.matches(msg))
+        })
+    }
+
+    /// Mark or unmark the selected message for comparison. Marks survive tab
+    /// switches, so one can be active and the other dead-lettered; a third
+    /// mark replaces the oldest.
+    pub fn toggle_compare_mark(&mut self) {
+        let Some(msg) = self.selected_message().cloned() else {
+            self.set_status("No message selected");
+            return;
+        };
+        if self.is_compare_marked(&msg) {
+            let tab = self.message_tab;
+            self.compare_marks.retain(|(marked, marked_tab)| {
+                *marked_tab != tab
+                    || !MessageRef::from_message(marked).is_some_and(|r| r.matches(&msg))
+            });
+        } else {
+            self.compare_marks.push((msg, self.message_tab));
+            if self.compare_marks.len() > 2 {
+                self.compare_marks.remove(0);
+            }
+        }
+        self.set_status(match self.compare_marks.len() {
+            2 => "2 messages marked — press = to compare".to_string(),
+            n => format!("{} message(s) marked for compare (m to mark another)", n),
+        });
+    }
+
+    pub fn open_compare(&mut self) {
+        if let [(left, _), (right, _)] = &self.compare_marks[..] {
+            self.compare_body_diff = diff_lines(&comparable_body(left), &comparable_body(right));
+            self.compare_scroll = 0;
+            self.modal = ActiveModal::CompareMessages;
+        } else {
+            self.set_status("Mark two messages with m to compare them");
+        }
+    }
+
+    /// Restrict the DLQ tab to one dead-letter reason (`None` shows all).
+    pub fn set_dlq_reason_filter(&mut self, reason: Option<String>) {
+        self.dlq_reason_filter = reason;
+        self.message_tab = MessageTab::DeadLetter;
+        self.message_selected = 0;
+        self.selected_message_detail = None;
+    }
+
+    /// Open the filter preset picker (`F` in the messages panel).
+    pub fn open_filter_presets(&mut self) {
+        let len = self.config.filter_presets.len();
+        self.filter_preset_selected = self.filter_preset_selected.min(len.saturating_sub(1));
+        self.modal = ActiveModal::FilterPresets;
+    }
+
+    /// Filter every tab with `filter` (`None` clears it). A tab that was
+    /// never peeked offers to peek it with the default count.
+    pub fn apply_message_filter(&mut self, filter: Option<MessageFilter>) {
+        self.message_selected = 0;
+        self.selected_message_detail = None;
+        self.modal = ActiveModal::None;
+        match filter {
+            Some(ref f) => self.set_status(format!("Filter: {}", f.describe())),
+            None => self.set_status("Message filter cleared"),
+        }
+        let unpeeked = filter.is_some()
+            && self.selected_entity().is_some()
+            && !self.tab_peeks.contains_key(&self.message_tab);
+        self.message_filter = filter;
+        if unpeeked {
+            self.modal = ActiveModal::ConfirmFilterPeek;
+        }
+    }
+
+    pub fn apply_selected_filter_preset(&mut self) {
+        if let Some(preset) = self.config.filter_presets.get(self.filter_preset_selected) {
+            let filter = preset.filter();
+            self.apply_message_filter(Some(filter));
+        }
+    }
+
+    /// `ConfirmFilterPeek`: peek the filtered tab with the default count.
+    pub fn peek_for_filter(&mut self) {
+        self.modal = ActiveModal::None;
+        if self.bg_running {
+            self.set_status("A background operation is in progress...");
+            return;
+        }
+        if self.message_tab == MessageTab::Transferred {
+            self.set_status("Peeking transfer DLQ...");
+        } else {
+            self.peek_dlq = self.message_tab == MessageTab::DeadLetter;
+            self.peek_both = false;
+            self.pending_peek_count = None;
+            self.peek_clamp_note = None;
+            self.set_status("Peeking messages...");
+        }
+    }
+
+    /// `]`: peek the current tab again with `pages` more pages than it shows.
+    /// Peeks have no cursor, so the larger peek starts from the head again. A
+    /// page is the peek count last used for the entity kind, and the total
+    /// is capped at `peek_max`.
+    pub fn peek_more_pages(&mut self, pages: usize) {
+        if self.message_tab == MessageTab::Transferred {
+            self.set_status("Paging is not available on the transfer DLQ");
+            return;
+        }
+        if self.selected_entity().is_none() {
+            self.set_status("Select a queue, topic, or subscription to peek");
+            return;
+        }
+        let page = match self.peek_kind_key() {
+            Some(kind) => self.config.settings.peek_count_for(kind),
+            None => self.config.settings.peek_count,
+        }
+        .max(1) as usize;
+        let shown = match self.message_tab {
+            MessageTab::DeadLetter => self.dlq_messages.len(),
+            _ => self.messages.len(),
+        };
+        let max = self.config.settings.peek_max.max(1) as usize;
+        let requested = pages.saturating_mul(page).saturating_add(shown);
+        if shown >= max {
+            self.set_status(format!(
+                "Already showing {} messages, the peek_max limit",
+                shown
+            ));
+            return;
+        }
+        self.peek_clamp_note = (requested > max).then(|| {
+            format!(
+                "capped at {} (requested {}); raise peek_max in config.toml to peek more",
+                max, requested
+            )
+        });
+        self.peek_dlq = self.message_tab == MessageTab::DeadLetter;
+        self.peek_both = false;
+        self.pending_peek_count = Some(requested.min(max) as i32);
+        self.set_status("Peeking messages...");
+    }
+
+    /// Type a filter expression: a new filter (`preset: None`) or an edit of
+    /// a saved preset.
+    pub fn open_message_filter_input(&mut self, preset: Option<usize>) {
+        let current = match preset {
+            Some(index) => self
+                .config
+                .filter_presets
+                .get(index)
+                .map(FilterPreset::filter),
+            None => self.message_filter.clone(),
+        };
+        let (scope, expression) = match current {
+            Some(f) => (f.scope, f.expression),
+            None => (FilterScope::default(), String::new()),
+        };
+        self.input_buffer = expression;
+        self.input_cursor = self.input_buffer.chars().count();
+        self.modal = ActiveModal::MessageFilterInput { scope, preset };
+    }
+
+    /// Enter in `MessageFilterInput`: apply the filter, or store the edited preset.
+    pub fn submit_message_filter_input(&mut self, scope: FilterScope, preset: Option<usize>) {
+        let expression = self.input_buffer.trim().to_string();
+        if expression.is_empty() {
+            self.set_error("Filter expression cannot be empty");
+            return;
+        }
+        match preset {
+            Some(index) => {
+                if let Some(p) = self.config.filter_presets.get_mut(index) {
+                    p.scope = scope;
+                    p.expression = expression;
+                    let name = p.name.clone();
+                    self.save_config();
+                    self.set_status(format!("Updated filter preset '{}'", name));
+                }
+                self.modal = ActiveModal::FilterPresets;
+            }
+            None => self.apply_message_filter(Some(MessageFilter { scope, expression })),
+        }
+    }
+
+    /// Name the active filter as a new preset (`preset: None`) or rename one.
+    pub fn open_filter_preset_name_input(&mut self, preset: Option<usize>) {
+        self.input_buffer = match preset {
+            Some(index) => match self.config.filter_presets.get(index) {
+                Some(p) => p.name.clone(),
+                None => return,
+            },
+            None if self.message_filter.is_none() => {
+                self.set_error("No filter active — press / to type one first");
+                return;
+            }
+            None => String::new(),
+        };
+        self.input_cursor = self.input_buffer.chars().count();
+        self.modal = ActiveModal::FilterPresetNameInput { preset };
+    }
+
+    pub fn submit_filter_preset_name(&mut self, preset: Option<usize>) {
+        let name = self.input_buffer.trim().to_string();
+        if name.is_empty() {
+            self.set_error("Preset name cannot be empty");
+            return;
+        }
+        let taken = self
+            .config
+            .filter_presets
+            .iter()
+            .enumerate()
+            .any(|(i, p)| p.name == name && Some(i) != preset);
+        match preset {
+            Some(_) if taken => {
+                self.set_error(format!("A filter preset named '{}' already exists", name));
+                return;
+            }
+            Some(index) => {
+                if let Some(p) = self.config.filter_presets.get_mut(index) {
+                    p.name = name.clone();
+                }
+                self.set_status(format!("Renamed filter preset to '{}'", name));
+            }
+            None => {
+                let Some(filter) = self.message_filter.clone() else {
+                    return;
+                };
+                self.config.save_filter_preset(FilterPreset {
+                    name: name.clone(),
+                    scope: filter.scope,
+                    expression: filter.expression,
+                });
+                self.filter_preset_selected = self
+                    .config
+                    .filter_presets
+                    .iter()
+                    .position(|p| p.name == name)
+                    .unwrap_or(0);
+                self.set_status(if taken {
+                    format!("Replaced filter preset '{}'", name)
+                } else {
+                    format!("Saved filter preset '{}'", name)
+                });
+            }
+        }
+        self.save_config();
+        self.modal = ActiveModal::FilterPresets;
+    }
+
+    /// `d` in the preset list: ask before deleting the selected preset.
+    pub fn open_delete_filter_preset(&mut self) {
+        if let Some(preset) = self.config.filter_presets.get(self.filter_preset_selected) {
+            self.modal = ActiveModal::ConfirmDeleteFilterPreset(preset.name.clone());
+        }
+    }
+
+    /// Delete the preset `name` and return to the preset list.
+    pub fn delete_filter_preset(&mut self, name: &str) {
+        self.modal = ActiveModal::FilterPresets;
+        let Some(index) = self
+            .config
+            .filter_presets
+            .iter()
+            .position(|p| p.name == name)
+        else {
+            return;
+        };
+        let removed = self.config.filter_presets.remove(index);
+        self.filter_preset_selected = index.min(self.config.filter_presets.len().saturating_sub(1));
+        self.save_config();
+        self.set_status(format!("Deleted filter preset '{}'", removed.name));
+    }
+
+    /// Open the column selector: enabled columns first (in display order),
+    /// followed by the remaining ones disabled.
+    pub fn open_column_selector(&mut self) {
+        let enabled = &self.config.message_columns;
+        let mut editor: Vec<(MessageColumn, bool)> = enabled.iter().map(|c| (*c, true)).collect();
+        editor.extend(
+            MessageColumn::ALL
+                .iter()
+                .filter(|c| !enabled.contains(c))
+                .map(|c| (*c, false)),
+        );
+        self.column_editor = editor;
+        self.column_editor_selected = 0;
+        self.modal = ActiveModal::ColumnSelector;
+    }
+
+    /// Move the highlighted column one step up (`-1`) or down (`1`).
+    pub fn move_column(&mut self, delta: isize) {
+        let from = self.column_editor_selected;
+        let Some(to) = from.checked_add_signed(delta) else {
+            return;
+        };
+        if to < self.column_editor.len() {
+            self.column_editor.swap(from, to);
+            self.column_editor_selected = to;
+        }
+    }
+
+    /// Persist the column selector's choices. Refuses an empty selection.
+    pub fn apply_column_selector(&mut self) -> bool {
+        let columns: Vec<MessageColumn> = self
+            .column_editor
+            .iter()
+            .filter(|(_, on)| *on)
+            .map(|(c, _)| *c)
+            .collect();
+        if columns.is_empty() {
+            return false;
+        }
+        self.config.message_columns = columns;
+        self.save_config();
+        true
+    }
+
+    pub fn open_settings(&mut self) {
+        self.settings_selected = 0;
+        self.settings_error = None;
+        self.modal = ActiveModal::Settings { editing: false };
+    }
+
+    /// Enter on a settings row: flip on/off settings, start typing the others.
+    pub fn edit_selected_setting(&mut self) {
+        let Some(&field) = SettingField::ALL.get(self.settings_selected) else {
+            return;
+        };
+        let current = self.config.settings.value_of(field);
+        if field.is_toggle() {
+            let flipped = if current == "true" { "false" } else { "true" };
+            self.apply_setting(field, flipped);
+        } else {
+            self.input_cursor = current.chars().count();
+            self.input_buffer = current;
+            self.settings_error = None;
+            self.modal = ActiveModal::Settings { editing: true };
+        }
+    }
+
+    /// Validate and store a setting, save the config and put the new value to
+    /// use right away. On a bad value nothing changes and the reason is kept
+    /// in `settings_error`.
+    pub fn apply_setting(&mut self, field: SettingField, raw: &str) -> bool {
+        if let Err(e) = self.config.settings.set_value(field, raw) {
+            self.settings_error = Some(e);
+            return false;
+        }
+        self.settings_error = None;
+
+        self.body_wrap = self.config.settings.body_wrap;
+        self.theme = Theme::detect(self.no_color_flag, self.config.settings.no_color);
+        let timeouts = self.lock_timeouts();
+        self.data_plane = self
+            .data_plane
+            .take()
+            .map(|dp| dp.with_lock_timeouts(timeouts));
+        self.update_dlq_alerts();
+
+        self.save_config();
+        self.set_status(format!("{} saved", field.label()));
+        true
+    }
+
+    /// Signal the running background task to stop.
+    pub fn cancel_bg(&self) {
+        self.bg_cancel.store(true, Ordering::Relaxed);
+    }
+
+    /// Write `event` to the structured event log, if one is enabled.
+    pub fn log_event(&self, event: LogEvent) {
+        if let Some(ref log) = self.event_log {
+            let _ = log.send(event);
+        }
+    }
+
+    /// Mark a background operation as running and start its status-bar timer.
+    pub fn start_bg_job(&mut self, operation: impl Into<String>, entity_path: Option<String>) {
+        self.bg_running = true;
+        self.bg_job = Some(BgJob {
+            operation: operation.into(),
+            entity_path,
+            started: Instant::now(),
+            samples: VecDeque::new(),
+        });
+    }
+
+    pub fn record_job_progress(&mut self, processed: u64) {
+        if let Some(ref mut job) = self.bg_job {
+            job.record(processed);
+        }
+        if self.purge_total.is_some() {
+            self.purge_done = processed;
+        }
+    }
+
+    /// Fraction of the running purge that is done, for the status-bar gauge.
+    /// Clamped to 1.0 since the runtime count may be stale; `None` when the
+    /// count was zero, as there is no meaningful fraction to show.
+    pub fn purge_ratio(&self) -> Option<f64> {
+        let total = self.purge_total.filter(|&total| total > 0)?;
+        Some((self.purge_done as f64 / total as f64).min(1.0))
+    }
+
+    /// Show the checked subscriptions of a topic about to be purged.
+    pub fn open_purge_preflight(
+        &mut self,
+        entity_path: String,
+        is_dlq: bool,
+        candidates: Vec<PurgeCandidate>,
+    ) {
+        let flagged = candidates.iter().filter(|c| c.problem.is_some()).count();
+        self.purge_candidates = candidates;
+        self.purge_candidate_selected = 0;
+        self.modal = ActiveModal::PurgePreflight {
+            entity_path,
+            is_dlq,
+        };
+        if flagged > 0 {
+            self.set_status(format!(
+                "{} subscription(s) can't be purged and are excluded — Space to include",
+                flagged
+            ));
+        } else {
+            self.set_status("Confirm the subscriptions to purge");
+        }
+    }
+
+    pub fn toggle_purge_candidate(&mut self) {
+        if let Some(c) = self.purge_candidates.get_mut(self.purge_candidate_selected) {
+            c.selected = !c.selected;
+        }
+    }
+
+    /// Select every path, or none if all are selected already.
+    pub fn toggle_all_purge_candidates(&mut self) {
+        let select = !self.purge_candidates.iter().all(|c| c.selected);
+        for c in &mut self.purge_candidates {
+            c.selected = select;
+        }
+    }
+
+    /// Start the purge of the selected pre-flight paths.
+    pub fn confirm_purge_preflight(&mut self, is_dlq: bool) {
+        let paths: Vec<String> = self
+            .purge_candidates
+            .iter()
+            .filter(|c| c.selected)
+            .map(|c| c.path.clone())
+            .collect();
+        if paths.is_empty() {
+            self.set_error("No subscriptions selected to purge");
+            return;
+        }
+        self.purge_paths = Some(paths);
+        self.set_status(if is_dlq {
+            "Clearing (delete DLQ)..."
+        } else {
+            "Clearing (delete)..."
+        });
+    }
+
+    /// Flip dry-run mode (`t`) of the open clear or bulk confirm modal.
+    pub fn toggle_dry_run(&mut self) {
+        match &mut self.modal {
+            ActiveModal::ClearOptions { dry_run, .. }
+            | ActiveModal::ConfirmBulkResend { dry_run, .. }
+            | ActiveModal::ConfirmBulkDelete { dry_run, .. } => *dry_run = !*dry_run,
+            _ => {}
+        }
+    }
+
+    /// Show what a dry run found.
+    pub fn open_dry_run_summary(&mut self, report: DryRunReport) {
+        self.set_status(report.summary());
+        self.dry_run_report = Some(report);
+        self.modal = ActiveModal::DryRunSummary;
+    }
+
+    /// List the messages a bulk resend failed on.
+    pub fn open_resend_report(&mut self, report: ResendReport) {
+        self.resend_report = Some(report);
+        self.resend_report_scroll = 0;
+        self.modal = ActiveModal::ResendReport;
+    }
+
+    /// `Ctrl+E`: show the failures of the last bulk resend again.
+    pub fn reopen_resend_report(&mut self) {
+        if self.resend_report.is_some() {
+            self.modal = ActiveModal::ResendReport;
+        } else {
+            self.set_status("No resend failures to show");
+        }
+    }
+
+    /// Close the report; it stays available until the next resend.
+    pub fn close_resend_report(&mut self) {
+        self.modal = ActiveModal::None;
+        self.set_status("Resend failures kept — Ctrl+E shows them again");
+    }
+
+    /// Scroll the report by `delta` lines, keeping the last failure in view.
+    pub fn scroll_resend_report(&mut self, delta: i32) {
+        let last = self
+            .resend_report
+            .as_ref()
+            .map_or(0, |r| r.failures.len().saturating_sub(1));
+        let scroll = (i64::from(self.resend_report_scroll) + i64::from(delta)).max(0);
+        self.resend_report_scroll = scroll.min(last as i64).min(i64::from(u16::MAX)) as u16;
+    }
+
+    /// Resend only the messages of the shown report, straight away: they
+    /// were confirmed with the original resend.
+    pub fn retry_failed_resends(&mut self) {
+        let Some(report) = self.resend_report.take() else {
+            return;
+        };
+        self.dlq_messages = report.failures.into_iter().map(|f| f.message).collect();
+        self.message_selected = 0;
+        self.bulk_resend_delay = None;
+        self.modal = ActiveModal::ConfirmBulkResend {
+            entity_path: report.entity_path,
+            count: self.dlq_messages.len() as u32,
+            is_topic: report.is_topic,
+            dry_run: false,
+        };
+        self.set_status("Bulk resending...");
+    }
+
+    /// Run the operation the shown dry run stood in for, with its parameters.
+    pub fn run_dry_run_for_real(&mut self) {
+        if let Some(report) = self.dry_run_report.take() {
+            self.modal = report.then;
+            self.set_status(report.op.trigger_status());
+        }
+    }
+
+    /// Signal every background task that was handed a cancellation token.
+    fn cancel_all_bg(&mut self) {
+        self.cancel_bg();
+        for token in self.bg_cancel_tokens.drain(..) {
+            token.store(true, Ordering::Relaxed);
+        }
+    }
+
+    /// Quit, or ask first when a background operation would be cut off.
+    pub fn request_quit(&mut self) {
+        if self.bg_running {
+            self.modal = ActiveModal::ConfirmQuit;
+        } else {
+            self.running = false;
+        }
+    }
+
+    /// Cancel the background operations and quit once they have stopped,
+    /// waiting at most `QUIT_CANCEL_GRACE`.
+    pub fn cancel_and_quit(&mut self) {
+        self.cancel_all_bg();
+        self.modal = ActiveModal::None;
+        self.quit_deadline = Some(Instant::now() + QUIT_CANCEL_GRACE);
+        self.set_status("Cancelling before quit...");
+    }
+
+    /// Queue a write of the config. `run_app` saves it on a blocking task
+    /// once changes stop for `CONFIG_SAVE_DEBOUNCE`; failures show in the
+    /// status bar when the write finishes.
+    pub fn save_config(&mut self) {
+        if self.demo {
+            return;
+        }
+        self.config_save_at = Some(Instant::now());
+    }
+
+    /// The config to write, once a queued save is due (or right away with
+    /// `flush`, on exit).
+    pub fn take_config_save(&mut self, flush: bool) -> Option<AppConfig> {
+        let due = self
+            .config_save_at
+            .is_some_and(|at| flush || at.elapsed() >= CONFIG_SAVE_DEBOUNCE);
+        if !due {
+            return None;
+        }
+        self.config_save_at = None;
+        Some(self.config.clone())
+    }
+
+    /// Whether a pending "cancel and quit" can exit now.
+    pub fn quit_due(&self) -> bool {
+        self.quit_deadline
+            .is_some_and(|deadline| !self.bg_running || Instant::now() >= deadline)
+    }
+
+    /// Create a fresh cancellation token for a new background task.
+    pub fn new_cancel_token(&mut self) -> Arc<AtomicBool> {
+        let token = Arc::new(AtomicBool::new(false));
+        self.bg_cancel = Arc::clone(&token);
+        // A token only we still hold belongs to a task that has finished
+        self.bg_cancel_tokens.retain(|t| Arc::strong_count(t) > 1);
+        self.bg_cancel_tokens.push(Arc::clone(&token));
+        token
+    }
+
+    /// Show `msg` in the detail view, starting at the top of its body and
+    /// properties.
+    pub fn open_message_detail(&mut self, msg: ReceivedMessage) {
+        self.selected_message_detail = Some(msg);
+        self.properties_focus = false;
+        self.property_selected = 0;
+        self.property_value_scroll = 0;
+        self.detail_body_scroll = 0;
+        self.body_scroll_horizontal = 0;
+        self.body_view_format = None;
+        self.detail_hex_dump = None;
+    }
+
+    /// Custom properties of the open message, sorted by name.
+    pub fn detail_properties(&self) -> Vec<&(String, String)> {
+        match self.selected_message_detail {
+            Some(ref msg) => sorted_properties(&msg.custom_properties),
+            None => Vec::new(),
+        }
+    }
+
+    /// Switch j/k between scrolling the body and walking the properties.
+    pub fn toggle_properties_focus(&mut self) {
+        if self.properties_focus {
+            self.properties_focus = false;
+            return;
+        }
+        let count = self.detail_properties().len();
+        if count == 0 {
+            self.set_status("This message has no custom properties");
+            return;
+        }
+        self.properties_focus = true;
+        self.property_selected = self.property_selected.min(count - 1);
+        self.property_value_scroll = 0;
+    }
+
+    /// Copy the full value of the selected custom property.
+    pub fn copy_selected_property(&mut self) {
+        let Some((name, value)) = self
+            .detail_properties()
+            .get(self.property_selected)
+            .map(|(k, v)| (k.clone(), v.clone()))
+        else {
+            return;
+        };
+        match crate::clipboard::copy_to_clipboard(&value) {
+            Ok(()) => self.set_status(format!(
+                "Property '{}' copied ({} chars)",
+                name,
+                value.chars().count()
+            )),
+            Err(e) => self.set_error(format!("Copy failed: {}", e)),
+        }
+    }
+
+    /// Toggle body word-wrap in the message detail view and persist it.
+    pub fn toggle_body_wrap(&mut self) {
+        self.body_wrap = !self.body_wrap;
+        self.body_scroll_horizontal = 0;
+        self.config.settings.body_wrap = self.body_wrap;
+        self.save_config();
+    }
+
+    /// Format the open message body is shown in.
+    pub fn body_display_format(&self) -> Option<BodyFormat> {
+        let msg = self.selected_message_detail.as_ref()?;
+        Some(
+            self.body_view_format
+                .unwrap_or_else(|| detect_body_format(&msg.body)),
+        )
+    }
+
+    /// Show the open message body in the next format that fits it.
+    pub fn cycle_body_format(&mut self) {
+        let Some(msg) = self.selected_message_detail.as_ref() else {
+            return;
+        };
+        let formats = available_formats(&msg.body);
+        let current = self
+            .body_view_format
+            .unwrap_or_else(|| detect_body_format(&msg.body));
+        let next = formats
+            .iter()
+            .position(|f| *f == current)
+            .map_or(formats[0], |i| formats[(i + 1) % formats.len()]);
+        self.body_view_format = Some(next);
+        self.detail_body_scroll = 0;
+        self.body_scroll_horizontal = 0;
+        if formats.len() == 1 {
+            self.set_status(format!("Body can only be shown as {}", next.label()));
+        } else {
+            self.set_status(format!("Body shown as {}", next.label()));
+        }
+    }
+
+    /// Switch the open message body between a hex dump and its detected format.
+    pub fn toggle_hex_view(&mut self) {
+        if self.selected_message_detail.is_none() {
+            return;
+        }
+        self.body_view_format = match self.body_view_format {
+            Some(BodyFormat::Hex) => None,
+            _ => Some(BodyFormat::Hex),
+        };
+        self.detail_body_scroll = 0;
+        self.body_scroll_horizontal = 0;
+    }
+
+    /// Switch the open message body between its decoded JWT header/claims and
+    /// its detected format.
+    pub fn toggle_jwt_view(&mut self) {
+        let Some(msg) = self.selected_message_detail.as_ref() else {
+            return;
+        };
+        if self.body_view_format == Some(BodyFormat::Jwt) {
+            self.body_view_format = None;
+            self.set_status("Body shown as stored");
+        } else if decode_jwt(&msg.body).is_some() {
+            self.body_view_format = Some(BodyFormat::Jwt);
+            self.set_status("JWT decoded — signature not verified");
+        } else {
+            self.set_status("Body is not a JWT");
+            return;
+        }
+        self.detail_body_scroll = 0;
+        self.body_scroll_horizontal = 0;
+    }
+
+    /// Lock-based receive timeouts from the persisted settings.
+    pub fn lock_timeouts(&self) -> LockTimeouts {
+        LockTimeouts {
+            peek_secs: self.config.settings.peek_lock_timeout_secs.max(1),
+            resend_secs: self.config.settings.resend_lock_timeout_secs.max(1),
+        }
+    }
+
+    /// Connect to a Service Bus namespace using a SAS connection string.
+    pub fn connect(&mut self, connection_string: &str) -> crate::client::Result<()> {
+        let cfg = ConnectionConfig::from_connection_string(connection_string)?;
+        self.use_connection(cfg);
+        Ok(())
+    }
+
+    /// Connect to a Service Bus namespace using Azure AD (Microsoft Entra ID).
+    pub fn connect_azure_ad(&mut self, namespace: &str) -> crate::client::Result<()> {
+        let credential = azure_identity::DefaultAzureCredential::new().map_err(|e| {
+            crate::client::ServiceBusError::Auth(format!("Azure AD credential error: {}", e))
+        })?;
+        let cfg = ConnectionConfig::from_azure_ad(namespace, credential);
+        self.use_connection(cfg);
+        Ok(())
+    }
+
+    /// `--demo`: start on default settings, connected to a seeded in-memory
+    /// namespace. Nothing is saved for the rest of the run.
+    pub fn start_demo(&mut self) {
+        self.config = AppConfig::default();
+        self.config_load_error = None;
+        self.demo = true;
+        let namespace = Arc::new(DemoNamespace::seeded());
+        self.use_transport(ConnectionConfig::demo(&namespace), namespace);
+        self.connection_name = Some("demo".to_string());
+        self.set_status("Demo mode: in-memory namespace, changes are not saved");
+    }
+
+    fn use_connection(&mut self, cfg: ConnectionConfig) {
+        let transport = Arc::new(HttpTransport::new(cfg.clone(), self.http.clone()));
+        self.use_transport(cfg, transport);
+    }
+
+    /// Build the clients for `cfg` on `transport`: HTTP to its namespace, or
+    /// an in-memory one (demo mode, tests).
+    pub fn use_transport(&mut self, cfg: ConnectionConfig, transport: Arc<dyn Transport>) {
+        self.reset_connection_state();
+        self.management = Some(ManagementClient::with_transport(
+            cfg.clone(),
+            Arc::clone(&transport),
+        ));
+        self.data_plane = Some(
+            DataPlaneClient::with_transport(transport).with_lock_timeouts(self.lock_timeouts()),
+        );
+        self.connection_config = Some(cfg);
+    }
+
+    /// Save the connection just made with `connection_string` and return its
+    /// name. While re-authenticating, the saved connection gets the new
+    /// string in place; otherwise a new entry is added (or an identical one
+    /// reused).
+    pub fn save_sas_connection(&mut self, connection_string: String) -> String {
+        if let Some(name) = self.reauth_connection.take() {
+            if self
+                .config
+                .replace_connection_string(&name, connection_string.clone())
+            {
+                return name;
+            }
+        }
+        let namespace = self
+            .connection_config
+            .as_ref()
+            .map(|c| c.namespace.clone())
+            .unwrap_or_else(|| "default".to_string());
+        self.config.add_connection(&namespace, connection_string)
+    }
+
+    /// Like `save_sas_connection`, for an Azure AD connection to the
+    /// fully-qualified `namespace`.
+    pub fn save_azure_ad_connection(&mut self, namespace: String) -> String {
+        if let Some(name) = self.reauth_connection.take() {
+            if self.config.replace_with_azure_ad(&name, namespace.clone()) {
+                return name;
+            }
+        }
+        self.config.add_azure_ad_connection(namespace)
+    }
+
+    /// Record the just-saved connection `name` as the active one, filing it
+    /// under the group picked at the start of the new-connection flow.
+    pub fn remember_connection(&mut self, name: String) {
+        self.reauth_connection = None;
+        if let Some(group) = self.pending_connection_group.take() {
+            self.config.set_connection_group(&name, Some(group));
+        }
+        self.save_config();
+        self.connection_name = Some(name);
+    }
+
+    /// Forget a connect flow left without connecting: the group picked for
+    /// the new connection and the connection being re-authenticated.
+    pub fn end_connect_flow(&mut self) {
+        self.pending_connection_group = None;
+        self.reauth_connection = None;
+    }
+
+    /// The active connection's name, if it is one of the saved connections.
+    fn saved_connection_name(&self) -> Option<String> {
+        let name = self.connection_name.as_deref()?;
+        self.config
+            .connections
+            .iter()
+            .any(|c| c.name == name)
+            .then(|| name.to_string())
+    }
+
+    /// `R` in the connection dialog: rename the active connection, starting
+    /// from its current name.
+    pub fn open_connection_rename(&mut self) {
+        let Some(connection) = self.saved_connection_name() else {
+            self.set_error("The current connection isn't a saved connection");
+            return;
+        };
+        self.input_buffer = connection.clone();
+        self.input_cursor = self.input_buffer.chars().count();
+        self.modal = ActiveModal::ConnectionRenameInput { connection };
+    }
+
+    /// Apply the name typed for `connection`; the dialog stays open on an
+    /// empty or taken name.
+    pub fn rename_connection(&mut self, connection: &str) {
+        match self
+            .config
+            .rename_connection(connection, &self.input_buffer)
+        {
+            Ok(new_name) => {
+                if self.connection_name.as_deref() == Some(connection) {
+                    self.connection_name = Some(new_name.clone());
+                }
+                self.input_buffer.clear();
+                self.input_cursor = 0;
+                self.modal = ActiveModal::None;
+                self.save_config();
+                self.set_status(format!(
+                    "Renamed connection '{}' to '{}'",
+                    connection, new_name
+                ));
+            }
+            Err(e) => self.set_error(e),
+        }
+    }
+
+    /// `X` in the connection dialog: ask before deleting the active connection.
+    pub fn open_delete_connection(&mut self) {
+        match self.saved_connection_name() {
+            Some(name) => self.modal = ActiveModal::ConfirmDeleteConnection(name),
+            None => self.set_error("The current connection isn't a saved connection"),
+        }
+    }
+
+    /// Remove the saved connection `name`, disconnecting first when it is
+    /// the active one.
+    pub fn delete_connection(&mut self, name: &str) {
+        if self.connection_name.as_deref() == Some(name) {
+            self.disconnect();
+        }
+        self.config.remove_connection(name);
+        self.modal = ActiveModal::None;
+        self.save_config();
+        self.set_status(format!("Deleted connection '{}'", name));
+    }
+
+    /// Reconnect to the saved connection of the last session and keep the
+    /// rest of it for `finish_session_restore`. Sessions whose connection has
+    /// since been removed are ignored.
+    pub fn restore_session(&mut self, session: SessionState) {
+        let Some(conn) = self
+            .config
+            .connections
+            .iter()
+            .find(|c| c.name == session.connection)
+            .cloned()
+        else {
+            return;
+        };
+        let result = if conn.is_azure_ad() {
+            self.connect_azure_ad(conn.namespace.as_deref().unwrap_or_default())
+        } else {
+            self.connect(conn.connection_string.as_deref().unwrap_or_default())
+        };
+        if let Err(e) = result {
+            self.set_error(format!("Could not restore '{}': {}", conn.name, e));
+            return;
+        }
+        self.connection_name = Some(conn.name);
+        self.expand_state.extend(session.expand_state.clone());
+        self.session_restore = Some(session);
+    }
+
+    /// Select the last session's entity and panel once the tree is in. A
+    /// subscription only shows up once its topic's subscriptions have been
+    /// listed, so until then its topic is selected and the restore stays
+    /// pending.
+    pub fn finish_session_restore(&mut self) {
+        if self.tree.is_none() {
+            return;
+        }
+        let Some(session) = self.session_restore.take() else {
+            return;
+        };
+        if let Some(path) = session.selected_entity.as_deref() {
+            if !self.reveal_entity(path) {
+                if let Some((topic, _)) = path.split_once("/Subscriptions/") {
+                    let topic_pending = self.reveal_entity(topic)
+                        && self
+                            .flat_nodes
+                            .get(self.tree_selected)
+                            .is_some_and(|n| n.counts_pending);
+                    if topic_pending {
+                        self.session_restore = Some(session);
+                        return;
+                    }
+                }
+            }
+        }
+        if let Some(tab) = session.message_tab {
+            self.message_tab = tab;
+        }
+        if let Some(focus) = session.focus {
+            self.focus = focus;
+        }
+        self.set_status(format!("Restored session '{}'", session.connection));
+    }
+
+    /// Esc while the last session is being restored: disconnect and start
+    /// fresh.
+    pub fn abandon_session_restore(&mut self) {
+        self.session_restore = None;
+        self.disconnect();
+        self.set_status("Session not restored. Press 'c' to connect, '?' for help");
+    }
+
+    /// Where the user is now, to be saved at exit; `None` when not connected
+    /// to a saved connection. A restore still pending is kept as it was.
+    pub fn session_state(&self) -> Option<SessionState> {
+        if let Some(ref pending) = self.session_restore {
+            return Some(pending.clone());
+        }
+        let connection = self.connection_name.clone()?;
+        let mut expand_state: std::collections::BTreeMap<String, bool> = self
+            .expand_state
+            .iter()
+            .map(|(id, expanded)| (id.clone(), *expanded))
+            .collect();
+        if let Some(ref tree) = self.tree {
+            let mut current = HashMap::new();
+            tree.collect_expand_state(&mut current);
+            expand_state.extend(current);
+        }
+        Some(SessionState {
+            connection,
+            selected_entity: self
+                .flat_nodes
+                .get(self.tree_selected)
+                .map(|n| n.path.clone())
+                .filter(|p| !p.is_empty()),
+            expand_state,
+            message_tab: Some(self.message_tab),
+            focus: Some(self.focus),
+        })
+    }
+
+    /// After a 401 from the connected namespace, open the connect flow to
+    /// enter new credentials for the same saved connection. Any open dialog
+    /// other than the error details is left alone.
+    pub fn open_reauthenticate(&mut self) {
+        let Some(name) = self.connection_name.clone() else {
+            return;
+        };
+        if !matches!(
+            self.modal,
+            ActiveModal::None | ActiveModal::ErrorDetail { .. }
+        ) {
+            return;
+        }
+        self.end_connect_flow();
+        self.reauth_connection = Some(name);
+        self.input_buffer.clear();
+        self.input_cursor = 0;
+        self.modal = ActiveModal::ConnectionModeSelect;
+    }
+
+    /// Groups of the connection list, without the connections that are
+    /// filtered out (see `connection_failures_only`).
+    pub fn listed_connection_groups(&self) -> Vec<(Option<&str>, Vec<usize>)> {
+        let mut groups = self.config.connection_groups();
+        if self.connection_failures_only {
+            for (_, indices) in &mut groups {
+                indices.retain(|&idx| {
+                    self.connection_tests
+                        .get(&self.config.connections[idx].name)
+                        .is_some_and(|t| matches!(t.outcome, TestOutcome::Failed { .. }))
+                });
+            }
+            groups.retain(|(_, indices)| !indices.is_empty());
+        }
+        groups
+    }
+
+    /// Connection indices in list order (headers excluded).
+    pub fn listed_connections(&self) -> Vec<usize> {
+        self.listed_connection_groups()
+            .into_iter()
+            .flat_map(|(_, indices)| indices)
+            .collect()
+    }
+
+    /// Saved connection highlighted in the grouped connection list, where
+    /// `input_field_index` counts listed connections (headers excluded).
+    pub fn selected_saved_connection(&self) -> Option<&SavedConnection> {
+        let order = self.listed_connections();
+        order
+            .get(self.input_field_index)
+            .and_then(|&idx| self.config.connections.get(idx))
+    }
+
+    /// Show only failing connections in the list, or all of them again.
+    pub fn toggle_connection_failures_only(&mut self) {
+        self.connection_failures_only = !self.connection_failures_only;
+        self.input_field_index = 0;
+        if self.connection_failures_only && self.listed_connections().is_empty() {
+            self.connection_failures_only = false;
+            self.set_status("No failed connection tests — press t to test all");
+        }
+    }
+
+    /// Record one connection test result.
+    pub fn apply_connection_test(&mut self, name: String, outcome: TestOutcome) {
+        self.connections_testing.remove(&name);
+        self.connection_tests.insert(
+            name,
+            ConnectionTest {
+                outcome,
+                at: Instant::now(),
+            },
+        );
+    }
+
+    /// Testing ended: report the tally.
+    pub fn finish_connection_tests(&mut self, cancelled: bool) {
+        self.bg_running = false;
+        let tested: Vec<&ConnectionTest> = self
+            .config
+            .connections
+            .iter()
+            .filter(|c| !self.connections_testing.contains(&c.name))
+            .filter_map(|c| self.connection_tests.get(&c.name))
+            .collect();
+        let failed = tested
+            .iter()
+            .filter(|t| matches!(t.outcome, TestOutcome::Failed { .. }))
+            .count();
+        let status = format!(
+            "{}: {} ok, {} failed",
+            if cancelled {
+                "Connection tests cancelled"
+            } else {
+                "Connections tested"
+            },
+            tested.len() - failed,
+            failed
+        );
+        self.connections_testing.clear();
+        self.set_status(status);
+    }
+
+    /// Move the connection list highlight to the connection `name`.
+    pub fn select_saved_connection(&mut self, name: &str) {
+        let order = self.listed_connections();
+        if let Some(pos) = order
+            .iter()
+            .position(|&idx| self.config.connections[idx].name == name)
+        {
+            self.input_field_index = pos;
+        }
+    }
+
+    /// Disconnect from the current Service Bus namespace and reset all state.
+    pub fn disconnect(&mut self) {
+        self.reset_connection_state();
+        self.set_status("Disconnected. Press 'c' to connect, '?' for help");
+    }
+
+    /// Drop everything tied to the current connection. Called on disconnect and
+    /// before connecting to another namespace, so nothing from the old one
+    /// (tasks, messages, detail) can leak into the new session.
+    fn reset_connection_state(&mut self) {
+        // Cancel any running background operations and ignore their late results
+        self.cancel_all_bg();
+        self.bg_tx = self.bg_tx.next_generation();
+        while self.bg_rx.try_recv().is_ok() {}
+
+        // Clear connection state
+        self.management = None;
+        self.data_plane = None;
+        self.connection_config = None;
+        self.connection_name = None;
+        self.namespace_info = None;
+        self.entity_metrics = None;
+        self.health_check = None;
+        self.health_check_in_flight = false;
+        self.health_check_manual = false;
+        self.detail_cache.clear();
+        self.entity_sizes.clear();
+        self.quota_acknowledged.clear();
+        self.forward_acknowledged.clear();
+
+        // Clear tree state
+        self.tree = None;
         self.flat_nodes.clear();
         self.tree_selected = 0;
+        self.expand_state.clear();
+        self.counts_in_flight.clear();
+        self.stale_entities.clear();
         self.detail_view = DetailView::None;
+        self.dismiss_detail_changes();
+        self.authorization_rules.clear();
+        self.dlq_alerts.clear();
+        self.alert_flash_until = None;
+        self.resend_report = None;
+
+        // Clear message state
+        self.messages.clear();
+        self.dlq_messages.clear();
+        self.transferred_messages.clear();
+        self.messages_entity = None;
+        self.tab_peeks.clear();
+        self.peek_cache.clear();
+        self.message_ops_in_flight.clear();
+        self.message_selected = 0;
+        self.dlq_reason_filter = None;
+        self.message_filter = None;
+        self.dlq_group_selected = 0;
+        self.selected_message_detail = None;
+        self.properties_focus = false;
+        self.detail_editing = false;
+        self.form_submitting = false;
+        self.edit_source_dlq_ref = None;
+        self.detail_body_scroll = 0;
+        self.body_scroll_horizontal = 0;
+        self.body_view_format = None;
+        self.peek_clamp_note = None;
+
+        // Reset UI state
+        self.focus = FocusPanel::Tree;
+        self.loading = false;
+        self.bg_running = false;
+        self.bg_job = None;
+        self.purge_total = None;
+        self.purge_done = 0;
+        self.status_message.clear();
+        self.status_is_error = false;
+    }
+
+    /// Rebuild the flat node list from the tree (e.g., after expand/collapse).
+    pub fn rebuild_flat_nodes(&mut self) {
+        if let Some(ref tree) = self.tree {
+            self.flat_nodes = tree.flatten();
+            if self.tree_selected >= self.flat_nodes.len() && !self.flat_nodes.is_empty() {
+                self.tree_selected = self.flat_nodes.len() - 1;
+            }
+        }
+    }
+
+    /// Toggle expand/collapse on the selected tree node.
+    pub fn toggle_expand(&mut self) {
+        if self.flat_nodes.is_empty() {
+            return;
+        }
+        let selected_id = self.flat_nodes[self.tree_selected].id.clone();
+        if let Some(ref mut tree) = self.tree {
+            toggle_node(tree, &selected_id);
+        }
+        self.rebuild_flat_nodes();
+        if let Some(node) = self.flat_nodes.get(self.tree_selected) {
+            self.expand_state.insert(node.id.clone(), node.expanded);
+        }
+    }
+
+    /// Carry the user's expand/collapse choices over to a freshly built tree.
+    ///
+    /// The visible nodes are snapshotted first (they reflect the latest
+    /// toggles and reveals); nodes hidden under a collapsed parent keep the
+    /// state recorded at the previous refresh. Nodes with no recorded state —
+    /// entities created since — keep their default.
+    pub fn restore_expand_state(&mut self, tree: &mut TreeNode) {
+        for node in &self.flat_nodes {
+            self.expand_state.insert(node.id.clone(), node.expanded);
+        }
+        let mut defaults = HashMap::new();
+        tree.collect_expand_state(&mut defaults);
+        for (id, expanded) in &self.expand_state {
+            if defaults.get(id).is_some_and(|default| default != expanded) {
+                toggle_node(tree, id);
+            }
+        }
+        // Drop entries for nodes that no longer exist
+        self.expand_state.clear();
+        tree.collect_expand_state(&mut self.expand_state);
+    }
+
+    /// Give topics in a freshly built tree the subscriptions and counts they
+    /// had in the previous tree, so badges don't blank out on every refresh.
+    /// The topics stay pending and are re-fetched once visible.
+    pub fn carry_over_subscriptions(&self, tree: &mut TreeNode) {
+        let Some(ref old) = self.tree else {
+            return;
+        };
+        let Some(topics) = tree
+            .children
+            .iter_mut()
+            .find(|n| n.entity_type == EntityType::TopicFolder)
+        else {
+            return;
+        };
+        for topic in &mut topics.children {
+            let Some(prev) = find_entity(old, &topic.path, &EntityType::Topic) else {
+                continue;
+            };
+            topic.children = prev.children.clone();
+            topic.message_count = prev.message_count;
+            topic.dlq_count = prev.dlq_count;
+        }
+    }
+
+    /// Pending topics in the tree viewport that nobody is fetching yet. They
+    /// are marked in flight; the caller must fetch each one and report back
+    /// with `BgEvent::NodeCountsLoaded`.
+    pub fn take_visible_pending_topics(&mut self) -> Vec<String> {
+        let start = self.tree_list_state.offset();
+        let end = (start + self.tree_viewport_height).min(self.flat_nodes.len());
+        let mut out = Vec::new();
+        for node in self.flat_nodes.get(start..end).unwrap_or_default() {
+            if node.entity_type == EntityType::Topic
+                && node.counts_pending
+                && self.counts_in_flight.insert(node.path.clone())
+            {
+                out.push(node.path.clone());
+            }
+        }
+        out
+    }
+
+    /// Topics whose subscriptions have never been listed this session.
+    pub fn unlisted_topics(&self) -> Vec<String> {
+        let mut out = Vec::new();
+        if let Some(ref tree) = self.tree {
+            collect_unlisted_topics(tree, &mut out);
+        }
+        out
+    }
+
+    /// A topic node's (active, dead-letter) counts: the sums over its
+    /// subscriptions, see `apply_node_counts`. The topic entry itself never
+    /// reports them.
+    fn topic_counts(&self, topic: &str) -> Option<(i64, i64)> {
+        let node = find_entity(self.tree.as_ref()?, topic, &EntityType::Topic)?;
+        Some((node.message_count?, node.dlq_count?))
+    }
+
+    /// Put a topic's freshly listed subscriptions and counts into the tree,
+    /// keeping the selection on the same node. Returns the paths that newly
+    /// crossed their DLQ threshold.
+    pub fn apply_node_counts(
+        &mut self,
+        topic: &str,
+        result: Result<Vec<(SubscriptionDescription, i64, i64)>, String>,
+    ) -> Vec<String> {
+        self.counts_in_flight.remove(topic);
+        let selected_id = self
+            .flat_nodes
+            .get(self.tree_selected)
+            .map(|n| n.id.clone());
+        let Some(node) = self
+            .tree
+            .as_mut()
+            .and_then(|tree| find_entity_mut(tree, topic, &EntityType::Topic))
+        else {
+            return Vec::new();
+        };
+        node.counts_pending = false;
+        let failure = match result {
+            Ok(subs) => {
+                let mut folder = subscription_folder(topic, &subs);
+                if let Some(&expanded) = self.expand_state.get(&folder.id) {
+                    folder.expanded = expanded;
+                }
+                for sub in &mut folder.children {
+                    if let Some(&expanded) = self.expand_state.get(&sub.id) {
+                        sub.expanded = expanded;
+                    }
+                }
+                node.message_count = Some(subs.iter().map(|(_, active, _)| active).sum());
+                node.dlq_count = Some(subs.iter().map(|(_, _, dlq)| dlq).sum());
+                node.children = if subs.is_empty() {
+                    Vec::new()
+                } else {
+                    vec![folder]
+                };
+                None
+            }
+            Err(e) => Some(e),
+        };
+        // Keep the topic's detail panel in step with the fresh counts
+        if let Some((active, dlq)) = self.topic_counts(topic) {
+            if let DetailView::Topic(desc, Some(rt), _) = &mut self.detail_view {
+                if desc.name == topic {
+                    rt.active_message_count = active;
+                    rt.dead_letter_message_count = dlq;
+                }
+            }
+        }
+        if let Some(e) = failure {
+            self.set_error(format!(
+                "Failed to list subscriptions of '{}': {}",
+                topic, e
+            ));
+        }
+        self.rebuild_flat_nodes();
+        if let Some(pos) =
+            selected_id.and_then(|id| self.flat_nodes.iter().position(|n| n.id == id))
+        {
+            self.tree_selected = pos;
+        }
+        self.update_dlq_alerts()
+    }
+
+    /// Record the loaded tree's queues and topics in the entity cache.
+    pub fn cache_tree_entities(&mut self) {
+        let (Some(name), Some(tree)) = (self.connection_name.clone(), self.tree.as_ref()) else {
+            return;
+        };
+        let mut entities = Vec::new();
+        collect_destinations(tree, &mut entities);
+        entities.sort_by(|a, b| a.0.cmp(&b.0));
+        self.entity_cache.insert(name, entities);
+    }
+
+    /// Destination entities matching the picker's filter, best match first.
+    pub fn copy_filtered_entities(&self) -> Vec<&(String, EntityType)> {
+        fuzzy_filter(
+            &self.copy_entity_filter,
+            &self.copy_dest_entities,
+            |(name, _)| name.as_str(),
+        )
+    }
+
+    /// Show a destination connection's entities: cached names right away,
+    /// then a background refresh.
+    pub fn load_destination_entities(&mut self) {
+        if let Some(cached) = self
+            .copy_dest_connection_name
+            .as_ref()
+            .and_then(|name| self.entity_cache.get(name))
+        {
+            self.copy_dest_entities = cached.clone();
+        }
+        self.set_status("Loading destination entities...");
+    }
+
+    /// Replace the destination list with freshly fetched entities, keeping the
+    /// highlighted entity selected if it still exists.
+    pub fn apply_destination_entities(
+        &mut self,
+        connection: String,
+        entities: Vec<(String, EntityType)>,
+    ) {
+        let is_current = self.copy_dest_connection_name.as_deref() == Some(connection.as_str());
+        self.entity_cache.insert(connection, entities.clone());
+        if !is_current {
+            return;
+        }
+        let selected = self
+            .copy_filtered_entities()
+            .get(self.copy_entity_selected)
+            .map(|(name, _)| name.clone());
+        self.copy_dest_entities = entities;
+        self.copy_entity_selected = selected
+            .and_then(|name| {
+                self.copy_filtered_entities()
+                    .iter()
+                    .position(|(n, _)| *n == name)
+            })
+            .unwrap_or(0);
+        self.copy_entity_list_state
+            .select(Some(self.copy_entity_selected));
+    }
+
+    /// Forget everything about an in-progress copy.
+    pub fn clear_copy_state(&mut self) {
+        self.copy_source_message = None;
+        self.copy_source_entity = None;
+        self.copy_dest_entities.clear();
+        self.copy_entity_filter.clear();
+        self.copy_entity_selected = 0;
+        self.copy_dest_connection_name = None;
+        self.copy_dest_connection_config = None;
+        self.copy_destination_entity = None;
+    }
+
+    /// Re-check DLQ thresholds against the counts in the freshly loaded tree.
+    /// Returns the paths that were not alerting before (these start flashing).
+    pub fn update_dlq_alerts(&mut self) -> Vec<String> {
+        let mut counts = Vec::new();
+        if let Some(ref tree) = self.tree {
+            collect_dlq_counts(tree, &mut counts);
+        }
+
+        let settings = &self.config.settings;
+        let mut alerts: Vec<DlqAlert> = counts
+            .into_iter()
+            .filter_map(|(path, count)| {
+                let threshold = settings.dlq_threshold_for(&path)?;
+                (count >= threshold).then_some(DlqAlert {
+                    path,
+                    count,
+                    threshold,
+                })
+            })
+            .collect();
+        alerts.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.path.cmp(&b.path)));
+
+        let newly: Vec<String> = alerts
+            .iter()
+            .filter(|a| !self.dlq_alerts.iter().any(|old| old.path == a.path))
+            .map(|a| a.path.clone())
+            .collect();
+        if !newly.is_empty() {
+            self.alert_flash_until = Some(Instant::now() + ALERT_FLASH);
+        }
+        self.dlq_alerts = alerts;
+        newly
+    }
+
+    /// Sub-queue paths are stale along with their queue or subscription.
+    pub fn is_entity_stale(&self, path: &str) -> bool {
+        self.stale_entities
+            .contains(entity_path::without_subqueue(path))
+    }
+
+    /// `path` was deleted outside the explorer: mark its node stale, drop its
+    /// detail and peeked messages, and point at `r`. Later loads of the same
+    /// path are skipped rather than failing again.
+    pub fn mark_entity_gone(&mut self, path: &str) {
+        self.stale_entities.insert(path.to_string());
+        let _ = self.peek_cache.take(path);
+        if self.messages_entity.as_deref() == Some(path) {
+            self.messages.clear();
+            self.dlq_messages.clear();
+            self.transferred_messages.clear();
+            self.tab_peeks.clear();
+            self.message_selected = 0;
+            self.dlq_reason_filter = None;
+            self.selected_message_detail = None;
+        }
+        if self.selected_entity().is_some_and(|(p, _)| p == path) {
+            self.detail_view = DetailView::None;
+            self.set_error(format!("'{}' no longer exists — press r to refresh", path));
+        }
+    }
+
+    pub fn is_dlq_alerting(&self, path: &str) -> bool {
+        !path.is_empty() && self.dlq_alerts.iter().any(|a| a.path == path)
+    }
+
+    /// Blink phase for alerting nodes right after they crossed the threshold.
+    pub fn alert_flash_on(&self) -> bool {
+        match self.alert_flash_until {
+            Some(until) => {
+                let left = until.saturating_duration_since(Instant::now());
+                !left.is_zero() && (left.as_millis() / 500) % 2 == 0
+            }
+            None => false,
+        }
+    }
+
+    /// Subscriptions shown in the topic routing diagram, if any.
+    pub fn topic_routes(&self) -> &[SubscriptionRoute] {
+        match self.detail_view {
+            DetailView::Topic(_, _, Some(ref routes)) => routes,
+            _ => &[],
+        }
+    }
+
+    /// Expand the tree down to the entity at `path` and select it.
+    pub fn reveal_entity(&mut self, path: &str) -> bool {
+        let Some(ref mut tree) = self.tree else {
+            return false;
+        };
+        if !expand_to_path(tree, path) {
+            return false;
+        }
+        self.rebuild_flat_nodes();
+        match self.flat_nodes.iter().position(|n| n.path == path) {
+            Some(idx) => {
+                self.tree_selected = idx;
+                self.focus = FocusPanel::Tree;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Forwarding edges known from the tree and from loaded details, with
+    /// targets matched to tree paths (entity names are case-insensitive).
+    pub fn forwarding_edges(&self) -> Vec<ForwardEdge> {
+        let mut nodes = Vec::new();
+        if let Some(ref tree) = self.tree {
+            collect_tree_nodes(tree, &mut nodes);
+        }
+        let mut raw: Vec<(String, String, ForwardKind)> = nodes
+            .iter()
+            .filter_map(|n| {
+                let to = n.forward_to.clone()?;
+                Some((n.path.clone(), to, ForwardKind::Messages))
+            })
+            .collect();
+        for detail in self.detail_cache.values() {
+            if let Some((from, targets)) = detail.forward_edges() {
+                raw.extend(
+                    targets
+                        .into_iter()
+                        .map(|(to, kind)| (from.clone(), to, kind)),
+                );
+            }
+        }
+        let canonical = |path: String| {
+            nodes
+                .iter()
+                .find(|n| n.path.eq_ignore_ascii_case(&path))
+                .map(|n| n.path.clone())
+                .unwrap_or(path)
+        };
+        raw.into_iter()
+            .map(|(from, to, kind)| ForwardEdge {
+                from: canonical(from),
+                to: canonical(to),
+                kind,
+            })
+            .collect()
+    }
+
+    /// Problem with forwarding `source` to `target`, judged from the entities
+    /// and forwarding loaded so far. `None` when the namespace listing is
+    /// partial (not loaded yet, or an entity-scoped key).
+    pub fn forward_chain_problem(&self, source: &str, target: &str) -> Option<ChainProblem> {
+        let target = entity_path::forward_target(target.trim());
+        if target.is_empty()
+            || self
+                .connection_config
+                .as_ref()
+                .is_some_and(|c| c.entity_path.is_some())
+        {
+            return None;
+        }
+        let mut nodes = Vec::new();
+        collect_tree_nodes(self.tree.as_ref()?, &mut nodes);
+        let entities: Vec<String> = nodes
+            .iter()
+            .filter(|n| {
+                matches!(
+                    n.entity_type,
+                    EntityType::Queue | EntityType::Topic | EntityType::Subscription
+                )
+            })
+            .map(|n| n.path.clone())
+            .collect();
+        forward_check::check_chain(source, target, &entities, &self.forwarding_edges())
+    }
+
+    /// Form error refusing to forward `source` to `target`. A chain flagged
+    /// once goes through when it is submitted again unchanged.
+    pub fn forward_chain_error(&mut self, source: &str, target: &str) -> Option<String> {
+        let problem = self.forward_chain_problem(source, target)?;
+        let key = format!("{}→{}", source, target.trim()).to_lowercase();
+        self.forward_acknowledged
+            .insert(key)
+            .then(|| format!("{}. Submit again to save anyway", problem.message()))
+    }
+
+    /// Open the forwarding graph of everything loaded so far.
+    pub fn open_forward_graph(&mut self) {
+        let edges = self.forwarding_edges();
+        if edges.is_empty() {
+            self.set_status("No auto-forwarding found among the loaded entities");
+            return;
+        }
+        self.forward_graph = forward_graph::layout(&edges);
+        self.forward_graph_selected = 0;
+        self.modal = ActiveModal::ForwardGraph;
+    }
+
+    /// Enter in the forwarding graph: select the highlighted entity in the tree.
+    pub fn reveal_forward_graph_selection(&mut self) {
+        let Some(line) = self.forward_graph.get(self.forward_graph_selected) else {
+            return;
+        };
+        let path = line.path.clone();
+        if self.reveal_entity(&path) {
+            self.modal = ActiveModal::None;
+        } else {
+            self.set_error(format!("'{}' is not in this namespace's tree", path));
+        }
+    }
+
+    /// Get the currently selected entity path and type. A dead-letter node
+    /// stands for its queue or subscription, so operations on the selection
+    /// (peek, resend, bulk ops) work on the parent.
+    pub fn selected_entity(&self) -> Option<(&str, &EntityType)> {
+        if self.flat_nodes.is_empty() {
+            return None;
+        }
+        let node = &self.flat_nodes[self.tree_selected];
+        if node.path.is_empty() {
+            None
+        } else if node.entity_type == EntityType::DeadLetterQueue {
+            Some(dead_letter_parent(&node.path))
+        } else {
+            Some((&node.path, &node.entity_type))
+        }
+    }
+
+    /// The selected tree node is a queue's or subscription's "Dead-letter" node.
+    pub fn selected_is_dead_letter_node(&self) -> bool {
+        self.flat_nodes
+            .get(self.tree_selected)
+            .is_some_and(|n| n.entity_type == EntityType::DeadLetterQueue)
+    }
+
+    /// A dead-letter node shows its messages on the DLQ tab; moving off it
+    /// goes back to the tab shown before, unless another tab was picked since.
+    pub fn sync_tab_to_dead_letter_node(&mut self) {
+        if self.selected_is_dead_letter_node() {
+            if self.message_tab != MessageTab::DeadLetter {
+                self.tab_before_dead_letter = Some(self.message_tab);
+                self.message_tab = MessageTab::DeadLetter;
+            }
+        } else if let Some(tab) = self
+            .tab_before_dead_letter
+            .take()
+            .filter(|_| self.message_tab == MessageTab::DeadLetter)
+        {
+            self.message_tab = tab;
+        }
+    }
+
+    /// One-line summary of the selected queue, topic or subscription for the
+    /// tree footer, e.g. "orders · Active · sessions · fwd→repair · 1.2k msgs
+    /// / 14 DLQ". Flags come from the loaded detail; until it arrives only
+    /// the tree's forwarding target and counts are shown.
+    pub fn selected_node_summary(&self) -> Option<String> {
+        let node = self.flat_nodes.get(self.tree_selected)?;
+        if !matches!(
+            node.entity_type,
+            EntityType::Queue | EntityType::Topic | EntityType::Subscription
+        ) {
+            return None;
+        }
+        let detail = Some(&self.detail_view)
+            .filter(|d| {
+                d.entity_path()
+                    .is_some_and(|p| p.eq_ignore_ascii_case(&node.path))
+            })
+            .or_else(|| {
+                self.detail_cache
+                    .iter()
+                    .find(|(path, _)| path.eq_ignore_ascii_case(&node.path))
+                    .map(|(_, detail)| detail)
+            });
+
+        let mut parts = vec![node.label.clone()];
+        let mut counts = node.message_count.zip(node.dlq_count);
+        match detail.and_then(DetailView::summary_flags) {
+            Some(flags) => {
+                parts.extend(flags);
+                counts = detail.and_then(DetailView::runtime_counts).or(counts);
+            }
+            None => {
+                if let Some(to) = &node.forward_to {
+                    parts.push(format!("fwd→{}", to));
+                }
+            }
+        }
+        match (counts, node.message_count) {
+            (Some((active, dlq)), _) => parts.push(format!(
+                "{} msgs / {} DLQ",
+                compact_count(active),
+                compact_count(dlq)
+            )),
+            (None, Some(active)) => parts.push(format!("{} msgs", compact_count(active))),
+            (None, None) => {}
+        }
+        Some(parts.join(" · "))
+    }
+
+    /// Queue or topic that sends and resends for the selected entity go to,
+    /// whichever message tab is active: a subscription (or its DLQ) resolves
+    /// to its topic.
+    pub fn selected_effective_send_entity(&self) -> Option<String> {
+        let (path, _) = self.selected_entity()?;
+        Some(entity_path::send_target(path).to_string())
+    }
+
+    /// Warning for sends to `path` (or its topic) when the entity was last
+    /// seen at or above `QUOTA_WARN_RATIO` of its size quota.
+    pub fn quota_warning(&self, path: &str) -> Option<String> {
+        let target = entity_path::send_target(path);
+        let usage = self
+            .entity_sizes
+            .get(target)
+            .filter(|u| u.is_near_quota())?;
+        Some(format!(
+            "'{}' is nearly full ({}) — sends may fail with QuotaExceeded",
+            target, usage
+        ))
+    }
+
+    /// Settings key under which the last peek count is remembered for the
+    /// selected entity's kind.
+    pub fn peek_kind_key(&self) -> Option<&'static str> {
+        match self.selected_entity()?.1 {
+            EntityType::Queue => Some("queue"),
+            EntityType::Topic => Some("topic"),
+            EntityType::Subscription => Some("subscription"),
+            _ => None,
+        }
+    }
 
-        // Clear message state
-        self.messages.clear();
-        self.dlq_messages.clear();
-        self.message_selected = 0;
-        self.selected_message_detail = None;
-        self.detail_editing = false;
-        self.edit_source_dlq_seq = None;
+    /// Open the peek-count modal pre-filled with the last count used for this kind.
+    pub fn open_peek_count_input(&mut self, dlq: bool) {
+        let count = match self.peek_kind_key() {
+            Some(kind) => self.config.settings.peek_count_for(kind),
+            None => self.config.settings.peek_count,
+        };
+        self.input_buffer = count.to_string();
+        self.input_cursor = self.input_buffer.chars().count();
+        self.modal = ActiveModal::PeekCountInput { custom: false };
+        self.peek_dlq = dlq;
+        self.peek_both = false;
+    }
 
-        // Reset UI state
-        self.focus = FocusPanel::Tree;
-        self.loading = false;
-        self.bg_running = false;
+    /// Open the peek-count modal for a peek of both active and dead-lettered
+    /// messages.
+    pub fn open_peek_both_input(&mut self) {
+        self.open_peek_count_input(false);
+        self.peek_both = true;
+    }
 
-        // Set status
-        self.set_status("Disconnected. Press 'c' to connect, '?' for help");
+    /// What "all" means for the pending peek: the larger of the two counts
+    /// when both tabs are peeked.
+    pub fn pending_peekable_count(&self) -> Option<i64> {
+        if self.peek_both {
+            let active = self.selected_peekable_count(false)?;
+            let dead = self.selected_peekable_count(true)?;
+            Some(active.max(dead))
+        } else {
+            self.selected_peekable_count(self.peek_dlq)
+        }
     }
 
-    /// Rebuild the flat node list from the tree (e.g., after expand/collapse).
-    pub fn rebuild_flat_nodes(&mut self) {
-        if let Some(ref tree) = self.tree {
-            self.flat_nodes = tree.flatten();
-            if self.tree_selected >= self.flat_nodes.len() && !self.flat_nodes.is_empty() {
-                self.tree_selected = self.flat_nodes.len() - 1;
+    /// Active (or DLQ) message count of the selected entity, from the runtime
+    /// info shown in the detail panel. `None` until that detail has loaded.
+    pub fn selected_peekable_count(&self, dlq: bool) -> Option<i64> {
+        let (path, _) = self.selected_entity()?;
+        let (active, dead) = match &self.detail_view {
+            DetailView::Queue(desc, Some(rt)) if desc.name == path => {
+                (rt.active_message_count, rt.dead_letter_message_count)
             }
+            DetailView::Topic(desc, Some(rt), _) if desc.name == path => {
+                (rt.active_message_count, rt.dead_letter_message_count)
+            }
+            DetailView::Subscription(desc, Some(rt))
+                if path == format!("{}/Subscriptions/{}", desc.topic_name, desc.name) =>
+            {
+                (rt.active_message_count, rt.dead_letter_message_count)
+            }
+            _ => return None,
+        };
+        Some(if dlq { dead } else { active })
+    }
+
+    /// Transfer dead-letter count of the selected queue or subscription, from
+    /// the runtime info in the detail panel. Topics have no transfer DLQ.
+    pub fn selected_transfer_dlq_count(&self) -> Option<i64> {
+        let (path, _) = self.selected_entity()?;
+        match &self.detail_view {
+            DetailView::Queue(desc, Some(rt)) if desc.name == path => {
+                Some(rt.transfer_dead_letter_message_count)
+            }
+            DetailView::Subscription(desc, Some(rt))
+                if path == format!("{}/Subscriptions/{}", desc.topic_name, desc.name) =>
+            {
+                Some(rt.transfer_dead_letter_message_count)
+            }
+            _ => None,
         }
     }
 
-    /// Toggle expand/collapse on the selected tree node.
-    pub fn toggle_expand(&mut self) {
-        if self.flat_nodes.is_empty() {
+    fn tab_messages_mut(&mut self, tab: MessageTab) -> &mut Vec<ReceivedMessage> {
+        match tab {
+            MessageTab::Messages => &mut self.messages,
+            MessageTab::DeadLetter => &mut self.dlq_messages,
+            MessageTab::Transferred => &mut self.transferred_messages,
+        }
+    }
+
+    /// Make the message panel show the selected entity: the lists of the
+    /// entity shown so far go into the peek cache and the selected one's come
+    /// out of it. Folders leave the panel as it is.
+    pub fn sync_messages_to_selection(&mut self) {
+        let Some(path) = self.selected_entity().map(|(p, _)| p.to_string()) else {
+            return;
+        };
+        if self.messages_entity.as_deref() == Some(path.as_str()) {
             return;
         }
-        let selected_id = self.flat_nodes[self.tree_selected].id.clone();
-        if let Some(ref mut tree) = self.tree {
-            toggle_node(tree, &selected_id);
+        if let Some(previous) = self.messages_entity.take() {
+            let peeks: Vec<(MessageTab, PeekStamp)> = self.tab_peeks.drain().collect();
+            for (tab, stamp) in peeks {
+                let messages = std::mem::take(self.tab_messages_mut(tab));
+                self.peek_cache.insert(
+                    &previous,
+                    CachedTab {
+                        tab,
+                        messages,
+                        peeked_at: stamp.at,
+                    },
+                );
+            }
         }
-        self.rebuild_flat_nodes();
-    }
 
-    /// Get the currently selected entity path and type.
-    pub fn selected_entity(&self) -> Option<(&str, &EntityType)> {
-        if self.flat_nodes.is_empty() {
-            return None;
+        self.messages.clear();
+        self.dlq_messages.clear();
+        self.transferred_messages.clear();
+        self.tab_peeks.clear();
+        for cached in self.peek_cache.take(&path) {
+            *self.tab_messages_mut(cached.tab) = cached.messages;
+            self.tab_peeks.insert(
+                cached.tab,
+                PeekStamp {
+                    at: cached.peeked_at,
+                    cached: true,
+                },
+            );
         }
-        let node = &self.flat_nodes[self.tree_selected];
-        if node.path.is_empty() {
-            None
-        } else {
-            Some((&node.path, &node.entity_type))
+        self.messages_entity = Some(path);
+        self.message_selected = 0;
+        self.dlq_reason_filter = None;
+        self.dlq_group_selected = 0;
+        self.selected_message_detail = None;
+    }
+
+    /// Put a finished peek of `entity` on its tab, or into the peek cache when
+    /// another entity is shown by now. Returns whether it is shown.
+    pub fn accept_peek(
+        &mut self,
+        entity: &str,
+        tab: MessageTab,
+        messages: Vec<ReceivedMessage>,
+    ) -> bool {
+        self.sync_messages_to_selection();
+        let now = Instant::now();
+        if self.messages_entity.as_deref() != Some(entity) {
+            self.peek_cache.insert(
+                entity,
+                CachedTab {
+                    tab,
+                    messages,
+                    peeked_at: now,
+                },
+            );
+            return false;
         }
+        *self.tab_messages_mut(tab) = messages;
+        self.tab_peeks.insert(
+            tab,
+            PeekStamp {
+                at: now,
+                cached: false,
+            },
+        );
+        true
+    }
+
+    /// Banner for a tab restored from the peek cache.
+    pub fn cached_peek_banner(&self) -> Option<String> {
+        let stamp = self.tab_peeks.get(&self.message_tab)?;
+        stamp.cached.then(|| {
+            format!(
+                "(cached, {} old — press p to refresh) ",
+                short_age(stamp.at.elapsed())
+            )
+        })
+    }
+
+    /// The transfer DLQ tab is only offered when there is something in it
+    /// (or it is already showing).
+    pub fn show_transfer_dlq_tab(&self) -> bool {
+        self.message_tab == MessageTab::Transferred
+            || self.selected_transfer_dlq_count().is_some_and(|n| n > 0)
     }
 
     /// Initialize the send message form fields.
     pub fn init_send_form(&mut self) {
         self.input_fields = vec![
             ("Body".to_string(), String::new()),
-            ("Content-Type".to_string(), "application/json".to_string()),
+            (
+                CONTENT_TYPE_FIELD.to_string(),
+                "application/json".to_string(),
+            ),
             ("Message ID".to_string(), String::new()),
             ("Correlation ID".to_string(), String::new()),
-            ("Session ID".to_string(), String::new()),
-            ("Label".to_string(), String::new()),
+            (SESSION_ID_FIELD.to_string(), String::new()),
+            (LABEL_FIELD.to_string(), String::new()),
             ("TTL (seconds)".to_string(), String::new()),
-            ("Custom Properties (k=v,...)".to_string(), String::new()),
+            ("To".to_string(), String::new()),
+            ("Reply To".to_string(), String::new()),
+            (REPLY_TO_SESSION_FIELD.to_string(), String::new()),
+            ("Partition Key".to_string(), String::new()),
+            (VIA_PARTITION_KEY_FIELD.to_string(), String::new()),
+            (CUSTOM_PROPERTIES_FIELD.to_string(), String::new()),
         ];
+        if self.session_entity_path().is_none() {
+            self.input_fields
+                .retain(|(l, _)| l != REPLY_TO_SESSION_FIELD);
+        }
+        if let Some(defaults) = self.remembered_send_defaults() {
+            let props = defaults
+                .custom_properties
+                .iter()
+                .map(|(k, v)| format!("{}={}", k, v))
+                .collect::<Vec<_>>()
+                .join(",");
+            let fields = [
+                (CONTENT_TYPE_FIELD, defaults.content_type.clone()),
+                (SESSION_ID_FIELD, defaults.session_id.clone()),
+                (LABEL_FIELD, defaults.label.clone()),
+                (CUSTOM_PROPERTIES_FIELD, Some(props)),
+            ];
+            for (label, value) in fields {
+                if let (Some(field), Some(value)) = (self.form_field_mut(label), value) {
+                    *field = value;
+                }
+            }
+        }
         self.input_field_index = 0;
         self.form_cursor = 0;
         self.modal = ActiveModal::SendMessage;
+        self.mark_form_pristine();
+        self.offer_draft(DraftKind::Send);
+    }
+
+    /// Value of the open form's field labelled `label`.
+    fn form_field_mut(&mut self, label: &str) -> Option<&mut String> {
+        self.input_fields
+            .iter_mut()
+            .find(|(l, _)| l == label)
+            .map(|(_, v)| v)
+    }
+
+    /// Record the open form's values as its unedited state.
+    fn mark_form_pristine(&mut self) {
+        self.form_initial = self.input_fields.iter().map(|(_, v)| v.clone()).collect();
+        self.form_discard_armed = false;
+    }
+
+    fn form_is_edited(&self) -> bool {
+        self.input_fields
+            .iter()
+            .map(|(_, v)| v)
+            .ne(self.form_initial.iter())
+    }
+
+    fn form_body_edited(&self) -> bool {
+        self.input_fields.first().map(|(_, v)| v) != self.form_initial.first()
+    }
+
+    /// Which draftable form is open, if any.
+    fn open_draft_kind(&self) -> Option<DraftKind> {
+        if self.detail_editing {
+            Some(DraftKind::Edit)
+        } else if self.modal == ActiveModal::SendMessage {
+            Some(DraftKind::Send)
+        } else {
+            None
+        }
+    }
+
+    /// A blank draft for the form of `kind` as it is open right now.
+    fn draft_for_current_form(&self, kind: DraftKind) -> Option<FormDraft> {
+        let (entity, message_id) = match kind {
+            DraftKind::Send => (self.selected_effective_send_entity()?, None),
+            DraftKind::Edit => (
+                self.selected_entity()?.0.to_string(),
+                self.selected_message_detail
+                    .as_ref()?
+                    .broker_properties
+                    .message_id
+                    .clone(),
+            ),
+        };
+        Some(FormDraft {
+            connection: self.connection_name.clone(),
+            entity,
+            message_id,
+            fields: Vec::new(),
+        })
+    }
+
+    /// Esc on a message form: `true` once it may close. An edited body takes
+    /// a second Esc; edited fields are kept as a draft either way.
+    pub fn confirm_form_close(&mut self) -> bool {
+        let Some(kind) = self.open_draft_kind() else {
+            return true;
+        };
+        if self.form_body_edited() && !self.form_discard_armed {
+            self.form_discard_armed = true;
+            self.set_status("Body edited — press Esc again to close (it is kept as a draft)");
+            return false;
+        }
+        self.form_discard_armed = false;
+        if self.form_is_edited() {
+            if let Some(mut draft) = self.draft_for_current_form(kind) {
+                draft.fields = self.input_fields.clone();
+                self.form_drafts.insert(kind, draft);
+                self.set_status("Draft kept — reopen the form to resume it");
+            }
+        }
+        true
+    }
+
+    /// Ask whether to resume the draft of `kind` if it belongs to the form
+    /// just opened.
+    fn offer_draft(&mut self, kind: DraftKind) {
+        let (Some(draft), Some(current)) = (
+            self.form_drafts.get(&kind),
+            self.draft_for_current_form(kind),
+        ) else {
+            return;
+        };
+        if draft.connection == current.connection
+            && draft.entity == current.entity
+            && draft.message_id == current.message_id
+        {
+            self.modal = ActiveModal::ResumeDraft { kind };
+        }
+    }
+
+    /// Fill the open form with its draft (`resume`) or drop the draft, then
+    /// return to the form.
+    pub fn settle_draft(&mut self, kind: DraftKind, resume: bool) {
+        if let Some(draft) = self.form_drafts.remove(&kind) {
+            if resume {
+                self.input_fields = draft.fields;
+                self.input_field_index = 0;
+                self.form_cursor = self.input_fields.first().map_or(0, |(_, v)| v.len());
+            }
+        }
+        self.modal = match kind {
+            DraftKind::Send => ActiveModal::SendMessage,
+            DraftKind::Edit => ActiveModal::None,
+        };
+    }
+
+    /// The form of `kind` was submitted successfully; its draft is obsolete.
+    pub fn clear_draft(&mut self, kind: DraftKind) {
+        self.form_drafts.remove(&kind);
+    }
+
+    /// Send-form defaults remembered for the selected entity on this connection.
+    fn remembered_send_defaults(&self) -> Option<&SendDefaults> {
+        let connection = self.connection_name.as_deref()?;
+        let entity = self.selected_effective_send_entity()?;
+        self.config.send_defaults_for(connection, &entity)
+    }
+
+    /// The send form's remembered fields, keyed by the entity `msg` goes to.
+    /// Custom property values are blanked unless the user opted in to keeping them.
+    pub fn send_defaults_for_message(&self, entity: &str, msg: &ServiceBusMessage) -> SendDefaults {
+        let keep_values = self.config.settings.remember_property_values;
+        SendDefaults {
+            connection: self.connection_name.clone().unwrap_or_default(),
+            entity: entity.to_string(),
+            content_type: msg.content_type.clone(),
+            label: msg.label.clone(),
+            session_id: msg.session_id.clone(),
+            custom_properties: msg
+                .custom_properties
+                .iter()
+                .map(|(k, v)| {
+                    (
+                        k.clone(),
+                        if keep_values {
+                            v.clone()
+                        } else {
+                            String::new()
+                        },
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    /// Remember the fields of a successful send for the next send to that entity.
+    pub fn remember_send_defaults(&mut self, defaults: SendDefaults) {
+        if defaults.connection.is_empty()
+            || self
+                .config
+                .send_defaults_for(&defaults.connection, &defaults.entity)
+                == Some(&defaults)
+        {
+            return;
+        }
+        self.config.remember_send_defaults(defaults);
+        self.save_config();
+    }
+
+    /// Drop the selected entity's remembered send fields and reset them in the
+    /// open send form.
+    pub fn forget_send_defaults(&mut self) {
+        let (Some(connection), Some(entity)) = (
+            self.connection_name.clone(),
+            self.selected_effective_send_entity(),
+        ) else {
+            return;
+        };
+        if !self.config.forget_send_defaults(&connection, &entity) {
+            self.set_status(format!("No remembered fields for {}", entity));
+            return;
+        }
+        self.save_config();
+        let blank = [
+            (CONTENT_TYPE_FIELD, "application/json"),
+            (SESSION_ID_FIELD, ""),
+            (LABEL_FIELD, ""),
+            (CUSTOM_PROPERTIES_FIELD, ""),
+        ];
+        for (label, value) in blank {
+            if let Some(field) = self.form_field_mut(label) {
+                *field = value.to_string();
+            }
+        }
+        self.form_cursor = self
+            .input_fields
+            .get(self.input_field_index)
+            .map_or(0, |(_, v)| v.len());
+        self.set_status(format!("Forgot remembered send fields for {}", entity));
     }
 
     /// Enter inline WYSIWYG edit mode in the message detail view.
     pub fn init_detail_edit(&mut self) {
         if let Some(ref msg) = self.selected_message_detail {
-            self.edit_source_dlq_seq = if self.message_tab == MessageTab::DeadLetter {
-                msg.broker_properties.sequence_number
+            self.edit_source_dlq_ref = if self.message_tab == MessageTab::DeadLetter {
+                MessageRef::from_message(msg)
             } else {
                 None
             };
             let msg = msg.clone();
             self.populate_edit_fields(&msg);
             self.detail_editing = true;
+            self.mark_form_pristine();
+            self.offer_draft(DraftKind::Edit);
         }
     }
 
@@ -437,11 +3771,16 @@ impl App {
             .map(|(k, v)| format!("{}={}", k, v))
             .collect::<Vec<_>>()
             .join(",");
+        let props = &msg.broker_properties;
+        let remaining_ttl = msg
+            .remaining_ttl_secs(chrono::Utc::now())
+            .map(|secs| secs.to_string())
+            .unwrap_or_default();
 
         self.input_fields = vec![
             ("Body".to_string(), msg.body.clone()),
             (
-                "Content-Type".to_string(),
+                CONTENT_TYPE_FIELD.to_string(),
                 msg.broker_properties
                     .content_type
                     .clone()
@@ -459,34 +3798,54 @@ impl App {
                     .unwrap_or_default(),
             ),
             (
-                "Session ID".to_string(),
+                SESSION_ID_FIELD.to_string(),
                 msg.broker_properties.session_id.clone().unwrap_or_default(),
             ),
             (
-                "Label".to_string(),
+                LABEL_FIELD.to_string(),
                 msg.broker_properties.label.clone().unwrap_or_default(),
             ),
-            ("TTL (seconds)".to_string(), String::new()),
-            ("Custom Properties (k=v,...)".to_string(), custom_props_str),
+            ("TTL (seconds)".to_string(), remaining_ttl),
+            ("To".to_string(), props.to.clone().unwrap_or_default()),
+            (
+                "Reply To".to_string(),
+                props.reply_to.clone().unwrap_or_default(),
+            ),
+            (
+                REPLY_TO_SESSION_FIELD.to_string(),
+                props.reply_to_session_id.clone().unwrap_or_default(),
+            ),
+            (
+                "Partition Key".to_string(),
+                props.partition_key.clone().unwrap_or_default(),
+            ),
+            (
+                VIA_PARTITION_KEY_FIELD.to_string(),
+                props.via_partition_key.clone().unwrap_or_default(),
+            ),
+            (CUSTOM_PROPERTIES_FIELD.to_string(), custom_props_str),
+            (DELAY_FIELD.to_string(), String::new()),
         ];
+        if props.reply_to_session_id.is_none() && self.session_entity_path().is_none() {
+            self.input_fields
+                .retain(|(l, _)| l != REPLY_TO_SESSION_FIELD);
+        }
         self.input_field_index = 0;
         self.form_cursor = self.input_fields[0].1.len();
     }
 
     /// Build a ServiceBusMessage from the current send form fields.
     pub fn build_message_from_form(&self) -> ServiceBusMessage {
-        let get =
-            |idx: usize| -> Option<String> {
-                self.input_fields.get(idx).and_then(|(_, v)| {
-                    if v.is_empty() {
-                        None
-                    } else {
-                        Some(v.clone())
-                    }
-                })
-            };
+        // Fields are looked up by label: the form leaves out fields that
+        // don't apply to the entity
+        let get = |label: &str| -> Option<String> {
+            self.input_fields
+                .iter()
+                .find(|(l, _)| l == label)
+                .and_then(|(_, v)| (!v.is_empty()).then(|| v.clone()))
+        };
 
-        let custom_props: Vec<(String, String)> = get(7)
+        let custom_props: Vec<(String, String)> = get(CUSTOM_PROPERTIES_FIELD)
             .map(|s| {
                 s.split(',')
                     .filter_map(|pair| {
@@ -504,18 +3863,41 @@ impl App {
             .unwrap_or_default();
 
         ServiceBusMessage {
-            body: get(0).unwrap_or_default(),
-            content_type: get(1),
-            message_id: get(2).or_else(|| Some(uuid::Uuid::new_v4().to_string())),
-            correlation_id: get(3),
-            session_id: get(4),
-            label: get(5),
-            time_to_live: get(6),
+            body: get("Body").unwrap_or_default(),
+            content_type: get(CONTENT_TYPE_FIELD),
+            message_id: get("Message ID").or_else(|| Some(uuid::Uuid::new_v4().to_string())),
+            correlation_id: get("Correlation ID"),
+            session_id: get(SESSION_ID_FIELD),
+            label: get(LABEL_FIELD),
+            time_to_live: get("TTL (seconds)"),
+            to: get("To"),
+            reply_to: get("Reply To"),
+            reply_to_session_id: get(REPLY_TO_SESSION_FIELD),
+            partition_key: get("Partition Key"),
+            via_partition_key: get(VIA_PARTITION_KEY_FIELD),
             custom_properties: custom_props,
-            ..Default::default()
+            // submit_form refuses delays that don't parse, and parsed ones fit
+            scheduled_enqueue_time: self
+                .form_delay()
+                .and_then(Result::ok)
+                .and_then(|delay| {
+                    delay::DelaySpec {
+                        delay,
+                        spread: None,
+                    }
+                    .enqueue_time(chrono::Utc::now(), 0, 1)
+                    .ok()
+                })
+                .map(delay::enqueue_time_header),
         }
     }
 
+    /// The edit form's resend delay, if one was entered.
+    pub fn form_delay(&self) -> Option<Result<Duration, String>> {
+        let (_, value) = self.input_fields.iter().find(|(l, _)| l == DELAY_FIELD)?;
+        (!value.trim().is_empty()).then(|| delay::parse_delay(value))
+    }
+
     /// Initialize create queue form.
     pub fn init_create_queue_form(&mut self) {
         self.input_fields = vec![
@@ -527,6 +3909,15 @@ impl App {
             ("Requires Session".to_string(), "false".to_string()),
             ("Enable Partitioning".to_string(), "false".to_string()),
             ("Dead-letter on Expiry".to_string(), "false".to_string()),
+            ("Duplicate Detection".to_string(), "false".to_string()),
+            (
+                "Duplicate Detection Window".to_string(),
+                "PT10M".to_string(),
+            ),
+            ("Auto-delete on Idle".to_string(), String::new()),
+            ("Forward To".to_string(), String::new()),
+            ("Forward DLQ To".to_string(), String::new()),
+            ("User Metadata".to_string(), String::new()),
         ];
         self.input_field_index = 0;
         self.form_cursor = 0;
@@ -557,17 +3948,42 @@ impl App {
             requires_session: get_bool(5),
             enable_partitioning: get_bool(6),
             dead_lettering_on_message_expiration: get_bool(7),
+            requires_duplicate_detection: get_bool(8),
+            duplicate_detection_history_time_window: get_str(9),
+            auto_delete_on_idle: get_str(10),
+            forward_to: get_str(11),
+            forward_dead_lettered_messages_to: get_str(12),
+            user_metadata: get_str(13),
             ..Default::default()
         }
     }
 
+    /// Whether the connected namespace is known to be Basic tier (queues only).
+    pub fn is_basic_tier(&self) -> bool {
+        self.namespace_info
+            .as_ref()
+            .is_some_and(NamespaceInfo::is_basic)
+    }
+
     /// Initialize create topic form.
     pub fn init_create_topic_form(&mut self) {
+        if self.is_basic_tier() {
+            self.set_error("Topics are not available on the Basic tier");
+            return;
+        }
         self.input_fields = vec![
             ("Topic Name".to_string(), String::new()),
             ("Max Size (MB)".to_string(), "1024".to_string()),
             ("Default TTL".to_string(), "P14D".to_string()),
             ("Enable Partitioning".to_string(), "false".to_string()),
+            ("Duplicate Detection".to_string(), "false".to_string()),
+            (
+                "Duplicate Detection Window".to_string(),
+                "PT10M".to_string(),
+            ),
+            ("Support Ordering".to_string(), String::new()),
+            ("Auto-delete on Idle".to_string(), String::new()),
+            ("User Metadata".to_string(), String::new()),
         ];
         self.input_field_index = 0;
         self.form_cursor = 0;
@@ -591,6 +4007,11 @@ impl App {
             max_size_in_megabytes: get_str(1).and_then(|v| v.parse().ok()),
             default_message_time_to_live: get_str(2),
             enable_partitioning: get_str(3).and_then(|v| v.parse().ok()),
+            requires_duplicate_detection: get_str(4).and_then(|v| v.parse().ok()),
+            duplicate_detection_history_time_window: get_str(5),
+            support_ordering: get_str(6).and_then(|v| v.parse().ok()),
+            auto_delete_on_idle: get_str(7),
+            user_metadata: get_str(8),
             ..Default::default()
         }
     }
@@ -605,6 +4026,8 @@ impl App {
             ("Max Delivery Count".to_string(), "10".to_string()),
             ("Requires Session".to_string(), "false".to_string()),
             ("Dead-letter on Expiry".to_string(), "false".to_string()),
+            ("Forward To".to_string(), String::new()),
+            ("Forward DLQ To".to_string(), String::new()),
         ];
         self.input_field_index = 1; // Skip topic name (pre-filled)
         self.form_cursor = 0;
@@ -631,10 +4054,319 @@ impl App {
             max_delivery_count: get_str(4).and_then(|v| v.parse().ok()),
             requires_session: get_str(5).and_then(|v| v.parse().ok()),
             dead_lettering_on_message_expiration: get_str(6).and_then(|v| v.parse().ok()),
+            forward_to: get_str(7),
+            forward_dead_lettered_messages_to: get_str(8),
             ..Default::default()
         }
     }
 
+    /// Initialize the property edit form from the entity in the detail panel.
+    /// Fields are a prefix of the create forms so `build_*_from_form` can read
+    /// them back; settings past the prefix are kept from the current description.
+    pub fn init_edit_entity_form(&mut self) -> bool {
+        let opt_str = |v: &Option<String>| v.clone().unwrap_or_default();
+        let opt = |v: Option<String>| v.unwrap_or_default();
+
+        let (entity_type, path, fields, first_editable) = match &self.detail_view {
+            DetailView::Queue(q, _) => (
+                EntityType::Queue,
+                q.name.clone(),
+                vec![
+                    ("Queue Name".to_string(), q.name.clone()),
+                    (
+                        "Max Size (MB)".to_string(),
+                        opt(q.max_size_in_megabytes.map(|v| v.to_string())),
+                    ),
+                    ("Lock Duration".to_string(), opt_str(&q.lock_duration)),
+                    (
+                        "Default TTL".to_string(),
+                        opt_str(&q.default_message_time_to_live),
+                    ),
+                    (
+                        "Max Delivery Count".to_string(),
+                        opt(q.max_delivery_count.map(|v| v.to_string())),
+                    ),
+                    (
+                        "Requires Session".to_string(),
+                        opt(q.requires_session.map(|v| v.to_string())),
+                    ),
+                    (
+                        "Enable Partitioning".to_string(),
+                        opt(q.enable_partitioning.map(|v| v.to_string())),
+                    ),
+                    (
+                        "Dead-letter on Expiry".to_string(),
+                        opt(q
+                            .dead_lettering_on_message_expiration
+                            .map(|v| v.to_string())),
+                    ),
+                ],
+                1,
+            ),
+            DetailView::Topic(t, ..) => (
+                EntityType::Topic,
+                t.name.clone(),
+                vec![
+                    ("Topic Name".to_string(), t.name.clone()),
+                    (
+                        "Max Size (MB)".to_string(),
+                        opt(t.max_size_in_megabytes.map(|v| v.to_string())),
+                    ),
+                    (
+                        "Default TTL".to_string(),
+                        opt_str(&t.default_message_time_to_live),
+                    ),
+                    (
+                        "Enable Partitioning".to_string(),
+                        opt(t.enable_partitioning.map(|v| v.to_string())),
+                    ),
+                ],
+                1,
+            ),
+            DetailView::Subscription(s, _) => (
+                EntityType::Subscription,
+                format!("{}/Subscriptions/{}", s.topic_name, s.name),
+                vec![
+                    ("Topic".to_string(), s.topic_name.clone()),
+                    ("Subscription Name".to_string(), s.name.clone()),
+                    ("Lock Duration".to_string(), opt_str(&s.lock_duration)),
+                    (
+                        "Default TTL".to_string(),
+                        opt_str(&s.default_message_time_to_live),
+                    ),
+                    (
+                        "Max Delivery Count".to_string(),
+                        opt(s.max_delivery_count.map(|v| v.to_string())),
+                    ),
+                    (
+                        "Requires Session".to_string(),
+                        opt(s.requires_session.map(|v| v.to_string())),
+                    ),
+                    (
+                        "Dead-letter on Expiry".to_string(),
+                        opt(s
+                            .dead_lettering_on_message_expiration
+                            .map(|v| v.to_string())),
+                    ),
+                ],
+                2,
+            ),
+            DetailView::Namespace(..) | DetailView::None => return false,
+        };
+
+        self.input_fields = fields;
+        self.input_field_index = first_editable; // Name fields are read-only
+        self.form_cursor = self.input_fields[first_editable].1.len();
+        self.modal = ActiveModal::EditEntity { entity_type, path };
+        true
+    }
+
+    /// Whether the form field at `idx` is shown but cannot be edited: the
+    /// name and creation-only settings of an entity being edited.
+    pub fn is_field_read_only(&self, idx: usize) -> bool {
+        matches!(self.modal, ActiveModal::EditEntity { .. })
+            && self
+                .input_fields
+                .get(idx)
+                .is_some_and(|(label, _)| IMMUTABLE_ENTITY_FIELDS.contains(&label.as_str()))
+    }
+
+    /// Move to the next (or previous) editable form field, if there is one.
+    pub fn step_form_field(&mut self, forward: bool) {
+        let next = if forward {
+            (self.input_field_index + 1..self.input_fields.len())
+                .find(|&idx| !self.is_field_read_only(idx))
+        } else {
+            (0..self.input_field_index)
+                .rev()
+                .find(|&idx| !self.is_field_read_only(idx))
+        };
+        if let Some(idx) = next {
+            self.input_field_index = idx;
+            self.form_cursor = self.input_fields[idx].1.len();
+        }
+    }
+
+    /// Queues and subscriptions in the loaded tree, in tree order.
+    pub fn searchable_entities(&self) -> Vec<String> {
+        let mut out = Vec::new();
+        if let Some(ref tree) = self.tree {
+            collect_message_holders(tree, &mut out);
+        }
+        out
+    }
+
+    /// Select the entity of the highlighted search hit in the tree.
+    pub fn jump_to_search_hit(&mut self) {
+        let Some(hit) = self
+            .search_results
+            .as_ref()
+            .and_then(|r| r.hits.get(self.search_selected))
+            .cloned()
+        else {
+            return;
+        };
+        self.modal = ActiveModal::None;
+        if !self.reveal_entity(&hit.entity_path) {
+            self.set_error(format!(
+                "'{}' is not in the tree — refresh first",
+                hit.entity_path
+            ));
+            return;
+        }
+        let key = if hit.is_dlq { "d" } else { "p" };
+        let seq = hit
+            .sequence_number
+            .map(|n| format!("sequence number {}", n))
+            .unwrap_or_else(|| "the message".to_string());
+        self.set_status(format!("Press {} to peek and look for {}", key, seq));
+    }
+
+    /// Path of the session-enabled queue/subscription in the detail panel.
+    pub fn session_entity_path(&self) -> Option<String> {
+        match &self.detail_view {
+            DetailView::Queue(q, _) if q.requires_session == Some(true) => Some(q.name.clone()),
+            DetailView::Subscription(s, _) if s.requires_session == Some(true) => {
+                Some(format!("{}/Subscriptions/{}", s.topic_name, s.name))
+            }
+            _ => None,
+        }
+    }
+
+    /// Show the selected session's messages in the Messages tab.
+    pub fn open_selected_session(&mut self) {
+        let Some(ref browse) = self.session_browse else {
+            return;
+        };
+        let Some((session_id, _)) = browse.sessions.get(self.session_selected) else {
+            return;
+        };
+        let session_id = session_id.clone();
+        self.messages = browse
+            .messages
+            .iter()
+            .filter(|m| m.broker_properties.session_id.as_deref() == Some(session_id.as_str()))
+            .cloned()
+            .collect();
+        self.message_tab = MessageTab::Messages;
+        self.message_selected = 0;
+        self.selected_message_detail = None;
+        self.focus = FocusPanel::Messages;
+        self.modal = ActiveModal::None;
+        self.set_status(format!(
+            "{} message(s) in session '{}'",
+            self.messages.len(),
+            session_id
+        ));
+    }
+
+    /// Open the forward-to prompt for the queue/subscription in the detail
+    /// panel, prefilled with its current target.
+    pub fn open_forward_to_input(&mut self) -> bool {
+        let (entity_type, path, current) = match &self.detail_view {
+            DetailView::Queue(q, _) => (EntityType::Queue, q.name.clone(), &q.forward_to),
+            DetailView::Subscription(s, _) => (
+                EntityType::Subscription,
+                format!("{}/Subscriptions/{}", s.topic_name, s.name),
+                &s.forward_to,
+            ),
+            _ => return false,
+        };
+        self.input_buffer = forward_label(current).unwrap_or_default();
+        self.input_cursor = self.input_buffer.chars().count();
+        self.form_error = None;
+        self.modal = ActiveModal::ForwardToInput { entity_type, path };
+        true
+    }
+
+    /// Open the status picker for the entity in the detail panel, with its
+    /// current status highlighted.
+    pub fn open_status_select(&mut self) -> bool {
+        let (entity_type, path, current) = match &self.detail_view {
+            DetailView::Queue(q, _) => (EntityType::Queue, q.name.clone(), q.status.clone()),
+            DetailView::Topic(t, ..) => (EntityType::Topic, t.name.clone(), t.status.clone()),
+            DetailView::Subscription(s, _) => (
+                EntityType::Subscription,
+                format!("{}/Subscriptions/{}", s.topic_name, s.name),
+                s.status.clone(),
+            ),
+            DetailView::Namespace(..) | DetailView::None => return false,
+        };
+        self.status_selected = status_options(&entity_type)
+            .iter()
+            .position(|s| Some(*s) == current.as_deref())
+            .unwrap_or(0);
+        self.modal = ActiveModal::StatusSelect { entity_type, path };
+        true
+    }
+
+    /// Queue description for an update: form values over the current description,
+    /// so settings the form does not show are sent back unchanged.
+    pub fn build_queue_update(&self) -> Option<QueueDescription> {
+        let DetailView::Queue(ref current, _) = self.detail_view else {
+            return None;
+        };
+        let form = self.build_queue_from_form();
+        Some(QueueDescription {
+            name: current.name.clone(),
+            max_size_in_megabytes: form.max_size_in_megabytes,
+            lock_duration: form.lock_duration,
+            default_message_time_to_live: form.default_message_time_to_live,
+            max_delivery_count: form.max_delivery_count,
+            dead_lettering_on_message_expiration: form.dead_lettering_on_message_expiration,
+            ..current.clone()
+        })
+    }
+
+    pub fn build_topic_update(&self) -> Option<TopicDescription> {
+        let DetailView::Topic(ref current, ..) = self.detail_view else {
+            return None;
+        };
+        let form = self.build_topic_from_form();
+        Some(TopicDescription {
+            name: current.name.clone(),
+            max_size_in_megabytes: form.max_size_in_megabytes,
+            default_message_time_to_live: form.default_message_time_to_live,
+            ..current.clone()
+        })
+    }
+
+    pub fn build_subscription_update(&self) -> Option<SubscriptionDescription> {
+        let DetailView::Subscription(ref current, _) = self.detail_view else {
+            return None;
+        };
+        let form = self.build_subscription_from_form();
+        Some(SubscriptionDescription {
+            name: current.name.clone(),
+            topic_name: current.topic_name.clone(),
+            lock_duration: form.lock_duration,
+            default_message_time_to_live: form.default_message_time_to_live,
+            max_delivery_count: form.max_delivery_count,
+            dead_lettering_on_message_expiration: form.dead_lettering_on_message_expiration,
+            ..current.clone()
+        })
+    }
+
+    /// Elements the edit form changes on the entity in the detail panel, as
+    /// `(element, text)` patches. `None` when its details are no longer loaded.
+    pub fn entity_update_patches(&self) -> Option<Vec<(String, String)>> {
+        match &self.detail_view {
+            DetailView::Queue(current, _) => Some(management::queue_patches(
+                current,
+                &self.build_queue_update()?,
+            )),
+            DetailView::Topic(current, ..) => Some(management::topic_patches(
+                current,
+                &self.build_topic_update()?,
+            )),
+            DetailView::Subscription(current, _) => Some(management::subscription_patches(
+                current,
+                &self.build_subscription_update()?,
+            )),
+            DetailView::Namespace(..) | DetailView::None => None,
+        }
+    }
+
     /// Initialize edit subscription filter form.
     pub fn init_edit_subscription_filter_form(
         &mut self,
@@ -668,25 +4400,215 @@ impl App {
             .filter(|v| !v.is_empty())
             .unwrap_or_else(|| "1=1".to_string());
 
-        (rule_name, sql_expression)
+        (rule_name, sql_expression)
+    }
+
+    /// The discovered namespace we are connected to (Azure AD connections made
+    /// through discovery), plus the credential to query ARM with.
+    pub fn connected_arm_namespace(
+        &self,
+    ) -> Option<(
+        DiscoveredNamespace,
+        Arc<dyn azure_core::credentials::TokenCredential>,
+    )> {
+        let cfg = self.connection_config.as_ref()?;
+        let AuthMode::AzureAd { ref credential } = cfg.auth_mode else {
+            return None;
+        };
+        let ns = self
+            .discovered_namespaces
+            .iter()
+            .find(|ns| ns.fqdn.eq_ignore_ascii_case(&cfg.namespace))?;
+        Some((ns.clone(), credential.clone()))
+    }
+
+    /// Record a health check. Checks started with `H` always report; periodic
+    /// ones only when the namespace stops or starts answering.
+    pub fn apply_health_check(&mut self, rtt_ms: u64, status: String) {
+        self.health_check_in_flight = false;
+        let was_ok = self.health_check.as_ref().is_none_or(HealthCheck::is_ok);
+        let check = HealthCheck { rtt_ms, status };
+        if std::mem::take(&mut self.health_check_manual) || was_ok != check.is_ok() {
+            if check.is_ok() {
+                self.set_status(format!("Namespace reachable (RTT: {}ms)", rtt_ms));
+            } else {
+                self.set_error(format!("Namespace unreachable: {}", check.status));
+            }
+        }
+        self.health_check = Some(check);
+    }
+
+    /// Metrics of queue or topic `name`, if they were loaded for it.
+    pub fn metrics_for(&self, name: &str) -> Option<EntityMetrics> {
+        self.entity_metrics
+            .as_ref()
+            .filter(|(entity, _)| entity == name)
+            .map(|(_, metrics)| *metrics)
+    }
+
+    /// Azure Portal URL of the entity at `path`. Only known for namespaces
+    /// found via discovery, which is where the subscription and resource
+    /// group come from.
+    pub fn entity_portal_url(&self, path: &str, entity_type: &EntityType) -> Option<String> {
+        let cfg = self.connection_config.as_ref()?;
+        let ns = self
+            .discovered_namespaces
+            .iter()
+            .find(|ns| ns.fqdn.eq_ignore_ascii_case(&cfg.namespace))?;
+        Some(entity_path::portal_url(
+            &ns.subscription_id,
+            ns.resource_group.as_deref()?,
+            &ns.name,
+            path,
+            *entity_type == EntityType::Topic,
+        ))
+    }
+
+    /// `Y` in the tree: copy the selected entity's path, or ask whether to
+    /// copy the path or the portal URL when the latter is known.
+    pub fn copy_selected_entity_path(&mut self) {
+        let Some((path, entity_type)) = self
+            .selected_entity()
+            .map(|(path, kind)| (path.to_string(), kind.clone()))
+        else {
+            return;
+        };
+        if !matches!(
+            entity_type,
+            EntityType::Queue | EntityType::Topic | EntityType::Subscription
+        ) {
+            self.set_status("Select a queue, topic, or subscription to copy its path");
+            return;
+        }
+        match self.entity_portal_url(&path, &entity_type) {
+            Some(portal_url) => self.modal = ActiveModal::CopyFormat { path, portal_url },
+            None => self.copy_text(&path, "Path"),
+        }
+    }
+
+    /// Copy `text` to the clipboard and report it as `what`.
+    pub fn copy_text(&mut self, text: &str, what: &str) {
+        match crate::clipboard::copy_to_clipboard(text) {
+            Ok(()) => self.set_status(format!("{} copied: {}", what, text)),
+            Err(e) => self.set_error(format!("Copy failed: {}", e)),
+        }
+    }
+
+    /// Discovered namespaces matching `namespace_search` (by namespace or
+    /// subscription name), in list order: grouped by subscription.
+    pub fn filtered_namespaces(&self) -> Vec<&DiscoveredNamespace> {
+        let query = self.namespace_search.trim();
+        let mut namespaces: Vec<&DiscoveredNamespace> = self
+            .discovered_namespaces
+            .iter()
+            .filter(|ns| {
+                fuzzy_score(query, &ns.name).is_some()
+                    || fuzzy_score(query, &ns.subscription_name).is_some()
+            })
+            .collect();
+        namespaces.sort_by(|a, b| a.subscription_name.cmp(&b.subscription_name));
+        namespaces
     }
 
-    /// Start namespace discovery flow.
+    /// Start namespace discovery flow. Once the signed-in tenant and
+    /// account are known, a fresh cached result of theirs is shown instead
+    /// of discovering again.
     pub fn start_namespace_discovery(&mut self) {
+        self.begin_namespace_discovery(false);
+    }
+
+    /// Discover namespaces again, ignoring the cache (`Ctrl+R`).
+    pub fn refresh_namespace_discovery(&mut self) {
+        self.begin_namespace_discovery(true);
+    }
+
+    fn begin_namespace_discovery(&mut self, skip_cache: bool) {
         self.discovered_namespaces.clear();
         self.discovery_warnings.clear();
+        self.discovery_cached_minutes = None;
+        self.discovery_skip_cache = skip_cache;
         self.namespace_list_state = 0;
+        self.namespace_search.clear();
         self.modal = ActiveModal::NamespaceDiscovery {
             state: DiscoveryState::Loading,
         };
         self.set_status("Discovering namespaces...");
     }
 
+    /// Cached discoveries the running discovery may answer with: the fresh
+    /// ones, or none after `Ctrl+R`.
+    pub fn usable_discovery_cache(&self, now: DateTime<Utc>) -> Vec<DiscoveryCacheEntry> {
+        if self.discovery_skip_cache {
+            return Vec::new();
+        }
+        let ttl = self.config.settings.discovery_cache_ttl_minutes;
+        self.config
+            .discovery_cache
+            .iter()
+            .filter(|entry| entry.is_fresh(ttl, now))
+            .cloned()
+            .collect()
+    }
+
+    /// A finished discovery run as `identity`. `cached_minutes` is the age
+    /// of a cached result; a new result that found anything is cached for
+    /// its identity, replacing only that identity's entry.
+    pub fn finish_namespace_discovery(
+        &mut self,
+        identity: Option<ArmIdentity>,
+        result: DiscoveryResult,
+        cached_minutes: Option<i64>,
+    ) {
+        let fetched = cached_minutes.is_none() && !result.namespaces.is_empty();
+        if let Some(identity) = identity.filter(|_| fetched) {
+            self.config.cache_discovery(DiscoveryCacheEntry::new(
+                &identity,
+                result.clone(),
+                Utc::now(),
+            ));
+            self.save_config();
+        }
+        self.show_discovered_namespaces(result);
+        self.discovery_cached_minutes = cached_minutes;
+    }
+
+    fn show_discovered_namespaces(&mut self, result: DiscoveryResult) {
+        self.discovered_namespaces = result.namespaces;
+        self.discovery_warnings = result.errors;
+        self.discovery_cached_minutes = None;
+
+        if self.discovered_namespaces.is_empty() {
+            let error_msg = if !self.discovery_warnings.is_empty() {
+                self.discovery_warnings.join("; ")
+            } else {
+                "No Service Bus namespaces found in your subscriptions".to_string()
+            };
+
+            self.modal = ActiveModal::NamespaceDiscovery {
+                state: DiscoveryState::Error(error_msg.clone()),
+            };
+            self.set_status(format!("Discovery complete: {}", error_msg));
+        } else {
+            self.modal = ActiveModal::NamespaceDiscovery {
+                state: DiscoveryState::List,
+            };
+            self.set_status(format!(
+                "Found {} namespace(s). Type to filter, Tab for manual entry.",
+                self.discovered_namespaces.len()
+            ));
+        }
+    }
+
     /// Fetch entity list from a destination connection for copy target selection.
     pub async fn fetch_destination_entities(
         config: crate::client::ConnectionConfig,
+        http: Arc<reqwest::Client>,
     ) -> crate::client::Result<Vec<(String, EntityType)>> {
-        let mgmt = crate::client::ManagementClient::new(config);
+        let mgmt = crate::client::ManagementClient::new(config, http);
+        if let Some(path) = mgmt.entity_path() {
+            let entity = mgmt.get_entity(path).await?;
+            return Ok(vec![(path.to_string(), entity.entity_type())]);
+        }
         let mut entities = Vec::new();
 
         // Fetch queues and topics in parallel
@@ -723,16 +4645,201 @@ fn toggle_node(node: &mut TreeNode, id: &str) -> bool {
     false
 }
 
+/// (path, DLQ count) of every queue and subscription in the tree.
+fn collect_dlq_counts(node: &TreeNode, out: &mut Vec<(String, i64)>) {
+    if matches!(
+        node.entity_type,
+        EntityType::Queue | EntityType::Subscription
+    ) {
+        if let Some(dlq) = node.dlq_count {
+            out.push((node.path.clone(), dlq));
+        }
+    }
+    for child in &node.children {
+        collect_dlq_counts(child, out);
+    }
+}
+
+/// Path of every queue and subscription in the tree — the entities that hold
+/// messages, in tree order.
+fn collect_message_holders(node: &TreeNode, out: &mut Vec<String>) {
+    if matches!(
+        node.entity_type,
+        EntityType::Queue | EntityType::Subscription
+    ) {
+        out.push(node.path.clone());
+    }
+    for child in &node.children {
+        collect_message_holders(child, out);
+    }
+}
+
+fn collect_unlisted_topics(node: &TreeNode, out: &mut Vec<String>) {
+    if node.entity_type == EntityType::Topic && node.counts_pending && node.children.is_empty() {
+        out.push(node.path.clone());
+    }
+    for child in &node.children {
+        collect_unlisted_topics(child, out);
+    }
+}
+
+fn find_entity<'a>(node: &'a TreeNode, path: &str, kind: &EntityType) -> Option<&'a TreeNode> {
+    if node.entity_type == *kind && node.path == path {
+        return Some(node);
+    }
+    node.children
+        .iter()
+        .find_map(|child| find_entity(child, path, kind))
+}
+
+fn find_entity_mut<'a>(
+    node: &'a mut TreeNode,
+    path: &str,
+    kind: &EntityType,
+) -> Option<&'a mut TreeNode> {
+    if node.entity_type == *kind && node.path == path {
+        return Some(node);
+    }
+    node.children
+        .iter_mut()
+        .find_map(|child| find_entity_mut(child, path, kind))
+}
+
+/// (path, type) of every queue and topic in the tree — valid send destinations.
+fn collect_destinations(node: &TreeNode, out: &mut Vec<(String, EntityType)>) {
+    if matches!(node.entity_type, EntityType::Queue | EntityType::Topic) {
+        out.push((node.path.clone(), node.entity_type.clone()));
+    }
+    for child in &node.children {
+        collect_destinations(child, out);
+    }
+}
+
+/// Every entity node of the tree (those with a path), in tree order.
+fn collect_tree_nodes<'a>(node: &'a TreeNode, out: &mut Vec<&'a TreeNode>) {
+    if !node.path.is_empty() {
+        out.push(node);
+    }
+    for child in &node.children {
+        collect_tree_nodes(child, out);
+    }
+}
+
+/// Expand every ancestor of the node at `path`. Returns false if no node has that path.
+fn expand_to_path(node: &mut TreeNode, path: &str) -> bool {
+    if node.path == path {
+        return true;
+    }
+    for child in &mut node.children {
+        if expand_to_path(child, path) {
+            node.expanded = true;
+            return true;
+        }
+    }
+    false
+}
+
+/// `1234` → `1.2k`, `3400000` → `3.4M`; small counts stay exact.
+fn compact_count(n: i64) -> String {
+    match n {
+        1_000_000.. => format!("{:.1}M", n as f64 / 1_000_000.0),
+        10_000.. => format!("{}k", n / 1000),
+        1_000.. => format!("{:.1}k", n as f64 / 1000.0),
+        _ => n.to_string(),
+    }
+}
+
+fn forward_label(forward_to: &Option<String>) -> Option<String> {
+    forward_to
+        .as_deref()
+        .map(entity_path::forward_target)
+        .filter(|t| !t.is_empty())
+        .map(str::to_string)
+}
+
+/// Queue or subscription (path and type) owning the dead-letter node at `path`.
+pub fn dead_letter_parent(path: &str) -> (&str, &'static EntityType) {
+    let parent = entity_path::without_subqueue(path);
+    if entity_path::split_subscription_path(parent).is_some() {
+        (parent, &EntityType::Subscription)
+    } else {
+        (parent, &EntityType::Queue)
+    }
+}
+
+/// "Dead-letter" child of a queue or subscription node, badged with the
+/// parent's DLQ count.
+fn dead_letter_node(parent: &TreeNode) -> TreeNode {
+    let mut node = TreeNode::new_entity(
+        &format!("{}:dlq", parent.id),
+        "Dead-letter",
+        EntityType::DeadLetterQueue,
+        &format!("{}/$deadletterqueue", parent.path),
+        parent.depth + 1,
+    );
+    node.message_count = parent.dlq_count;
+    node
+}
+
+/// Subscriptions folder of a topic node, built from a listing with counts.
+fn subscription_folder(topic: &str, subs: &[(SubscriptionDescription, i64, i64)]) -> TreeNode {
+    let mut folder = TreeNode::new_folder(
+        &format!("t:{}:subs", topic),
+        &format!("Subscriptions ({})", subs.len()),
+        EntityType::SubscriptionFolder,
+        3,
+    );
+    for (s, active_count, dlq_count) in subs {
+        let mut sub_node = TreeNode::new_entity(
+            &format!("s:{}:{}", topic, s.name),
+            &s.name,
+            EntityType::Subscription,
+            &format!("{}/Subscriptions/{}", topic, s.name),
+            4,
+        );
+        sub_node.message_count = Some(*active_count);
+        sub_node.dlq_count = Some(*dlq_count);
+        sub_node.forward_to = forward_label(&s.forward_to);
+        sub_node.children.push(dead_letter_node(&sub_node));
+        folder.children.push(sub_node);
+    }
+    folder
+}
+
+/// The one queue or topic an entity-scoped connection (`EntityPath=`) may
+/// list, shaped like the namespace-wide listing.
+async fn scoped_entities(
+    mgmt: &ManagementClient,
+    path: &str,
+) -> crate::client::Result<(Vec<(QueueDescription, i64, i64)>, Vec<TopicDescription>)> {
+    Ok(match mgmt.get_entity(path).await? {
+        EntityDescription::Queue(queue, runtime) => (
+            vec![(
+                *queue,
+                runtime.active_message_count,
+                runtime.dead_letter_message_count,
+            )],
+            Vec::new(),
+        ),
+        EntityDescription::Topic(topic) => (Vec::new(), vec![topic]),
+    })
+}
+
 /// Build the entity tree from the management API (runs on a spawned task).
 pub async fn build_tree(
     mgmt: ManagementClient,
     namespace: String,
 ) -> crate::client::Result<(TreeNode, Vec<FlatNode>)> {
-    // Parallel fetch: queues + topics in one round trip pair
-    let (queues_result, topics_result) =
-        tokio::join!(mgmt.list_queues_with_counts(), mgmt.list_topics());
-    let queues = queues_result?;
-    let topics = topics_result?;
+    let (queues, topics) = match mgmt.entity_path() {
+        Some(path) => scoped_entities(&mgmt, path).await?,
+        None => {
+            // Parallel fetch: queues + topics in one round trip pair. Queue
+            // counts come with the queue feed at no extra cost.
+            let (queues_result, topics_result) =
+                tokio::join!(mgmt.list_queues_with_counts(), mgmt.list_topics());
+            (queues_result?, topics_result?)
+        }
+    };
 
     let mut root = TreeNode::new_folder("root", &namespace, EntityType::Namespace, 0);
 
@@ -748,32 +4855,16 @@ pub async fn build_tree(
         );
         node.message_count = Some(*active_count);
         node.dlq_count = Some(*dlq_count);
+        node.forward_to = forward_label(&q.forward_to);
+        node.children.push(dead_letter_node(&node));
         queue_folder.children.push(node);
     }
     root.children.push(queue_folder);
 
-    // Topics folder — fetch all subscription lists concurrently.
+    // Topics folder. Listing a topic's subscriptions (which is where their
+    // counts come from) costs a request per topic, so topics start out
+    // pending and are filled in as they scroll into view.
     let mut topic_folder = TreeNode::new_folder("topics", "Topics", EntityType::TopicFolder, 1);
-
-    // Spawn concurrent subscription list fetches for all topics
-    let mut sub_handles = Vec::with_capacity(topics.len());
-    for t in &topics {
-        let mgmt_clone = mgmt.clone();
-        let topic_name = t.name.clone();
-        sub_handles.push(tokio::spawn(async move {
-            let subs = mgmt_clone.list_subscriptions_with_counts(&topic_name).await;
-            (topic_name, subs)
-        }));
-    }
-
-    // Collect results (order doesn't matter, we match by topic name)
-    let mut subs_by_topic = std::collections::HashMap::new();
-    for handle in sub_handles {
-        if let Ok((topic_name, Ok(subs))) = handle.await {
-            subs_by_topic.insert(topic_name, subs);
-        }
-    }
-
     for t in &topics {
         let mut topic_node = TreeNode::new_entity(
             &format!("t:{}", t.name),
@@ -782,44 +4873,809 @@ pub async fn build_tree(
             &t.name,
             2,
         );
+        topic_node.counts_pending = true;
+        topic_folder.children.push(topic_node);
+    }
+    root.children.push(topic_folder);
 
-        if let Some(subs) = subs_by_topic.remove(&t.name) {
-            let mut total_active = 0i64;
-            let mut total_dlq = 0i64;
+    let flat_nodes = root.flatten();
+    Ok((root, flat_nodes))
+}
 
-            let mut sub_folder = TreeNode::new_folder(
-                &format!("t:{}:subs", t.name),
-                "Subscriptions",
-                EntityType::SubscriptionFolder,
-                3,
-            );
-            for (s, active_count, dlq_count) in &subs {
-                total_active += active_count;
-                total_dlq += dlq_count;
-
-                let sub_path = format!("{}/Subscriptions/{}", t.name, s.name);
-                let mut sub_node = TreeNode::new_entity(
-                    &format!("s:{}:{}", t.name, s.name),
-                    &s.name,
-                    EntityType::Subscription,
-                    &sub_path,
-                    4,
-                );
-                sub_node.message_count = Some(*active_count);
-                sub_node.dlq_count = Some(*dlq_count);
-                sub_folder.children.push(sub_node);
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn app() -> App {
+        App::with_config(AppConfig::default(), None)
+    }
+
+    fn message(seq: i64, enqueued: &str, ttl_secs: Option<f64>) -> ReceivedMessage {
+        ReceivedMessage {
+            body: String::new(),
+            broker_properties: BrokerProperties {
+                sequence_number: Some(seq),
+                enqueued_time_utc: Some(enqueued.to_string()),
+                time_to_live: ttl_secs,
+                ..Default::default()
+            },
+            custom_properties: Vec::new(),
+            lock_token_uri: None,
+            source_entity: None,
+            raw_body: None,
+        }
+    }
+
+    fn sequence_numbers(messages: &[&ReceivedMessage]) -> Vec<i64> {
+        messages
+            .iter()
+            .filter_map(|m| m.broker_properties.sequence_number)
+            .collect()
+    }
+
+    #[test]
+    fn expiry_sort_orders_soonest_first_and_toggles_back() {
+        let mut app = app();
+        let enqueued = "Mon, 06 May 2024 12:00:00 GMT";
+        app.messages = vec![
+            message(1, enqueued, Some(3600.0)),
+            message(2, enqueued, None),
+            message(3, enqueued, Some(60.0)),
+        ];
+        app.message_selected = 2;
+
+        app.toggle_expiry_sort();
+        assert_eq!(app.message_selected, 0);
+        // Messages that never expire go last
+        assert_eq!(sequence_numbers(&app.visible_messages()), vec![3, 1, 2]);
+
+        // Dead-lettered messages keep peek order
+        app.message_tab = MessageTab::DeadLetter;
+        app.dlq_messages = app.messages.clone();
+        assert_eq!(sequence_numbers(&app.visible_messages()), vec![1, 2, 3]);
+
+        app.message_tab = MessageTab::Messages;
+        app.toggle_expiry_sort();
+        assert_eq!(sequence_numbers(&app.visible_messages()), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn config_saves_wait_for_changes_to_settle() {
+        let mut app = app();
+        assert!(app.take_config_save(true).is_none());
+
+        app.save_config();
+        app.save_config();
+        assert!(app.take_config_save(false).is_none());
+
+        // Rapid saves collapse into one write once the debounce has passed
+        app.config_save_at = Instant::now().checked_sub(CONFIG_SAVE_DEBOUNCE);
+        assert!(app.take_config_save(false).is_some());
+        assert!(app.take_config_save(false).is_none());
+
+        // Quitting writes a queued save straight away
+        app.save_config();
+        assert!(app.take_config_save(true).is_some());
+
+        // Demo connections are never written
+        app.demo = true;
+        app.save_config();
+        assert!(app.take_config_save(true).is_none());
+    }
+
+    #[tokio::test]
+    async fn entity_scoped_tree_lists_only_its_entity_in_one_request() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/orders"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"<entry><title type="text">orders</title><content type="application/xml"><QueueDescription><CountDetails xmlns:d2p1="http://schemas.microsoft.com/netservices/2011/06/servicebus"><d2p1:ActiveMessageCount>4</d2p1:ActiveMessageCount><d2p1:DeadLetterMessageCount>1</d2p1:DeadLetterMessageCount></CountDetails></QueueDescription></content></entry>"#,
+            ))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let conn_str = format!(
+            "Endpoint=sb://{};SharedAccessKeyName=send;SharedAccessKey=a2V5;UseDevelopmentEmulator=true;EntityPath=orders",
+            server.address()
+        );
+        let mgmt = ManagementClient::new(
+            ConnectionConfig::from_connection_string(&conn_str).unwrap(),
+            Arc::new(reqwest::Client::new()),
+        );
+
+        let (_, flat_nodes) = build_tree(mgmt, "ns".to_string()).await.unwrap();
+        let entities: Vec<_> = flat_nodes
+            .iter()
+            .filter(|n| matches!(n.entity_type, EntityType::Queue | EntityType::Topic))
+            .collect();
+        assert_eq!(entities.len(), 1);
+        assert_eq!(entities[0].path, "orders");
+        assert_eq!(entities[0].message_count, Some(4));
+        assert_eq!(entities[0].dlq_count, Some(1));
+    }
+
+    #[test]
+    fn search_hits_match_message_or_correlation_id_exactly() {
+        let mut msg = message(7, "Wed, 05 Sep 2018 05:06:43 GMT", None);
+        msg.broker_properties.message_id = Some("order-7".to_string());
+        msg.broker_properties.correlation_id = Some("batch-1".to_string());
+
+        let hit = SearchHit::from_match("orders", true, &msg, "order-7").unwrap();
+        assert_eq!(hit.entity_path, "orders");
+        assert!(hit.is_dlq);
+        assert_eq!(hit.sequence_number, Some(7));
+        assert_eq!(hit.correlation_id.as_deref(), Some("batch-1"));
+        assert!(SearchHit::from_match("orders", false, &msg, "batch-1").is_some());
+        assert!(SearchHit::from_match("orders", false, &msg, "order").is_none());
+        assert!(SearchHit::from_match("orders", false, &msg, "").is_none());
+    }
+
+    #[test]
+    fn resend_report_scrolls_and_outlives_its_modal() {
+        let mut app = app();
+        app.reopen_resend_report();
+        assert_eq!(app.modal, ActiveModal::None);
+
+        let failures = (1..=30)
+            .map(|seq| crate::bulk_ops::ResendFailure {
+                message: message(seq, "Wed, 05 Sep 2018 05:06:43 GMT", None),
+                kind: crate::client::ErrorKind::Throttled,
+                error: "busy".to_string(),
+            })
+            .collect();
+        app.open_resend_report(ResendReport {
+            entity_path: "orders".to_string(),
+            is_topic: false,
+            failures,
+        });
+        app.scroll_resend_report(-1);
+        assert_eq!(app.resend_report_scroll, 0);
+        app.scroll_resend_report(25);
+        app.scroll_resend_report(10);
+        assert_eq!(app.resend_report_scroll, 29, "stops at the last failure");
+
+        app.close_resend_report();
+        assert_eq!(app.modal, ActiveModal::None);
+        app.reopen_resend_report();
+        assert_eq!(app.modal, ActiveModal::ResendReport);
+        assert_eq!(app.resend_report.as_ref().unwrap().failures.len(), 30);
+    }
+
+    #[test]
+    fn edit_form_skips_and_keeps_immutable_entity_settings() {
+        let mut app = app();
+        app.detail_view = DetailView::Queue(
+            QueueDescription {
+                name: "orders".to_string(),
+                max_delivery_count: Some(10),
+                requires_session: Some(false),
+                enable_partitioning: Some(false),
+                dead_lettering_on_message_expiration: Some(false),
+                ..Default::default()
+            },
+            None,
+        );
+        assert!(app.init_edit_entity_form());
+        assert_eq!(app.input_field_index, 1);
+        app.step_form_field(false);
+        assert_eq!(app.input_field_index, 1, "the queue name is read-only");
+
+        let label = |app: &App| app.input_fields[app.input_field_index].0.clone();
+        app.input_field_index = 4;
+        assert_eq!(label(&app), "Max Delivery Count");
+        app.step_form_field(true);
+        assert_eq!(label(&app), "Dead-letter on Expiry");
+        app.step_form_field(false);
+        assert_eq!(label(&app), "Max Delivery Count");
+
+        // Whatever the read-only fields hold is not sent
+        app.form_field_mut("Requires Session")
+            .unwrap()
+            .push_str("true");
+        app.form_field_mut("Queue Name")
+            .unwrap()
+            .push_str("-renamed");
+        app.form_field_mut("Max Delivery Count")
+            .unwrap()
+            .replace_range(.., "5");
+        let patches = app.entity_update_patches().unwrap();
+        assert_eq!(patches, [("MaxDeliveryCount".to_string(), "5".to_string())]);
+    }
+
+    #[test]
+    fn remembered_send_defaults_fill_and_reset_their_fields() {
+        let mut app = app();
+        app.connection_name = Some("dev".to_string());
+        app.flat_nodes =
+            TreeNode::new_entity("q:orders", "orders", EntityType::Queue, "orders", 2).flatten();
+        app.config.remember_send_defaults(SendDefaults {
+            connection: "dev".to_string(),
+            entity: "orders".to_string(),
+            content_type: Some("text/plain".to_string()),
+            label: Some("order.created".to_string()),
+            session_id: None,
+            custom_properties: vec![("tenant".to_string(), String::new())],
+        });
+        let field = |app: &App, label: &str| {
+            app.input_fields
+                .iter()
+                .find(|(l, _)| l == label)
+                .map(|(_, v)| v.clone())
+        };
+
+        app.init_send_form();
+        assert_eq!(
+            field(&app, CONTENT_TYPE_FIELD).as_deref(),
+            Some("text/plain")
+        );
+        assert_eq!(field(&app, LABEL_FIELD).as_deref(), Some("order.created"));
+        assert_eq!(field(&app, SESSION_ID_FIELD).as_deref(), Some(""));
+        assert_eq!(
+            field(&app, CUSTOM_PROPERTIES_FIELD).as_deref(),
+            Some("tenant=")
+        );
+
+        app.forget_send_defaults();
+        assert_eq!(
+            field(&app, CONTENT_TYPE_FIELD).as_deref(),
+            Some("application/json")
+        );
+        assert_eq!(field(&app, LABEL_FIELD).as_deref(), Some(""));
+        assert_eq!(field(&app, CUSTOM_PROPERTIES_FIELD).as_deref(), Some(""));
+        assert!(app.config.send_defaults_for("dev", "orders").is_none());
+    }
+
+    #[test]
+    fn send_form_offers_reply_to_session_only_for_session_entities() {
+        let mut app = app();
+        let has_field = |app: &App, label: &str| app.input_fields.iter().any(|(l, _)| l == label);
+
+        app.init_send_form();
+        assert!(!has_field(&app, REPLY_TO_SESSION_FIELD));
+        assert!(has_field(&app, VIA_PARTITION_KEY_FIELD));
+
+        app.detail_view = DetailView::Queue(
+            QueueDescription {
+                name: "orders".to_string(),
+                requires_session: Some(true),
+                ..Default::default()
+            },
+            None,
+        );
+        app.init_send_form();
+        assert!(has_field(&app, REPLY_TO_SESSION_FIELD));
+
+        for (label, value) in app.input_fields.iter_mut() {
+            match label.as_str() {
+                REPLY_TO_SESSION_FIELD => *value = "replies-1".to_string(),
+                VIA_PARTITION_KEY_FIELD => *value = "tx-7".to_string(),
+                CUSTOM_PROPERTIES_FIELD => *value = "tenant=acme".to_string(),
+                _ => {}
             }
+        }
+        let msg = app.build_message_from_form();
+        assert_eq!(msg.reply_to_session_id.as_deref(), Some("replies-1"));
+        assert_eq!(msg.via_partition_key.as_deref(), Some("tx-7"));
+        assert_eq!(msg.content_type.as_deref(), Some("application/json"));
+        assert_eq!(
+            msg.custom_properties,
+            vec![("tenant".to_string(), "acme".to_string())]
+        );
+    }
+
+    #[test]
+    fn purge_gauge_needs_a_nonzero_total() {
+        let mut app = app();
+        assert_eq!(app.purge_ratio(), None);
+        app.purge_total = Some(0);
+        app.purge_done = 3;
+        assert_eq!(app.purge_ratio(), None);
+        app.purge_total = Some(4);
+        assert_eq!(app.purge_ratio(), Some(0.75));
+        app.purge_done = 9;
+        assert_eq!(app.purge_ratio(), Some(1.0));
+    }
+
+    #[test]
+    fn compare_diff_is_computed_once_when_the_modal_opens() {
+        let mut app = app();
+        app.open_compare();
+        assert!(app.compare_body_diff.is_empty());
+        assert!(!matches!(app.modal, ActiveModal::CompareMessages));
 
-            // Set aggregated counts on topic
-            topic_node.message_count = Some(total_active);
-            topic_node.dlq_count = Some(total_dlq);
+        let mut left = message(1, "2024-01-01T00:00:00Z", None);
+        left.body = "a\nb".to_string();
+        let mut right = message(2, "2024-01-01T00:00:00Z", None);
+        right.body = "a\nc".to_string();
+        app.compare_marks = vec![
+            (left, MessageTab::Messages),
+            (right, MessageTab::DeadLetter),
+        ];
+        app.open_compare();
+        assert!(matches!(app.modal, ActiveModal::CompareMessages));
+        assert_eq!(
+            app.compare_body_diff,
+            vec![
+                DiffLine::Same("a".into()),
+                DiffLine::Removed("b".into()),
+                DiffLine::Added("c".into()),
+            ]
+        );
+    }
 
-            topic_node.children.push(sub_folder);
+    fn queue_tree(queues: &[&str]) -> TreeNode {
+        let mut root = TreeNode::new_folder("root", "ns", EntityType::Namespace, 0);
+        let mut folder = TreeNode::new_folder("queues", "Queues", EntityType::QueueFolder, 1);
+        for name in queues {
+            let mut node =
+                TreeNode::new_entity(&format!("q:{}", name), name, EntityType::Queue, name, 2);
+            node.children.push(dead_letter_node(&node));
+            folder.children.push(node);
         }
-        topic_folder.children.push(topic_node);
+        root.children.push(folder);
+        root.children.push(TreeNode::new_folder(
+            "topics",
+            "Topics",
+            EntityType::TopicFolder,
+            1,
+        ));
+        root
     }
-    root.children.push(topic_folder);
 
-    let flat_nodes = root.flatten();
-    Ok((root, flat_nodes))
+    #[test]
+    fn expand_state_survives_a_rebuilt_tree() {
+        let mut app = app();
+        let mut old = queue_tree(&["orders", "gone"]);
+        toggle_node(&mut old, "q:orders");
+        toggle_node(&mut old, "topics");
+        app.flat_nodes = old.flatten();
+
+        let mut rebuilt = queue_tree(&["orders", "new"]);
+        app.restore_expand_state(&mut rebuilt);
+
+        let mut state = HashMap::new();
+        rebuilt.collect_expand_state(&mut state);
+        assert!(state["q:orders"]);
+        assert!(!state["topics"]);
+        assert!(!state["q:new"]);
+        assert!(state["queues"]);
+        assert!(!app.expand_state.contains_key("q:gone"));
+        assert_eq!(app.expand_state, state);
+    }
+
+    #[test]
+    fn dead_letter_nodes_stand_for_their_queue_or_subscription() {
+        let subs = [(
+            SubscriptionDescription {
+                name: "audit".to_string(),
+                topic_name: "events".to_string(),
+                ..Default::default()
+            },
+            5,
+            2,
+        )];
+        let folder = subscription_folder("events", &subs);
+        let sub = &folder.children[0];
+        let dlq = &sub.children[0];
+        assert_eq!(dlq.id, "s:events:audit:dlq");
+        assert_eq!(dlq.path, "events/Subscriptions/audit/$deadletterqueue");
+        assert_eq!(dlq.entity_type, EntityType::DeadLetterQueue);
+        assert_eq!(dlq.depth, sub.depth + 1);
+        assert_eq!(dlq.message_count, Some(2));
+        assert_eq!(
+            dead_letter_parent(&dlq.path),
+            ("events/Subscriptions/audit", &EntityType::Subscription)
+        );
+        assert_eq!(
+            dead_letter_parent("orders/$deadletterqueue"),
+            ("orders", &EntityType::Queue)
+        );
+    }
+
+    #[tokio::test]
+    async fn built_tree_gives_each_queue_a_dead_letter_child() {
+        let namespace = Arc::new(DemoNamespace::seeded().without_latency());
+        let mgmt = ManagementClient::with_transport(ConnectionConfig::demo(&namespace), namespace);
+        let (tree, _) = build_tree(mgmt, "demo".to_string()).await.unwrap();
+        let queues = &tree.children[0];
+        assert!(!queues.children.is_empty());
+        for queue in &queues.children {
+            let [dlq] = &queue.children[..] else {
+                panic!("{} should have exactly one child", queue.path);
+            };
+            assert_eq!(dlq.entity_type, EntityType::DeadLetterQueue);
+            assert_eq!(dlq.path, format!("{}/$deadletterqueue", queue.path));
+            assert_eq!(dlq.message_count, queue.dlq_count);
+        }
+    }
+
+    #[test]
+    fn leaving_a_dead_letter_node_restores_the_previous_tab() {
+        let mut app = app();
+        let mut tree = queue_tree(&["orders"]);
+        toggle_node(&mut tree, "q:orders");
+        app.flat_nodes = tree.flatten();
+        let queue = app
+            .flat_nodes
+            .iter()
+            .position(|n| n.id == "q:orders")
+            .unwrap();
+        let dlq = app
+            .flat_nodes
+            .iter()
+            .position(|n| n.id == "q:orders:dlq")
+            .unwrap();
+
+        app.tree_selected = dlq;
+        app.sync_tab_to_dead_letter_node();
+        assert_eq!(app.message_tab, MessageTab::DeadLetter);
+        assert_eq!(app.selected_entity(), Some(("orders", &EntityType::Queue)));
+        app.tree_selected = queue;
+        app.sync_tab_to_dead_letter_node();
+        assert_eq!(app.message_tab, MessageTab::Messages);
+
+        // A tab picked on the node itself is kept
+        app.tree_selected = dlq;
+        app.sync_tab_to_dead_letter_node();
+        app.message_tab = MessageTab::Transferred;
+        app.tree_selected = queue;
+        app.sync_tab_to_dead_letter_node();
+        assert_eq!(app.message_tab, MessageTab::Transferred);
+    }
+
+    #[test]
+    fn sub_queues_are_stale_with_their_entity() {
+        let mut app = app();
+        app.mark_entity_gone("orders");
+        assert!(app.is_entity_stale("orders/$deadletterqueue"));
+        assert!(app.is_entity_stale("orders/$transfer/$deadletterqueue"));
+        assert!(!app.is_entity_stale("payments/$deadletterqueue"));
+    }
+
+    fn topic_tree(topics: &[&str]) -> TreeNode {
+        let mut root = queue_tree(&[]);
+        for name in topics {
+            let mut node =
+                TreeNode::new_entity(&format!("t:{}", name), name, EntityType::Topic, name, 2);
+            node.counts_pending = true;
+            root.children[1].children.push(node);
+        }
+        root
+    }
+
+    fn subscriptions(
+        topic: &str,
+        counts: &[(&str, i64, i64)],
+    ) -> Vec<(SubscriptionDescription, i64, i64)> {
+        counts
+            .iter()
+            .map(|(name, active, dlq)| {
+                let desc = SubscriptionDescription {
+                    name: name.to_string(),
+                    topic_name: topic.to_string(),
+                    ..Default::default()
+                };
+                (desc, *active, *dlq)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn only_visible_pending_topics_are_taken_and_only_once() {
+        let mut app = app();
+        app.tree = Some(topic_tree(&["a", "b", "c"]));
+        app.rebuild_flat_nodes();
+        // root, Queues, Topics, a, b — c is below the viewport
+        app.tree_viewport_height = 5;
+
+        assert_eq!(app.take_visible_pending_topics(), vec!["a", "b"]);
+        assert!(app.take_visible_pending_topics().is_empty());
+
+        app.tree_viewport_height = 6;
+        assert_eq!(app.take_visible_pending_topics(), vec!["c"]);
+    }
+
+    #[test]
+    fn node_counts_fill_the_topic_and_keep_the_selection() {
+        let mut app = app();
+        app.tree = Some(topic_tree(&["a", "b"]));
+        app.rebuild_flat_nodes();
+        app.tree_viewport_height = 10;
+        app.take_visible_pending_topics();
+        app.tree_selected = app.flat_nodes.iter().position(|n| n.id == "t:b").unwrap();
+        app.expand_state.insert("t:a".to_string(), true);
+
+        app.apply_node_counts("a", Ok(subscriptions("a", &[("x", 3, 1), ("y", 4, 0)])));
+        let topic = app.flat_nodes.iter().find(|n| n.id == "t:a").unwrap();
+        assert_eq!((topic.message_count, topic.dlq_count), (Some(7), Some(1)));
+        assert!(!topic.counts_pending);
+        assert!(!app.counts_in_flight.contains("a"));
+        assert_eq!(app.flat_nodes[app.tree_selected].id, "t:b");
+
+        app.apply_node_counts("b", Err("boom".to_string()));
+        let topic = app.flat_nodes.iter().find(|n| n.id == "t:b").unwrap();
+        assert!(!topic.counts_pending);
+        assert_eq!(topic.message_count, None);
+        assert!(app.status_is_error);
+        assert!(app.status_message.contains("boom"));
+    }
+
+    #[test]
+    fn refreshed_tree_keeps_listed_subscriptions_until_refetched() {
+        let mut app = app();
+        app.tree = Some(topic_tree(&["a", "b"]));
+        app.apply_node_counts("a", Ok(subscriptions("a", &[("x", 3, 1)])));
+
+        let mut fresh = topic_tree(&["a", "b", "new"]);
+        app.carry_over_subscriptions(&mut fresh);
+        let topics = &fresh.children[1].children;
+        assert_eq!(topics[0].children.len(), 1);
+        assert_eq!(
+            (topics[0].message_count, topics[0].dlq_count),
+            (Some(3), Some(1))
+        );
+        assert!(topics[0].counts_pending);
+        assert!(topics[1].children.is_empty());
+        assert_eq!(topics[1].message_count, None);
+        assert!(topics[2].children.is_empty());
+    }
+
+    #[test]
+    fn closing_an_edited_send_form_keeps_a_draft() {
+        let mut app = app();
+        app.connection_name = Some("dev".to_string());
+        app.flat_nodes =
+            TreeNode::new_entity("q:orders", "orders", EntityType::Queue, "orders", 2).flatten();
+        assert!(app.confirm_form_close());
+
+        // Untouched: closes at once, nothing kept
+        app.init_send_form();
+        assert!(app.confirm_form_close());
+        assert!(app.form_drafts.is_empty());
+
+        // Edited fields other than the body: closes at once, kept as a draft
+        app.init_send_form();
+        *app.form_field_mut(LABEL_FIELD).unwrap() = "order.created".to_string();
+        assert!(app.confirm_form_close());
+        app.modal = ActiveModal::None;
+        app.init_send_form();
+        assert_eq!(
+            app.modal,
+            ActiveModal::ResumeDraft {
+                kind: DraftKind::Send
+            }
+        );
+        app.settle_draft(DraftKind::Send, true);
+        assert_eq!(app.modal, ActiveModal::SendMessage);
+        assert_eq!(
+            app.form_field_mut(LABEL_FIELD).map(|v| v.as_str()),
+            Some("order.created")
+        );
+
+        // An edited body takes a second Esc
+        app.input_fields[0].1 = "{}".to_string();
+        assert!(!app.confirm_form_close());
+        assert!(app.form_discard_armed);
+        assert!(app.confirm_form_close());
+        assert!(!app.form_discard_armed);
+        assert_eq!(app.form_drafts[&DraftKind::Send].fields[0].1, "{}");
+    }
+
+    #[test]
+    fn reauthenticating_updates_the_saved_connection_in_place() {
+        let mut app = app();
+        let old = "Endpoint=sb://orders.servicebus.windows.net/;SharedAccessKeyName=k;SharedAccessKey=old";
+        let name = app
+            .config
+            .add_connection("orders.servicebus.windows.net", old.to_string());
+        app.connection_name = Some(name.clone());
+
+        app.open_reauthenticate();
+        assert_eq!(app.reauth_connection.as_deref(), Some(name.as_str()));
+        let new = old.replace("=old", "=new");
+        assert_eq!(app.save_sas_connection(new.clone()), name);
+        assert_eq!(app.config.connections.len(), 1);
+        assert_eq!(
+            app.config.connections[0].connection_string.as_deref(),
+            Some(new.as_str())
+        );
+        assert_eq!(app.reauth_connection, None);
+
+        app.modal = ActiveModal::None;
+        app.open_reauthenticate();
+        let namespace = "orders.servicebus.windows.net".to_string();
+        assert_eq!(app.save_azure_ad_connection(namespace.clone()), name);
+        assert_eq!(app.config.connections.len(), 1);
+        let saved = &app.config.connections[0];
+        assert!(saved.is_azure_ad());
+        assert_eq!(saved.namespace.as_deref(), Some(namespace.as_str()));
+        assert_eq!(saved.connection_string, None);
+
+        // Outside a re-authentication a different namespace is a new entry
+        app.save_azure_ad_connection("billing.servicebus.windows.net".to_string());
+        assert_eq!(app.config.connections.len(), 2);
+    }
+
+    #[test]
+    fn filter_presets_are_deleted_only_once_confirmed() {
+        let mut app = app();
+        for name in ["dlq", "tenant"] {
+            app.config.filter_presets.push(FilterPreset {
+                name: name.to_string(),
+                scope: FilterScope::CustomProperties,
+                expression: "tenantId".to_string(),
+            });
+        }
+        app.modal = ActiveModal::FilterPresets;
+        app.filter_preset_selected = 1;
+
+        app.open_delete_filter_preset();
+        assert_eq!(
+            app.modal,
+            ActiveModal::ConfirmDeleteFilterPreset("tenant".to_string())
+        );
+        assert_eq!(app.config.filter_presets.len(), 2);
+
+        app.delete_filter_preset("tenant");
+        assert_eq!(app.modal, ActiveModal::FilterPresets);
+        assert_eq!(app.config.filter_presets.len(), 1);
+        assert_eq!(app.config.filter_presets[0].name, "dlq");
+        assert_eq!(app.filter_preset_selected, 0);
+
+        app.filter_preset_selected = 5;
+        app.open_delete_filter_preset();
+        assert_eq!(app.modal, ActiveModal::FilterPresets);
+    }
+
+    #[test]
+    fn opening_a_message_drops_the_previous_hex_dump() {
+        let mut app = app();
+        app.open_message_detail(message(1, "2024-01-01T00:00:00Z", None));
+        app.detail_hex_dump = Some(vec![Line::from("00000000  cached")]);
+        app.detail_body_scroll = 12;
+        app.properties_focus = true;
+
+        app.open_message_detail(message(2, "2024-01-01T00:00:00Z", None));
+        assert!(app.detail_hex_dump.is_none());
+        assert_eq!(app.detail_body_scroll, 0);
+        assert!(!app.properties_focus);
+        assert_eq!(
+            app.selected_message_detail
+                .as_ref()
+                .and_then(|m| m.broker_properties.sequence_number),
+            Some(2)
+        );
+    }
+
+    #[test]
+    fn properties_sort_by_name_ignoring_case() {
+        let props: Vec<(String, String)> = [("b", "1"), ("A", "2"), ("a", "3"), ("B", "4")]
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        let names: Vec<&str> = sorted_properties(&props)
+            .iter()
+            .map(|(k, _)| k.as_str())
+            .collect();
+        // Names equal but for case keep a fixed order: upper case first
+        assert_eq!(names, vec!["A", "a", "B", "b"]);
+        assert!(sorted_properties(&[]).is_empty());
+    }
+
+    #[test]
+    fn discovery_cache_is_served_per_identity_and_skipped_on_refresh() {
+        let mut app = app();
+        let ada = ArmIdentity {
+            tenant_id: "t1".into(),
+            account: "ada@contoso.com".into(),
+        };
+        let result = |error: &str| DiscoveryResult {
+            namespaces: vec![DiscoveredNamespace {
+                fqdn: "dev.servicebus.windows.net".into(),
+                name: "dev".into(),
+                subscription_id: "0000".into(),
+                subscription_name: "Dev".into(),
+                resource_id: String::new(),
+                resource_group: None,
+                location: "westeurope".into(),
+                status: "Active".into(),
+                detail: None,
+            }],
+            errors: vec![error.into()],
+        };
+
+        app.start_namespace_discovery();
+        app.finish_namespace_discovery(Some(ada.clone()), result("fetched"), None);
+        assert_eq!(app.config.discovery_cache.len(), 1);
+        assert!(app.config.discovery_cache[0].is_for(&ada));
+        assert_eq!(app.discovery_cached_minutes, None);
+
+        // Without an identity the result is shown but not cached.
+        app.finish_namespace_discovery(None, result("anonymous"), None);
+        assert_eq!(app.config.discovery_cache[0].result.errors, vec!["fetched"]);
+
+        // A result served from the cache is not written back.
+        app.start_namespace_discovery();
+        assert_eq!(app.usable_discovery_cache(Utc::now()).len(), 1);
+        app.finish_namespace_discovery(Some(ada.clone()), result("cached"), Some(3));
+        assert_eq!(app.discovery_cached_minutes, Some(3));
+        assert_eq!(app.config.discovery_cache[0].result.errors, vec!["fetched"]);
+
+        app.refresh_namespace_discovery();
+        assert!(app.usable_discovery_cache(Utc::now()).is_empty());
+        app.config.settings.discovery_cache_ttl_minutes = 0;
+        app.start_namespace_discovery();
+        assert!(app.usable_discovery_cache(Utc::now()).is_empty());
+    }
+
+    #[test]
+    fn compact_count_switches_units_at_the_boundaries() {
+        assert_eq!(compact_count(999), "999");
+        assert_eq!(compact_count(1_000), "1.0k");
+        assert_eq!(compact_count(999_999), "999k");
+        assert_eq!(compact_count(1_000_000), "1.0M");
+    }
+
+    #[test]
+    fn node_summary_prefers_loaded_detail_over_the_tree_node() {
+        let mut app = app();
+        app.flat_nodes = queue_tree(&["orders"]).flatten();
+        app.tree_selected = app
+            .flat_nodes
+            .iter()
+            .position(|n| n.id == "q:orders")
+            .unwrap();
+        let node = &mut app.flat_nodes[app.tree_selected];
+        node.message_count = Some(1_500);
+        node.dlq_count = Some(3);
+        node.forward_to = Some("audit".to_string());
+        assert_eq!(
+            app.selected_node_summary().as_deref(),
+            Some("orders · fwd→audit · 1.5k msgs / 3 DLQ")
+        );
+
+        app.detail_view = DetailView::Queue(
+            QueueDescription {
+                name: "Orders".to_string(),
+                status: Some("Active".to_string()),
+                requires_session: Some(true),
+                forward_to: Some("sb://ns.servicebus.windows.net/archive".to_string()),
+                ..Default::default()
+            },
+            Some(QueueRuntimeInfo {
+                name: "Orders".to_string(),
+                active_message_count: 12_345,
+                ..Default::default()
+            }),
+        );
+        assert_eq!(
+            app.selected_node_summary().as_deref(),
+            Some("orders · Active · sessions · fwd→archive · 12k msgs / 0 DLQ")
+        );
+
+        app.tree_selected = 0;
+        assert_eq!(app.selected_node_summary(), None, "namespace root");
+    }
+
+    #[tokio::test]
+    async fn clients_can_run_on_an_in_memory_transport() {
+        let mut app = app();
+        let namespace = Arc::new(DemoNamespace::seeded().without_latency());
+        app.use_transport(ConnectionConfig::demo(&namespace), namespace);
+
+        let mgmt = app.management.clone().unwrap();
+        let (_, flat_nodes) = build_tree(mgmt, "demo".to_string()).await.unwrap();
+        assert!(flat_nodes.iter().any(|n| n.path == "orders"));
+        let peeked = app
+            .data_plane
+            .as_ref()
+            .unwrap()
+            .peek_messages("orders", 2)
+            .await
+            .unwrap();
+        assert_eq!(peeked.len(), 2);
+    }
 }
@@ -0,0 +1,367 @@
+use quick_xml::events::Event;
+use quick_xml::Reader;
+
+use crate::jwt::decode_jwt;
+
+/// Why a message body failed validation, and where (byte offset into the body).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BodyError {
+    pub offset: usize,
+    pub message: String,
+}
+
+/// Media type without parameters, lower-cased (`application/json; charset=utf-8` → `application/json`).
+fn media_type(content_type: &str) -> String {
+    content_type
+        .split(';')
+        .next()
+        .unwrap_or("")
+        .trim()
+        .to_ascii_lowercase()
+}
+
+pub fn is_json_content_type(content_type: &str) -> bool {
+    let mt = media_type(content_type);
+    mt == "application/json" || mt == "text/json" || mt.ends_with("+json")
+}
+
+pub fn is_xml_content_type(content_type: &str) -> bool {
+    let mt = media_type(content_type);
+    mt == "application/xml" || mt == "text/xml" || mt.ends_with("+xml")
+}
+
+/// Check that `body` is well-formed for its content type. Bodies of other
+/// content types, and empty bodies, are always accepted.
+pub fn validate_body(content_type: &str, body: &str) -> Result<(), BodyError> {
+    if body.trim().is_empty() {
+        return Ok(());
+    }
+    if is_json_content_type(content_type) {
+        validate_json(body)
+    } else if is_xml_content_type(content_type) {
+        validate_xml(body)
+    } else {
+        Ok(())
+    }
+}
+
+/// Pretty-print a JSON body, or report where it fails to parse.
+pub fn format_json(body: &str) -> Result<String, BodyError> {
+    match serde_json::from_str::<serde_json::Value>(body) {
+        Ok(value) => Ok(serde_json::to_string_pretty(&value).unwrap_or_else(|_| body.to_string())),
+        Err(e) => Err(json_error(body, &e)),
+    }
+}
+
+fn validate_json(body: &str) -> Result<(), BodyError> {
+    serde_json::from_str::<serde_json::Value>(body)
+        .map(|_| ())
+        .map_err(|e| json_error(body, &e))
+}
+
+fn json_error(body: &str, err: &serde_json::Error) -> BodyError {
+    BodyError {
+        offset: line_col_to_offset(body, err.line(), err.column()),
+        message: format!("Invalid JSON: {}", err),
+    }
+}
+
+fn validate_xml(body: &str) -> Result<(), BodyError> {
+    let mut reader = Reader::from_str(body);
+    let mut depth = 0usize;
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(_)) => depth += 1,
+            Ok(Event::End(_)) => depth = depth.saturating_sub(1),
+            Ok(Event::Eof) if depth > 0 => {
+                return Err(BodyError {
+                    offset: body.len(),
+                    message: "Invalid XML: unclosed element at end of body".to_string(),
+                });
+            }
+            Ok(Event::Eof) => return Ok(()),
+            Err(e) => {
+                return Err(BodyError {
+                    offset: clamp_to_char_boundary(body, reader.error_position() as usize),
+                    message: format!("Invalid XML: {}", e),
+                });
+            }
+            Ok(_) => {}
+        }
+    }
+}
+
+/// Minimal XML indenter: one tag per line, text kept next to its element.
+pub fn indent_xml(xml: &str) -> String {
+    let mut out = String::new();
+    let mut depth: usize = 0;
+    let mut rest = xml;
+
+    while let Some(start) = rest.find('<') {
+        let text = rest[..start].trim();
+        if !text.is_empty() {
+            out.push_str(text);
+        }
+        let Some(end) = rest[start..].find('>') else {
+            break;
+        };
+        let tag = &rest[start..start + end + 1];
+        rest = &rest[start + end + 1..];
+
+        let is_closing = tag.starts_with("</");
+        let is_self_contained =
+            tag.ends_with("/>") || tag.starts_with("<?") || tag.starts_with("<!");
+
+        if is_closing {
+            depth = depth.saturating_sub(1);
+            // Keep `<a>text</a>` on one line
+            if !text.is_empty() {
+                out.push_str(tag);
+                out.push('\n');
+                continue;
+            }
+        }
+
+        if !out.is_empty() && !out.ends_with('\n') {
+            out.push('\n');
+        }
+        out.push_str(&"  ".repeat(depth));
+        out.push_str(tag);
+
+        if !is_closing && !is_self_contained {
+            depth += 1;
+        } else {
+            out.push('\n');
+        }
+    }
+
+    let tail = rest.trim();
+    if !tail.is_empty() {
+        out.push_str(tail);
+    }
+    out.trim_end().to_string()
+}
+
+/// How a message body is shown in the detail view.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BodyFormat {
+    Json,
+    Xml,
+    Csv,
+    Jwt,
+    PlainText,
+    Binary,
+    Hex,
+}
+
+impl BodyFormat {
+    pub fn label(self) -> &'static str {
+        match self {
+            BodyFormat::Json => "JSON",
+            BodyFormat::Xml => "XML",
+            BodyFormat::Csv => "CSV",
+            BodyFormat::Jwt => "JWT",
+            BodyFormat::PlainText => "Text",
+            BodyFormat::Binary => "Binary",
+            BodyFormat::Hex => "Hex",
+        }
+    }
+}
+
+/// Guess a body's format from its content: `{`/`[` → JSON, `<` → XML, lines
+/// with the same number of fields → CSV. Bodies with NULs or bytes that were
+/// not valid UTF-8 are binary.
+pub fn detect_body_format(body: &str) -> BodyFormat {
+    if body.contains(['\0', '\u{FFFD}']) {
+        return BodyFormat::Binary;
+    }
+    let trimmed = body.trim_start();
+    if trimmed.starts_with('{') || trimmed.starts_with('[') {
+        BodyFormat::Json
+    } else if trimmed.starts_with('<') {
+        BodyFormat::Xml
+    } else if parse_csv(body).is_some() {
+        BodyFormat::Csv
+    } else {
+        BodyFormat::PlainText
+    }
+}
+
+/// Formats a body can sensibly be shown as, the detected one first and plain
+/// text then hex always last. This is the order `M` cycles through.
+pub fn available_formats(body: &str) -> Vec<BodyFormat> {
+    let mut formats = vec![detect_body_format(body)];
+    let trimmed = body.trim();
+    let candidates = [
+        (BodyFormat::Json, validate_json(trimmed).is_ok()),
+        (BodyFormat::Xml, trimmed.starts_with('<')),
+        (BodyFormat::Csv, parse_csv(body).is_some()),
+        (BodyFormat::Jwt, decode_jwt(body).is_some()),
+        (BodyFormat::PlainText, true),
+        (BodyFormat::Hex, true),
+    ];
+    for (format, fits) in candidates {
+        if fits && !formats.contains(&format) {
+            formats.push(format);
+        }
+    }
+    formats
+}
+
+/// The body as text in the given format. CSV and JWT are rendered as tables,
+/// binary and hex as a hex dump by the caller, so they (like plain text) come
+/// back unchanged.
+pub fn render_body(body: &str, format: BodyFormat) -> String {
+    match format {
+        BodyFormat::Json => format_json(body).unwrap_or_else(|_| body.to_string()),
+        BodyFormat::Xml => indent_xml(body.trim()),
+        BodyFormat::Csv
+        | BodyFormat::Jwt
+        | BodyFormat::PlainText
+        | BodyFormat::Binary
+        | BodyFormat::Hex => body.to_string(),
+    }
+}
+
+/// Split a CSV body into rows of fields (double-quoted fields may contain
+/// commas and `""`). `None` unless there are at least two rows, every row has
+/// the same number of fields, and that number is more than one.
+pub fn parse_csv(body: &str) -> Option<Vec<Vec<String>>> {
+    let rows: Vec<Vec<String>> = body
+        .lines()
+        .map(|line| line.trim_end_matches('\r'))
+        .filter(|line| !line.trim().is_empty())
+        .map(split_csv_line)
+        .collect();
+    let columns = rows.first()?.len();
+    (rows.len() >= 2 && columns >= 2 && rows.iter().all(|r| r.len() == columns)).then_some(rows)
+}
+
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+/// Convert serde_json's 1-based line/column into a byte offset into `text`.
+fn line_col_to_offset(text: &str, line: usize, column: usize) -> usize {
+    let line_start: usize = text
+        .split_inclusive('\n')
+        .take(line.saturating_sub(1))
+        .map(str::len)
+        .sum();
+    clamp_to_char_boundary(text, line_start + column.saturating_sub(1))
+}
+
+fn clamp_to_char_boundary(text: &str, offset: usize) -> usize {
+    let mut offset = offset.min(text.len());
+    while !text.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    offset
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_error_points_at_offending_line() {
+        let body = "{\n  \"a\": 1,\n  \"b\": oops\n}";
+        let err = validate_body("application/json", body).unwrap_err();
+        assert!(err.message.starts_with("Invalid JSON"));
+        assert_eq!(body[..err.offset].matches('\n').count(), 2);
+    }
+
+    #[test]
+    fn content_type_parameters_are_ignored() {
+        assert!(is_json_content_type("application/json; charset=utf-8"));
+        assert!(is_json_content_type("application/cloudevents+json"));
+        assert!(is_xml_content_type("text/xml"));
+        assert!(!is_json_content_type("text/plain"));
+    }
+
+    #[test]
+    fn xml_well_formedness() {
+        assert!(validate_body("application/xml", "<a><b/></a>").is_ok());
+        assert!(validate_body("application/xml", "<a><b></a>").is_err());
+        assert!(validate_body("application/xml", "<a>").is_err());
+    }
+
+    #[test]
+    fn other_content_types_and_empty_bodies_pass() {
+        assert!(validate_body("text/plain", "{ not json").is_ok());
+        assert!(validate_body("application/json", "   ").is_ok());
+    }
+
+    #[test]
+    fn detects_body_formats() {
+        assert_eq!(detect_body_format("  {\"a\":1}"), BodyFormat::Json);
+        assert_eq!(detect_body_format("[1,2]"), BodyFormat::Json);
+        assert_eq!(detect_body_format("<order id=\"1\"/>"), BodyFormat::Xml);
+        assert_eq!(
+            detect_body_format("id,name\r\n1,\"Smith, J\"\r\n2,Doe\r\n"),
+            BodyFormat::Csv
+        );
+        assert_eq!(detect_body_format("a,b\nc"), BodyFormat::PlainText);
+        assert_eq!(detect_body_format("hello, world"), BodyFormat::PlainText);
+        assert_eq!(
+            detect_body_format("PK\u{3}\u{4}\u{FFFD}\u{FFFD}"),
+            BodyFormat::Binary
+        );
+    }
+
+    #[test]
+    fn csv_fields_honour_quotes() {
+        let rows = parse_csv("a,b\n\"x, y\",\"say \"\"hi\"\"\"").unwrap();
+        assert_eq!(rows[1], vec!["x, y".to_string(), "say \"hi\"".to_string()]);
+    }
+
+    #[test]
+    fn available_formats_start_with_the_detected_one() {
+        assert_eq!(
+            available_formats("{\"a\":1}"),
+            vec![BodyFormat::Json, BodyFormat::PlainText, BodyFormat::Hex]
+        );
+        assert_eq!(
+            available_formats("{broken"),
+            vec![BodyFormat::Json, BodyFormat::PlainText, BodyFormat::Hex]
+        );
+        assert_eq!(
+            available_formats("a,b\n1,2"),
+            vec![BodyFormat::Csv, BodyFormat::PlainText, BodyFormat::Hex]
+        );
+        assert_eq!(
+            available_formats("plain"),
+            vec![BodyFormat::PlainText, BodyFormat::Hex]
+        );
+    }
+
+    #[test]
+    fn renders_xml_indented() {
+        assert_eq!(
+            render_body(" <a><b>1</b></a> ", BodyFormat::Xml),
+            "<a>\n  <b>1</b>\n</a>"
+        );
+    }
+
+    #[test]
+    fn formats_valid_json() {
+        assert_eq!(format_json("{\"a\":1}").unwrap(), "{\n  \"a\": 1\n}");
+        assert!(format_json("{").is_err());
+    }
+}
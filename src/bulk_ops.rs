@@ -1,14 +1,9 @@
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
-use tokio::sync::mpsc::UnboundedSender;
-
-use crate::app::BgEvent;
-use crate::client::{DataPlaneClient, ManagementClient};
-
-pub fn send_path_owned(entity_path: &str) -> String {
-    crate::client::entity_path::send_target(entity_path).to_string()
-}
+use crate::app::{ActiveModal, BgEvent, BgSender};
+use crate::client::models::{ReceivedMessage, SubscriptionDescription};
+use crate::client::{entity_path, DataPlaneClient, ErrorKind, ManagementClient};
 
 pub async fn resolve_purge_paths(
     mgmt: Option<&ManagementClient>,
@@ -40,6 +35,255 @@ pub async fn resolve_purge_paths(
     }
 }
 
+/// One subscription path of a topic purge, checked before the purge starts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PurgeCandidate {
+    pub path: String,
+    /// Why receive-and-delete won't drain this path, if anything.
+    pub problem: Option<String>,
+    /// Included in the purge; problem paths start out excluded.
+    pub selected: bool,
+}
+
+/// Why receive-and-delete can't drain a subscription (or its DLQ).
+pub fn purge_blocker(desc: &SubscriptionDescription, is_dlq: bool) -> Option<String> {
+    if let Some(status @ ("Disabled" | "ReceiveDisabled")) = desc.status.as_deref() {
+        return Some(status.to_string());
+    }
+    // Dead-letter queues are never session-enabled and don't forward
+    if is_dlq {
+        return None;
+    }
+    if desc.requires_session == Some(true) {
+        return Some("requires sessions".to_string());
+    }
+    desc.forward_to
+        .as_ref()
+        .map(|target| format!("forwards to {}", target))
+}
+
+/// List a topic's subscriptions as purge candidates, flagging those that
+/// can't be purged.
+pub async fn preflight_topic_purge(
+    mgmt: &ManagementClient,
+    topic: &str,
+    is_dlq: bool,
+) -> Result<Vec<PurgeCandidate>, String> {
+    let subs = mgmt
+        .list_subscriptions(topic)
+        .await
+        .map_err(|e| format!("Failed to list subscriptions: {}", e))?;
+    Ok(subs
+        .iter()
+        .map(|s| {
+            let sub_path = format!("{}/subscriptions/{}", topic, s.name);
+            let problem = purge_blocker(s, is_dlq);
+            PurgeCandidate {
+                path: if is_dlq {
+                    format!("{}/$deadletterqueue", sub_path)
+                } else {
+                    sub_path
+                },
+                selected: problem.is_none(),
+                problem,
+            }
+        })
+        .collect())
+}
+
+/// "Deleted 120 messages (a: 100, b: 20, c: failed)" plus the first failure,
+/// naming paths by their subscription when purging a topic.
+pub fn purge_summary(results: &[(String, Result<u64, String>)]) -> (String, Option<String>) {
+    let total: u64 = results.iter().filter_map(|(_, r)| r.as_ref().ok()).sum();
+    let mut status = format!("Deleted {} messages", total);
+    if results.len() > 1 {
+        let parts: Vec<String> = results
+            .iter()
+            .map(|(path, result)| {
+                let name = purge_path_label(path);
+                match result {
+                    Ok(n) => format!("{}: {}", name, n),
+                    Err(_) => format!("{}: failed", name),
+                }
+            })
+            .collect();
+        status = format!("{} ({})", status, parts.join(", "));
+    }
+    let failure = results.iter().find_map(|(path, result)| {
+        result
+            .as_ref()
+            .err()
+            .map(|e| format!("Purge of {} failed: {}", path, e))
+    });
+    (status, failure)
+}
+
+/// `orders/subscriptions/audit/$deadletterqueue` → `audit`.
+fn purge_path_label(path: &str) -> &str {
+    let path = path.strip_suffix("/$deadletterqueue").unwrap_or(path);
+    match path.rsplit_once("/subscriptions/") {
+        Some((_, sub)) => sub,
+        None => path,
+    }
+}
+
+/// Message ids a dry run lists as a sample of what would be touched.
+pub const DRY_RUN_SAMPLE_IDS: usize = 10;
+
+/// The bulk operation a dry run stands in for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DryRunOp {
+    /// `ClearOptions` d / l: delete everything in the entity (or its DLQs).
+    Purge { is_dlq: bool, is_topic: bool },
+    /// `ClearOptions` r: resend the whole DLQ.
+    ResendAll,
+    /// `ConfirmBulkResend`: resend the peeked DLQ messages.
+    BulkResend,
+    /// `ConfirmBulkDelete`.
+    BulkDelete,
+}
+
+impl DryRunOp {
+    fn verb(self) -> &'static str {
+        match self {
+            DryRunOp::Purge { .. } | DryRunOp::BulkDelete => "delete",
+            DryRunOp::ResendAll | DryRunOp::BulkResend => "resend",
+        }
+    }
+
+    /// Status that starts the real operation from its confirm modal.
+    pub fn trigger_status(self) -> &'static str {
+        match self {
+            DryRunOp::Purge {
+                is_dlq: false,
+                is_topic: true,
+            } => "Checking subscriptions (delete)...",
+            DryRunOp::Purge {
+                is_dlq: true,
+                is_topic: true,
+            } => "Checking subscriptions (delete DLQ)...",
+            DryRunOp::Purge { is_dlq: false, .. } => "Clearing (delete)...",
+            DryRunOp::Purge { is_dlq: true, .. } => "Clearing (delete DLQ)...",
+            DryRunOp::ResendAll => "Clearing (resend)...",
+            DryRunOp::BulkResend => "Bulk resending...",
+            DryRunOp::BulkDelete => "Bulk deleting...",
+        }
+    }
+}
+
+/// What a bulk operation would have touched, found without mutating anything.
+#[derive(Debug, Clone)]
+pub struct DryRunReport {
+    pub op: DryRunOp,
+    /// Where resent messages would go.
+    pub send_target: Option<String>,
+    /// Messages found per path, or why a path could not be scanned.
+    pub counts: Vec<(String, Result<u64, String>)>,
+    pub sample_ids: Vec<String>,
+    /// The confirm modal to reopen (with dry run off) to run it for real.
+    pub then: ActiveModal,
+}
+
+impl DryRunReport {
+    /// "Would delete 120 messages (a: 100, b: 20, c: failed)".
+    pub fn summary(&self) -> String {
+        let total: u64 = self
+            .counts
+            .iter()
+            .filter_map(|(_, r)| r.as_ref().ok())
+            .sum();
+        let mut summary = format!("Would {} {} messages", self.op.verb(), total);
+        if self.counts.len() > 1 {
+            let parts: Vec<String> = self
+                .counts
+                .iter()
+                .map(|(path, result)| match result {
+                    Ok(n) => format!("{}: {}", purge_path_label(path), n),
+                    Err(_) => format!("{}: failed", purge_path_label(path)),
+                })
+                .collect();
+            summary = format!("{} ({})", summary, parts.join(", "));
+        }
+        if let Some(ref target) = self.send_target {
+            summary = format!("{} to {}", summary, target);
+        }
+        summary
+    }
+}
+
+/// Count what a purge or DLQ resend of `paths` would touch. Counts come from
+/// each entity's runtime info; only the first `DRY_RUN_SAMPLE_IDS` messages
+/// are peek-locked for their ids, and those locks are abandoned, so nothing
+/// is completed or sent (the sampled messages' delivery counts go up by one).
+pub async fn dry_run_scan(
+    mgmt: Option<&ManagementClient>,
+    dp: &DataPlaneClient,
+    paths: &[String],
+    cancel: &Arc<AtomicBool>,
+    tx: &BgSender,
+) -> Option<(Vec<(String, Result<u64, String>)>, Vec<String>)> {
+    let mut counts = Vec::with_capacity(paths.len());
+    let mut sample_ids = Vec::new();
+    for (i, path) in paths.iter().enumerate() {
+        if cancel.load(Ordering::Relaxed) {
+            return None;
+        }
+        let _ = tx.send(BgEvent::Progress(format!(
+            "Dry run: counting {} ({}/{})... (Esc to cancel)",
+            path,
+            i + 1,
+            paths.len()
+        )));
+        let count = match mgmt {
+            Some(mgmt) => message_count(mgmt, path).await,
+            None => Err("Not connected".to_string()),
+        };
+        let room = DRY_RUN_SAMPLE_IDS.saturating_sub(sample_ids.len());
+        if matches!(count, Ok(n) if n > 0) && room > 0 {
+            // Sampling is best effort; the count already answers the dry run
+            if let Ok(messages) = dp.peek_messages(path, room as i32).await {
+                sample_ids.extend(
+                    messages
+                        .iter()
+                        .filter_map(|m| m.broker_properties.message_id.clone())
+                        .take(room),
+                );
+            }
+        }
+        counts.push((path.clone(), count));
+    }
+    Some((counts, sample_ids))
+}
+
+/// Messages waiting at `path` (a queue, a subscription, or either's DLQ),
+/// read from the management API's count details.
+async fn message_count(mgmt: &ManagementClient, path: &str) -> Result<u64, String> {
+    let entity = entity_path::without_subqueue(path);
+    let (active, dead_letter) = match entity_path::split_subscription_path(entity) {
+        Some((topic, sub)) => mgmt
+            .get_subscription_runtime_info(topic, sub)
+            .await
+            .map(|info| (info.active_message_count, info.dead_letter_message_count)),
+        None => mgmt
+            .get_queue_runtime_info(entity)
+            .await
+            .map(|info| (info.active_message_count, info.dead_letter_message_count)),
+    }
+    .map_err(|e| e.to_string())?;
+    Ok(pick_count(path, active, dead_letter))
+}
+
+/// The count a dry run of `path` reports: the DLQ's for dead-letter paths,
+/// the active count otherwise.
+fn pick_count(path: &str, active: i64, dead_letter: i64) -> u64 {
+    let n = if entity_path::without_subqueue(path) != path {
+        dead_letter
+    } else {
+        active
+    };
+    n.max(0) as u64
+}
+
 pub async fn resolve_resend_pairs(
     mgmt: Option<&ManagementClient>,
     entity_path: &str,
@@ -65,15 +309,81 @@ pub async fn resolve_resend_pairs(
     }
 }
 
+/// Scheduled message count of the queue or topic at `path`, for confirming
+/// that delayed resends landed. `None` when neither lookup succeeds.
+pub async fn scheduled_count(mgmt: &ManagementClient, path: &str) -> Option<i64> {
+    if let Ok(rt) = mgmt.get_queue_runtime_info(path).await {
+        return Some(rt.scheduled_message_count);
+    }
+    mgmt.get_topic_runtime_info(path)
+        .await
+        .ok()
+        .map(|rt| rt.scheduled_message_count)
+}
+
+/// "scheduled for 13:00:00 UTC · 12 scheduled on orders"
+pub async fn scheduled_note(mgmt: Option<&ManagementClient>, window: String, path: &str) -> String {
+    let count = match mgmt {
+        Some(mgmt) => scheduled_count(mgmt, path).await,
+        None => None,
+    };
+    match count {
+        Some(n) => format!("scheduled for {} · {} scheduled on {}", window, n, path),
+        None => format!("scheduled for {}", window),
+    }
+}
+
+/// A peeked DLQ message bulk resend could not send. The send failed before
+/// the original was removed, so it is still in the DLQ.
+#[derive(Debug, Clone)]
+pub struct ResendFailure {
+    pub message: ReceivedMessage,
+    pub kind: ErrorKind,
+    pub error: String,
+}
+
+impl ResendFailure {
+    /// "seq 42 (order-7): throttled"
+    pub fn describe(&self) -> String {
+        let props = &self.message.broker_properties;
+        let seq = props
+            .sequence_number
+            .map(|n| format!("seq {}", n))
+            .unwrap_or_else(|| "seq ?".to_string());
+        match props.message_id {
+            Some(ref id) => format!("{} ({}): {}", seq, id, self.kind.label()),
+            None => format!("{}: {}", seq, self.kind.label()),
+        }
+    }
+}
+
+/// The messages a bulk resend of `entity_path`'s DLQ failed on, kept so they
+/// can be resent again (`ResendReport` modal).
+#[derive(Debug, Clone)]
+pub struct ResendReport {
+    pub entity_path: String,
+    pub is_topic: bool,
+    pub failures: Vec<ResendFailure>,
+}
+
+impl ResendReport {
+    /// One line for the event log: "failed: seq 42 (order-7): throttled; ..."
+    pub fn log_line(&self) -> String {
+        let parts: Vec<String> = self.failures.iter().map(|f| f.describe()).collect();
+        format!("failed: {}", parts.join("; "))
+    }
+}
+
 pub async fn resend_dlq_loop(
     dp: &DataPlaneClient,
     pairs: &[(String, String)],
     max_per_path: Option<u32>,
     cancel: &Arc<AtomicBool>,
-    tx: &UnboundedSender<BgEvent>,
+    tx: &BgSender,
 ) -> Result<(u32, u32), String> {
     let mut resent = 0u32;
     let mut errors = 0u32;
+    let timeout = dp.lock_timeouts().resend_secs;
 
     for (dlq_path, send_target) in pairs {
         let mut path_count = 0u32;
@@ -90,7 +400,7 @@ pub async fn resend_dlq_loop(
                 ));
             }
 
-            let locked = match dp.peek_lock(dlq_path, 1).await {
+            let locked = match dp.peek_lock(dlq_path, timeout).await {
                 Ok(Some(msg)) => msg,
                 Ok(None) => break,
                 Err(e) => return Err(format!("Resend failed after {} messages: {}", resent, e)),
@@ -121,13 +431,158 @@ pub async fn resend_dlq_loop(
 
             path_count += 1;
             if (resent + errors).is_multiple_of(50) {
-                let _ = tx.send(BgEvent::Progress(format!(
-                    "Resent {} messages ({} errors)... (Esc to cancel)",
-                    resent, errors
-                )));
+                let _ = tx.send(BgEvent::JobProgress {
+                    processed: (resent + errors) as u64,
+                    message: format!(
+                        "Resent {} messages ({} errors)... (Esc to cancel)",
+                        resent, errors
+                    ),
+                });
             }
         }
     }
 
     Ok((resent, errors))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn purge_blockers_depend_on_the_queue_being_purged() {
+        let sub = |requires_session, forward_to: Option<&str>, status: Option<&str>| {
+            SubscriptionDescription {
+                name: "audit".to_string(),
+                requires_session: Some(requires_session),
+                forward_to: forward_to.map(str::to_string),
+                status: status.map(str::to_string),
+                ..Default::default()
+            }
+        };
+        assert_eq!(
+            purge_blocker(&sub(false, None, Some("Active")), false),
+            None
+        );
+        assert_eq!(
+            purge_blocker(&sub(true, None, None), false).as_deref(),
+            Some("requires sessions")
+        );
+        assert_eq!(
+            purge_blocker(&sub(false, Some("archive"), None), false).as_deref(),
+            Some("forwards to archive")
+        );
+        // The DLQ can still be drained
+        assert_eq!(purge_blocker(&sub(true, Some("archive"), None), true), None);
+        assert_eq!(
+            purge_blocker(&sub(false, None, Some("ReceiveDisabled")), true).as_deref(),
+            Some("ReceiveDisabled")
+        );
+    }
+
+    #[test]
+    fn purge_summary_reports_each_path() {
+        let results = vec![
+            ("orders/subscriptions/a".to_string(), Ok(100)),
+            ("orders/subscriptions/b".to_string(), Ok(20)),
+            (
+                "orders/subscriptions/c".to_string(),
+                Err("401 Unauthorized".to_string()),
+            ),
+        ];
+        let (status, failure) = purge_summary(&results);
+        assert_eq!(status, "Deleted 120 messages (a: 100, b: 20, c: failed)");
+        assert_eq!(
+            failure.as_deref(),
+            Some("Purge of orders/subscriptions/c failed: 401 Unauthorized")
+        );
+
+        let (status, failure) = purge_summary(&[("orders".to_string(), Ok(3))]);
+        assert_eq!(status, "Deleted 3 messages");
+        assert_eq!(failure, None);
+    }
+
+    #[test]
+    fn resend_report_names_each_failure() {
+        use crate::client::models::BrokerProperties;
+        let failure = |seq, id: Option<&str>, kind| ResendFailure {
+            message: ReceivedMessage {
+                body: String::new(),
+                broker_properties: BrokerProperties {
+                    sequence_number: Some(seq),
+                    message_id: id.map(str::to_string),
+                    ..Default::default()
+                },
+                custom_properties: Vec::new(),
+                lock_token_uri: None,
+                source_entity: None,
+                raw_body: None,
+            },
+            kind,
+            error: "boom".to_string(),
+        };
+        let report = ResendReport {
+            entity_path: "orders".to_string(),
+            is_topic: false,
+            failures: vec![
+                failure(42, Some("order-7"), ErrorKind::Throttled),
+                failure(43, None, ErrorKind::QuotaExceeded),
+            ],
+        };
+        assert_eq!(
+            report.log_line(),
+            "failed: seq 42 (order-7): throttled; seq 43: quota exceeded"
+        );
+    }
+
+    #[test]
+    fn dry_run_summary_reports_each_path() {
+        let mut report = DryRunReport {
+            op: DryRunOp::Purge {
+                is_dlq: true,
+                is_topic: true,
+            },
+            send_target: None,
+            counts: vec![
+                (
+                    "orders/subscriptions/a/$deadletterqueue".to_string(),
+                    Ok(20),
+                ),
+                (
+                    "orders/subscriptions/b/$deadletterqueue".to_string(),
+                    Ok(1000),
+                ),
+                (
+                    "orders/subscriptions/c/$deadletterqueue".to_string(),
+                    Err("403 Forbidden".to_string()),
+                ),
+            ],
+            sample_ids: Vec::new(),
+            then: ActiveModal::None,
+        };
+        assert_eq!(
+            report.summary(),
+            "Would delete 1020 messages (a: 20, b: 1000, c: failed)"
+        );
+        assert_eq!(
+            report.op.trigger_status(),
+            "Checking subscriptions (delete DLQ)..."
+        );
+
+        report.op = DryRunOp::BulkResend;
+        report.send_target = Some("orders".to_string());
+        report.counts = vec![("orders/$deadletterqueue".to_string(), Ok(1500))];
+        assert_eq!(report.summary(), "Would resend 1500 messages to orders");
+    }
+
+    #[test]
+    fn dry_run_counts_come_from_the_matching_queue() {
+        assert_eq!(pick_count("orders", 12, 3), 12);
+        assert_eq!(pick_count("orders/$deadletterqueue", 12, 3), 3);
+        assert_eq!(
+            pick_count("orders/subscriptions/audit/$deadletterqueue", 12, 3),
+            3
+        );
+        assert_eq!(pick_count("orders/subscriptions/audit", -1, 3), 0);
+    }
+}
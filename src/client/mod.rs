@@ -1,12 +0,0 @@
-pub mod auth;
-pub mod data_plane;
-pub mod entity_path;
-pub mod error;
-pub mod management;
-pub mod models;
-pub mod resource_manager;
-
-pub use auth::ConnectionConfig;
-pub use data_plane::DataPlaneClient;
-pub use error::{Result, ServiceBusError};
-pub use management::ManagementClient;
@@ -0,0 +1,14 @@
+use std::io::{self, Write};
+
+use base64::Engine;
+
+/// Copy text to the system clipboard using the OSC 52 terminal escape.
+///
+/// Works in most modern terminals (including over SSH) without a native
+/// clipboard dependency; terminals that don't support it simply ignore it.
+pub fn copy_to_clipboard(text: &str) -> io::Result<()> {
+    let encoded = base64::engine::general_purpose::STANDARD.encode(text);
+    let mut stdout = io::stdout();
+    write!(stdout, "\x1b]52;c;{}\x07", encoded)?;
+    stdout.flush()
+}
@@ -1,13 +1,201 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use crate::client::resource_manager::{ArmIdentity, DiscoveryResult};
+use crate::message_filter::FilterPreset;
+
+/// Config file given with `--config`; replaces the platform default.
+static CONFIG_PATH_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
 
 /// Application configuration, persisted as TOML.
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppConfig {
     #[serde(default)]
     pub connections: Vec<SavedConnection>,
     #[serde(default)]
     pub settings: AppSettings,
+    /// Columns shown in the message table, in display order.
+    #[serde(default = "MessageColumn::defaults")]
+    pub message_columns: Vec<MessageColumn>,
+    /// Send-form fields remembered per connection and entity, least recently
+    /// used first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub send_defaults: Vec<SendDefaults>,
+    /// Saved message filters, in the order they were added.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub filter_presets: Vec<FilterPreset>,
+    /// Last namespace discovery per tenant and account, reused while
+    /// younger than `discovery_cache_ttl_minutes`.
+    #[serde(
+        default,
+        skip_serializing_if = "Vec::is_empty",
+        deserialize_with = "keyed_discovery_cache"
+    )]
+    pub discovery_cache: Vec<DiscoveryCacheEntry>,
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            connections: Vec::new(),
+            settings: AppSettings::default(),
+            message_columns: MessageColumn::defaults(),
+            send_defaults: Vec::new(),
+            filter_presets: Vec::new(),
+            discovery_cache: Vec::new(),
+        }
+    }
+}
+
+/// Older versions kept one `[discovery_cache]` table without saying whose
+/// discovery it was; such a cache is dropped rather than failing the load.
+fn keyed_discovery_cache<'de, D>(deserializer: D) -> Result<Vec<DiscoveryCacheEntry>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Stored {
+        Keyed(Vec<DiscoveryCacheEntry>),
+        Unkeyed(serde::de::IgnoredAny),
+    }
+    Ok(match Stored::deserialize(deserializer)? {
+        Stored::Keyed(entries) => entries,
+        Stored::Unkeyed(_) => Vec::new(),
+    })
+}
+
+/// A namespace discovery result with who ran it and when it was fetched.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiscoveryCacheEntry {
+    pub tenant_id: String,
+    pub account: String,
+    pub result: DiscoveryResult,
+    /// RFC 3339.
+    pub fetched_at: String,
+}
+
+impl DiscoveryCacheEntry {
+    pub fn new(identity: &ArmIdentity, result: DiscoveryResult, now: DateTime<Utc>) -> Self {
+        Self {
+            tenant_id: identity.tenant_id.clone(),
+            account: identity.account.clone(),
+            result,
+            fetched_at: now.to_rfc3339(),
+        }
+    }
+
+    pub fn identity(&self) -> ArmIdentity {
+        ArmIdentity {
+            tenant_id: self.tenant_id.clone(),
+            account: self.account.clone(),
+        }
+    }
+
+    /// Fetched by `identity`; accounts compare case-insensitively, as
+    /// Azure AD treats them.
+    pub fn is_for(&self, identity: &ArmIdentity) -> bool {
+        self.tenant_id == identity.tenant_id && self.account.eq_ignore_ascii_case(&identity.account)
+    }
+
+    /// Whole minutes since the fetch; `None` if `fetched_at` doesn't parse.
+    pub fn age_minutes(&self, now: DateTime<Utc>) -> Option<i64> {
+        let fetched = DateTime::parse_from_rfc3339(&self.fetched_at).ok()?;
+        Some((now - fetched.with_timezone(&Utc)).num_minutes())
+    }
+
+    /// Young enough to show instead of discovering again. A TTL of 0 turns
+    /// the cache off; entries dated in the future are not trusted.
+    pub fn is_fresh(&self, ttl_minutes: u64, now: DateTime<Utc>) -> bool {
+        ttl_minutes > 0
+            && self
+                .age_minutes(now)
+                .is_some_and(|age| (0..ttl_minutes as i64).contains(&age))
+    }
+}
+
+/// Most entities whose send defaults are kept; the least recently used go first.
+const MAX_SEND_DEFAULTS: usize = 100;
+
+/// Send-form fields pre-filled for an entity, taken from the last successful
+/// send to it.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SendDefaults {
+    pub connection: String,
+    pub entity: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    /// Custom property names with their values, or with empty values unless
+    /// `remember_property_values` is on.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub custom_properties: Vec<(String, String)>,
+}
+
+/// A column that can be shown in the message table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MessageColumn {
+    Index,
+    MessageId,
+    SequenceNumber,
+    Size,
+    EnqueuedTime,
+    DeliveryCount,
+    SessionId,
+    CorrelationId,
+    Label,
+    State,
+    /// Time left until the message expires.
+    Expires,
+}
+
+impl MessageColumn {
+    pub const ALL: [MessageColumn; 11] = [
+        MessageColumn::Index,
+        MessageColumn::MessageId,
+        MessageColumn::SequenceNumber,
+        MessageColumn::Size,
+        MessageColumn::EnqueuedTime,
+        MessageColumn::DeliveryCount,
+        MessageColumn::SessionId,
+        MessageColumn::CorrelationId,
+        MessageColumn::Label,
+        MessageColumn::State,
+        MessageColumn::Expires,
+    ];
+
+    /// The original fixed layout: #, Message ID, Seq #, Subject, Enqueued.
+    pub fn defaults() -> Vec<MessageColumn> {
+        vec![
+            MessageColumn::Index,
+            MessageColumn::MessageId,
+            MessageColumn::SequenceNumber,
+            MessageColumn::Label,
+            MessageColumn::EnqueuedTime,
+        ]
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            MessageColumn::Index => "#",
+            MessageColumn::MessageId => "Message ID",
+            MessageColumn::SequenceNumber => "Seq #",
+            MessageColumn::Size => "Size",
+            MessageColumn::EnqueuedTime => "Enqueued",
+            MessageColumn::DeliveryCount => "Deliveries",
+            MessageColumn::SessionId => "Session ID",
+            MessageColumn::CorrelationId => "Correlation ID",
+            MessageColumn::Label => "Subject",
+            MessageColumn::State => "State",
+            MessageColumn::Expires => "Expires",
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -23,6 +211,9 @@ pub struct SavedConnection {
     /// Authentication type tag: "sas" (default) or "azure_ad".
     #[serde(default = "default_auth_type")]
     pub auth_type: String,
+    /// Optional group the connection is listed under (e.g. "staging").
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
 }
 
 fn default_auth_type() -> String {
@@ -40,78 +231,660 @@ pub struct AppSettings {
     pub peek_count: i32,
     pub auto_refresh_secs: u64,
     pub log_to_file: bool,
+    /// Upper bound for a single peek, including the "all" preset.
+    #[serde(default = "default_peek_max")]
+    pub peek_max: i32,
+    /// Last peek count used per entity kind ("queue", "topic", "subscription").
+    #[serde(default)]
+    pub last_peek_counts: BTreeMap<String, i32>,
+    /// Server-side wait (seconds) for each peek-lock while peeking.
+    #[serde(default = "default_peek_lock_timeout_secs")]
+    pub peek_lock_timeout_secs: u32,
+    /// Server-side wait (seconds) for each peek-lock during DLQ resend/removal.
+    #[serde(default = "default_resend_lock_timeout_secs")]
+    pub resend_lock_timeout_secs: u32,
+    /// Check JSON/XML bodies for well-formedness before sending. Disable to
+    /// send intentionally malformed test payloads.
+    #[serde(default = "default_true")]
+    pub validate_body: bool,
+    /// Refuse to send or resend a message with an empty body, for consumers
+    /// that can't handle one.
+    #[serde(default)]
+    pub require_non_empty_body: bool,
+    /// Wrap long lines in the message body view (`W` toggles).
+    #[serde(default = "default_true")]
+    pub body_wrap: bool,
+    /// Flag queues/subscriptions whose DLQ holds at least this many messages.
+    #[serde(default)]
+    pub dlq_alert_threshold: Option<i64>,
+    /// Per-entity overrides of `dlq_alert_threshold`, keyed by entity path.
+    #[serde(default)]
+    pub dlq_alert_thresholds: BTreeMap<String, i64>,
+    /// Ring the terminal bell when an entity newly crosses its DLQ threshold.
+    #[serde(default = "default_true")]
+    pub alert_bell: bool,
+    /// Parallel receive-and-delete workers per purged path. Lower this for
+    /// small namespaces that throttle.
+    #[serde(default = "default_purge_concurrency")]
+    pub purge_concurrency: usize,
+    /// Subscription DLQs peeked at once when peeking a topic's dead letters.
+    #[serde(default = "default_peek_concurrency")]
+    pub peek_concurrency: usize,
+    /// Messages peeked from each active queue and DLQ by the namespace search.
+    #[serde(default = "default_search_peek_cap")]
+    pub search_peek_cap: i32,
+    /// Render without colors, using bold/underline/reverse instead. Also
+    /// turned on by `--no-color`, `NO_COLOR` or `TERM=dumb`.
+    #[serde(default)]
+    pub no_color: bool,
+    /// Also remember custom property values (not just names) as send defaults.
+    #[serde(default)]
+    pub remember_property_values: bool,
+    /// Ping the management API this often while connected; `H` checks once.
+    #[serde(default)]
+    pub health_check_interval_secs: Option<u64>,
+    /// Reconnect to the last connection and reselect the last entity on
+    /// launch (`--no-restore` skips it once).
+    #[serde(default = "default_true")]
+    pub restore_session: bool,
+    /// Highlight messages that expire within this many seconds (0 = off).
+    #[serde(default = "default_expiry_warning_secs")]
+    pub expiry_warning_secs: u64,
+    /// Reuse the last namespace discovery for this many minutes (0 = off).
+    #[serde(default = "default_discovery_cache_ttl_minutes")]
+    pub discovery_cache_ttl_minutes: u64,
+}
+
+fn default_true() -> bool {
+    true
 }
 
+fn default_peek_max() -> i32 {
+    5000
+}
+
+fn default_peek_lock_timeout_secs() -> u32 {
+    1
+}
+
+fn default_resend_lock_timeout_secs() -> u32 {
+    5
+}
+
+fn default_purge_concurrency() -> usize {
+    32
+}
+
+fn default_peek_concurrency() -> usize {
+    4
+}
+
+fn default_search_peek_cap() -> i32 {
+    100
+}
+
+fn default_expiry_warning_secs() -> u64 {
+    300
+}
+
+fn default_discovery_cache_ttl_minutes() -> u64 {
+    60
+}
+
+const MAX_PEEK: i32 = 100_000;
+const MAX_CONCURRENCY: usize = 256;
+const MAX_LOCK_TIMEOUT_SECS: u32 = 60;
+const MIN_HEALTH_CHECK_INTERVAL_SECS: u64 = 5;
+const MAX_EXPIRY_WARNING_SECS: u64 = 30 * 86_400;
+
 impl Default for AppSettings {
     fn default() -> Self {
         Self {
             peek_count: 25,
             auto_refresh_secs: 0, // 0 = disabled
             log_to_file: false,
+            peek_max: default_peek_max(),
+            last_peek_counts: BTreeMap::new(),
+            peek_lock_timeout_secs: default_peek_lock_timeout_secs(),
+            resend_lock_timeout_secs: default_resend_lock_timeout_secs(),
+            validate_body: true,
+            require_non_empty_body: false,
+            body_wrap: true,
+            dlq_alert_threshold: None,
+            dlq_alert_thresholds: BTreeMap::new(),
+            alert_bell: true,
+            purge_concurrency: default_purge_concurrency(),
+            peek_concurrency: default_peek_concurrency(),
+            search_peek_cap: default_search_peek_cap(),
+            no_color: false,
+            remember_property_values: false,
+            health_check_interval_secs: None,
+            restore_session: true,
+            expiry_warning_secs: default_expiry_warning_secs(),
+            discovery_cache_ttl_minutes: default_discovery_cache_ttl_minutes(),
+        }
+    }
+}
+
+/// A setting that can be changed from the in-app settings editor (`,`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingField {
+    PeekCount,
+    PeekMax,
+    PeekConcurrency,
+    PurgeConcurrency,
+    SearchPeekCap,
+    AutoRefreshSecs,
+    HealthCheckIntervalSecs,
+    ExpiryWarningSecs,
+    DiscoveryCacheTtlMinutes,
+    PeekLockTimeoutSecs,
+    ResendLockTimeoutSecs,
+    DlqAlertThreshold,
+    ValidateBody,
+    RequireNonEmptyBody,
+    BodyWrap,
+    AlertBell,
+    NoColor,
+    RememberPropertyValues,
+    RestoreSession,
+}
+
+impl SettingField {
+    pub const ALL: [SettingField; 19] = [
+        SettingField::PeekCount,
+        SettingField::PeekMax,
+        SettingField::PeekConcurrency,
+        SettingField::PurgeConcurrency,
+        SettingField::SearchPeekCap,
+        SettingField::AutoRefreshSecs,
+        SettingField::HealthCheckIntervalSecs,
+        SettingField::ExpiryWarningSecs,
+        SettingField::DiscoveryCacheTtlMinutes,
+        SettingField::PeekLockTimeoutSecs,
+        SettingField::ResendLockTimeoutSecs,
+        SettingField::DlqAlertThreshold,
+        SettingField::ValidateBody,
+        SettingField::RequireNonEmptyBody,
+        SettingField::BodyWrap,
+        SettingField::AlertBell,
+        SettingField::NoColor,
+        SettingField::RememberPropertyValues,
+        SettingField::RestoreSession,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SettingField::PeekCount => "Default peek count",
+            SettingField::PeekMax => "Max peek count",
+            SettingField::PeekConcurrency => "Peek concurrency",
+            SettingField::PurgeConcurrency => "Purge concurrency",
+            SettingField::SearchPeekCap => "Search peek per entity",
+            SettingField::AutoRefreshSecs => "Auto-refresh (s, 0 = off)",
+            SettingField::HealthCheckIntervalSecs => "Health check every (s)",
+            SettingField::ExpiryWarningSecs => "Expiry warning (s, 0 = off)",
+            SettingField::DiscoveryCacheTtlMinutes => "Discovery cache (min, 0 = off)",
+            SettingField::PeekLockTimeoutSecs => "Peek lock timeout (s)",
+            SettingField::ResendLockTimeoutSecs => "Resend lock timeout (s)",
+            SettingField::DlqAlertThreshold => "DLQ alert threshold",
+            SettingField::ValidateBody => "Validate JSON/XML bodies",
+            SettingField::RequireNonEmptyBody => "Require a message body",
+            SettingField::BodyWrap => "Wrap message bodies",
+            SettingField::AlertBell => "Bell on DLQ alert",
+            SettingField::NoColor => "Monochrome (no color)",
+            SettingField::RememberPropertyValues => "Remember property values",
+            SettingField::RestoreSession => "Restore last session",
+        }
+    }
+
+    /// On/off settings are toggled instead of typed.
+    pub fn is_toggle(self) -> bool {
+        matches!(
+            self,
+            SettingField::ValidateBody
+                | SettingField::RequireNonEmptyBody
+                | SettingField::BodyWrap
+                | SettingField::AlertBell
+                | SettingField::NoColor
+                | SettingField::RememberPropertyValues
+                | SettingField::RestoreSession
+        )
+    }
+}
+
+impl AppSettings {
+    /// Peek count to pre-fill for an entity kind: the last one used, else the default.
+    pub fn peek_count_for(&self, kind: &str) -> i32 {
+        self.last_peek_counts
+            .get(kind)
+            .copied()
+            .unwrap_or(self.peek_count)
+    }
+
+    pub fn value_of(&self, field: SettingField) -> String {
+        match field {
+            SettingField::PeekCount => self.peek_count.to_string(),
+            SettingField::PeekMax => self.peek_max.to_string(),
+            SettingField::PeekConcurrency => self.peek_concurrency.to_string(),
+            SettingField::PurgeConcurrency => self.purge_concurrency.to_string(),
+            SettingField::SearchPeekCap => self.search_peek_cap.to_string(),
+            SettingField::AutoRefreshSecs => self.auto_refresh_secs.to_string(),
+            SettingField::HealthCheckIntervalSecs => self
+                .health_check_interval_secs
+                .map(|s| s.to_string())
+                .unwrap_or_default(),
+            SettingField::ExpiryWarningSecs => self.expiry_warning_secs.to_string(),
+            SettingField::DiscoveryCacheTtlMinutes => self.discovery_cache_ttl_minutes.to_string(),
+            SettingField::PeekLockTimeoutSecs => self.peek_lock_timeout_secs.to_string(),
+            SettingField::ResendLockTimeoutSecs => self.resend_lock_timeout_secs.to_string(),
+            SettingField::DlqAlertThreshold => self
+                .dlq_alert_threshold
+                .map(|t| t.to_string())
+                .unwrap_or_default(),
+            SettingField::ValidateBody => self.validate_body.to_string(),
+            SettingField::RequireNonEmptyBody => self.require_non_empty_body.to_string(),
+            SettingField::BodyWrap => self.body_wrap.to_string(),
+            SettingField::AlertBell => self.alert_bell.to_string(),
+            SettingField::NoColor => self.no_color.to_string(),
+            SettingField::RememberPropertyValues => self.remember_property_values.to_string(),
+            SettingField::RestoreSession => self.restore_session.to_string(),
+        }
+    }
+
+    /// Parse and range-check `raw` for `field`; the setting is left untouched
+    /// on error.
+    pub fn set_value(&mut self, field: SettingField, raw: &str) -> Result<(), String> {
+        let raw = raw.trim();
+        match field {
+            SettingField::PeekCount => {
+                self.peek_count = parse_in_range(raw, 1, self.peek_max)?;
+            }
+            SettingField::PeekMax => {
+                self.peek_max = parse_in_range(raw, 1, MAX_PEEK)?;
+                self.peek_count = self.peek_count.min(self.peek_max);
+            }
+            SettingField::PeekConcurrency => {
+                self.peek_concurrency = parse_in_range(raw, 1, MAX_CONCURRENCY)?;
+            }
+            SettingField::PurgeConcurrency => {
+                self.purge_concurrency = parse_in_range(raw, 1, MAX_CONCURRENCY)?;
+            }
+            SettingField::SearchPeekCap => {
+                self.search_peek_cap = parse_in_range(raw, 1, MAX_PEEK)?;
+            }
+            SettingField::AutoRefreshSecs => {
+                self.auto_refresh_secs = parse_in_range(raw, 0, 86_400)?;
+            }
+            SettingField::HealthCheckIntervalSecs => {
+                self.health_check_interval_secs = if raw.is_empty() {
+                    None
+                } else {
+                    Some(parse_in_range(raw, MIN_HEALTH_CHECK_INTERVAL_SECS, 86_400)?)
+                };
+            }
+            SettingField::ExpiryWarningSecs => {
+                self.expiry_warning_secs = parse_in_range(raw, 0, MAX_EXPIRY_WARNING_SECS)?;
+            }
+            SettingField::DiscoveryCacheTtlMinutes => {
+                self.discovery_cache_ttl_minutes = parse_in_range(raw, 0, 30 * 1_440)?;
+            }
+            SettingField::PeekLockTimeoutSecs => {
+                self.peek_lock_timeout_secs = parse_in_range(raw, 1, MAX_LOCK_TIMEOUT_SECS)?;
+            }
+            SettingField::ResendLockTimeoutSecs => {
+                self.resend_lock_timeout_secs = parse_in_range(raw, 1, MAX_LOCK_TIMEOUT_SECS)?;
+            }
+            SettingField::DlqAlertThreshold => {
+                self.dlq_alert_threshold = if raw.is_empty() {
+                    None
+                } else {
+                    Some(parse_in_range(raw, 1, i64::MAX)?)
+                };
+            }
+            SettingField::ValidateBody => self.validate_body = parse_bool(raw)?,
+            SettingField::RequireNonEmptyBody => self.require_non_empty_body = parse_bool(raw)?,
+            SettingField::BodyWrap => self.body_wrap = parse_bool(raw)?,
+            SettingField::AlertBell => self.alert_bell = parse_bool(raw)?,
+            SettingField::NoColor => self.no_color = parse_bool(raw)?,
+            SettingField::RememberPropertyValues => {
+                self.remember_property_values = parse_bool(raw)?
+            }
+            SettingField::RestoreSession => self.restore_session = parse_bool(raw)?,
+        }
+        Ok(())
+    }
+
+    /// Reset out-of-range values from a hand-edited config file to defaults.
+    fn validate(&mut self) {
+        let defaults = Self::default();
+        if !(1..=MAX_PEEK).contains(&self.peek_max) {
+            self.peek_max = defaults.peek_max;
+        }
+        if !(1..=self.peek_max).contains(&self.peek_count) {
+            self.peek_count = defaults.peek_count.min(self.peek_max);
+        }
+        if !(1..=MAX_CONCURRENCY).contains(&self.purge_concurrency) {
+            self.purge_concurrency = defaults.purge_concurrency;
+        }
+        if !(1..=MAX_CONCURRENCY).contains(&self.peek_concurrency) {
+            self.peek_concurrency = defaults.peek_concurrency;
+        }
+        if !(1..=MAX_PEEK).contains(&self.search_peek_cap) {
+            self.search_peek_cap = defaults.search_peek_cap;
+        }
+        if !(1..=MAX_LOCK_TIMEOUT_SECS).contains(&self.peek_lock_timeout_secs) {
+            self.peek_lock_timeout_secs = defaults.peek_lock_timeout_secs;
+        }
+        if !(1..=MAX_LOCK_TIMEOUT_SECS).contains(&self.resend_lock_timeout_secs) {
+            self.resend_lock_timeout_secs = defaults.resend_lock_timeout_secs;
         }
+        if self
+            .health_check_interval_secs
+            .is_some_and(|s| s < MIN_HEALTH_CHECK_INTERVAL_SECS)
+        {
+            self.health_check_interval_secs = Some(MIN_HEALTH_CHECK_INTERVAL_SECS);
+        }
+        self.expiry_warning_secs = self.expiry_warning_secs.min(MAX_EXPIRY_WARNING_SECS);
+    }
+
+    /// DLQ alert threshold for an entity path: its override, else the global one.
+    pub fn dlq_threshold_for(&self, path: &str) -> Option<i64> {
+        self.dlq_alert_thresholds
+            .get(path)
+            .copied()
+            .or(self.dlq_alert_threshold)
+    }
+}
+
+fn parse_in_range<T>(raw: &str, min: T, max: T) -> Result<T, String>
+where
+    T: std::str::FromStr + PartialOrd + std::fmt::Display + Copy,
+{
+    match raw.parse::<T>() {
+        Ok(v) if v >= min && v <= max => Ok(v),
+        _ => Err(format!("Enter a whole number from {} to {}", min, max)),
+    }
+}
+
+fn parse_bool(raw: &str) -> Result<bool, String> {
+    match raw.to_ascii_lowercase().as_str() {
+        "true" | "on" | "yes" | "1" => Ok(true),
+        "false" | "off" | "no" | "0" => Ok(false),
+        _ => Err("Enter true or false".to_string()),
     }
 }
 
 impl AppConfig {
-    /// Standard config file path: ~/.config/sb-explorer/config.toml
+    /// Config file path: the `--config` override if given, otherwise
+    /// `sb-explorer/config.toml` under the platform config directory
+    /// (`$XDG_CONFIG_HOME` or `~/.config` on Linux).
     pub fn config_path() -> PathBuf {
+        if let Some(path) = CONFIG_PATH_OVERRIDE.get() {
+            return path.clone();
+        }
         let base = dirs_fallback();
         base.join("sb-explorer").join("config.toml")
     }
 
-    /// Load config from disk. Returns default if file doesn't exist.
-    pub fn load() -> Self {
+    /// Use `path` instead of the platform default. Must be called before the
+    /// config is first loaded; later calls are ignored.
+    pub fn set_config_path(path: PathBuf) {
+        let _ = CONFIG_PATH_OVERRIDE.set(path);
+    }
+
+    /// Load config from disk. Returns default if file doesn't exist, and
+    /// default plus the reason if it can't be read or parsed. A broken file
+    /// is copied aside first (`config.toml.bak`) so the next save can't
+    /// lose what was in it.
+    pub fn load() -> (Self, Option<String>) {
         let path = Self::config_path();
-        if path.exists() {
-            let content = std::fs::read_to_string(&path).unwrap_or_default();
-            toml::from_str(&content).unwrap_or_default()
-        } else {
-            Self::default()
+        if !path.exists() {
+            return (Self::default(), None);
+        }
+        let loaded = std::fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|content| Self::parse(&content));
+        match loaded {
+            Ok(config) => (config, None),
+            Err(e) => {
+                let backup = path.with_extension("toml.bak");
+                let mut message = format!(
+                    "Config {} not loaded, using defaults: {}",
+                    path.display(),
+                    e
+                );
+                if std::fs::copy(&path, &backup).is_ok() {
+                    message.push_str(&format!(" (copy kept at {})", backup.display()));
+                }
+                (Self::default(), Some(message))
+            }
         }
     }
 
-    /// Save config to disk.
+    /// Parse config file contents; errors name the line, to fit the status bar.
+    fn parse(content: &str) -> Result<Self, String> {
+        let mut config: Self = toml::from_str(content).map_err(|e| match e.span() {
+            Some(span) => {
+                let line = content.as_bytes()[..span.start.min(content.len())]
+                    .iter()
+                    .filter(|&&b| b == b'\n')
+                    .count()
+                    + 1;
+                format!("line {}: {}", line, e.message().trim())
+            }
+            None => e.message().trim().to_string(),
+        })?;
+        config.settings.validate();
+        Ok(config)
+    }
+
+    /// Save config to disk. Blocking; the app queues saves with
+    /// `App::save_config` instead of calling this on the UI thread.
     pub fn save(&self) -> anyhow::Result<()> {
-        let path = Self::config_path();
-        if let Some(parent) = path.parent() {
-            std::fs::create_dir_all(parent)?;
-        }
         let content = toml::to_string_pretty(self)?;
-        std::fs::write(&path, content)?;
+        write_atomic(&Self::config_path(), &content)?;
         Ok(())
     }
 
-    pub fn add_connection(&mut self, name: String, connection_string: String) {
-        // Remove existing with same name
-        self.connections.retain(|c| c.name != name);
+    /// Save a SAS connection to `namespace` and return its name. Saving the same
+    /// connection string again keeps the existing entry (and any rename);
+    /// otherwise the name is derived from the namespace, see `unique_name`.
+    pub fn add_connection(&mut self, namespace: &str, connection_string: String) -> String {
+        if let Some(existing) = self
+            .connections
+            .iter()
+            .find(|c| c.connection_string.as_deref() == Some(connection_string.as_str()))
+        {
+            return existing.name.clone();
+        }
+        let name = self.unique_name(namespace);
         self.connections.push(SavedConnection {
-            name,
+            name: name.clone(),
             connection_string: Some(connection_string),
             namespace: None,
             auth_type: "sas".to_string(),
+            group: None,
         });
+        name
     }
 
-    pub fn add_azure_ad_connection(&mut self, name: String, namespace: String) {
-        self.connections.retain(|c| c.name != name);
+    /// Save an Azure AD connection to the fully-qualified `namespace` and
+    /// return its name (existing entry for the same namespace is reused).
+    pub fn add_azure_ad_connection(&mut self, namespace: String) -> String {
+        if let Some(existing) = self.connections.iter().find(|c| {
+            c.is_azure_ad()
+                && c.namespace
+                    .as_deref()
+                    .is_some_and(|ns| ns.eq_ignore_ascii_case(&namespace))
+        }) {
+            return existing.name.clone();
+        }
+        let name = self.unique_name(&namespace);
         self.connections.push(SavedConnection {
-            name,
+            name: name.clone(),
             connection_string: None,
             namespace: Some(namespace),
             auth_type: "azure_ad".to_string(),
+            group: None,
         });
+        name
+    }
+
+    /// Connection name for a namespace: the part before the first `.`
+    /// (`orders-staging.servicebus.windows.net` → `orders-staging`), with
+    /// `-2`, `-3`, … appended while that name is taken.
+    fn unique_name(&self, namespace: &str) -> String {
+        let base = namespace
+            .trim_start_matches("sb://")
+            .split(['.', '/', ':'])
+            .next()
+            .filter(|s| !s.is_empty())
+            .unwrap_or("default");
+        let taken = |name: &str| self.connections.iter().any(|c| c.name == name);
+        if !taken(base) {
+            return base.to_string();
+        }
+        (2..)
+            .map(|n| format!("{}-{}", base, n))
+            .find(|name| !taken(name))
+            .unwrap_or_else(|| base.to_string())
+    }
+
+    /// Give the saved connection `name` a new connection string (after its
+    /// key was rotated). Returns false when there is no such connection.
+    pub fn replace_connection_string(&mut self, name: &str, connection_string: String) -> bool {
+        let Some(conn) = self.connections.iter_mut().find(|c| c.name == name) else {
+            return false;
+        };
+        conn.connection_string = Some(connection_string);
+        conn.namespace = None;
+        conn.auth_type = "sas".to_string();
+        true
+    }
+
+    /// Rename the saved connection `name` to `new_name` (trimmed), carrying
+    /// its remembered send defaults along. Returns the new name.
+    pub fn rename_connection(&mut self, name: &str, new_name: &str) -> Result<String, String> {
+        let new_name = new_name.trim();
+        if new_name.is_empty() {
+            return Err("Connection name can't be empty".to_string());
+        }
+        if new_name != name && self.connections.iter().any(|c| c.name == new_name) {
+            return Err(format!("A connection named '{}' already exists", new_name));
+        }
+        let Some(conn) = self.connections.iter_mut().find(|c| c.name == name) else {
+            return Err(format!("No saved connection named '{}'", name));
+        };
+        conn.name = new_name.to_string();
+        for defaults in self.send_defaults.iter_mut() {
+            if defaults.connection == name {
+                defaults.connection = new_name.to_string();
+            }
+        }
+        Ok(new_name.to_string())
+    }
+
+    /// Switch the saved connection `name` to Azure AD against the
+    /// fully-qualified `namespace`. Returns false when there is no such
+    /// connection.
+    pub fn replace_with_azure_ad(&mut self, name: &str, namespace: String) -> bool {
+        let Some(conn) = self.connections.iter_mut().find(|c| c.name == name) else {
+            return false;
+        };
+        conn.connection_string = None;
+        conn.namespace = Some(namespace);
+        conn.auth_type = "azure_ad".to_string();
+        true
     }
 
     pub fn remove_connection(&mut self, name: &str) {
         self.connections.retain(|c| c.name != name);
     }
+
+    /// Put the connection `name` in `group`; blank or `None` ungroups it.
+    pub fn set_connection_group(&mut self, name: &str, group: Option<String>) {
+        let group = group
+            .map(|g| g.trim().to_string())
+            .filter(|g| !g.is_empty());
+        if let Some(conn) = self.connections.iter_mut().find(|c| c.name == name) {
+            conn.group = group;
+        }
+    }
+
+    /// Connection indices as listed: groups sorted by name, each keeping
+    /// the saved order of its connections, with ungrouped ones (`None`) last.
+    pub fn connection_groups(&self) -> Vec<(Option<&str>, Vec<usize>)> {
+        let mut groups: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+        let mut ungrouped = Vec::new();
+        for (idx, conn) in self.connections.iter().enumerate() {
+            match conn.group.as_deref() {
+                Some(group) => groups.entry(group).or_default().push(idx),
+                None => ungrouped.push(idx),
+            }
+        }
+        let mut listed: Vec<(Option<&str>, Vec<usize>)> = groups
+            .into_iter()
+            .map(|(group, indices)| (Some(group), indices))
+            .collect();
+        if !ungrouped.is_empty() {
+            listed.push((None, ungrouped));
+        }
+        listed
+    }
+
+    pub fn send_defaults_for(&self, connection: &str, entity: &str) -> Option<&SendDefaults> {
+        self.send_defaults
+            .iter()
+            .find(|d| d.connection == connection && d.entity == entity)
+    }
+
+    /// Store `defaults` as the most recently used, replacing the entity's
+    /// previous ones and dropping the oldest past `MAX_SEND_DEFAULTS`.
+    pub fn remember_send_defaults(&mut self, defaults: SendDefaults) {
+        self.forget_send_defaults(&defaults.connection, &defaults.entity);
+        self.send_defaults.push(defaults);
+        let excess = self.send_defaults.len().saturating_sub(MAX_SEND_DEFAULTS);
+        self.send_defaults.drain(..excess);
+    }
+
+    /// Drop an entity's send defaults. Returns whether there were any.
+    pub fn forget_send_defaults(&mut self, connection: &str, entity: &str) -> bool {
+        let before = self.send_defaults.len();
+        self.send_defaults
+            .retain(|d| !(d.connection == connection && d.entity == entity));
+        self.send_defaults.len() != before
+    }
+
+    /// Add `preset`, or replace the preset of the same name in place.
+    pub fn save_filter_preset(&mut self, preset: FilterPreset) {
+        match self
+            .filter_presets
+            .iter_mut()
+            .find(|p| p.name == preset.name)
+        {
+            Some(existing) => *existing = preset,
+            None => self.filter_presets.push(preset),
+        }
+    }
+
+    /// Add `entry`, or replace the cached discovery of the same identity.
+    pub fn cache_discovery(&mut self, entry: DiscoveryCacheEntry) {
+        let identity = entry.identity();
+        self.discovery_cache.retain(|e| !e.is_for(&identity));
+        self.discovery_cache.push(entry);
+    }
 }
 
 /// Cross-platform config directory fallback.
 fn dirs_fallback() -> PathBuf {
-    // Try XDG_CONFIG_HOME, then platform defaults
-    if let Ok(xdg) = std::env::var("XDG_CONFIG_HOME") {
-        return PathBuf::from(xdg);
+    #[cfg(all(unix, not(target_os = "macos")))]
+    {
+        if let Some(xdg) = xdg_config_home(std::env::var_os("XDG_CONFIG_HOME")) {
+            return xdg;
+        }
+        if let Ok(home) = std::env::var("HOME") {
+            return PathBuf::from(home).join(".config");
+        }
     }
 
     #[cfg(target_os = "macos")]
@@ -130,13 +903,433 @@ fn dirs_fallback() -> PathBuf {
         }
     }
 
-    #[cfg(target_os = "linux")]
+    // Fallback to current dir
+    PathBuf::from(".")
+}
+
+/// `$XDG_CONFIG_HOME` if usable. The base directory spec says an empty or
+/// relative value is invalid and must be ignored.
+#[cfg(all(unix, not(target_os = "macos")))]
+fn xdg_config_home(value: Option<std::ffi::OsString>) -> Option<PathBuf> {
+    value.map(PathBuf::from).filter(|path| path.is_absolute())
+}
+
+/// Write `content` to a temporary file next to `path` and rename it into
+/// place, so a crash mid-write leaves the old file intact instead of a
+/// truncated one. The file keeps the old file's permissions, or is private
+/// to the user when new, since it holds connection strings.
+pub fn write_atomic(path: &Path, content: &str) -> std::io::Result<()> {
+    use std::io::Write;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    static TMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    // Unique per write, so concurrent saves never share a temp file
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(format!(
+        ".{}.{}.tmp",
+        std::process::id(),
+        TMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    let tmp = PathBuf::from(tmp);
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
     {
-        if let Ok(home) = std::env::var("HOME") {
-            return PathBuf::from(home).join(".config");
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        let mode = std::fs::metadata(path)
+            .map(|m| m.permissions().mode() & 0o777)
+            .unwrap_or(0o600);
+        options.mode(mode);
+    }
+    let written = options.open(&tmp).and_then(|mut file| {
+        file.write_all(content.as_bytes())?;
+        file.sync_all()
+    });
+    written
+        .and_then(|_| std::fs::rename(&tmp, path))
+        .inspect_err(|_| {
+            let _ = std::fs::remove_file(&tmp);
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn connection_names_come_from_the_namespace() {
+        let mut config = AppConfig::default();
+        let first = config.add_connection(
+            "my-company-orders-staging.servicebus.windows.net",
+            "Endpoint=sb://a/;SharedAccessKeyName=k;SharedAccessKey=1".into(),
+        );
+        assert_eq!(first, "my-company-orders-staging");
+
+        // Different key, same namespace: new entry with a suffix
+        let second = config.add_connection(
+            "my-company-orders-staging.servicebus.windows.net",
+            "Endpoint=sb://a/;SharedAccessKeyName=k;SharedAccessKey=2".into(),
+        );
+        assert_eq!(second, "my-company-orders-staging-2");
+
+        let ad = config
+            .add_azure_ad_connection("my-company-orders-staging.servicebus.windows.net".into());
+        assert_eq!(ad, "my-company-orders-staging-3");
+    }
+
+    #[test]
+    fn atomic_writes_replace_the_file_without_leftovers() {
+        let dir = std::env::temp_dir().join(format!("sb-explorer-test-{}", std::process::id()));
+        let path = dir.join("config.toml");
+        write_atomic(&path, "first").unwrap();
+        write_atomic(&path, "second").unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "second");
+        let leftovers: Vec<_> = std::fs::read_dir(&dir)
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_name() != "config.toml")
+            .collect();
+        assert!(leftovers.is_empty(), "{:?}", leftovers);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = |p: &Path| std::fs::metadata(p).unwrap().permissions().mode() & 0o777;
+            assert_eq!(mode(&path), 0o600);
+            // An existing file's mode is kept
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o640)).unwrap();
+            write_atomic(&path, "third").unwrap();
+            assert_eq!(mode(&path), 0o640);
         }
+        let _ = std::fs::remove_dir_all(&dir);
     }
 
-    // Fallback to current dir
-    PathBuf::from(".")
+    #[test]
+    fn renaming_a_connection_keeps_its_send_defaults() {
+        let mut config = AppConfig::default();
+        config.add_azure_ad_connection("dev.servicebus.windows.net".into());
+        config.add_azure_ad_connection("prod.servicebus.windows.net".into());
+        config.remember_send_defaults(SendDefaults {
+            connection: "dev".to_string(),
+            entity: "orders".to_string(),
+            ..Default::default()
+        });
+
+        assert_eq!(
+            config.rename_connection("dev", " dev-eu "),
+            Ok("dev-eu".to_string())
+        );
+        assert_eq!(config.connections[0].name, "dev-eu");
+        assert!(config.send_defaults_for("dev-eu", "orders").is_some());
+
+        assert!(config.rename_connection("dev-eu", "prod").is_err());
+        assert!(config.rename_connection("dev-eu", "  ").is_err());
+        assert!(config.rename_connection("missing", "x").is_err());
+        assert_eq!(
+            config.rename_connection("prod", "prod"),
+            Ok("prod".to_string())
+        );
+    }
+
+    #[test]
+    fn connections_are_listed_by_group() {
+        let mut config = AppConfig::default();
+        for ns in ["dev-eu", "prod-eu", "scratch", "dev-us"] {
+            config.add_azure_ad_connection(format!("{}.servicebus.windows.net", ns));
+        }
+        config.set_connection_group("prod-eu", Some("prod".into()));
+        config.set_connection_group("dev-us", Some(" dev ".into()));
+        config.set_connection_group("dev-eu", Some("dev".into()));
+        config.set_connection_group("scratch", Some("  ".into()));
+
+        let groups: Vec<_> = config
+            .connection_groups()
+            .into_iter()
+            .map(|(group, indices)| (group.map(str::to_string), indices))
+            .collect();
+        assert_eq!(
+            groups,
+            vec![
+                (Some("dev".to_string()), vec![0, 3]),
+                (Some("prod".to_string()), vec![1]),
+                (None, vec![2]),
+            ]
+        );
+    }
+
+    fn defaults_for(entity: &str) -> SendDefaults {
+        SendDefaults {
+            connection: "prod".into(),
+            entity: entity.into(),
+            label: Some("order.created".into()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn send_defaults_are_capped_least_recently_used_first() {
+        let mut config = AppConfig::default();
+        for i in 0..MAX_SEND_DEFAULTS {
+            config.remember_send_defaults(defaults_for(&format!("q{}", i)));
+        }
+        // Re-using q0 makes it the most recent, so q1 is evicted instead
+        config.remember_send_defaults(defaults_for("q0"));
+        config.remember_send_defaults(defaults_for("extra"));
+
+        assert_eq!(config.send_defaults.len(), MAX_SEND_DEFAULTS);
+        assert!(config.send_defaults_for("prod", "q0").is_some());
+        assert!(config.send_defaults_for("prod", "q1").is_none());
+        assert!(config.send_defaults_for("staging", "q0").is_none());
+
+        assert!(config.forget_send_defaults("prod", "q0"));
+        assert!(!config.forget_send_defaults("prod", "q0"));
+    }
+
+    #[test]
+    fn send_defaults_round_trip_through_toml() {
+        let mut config = AppConfig::default();
+        let mut defaults = defaults_for("orders");
+        defaults.custom_properties = vec![("tenant".into(), String::new())];
+        config.remember_send_defaults(defaults.clone());
+
+        let text = toml::to_string_pretty(&config).unwrap();
+        let loaded: AppConfig = toml::from_str(&text).unwrap();
+        assert_eq!(loaded.send_defaults, vec![defaults]);
+    }
+
+    #[test]
+    fn filter_presets_replace_by_name_and_round_trip_through_toml() {
+        use crate::message_filter::FilterScope;
+        let preset = |name: &str, expression: &str| FilterPreset {
+            name: name.into(),
+            scope: FilterScope::CustomProperties,
+            expression: expression.into(),
+        };
+        let mut config = AppConfig::default();
+        config.save_filter_preset(preset("contoso", "tenantId=contoso"));
+        config.save_filter_preset(preset("fabrikam", "tenantId=fabrikam"));
+        config.save_filter_preset(preset("contoso", "tenantId=Contoso"));
+        assert_eq!(
+            config.filter_presets,
+            vec![
+                preset("contoso", "tenantId=Contoso"),
+                preset("fabrikam", "tenantId=fabrikam")
+            ]
+        );
+
+        let text = toml::to_string_pretty(&config).unwrap();
+        assert!(text.contains("scope = \"custom_properties\""), "{}", text);
+        let loaded: AppConfig = toml::from_str(&text).unwrap();
+        assert_eq!(loaded.filter_presets, config.filter_presets);
+    }
+
+    #[test]
+    fn discovery_cache_round_trips_and_expires() {
+        use crate::client::resource_manager::{DiscoveredNamespace, NamespaceDetail};
+        let ada = ArmIdentity {
+            tenant_id: "t1".into(),
+            account: "ada@contoso.com".into(),
+        };
+        let fetched = DateTime::parse_from_rfc3339("2026-10-16T09:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let namespace = DiscoveredNamespace {
+            fqdn: "dev.servicebus.windows.net".into(),
+            name: "dev".into(),
+            subscription_id: "0000".into(),
+            subscription_name: "Dev".into(),
+            resource_id: "/subscriptions/0000/resourceGroups/rg/providers/x/dev".into(),
+            resource_group: Some("rg".into()),
+            location: "westeurope".into(),
+            status: "Active".into(),
+            detail: Some(NamespaceDetail {
+                sku_name: Some("Standard".into()),
+                ..Default::default()
+            }),
+        };
+        let config = AppConfig {
+            discovery_cache: vec![DiscoveryCacheEntry::new(
+                &ada,
+                DiscoveryResult {
+                    namespaces: vec![namespace],
+                    errors: vec!["Prod: 403 Forbidden".into()],
+                },
+                fetched,
+            )],
+            ..Default::default()
+        };
+
+        let text = toml::to_string_pretty(&config).unwrap();
+        let loaded: AppConfig = toml::from_str(&text).unwrap();
+        let entry = &loaded.discovery_cache[0];
+        assert!(entry.is_for(&ArmIdentity {
+            account: "Ada@Contoso.com".into(),
+            ..ada.clone()
+        }));
+        assert_eq!(
+            entry.result.namespaces[0].fqdn,
+            "dev.servicebus.windows.net"
+        );
+        assert_eq!(entry.result.errors, vec!["Prod: 403 Forbidden".to_string()]);
+
+        let later = |minutes| fetched + chrono::Duration::minutes(minutes);
+        assert_eq!(entry.age_minutes(later(59)), Some(59));
+        assert!(entry.is_fresh(60, later(59)));
+        assert!(!entry.is_fresh(60, later(60)));
+        assert!(!entry.is_fresh(0, later(0)));
+        assert!(!entry.is_fresh(60, later(-5)));
+    }
+
+    #[test]
+    fn discovery_cache_is_kept_per_tenant_and_account() {
+        let identity = |tenant: &str, account: &str| ArmIdentity {
+            tenant_id: tenant.into(),
+            account: account.into(),
+        };
+        let entry = |identity: &ArmIdentity, error: &str| {
+            DiscoveryCacheEntry::new(
+                identity,
+                DiscoveryResult {
+                    namespaces: Vec::new(),
+                    errors: vec![error.into()],
+                },
+                Utc::now(),
+            )
+        };
+        let ada = identity("t1", "ada@contoso.com");
+        let ada_elsewhere = identity("t2", "ada@contoso.com");
+        let bob = identity("t1", "bob@contoso.com");
+
+        let mut config = AppConfig::default();
+        config.cache_discovery(entry(&ada, "first"));
+        config.cache_discovery(entry(&ada_elsewhere, "other tenant"));
+        config.cache_discovery(entry(&ada, "second"));
+
+        assert_eq!(config.discovery_cache.len(), 2);
+        let errors_of = |who: &ArmIdentity| {
+            config
+                .discovery_cache
+                .iter()
+                .find(|e| e.is_for(who))
+                .map(|e| e.result.errors[0].clone())
+        };
+        assert_eq!(errors_of(&ada).as_deref(), Some("second"));
+        assert_eq!(errors_of(&ada_elsewhere).as_deref(), Some("other tenant"));
+        assert_eq!(errors_of(&bob), None);
+    }
+
+    #[test]
+    fn unkeyed_discovery_cache_is_dropped_on_load() {
+        let config = AppConfig::parse(
+            "[discovery_cache]\nfetched_at = \"2026-10-16T09:00:00Z\"\n\n\
+             [discovery_cache.result]\nnamespaces = []\nerrors = []\n",
+        )
+        .unwrap();
+        assert!(config.discovery_cache.is_empty());
+    }
+
+    #[test]
+    fn invalid_toml_is_reported_with_its_line() {
+        let err = AppConfig::parse("[settings]\nbody_wrap = tru\n").unwrap_err();
+        assert!(err.starts_with("line 2: "), "{}", err);
+        // Errors at the start of a line belong to that line, not the one before
+        let err = AppConfig::parse("[settings]\nbody_wrap = true\n!\n").unwrap_err();
+        assert!(err.starts_with("line 3: "), "{}", err);
+        let err = AppConfig::parse("!").unwrap_err();
+        assert!(err.starts_with("line 1: "), "{}", err);
+        assert!(AppConfig::parse("").is_ok());
+    }
+
+    #[test]
+    fn oversized_expiry_warning_is_clamped() {
+        let mut config = AppConfig::default();
+        config.settings.expiry_warning_secs = i64::MAX as u64;
+        let content = toml::to_string_pretty(&config).unwrap();
+        let config = AppConfig::parse(&content).unwrap();
+        assert_eq!(config.settings.expiry_warning_secs, MAX_EXPIRY_WARNING_SECS);
+    }
+
+    #[test]
+    fn invalid_setting_values_are_rejected() {
+        let mut settings = AppSettings::default();
+        assert!(settings
+            .set_value(SettingField::PurgeConcurrency, "0")
+            .is_err());
+        assert!(settings
+            .set_value(SettingField::PurgeConcurrency, "lots")
+            .is_err());
+        assert_eq!(settings.purge_concurrency, 32);
+
+        settings
+            .set_value(SettingField::PurgeConcurrency, " 8 ")
+            .unwrap();
+        assert_eq!(settings.value_of(SettingField::PurgeConcurrency), "8");
+
+        settings.set_value(SettingField::PeekMax, "10").unwrap();
+        assert_eq!(settings.peek_count, 10);
+        assert!(settings.set_value(SettingField::PeekCount, "11").is_err());
+
+        settings
+            .set_value(SettingField::DlqAlertThreshold, "")
+            .unwrap();
+        assert_eq!(settings.dlq_alert_threshold, None);
+        settings.set_value(SettingField::AlertBell, "off").unwrap();
+        assert!(!settings.alert_bell);
+
+        assert!(settings
+            .set_value(SettingField::HealthCheckIntervalSecs, "1")
+            .is_err());
+        settings
+            .set_value(SettingField::HealthCheckIntervalSecs, "30")
+            .unwrap();
+        assert_eq!(settings.health_check_interval_secs, Some(30));
+        settings
+            .set_value(SettingField::HealthCheckIntervalSecs, "")
+            .unwrap();
+        assert_eq!(settings.health_check_interval_secs, None);
+    }
+
+    #[test]
+    fn saving_the_same_connection_again_reuses_its_name() {
+        let mut config = AppConfig::default();
+        let cs = "Endpoint=sb://a/;SharedAccessKeyName=k;SharedAccessKey=1".to_string();
+        config.add_connection("orders.servicebus.windows.net", cs.clone());
+        config.connections[0].name = "Orders (prod)".into();
+        assert_eq!(
+            config.add_connection("orders.servicebus.windows.net", cs),
+            "Orders (prod)"
+        );
+        assert_eq!(config.connections.len(), 1);
+
+        config.add_azure_ad_connection("orders.servicebus.windows.net".into());
+        assert_eq!(
+            config.add_azure_ad_connection("ORDERS.servicebus.windows.net".into()),
+            "orders"
+        );
+        assert_eq!(config.connections.len(), 2);
+    }
+
+    #[test]
+    fn replacing_credentials_needs_an_existing_connection() {
+        let mut config = AppConfig::default();
+        assert!(!config.replace_connection_string("gone", "Endpoint=sb://a/".into()));
+        assert!(!config.replace_with_azure_ad("gone", "a.servicebus.windows.net".into()));
+        assert!(config.connections.is_empty());
+    }
+
+    #[test]
+    #[cfg(all(unix, not(target_os = "macos")))]
+    fn ignores_empty_or_relative_xdg_config_home() {
+        assert_eq!(
+            xdg_config_home(Some("/home/me/.cfg".into())),
+            Some(PathBuf::from("/home/me/.cfg"))
+        );
+        assert_eq!(xdg_config_home(Some("".into())), None);
+        assert_eq!(xdg_config_home(Some("relative/cfg".into())), None);
+        assert_eq!(xdg_config_home(None), None);
+    }
 }
@@ -0,0 +1,157 @@
+use std::time::{Duration, Instant};
+
+use crate::client::{ManagementClient, ServiceBusError};
+
+/// How long one connection may take to answer before it counts as timed out.
+pub const TEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// Connections tested at the same time.
+pub const TEST_CONCURRENCY: usize = 4;
+
+/// Why a connection test failed, coarse enough to act on at a glance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailureClass {
+    AuthFailed,
+    Forbidden,
+    Dns,
+    Unreachable,
+    Timeout,
+    Other,
+}
+
+impl FailureClass {
+    pub fn label(self) -> &'static str {
+        match self {
+            FailureClass::AuthFailed => "auth failed",
+            FailureClass::Forbidden => "forbidden",
+            FailureClass::Dns => "DNS",
+            FailureClass::Unreachable => "unreachable",
+            FailureClass::Timeout => "timeout",
+            FailureClass::Other => "error",
+        }
+    }
+
+    pub fn of(err: &ServiceBusError) -> Self {
+        match err {
+            ServiceBusError::Auth(_) | ServiceBusError::InvalidConnectionString(_) => {
+                FailureClass::AuthFailed
+            }
+            ServiceBusError::EmulatorNotRunning(_) => FailureClass::Unreachable,
+            ServiceBusError::Http(e) if e.is_timeout() => FailureClass::Timeout,
+            ServiceBusError::Http(e) if e.is_connect() => {
+                if is_dns_failure(e) {
+                    FailureClass::Dns
+                } else {
+                    FailureClass::Unreachable
+                }
+            }
+            _ => match err.status() {
+                Some(401) => FailureClass::AuthFailed,
+                Some(403) => FailureClass::Forbidden,
+                _ => FailureClass::Other,
+            },
+        }
+    }
+}
+
+/// reqwest reports failed lookups as connect errors; only the source chain
+/// tells them apart from refused connections.
+fn is_dns_failure(err: &(dyn std::error::Error + 'static)) -> bool {
+    let mut source = Some(err);
+    while let Some(e) = source {
+        let text = e.to_string().to_ascii_lowercase();
+        if text.contains("dns error") || text.contains("failed to lookup") {
+            return true;
+        }
+        source = e.source();
+    }
+    false
+}
+
+/// Result of testing one saved connection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TestOutcome {
+    Ok { latency: Duration },
+    Failed { class: FailureClass, detail: String },
+}
+
+/// The last test of a saved connection, kept for the session.
+#[derive(Debug, Clone)]
+pub struct ConnectionTest {
+    pub outcome: TestOutcome,
+    pub at: Instant,
+}
+
+/// One management GET against the namespace (or, for an entity-scoped key,
+/// its entity), bounded by `TEST_TIMEOUT`.
+pub async fn test_connection(mgmt: ManagementClient) -> TestOutcome {
+    let started = Instant::now();
+    let probe = async {
+        match mgmt.entity_path() {
+            Some(_) => mgmt.ping().await.map(|_| ()),
+            None => mgmt.get_namespace_info().await.map(|_| ()),
+        }
+    };
+    match tokio::time::timeout(TEST_TIMEOUT, probe).await {
+        Ok(Ok(())) => TestOutcome::Ok {
+            latency: started.elapsed(),
+        },
+        Ok(Err(e)) => TestOutcome::Failed {
+            class: FailureClass::of(&e),
+            detail: e.to_string(),
+        },
+        Err(_) => TestOutcome::Failed {
+            class: FailureClass::Timeout,
+            detail: format!("no answer within {}s", TEST_TIMEOUT.as_secs()),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn failures_are_classified_by_status_and_kind() {
+        let api = |status| ServiceBusError::Api {
+            status,
+            body: String::new(),
+        };
+        assert_eq!(FailureClass::of(&api(401)), FailureClass::AuthFailed);
+        assert_eq!(FailureClass::of(&api(403)), FailureClass::Forbidden);
+        assert_eq!(FailureClass::of(&api(500)), FailureClass::Other);
+        assert_eq!(
+            FailureClass::of(&ServiceBusError::Auth("expired".into())),
+            FailureClass::AuthFailed
+        );
+        assert_eq!(
+            FailureClass::of(&ServiceBusError::EmulatorNotRunning("localhost".into())),
+            FailureClass::Unreachable
+        );
+    }
+
+    #[tokio::test]
+    async fn entity_scoped_keys_are_tested_against_their_entity() {
+        use std::sync::Arc;
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        // Namespace-wide requests fall through to wiremock's 404
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/orders"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let conn_str = format!(
+            "Endpoint=sb://{};SharedAccessKeyName=send;SharedAccessKey=a2V5;UseDevelopmentEmulator=true;EntityPath=orders",
+            server.address()
+        );
+        let mgmt = ManagementClient::new(
+            crate::client::ConnectionConfig::from_connection_string(&conn_str).unwrap(),
+            Arc::new(reqwest::Client::new()),
+        );
+        let outcome = test_connection(mgmt).await;
+        assert!(matches!(outcome, TestOutcome::Ok { .. }), "{:?}", outcome);
+    }
+}
@@ -0,0 +1,219 @@
+use chrono::{DateTime, Utc};
+use std::time::Duration;
+
+/// Longest delay (and spread) accepted: a year.
+pub const MAX_DELAY: Duration = Duration::from_secs(365 * 86_400);
+
+/// A delay before resent messages become visible, optionally spread out so
+/// that `n` messages arrive over `spread` instead of all at once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DelaySpec {
+    pub delay: Duration,
+    pub spread: Option<Duration>,
+}
+
+impl DelaySpec {
+    /// Enqueue time of message `index` out of `total`, spaced evenly from
+    /// `now + delay` up to (but not including) `now + delay + spread`. Fails
+    /// when that is past the dates chrono can represent.
+    pub fn enqueue_time(
+        &self,
+        now: DateTime<Utc>,
+        index: usize,
+        total: usize,
+    ) -> Result<DateTime<Utc>, String> {
+        let offset = match self.spread {
+            Some(spread) if total > 1 => spread.mul_f64(index as f64 / total as f64),
+            _ => Duration::ZERO,
+        };
+        self.delay
+            .checked_add(offset)
+            .and_then(|d| chrono::Duration::from_std(d).ok())
+            .and_then(|d| now.checked_add_signed(d))
+            .ok_or_else(|| format!("Delay {} is too long", self))
+    }
+
+    /// "14:05:00 UTC" or "14:05:00–14:35:00 UTC" for `total` messages sent `now`.
+    pub fn window(&self, now: DateTime<Utc>, total: usize) -> Result<String, String> {
+        let first = self.enqueue_time(now, 0, total)?;
+        let last = self.enqueue_time(now, total.saturating_sub(1), total)?;
+        Ok(if first == last {
+            format!("{} UTC", first.format("%H:%M:%S"))
+        } else {
+            format!(
+                "{}–{} UTC",
+                first.format("%H:%M:%S"),
+                last.format("%H:%M:%S")
+            )
+        })
+    }
+}
+
+impl std::fmt::Display for DelaySpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", format_delay(self.delay))?;
+        if let Some(spread) = self.spread {
+            write!(f, " over {}", format_delay(spread))?;
+        }
+        Ok(())
+    }
+}
+
+/// Parse a relative delay: `90s`, `10m`, `1h30m`, `2d`, optionally written
+/// `+10m` or `in 10m`. A bare number is taken as minutes. Delays over
+/// [`MAX_DELAY`] are refused.
+pub fn parse_delay(input: &str) -> Result<Duration, String> {
+    let too_long = || format!("Delay is too long (at most {})", format_delay(MAX_DELAY));
+    let text = input.trim();
+    let text = text.strip_prefix("in ").unwrap_or(text);
+    let text = text.trim_start_matches('+').trim();
+    if text.is_empty() {
+        return Err("Delay is empty".to_string());
+    }
+    if let Ok(minutes) = text.parse::<u64>() {
+        return minutes
+            .checked_mul(60)
+            .map(Duration::from_secs)
+            .filter(|d| *d <= MAX_DELAY)
+            .ok_or_else(too_long);
+    }
+
+    let mut total = 0u64;
+    let mut digits = String::new();
+    for c in text.chars() {
+        if c.is_ascii_digit() {
+            digits.push(c);
+            continue;
+        }
+        if c.is_whitespace() {
+            continue;
+        }
+        let unit = match c.to_ascii_lowercase() {
+            's' => 1,
+            'm' => 60,
+            'h' => 3600,
+            'd' => 86_400,
+            _ => return Err(format!("Unknown delay unit '{}' (use s, m, h or d)", c)),
+        };
+        let value: u64 = digits
+            .parse()
+            .map_err(|_| format!("Missing number before '{}'", c))?;
+        total = value
+            .checked_mul(unit)
+            .and_then(|secs| total.checked_add(secs))
+            .filter(|secs| *secs <= MAX_DELAY.as_secs())
+            .ok_or_else(too_long)?;
+        digits.clear();
+    }
+    if !digits.is_empty() {
+        return Err(format!(
+            "Missing unit after '{}' (use s, m, h or d)",
+            digits
+        ));
+    }
+    Ok(Duration::from_secs(total))
+}
+
+/// Parse `DELAY` or `DELAY over SPREAD`, e.g. `1h over 30m`.
+pub fn parse_delay_spec(input: &str) -> Result<DelaySpec, String> {
+    match input.split_once(" over ") {
+        Some((delay, spread)) => Ok(DelaySpec {
+            delay: parse_delay(delay)?,
+            spread: Some(parse_delay(spread)?),
+        }),
+        None => Ok(DelaySpec {
+            delay: parse_delay(input)?,
+            spread: None,
+        }),
+    }
+}
+
+/// `5400s` → `1h30m`.
+pub fn format_delay(delay: Duration) -> String {
+    let secs = delay.as_secs();
+    if secs == 0 {
+        return "0s".to_string();
+    }
+    [
+        (secs / 86_400, "d"),
+        (secs / 3600 % 24, "h"),
+        (secs / 60 % 60, "m"),
+        (secs % 60, "s"),
+    ]
+    .iter()
+    .filter(|(n, _)| *n > 0)
+    .map(|(n, unit)| format!("{}{}", n, unit))
+    .collect()
+}
+
+/// `ScheduledEnqueueTimeUtc` broker property value (RFC 1123).
+pub fn enqueue_time_header(at: DateTime<Utc>) -> String {
+    at.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn parses_relative_delays() {
+        assert_eq!(parse_delay("10m"), Ok(Duration::from_secs(600)));
+        assert_eq!(parse_delay("+1h30m"), Ok(Duration::from_secs(5400)));
+        assert_eq!(parse_delay("in 2d"), Ok(Duration::from_secs(172_800)));
+        assert_eq!(parse_delay(" 90s "), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_delay("15"), Ok(Duration::from_secs(900)));
+        assert!(parse_delay("").is_err());
+        assert!(parse_delay("1x").is_err());
+        assert!(parse_delay("1h30").is_err());
+        assert!(parse_delay("h").is_err());
+        assert_eq!(format_delay(Duration::from_secs(5400)), "1h30m");
+        assert_eq!(format_delay(Duration::from_secs(90_061)), "1d1h1m1s");
+    }
+
+    #[test]
+    fn spread_staggers_enqueue_times() {
+        let spec = parse_delay_spec("1h over 30m").unwrap();
+        assert_eq!(spec.to_string(), "1h over 30m");
+        let now = Utc.with_ymd_and_hms(2024, 5, 6, 12, 0, 0).unwrap();
+        let times: Vec<_> = (0..3)
+            .map(|i| spec.enqueue_time(now, i, 3).unwrap())
+            .collect();
+        assert_eq!(
+            times[0],
+            Utc.with_ymd_and_hms(2024, 5, 6, 13, 0, 0).unwrap()
+        );
+        assert_eq!(
+            times[1],
+            Utc.with_ymd_and_hms(2024, 5, 6, 13, 10, 0).unwrap()
+        );
+        assert_eq!(
+            times[2],
+            Utc.with_ymd_and_hms(2024, 5, 6, 13, 20, 0).unwrap()
+        );
+        assert_eq!(spec.window(now, 3).unwrap(), "13:00:00–13:20:00 UTC");
+        assert_eq!(spec.window(now, 1).unwrap(), "13:00:00 UTC");
+        assert_eq!(
+            enqueue_time_header(times[0]),
+            "Mon, 06 May 2024 13:00:00 GMT"
+        );
+    }
+
+    #[test]
+    fn huge_delays_are_refused_instead_of_overflowing() {
+        assert_eq!(parse_delay("365d"), Ok(MAX_DELAY));
+        assert!(parse_delay("366d").is_err());
+        assert!(parse_delay("100000000d").is_err());
+        assert!(parse_delay("99999999999999999").is_err());
+        assert!(parse_delay_spec("1h over 400d").is_err());
+
+        // Specs built by hand still fail softly
+        let spec = DelaySpec {
+            delay: Duration::from_secs(u64::MAX / 2),
+            spread: None,
+        };
+        let now = Utc.with_ymd_and_hms(2024, 5, 6, 12, 0, 0).unwrap();
+        assert!(spec.enqueue_time(now, 0, 1).is_err());
+        assert!(spec.window(now, 1).is_err());
+    }
+}
@@ -0,0 +1,189 @@
+use crate::body_format::indent_xml;
+use crate::client::error::summarize_body;
+use crate::client::{ErrorKind, ServiceBusError};
+
+/// Failures whose text is longer than this (or spans lines) open the error
+/// modal instead of being squeezed into the status bar.
+const STATUS_BAR_LIMIT: usize = 120;
+
+/// A failed background operation, kept structured so the error modal can show
+/// the HTTP status and full response body separately from the summary.
+#[derive(Debug, Clone)]
+pub struct ErrorReport {
+    pub operation: String,
+    pub status: Option<u16>,
+    pub summary: String,
+    pub body: Option<String>,
+    /// Service Bus error code and tracking id, for support requests.
+    pub code: Option<String>,
+    pub tracking_id: Option<String>,
+    /// The namespace answered 401; credentials need to be entered again.
+    pub unauthorized: bool,
+}
+
+impl ErrorReport {
+    pub fn from_service_error(operation: &str, err: &ServiceBusError) -> Self {
+        let detail = err.api_detail().unwrap_or_default();
+        let summary = match err {
+            ServiceBusError::Api { body, .. } => summarize_body(body),
+            ServiceBusError::Unauthorized { body } => {
+                format!(
+                    "Unauthorized — the key may have been rotated ({})",
+                    summarize_body(body)
+                )
+            }
+            other => other.to_string(),
+        };
+        let summary = match err.kind() {
+            ErrorKind::MessageLockLost => {
+                "Lock lost — message was processed by another client".to_string()
+            }
+            ErrorKind::Throttled => {
+                format!("Throttled — namespace is busy, retry shortly ({})", summary)
+            }
+            ErrorKind::EntityDisabled => {
+                format!("Entity is disabled — enable it first ({})", summary)
+            }
+            ErrorKind::QuotaExceeded => format!(
+                "Quota exceeded — purge with 'P' or raise the max size ({})",
+                summary
+            ),
+            _ => summary,
+        };
+        Self {
+            operation: operation.to_string(),
+            status: err.status(),
+            summary,
+            body: err.body().map(str::to_string),
+            code: detail.code,
+            tracking_id: detail.tracking_id,
+            unauthorized: matches!(err, ServiceBusError::Unauthorized { .. }),
+        }
+    }
+
+    /// Wrap a plain failure message (no structured status/body).
+    pub fn from_message(message: &str) -> Self {
+        let is_long = message.len() > STATUS_BAR_LIMIT || message.contains('\n');
+        Self {
+            operation: String::new(),
+            status: None,
+            summary: summarize_body(message),
+            body: is_long.then(|| message.to_string()),
+            code: None,
+            tracking_id: None,
+            unauthorized: false,
+        }
+    }
+
+    /// Single line for the status bar.
+    pub fn status_line(&self) -> String {
+        let mut line = match (self.operation.is_empty(), self.status) {
+            (true, None) => self.summary.clone(),
+            (true, Some(status)) => format!("HTTP {}: {}", status, self.summary),
+            (false, None) => format!("{}: {}", self.operation, self.summary),
+            (false, Some(status)) => {
+                format!("{}: HTTP {}: {}", self.operation, status, self.summary)
+            }
+        };
+        if line.chars().count() > STATUS_BAR_LIMIT {
+            line = line.chars().take(STATUS_BAR_LIMIT - 1).collect();
+            line.push('…');
+        }
+        line
+    }
+
+    /// Whether the full report is worth opening the error modal for.
+    pub fn needs_modal(&self) -> bool {
+        let summary_long = self.summary.len() > STATUS_BAR_LIMIT || self.summary.contains('\n');
+        let body_long = self
+            .body
+            .as_deref()
+            .is_some_and(|b| b.len() > STATUS_BAR_LIMIT || b.contains('\n'));
+        summary_long || body_long
+    }
+
+    /// Full plain-text rendering, used both for the modal and the clipboard.
+    pub fn full_text(&self) -> String {
+        let mut out = String::new();
+        if !self.operation.is_empty() {
+            out.push_str(&format!("Operation: {}\n", self.operation));
+        }
+        if let Some(status) = self.status {
+            out.push_str(&format!("HTTP status: {}\n", status));
+        }
+        if let Some(ref code) = self.code {
+            out.push_str(&format!("Error code: {}\n", code));
+        }
+        if let Some(ref id) = self.tracking_id {
+            out.push_str(&format!("Tracking id: {}\n", id));
+        }
+        out.push_str(&format!("Error: {}\n", self.summary));
+        if let Some(ref body) = self.body {
+            out.push('\n');
+            out.push_str(&pretty_body(body));
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// Pretty-print an error body if it is JSON or XML; otherwise return it trimmed.
+pub fn pretty_body(body: &str) -> String {
+    let trimmed = body.trim();
+    if let Ok(json) = serde_json::from_str::<serde_json::Value>(trimmed) {
+        if let Ok(pretty) = serde_json::to_string_pretty(&json) {
+            return pretty;
+        }
+    }
+    if trimmed.starts_with('<') {
+        return indent_xml(trimmed);
+    }
+    trimmed.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn indents_service_bus_xml_error() {
+        let body = "<Error><Code>409</Code><Detail>Conflict</Detail></Error>";
+        assert_eq!(
+            pretty_body(body),
+            "<Error>\n  <Code>409</Code>\n  <Detail>Conflict</Detail>\n</Error>"
+        );
+    }
+
+    #[test]
+    fn api_error_with_xml_body_opens_modal() {
+        let err = ServiceBusError::Api {
+            status: 409,
+            body: "<Error>\n<Code>409</Code>\n<Detail>Conflict</Detail>\n</Error>".into(),
+        };
+        let report = ErrorReport::from_service_error("Create failed", &err);
+        assert!(report.needs_modal());
+        assert_eq!(report.status_line(), "Create failed: HTTP 409: Conflict");
+        assert!(report.full_text().contains("HTTP status: 409"));
+    }
+
+    #[test]
+    fn lock_lost_gets_a_friendly_summary() {
+        let err = ServiceBusError::Api {
+            status: 410,
+            body: "<Error><Code>410</Code><Detail>The lock supplied is invalid. TrackingId:t-1, SystemTracker:x</Detail></Error>".into(),
+        };
+        let report = ErrorReport::from_service_error("Complete failed", &err);
+        assert_eq!(
+            report.status_line(),
+            "Complete failed: HTTP 410: Lock lost — message was processed by another client"
+        );
+        assert!(report.full_text().contains("Tracking id: t-1"));
+    }
+
+    #[test]
+    fn short_message_stays_in_status_bar() {
+        let report = ErrorReport::from_message("Invalid number");
+        assert!(!report.needs_modal());
+        assert_eq!(report.status_line(), "Invalid number");
+    }
+}
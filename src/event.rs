@@ -1,12 +1,15 @@
 use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 use std::time::Duration;
 
-use crate::app::{ActiveModal, App, FocusPanel, MessageTab};
+use crate::app::{ActiveModal, App, DetailView, FocusPanel, MessageTab, PROPERTY_VALUE_LINES};
 use crate::client::models::EntityType;
 use crate::event_modal;
 
 const BG_BUSY_MSG: &str = "A background operation is in progress...";
 
+/// Largest count prefix accepted; further digits are ignored.
+const MAX_COUNT: usize = 9999;
+
 /// Poll for input events and process them against app state.
 /// Returns true if the app should continue running.
 pub fn handle_events(app: &mut App) -> anyhow::Result<bool> {
@@ -19,12 +22,22 @@ pub fn handle_events(app: &mut App) -> anyhow::Result<bool> {
             }
 
             // If a background operation is running, Esc cancels it
-            if app.bg_running && key.code == KeyCode::Esc {
+            if app.bg_running && key.code == KeyCode::Esc && app.modal != ActiveModal::ConfirmQuit {
+                app.pending_count = None;
                 app.cancel_bg();
                 app.set_status("Cancelling...");
                 return Ok(app.running);
             }
 
+            // Esc while the last session is being restored starts fresh instead
+            if app.session_restore.is_some()
+                && key.code == KeyCode::Esc
+                && app.modal == ActiveModal::None
+            {
+                app.abandon_session_restore();
+                return Ok(app.running);
+            }
+
             // If a modal is open, route to modal handler
             if app.modal != ActiveModal::None {
                 event_modal::handle_modal_input(app, key);
@@ -33,24 +46,92 @@ pub fn handle_events(app: &mut App) -> anyhow::Result<bool> {
 
             // If inline editing is active, skip global keys — route directly to panel handler
             if app.detail_editing {
-                handle_message_input(app, key);
+                handle_message_input(app, key, None);
                 return Ok(app.running);
             }
 
+            // Digits build a count for the next tree or message key; Esc drops it
+            if push_count_digit(app, key) {
+                return Ok(true);
+            }
+            let count = app.pending_count.take();
+            if count.is_some() && key.code == KeyCode::Esc {
+                return Ok(true);
+            }
+
             // Global keys
             match key.code {
                 KeyCode::Char('q') if key.modifiers.is_empty() => {
-                    app.running = false;
-                    return Ok(false);
+                    app.request_quit();
+                    return Ok(app.running);
                 }
                 KeyCode::Char('c') if key.modifiers == KeyModifiers::CONTROL => {
-                    app.running = false;
-                    return Ok(false);
+                    app.request_quit();
+                    return Ok(app.running);
                 }
                 KeyCode::Char('?') => {
                     app.modal = ActiveModal::Help;
                     return Ok(true);
                 }
+                KeyCode::Char(',') => {
+                    app.open_settings();
+                    return Ok(true);
+                }
+                KeyCode::Char('!') if !app.dlq_alerts.is_empty() => {
+                    let worst = app.dlq_alerts[0].clone();
+                    if app.reveal_entity(&worst.path) {
+                        app.set_status(format!(
+                            "'{}' has {} dead-lettered messages (threshold {})",
+                            worst.path, worst.count, worst.threshold
+                        ));
+                    }
+                    return Ok(true);
+                }
+                KeyCode::Char('/') => {
+                    if app.data_plane.is_none() || app.tree.is_none() {
+                        app.set_error("Connect to a namespace first");
+                    } else if app.bg_running {
+                        app.set_status(BG_BUSY_MSG);
+                    } else {
+                        app.input_buffer.clear();
+                        app.input_cursor = 0;
+                        app.modal = ActiveModal::MessageSearchInput;
+                    }
+                    return Ok(true);
+                }
+                KeyCode::Char('H') => {
+                    if app.management.is_none() {
+                        app.set_error("Connect to a namespace first");
+                    } else if !app.health_check_in_flight {
+                        app.health_check_manual = true;
+                        app.set_status("Checking namespace health...");
+                    }
+                    return Ok(true);
+                }
+                KeyCode::Char('L') => {
+                    if app.connection_config.is_none() {
+                        app.set_error("Connect to a namespace first");
+                    } else {
+                        app.modal = ActiveModal::ApiStats;
+                    }
+                    return Ok(true);
+                }
+                KeyCode::Char('o') if key.modifiers == KeyModifiers::CONTROL => {
+                    app.open_column_selector();
+                    return Ok(true);
+                }
+                KeyCode::Char('e') if key.modifiers == KeyModifiers::CONTROL => {
+                    app.reopen_resend_report();
+                    return Ok(true);
+                }
+                KeyCode::Char('g') if key.modifiers == KeyModifiers::CONTROL => {
+                    if app.tree.is_none() {
+                        app.set_error("Connect to a namespace first");
+                    } else {
+                        app.open_forward_graph();
+                    }
+                    return Ok(true);
+                }
                 KeyCode::Char('c') if key.modifiers.is_empty() => {
                     if app.bg_running {
                         app.set_status(
@@ -58,6 +139,7 @@ pub fn handle_events(app: &mut App) -> anyhow::Result<bool> {
                         );
                     } else if app.management.is_none() {
                         // Open connection flow
+                        app.end_connect_flow();
                         app.input_buffer.clear();
                         app.input_cursor = 0;
                         if app.config.connections.is_empty() {
@@ -92,22 +174,57 @@ pub fn handle_events(app: &mut App) -> anyhow::Result<bool> {
 
             // Panel-specific keys
             match app.focus {
-                FocusPanel::Tree => handle_tree_input(app, key),
+                FocusPanel::Tree => handle_tree_input(app, key, count),
                 FocusPanel::Detail => handle_detail_input(app, key),
-                FocusPanel::Messages => handle_message_input(app, key),
+                FocusPanel::Messages => handle_message_input(app, key, count),
             }
         }
     }
     Ok(app.running)
 }
 
-fn handle_tree_input(app: &mut App, key: KeyEvent) {
+/// Add a typed digit to the pending count. A count can't start with `0`,
+/// nor in the messages panel with `1`, `2` or `4`, which switch tabs there
+/// unless a count is already pending.
+fn push_count_digit(app: &mut App, key: KeyEvent) -> bool {
+    let KeyCode::Char(c @ '0'..='9') = key.code else {
+        return false;
+    };
+    if !key.modifiers.is_empty() || app.focus == FocusPanel::Detail {
+        return false;
+    }
+    let tab_key = app.focus == FocusPanel::Messages && matches!(c, '1' | '2' | '4');
+    if app.pending_count.is_none() && (c == '0' || tab_key) {
+        return false;
+    }
+    let digit = c as usize - '0' as usize;
+    let count = app.pending_count.unwrap_or(0);
+    app.pending_count = Some(
+        count
+            .saturating_mul(10)
+            .saturating_add(digit)
+            .min(MAX_COUNT),
+    );
+    true
+}
+
+/// `count` is the count typed before the key: it repeats moves, picks the
+/// row for `g`/`G` and sets the number of messages `p` peeks.
+fn handle_tree_input(app: &mut App, key: KeyEvent, count: Option<usize>) {
+    let times = count.unwrap_or(1);
     match key.code {
         KeyCode::Up | KeyCode::Char('k') => {
-            move_selection_up(&mut app.tree_selected);
+            move_selection_up(&mut app.tree_selected, times);
         }
         KeyCode::Down | KeyCode::Char('j') => {
-            move_selection_down(&mut app.tree_selected, app.flat_nodes.len());
+            move_selection_down(&mut app.tree_selected, app.flat_nodes.len(), times);
+        }
+        // With a count, `g` and `G` go to that row (1-based), as in vim
+        KeyCode::Home | KeyCode::Char('g') | KeyCode::End | KeyCode::Char('G')
+            if count.is_some() =>
+        {
+            app.tree_selected = 0;
+            move_selection_down(&mut app.tree_selected, app.flat_nodes.len(), times - 1);
         }
         KeyCode::Home | KeyCode::Char('g') => {
             app.tree_selected = 0;
@@ -157,16 +274,35 @@ fn handle_tree_input(app: &mut App, key: KeyEvent) {
                 }
             }
         }
-        // 'p' = peek messages — prompt for count
+        // 'p' = peek messages — prompt for count; a dead-letter node peeks its DLQ
+        KeyCode::Char('p') if app.selected_is_dead_letter_node() => {
+            if !block_if_bg_running(app, BG_BUSY_MSG) {
+                app.open_peek_count_input(true);
+                prefill_peek_count(app, count);
+            }
+        }
         KeyCode::Char('p') => {
             if !block_if_bg_running(app, BG_BUSY_MSG) {
                 if let Some((_, entity_type)) = app.selected_entity() {
                     match entity_type {
                         EntityType::Queue | EntityType::Subscription => {
-                            app.input_buffer = app.config.settings.peek_count.to_string();
-                            app.input_cursor = app.input_buffer.len();
-                            app.modal = ActiveModal::PeekCountInput;
-                            app.peek_dlq = false;
+                            app.open_peek_count_input(false);
+                            prefill_peek_count(app, count);
+                        }
+                        _ => {
+                            app.set_status("Select a queue or subscription to peek messages");
+                        }
+                    }
+                }
+            }
+        }
+        // 'b' = peek active and dead-lettered messages together
+        KeyCode::Char('b') => {
+            if !block_if_bg_running(app, BG_BUSY_MSG) {
+                if let Some((_, entity_type)) = app.selected_entity() {
+                    match entity_type {
+                        EntityType::Queue | EntityType::Subscription => {
+                            app.open_peek_both_input();
                         }
                         _ => {
                             app.set_status("Select a queue or subscription to peek messages");
@@ -183,9 +319,13 @@ fn handle_tree_input(app: &mut App, key: KeyEvent) {
                     EntityType::QueueFolder | EntityType::Queue => {
                         app.init_create_queue_form();
                     }
-                    EntityType::TopicFolder | EntityType::Topic => {
+                    EntityType::TopicFolder => {
                         app.init_create_topic_form();
                     }
+                    EntityType::Topic => {
+                        let topic = node.path.clone();
+                        app.modal = ActiveModal::CreateOnTopic { topic };
+                    }
                     EntityType::SubscriptionFolder | EntityType::Subscription => {
                         // Find the parent topic name
                         let topic = event_modal::find_parent_topic(app);
@@ -200,6 +340,9 @@ fn handle_tree_input(app: &mut App, key: KeyEvent) {
             }
         }
         // 'x' = delete selected entity
+        KeyCode::Char('x') if app.selected_is_dead_letter_node() => {
+            app.set_status("Select the queue or subscription itself to delete it");
+        }
         KeyCode::Char('x') => {
             if !block_if_bg_running(app, BG_BUSY_MSG) {
                 if let Some((
@@ -221,10 +364,7 @@ fn handle_tree_input(app: &mut App, key: KeyEvent) {
                 if let Some((_, entity_type)) = app.selected_entity() {
                     match entity_type {
                         EntityType::Queue | EntityType::Subscription | EntityType::Topic => {
-                            app.input_buffer = app.config.settings.peek_count.to_string();
-                            app.input_cursor = app.input_buffer.len();
-                            app.modal = ActiveModal::PeekCountInput;
-                            app.peek_dlq = true;
+                            app.open_peek_count_input(true);
                         }
                         _ => {
                             app.set_status(
@@ -236,26 +376,9 @@ fn handle_tree_input(app: &mut App, key: KeyEvent) {
             }
         }
         // 'P' (shift+p) = clear entity (choose delete or resend)
-        KeyCode::Char('P') => {
-            if !block_if_bg_running(app, BG_BUSY_MSG) {
-                if let Some((path, entity_type)) = app.selected_entity() {
-                    match entity_type {
-                        EntityType::Queue | EntityType::Subscription | EntityType::Topic => {
-                            let entity_path = path.to_string();
-                            let is_topic = *entity_type == EntityType::Topic;
-                            app.modal = ActiveModal::ClearOptions {
-                                entity_path: entity_path.clone(),
-                                base_entity_path: entity_path,
-                                is_topic,
-                            };
-                        }
-                        _ => {
-                            app.set_status("Select a queue, topic, or subscription to clear");
-                        }
-                    }
-                }
-            }
-        }
+        KeyCode::Char('P') => open_clear_options(app),
+        // 'D' on a dead-letter node = delete or resend its messages
+        KeyCode::Char('D') if app.selected_is_dead_letter_node() => open_clear_options(app),
         // 'f' = edit subscription SQL filter rule
         KeyCode::Char('f') => {
             if !block_if_bg_running(app, BG_BUSY_MSG) {
@@ -268,10 +391,54 @@ fn handle_tree_input(app: &mut App, key: KeyEvent) {
                 }
             }
         }
+        // 'Y' = copy entity path (or Azure Portal URL)
+        KeyCode::Char('Y') => app.copy_selected_entity_path(),
+        // 'a' = view SAS authorization rules (read-only)
+        KeyCode::Char('a') => {
+            if !block_if_bg_running(app, BG_BUSY_MSG) {
+                if let Some((_, entity_type)) = app.selected_entity() {
+                    match entity_type {
+                        EntityType::Queue | EntityType::Topic => {
+                            app.set_status("Loading authorization rules...");
+                        }
+                        _ => {
+                            app.set_status("Select a queue or topic to view its SAS policies");
+                        }
+                    }
+                }
+            }
+        }
         _ => {}
     }
 }
 
+/// Ask how to clear the selected entity. A dead-letter node only offers the
+/// DLQ options (delete, or resend to its queue or subscription).
+fn open_clear_options(app: &mut App) {
+    if block_if_bg_running(app, BG_BUSY_MSG) {
+        return;
+    }
+    let dlq_only = app.selected_is_dead_letter_node();
+    if let Some((path, entity_type)) = app.selected_entity() {
+        match entity_type {
+            EntityType::Queue | EntityType::Subscription | EntityType::Topic => {
+                let entity_path = path.to_string();
+                let is_topic = *entity_type == EntityType::Topic;
+                app.modal = ActiveModal::ClearOptions {
+                    entity_path: entity_path.clone(),
+                    base_entity_path: entity_path,
+                    is_topic,
+                    dry_run: false,
+                    dlq_only,
+                };
+            }
+            _ => {
+                app.set_status("Select a queue, topic, or subscription to clear");
+            }
+        }
+    }
+}
+
 fn handle_detail_input(app: &mut App, key: KeyEvent) {
     match key.code {
         KeyCode::Char('1') => {
@@ -282,50 +449,166 @@ fn handle_detail_input(app: &mut App, key: KeyEvent) {
             app.message_tab = MessageTab::DeadLetter;
             app.focus = FocusPanel::Messages;
         }
+        KeyCode::Char('4') => open_transfer_dlq_tab(app),
+        // Topic routing diagram: pick a subscription and jump to it in the tree
+        KeyCode::Up | KeyCode::Char('k') => move_selection_up(&mut app.route_selected, 1),
+        KeyCode::Down | KeyCode::Char('j') => {
+            let len = app.topic_routes().len();
+            move_selection_down(&mut app.route_selected, len, 1);
+        }
+        KeyCode::Char('R') => {
+            let target = match &app.detail_view {
+                DetailView::Topic(desc, _, Some(routes)) => routes
+                    .get(app.route_selected)
+                    .map(|r| format!("{}/Subscriptions/{}", desc.name, r.name)),
+                _ => None,
+            };
+            match target {
+                Some(path) => {
+                    if !app.reveal_entity(&path) {
+                        app.set_error(format!("'{}' is not in the tree — refresh first", path));
+                    }
+                }
+                None => app.set_status("Select a topic with subscriptions to jump to one"),
+            }
+        }
+        KeyCode::Char('E') => {
+            if app.management.is_none() {
+                app.set_error("Not connected");
+            } else if !app.init_edit_entity_form() {
+                app.set_error("Select a queue, topic or subscription to edit");
+            }
+        }
+        KeyCode::Char('F') => {
+            if app.management.is_none() {
+                app.set_error("Not connected");
+            } else if !app.open_forward_to_input() {
+                app.set_error("Select a queue or subscription to configure forwarding");
+            }
+        }
+        KeyCode::Char('S') => {
+            if app.data_plane.is_none() {
+                app.set_error("Not connected");
+            } else if app.bg_running {
+                app.set_status(BG_BUSY_MSG);
+            } else if app.session_entity_path().is_none() {
+                app.set_error("Select a session-enabled queue or subscription");
+            } else {
+                app.set_status("Browsing sessions...");
+            }
+        }
+        KeyCode::Char('T') => {
+            if app.management.is_none() {
+                app.set_error("Not connected");
+            } else if !app.open_status_select() {
+                app.set_error("Select a queue, topic or subscription to change its status");
+            }
+        }
+        KeyCode::Char('U') => {
+            app.user_metadata_expanded = !app.user_metadata_expanded;
+        }
+        KeyCode::Esc => {
+            app.dismiss_detail_changes();
+        }
         _ => {}
     }
 }
 
-fn handle_message_input(app: &mut App, key: KeyEvent) {
+/// `count` repeats moves and scrolls (`10j`).
+fn handle_message_input(app: &mut App, key: KeyEvent, count: Option<usize>) {
     // If inline editing is active, route to the field editor
     if app.detail_editing {
         event_modal::handle_detail_edit_input(app, key);
         return;
     }
 
-    let messages = match app.message_tab {
-        MessageTab::Messages => &app.messages,
-        MessageTab::DeadLetter => &app.dlq_messages,
-    };
-    let len = messages.len();
+    let times = count.unwrap_or(1);
+    if app.in_dlq_group_summary() && handle_dlq_group_input(app, key, times) {
+        return;
+    }
+
+    let len = app.visible_messages().len();
+
+    // Properties focus (`v`): j/k walk the custom properties of the open message
+    let properties_focus = app.properties_focus && app.selected_message_detail.is_some();
 
     match key.code {
+        KeyCode::Up | KeyCode::Char('k') if properties_focus => {
+            move_selection_up(&mut app.property_selected, times);
+            app.property_value_scroll = 0;
+        }
+        KeyCode::Down | KeyCode::Char('j') if properties_focus => {
+            let count = app.detail_properties().len();
+            move_selection_down(&mut app.property_selected, count, times);
+            app.property_value_scroll = 0;
+        }
+        KeyCode::PageUp if properties_focus => {
+            app.property_value_scroll = app
+                .property_value_scroll
+                .saturating_sub(clamp_u16(times.saturating_mul(PROPERTY_VALUE_LINES)));
+        }
+        KeyCode::PageDown if properties_focus => {
+            app.property_value_scroll = app
+                .property_value_scroll
+                .saturating_add(clamp_u16(times.saturating_mul(PROPERTY_VALUE_LINES)));
+        }
+        KeyCode::Char('y') if properties_focus => app.copy_selected_property(),
+        KeyCode::Esc if properties_focus => app.properties_focus = false,
         KeyCode::Up | KeyCode::Char('k') => {
             // Scroll body when viewing message detail, else navigate list
             if app.selected_message_detail.is_some() {
-                app.detail_body_scroll = app.detail_body_scroll.saturating_sub(1);
+                app.detail_body_scroll = app.detail_body_scroll.saturating_sub(clamp_u16(times));
             } else {
-                move_selection_up(&mut app.message_selected);
+                move_selection_up(&mut app.message_selected, times);
             }
         }
         KeyCode::Down | KeyCode::Char('j') => {
             if app.selected_message_detail.is_some() {
-                app.detail_body_scroll = app.detail_body_scroll.saturating_add(1);
+                app.detail_body_scroll = app.detail_body_scroll.saturating_add(clamp_u16(times));
             } else {
-                move_selection_down(&mut app.message_selected, len);
+                move_selection_down(&mut app.message_selected, len, times);
             }
         }
+        // ] = peek `count` more pages into the current tab
+        KeyCode::Char(']') if !block_if_bg_running(app, BG_BUSY_MSG) => {
+            app.peek_more_pages(times);
+        }
         KeyCode::Enter => {
             // Show message detail
-            let msgs = match app.message_tab {
-                MessageTab::Messages => &app.messages,
-                MessageTab::DeadLetter => &app.dlq_messages,
-            };
-            if let Some(msg) = msgs.get(app.message_selected) {
-                app.selected_message_detail = Some(msg.clone());
-                app.detail_body_scroll = 0;
+            if let Some(msg) = app.selected_message().cloned() {
+                app.open_message_detail(msg);
             }
         }
+        KeyCode::Char('F') if app.selected_message_detail.is_none() => app.open_filter_presets(),
+        KeyCode::Char('m') => app.toggle_compare_mark(),
+        KeyCode::Char('=') => app.open_compare(),
+        KeyCode::Char('O') if app.selected_message_detail.is_none() => app.toggle_expiry_sort(),
+        KeyCode::Char('W') if app.selected_message_detail.is_some() => {
+            app.toggle_body_wrap();
+            app.set_status(if app.body_wrap {
+                "Body wrap on"
+            } else {
+                "Body wrap off — h/l to scroll sideways"
+            });
+        }
+        KeyCode::Char('v') if app.selected_message_detail.is_some() => {
+            app.toggle_properties_focus()
+        }
+        KeyCode::Char('M') if app.selected_message_detail.is_some() => app.cycle_body_format(),
+        KeyCode::Char('J') if app.selected_message_detail.is_some() => app.toggle_jwt_view(),
+        KeyCode::Char('X') if app.selected_message_detail.is_some() => app.toggle_hex_view(),
+        KeyCode::Left | KeyCode::Char('h')
+            if app.selected_message_detail.is_some() && !app.body_wrap =>
+        {
+            let step = clamp_u16(times.saturating_mul(4));
+            app.body_scroll_horizontal = app.body_scroll_horizontal.saturating_sub(step);
+        }
+        KeyCode::Right | KeyCode::Char('l')
+            if app.selected_message_detail.is_some() && !app.body_wrap =>
+        {
+            let step = clamp_u16(times.saturating_mul(4));
+            app.body_scroll_horizontal = app.body_scroll_horizontal.saturating_add(step);
+        }
         KeyCode::Char('1') => {
             app.message_tab = MessageTab::Messages;
             app.message_selected = 0;
@@ -334,9 +617,13 @@ fn handle_message_input(app: &mut App, key: KeyEvent) {
             app.message_tab = MessageTab::DeadLetter;
             app.message_selected = 0;
         }
+        KeyCode::Char('4') => open_transfer_dlq_tab(app),
         // R = Bulk resend from DLQ back to main entity
         KeyCode::Char('R') => {
-            if block_if_bg_running(app, BG_BUSY_MSG) {
+            if block_if_bg_running(app, BG_BUSY_MSG)
+                || block_if_reason_filtered(app)
+                || block_if_message_ops(app)
+            {
                 return;
             }
             if app.message_tab == MessageTab::DeadLetter {
@@ -347,10 +634,12 @@ fn handle_message_input(app: &mut App, key: KeyEvent) {
                             let is_topic = *entity_type == EntityType::Topic;
                             let count = app.dlq_messages.len() as u32;
                             if count > 0 {
+                                app.bulk_resend_delay = None;
                                 app.modal = ActiveModal::ConfirmBulkResend {
                                     entity_path: base_path,
                                     count,
                                     is_topic,
+                                    dry_run: false,
                                 };
                             } else {
                                 app.set_status("No DLQ messages to resend");
@@ -368,50 +657,70 @@ fn handle_message_input(app: &mut App, key: KeyEvent) {
             }
         }
         // D = Bulk delete visible messages
-        KeyCode::Char('D') => {
-            if !block_if_bg_running(app, BG_BUSY_MSG) {
-                if let Some((path, entity_type)) = app.selected_entity() {
-                    match entity_type {
-                        EntityType::Queue | EntityType::Subscription | EntityType::Topic => {
-                            let is_dlq = app.message_tab == MessageTab::DeadLetter;
-                            let is_topic = *entity_type == EntityType::Topic;
-                            let msgs = if is_dlq {
-                                &app.dlq_messages
-                            } else {
-                                &app.messages
+        KeyCode::Char('D')
+            if !block_if_bg_running(app, BG_BUSY_MSG)
+                && !block_if_reason_filtered(app)
+                && !block_if_message_ops(app) =>
+        {
+            if let Some((path, entity_type)) = app.selected_entity() {
+                match entity_type {
+                    _ if app.message_tab == MessageTab::Transferred => {
+                        app.set_status("Bulk delete is not available on the transfer DLQ");
+                    }
+                    EntityType::Queue | EntityType::Subscription | EntityType::Topic => {
+                        let is_dlq = app.message_tab == MessageTab::DeadLetter;
+                        let is_topic = *entity_type == EntityType::Topic;
+                        let msgs = if is_dlq {
+                            &app.dlq_messages
+                        } else {
+                            &app.messages
+                        };
+                        let count = msgs.len() as u32;
+                        if count > 0 {
+                            app.modal = ActiveModal::ConfirmBulkDelete {
+                                entity_path: path.to_string(),
+                                count,
+                                is_dlq,
+                                is_topic,
+                                dry_run: false,
                             };
-                            let count = msgs.len() as u32;
-                            if count > 0 {
-                                app.modal = ActiveModal::ConfirmBulkDelete {
-                                    entity_path: path.to_string(),
-                                    count,
-                                    is_dlq,
-                                    is_topic,
-                                };
-                            } else {
-                                app.set_status("No messages to delete");
-                            }
-                        }
-                        _ => {
-                            app.set_status("Select a queue, topic, or subscription");
+                        } else {
+                            app.set_status("No messages to delete");
                         }
                     }
+                    _ => {
+                        app.set_status("Select a queue, topic, or subscription");
+                    }
                 }
             }
         }
+        // S = DLQ reason breakdown (select a reason to filter the list)
+        KeyCode::Char('S') => {
+            if app.dlq_messages.is_empty() {
+                app.set_status("Peek the dead-letter queue first (d on an entity)");
+            } else {
+                app.dlq_stats_selected = 0;
+                app.modal = ActiveModal::DlqStats;
+            }
+        }
         // e = Edit & resend selected message
+        KeyCode::Char('e')
+            if app
+                .selected_message_detail
+                .as_ref()
+                .or(app.selected_message())
+                .is_some_and(|m| app.is_message_op_in_flight(m)) =>
+        {
+            app.set_status("An operation on this message is already in progress");
+        }
         KeyCode::Char('e') => {
             if app.selected_message_detail.is_some() {
                 // Enter inline WYSIWYG edit mode
                 app.init_detail_edit();
             } else {
                 // No detail open — use list selection and enter inline edit
-                let msg = match app.message_tab {
-                    MessageTab::Messages => app.messages.get(app.message_selected).cloned(),
-                    MessageTab::DeadLetter => app.dlq_messages.get(app.message_selected).cloned(),
-                };
-                if let Some(msg) = msg {
-                    app.selected_message_detail = Some(msg);
+                if let Some(msg) = app.selected_message().cloned() {
+                    app.open_message_detail(msg);
                     app.init_detail_edit();
                 } else {
                     app.set_status("No message selected");
@@ -425,12 +734,7 @@ fn handle_message_input(app: &mut App, key: KeyEvent) {
                 let msg = if app.selected_message_detail.is_some() {
                     app.selected_message_detail.clone()
                 } else {
-                    match app.message_tab {
-                        MessageTab::Messages => app.messages.get(app.message_selected).cloned(),
-                        MessageTab::DeadLetter => {
-                            app.dlq_messages.get(app.message_selected).cloned()
-                        }
-                    }
+                    app.selected_message().cloned()
                 };
                 let has_connections = !app.config.connections.is_empty();
                 let entity_path = app.selected_entity().map(|(path, _)| path.to_string());
@@ -450,14 +754,60 @@ fn handle_message_input(app: &mut App, key: KeyEvent) {
                 }
             }
         }
+        KeyCode::Char('G') if app.message_tab == MessageTab::DeadLetter => {
+            app.dlq_group_view = !app.dlq_group_view;
+            if app.dlq_group_view {
+                app.set_dlq_reason_filter(None);
+                app.dlq_group_selected = 0;
+            }
+        }
+        KeyCode::Esc
+            if app.selected_message_detail.is_none()
+                && app.dlq_group_view
+                && app.dlq_reason_filter.is_some() =>
+        {
+            // Collapse the expanded group back to the summary
+            app.set_dlq_reason_filter(None);
+        }
         KeyCode::Esc => {
             app.selected_message_detail = None;
             app.detail_body_scroll = 0;
+            app.body_scroll_horizontal = 0;
+            app.body_view_format = None;
         }
         _ => {}
     }
 }
 
+/// Switch to the transfer DLQ tab and peek it. Only offered when the
+/// selected entity reports transfer dead-letters.
+fn open_transfer_dlq_tab(app: &mut App) {
+    if block_if_bg_running(app, BG_BUSY_MSG) {
+        return;
+    }
+    match app.selected_transfer_dlq_count() {
+        Some(n) if n > 0 => {
+            app.message_tab = MessageTab::Transferred;
+            app.message_selected = 0;
+            app.focus = FocusPanel::Messages;
+            app.set_status("Peeking transfer DLQ...");
+        }
+        Some(_) => app.set_status("No transfer dead-letter messages on this entity"),
+        None => app.set_status("Select a queue or subscription to view its transfer DLQ"),
+    }
+}
+
+/// Bulk DLQ operations scan the same queue as a running single-message
+/// operation and would race it for the message.
+fn block_if_message_ops(app: &mut App) -> bool {
+    if app.message_ops_in_flight.is_empty() {
+        false
+    } else {
+        app.set_status("Wait for the message operation in progress to finish");
+        true
+    }
+}
+
 fn block_if_bg_running(app: &mut App, message: &str) -> bool {
     if app.bg_running {
         app.set_status(message);
@@ -467,14 +817,217 @@ fn block_if_bg_running(app: &mut App, message: &str) -> bool {
     }
 }
 
-fn move_selection_up(selected: &mut usize) {
-    if *selected > 0 {
-        *selected -= 1;
+/// Keys for the grouped DLQ summary. Returns false for keys that should fall
+/// through to the regular message handling (tab switches, bulk operations).
+fn handle_dlq_group_input(app: &mut App, key: KeyEvent, times: usize) -> bool {
+    let groups = app.dlq_groups().len();
+    match key.code {
+        KeyCode::Up | KeyCode::Char('k') => move_selection_up(&mut app.dlq_group_selected, times),
+        KeyCode::Down | KeyCode::Char('j') => {
+            move_selection_down(&mut app.dlq_group_selected, groups, times)
+        }
+        KeyCode::Enter => {
+            let reason = app
+                .dlq_groups()
+                .get(app.dlq_group_selected)
+                .map(|g| g.reason.clone());
+            if let Some(reason) = reason {
+                app.set_dlq_reason_filter(Some(reason));
+            }
+        }
+        KeyCode::Char('e') | KeyCode::Char('C') => {
+            app.set_status("Press Enter to expand a group, then select a message");
+        }
+        _ => return false,
+    }
+    true
+}
+
+/// Bulk operations act on the whole DLQ on the server, not on the filtered view.
+fn block_if_reason_filtered(app: &mut App) -> bool {
+    if app.message_tab == MessageTab::DeadLetter && app.dlq_reason_filter.is_some() {
+        app.set_status(
+            "Bulk operations apply to the whole DLQ — clear the reason filter first (S, then c)",
+        );
+        true
+    } else if app.message_filter.is_some() {
+        app.set_status("Bulk operations ignore the message filter — clear it first (F, then c)");
+        true
+    } else {
+        false
     }
 }
 
-fn move_selection_down(selected: &mut usize, len: usize) {
+fn move_selection_up(selected: &mut usize, by: usize) {
+    *selected = selected.saturating_sub(by);
+}
+
+/// Move down `by` rows, stopping at the last of `len`.
+fn move_selection_down(selected: &mut usize, len: usize, by: usize) {
     if *selected + 1 < len {
-        *selected += 1;
+        *selected = selected.saturating_add(by).min(len - 1);
+    }
+}
+
+fn clamp_u16(n: usize) -> u16 {
+    n.min(u16::MAX as usize) as u16
+}
+
+/// `50p`: the peek-count prompt starts at the typed count instead of the
+/// last one used.
+fn prefill_peek_count(app: &mut App, count: Option<usize>) {
+    if let (Some(count), ActiveModal::PeekCountInput { .. }) = (count, &app.modal) {
+        app.input_buffer = count.to_string();
+        app.input_cursor = app.input_buffer.chars().count();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::models::{ReceivedMessage, TreeNode};
+    use crate::config::AppConfig;
+
+    fn app() -> App {
+        App::with_config(AppConfig::default(), None)
+    }
+
+    fn key(c: char) -> KeyEvent {
+        KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE)
+    }
+
+    fn type_digits(app: &mut App, digits: &str) -> Vec<bool> {
+        digits
+            .chars()
+            .map(|c| push_count_digit(app, key(c)))
+            .collect()
+    }
+
+    #[test]
+    fn count_prefix_skips_leading_zero_and_modified_digits() {
+        let mut app = app();
+        assert_eq!(type_digits(&mut app, "0"), vec![false]);
+        assert_eq!(app.pending_count, None);
+        assert_eq!(type_digits(&mut app, "105"), vec![true; 3]);
+        assert_eq!(app.pending_count, Some(105));
+
+        app.pending_count = None;
+        let ctrl = KeyEvent::new(KeyCode::Char('5'), KeyModifiers::CONTROL);
+        assert!(!push_count_digit(&mut app, ctrl));
+        app.focus = FocusPanel::Detail;
+        assert_eq!(type_digits(&mut app, "5"), vec![false]);
+        assert_eq!(app.pending_count, None);
+    }
+
+    #[test]
+    fn tab_keys_start_no_count_in_the_messages_panel() {
+        let mut app = app();
+        app.focus = FocusPanel::Messages;
+        assert_eq!(type_digits(&mut app, "124"), vec![false; 3]);
+        assert_eq!(app.pending_count, None);
+        // Once a count has started they are digits
+        assert_eq!(type_digits(&mut app, "3124"), vec![true; 4]);
+        assert_eq!(app.pending_count, Some(3124));
+
+        app.pending_count = None;
+        app.focus = FocusPanel::Tree;
+        assert_eq!(type_digits(&mut app, "1"), vec![true]);
+    }
+
+    #[test]
+    fn count_prefix_saturates_at_max_count() {
+        let mut app = app();
+        type_digits(&mut app, "123456789");
+        assert_eq!(app.pending_count, Some(MAX_COUNT));
+        type_digits(&mut app, "9");
+        assert_eq!(app.pending_count, Some(MAX_COUNT));
+    }
+
+    #[test]
+    fn counted_g_goes_to_that_row_of_the_tree() {
+        let mut app = app();
+        let mut root = TreeNode::new_folder("root", "ns", EntityType::Namespace, 0);
+        for name in ["a", "b", "c", "d"] {
+            root.children
+                .push(TreeNode::new_entity(name, name, EntityType::Queue, name, 1));
+        }
+        app.flat_nodes = root.flatten();
+
+        handle_tree_input(&mut app, key('g'), Some(3));
+        assert_eq!(app.tree_selected, 2);
+        handle_tree_input(&mut app, key('G'), Some(1));
+        assert_eq!(app.tree_selected, 0);
+        handle_tree_input(&mut app, key('G'), Some(99));
+        assert_eq!(app.tree_selected, 4);
+        handle_tree_input(&mut app, key('g'), None);
+        assert_eq!(app.tree_selected, 0);
+        handle_tree_input(&mut app, key('G'), None);
+        assert_eq!(app.tree_selected, 4);
+    }
+
+    #[test]
+    fn counted_bracket_peeks_that_many_more_pages() {
+        let mut app = app();
+        app.flat_nodes =
+            TreeNode::new_entity("q:orders", "orders", EntityType::Queue, "orders", 2).flatten();
+        app.config.settings.peek_count = 10;
+        app.config.settings.peek_max = 40;
+        let message = ReceivedMessage {
+            body: String::new(),
+            broker_properties: Default::default(),
+            custom_properties: Vec::new(),
+            lock_token_uri: None,
+            source_entity: None,
+            raw_body: None,
+        };
+        app.messages = vec![message; 3];
+
+        handle_message_input(&mut app, key(']'), Some(2));
+        assert_eq!(app.pending_peek_count, Some(23));
+        assert_eq!(app.status_message, "Peeking messages...");
+        assert!(!app.peek_dlq);
+
+        app.message_tab = MessageTab::DeadLetter;
+        handle_message_input(&mut app, key(']'), Some(5));
+        assert_eq!(app.pending_peek_count, Some(40));
+        assert!(app.peek_dlq);
+        assert!(app.peek_clamp_note.is_some());
+
+        app.message_tab = MessageTab::Transferred;
+        app.pending_peek_count = None;
+        handle_message_input(&mut app, key(']'), None);
+        assert_eq!(app.pending_peek_count, None);
+    }
+
+    #[test]
+    fn page_keys_scroll_the_selected_property_value() {
+        let mut app = app();
+        app.selected_message_detail = Some(ReceivedMessage {
+            body: String::new(),
+            broker_properties: Default::default(),
+            custom_properties: vec![
+                ("b".to_string(), "x\n".repeat(40)),
+                ("a".to_string(), "short".to_string()),
+            ],
+            lock_token_uri: None,
+            source_entity: None,
+            raw_body: None,
+        });
+        app.toggle_properties_focus();
+        let page = |code| KeyEvent::new(code, KeyModifiers::NONE);
+
+        handle_message_input(&mut app, page(KeyCode::PageDown), Some(2));
+        assert_eq!(
+            usize::from(app.property_value_scroll),
+            2 * PROPERTY_VALUE_LINES
+        );
+        handle_message_input(&mut app, page(KeyCode::PageUp), None);
+        assert_eq!(usize::from(app.property_value_scroll), PROPERTY_VALUE_LINES);
+        assert_eq!(app.detail_body_scroll, 0);
+
+        // Another property starts at its first line
+        handle_message_input(&mut app, key('j'), None);
+        assert_eq!(app.property_selected, 1);
+        assert_eq!(app.property_value_scroll, 0);
     }
 }
@@ -0,0 +1,342 @@
+use std::io::Write;
+
+use chrono::{SecondsFormat, Utc};
+use serde::Serialize;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use crate::app::{BgEvent, BgJob};
+use crate::connection_test::TestOutcome;
+
+/// Whether structured events should be written: `--log-format json` or
+/// `RUST_LOG=json`.
+pub fn json_requested(flag: bool) -> bool {
+    flag || std::env::var("RUST_LOG").is_ok_and(|v| v.eq_ignore_ascii_case("json"))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Info,
+    Warn,
+    Error,
+}
+
+/// One line of the stderr event log.
+#[derive(Debug, Clone, Serialize)]
+pub struct LogEvent {
+    pub timestamp: String,
+    pub level: LogLevel,
+    /// Operation name, e.g. `purge_complete`.
+    pub event: &'static str,
+    pub entity: Option<String>,
+    pub duration_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl LogEvent {
+    pub fn new(level: LogLevel, event: &'static str) -> Self {
+        Self {
+            timestamp: Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+            level,
+            event,
+            entity: None,
+            duration_ms: None,
+            message: None,
+        }
+    }
+
+    /// The log entry for a background result, or `None` for progress updates.
+    /// Results that end a background job take its entity and duration.
+    pub fn from_bg_event(event: &BgEvent, job: Option<&BgJob>) -> Option<Self> {
+        use LogLevel::*;
+        let (level, name, entity, message) = match event {
+            BgEvent::Progress(_) | BgEvent::JobProgress { .. } => return None,
+            BgEvent::MessageOpFinished { .. } => return None,
+            BgEvent::PurgeComplete { results } => {
+                let (summary, failure) = crate::bulk_ops::purge_summary(results);
+                let level = if failure.is_some() { Warn } else { Info };
+                (level, "purge_complete", None, Some(summary))
+            }
+            BgEvent::PurgePreflightLoaded {
+                entity_path,
+                candidates,
+                ..
+            } => (
+                if candidates.is_ok() { Info } else { Warn },
+                "purge_preflight_loaded",
+                Some(entity_path.clone()),
+                candidates.as_ref().err().cloned(),
+            ),
+            BgEvent::ResendComplete {
+                resent,
+                errors,
+                cancelled,
+                failures,
+                ..
+            } => {
+                let mut summary = format!("{} resent, {} errors", resent, errors);
+                if let Some(report) = failures {
+                    summary = format!("{}; {}", summary, report.log_line());
+                }
+                (
+                    if *errors > 0 || *cancelled {
+                        Warn
+                    } else {
+                        Info
+                    },
+                    if *cancelled {
+                        "resend_cancelled"
+                    } else {
+                        "resend_complete"
+                    },
+                    None,
+                    Some(summary),
+                )
+            }
+            BgEvent::BulkDeleteComplete { deleted, .. } => (
+                Info,
+                "bulk_delete_complete",
+                None,
+                Some(format!("{} messages", deleted)),
+            ),
+            BgEvent::DryRunComplete(report) => {
+                (Info, "dry_run_complete", None, Some(report.summary()))
+            }
+            BgEvent::Cancelled { message } => (Warn, "cancelled", None, Some(message.clone())),
+            BgEvent::Failed(message) => (Error, "failed", None, Some(message.clone())),
+            BgEvent::OperationFailed(report) => (
+                Error,
+                "operation_failed",
+                None,
+                Some(format!("{}: {}", report.operation, report.summary)),
+            ),
+            BgEvent::TreeRefreshed { .. } => (Info, "tree_refreshed", None, None),
+            BgEvent::NodeCountsLoaded {
+                topic,
+                subscriptions,
+            } => (
+                if subscriptions.is_ok() { Info } else { Warn },
+                "node_counts_loaded",
+                Some(topic.clone()),
+                subscriptions.as_ref().err().cloned(),
+            ),
+            BgEvent::DetailLoaded(_) => (Info, "detail_loaded", None, None),
+            BgEvent::EntityGone { path } => (Warn, "entity_gone", Some(path.clone()), None),
+            BgEvent::EntityMetricsLoaded { entity, .. } => {
+                (Info, "entity_metrics_loaded", Some(entity.clone()), None)
+            }
+            BgEvent::HealthCheckResult { rtt_ms, status } => {
+                if status == crate::app::HEALTH_OK {
+                    (
+                        Info,
+                        "health_check",
+                        None,
+                        Some(format!("RTT {}ms", rtt_ms)),
+                    )
+                } else {
+                    (Warn, "health_check", None, Some(status.clone()))
+                }
+            }
+            BgEvent::NamespaceInfoLoaded(info) => {
+                (Info, "namespace_info_loaded", Some(info.name.clone()), None)
+            }
+            BgEvent::ConnectionTested { name, outcome } => match outcome {
+                TestOutcome::Ok { .. } => (Info, "connection_tested", Some(name.clone()), None),
+                TestOutcome::Failed { detail, .. } => (
+                    Warn,
+                    "connection_tested",
+                    Some(name.clone()),
+                    Some(detail.clone()),
+                ),
+            },
+            BgEvent::ConnectionTestsDone { .. } => (Info, "connection_tests_done", None, None),
+            BgEvent::SubscriptionFilterLoaded {
+                topic_name,
+                sub_name,
+                ..
+            } => (
+                Info,
+                "subscription_filter_loaded",
+                Some(format!("{}/subscriptions/{}", topic_name, sub_name)),
+                None,
+            ),
+            BgEvent::PeekComplete {
+                entity, messages, ..
+            } => (
+                Info,
+                "peek_complete",
+                Some(entity.clone()),
+                Some(format!("{} messages", messages.len())),
+            ),
+            BgEvent::PeekBothComplete {
+                entity,
+                active,
+                dead_lettered,
+            } => (
+                if active.is_ok() && dead_lettered.is_ok() {
+                    Info
+                } else {
+                    Warn
+                },
+                "peek_both_complete",
+                Some(entity.clone()),
+                Some(crate::app::peek_both_summary(active, dead_lettered)),
+            ),
+            BgEvent::SendComplete { status, .. } => {
+                (Info, "send_complete", None, Some(status.clone()))
+            }
+            BgEvent::EntityCreated { status } => {
+                (Info, "entity_created", None, Some(status.clone()))
+            }
+            BgEvent::EntityUpdated { status } => {
+                (Info, "entity_updated", None, Some(status.clone()))
+            }
+            BgEvent::EntityDeleted { status } => {
+                (Info, "entity_deleted", None, Some(status.clone()))
+            }
+            BgEvent::ResendSendComplete { status, .. } => {
+                (Info, "resend_send_complete", None, Some(status.clone()))
+            }
+            BgEvent::NamespacesDiscovered { .. } => (Info, "namespaces_discovered", None, None),
+            BgEvent::DiscoveryFailed(message) => {
+                (Error, "discovery_failed", None, Some(message.clone()))
+            }
+            BgEvent::DestinationEntitiesLoaded { connection, .. } => (
+                Info,
+                "destination_entities_loaded",
+                Some(connection.clone()),
+                None,
+            ),
+            BgEvent::MessageCopyComplete { status } => {
+                (Info, "message_copy_complete", None, Some(status.clone()))
+            }
+            BgEvent::SubscriptionFilterUpdated { status } => (
+                Info,
+                "subscription_filter_updated",
+                None,
+                Some(status.clone()),
+            ),
+            BgEvent::AuthorizationRulesLoaded { entity_path, .. } => (
+                Info,
+                "authorization_rules_loaded",
+                Some(entity_path.clone()),
+                None,
+            ),
+            BgEvent::SessionsBrowsed(_) => (Info, "sessions_browsed", None, None),
+            BgEvent::SearchComplete(_) => (Info, "search_complete", None, None),
+        };
+
+        let job = job.filter(|_| ends_job(event));
+        let mut entry = Self::new(level, name);
+        entry.entity = entity.or_else(|| job.and_then(|j| j.entity_path.clone()));
+        entry.duration_ms = job.map(|j| j.elapsed().as_millis() as u64);
+        entry.message = message;
+        Some(entry)
+    }
+}
+
+/// Events that finish an operation started with `App::start_bg_job`.
+fn ends_job(event: &BgEvent) -> bool {
+    matches!(
+        event,
+        BgEvent::PurgeComplete { .. }
+            | BgEvent::ResendComplete { .. }
+            | BgEvent::BulkDeleteComplete { .. }
+            | BgEvent::DryRunComplete(_)
+            | BgEvent::Cancelled { .. }
+            | BgEvent::Failed(_)
+            | BgEvent::OperationFailed(_)
+            | BgEvent::ConnectionTestsDone { .. }
+            | BgEvent::NamespacesDiscovered { .. }
+            | BgEvent::DiscoveryFailed(_)
+            | BgEvent::DestinationEntitiesLoaded { .. }
+            | BgEvent::MessageCopyComplete { .. }
+            | BgEvent::AuthorizationRulesLoaded { .. }
+            | BgEvent::SessionsBrowsed(_)
+            | BgEvent::SearchComplete(_)
+    )
+}
+
+/// Start the task that writes events to stderr as newline-delimited JSON.
+/// stdout belongs to the TUI, so stderr is expected to be redirected.
+/// The task ends once every sender is dropped and the queued events are
+/// written; await its handle before exiting so none are lost.
+pub fn spawn_writer() -> (mpsc::UnboundedSender<LogEvent>, JoinHandle<()>) {
+    spawn_writer_to(std::io::stderr())
+}
+
+fn spawn_writer_to<W: Write + Send + 'static>(
+    mut out: W,
+) -> (mpsc::UnboundedSender<LogEvent>, JoinHandle<()>) {
+    let (tx, mut rx) = mpsc::unbounded_channel::<LogEvent>();
+    let task = tokio::spawn(async move {
+        while let Some(event) = rx.recv().await {
+            if let Ok(line) = serde_json::to_string(&event) {
+                let _ = writeln!(out, "{}", line);
+            }
+        }
+        let _ = out.flush();
+    });
+    (tx, task)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_serialize_with_all_fields() {
+        let event = BgEvent::PurgeComplete {
+            results: vec![("orders".to_string(), Ok(12))],
+        };
+        let entry = LogEvent::from_bg_event(&event, None).unwrap();
+        let json: serde_json::Value = serde_json::to_value(&entry).unwrap();
+        assert_eq!(json["level"], "info");
+        assert_eq!(json["event"], "purge_complete");
+        assert_eq!(json["message"], "Deleted 12 messages");
+        assert!(json["entity"].is_null());
+        assert!(json["duration_ms"].is_null());
+        assert!(json["timestamp"].as_str().unwrap().ends_with('Z'));
+
+        assert!(LogEvent::from_bg_event(&BgEvent::Progress("x".into()), None).is_none());
+        let failed = LogEvent::from_bg_event(&BgEvent::Failed("boom".into()), None).unwrap();
+        assert_eq!(failed.level, LogLevel::Error);
+    }
+
+    /// Collects what the writer writes.
+    #[derive(Clone, Default)]
+    struct Sink(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+    impl Write for Sink {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn writer_drains_queued_events_before_ending() {
+        let sink = Sink::default();
+        let (tx, task) = spawn_writer_to(sink.clone());
+        for event in ["first", "second", "third"] {
+            tx.send(LogEvent::new(LogLevel::Info, event)).unwrap();
+        }
+        drop(tx);
+        task.await.unwrap();
+
+        let written = String::from_utf8(sink.0.lock().unwrap().clone()).unwrap();
+        let events: Vec<String> = written
+            .lines()
+            .map(|line| {
+                serde_json::from_str::<serde_json::Value>(line).unwrap()["event"].to_string()
+            })
+            .collect();
+        assert_eq!(events, vec!["\"first\"", "\"second\"", "\"third\""]);
+    }
+}
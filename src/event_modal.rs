@@ -1,8 +1,11 @@
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 
-use crate::app::{ActiveModal, App, DiscoveryState};
+use crate::app::{status_options, ActiveModal, App, DiscoveryState, DlqStats};
+use crate::body_format;
 use crate::client::entity_path;
 use crate::client::models::EntityType;
+use crate::config::SettingField;
+use crate::text_width;
 
 fn move_selection_up(selected: &mut usize) {
     if *selected > 0 {
@@ -16,22 +19,25 @@ fn move_selection_down(selected: &mut usize, len: usize) {
     }
 }
 
+/// Edit a single-line input. `cursor` is a character index, not a byte offset.
 fn handle_single_line_input(
     input: &mut String,
     cursor: &mut usize,
     key: KeyEvent,
     allow_char: impl Fn(char) -> bool,
 ) -> bool {
+    let len = input.chars().count();
+    *cursor = (*cursor).min(len);
     match key.code {
         KeyCode::Char(c) if allow_char(c) => {
-            input.insert(*cursor, c);
+            input.insert(text_width::byte_offset(input, *cursor), c);
             *cursor += 1;
             true
         }
         KeyCode::Backspace => {
             if *cursor > 0 {
                 *cursor -= 1;
-                input.remove(*cursor);
+                input.remove(text_width::byte_offset(input, *cursor));
             }
             true
         }
@@ -42,7 +48,7 @@ fn handle_single_line_input(
             true
         }
         KeyCode::Right => {
-            if *cursor < input.len() {
+            if *cursor < len {
                 *cursor += 1;
             }
             true
@@ -52,7 +58,7 @@ fn handle_single_line_input(
             true
         }
         KeyCode::End => {
-            *cursor = input.len();
+            *cursor = len;
             true
         }
         _ => false,
@@ -60,10 +66,272 @@ fn handle_single_line_input(
 }
 
 pub fn handle_modal_input(app: &mut App, key: KeyEvent) {
+    handle_modal_key(app, key);
+    // However the dialogs were closed, a connect flow that did not connect
+    // is over
+    if app.modal == ActiveModal::None {
+        app.end_connect_flow();
+    }
+}
+
+fn handle_modal_key(app: &mut App, key: KeyEvent) {
     match &app.modal {
         ActiveModal::Help => {
             app.modal = ActiveModal::None;
         }
+        ActiveModal::ErrorDetail { previous } => match key.code {
+            KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => {
+                app.modal = *previous.clone();
+                app.error_report = None;
+            }
+            KeyCode::Char('y') => {
+                if let Some(ref report) = app.error_report {
+                    match crate::clipboard::copy_to_clipboard(&report.full_text()) {
+                        Ok(()) => app.set_status("Error details copied to clipboard"),
+                        Err(e) => app.set_error(format!("Copy failed: {}", e)),
+                    }
+                }
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                app.error_scroll = app.error_scroll.saturating_add(1);
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                app.error_scroll = app.error_scroll.saturating_sub(1);
+            }
+            KeyCode::PageDown => {
+                app.error_scroll = app.error_scroll.saturating_add(10);
+            }
+            KeyCode::PageUp => {
+                app.error_scroll = app.error_scroll.saturating_sub(10);
+            }
+            _ => {}
+        },
+        ActiveModal::ColumnSelector => match key.code {
+            // Shift+↑/↓ (or K/J) reorders the highlighted column
+            KeyCode::Up if key.modifiers.contains(KeyModifiers::SHIFT) => app.move_column(-1),
+            KeyCode::Down if key.modifiers.contains(KeyModifiers::SHIFT) => app.move_column(1),
+            KeyCode::Char('K') => app.move_column(-1),
+            KeyCode::Char('J') => app.move_column(1),
+            KeyCode::Up | KeyCode::Char('k') => {
+                move_selection_up(&mut app.column_editor_selected);
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                move_selection_down(&mut app.column_editor_selected, app.column_editor.len());
+            }
+            KeyCode::Char(' ') => {
+                if let Some(entry) = app.column_editor.get_mut(app.column_editor_selected) {
+                    entry.1 = !entry.1;
+                }
+            }
+            KeyCode::Enter => {
+                if app.apply_column_selector() {
+                    app.modal = ActiveModal::None;
+                    app.set_status("Message columns saved");
+                } else {
+                    app.set_error("Select at least one column");
+                }
+            }
+            KeyCode::Esc => {
+                app.modal = ActiveModal::None;
+            }
+            _ => {}
+        },
+        ActiveModal::CompareMessages => match key.code {
+            KeyCode::Up | KeyCode::Char('k') => {
+                app.compare_scroll = app.compare_scroll.saturating_sub(1);
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                app.compare_scroll = app.compare_scroll.saturating_add(1);
+            }
+            KeyCode::PageUp => app.compare_scroll = app.compare_scroll.saturating_sub(10),
+            KeyCode::PageDown => app.compare_scroll = app.compare_scroll.saturating_add(10),
+            KeyCode::Esc | KeyCode::Char('q') => app.modal = ActiveModal::None,
+            _ => {}
+        },
+        ActiveModal::MessageSearchInput => match key.code {
+            KeyCode::Enter if !app.input_buffer.trim().is_empty() => {
+                app.modal = ActiveModal::None;
+                app.set_status("Searching namespace...");
+            }
+            KeyCode::Esc => app.modal = ActiveModal::None,
+            _ => {}
+        },
+        ActiveModal::SearchResults => {
+            let len = app.search_results.as_ref().map_or(0, |r| r.hits.len());
+            match key.code {
+                KeyCode::Up | KeyCode::Char('k') => move_selection_up(&mut app.search_selected),
+                KeyCode::Down | KeyCode::Char('j') => {
+                    move_selection_down(&mut app.search_selected, len);
+                }
+                KeyCode::Enter => app.jump_to_search_hit(),
+                KeyCode::Esc | KeyCode::Char('q') => app.modal = ActiveModal::None,
+                _ => {}
+            }
+        }
+        ActiveModal::ApiStats => {
+            if matches!(
+                key.code,
+                KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') | KeyCode::Char('L')
+            ) {
+                app.modal = ActiveModal::None;
+            }
+        }
+        ActiveModal::ForwardGraph => {
+            let len = app.forward_graph.len();
+            match key.code {
+                KeyCode::Up | KeyCode::Char('k') => {
+                    move_selection_up(&mut app.forward_graph_selected)
+                }
+                KeyCode::Down | KeyCode::Char('j') => {
+                    move_selection_down(&mut app.forward_graph_selected, len);
+                }
+                KeyCode::Enter => app.reveal_forward_graph_selection(),
+                KeyCode::Esc | KeyCode::Char('q') => app.modal = ActiveModal::None,
+                _ => {}
+            }
+        }
+        ActiveModal::Sessions => {
+            let len = app.session_browse.as_ref().map_or(0, |b| b.sessions.len());
+            match key.code {
+                KeyCode::Up | KeyCode::Char('k') => move_selection_up(&mut app.session_selected),
+                KeyCode::Down | KeyCode::Char('j') => {
+                    move_selection_down(&mut app.session_selected, len);
+                }
+                KeyCode::Enter => app.open_selected_session(),
+                KeyCode::Esc | KeyCode::Char('q') => app.modal = ActiveModal::None,
+                _ => {}
+            }
+        }
+        ActiveModal::ForwardToInput { path, .. } => match key.code {
+            // Blank input clears forwarding
+            KeyCode::Enter => {
+                let (path, target) = (path.clone(), app.input_buffer.clone());
+                app.form_error = app.forward_chain_error(&path, &target);
+                if app.form_error.is_none() {
+                    app.set_status("Updating forwarding...");
+                }
+            }
+            KeyCode::Esc => {
+                app.input_buffer.clear();
+                app.form_error = None;
+                app.modal = ActiveModal::None;
+            }
+            _ => {}
+        },
+        ActiveModal::CopyFormat { path, portal_url } => match key.code {
+            KeyCode::Char('p') | KeyCode::Char('P') | KeyCode::Enter => {
+                let path = path.clone();
+                app.modal = ActiveModal::None;
+                app.copy_text(&path, "Path");
+            }
+            KeyCode::Char('u') | KeyCode::Char('U') => {
+                let url = portal_url.clone();
+                app.modal = ActiveModal::None;
+                app.copy_text(&url, "Portal URL");
+            }
+            KeyCode::Esc | KeyCode::Char('q') => app.modal = ActiveModal::None,
+            _ => {}
+        },
+        ActiveModal::ResumeDraft { kind } => match key.code {
+            KeyCode::Char('r') | KeyCode::Char('R') | KeyCode::Enter => {
+                app.settle_draft(*kind, true);
+            }
+            KeyCode::Char('d') | KeyCode::Char('D') => app.settle_draft(*kind, false),
+            // Not now: close the form, keep the draft for next time
+            KeyCode::Esc => {
+                app.detail_editing = false;
+                app.modal = ActiveModal::None;
+            }
+            _ => {}
+        },
+        ActiveModal::CreateOnTopic { topic } => match key.code {
+            KeyCode::Char('t') | KeyCode::Char('T') => app.init_create_topic_form(),
+            KeyCode::Char('s') | KeyCode::Char('S') | KeyCode::Enter => {
+                let topic = topic.clone();
+                app.init_create_subscription_form(&topic);
+            }
+            KeyCode::Esc | KeyCode::Char('n') | KeyCode::Char('N') => {
+                app.modal = ActiveModal::None;
+            }
+            _ => {}
+        },
+        ActiveModal::StatusSelect { entity_type, .. } => {
+            let len = status_options(entity_type).len();
+            match key.code {
+                KeyCode::Up | KeyCode::Char('k') => move_selection_up(&mut app.status_selected),
+                KeyCode::Down | KeyCode::Char('j') => {
+                    move_selection_down(&mut app.status_selected, len);
+                }
+                KeyCode::Enter => app.set_status("Updating status..."),
+                KeyCode::Esc | KeyCode::Char('q') => app.modal = ActiveModal::None,
+                _ => {}
+            }
+        }
+        ActiveModal::Settings { editing: false } => match key.code {
+            KeyCode::Up | KeyCode::Char('k') => {
+                move_selection_up(&mut app.settings_selected);
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                move_selection_down(&mut app.settings_selected, SettingField::ALL.len());
+            }
+            KeyCode::Enter | KeyCode::Char(' ') => app.edit_selected_setting(),
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char(',') => {
+                app.modal = ActiveModal::None;
+            }
+            _ => {}
+        },
+        ActiveModal::Settings { editing: true } => match key.code {
+            KeyCode::Enter => {
+                let field = SettingField::ALL[app.settings_selected];
+                let raw = app.input_buffer.clone();
+                if app.apply_setting(field, &raw) {
+                    app.modal = ActiveModal::Settings { editing: false };
+                }
+            }
+            KeyCode::Esc => {
+                app.settings_error = None;
+                app.modal = ActiveModal::Settings { editing: false };
+            }
+            _ => {}
+        },
+        ActiveModal::DlqStats => {
+            let reasons = DlqStats::from_messages(&app.dlq_messages).reasons;
+            match key.code {
+                KeyCode::Up | KeyCode::Char('k') => {
+                    move_selection_up(&mut app.dlq_stats_selected);
+                }
+                KeyCode::Down | KeyCode::Char('j') => {
+                    move_selection_down(&mut app.dlq_stats_selected, reasons.len());
+                }
+                KeyCode::Enter => {
+                    if let Some((reason, count)) = reasons.get(app.dlq_stats_selected) {
+                        let status =
+                            format!("Showing {} DLQ message(s) with reason '{}'", count, reason);
+                        app.set_dlq_reason_filter(Some(reason.clone()));
+                        app.modal = ActiveModal::None;
+                        app.set_status(status);
+                    }
+                }
+                KeyCode::Char('c') => {
+                    app.set_dlq_reason_filter(None);
+                    app.modal = ActiveModal::None;
+                    app.set_status("DLQ reason filter cleared");
+                }
+                KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('S') => {
+                    app.modal = ActiveModal::None;
+                }
+                _ => {}
+            }
+        }
+        ActiveModal::AuthorizationRules { .. } => {
+            if matches!(
+                key.code,
+                KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') | KeyCode::Char('a')
+            ) {
+                app.modal = ActiveModal::None;
+                app.authorization_rules.clear();
+            }
+        }
         ActiveModal::ConnectionModeSelect => match key.code {
             KeyCode::Char('1') | KeyCode::Char('s') | KeyCode::Char('S') => {
                 app.input_buffer.clear();
@@ -73,6 +341,12 @@ pub fn handle_modal_input(app: &mut App, key: KeyEvent) {
             KeyCode::Char('2') | KeyCode::Char('a') | KeyCode::Char('A') => {
                 app.start_namespace_discovery();
             }
+            KeyCode::Char('4') | KeyCode::Char('e') | KeyCode::Char('E') => {
+                // Pre-fill the emulator's well-known connection string; Enter connects
+                app.input_buffer = crate::client::auth::EMULATOR_CONNECTION_STRING.to_string();
+                app.input_cursor = app.input_buffer.chars().count();
+                app.modal = ActiveModal::ConnectionInput;
+            }
             KeyCode::Esc => {
                 app.modal = ActiveModal::None;
             }
@@ -86,27 +360,23 @@ pub fn handle_modal_input(app: &mut App, key: KeyEvent) {
                 }
             }
             DiscoveryState::List => match key.code {
-                KeyCode::Up | KeyCode::Char('k') => {
+                KeyCode::Up => {
                     move_selection_up(&mut app.namespace_list_state);
                 }
-                KeyCode::Down | KeyCode::Char('j') => {
-                    move_selection_down(
-                        &mut app.namespace_list_state,
-                        app.discovered_namespaces.len(),
-                    );
+                KeyCode::Down => {
+                    let count = app.filtered_namespaces().len();
+                    move_selection_down(&mut app.namespace_list_state, count);
                 }
                 KeyCode::Enter => {
                     if let Some(ns) = app
-                        .discovered_namespaces
+                        .filtered_namespaces()
                         .get(app.namespace_list_state)
-                        .cloned()
+                        .map(|ns| (*ns).clone())
                     {
                         match app.connect_azure_ad(&ns.fqdn) {
                             Ok(_) => {
-                                app.config
-                                    .add_azure_ad_connection(ns.name.clone(), ns.fqdn.clone());
-                                let _ = app.config.save();
-                                app.connection_name = Some(ns.name.clone());
+                                let name = app.save_azure_ad_connection(ns.fqdn.clone());
+                                app.remember_connection(name);
                                 app.modal = ActiveModal::None;
                                 app.set_status("Connected via Azure AD! Loading entities...");
                             }
@@ -117,14 +387,29 @@ pub fn handle_modal_input(app: &mut App, key: KeyEvent) {
                         }
                     }
                 }
-                KeyCode::Char('m') | KeyCode::Char('M') => {
+                KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    app.refresh_namespace_discovery();
+                }
+                KeyCode::Tab => {
                     app.input_buffer.clear();
                     app.input_cursor = 0;
                     app.modal = ActiveModal::AzureAdNamespaceInput;
                 }
+                KeyCode::Esc if !app.namespace_search.is_empty() => {
+                    app.namespace_search.clear();
+                    app.namespace_list_state = 0;
+                }
                 KeyCode::Esc => {
                     app.modal = ActiveModal::None;
                 }
+                KeyCode::Backspace => {
+                    app.namespace_search.pop();
+                    app.namespace_list_state = 0;
+                }
+                KeyCode::Char(c) => {
+                    app.namespace_search.push(c);
+                    app.namespace_list_state = 0;
+                }
                 _ => {}
             },
             DiscoveryState::Error(_) => match key.code {
@@ -150,10 +435,8 @@ pub fn handle_modal_input(app: &mut App, key: KeyEvent) {
                     };
                     match app.connect_azure_ad(&fqns) {
                         Ok(_) => {
-                            app.config
-                                .add_azure_ad_connection(fqns.clone(), fqns.clone());
-                            let _ = app.config.save();
-                            app.connection_name = Some(fqns);
+                            let name = app.save_azure_ad_connection(fqns);
+                            app.remember_connection(name);
                             app.modal = ActiveModal::None;
                             app.set_status("Connected via Azure AD! Loading entities...");
                         }
@@ -168,6 +451,15 @@ pub fn handle_modal_input(app: &mut App, key: KeyEvent) {
             }
             _ => {}
         },
+        ActiveModal::ConfirmQuit => match key.code {
+            KeyCode::Char('q') | KeyCode::Char('Q') => app.running = false,
+            KeyCode::Char('c') if key.modifiers == KeyModifiers::CONTROL => app.running = false,
+            KeyCode::Char('c') | KeyCode::Char('C') => app.cancel_and_quit(),
+            KeyCode::Char('k') | KeyCode::Char('K') | KeyCode::Esc => {
+                app.modal = ActiveModal::None;
+            }
+            _ => {}
+        },
         ActiveModal::ConfirmDelete(_) => match key.code {
             KeyCode::Char('y') | KeyCode::Char('Y') => {
                 app.set_status("Deleting...");
@@ -177,58 +469,199 @@ pub fn handle_modal_input(app: &mut App, key: KeyEvent) {
             }
             _ => {}
         },
-        ActiveModal::ConfirmBulkResend { .. } => match key.code {
+        ActiveModal::ConfirmBulkResend {
+            entity_path,
+            count,
+            is_topic,
+            dry_run,
+        } => match key.code {
             KeyCode::Char('y') | KeyCode::Char('Y') => {
-                app.set_status("Bulk resending...");
+                app.set_status(if *dry_run {
+                    "Dry run (bulk resend)..."
+                } else {
+                    "Bulk resending..."
+                });
+            }
+            KeyCode::Char('t') | KeyCode::Char('T') => app.toggle_dry_run(),
+            KeyCode::Char('d') | KeyCode::Char('D') => {
+                app.input_buffer = app
+                    .bulk_resend_delay
+                    .map(|spec| spec.to_string())
+                    .unwrap_or_default();
+                app.input_cursor = app.input_buffer.chars().count();
+                app.modal = ActiveModal::BulkResendDelayInput {
+                    entity_path: entity_path.clone(),
+                    count: *count,
+                    is_topic: *is_topic,
+                    dry_run: *dry_run,
+                };
             }
             KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
                 app.modal = ActiveModal::None;
             }
             _ => {}
         },
-        ActiveModal::ConfirmBulkDelete { .. } => match key.code {
+        ActiveModal::BulkResendDelayInput {
+            entity_path,
+            count,
+            is_topic,
+            dry_run,
+        } => {
+            let back = ActiveModal::ConfirmBulkResend {
+                entity_path: entity_path.clone(),
+                count: *count,
+                is_topic: *is_topic,
+                dry_run: *dry_run,
+            };
+            match key.code {
+                // Blank input resends immediately
+                KeyCode::Enter => {
+                    let input = app.input_buffer.trim().to_string();
+                    let parsed = if input.is_empty() {
+                        Ok(None)
+                    } else {
+                        crate::delay::parse_delay_spec(&input).map(Some)
+                    };
+                    match parsed {
+                        Ok(spec) => {
+                            app.bulk_resend_delay = spec;
+                            app.modal = back;
+                        }
+                        Err(e) => app.set_error(e),
+                    }
+                }
+                KeyCode::Esc => app.modal = back,
+                _ => {}
+            }
+        }
+        ActiveModal::ConfirmBulkDelete { dry_run, .. } => match key.code {
             KeyCode::Char('y') | KeyCode::Char('Y') => {
-                app.set_status("Bulk deleting...");
+                app.set_status(if *dry_run {
+                    "Dry run (bulk delete)..."
+                } else {
+                    "Bulk deleting..."
+                });
             }
+            KeyCode::Char('t') | KeyCode::Char('T') => app.toggle_dry_run(),
             KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
                 app.modal = ActiveModal::None;
             }
             _ => {}
         },
-        ActiveModal::PeekCountInput => match key.code {
-            KeyCode::Enter => {
-                if let Ok(count) = app.input_buffer.trim().parse::<i32>() {
-                    if count > 0 {
-                        app.pending_peek_count = Some(count);
-                        app.modal = ActiveModal::None;
-                        app.set_status("Peeking messages...");
+        ActiveModal::PeekCountInput { custom } => {
+            let custom = *custom;
+            match key.code {
+                KeyCode::Enter => {
+                    if let Ok(count) = app.input_buffer.trim().parse::<i64>() {
+                        if count > 0 {
+                            submit_peek_count(app, count);
+                        } else {
+                            app.set_error("Count must be a positive number");
+                        }
                     } else {
-                        app.set_error("Count must be a positive number");
+                        app.set_error("Invalid number");
                     }
-                } else {
-                    app.set_error("Invalid number");
                 }
+                KeyCode::Esc => {
+                    app.modal = ActiveModal::None;
+                }
+                KeyCode::Tab | KeyCode::Char('c') if !custom => {
+                    app.input_cursor = app.input_buffer.chars().count();
+                    app.modal = ActiveModal::PeekCountInput { custom: true };
+                }
+                KeyCode::Char('1') if !custom => submit_peek_count(app, 10),
+                KeyCode::Char('2') if !custom => submit_peek_count(app, 100),
+                KeyCode::Char('3') if !custom => submit_peek_count(app, 1000),
+                KeyCode::Char('a') if !custom => match app.pending_peekable_count() {
+                    Some(0) => app.set_error("Nothing to peek — the entity is empty"),
+                    Some(count) => submit_peek_count(app, count),
+                    None => app
+                        .set_error("Message counts not loaded yet — wait for the properties panel"),
+                },
+                _ => {}
             }
-            KeyCode::Esc => {
-                app.modal = ActiveModal::None;
-            }
-            _ => {}
-        },
-        ActiveModal::ClearOptions { .. } => match key.code {
-            KeyCode::Char('d') | KeyCode::Char('D') => {
-                app.set_status("Clearing (delete)...");
+        }
+        // A topic purge covers every subscription; check them first. A dry
+        // run scans all of them instead.
+        ActiveModal::ClearOptions {
+            is_topic,
+            dry_run,
+            dlq_only,
+            ..
+        } => match key.code {
+            KeyCode::Char('d') | KeyCode::Char('D') if !*dlq_only => {
+                app.set_status(if *dry_run {
+                    "Dry run (delete)..."
+                } else if *is_topic {
+                    "Checking subscriptions (delete)..."
+                } else {
+                    "Clearing (delete)..."
+                });
             }
             KeyCode::Char('l') | KeyCode::Char('L') => {
-                app.set_status("Clearing (delete DLQ)...");
+                app.set_status(if *dry_run {
+                    "Dry run (delete DLQ)..."
+                } else if *is_topic {
+                    "Checking subscriptions (delete DLQ)..."
+                } else {
+                    "Clearing (delete DLQ)..."
+                });
             }
             KeyCode::Char('r') | KeyCode::Char('R') => {
-                app.set_status("Clearing (resend)...");
+                app.set_status(if *dry_run {
+                    "Dry run (resend)..."
+                } else {
+                    "Clearing (resend)..."
+                });
+            }
+            KeyCode::Char('t') | KeyCode::Char('T') => app.toggle_dry_run(),
+            KeyCode::Esc | KeyCode::Char('n') | KeyCode::Char('N') => {
+                app.modal = ActiveModal::None;
+            }
+            _ => {}
+        },
+        ActiveModal::DryRunSummary => match key.code {
+            KeyCode::Enter | KeyCode::Char('y') | KeyCode::Char('Y') => {
+                app.run_dry_run_for_real();
             }
             KeyCode::Esc | KeyCode::Char('n') | KeyCode::Char('N') => {
+                app.dry_run_report = None;
                 app.modal = ActiveModal::None;
             }
             _ => {}
         },
+        ActiveModal::ResendReport => match key.code {
+            KeyCode::Char('r') | KeyCode::Char('R') => app.retry_failed_resends(),
+            KeyCode::Down | KeyCode::Char('j') => app.scroll_resend_report(1),
+            KeyCode::Up | KeyCode::Char('k') => app.scroll_resend_report(-1),
+            KeyCode::PageDown => app.scroll_resend_report(10),
+            KeyCode::PageUp => app.scroll_resend_report(-10),
+            KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => app.close_resend_report(),
+            _ => {}
+        },
+        ActiveModal::PurgePreflight { is_dlq, .. } => {
+            let is_dlq = *is_dlq;
+            let len = app.purge_candidates.len();
+            match key.code {
+                KeyCode::Up | KeyCode::Char('k') => {
+                    move_selection_up(&mut app.purge_candidate_selected)
+                }
+                KeyCode::Down | KeyCode::Char('j') => {
+                    move_selection_down(&mut app.purge_candidate_selected, len);
+                }
+                KeyCode::Char(' ') => app.toggle_purge_candidate(),
+                KeyCode::Char('a') => app.toggle_all_purge_candidates(),
+                KeyCode::Enter | KeyCode::Char('y') | KeyCode::Char('Y') => {
+                    app.confirm_purge_preflight(is_dlq);
+                }
+                KeyCode::Esc | KeyCode::Char('n') | KeyCode::Char('N') => {
+                    app.purge_candidates.clear();
+                    app.modal = ActiveModal::None;
+                    app.set_status("Purge cancelled");
+                }
+                _ => {}
+            }
+        }
         ActiveModal::ConnectionList => match key.code {
             KeyCode::Esc => {
                 app.modal = ActiveModal::None;
@@ -236,16 +669,33 @@ pub fn handle_modal_input(app: &mut App, key: KeyEvent) {
             KeyCode::Char('n') => {
                 app.input_buffer.clear();
                 app.input_cursor = 0;
-                app.modal = ActiveModal::ConnectionModeSelect;
+                app.modal = ActiveModal::ConnectionGroupInput { connection: None };
+            }
+            KeyCode::Char('G') => {
+                if let Some(conn) = app.selected_saved_connection() {
+                    let connection = Some(conn.name.clone());
+                    app.input_buffer = conn.group.clone().unwrap_or_default();
+                    app.input_cursor = app.input_buffer.chars().count();
+                    app.modal = ActiveModal::ConnectionGroupInput { connection };
+                }
             }
+            KeyCode::Char('t') => {
+                if app.bg_running {
+                    app.set_status("A background operation is in progress...");
+                } else {
+                    app.set_status("Testing connections...");
+                }
+            }
+            KeyCode::Char('f') => app.toggle_connection_failures_only(),
             KeyCode::Up | KeyCode::Char('k') => {
                 move_selection_up(&mut app.input_field_index);
             }
             KeyCode::Down | KeyCode::Char('j') => {
-                move_selection_down(&mut app.input_field_index, app.config.connections.len());
+                let listed = app.listed_connections().len();
+                move_selection_down(&mut app.input_field_index, listed);
             }
             KeyCode::Enter => {
-                if let Some(conn) = app.config.connections.get(app.input_field_index) {
+                if let Some(conn) = app.selected_saved_connection() {
                     let name = conn.name.clone();
                     let is_ad = conn.is_azure_ad();
                     let ns = conn.namespace.clone().unwrap_or_default();
@@ -273,10 +723,10 @@ pub fn handle_modal_input(app: &mut App, key: KeyEvent) {
                 }
             }
             KeyCode::Char('d') => {
-                if let Some(conn) = app.config.connections.get(app.input_field_index) {
+                if let Some(conn) = app.selected_saved_connection() {
                     let name = conn.name.clone();
                     app.config.remove_connection(&name);
-                    let _ = app.config.save();
+                    app.save_config();
                     if app.input_field_index > 0 {
                         app.input_field_index -= 1;
                     }
@@ -287,6 +737,31 @@ pub fn handle_modal_input(app: &mut App, key: KeyEvent) {
             }
             _ => {}
         },
+        ActiveModal::ConnectionGroupInput { connection } => match key.code {
+            // Blank input leaves the connection ungrouped
+            KeyCode::Enter => {
+                let group = Some(app.input_buffer.trim().to_string()).filter(|g| !g.is_empty());
+                app.input_buffer.clear();
+                app.input_cursor = 0;
+                match connection.clone() {
+                    Some(name) => {
+                        app.config.set_connection_group(&name, group);
+                        app.save_config();
+                        app.select_saved_connection(&name);
+                        app.modal = ActiveModal::ConnectionList;
+                    }
+                    None => {
+                        app.pending_connection_group = group;
+                        app.modal = ActiveModal::ConnectionModeSelect;
+                    }
+                }
+            }
+            KeyCode::Esc => {
+                app.input_buffer.clear();
+                app.modal = ActiveModal::ConnectionList;
+            }
+            _ => {}
+        },
         ActiveModal::ConnectionSwitch => match key.code {
             KeyCode::Char('d') | KeyCode::Char('D') => {
                 app.disconnect();
@@ -304,11 +779,105 @@ pub fn handle_modal_input(app: &mut App, key: KeyEvent) {
                     app.modal = ActiveModal::ConnectionList;
                 }
             }
+            KeyCode::Char('r') | KeyCode::Char('R') => app.open_connection_rename(),
+            KeyCode::Char('x') | KeyCode::Char('X') => app.open_delete_connection(),
             KeyCode::Esc | KeyCode::Char('c') | KeyCode::Char('C') => {
                 app.modal = ActiveModal::None;
             }
             _ => {}
         },
+        ActiveModal::FilterPresets => {
+            let len = app.config.filter_presets.len();
+            let selected = (app.filter_preset_selected < len).then_some(app.filter_preset_selected);
+            match key.code {
+                KeyCode::Up | KeyCode::Char('k') => {
+                    move_selection_up(&mut app.filter_preset_selected)
+                }
+                KeyCode::Down | KeyCode::Char('j') => {
+                    move_selection_down(&mut app.filter_preset_selected, len)
+                }
+                KeyCode::Enter => app.apply_selected_filter_preset(),
+                KeyCode::Char('/') => app.open_message_filter_input(None),
+                KeyCode::Char('s') => app.open_filter_preset_name_input(None),
+                KeyCode::Char('e') if selected.is_some() => app.open_message_filter_input(selected),
+                KeyCode::Char('r') if selected.is_some() => {
+                    app.open_filter_preset_name_input(selected)
+                }
+                KeyCode::Char('d') => app.open_delete_filter_preset(),
+                KeyCode::Char('c') => app.apply_message_filter(None),
+                KeyCode::Esc => app.modal = ActiveModal::None,
+                _ => {}
+            }
+        }
+        ActiveModal::ConfirmDeleteFilterPreset(name) => match key.code {
+            KeyCode::Char('y') | KeyCode::Char('Y') => {
+                let name = name.clone();
+                app.delete_filter_preset(&name);
+            }
+            KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
+                app.modal = ActiveModal::FilterPresets;
+            }
+            _ => {}
+        },
+        ActiveModal::MessageFilterInput { scope, preset } => match key.code {
+            KeyCode::Enter => {
+                let (scope, preset) = (*scope, *preset);
+                app.submit_message_filter_input(scope, preset);
+            }
+            KeyCode::Tab => {
+                app.modal = ActiveModal::MessageFilterInput {
+                    scope: scope.next(),
+                    preset: *preset,
+                };
+            }
+            KeyCode::Esc => {
+                app.input_buffer.clear();
+                app.input_cursor = 0;
+                app.modal = ActiveModal::FilterPresets;
+            }
+            _ => {}
+        },
+        ActiveModal::FilterPresetNameInput { preset } => match key.code {
+            KeyCode::Enter => {
+                let preset = *preset;
+                app.submit_filter_preset_name(preset);
+            }
+            KeyCode::Esc => {
+                app.input_buffer.clear();
+                app.input_cursor = 0;
+                app.modal = ActiveModal::FilterPresets;
+            }
+            _ => {}
+        },
+        ActiveModal::ConfirmFilterPeek => match key.code {
+            KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter => app.peek_for_filter(),
+            KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
+                app.modal = ActiveModal::None;
+            }
+            _ => {}
+        },
+        ActiveModal::ConnectionRenameInput { connection } => match key.code {
+            KeyCode::Enter => {
+                let connection = connection.clone();
+                app.rename_connection(&connection);
+            }
+            KeyCode::Esc => {
+                app.input_buffer.clear();
+                app.input_cursor = 0;
+                app.modal = ActiveModal::ConnectionSwitch;
+            }
+            _ => {}
+        },
+        ActiveModal::ConfirmDeleteConnection(name) => match key.code {
+            KeyCode::Char('y') | KeyCode::Char('Y') => {
+                let name = name.clone();
+                app.delete_connection(&name);
+            }
+            KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
+                app.modal = ActiveModal::ConnectionSwitch;
+            }
+            _ => {}
+        },
         ActiveModal::ConnectionInput => match key.code {
             KeyCode::Esc => {
                 app.modal = ActiveModal::None;
@@ -318,14 +887,8 @@ pub fn handle_modal_input(app: &mut App, key: KeyEvent) {
                 if !cs.is_empty() {
                     match app.connect(&cs) {
                         Ok(_) => {
-                            let ns = app
-                                .connection_config
-                                .as_ref()
-                                .map(|c| c.namespace.clone())
-                                .unwrap_or_else(|| "default".to_string());
-                            app.config.add_connection(ns.clone(), cs);
-                            let _ = app.config.save();
-                            app.connection_name = Some(ns);
+                            let name = app.save_sas_connection(cs);
+                            app.remember_connection(name);
                             app.modal = ActiveModal::None;
                             app.set_status("Connected! Loading entities...");
                         }
@@ -366,34 +929,16 @@ pub fn handle_modal_input(app: &mut App, key: KeyEvent) {
             KeyCode::Enter => {
                 if let Some(conn) = app.config.connections.get(app.input_field_index) {
                     let name = conn.name.clone();
-                    let is_ad = conn.is_azure_ad();
 
-                    let config_result: Result<crate::client::ConnectionConfig, String> = if is_ad {
-                        if let Some(ref ns) = conn.namespace {
-                            match azure_identity::DefaultAzureCredential::new() {
-                                Ok(cred) => {
-                                    Ok(crate::client::ConnectionConfig::from_azure_ad(ns, cred))
-                                }
-                                Err(e) => Err(format!("Azure AD credential error: {}", e)),
-                            }
-                        } else {
-                            Err("No namespace configured for Azure AD connection".to_string())
-                        }
-                    } else if let Some(ref cs) = conn.connection_string {
-                        crate::client::ConnectionConfig::from_connection_string(cs)
-                            .map_err(|e| format!("Connection string parse error: {}", e))
-                    } else {
-                        Err("No connection string configured".to_string())
-                    };
-
-                    match config_result {
+                    match crate::app::saved_connection_config(conn) {
                         Ok(config) => {
                             app.copy_dest_connection_name = Some(name);
                             app.copy_dest_connection_config = Some(config);
                             app.copy_dest_entities.clear();
+                            app.copy_entity_filter.clear();
                             app.copy_entity_selected = 0;
                             app.copy_entity_list_state.select(Some(0));
-                            app.set_status("Loading destination entities...");
+                            app.load_destination_entities();
                             app.modal = ActiveModal::CopySelectEntity;
                         }
                         Err(e) => {
@@ -409,14 +954,13 @@ pub fn handle_modal_input(app: &mut App, key: KeyEvent) {
             _ => {}
         },
         ActiveModal::CopySelectEntity => match key.code {
+            KeyCode::Esc if !app.copy_entity_filter.is_empty() => {
+                app.copy_entity_filter.clear();
+                app.copy_entity_selected = 0;
+            }
             KeyCode::Esc => {
                 app.modal = ActiveModal::None;
-                app.copy_source_message = None;
-                app.copy_source_entity = None;
-                app.copy_dest_entities.clear();
-                app.copy_entity_selected = 0;
-                app.copy_dest_connection_name = None;
-                app.copy_dest_connection_config = None;
+                app.clear_copy_state();
             }
             KeyCode::Up => {
                 move_selection_up(&mut app.copy_entity_selected);
@@ -424,21 +968,15 @@ pub fn handle_modal_input(app: &mut App, key: KeyEvent) {
                     .select(Some(app.copy_entity_selected));
             }
             KeyCode::Down => {
-                move_selection_down(&mut app.copy_entity_selected, app.copy_dest_entities.len());
-                app.copy_entity_list_state
-                    .select(Some(app.copy_entity_selected));
-            }
-            KeyCode::Char('k') if key.modifiers.is_empty() => {
-                move_selection_up(&mut app.copy_entity_selected);
+                let count = app.copy_filtered_entities().len();
+                move_selection_down(&mut app.copy_entity_selected, count);
                 app.copy_entity_list_state
                     .select(Some(app.copy_entity_selected));
             }
-            KeyCode::Char('j') if key.modifiers.is_empty() => {
-                move_selection_down(&mut app.copy_entity_selected, app.copy_dest_entities.len());
-                app.copy_entity_list_state
-                    .select(Some(app.copy_entity_selected));
+            KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                app.load_destination_entities();
             }
-            KeyCode::Char('s') | KeyCode::Char('S') => {
+            KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) => {
                 if let Some(ref src_entity) = app.copy_source_entity {
                     let entity_name = entity_path::send_target(src_entity);
                     let exists = app
@@ -458,14 +996,26 @@ pub fn handle_modal_input(app: &mut App, key: KeyEvent) {
                 }
             }
             KeyCode::Enter => {
-                if let Some((entity, _)) = app.copy_dest_entities.get(app.copy_entity_selected) {
-                    app.copy_destination_entity = Some(entity.clone());
+                let selected = app
+                    .copy_filtered_entities()
+                    .get(app.copy_entity_selected)
+                    .map(|(entity, _)| entity.clone());
+                if let Some(entity) = selected {
+                    app.copy_destination_entity = Some(entity);
                     if let Some(msg) = app.copy_source_message.clone() {
                         app.populate_edit_fields(&msg);
                         app.modal = ActiveModal::CopyEditMessage;
                     }
                 }
             }
+            KeyCode::Backspace => {
+                app.copy_entity_filter.pop();
+                app.copy_entity_selected = 0;
+            }
+            KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                app.copy_entity_filter.push(c);
+                app.copy_entity_selected = 0;
+            }
             _ => {}
         },
         ActiveModal::SendMessage
@@ -474,6 +1024,7 @@ pub fn handle_modal_input(app: &mut App, key: KeyEvent) {
         | ActiveModal::CreateTopic
         | ActiveModal::CreateSubscription
         | ActiveModal::EditSubscriptionFilter
+        | ActiveModal::EditEntity { .. }
         | ActiveModal::CopyEditMessage => {
             handle_form_input(app, key);
         }
@@ -487,7 +1038,7 @@ pub fn handle_modal_input(app: &mut App, key: KeyEvent) {
                     true
                 });
         }
-        ActiveModal::PeekCountInput => {
+        ActiveModal::PeekCountInput { custom: true } => {
             let _ =
                 handle_single_line_input(&mut app.input_buffer, &mut app.input_cursor, key, |c| {
                     c.is_ascii_digit()
@@ -499,10 +1050,71 @@ pub fn handle_modal_input(app: &mut App, key: KeyEvent) {
                     true
                 });
         }
+        ActiveModal::BulkResendDelayInput { .. } => {
+            let _ =
+                handle_single_line_input(&mut app.input_buffer, &mut app.input_cursor, key, |_| {
+                    true
+                });
+        }
+        ActiveModal::ConnectionGroupInput { .. } | ActiveModal::ConnectionRenameInput { .. } => {
+            let _ =
+                handle_single_line_input(&mut app.input_buffer, &mut app.input_cursor, key, |_| {
+                    true
+                });
+        }
+        ActiveModal::MessageSearchInput
+        | ActiveModal::MessageFilterInput { .. }
+        | ActiveModal::FilterPresetNameInput { .. } => {
+            let _ =
+                handle_single_line_input(&mut app.input_buffer, &mut app.input_cursor, key, |_| {
+                    true
+                });
+        }
+        ActiveModal::ForwardToInput { .. } => {
+            app.form_error = None;
+            let _ =
+                handle_single_line_input(&mut app.input_buffer, &mut app.input_cursor, key, |c| {
+                    !c.is_whitespace()
+                });
+        }
+        ActiveModal::Settings { editing: true } => {
+            let _ =
+                handle_single_line_input(&mut app.input_buffer, &mut app.input_cursor, key, |c| {
+                    c.is_ascii_graphic()
+                });
+        }
         _ => {}
     }
 }
 
+/// Clamp a requested peek count to `peek_max`, remember it for the selected
+/// entity kind, and hand it to the peek dispatcher.
+fn submit_peek_count(app: &mut App, requested: i64) {
+    let max = app.config.settings.peek_max.max(1);
+    let count = if requested > i64::from(max) {
+        app.peek_clamp_note = Some(format!(
+            "capped at {} (requested {}); raise peek_max in config.toml to peek more",
+            max, requested
+        ));
+        max
+    } else {
+        app.peek_clamp_note = None;
+        requested as i32
+    };
+
+    if let Some(kind) = app.peek_kind_key() {
+        app.config
+            .settings
+            .last_peek_counts
+            .insert(kind.to_string(), count);
+        app.save_config();
+    }
+
+    app.pending_peek_count = Some(count);
+    app.modal = ActiveModal::None;
+    app.set_status("Peeking messages...");
+}
+
 pub fn find_parent_topic(app: &App) -> Option<String> {
     if app.flat_nodes.is_empty() {
         return None;
@@ -527,7 +1139,10 @@ pub fn find_parent_topic(app: &App) -> Option<String> {
 pub fn handle_detail_edit_input(app: &mut App, key: KeyEvent) {
     match key.code {
         KeyCode::Esc => {
-            app.detail_editing = false;
+            if app.confirm_form_close() {
+                app.detail_editing = false;
+                note_abandoned_submit(app);
+            }
         }
         _ => {
             handle_field_edit(app, key);
@@ -538,16 +1153,15 @@ pub fn handle_detail_edit_input(app: &mut App, key: KeyEvent) {
 fn handle_form_input(app: &mut App, key: KeyEvent) {
     match key.code {
         KeyCode::Esc => {
+            if !app.confirm_form_close() {
+                return;
+            }
             if app.modal == ActiveModal::CopyEditMessage {
-                app.copy_source_message = None;
-                app.copy_source_entity = None;
-                app.copy_dest_entities.clear();
-                app.copy_entity_selected = 0;
-                app.copy_dest_connection_name = None;
-                app.copy_dest_connection_config = None;
-                app.copy_destination_entity = None;
+                app.clear_copy_state();
             }
+            app.form_error = None;
             app.modal = ActiveModal::None;
+            note_abandoned_submit(app);
         }
         _ => {
             handle_field_edit(app, key);
@@ -555,7 +1169,16 @@ fn handle_form_input(app: &mut App, key: KeyEvent) {
     }
 }
 
+/// Closing a form does not recall a request it already sent; say so.
+fn note_abandoned_submit(app: &mut App) {
+    if std::mem::take(&mut app.form_submitting) {
+        app.set_status("Form closed — the request was already sent and may still complete");
+    }
+}
+
 fn handle_field_edit(app: &mut App, key: KeyEvent) {
+    app.form_error = None;
+    app.form_discard_armed = false;
     let is_body = app.input_field_index == 0
         && app
             .input_fields
@@ -564,30 +1187,10 @@ fn handle_field_edit(app: &mut App, key: KeyEvent) {
             .unwrap_or(false);
 
     match key.code {
-        KeyCode::Tab => {
-            if app.input_field_index + 1 < app.input_fields.len() {
-                app.input_field_index += 1;
-                app.form_cursor = app.input_fields[app.input_field_index].1.len();
-            }
-        }
-        KeyCode::BackTab => {
-            if app.input_field_index > 0 {
-                app.input_field_index -= 1;
-                app.form_cursor = app.input_fields[app.input_field_index].1.len();
-            }
-        }
-        KeyCode::Down if !is_body => {
-            if app.input_field_index + 1 < app.input_fields.len() {
-                app.input_field_index += 1;
-                app.form_cursor = app.input_fields[app.input_field_index].1.len();
-            }
-        }
-        KeyCode::Up if !is_body => {
-            if app.input_field_index > 0 {
-                app.input_field_index -= 1;
-                app.form_cursor = app.input_fields[app.input_field_index].1.len();
-            }
-        }
+        KeyCode::Tab => app.step_form_field(true),
+        KeyCode::BackTab => app.step_form_field(false),
+        KeyCode::Down if !is_body => app.step_form_field(true),
+        KeyCode::Up if !is_body => app.step_form_field(false),
         KeyCode::Up if is_body => {
             if let Some((_, ref val)) = app.input_fields.get(app.input_field_index) {
                 let (line_start, col) = cursor_line_col(val, app.form_cursor);
@@ -595,8 +1198,8 @@ fn handle_field_edit(app: &mut App, key: KeyEvent) {
                     let prev_line_end = line_start - 1;
                     let prev_text = &val[..prev_line_end];
                     let prev_line_start = prev_text.rfind('\n').map(|i| i + 1).unwrap_or(0);
-                    let prev_line_len = prev_line_end - prev_line_start;
-                    app.form_cursor = prev_line_start + col.min(prev_line_len);
+                    let prev_line = &val[prev_line_start..prev_line_end];
+                    app.form_cursor = prev_line_start + text_width::byte_at_column(prev_line, col);
                 }
             }
         }
@@ -609,8 +1212,8 @@ fn handle_field_edit(app: &mut App, key: KeyEvent) {
                         .find('\n')
                         .map(|i| next_line_start + i)
                         .unwrap_or(val.len());
-                    let next_line_len = next_line_end - next_line_start;
-                    app.form_cursor = next_line_start + col.min(next_line_len);
+                    let next_line = &val[next_line_start..next_line_end];
+                    app.form_cursor = next_line_start + text_width::byte_at_column(next_line, col);
                 }
             }
         }
@@ -645,13 +1248,19 @@ fn handle_field_edit(app: &mut App, key: KeyEvent) {
             }
         }
         KeyCode::F(2) => {
-            app.set_status("Submitting...");
+            submit_form(app);
         }
         KeyCode::Enter
             if key.modifiers.contains(KeyModifiers::CONTROL)
                 || key.modifiers.contains(KeyModifiers::ALT) =>
         {
-            app.set_status("Submitting...");
+            submit_form(app);
+        }
+        KeyCode::Char('f') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            format_form_body(app);
+        }
+        KeyCode::F(3) if app.modal == ActiveModal::SendMessage => {
+            app.forget_send_defaults();
         }
         KeyCode::Left => {
             if app.form_cursor > 0 {
@@ -712,8 +1321,217 @@ fn handle_field_edit(app: &mut App, key: KeyEvent) {
     }
 }
 
+/// Value of the form field with the given label, if present.
+fn form_field<'a>(app: &'a App, label: &str) -> Option<&'a str> {
+    app.input_fields
+        .iter()
+        .find(|(l, _)| l == label)
+        .map(|(_, v)| v.as_str())
+}
+
+/// Point the body editor at a validation error and show it in the hint line.
+fn show_body_error(app: &mut App, err: body_format::BodyError) {
+    app.input_field_index = 0;
+    app.form_cursor = err.offset;
+    app.form_error = Some(err.message);
+}
+
+/// Submit the active form, first checking JSON/XML bodies for well-formedness
+/// (unless disabled in settings) and, if required, that there is a body.
+/// Invalid bodies keep the form open.
+fn submit_form(app: &mut App) {
+    if app.form_submitting {
+        app.set_status("Still sending — wait for the previous submit to finish");
+        return;
+    }
+    if app.config.settings.require_non_empty_body
+        && form_field(app, "Body").is_some_and(|body| body.is_empty())
+    {
+        show_body_error(
+            app,
+            body_format::BodyError {
+                message: "Message body cannot be empty".to_string(),
+                offset: 0,
+            },
+        );
+        return;
+    }
+    if app.config.settings.validate_body {
+        if let (Some(body), Some(content_type)) = (
+            form_field(app, "Body"),
+            form_field(app, crate::app::CONTENT_TYPE_FIELD),
+        ) {
+            if let Err(err) = body_format::validate_body(content_type, body) {
+                show_body_error(app, err);
+                return;
+            }
+        }
+    }
+    if let Some(Err(e)) = app.form_delay() {
+        if let Some(idx) = app
+            .input_fields
+            .iter()
+            .position(|(l, _)| l == crate::app::DELAY_FIELD)
+        {
+            app.input_field_index = idx;
+            app.form_cursor = app.input_fields[idx].1.len();
+        }
+        app.form_error = Some(e);
+        return;
+    }
+    if app.modal == ActiveModal::SendMessage {
+        if let Some(path) = app.selected_effective_send_entity() {
+            if let Some(warning) = app.quota_warning(&path) {
+                if app.quota_acknowledged.insert(path) {
+                    app.form_error = Some(format!("{}. Submit again to send anyway", warning));
+                    return;
+                }
+            }
+        }
+    }
+    app.form_error = form_forward_chain_error(app);
+    if app.form_error.is_none() {
+        app.set_status("Submitting...");
+    }
+}
+
+/// Loop or missing-entity error for the forwarding targets of a create form.
+fn form_forward_chain_error(app: &mut App) -> Option<String> {
+    let source = match app.modal {
+        ActiveModal::CreateQueue => form_field(app, "Queue Name")?.trim().to_string(),
+        ActiveModal::CreateSubscription => format!(
+            "{}/Subscriptions/{}",
+            form_field(app, "Topic")?.trim(),
+            form_field(app, "Subscription Name")?.trim()
+        ),
+        _ => return None,
+    };
+    let targets: Vec<String> = ["Forward To", "Forward DLQ To"]
+        .into_iter()
+        .filter_map(|label| form_field(app, label).map(str::to_string))
+        .collect();
+    targets
+        .iter()
+        .find_map(|target| app.forward_chain_error(&source, target))
+}
+
+/// Ctrl+F: pretty-print a JSON body in place.
+fn format_form_body(app: &mut App) {
+    let Some(body) = form_field(app, "Body") else {
+        return;
+    };
+    match body_format::format_json(body) {
+        Ok(formatted) => {
+            if let Some((_, ref mut val)) = app.input_fields.first_mut() {
+                *val = formatted;
+            }
+            app.input_field_index = 0;
+            app.form_cursor = 0;
+        }
+        Err(err) => show_body_error(app, err),
+    }
+}
+
+/// Byte offset of the cursor's line start, and the cursor's display column in it.
 fn cursor_line_col(text: &str, cursor: usize) -> (usize, usize) {
     let before = &text[..cursor.min(text.len())];
     let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
-    (line_start, cursor - line_start)
+    (line_start, text_width::width(&before[line_start..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn type_keys(input: &mut String, cursor: &mut usize, keys: &[KeyCode], digits_only: bool) {
+        for code in keys {
+            let key = KeyEvent::new(*code, KeyModifiers::NONE);
+            handle_single_line_input(input, cursor, key, |c| !digits_only || c.is_ascii_digit());
+        }
+    }
+
+    fn chars(s: &str) -> Vec<KeyCode> {
+        s.chars().map(KeyCode::Char).collect()
+    }
+
+    #[test]
+    fn connection_input_edits_multibyte_text_by_character() {
+        let (mut input, mut cursor) = (String::new(), 0);
+        type_keys(&mut input, &mut cursor, &chars("ns-日本🚀"), false);
+        assert_eq!(cursor, 6);
+
+        type_keys(
+            &mut input,
+            &mut cursor,
+            &[KeyCode::Left, KeyCode::Backspace],
+            false,
+        );
+        assert_eq!(input, "ns-日🚀");
+        assert_eq!(cursor, 4);
+        assert_eq!(text_width::cursor_column(&input, cursor), 5);
+
+        // A combining accent is its own char but adds no column
+        type_keys(&mut input, &mut cursor, &[KeyCode::End], false);
+        type_keys(&mut input, &mut cursor, &chars("e\u{301}"), false);
+        assert_eq!(input, "ns-日🚀e\u{301}");
+        assert_eq!(text_width::cursor_column(&input, cursor), 8);
+    }
+
+    #[test]
+    fn peek_count_input_rejects_fullwidth_digits() {
+        let (mut input, mut cursor) = (String::new(), 0);
+        type_keys(&mut input, &mut cursor, &chars("1２0"), true);
+        assert_eq!(input, "10");
+        assert_eq!(cursor, 2);
+    }
+
+    #[test]
+    fn form_body_vertical_moves_keep_display_column() {
+        let body = "日本語\nabcdef";
+        // End of the first line: 3 wide chars = 6 columns
+        let (start, col) = cursor_line_col(body, "日本語".len());
+        assert_eq!((start, col), (0, 6));
+        assert_eq!(text_width::byte_at_column("abcdef", col), 6);
+
+        // Column 3 of "abcdef" falls inside 本, so moving up lands on its start
+        let (_, col) = cursor_line_col(body, "日本語\nabc".len());
+        assert_eq!(col, 3);
+        assert_eq!(text_width::byte_at_column("日本語", col), "日".len());
+    }
+
+    #[test]
+    fn leaving_the_connect_flow_forgets_its_group_and_reauth_target() {
+        let esc = KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE);
+        let flows = [
+            ActiveModal::ConnectionModeSelect,
+            ActiveModal::ConnectionInput,
+            ActiveModal::AzureAdNamespaceInput,
+            ActiveModal::ConnectionList,
+            ActiveModal::NamespaceDiscovery {
+                state: DiscoveryState::Error("no subscriptions".into()),
+            },
+        ];
+        for modal in flows {
+            let mut app = App::with_config(crate::config::AppConfig::default(), None);
+            app.pending_connection_group = Some("prod".into());
+            app.reauth_connection = Some("orders".into());
+            app.modal = modal.clone();
+
+            handle_modal_input(&mut app, esc);
+            assert_eq!(app.modal, ActiveModal::None, "{:?}", modal);
+            assert_eq!(app.pending_connection_group, None, "{:?}", modal);
+            assert_eq!(app.reauth_connection, None, "{:?}", modal);
+        }
+
+        // Moving between the flow's dialogs keeps them
+        let mut app = App::with_config(crate::config::AppConfig::default(), None);
+        app.pending_connection_group = Some("prod".into());
+        app.modal = ActiveModal::ConnectionModeSelect;
+        handle_modal_input(
+            &mut app,
+            KeyEvent::new(KeyCode::Char('1'), KeyModifiers::NONE),
+        );
+        assert_eq!(app.modal, ActiveModal::ConnectionInput);
+        assert_eq!(app.pending_connection_group.as_deref(), Some("prod"));
+    }
 }
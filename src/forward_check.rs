@@ -0,0 +1,204 @@
+use std::collections::HashSet;
+
+use crate::client::entity_path;
+use crate::forward_graph::ForwardEdge;
+
+/// Hops followed from a new forwarding target before the walk gives up.
+pub const MAX_CHAIN_DEPTH: usize = 16;
+
+/// Why a forwarding target was refused. Each variant carries the chain from
+/// the entity being saved to where the problem shows up.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChainProblem {
+    /// The chain comes back to an entity already on it.
+    Cycle(Vec<String>),
+    /// The chain reaches a queue or topic that does not exist.
+    Missing(Vec<String>),
+}
+
+impl ChainProblem {
+    /// `Forwarding loop: orders → audit → orders`
+    pub fn message(&self) -> String {
+        match self {
+            ChainProblem::Cycle(chain) => format!("Forwarding loop: {}", chain.join(" → ")),
+            ChainProblem::Missing(chain) => format!(
+                "'{}' does not exist: {}",
+                chain.last().map(String::as_str).unwrap_or_default(),
+                chain.join(" → ")
+            ),
+        }
+    }
+}
+
+/// Follow where messages sent to `target` end up if `source` forwards there:
+/// along `edges` (messages and dead-letters alike) and from a topic into each
+/// of its subscriptions. `entities` are the known queue, topic and
+/// subscription paths; paths compare case-insensitively.
+pub fn check_chain(
+    source: &str,
+    target: &str,
+    entities: &[String],
+    edges: &[ForwardEdge],
+) -> Option<ChainProblem> {
+    let mut chain = vec![source.to_string()];
+    let mut explored = HashSet::new();
+    walk(target, entities, edges, &mut chain, &mut explored)
+}
+
+fn walk(
+    node: &str,
+    entities: &[String],
+    edges: &[ForwardEdge],
+    chain: &mut Vec<String>,
+    explored: &mut HashSet<String>,
+) -> Option<ChainProblem> {
+    if let Some(seen) = chain.iter().find(|c| c.eq_ignore_ascii_case(node)) {
+        let seen = seen.clone();
+        chain.push(seen);
+        return Some(ChainProblem::Cycle(chain.clone()));
+    }
+    let Some(entity) = entities.iter().find(|e| e.eq_ignore_ascii_case(node)) else {
+        chain.push(node.to_string());
+        return Some(ChainProblem::Missing(chain.clone()));
+    };
+    if chain.len() > MAX_CHAIN_DEPTH || !explored.insert(entity.to_lowercase()) {
+        return None;
+    }
+
+    chain.push(entity.clone());
+    let forwards = edges
+        .iter()
+        .filter(|e| e.from.eq_ignore_ascii_case(entity))
+        .map(|e| e.to.as_str());
+    let subscriptions = entities.iter().map(String::as_str).filter(|e| {
+        entity_path::split_subscription_path(e)
+            .is_some_and(|(topic, _)| topic.eq_ignore_ascii_case(entity))
+    });
+    for next in forwards.chain(subscriptions) {
+        if let Some(problem) = walk(next, entities, edges, chain, explored) {
+            return Some(problem);
+        }
+    }
+    chain.pop();
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::forward_graph::ForwardKind;
+
+    fn entities(paths: &[&str]) -> Vec<String> {
+        paths.iter().map(|p| p.to_string()).collect()
+    }
+
+    fn edge(from: &str, to: &str, kind: ForwardKind) -> ForwardEdge {
+        ForwardEdge {
+            from: from.to_string(),
+            to: to.to_string(),
+            kind,
+        }
+    }
+
+    #[test]
+    fn chains_that_end_at_a_real_entity_pass() {
+        let known = entities(&[
+            "orders",
+            "archive",
+            "cold",
+            "events",
+            "events/Subscriptions/audit",
+        ]);
+        let edges = vec![
+            edge("archive", "cold", ForwardKind::Messages),
+            edge(
+                "events/Subscriptions/audit",
+                "cold",
+                ForwardKind::DeadLetters,
+            ),
+        ];
+        assert_eq!(check_chain("orders", "Archive", &known, &edges), None);
+        assert_eq!(check_chain("orders", "events", &known, &edges), None);
+        // A new entity that is not loaded yet can still be the source
+        assert_eq!(check_chain("new-queue", "archive", &known, &edges), None);
+    }
+
+    #[test]
+    fn loops_back_to_the_source_are_listed() {
+        let known = entities(&["orders", "retry", "events", "events/Subscriptions/replay"]);
+        let edges = vec![
+            edge("retry", "orders", ForwardKind::DeadLetters),
+            edge(
+                "events/Subscriptions/replay",
+                "orders",
+                ForwardKind::Messages,
+            ),
+        ];
+        let problem = check_chain("orders", "retry", &known, &edges).unwrap();
+        assert_eq!(
+            problem,
+            ChainProblem::Cycle(entities(&["orders", "retry", "orders"]))
+        );
+        assert_eq!(
+            problem.message(),
+            "Forwarding loop: orders → retry → orders"
+        );
+
+        // Through a topic's subscriptions
+        assert_eq!(
+            check_chain("orders", "events", &known, &edges),
+            Some(ChainProblem::Cycle(entities(&[
+                "orders",
+                "events",
+                "events/Subscriptions/replay",
+                "orders"
+            ])))
+        );
+        // Forwarding to itself
+        assert_eq!(
+            check_chain("orders", "ORDERS", &known, &edges),
+            Some(ChainProblem::Cycle(entities(&["orders", "orders"])))
+        );
+    }
+
+    #[test]
+    fn existing_loops_and_missing_targets_are_caught() {
+        let known = entities(&["orders", "a", "b", "c"]);
+        let edges = vec![
+            edge("a", "b", ForwardKind::Messages),
+            edge("b", "a", ForwardKind::Messages),
+            edge("c", "gone", ForwardKind::DeadLetters),
+        ];
+        assert_eq!(
+            check_chain("orders", "a", &known, &edges),
+            Some(ChainProblem::Cycle(entities(&["orders", "a", "b", "a"])))
+        );
+
+        let missing = check_chain("orders", "c", &known, &edges).unwrap();
+        assert_eq!(
+            missing,
+            ChainProblem::Missing(entities(&["orders", "c", "gone"]))
+        );
+        assert_eq!(
+            missing.message(),
+            "'gone' does not exist: orders → c → gone"
+        );
+        assert_eq!(
+            check_chain("orders", "typo", &known, &edges),
+            Some(ChainProblem::Missing(entities(&["orders", "typo"])))
+        );
+    }
+
+    #[test]
+    fn long_chains_stop_at_the_depth_limit() {
+        let names: Vec<String> = (0..MAX_CHAIN_DEPTH * 2)
+            .map(|i| format!("q{}", i))
+            .collect();
+        let edges: Vec<ForwardEdge> = names
+            .windows(2)
+            .map(|w| edge(&w[0], &w[1], ForwardKind::Messages))
+            .chain([edge(names.last().unwrap(), "source", ForwardKind::Messages)])
+            .collect();
+        assert_eq!(check_chain("source", "q0", &names, &edges), None);
+    }
+}
@@ -0,0 +1,218 @@
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+/// What an edge forwards: all messages (`ForwardTo`) or dead-letters only
+/// (`ForwardDeadLetteredMessagesTo`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ForwardKind {
+    Messages,
+    DeadLetters,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ForwardEdge {
+    pub from: String,
+    pub to: String,
+    pub kind: ForwardKind,
+}
+
+/// One row of the rendered graph: an entity, drawn under the entity that
+/// forwards to it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphLine {
+    /// Tree drawing and arrow in front of the path.
+    pub prefix: String,
+    pub path: String,
+    /// "cycle" when this edge closes a loop, "see above" when the entity's
+    /// own forwarding was already drawn.
+    pub note: Option<&'static str>,
+    /// The entity is part of a forwarding cycle.
+    pub in_cycle: bool,
+}
+
+/// Entities that can reach themselves by following edges.
+pub fn cycle_nodes(edges: &[ForwardEdge]) -> HashSet<String> {
+    let adjacency = adjacency(edges);
+    adjacency
+        .keys()
+        .filter(|start| {
+            let mut seen = HashSet::new();
+            let mut stack: Vec<&str> = adjacency[*start].iter().map(|(to, _)| *to).collect();
+            while let Some(node) = stack.pop() {
+                if node == **start {
+                    return true;
+                }
+                if seen.insert(node) {
+                    if let Some(next) = adjacency.get(node) {
+                        stack.extend(next.iter().map(|(to, _)| *to));
+                    }
+                }
+            }
+            false
+        })
+        .map(|s| s.to_string())
+        .collect()
+}
+
+/// Draw the graph as trees hanging off entities nothing forwards to. Loops
+/// with no such entry point start at their alphabetically first member.
+pub fn layout(edges: &[ForwardEdge]) -> Vec<GraphLine> {
+    let adjacency = adjacency(edges);
+    let targets: HashSet<&str> = edges.iter().map(|e| e.to.as_str()).collect();
+    let cycles = cycle_nodes(edges);
+
+    let mut lines = Vec::new();
+    let mut visited: HashSet<&str> = HashSet::new();
+    let roots = adjacency
+        .keys()
+        .filter(|n| !targets.contains(*n))
+        .chain(adjacency.keys().filter(|n| targets.contains(*n)));
+    for &root in roots {
+        if !visited.insert(root) {
+            continue;
+        }
+        lines.push(GraphLine {
+            prefix: String::new(),
+            path: root.to_string(),
+            note: None,
+            in_cycle: cycles.contains(root),
+        });
+        draw_children(
+            root,
+            "",
+            &mut vec![root],
+            &adjacency,
+            &cycles,
+            &mut visited,
+            &mut lines,
+        );
+    }
+    lines
+}
+
+fn draw_children<'a>(
+    node: &'a str,
+    indent: &str,
+    stack: &mut Vec<&'a str>,
+    adjacency: &BTreeMap<&'a str, Vec<(&'a str, ForwardKind)>>,
+    cycles: &HashSet<String>,
+    visited: &mut HashSet<&'a str>,
+    lines: &mut Vec<GraphLine>,
+) {
+    let Some(children) = adjacency.get(node) else {
+        return;
+    };
+    for (i, &(child, kind)) in children.iter().enumerate() {
+        let last = i + 1 == children.len();
+        let arrow = match kind {
+            ForwardKind::Messages => "─▶ ",
+            ForwardKind::DeadLetters => "─▶ [DLQ] ",
+        };
+        let branch = if last { "└" } else { "├" };
+        let note = if stack.contains(&child) {
+            Some("cycle")
+        } else if visited.contains(child) && adjacency.contains_key(child) {
+            Some("see above")
+        } else {
+            None
+        };
+        lines.push(GraphLine {
+            prefix: format!("{}{}{}", indent, branch, arrow),
+            path: child.to_string(),
+            note,
+            in_cycle: cycles.contains(child),
+        });
+        if note.is_none() && visited.insert(child) {
+            stack.push(child);
+            let child_indent = format!("{}{}", indent, if last { "    " } else { "│   " });
+            draw_children(
+                child,
+                &child_indent,
+                stack,
+                adjacency,
+                cycles,
+                visited,
+                lines,
+            );
+            stack.pop();
+        }
+    }
+}
+
+fn adjacency(edges: &[ForwardEdge]) -> BTreeMap<&str, Vec<(&str, ForwardKind)>> {
+    let mut adjacency: BTreeMap<&str, Vec<(&str, ForwardKind)>> = BTreeMap::new();
+    let unique: BTreeSet<&ForwardEdge> = edges.iter().collect();
+    for edge in unique {
+        adjacency
+            .entry(edge.from.as_str())
+            .or_default()
+            .push((edge.to.as_str(), edge.kind));
+    }
+    adjacency
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edge(from: &str, to: &str, kind: ForwardKind) -> ForwardEdge {
+        ForwardEdge {
+            from: from.to_string(),
+            to: to.to_string(),
+            kind,
+        }
+    }
+
+    #[test]
+    fn chains_are_drawn_from_their_entry_point() {
+        let edges = vec![
+            edge("orders", "archive", ForwardKind::Messages),
+            edge("archive", "cold", ForwardKind::Messages),
+            edge("orders", "poison", ForwardKind::DeadLetters),
+        ];
+        let lines = layout(&edges);
+        let drawn: Vec<String> = lines
+            .iter()
+            .map(|l| format!("{}{}", l.prefix, l.path))
+            .collect();
+        assert_eq!(
+            drawn,
+            vec!["orders", "├─▶ archive", "│   └─▶ cold", "└─▶ [DLQ] poison"]
+        );
+        assert!(lines.iter().all(|l| !l.in_cycle && l.note.is_none()));
+    }
+
+    #[test]
+    fn cycles_are_detected_and_cut() {
+        let edges = vec![
+            edge("a", "b", ForwardKind::Messages),
+            edge("b", "a", ForwardKind::Messages),
+            edge("entry", "a", ForwardKind::Messages),
+            edge("side", "x", ForwardKind::Messages),
+        ];
+        let cycles = cycle_nodes(&edges);
+        assert_eq!(
+            cycles,
+            ["a", "b"]
+                .iter()
+                .map(|s| s.to_string())
+                .collect::<HashSet<_>>()
+        );
+
+        let lines = layout(&edges);
+        let drawn: Vec<(String, Option<&str>, bool)> = lines
+            .iter()
+            .map(|l| (format!("{}{}", l.prefix, l.path), l.note, l.in_cycle))
+            .collect();
+        assert_eq!(
+            drawn,
+            vec![
+                ("entry".to_string(), None, false),
+                ("└─▶ a".to_string(), None, true),
+                ("    └─▶ b".to_string(), None, true),
+                ("        └─▶ a".to_string(), Some("cycle"), true),
+                ("side".to_string(), None, false),
+                ("└─▶ x".to_string(), None, false),
+            ]
+        );
+    }
+}
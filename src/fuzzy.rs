@@ -0,0 +1,77 @@
+/// Case-insensitive subsequence match of `query` against `candidate`.
+///
+/// Returns `None` when some query character cannot be matched in order.
+/// Higher scores are better: consecutive matches and matches at the start of
+/// the name or right after a separator (`/`, `-`, `_`, `.`) earn a bonus,
+/// skipped characters cost a point each.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<i64> {
+    if query.is_empty() {
+        return Some(0);
+    }
+    let mut query_chars = query.chars().flat_map(char::to_lowercase).peekable();
+    let mut score = 0i64;
+    let mut prev: Option<char> = None;
+    let mut prev_matched = false;
+
+    for c in candidate.chars() {
+        let Some(&q) = query_chars.peek() else {
+            break;
+        };
+        let at_boundary = matches!(prev, None | Some('/' | '-' | '_' | '.' | ' '));
+        if c.to_lowercase().eq(std::iter::once(q)) {
+            score += 1;
+            if prev_matched {
+                score += 5;
+            }
+            if at_boundary {
+                score += 8;
+            }
+            query_chars.next();
+            prev_matched = true;
+        } else {
+            score -= 1;
+            prev_matched = false;
+        }
+        prev = Some(c);
+    }
+
+    query_chars.peek().is_none().then_some(score)
+}
+
+/// Items of `items` matching `query`, best match first. Ties keep their
+/// original order, so an empty query returns everything unchanged.
+pub fn fuzzy_filter<'a, T>(query: &str, items: &'a [T], key: impl Fn(&T) -> &str) -> Vec<&'a T> {
+    let mut scored: Vec<(i64, &T)> = items
+        .iter()
+        .filter_map(|item| fuzzy_score(query, key(item)).map(|s| (s, item)))
+        .collect();
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    scored.into_iter().map(|(_, item)| item).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_subsequence_case_insensitively() {
+        assert!(fuzzy_score("ordq", "Orders-Queue").is_some());
+        assert!(fuzzy_score("qo", "orders-queue").is_none());
+        assert_eq!(fuzzy_score("", "anything"), Some(0));
+    }
+
+    #[test]
+    fn prefers_prefix_and_word_starts() {
+        let names = ["audit-orders", "orders", "payment-orders-dlq", "o-r-d"];
+        let ranked = fuzzy_filter("orders", &names, |n| *n);
+        assert_eq!(*ranked[0], "orders");
+        assert!(!ranked.contains(&&"o-r-d"));
+    }
+
+    #[test]
+    fn empty_query_keeps_order() {
+        let names = ["b", "a", "c"];
+        let all: Vec<&&str> = fuzzy_filter("", &names, |n| *n);
+        assert_eq!(all, vec![&"b", &"a", &"c"]);
+    }
+}
@@ -1,13 +1,27 @@
 mod app;
+mod body_format;
 mod bulk_ops;
-mod client;
+mod clipboard;
 mod config;
+mod connection_test;
+mod delay;
+mod error_report;
 mod event;
+mod event_log;
 mod event_modal;
+mod forward_check;
+mod forward_graph;
+mod fuzzy;
+mod jwt;
+mod message_diff;
+mod message_filter;
+mod peek_cache;
+mod session;
+mod text_width;
 mod ui;
 
 use std::future::Future;
-use std::io;
+use std::io::{self, Write};
 
 use crossterm::{
     event::{DisableMouseCapture, EnableMouseCapture},
@@ -15,30 +29,60 @@ use crossterm::{
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::prelude::*;
+use service_bus_rest_client as client;
 
-use app::{ActiveModal, App, BgEvent, DetailView, DiscoveryState, FocusPanel, MessageTab};
-use bulk_ops::{resend_dlq_loop, resolve_purge_paths, resolve_resend_pairs, send_path_owned};
+use app::{
+    status_options, ActiveModal, App, BgEvent, BgSender, DetailView, DiscoveryState, DlqStats,
+    DraftKind, FocusPanel, MessageTab, SearchHit, SearchResults, SessionBrowse, COUNT_BATCH_SIZE,
+    MAX_BROWSED_SESSIONS,
+};
+use bulk_ops::{
+    dry_run_scan, preflight_topic_purge, purge_summary, resend_dlq_loop, resolve_purge_paths,
+    resolve_resend_pairs, scheduled_note, DryRunOp, DryRunReport, ResendFailure, ResendReport,
+    DRY_RUN_SAMPLE_IDS,
+};
 use client::entity_path;
-use client::models::EntityType;
+use client::models::{EntityType, MessageRef};
+use error_report::ErrorReport;
+use event_log::LogEvent;
+use ui::theme::Theme;
 
-fn send_failed(tx: &tokio::sync::mpsc::UnboundedSender<BgEvent>, message: impl Into<String>) {
+fn send_failed(tx: &BgSender, message: impl Into<String>) {
     let _ = tx.send(BgEvent::Failed(message.into()));
 }
 
-fn send_failed_with<E: std::fmt::Display>(
-    tx: &tokio::sync::mpsc::UnboundedSender<BgEvent>,
-    context: &str,
-    err: E,
-) {
-    send_failed(tx, format!("{}: {}", context, err));
+fn send_failed_with(tx: &BgSender, context: &str, err: client::ServiceBusError) {
+    let report = ErrorReport::from_service_error(context, &err);
+    let _ = tx.send(BgEvent::OperationFailed(report));
+}
+
+/// Send `EntityGone` when `err` is a 404 for `path`; returns whether it was.
+fn report_if_gone(tx: &BgSender, path: &str, err: &client::ServiceBusError) -> bool {
+    let gone = err.kind() == client::ErrorKind::NotFound;
+    if gone {
+        let _ = tx.send(BgEvent::EntityGone {
+            path: path.to_string(),
+        });
+    }
+    gone
 }
 
-fn spawn_entity_create<T, Fut>(
-    tx: tokio::sync::mpsc::UnboundedSender<BgEvent>,
-    kind: &'static str,
-    name: String,
-    op: Fut,
-) where
+/// Run a single-message operation and report `MessageOpFinished` when it ends,
+/// so its in-flight marker clears even if the operation panics.
+fn spawn_message_op<Fut>(tx: BgSender, target: MessageRef, op: Fut)
+where
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    tokio::spawn(async move {
+        if let Err(e) = tokio::spawn(op).await {
+            send_failed(&tx, format!("Message operation aborted: {}", e));
+        }
+        let _ = tx.send(BgEvent::MessageOpFinished { target });
+    });
+}
+
+fn spawn_entity_create<T, Fut>(tx: BgSender, kind: &'static str, name: String, op: Fut)
+where
     Fut: Future<Output = client::Result<T>> + Send + 'static,
     T: Send + 'static,
 {
@@ -56,8 +100,188 @@ fn spawn_entity_create<T, Fut>(
     });
 }
 
+fn spawn_entity_update<T, Fut>(tx: BgSender, kind: &'static str, path: String, op: Fut)
+where
+    Fut: Future<Output = client::Result<T>> + Send + 'static,
+    T: Send + 'static,
+{
+    tokio::spawn(async move {
+        match op.await {
+            Ok(_) => {
+                let _ = tx.send(BgEvent::EntityUpdated {
+                    status: format!("{} '{}' updated", kind, path),
+                });
+            }
+            Err(e) => {
+                send_failed_with(&tx, "Update failed", e);
+            }
+        }
+    });
+}
+
+/// Spawn a partial update of the queue, topic or subscription at `path`:
+/// only the `(element, text)` patches are changed, empty text clears one.
+fn spawn_entity_patch(
+    tx: BgSender,
+    mgmt: client::ManagementClient,
+    entity_type: &EntityType,
+    path: String,
+    patches: Vec<(String, String)>,
+) {
+    let (kind, target) = (entity_type.clone(), path.clone());
+    let label = match entity_type {
+        EntityType::Queue => "Queue",
+        EntityType::Topic => "Topic",
+        EntityType::Subscription => "Subscription",
+        _ => return,
+    };
+    spawn_entity_update(tx, label, path, async move {
+        let patches: Vec<(&str, &str)> = patches
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .collect();
+        match kind {
+            EntityType::Queue => mgmt.patch_queue(&target, &patches).await.map(|_| ()),
+            EntityType::Topic => mgmt.patch_topic(&target, &patches).await.map(|_| ()),
+            _ => {
+                let (topic, sub) = entity_path::split_subscription_path(&target)
+                    .ok_or_else(|| client::ServiceBusError::NotFound(target.clone()))?;
+                mgmt.patch_subscription(topic, sub, &patches)
+                    .await
+                    .map(|_| ())
+            }
+        }
+    });
+}
+
+/// Terminal bell (BEL), written straight to the terminal between frames.
+fn ring_bell() {
+    use std::io::Write;
+    let mut out = io::stdout();
+    let _ = out.write_all(b"\x07");
+    let _ = out.flush();
+}
+
+/// Announce entities that just crossed their DLQ threshold.
+fn report_dlq_alerts(app: &mut App, newly_alerting: &[String]) {
+    if newly_alerting.is_empty() {
+        return;
+    }
+    if app.config.settings.alert_bell {
+        ring_bell();
+    }
+    app.set_error(format!(
+        "DLQ threshold crossed: {}",
+        newly_alerting.join(", ")
+    ));
+}
+
+/// Command-line flags. Unknown arguments are ignored.
+#[derive(Debug, Default)]
+struct CliArgs {
+    no_color: bool,
+    config: Option<std::path::PathBuf>,
+    /// `--log-format json`: structured events on stderr.
+    json_log: bool,
+    /// `--no-restore`: start disconnected even if `restore_session` is on.
+    no_restore: bool,
+    /// `--demo`: explore an in-memory namespace instead of Azure.
+    demo: bool,
+    /// `--version` / `-V`: print the version and exit.
+    version: bool,
+    /// `--help` / `-h`: print the flags and key bindings and exit.
+    help: bool,
+}
+
+const USAGE: &str = "\
+Usage: service-bus-explorer-tui [OPTIONS]
+
+Options:
+  --config <FILE>      Use FILE instead of the default config.toml
+  --no-color           Render without colors
+  --no-restore         Start disconnected instead of restoring the last session
+  --demo               Explore a sample in-memory namespace; nothing is saved
+  --log-format json    Write structured events to stderr
+  -V, --version        Print the version and exit
+  -h, --help           Print this help and exit
+";
+
+/// `service-bus-explorer-tui 0.0.13 (1a2b3c4 2026-10-16)`
+fn version_line() -> String {
+    format!(
+        "service-bus-explorer-tui {} ({} {})",
+        env!("CARGO_PKG_VERSION"),
+        env!("CARGO_BUILD_GIT_HASH"),
+        env!("CARGO_BUILD_DATE")
+    )
+}
+
+fn parse_args(args: impl IntoIterator<Item = String>) -> Result<CliArgs, String> {
+    let mut parsed = CliArgs::default();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == "--version" || arg == "-V" {
+            parsed.version = true;
+        } else if arg == "--help" || arg == "-h" {
+            parsed.help = true;
+        } else if arg == "--no-color" {
+            parsed.no_color = true;
+        } else if arg == "--no-restore" {
+            parsed.no_restore = true;
+        } else if arg == "--demo" {
+            parsed.demo = true;
+        } else if arg == "--config" {
+            let path = args.next().ok_or("--config needs a file path")?;
+            parsed.config = Some(path.into());
+        } else if let Some(path) = arg.strip_prefix("--config=") {
+            parsed.config = Some(path.into());
+        } else if arg == "--log-format" || arg.starts_with("--log-format=") {
+            let format = match arg.strip_prefix("--log-format=") {
+                Some(format) => format.to_string(),
+                None => args.next().ok_or("--log-format needs a format (json)")?,
+            };
+            if format != "json" {
+                return Err(format!(
+                    "Unsupported log format '{}' (expected json)",
+                    format
+                ));
+            }
+            parsed.json_log = true;
+        }
+    }
+    Ok(parsed)
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(2);
+        }
+    };
+    // Written without println! so `--help | head` doesn't panic on a closed pipe
+    if args.version {
+        let _ = writeln!(io::stdout(), "{}", version_line());
+        return Ok(());
+    }
+    if args.help {
+        let _ = write!(
+            io::stdout(),
+            "{}\n\n{}\nKey bindings:\n{}",
+            version_line(),
+            USAGE,
+            ui::help::help_text()
+        );
+        return Ok(());
+    }
+    if let Some(path) = args.config {
+        config::AppConfig::set_config_path(path);
+    }
+    let no_color = args.no_color;
+    let json_log = event_log::json_requested(args.json_log);
+
     // Setup terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -65,7 +289,14 @@ async fn main() -> anyhow::Result<()> {
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    let result = run_app(&mut terminal).await;
+    let start = if args.demo {
+        Start::Demo
+    } else if args.no_restore {
+        Start::Fresh
+    } else {
+        Start::Restore
+    };
+    let result = run_app(&mut terminal, no_color, json_log, start).await;
 
     // Restore terminal
     disable_raw_mode()?;
@@ -83,15 +314,57 @@ async fn main() -> anyhow::Result<()> {
     Ok(())
 }
 
-async fn run_app(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> anyhow::Result<()> {
+/// What `run_app` connects to before the first frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Start {
+    /// The last session, when `restore_session` is on.
+    Restore,
+    /// Nothing (`--no-restore`).
+    Fresh,
+    /// The in-memory demo namespace (`--demo`).
+    Demo,
+}
+
+async fn run_app(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    no_color: bool,
+    json_log: bool,
+    start: Start,
+) -> anyhow::Result<()> {
     let mut app = App::new();
+    app.no_color_flag = no_color;
+    let mut log_writer = None;
+    if json_log {
+        let (tx, task) = event_log::spawn_writer();
+        app.event_log = Some(tx);
+        log_writer = Some(task);
+    }
+    if start == Start::Demo {
+        app.start_demo();
+    }
+    if let Some(ref err) = app.config_load_error {
+        app.set_error(err.clone());
+    }
+    app.theme = Theme::detect(no_color, app.config.settings.no_color);
+    if start == Start::Restore && app.config.settings.restore_session {
+        if let Some(session) = session::SessionState::load() {
+            app.restore_session(session);
+        }
+    }
     let mut needs_refresh = false;
+    // Auto-refresh (`auto_refresh_secs`) reloads counts without touching the status bar
+    let mut last_refresh = std::time::Instant::now();
+    let mut quiet_refresh = false;
+    let mut last_health_check = std::time::Instant::now();
     let mut last_selected: usize = usize::MAX;
+    // Config write in progress; at most one at a time, so saves land in order
+    let mut config_save: Option<tokio::task::JoinHandle<anyhow::Result<()>>> = None;
 
     loop {
         // Draw
         terminal.draw(|frame| {
             ui::layout::render(frame, &mut app);
+            app.theme.apply(frame.buffer_mut());
         })?;
 
         // Handle events
@@ -99,33 +372,70 @@ async fn run_app(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> anyho
             break;
         }
 
-        if !app.running {
+        if !app.running || app.quit_due() {
             break;
         }
 
         // ──────── Poll background task results ────────
-        while let Ok(event) = app.bg_rx.try_recv() {
+        while let Ok((generation, event)) = app.bg_rx.try_recv() {
+            // Results from tasks started before a disconnect/switch are stale
+            if generation != app.bg_tx.generation() {
+                continue;
+            }
+            if app.event_log.is_some() {
+                if let Some(entry) = LogEvent::from_bg_event(&event, app.bg_job.as_ref()) {
+                    app.log_event(entry);
+                }
+            }
             match event {
                 BgEvent::Progress(msg) => {
                     app.set_status(msg);
                 }
-                BgEvent::PurgeComplete { count } => {
-                    app.set_status(format!("Deleted {} messages", count));
+                BgEvent::JobProgress { processed, message } => {
+                    app.record_job_progress(processed);
+                    app.set_status(message);
+                }
+                BgEvent::PurgeComplete { results } => {
+                    match purge_summary(&results) {
+                        (status, Some(failure)) => {
+                            app.set_error(format!("{}; {}", status, failure))
+                        }
+                        (status, None) => app.set_status(status),
+                    }
                     app.messages.clear();
                     app.dlq_messages.clear();
+                    app.transferred_messages.clear();
                     app.message_selected = 0;
                     app.bg_running = false;
                     needs_refresh = true;
                 }
-                BgEvent::ResendComplete { resent, errors } => {
-                    if errors > 0 {
-                        app.set_status(format!("Resent {} messages ({} errors)", resent, errors));
+                BgEvent::ResendComplete {
+                    resent,
+                    errors,
+                    cancelled,
+                    scheduled,
+                    failures,
+                } => {
+                    let mut status = if cancelled {
+                        format!(
+                            "Cancelled after resending {} messages ({} errors)",
+                            resent, errors
+                        )
+                    } else if errors > 0 {
+                        format!("Resent {} messages ({} errors)", resent, errors)
                     } else {
-                        app.set_status(format!("Resent {} messages", resent));
+                        format!("Resent {} messages", resent)
+                    };
+                    if let Some(note) = scheduled {
+                        status = format!("{}, {}", status, note);
                     }
+                    app.set_status(status);
                     app.dlq_messages.clear();
                     app.message_selected = 0;
                     app.bg_running = false;
+                    if let Some(report) = failures {
+                        app.open_resend_report(report);
+                    }
                     needs_refresh = true;
                 }
                 BgEvent::BulkDeleteComplete { deleted, was_dlq } => {
@@ -139,41 +449,38 @@ async fn run_app(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> anyho
                     app.bg_running = false;
                     needs_refresh = true;
                 }
+                BgEvent::DryRunComplete(report) => {
+                    app.bg_running = false;
+                    app.open_dry_run_summary(*report);
+                }
                 BgEvent::Cancelled { message } => {
                     app.set_status(message);
                     app.bg_running = false;
                     needs_refresh = true;
                 }
                 BgEvent::Failed(msg) => {
-                    app.set_error(msg);
+                    app.report_error(ErrorReport::from_message(&msg));
                     app.bg_running = false;
                     app.loading = false;
+                    app.form_submitting = false;
                 }
-                BgEvent::NamespacesDiscovered { result } => {
-                    app.bg_running = false;
-                    app.discovered_namespaces = result.namespaces;
-                    app.discovery_warnings = result.errors;
-
-                    if app.discovered_namespaces.is_empty() {
-                        let error_msg = if !app.discovery_warnings.is_empty() {
-                            app.discovery_warnings.join("; ")
-                        } else {
-                            "No Service Bus namespaces found in your subscriptions".to_string()
-                        };
-
-                        app.modal = ActiveModal::NamespaceDiscovery {
-                            state: DiscoveryState::Error(error_msg.clone()),
-                        };
-                        app.set_status(format!("Discovery complete: {}", error_msg));
-                    } else {
-                        app.modal = ActiveModal::NamespaceDiscovery {
-                            state: DiscoveryState::List,
-                        };
-                        app.set_status(format!(
-                            "Found {} namespace(s). Select one or press 'm' for manual entry.",
-                            app.discovered_namespaces.len()
-                        ));
+                BgEvent::OperationFailed(report) => {
+                    let unauthorized = report.unauthorized;
+                    app.report_error(report);
+                    if unauthorized {
+                        app.open_reauthenticate();
                     }
+                    app.bg_running = false;
+                    app.loading = false;
+                    app.form_submitting = false;
+                }
+                BgEvent::NamespacesDiscovered {
+                    identity,
+                    result,
+                    cached_minutes,
+                } => {
+                    app.bg_running = false;
+                    app.finish_namespace_discovery(identity, result, cached_minutes);
                 }
                 BgEvent::DiscoveryFailed(err) => {
                     app.bg_running = false;
@@ -199,14 +506,13 @@ async fn run_app(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> anyho
                     let prev_selected_id =
                         app.flat_nodes.get(app.tree_selected).map(|n| n.id.clone());
 
-                    if let Some(ref old_tree) = app.tree {
-                        let mut expanded_ids = std::collections::HashSet::new();
-                        old_tree.collect_expanded_ids(&mut expanded_ids);
-                        tree.apply_expanded_ids(&expanded_ids);
-                    }
+                    app.carry_over_subscriptions(&mut tree);
+                    app.restore_expand_state(&mut tree);
 
                     app.flat_nodes = tree.flatten();
                     app.tree = Some(tree);
+                    app.cache_tree_entities();
+                    app.stale_entities.clear();
 
                     // Restore selection by node ID, fall back to clamping
                     if let Some(ref prev_id) = prev_selected_id {
@@ -220,10 +526,62 @@ async fn run_app(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> anyho
                     }
 
                     app.loading = false;
-                    app.set_status(format!("Loaded {} queues, {} topics", q_count, t_count));
+                    // Reload the selected entity's detail so external changes show up
+                    last_selected = usize::MAX;
+                    if !std::mem::take(&mut quiet_refresh) {
+                        app.set_status(format!("Loaded {} queues, {} topics", q_count, t_count));
+                    }
+                    app.finish_session_restore();
+
+                    let newly_alerting = app.update_dlq_alerts();
+                    report_dlq_alerts(&mut app, &newly_alerting);
+                }
+                BgEvent::NodeCountsLoaded {
+                    topic,
+                    subscriptions,
+                } => {
+                    let newly_alerting = app.apply_node_counts(&topic, subscriptions);
+                    report_dlq_alerts(&mut app, &newly_alerting);
+                    app.finish_session_restore();
                 }
                 BgEvent::DetailLoaded(detail) => {
-                    app.detail_view = *detail;
+                    app.apply_detail(*detail);
+                }
+                BgEvent::EntityGone { path } => {
+                    app.mark_entity_gone(&path);
+                }
+                BgEvent::PurgePreflightLoaded {
+                    entity_path,
+                    is_dlq,
+                    candidates,
+                } => {
+                    // Ignore the result if the clear dialog was closed meanwhile
+                    if matches!(app.modal, ActiveModal::ClearOptions { .. }) {
+                        match candidates {
+                            Ok(candidates) => {
+                                app.open_purge_preflight(entity_path, is_dlq, candidates)
+                            }
+                            Err(e) => {
+                                app.modal = ActiveModal::None;
+                                app.set_error(e);
+                            }
+                        }
+                    }
+                }
+                BgEvent::NamespaceInfoLoaded(info) => {
+                    app.namespace_info = Some(info);
+                }
+                BgEvent::EntityMetricsLoaded { entity, metrics } => {
+                    app.entity_metrics = Some((entity, metrics));
+                }
+                BgEvent::HealthCheckResult { rtt_ms, status } => {
+                    app.apply_health_check(rtt_ms, status);
+                }
+                BgEvent::ConnectionTested { name, outcome } => {
+                    app.apply_connection_test(name, outcome);
+                }
+                BgEvent::ConnectionTestsDone { cancelled } => {
+                    app.finish_connection_tests(cancelled);
                 }
                 BgEvent::SubscriptionFilterLoaded {
                     topic_name,
@@ -240,30 +598,106 @@ async fn run_app(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> anyho
                     );
                     app.set_status("Edit the SQL filter and press F2 to update");
                 }
-                BgEvent::PeekComplete { messages, is_dlq } => {
+                BgEvent::PeekComplete {
+                    entity,
+                    messages,
+                    tab,
+                } => {
                     let count = messages.len();
+                    let is_dlq = tab == MessageTab::DeadLetter;
+                    // Results for an entity the user has moved away from wait in the cache
+                    if !app.accept_peek(&entity, tab, messages) {
+                        app.peek_clamp_note = None;
+                        app.set_status(format!(
+                            "Peeked {} messages from '{}' — cached until you select it again",
+                            count, entity
+                        ));
+                        continue;
+                    }
                     if is_dlq {
-                        app.dlq_messages = messages;
-                        app.message_tab = MessageTab::DeadLetter;
-                    } else {
-                        app.messages = messages;
-                        app.message_tab = MessageTab::Messages;
+                        app.dlq_reason_filter = None;
+                        app.dlq_group_selected = 0;
                     }
+                    app.message_tab = tab;
                     app.message_selected = 0;
                     app.selected_message_detail = None;
                     app.focus = FocusPanel::Messages;
+                    let kind = match tab {
+                        MessageTab::Messages => "",
+                        MessageTab::DeadLetter => " DLQ",
+                        MessageTab::Transferred => " transfer DLQ",
+                    };
+                    let mut status = format!("Peeked {}{} messages", count, kind);
                     if is_dlq {
-                        app.set_status(format!("Peeked {} DLQ messages", count));
+                        let stats = DlqStats::from_messages(&app.dlq_messages);
+                        if let Some((reason, n)) = stats.reasons.first() {
+                            status.push_str(&format!(
+                                " — top reason: {} ({:.0}%), S for breakdown",
+                                reason,
+                                stats.percent(*n)
+                            ));
+                        }
+                    }
+                    if let Some(note) = app.peek_clamp_note.take() {
+                        status.push_str(&format!(" — {}", note));
+                    }
+                    app.set_status(status);
+                }
+                BgEvent::PeekBothComplete {
+                    entity,
+                    active,
+                    dead_lettered,
+                } => {
+                    let mut status = app::peek_both_summary(&active, &dead_lettered);
+                    let failed = active.is_err() || dead_lettered.is_err();
+                    let mut shown = true;
+                    for (tab, result) in [
+                        (MessageTab::Messages, active),
+                        (MessageTab::DeadLetter, dead_lettered),
+                    ] {
+                        if let Ok(messages) = result {
+                            shown &= app.accept_peek(&entity, tab, messages);
+                        }
+                    }
+                    if shown {
+                        // Both tabs are filled; the one being looked at stays
+                        app.dlq_reason_filter = None;
+                        app.dlq_group_selected = 0;
+                        app.message_selected = 0;
+                        app.selected_message_detail = None;
+                        app.focus = FocusPanel::Messages;
+                        if let Some(note) = app.peek_clamp_note.take() {
+                            status.push_str(&format!(" — {}", note));
+                        }
                     } else {
-                        app.set_status(format!("Peeked {} messages", count));
+                        app.peek_clamp_note = None;
+                        status.push_str(&format!(
+                            " from '{}' — cached until you select it again",
+                            entity
+                        ));
+                    }
+                    if failed {
+                        app.set_error(status);
+                    } else {
+                        app.set_status(status);
                     }
                 }
-                BgEvent::SendComplete { status } => {
+                BgEvent::SendComplete { status, defaults } => {
+                    app.remember_send_defaults(defaults);
+                    app.clear_draft(DraftKind::Send);
                     app.set_status(status);
+                    app.form_submitting = false;
                     app.modal = ActiveModal::None;
                 }
                 BgEvent::EntityCreated { status } => {
                     app.set_status(status);
+                    app.form_submitting = false;
+                    app.modal = ActiveModal::None;
+                    needs_refresh = true;
+                }
+                BgEvent::EntityUpdated { status } => {
+                    app.set_status(status);
+                    app.form_submitting = false;
                     app.modal = ActiveModal::None;
                     needs_refresh = true;
                 }
@@ -274,14 +708,15 @@ async fn run_app(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> anyho
                 }
                 BgEvent::ResendSendComplete {
                     status,
-                    dlq_seq_removed,
+                    dlq_removed,
                     was_inline,
                 } => {
-                    if let Some(seq) = dlq_seq_removed {
-                        app.dlq_messages
-                            .retain(|m| m.broker_properties.sequence_number != Some(seq));
+                    if let Some(removed) = dlq_removed {
+                        app.dlq_messages.retain(|m| !removed.matches(m));
                     }
                     app.set_status(status);
+                    app.form_submitting = false;
+                    app.clear_draft(DraftKind::Edit);
                     if was_inline {
                         app.detail_editing = false;
                         app.selected_message_detail = None;
@@ -289,10 +724,14 @@ async fn run_app(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> anyho
                         app.modal = ActiveModal::None;
                     }
                 }
-                BgEvent::DestinationEntitiesLoaded { entities } => {
-                    app.copy_dest_entities = entities;
-                    app.copy_entity_selected = 0;
-                    app.copy_entity_list_state.select(Some(0));
+                BgEvent::MessageOpFinished { target } => {
+                    app.finish_message_op(&target);
+                }
+                BgEvent::DestinationEntitiesLoaded {
+                    connection,
+                    entities,
+                } => {
+                    app.apply_destination_entities(connection, entities);
                     app.bg_running = false;
 
                     if app.copy_dest_entities.is_empty() {
@@ -304,21 +743,59 @@ async fn run_app(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> anyho
                 BgEvent::MessageCopyComplete { status } => {
                     app.set_status(status);
                     app.bg_running = false;
-                    app.copy_source_message = None;
-                    app.copy_source_entity = None;
-                    app.copy_dest_entities.clear();
-                    app.copy_entity_selected = 0;
-                    app.copy_dest_connection_name = None;
-                    app.copy_dest_connection_config = None;
-                    app.copy_destination_entity = None;
+                    app.clear_copy_state();
                 }
                 BgEvent::SubscriptionFilterUpdated { status } => {
                     app.set_status(status);
+                    app.form_submitting = false;
                     app.modal = ActiveModal::None;
                     app.bg_running = false;
                 }
+                BgEvent::SearchComplete(results) => {
+                    app.bg_running = false;
+                    app.set_status(format!(
+                        "Found {} match(es) for '{}' in {}/{} entities",
+                        results.hits.len(),
+                        results.query,
+                        results.searched,
+                        results.total
+                    ));
+                    app.search_results = Some(*results);
+                    app.search_selected = 0;
+                    app.modal = ActiveModal::SearchResults;
+                }
+                BgEvent::SessionsBrowsed(browse) => {
+                    app.bg_running = false;
+                    let mut status = format!(
+                        "{} session(s) on '{}'",
+                        browse.sessions.len(),
+                        browse.entity_path
+                    );
+                    if browse.truncated {
+                        status.push_str(" (stopped early)");
+                    }
+                    app.set_status(status);
+                    app.session_browse = Some(*browse);
+                    app.session_selected = 0;
+                    app.modal = ActiveModal::Sessions;
+                }
+                BgEvent::AuthorizationRulesLoaded { entity_path, rules } => {
+                    app.bg_running = false;
+                    app.set_status(format!(
+                        "{} authorization rule(s) on '{}'",
+                        rules.len(),
+                        entity_path
+                    ));
+                    app.authorization_rules = rules;
+                    app.modal = ActiveModal::AuthorizationRules { entity_path };
+                }
             }
         }
+        if !app.bg_running {
+            app.bg_job = None;
+            app.purge_total = None;
+            app.purge_done = 0;
+        }
 
         // ──────── Async action dispatch ────────
         // All operations are spawned as background tasks to keep the UI responsive.
@@ -334,11 +811,26 @@ async fn run_app(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> anyho
             needs_refresh = true;
         }
 
+        let auto_refresh_secs = app.config.settings.auto_refresh_secs;
+        let auto_refresh = auto_refresh_secs > 0
+            && last_refresh.elapsed().as_secs() >= auto_refresh_secs
+            && app.management.is_some()
+            && !app.loading
+            && !app.bg_running
+            && app.modal == ActiveModal::None;
+        let manual_refresh = needs_refresh || app.status_message == "Refreshing...";
+
         // Refresh tree (spawned)
-        if needs_refresh || app.status_message == "Refreshing..." {
+        if manual_refresh || auto_refresh {
             if let Some(mgmt) = app.management.as_ref().cloned() {
                 app.loading = true;
-                app.set_status("Loading entities...");
+                last_refresh = std::time::Instant::now();
+                quiet_refresh = !manual_refresh;
+                if app.session_restore.is_some() {
+                    app.set_status("Restoring last session... (Esc for a fresh start)");
+                } else if !quiet_refresh {
+                    app.set_status("Loading entities...");
+                }
 
                 let mgmt = mgmt;
                 let namespace = app
@@ -348,6 +840,21 @@ async fn run_app(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> anyho
                     .unwrap_or_else(|| "Namespace".to_string());
                 let tx = app.bg_tx.clone();
 
+                // Tier and name for the header, once per connection. An
+                // entity-scoped key isn't allowed to read the namespace.
+                if app.tree.is_none()
+                    && app.namespace_info.is_none()
+                    && mgmt.entity_path().is_none()
+                {
+                    let mgmt = mgmt.clone();
+                    let tx = tx.clone();
+                    tokio::spawn(async move {
+                        if let Ok(info) = mgmt.get_namespace_info().await {
+                            let _ = tx.send(BgEvent::NamespaceInfoLoaded(info));
+                        }
+                    });
+                }
+
                 tokio::spawn(async move {
                     match app::build_tree(mgmt, namespace).await {
                         Ok((tree, flat_nodes)) => {
@@ -362,19 +869,160 @@ async fn run_app(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> anyho
             needs_refresh = false;
         }
 
+        // Config saves: debounced and written off the UI thread
+        if let Some(task) = config_save.take_if(|task| task.is_finished()) {
+            match task.await {
+                Ok(Ok(())) => {}
+                Ok(Err(e)) => app.set_error(format!("Failed to save config: {}", e)),
+                Err(e) => app.set_error(format!("Failed to save config: {}", e)),
+            }
+        }
+        if config_save.is_none() {
+            if let Some(config) = app.take_config_save(false) {
+                config_save = Some(tokio::task::spawn_blocking(move || config.save()));
+            }
+        }
+
+        // Namespace health check: `H`, or every `health_check_interval_secs`
+        let health_due = app
+            .config
+            .settings
+            .health_check_interval_secs
+            .is_some_and(|secs| last_health_check.elapsed().as_secs() >= secs);
+        if (health_due || app.status_message == "Checking namespace health...")
+            && !app.health_check_in_flight
+        {
+            if let Some(mgmt) = app.management.clone() {
+                app.health_check_in_flight = true;
+                last_health_check = std::time::Instant::now();
+                let tx = app.bg_tx.clone();
+                tokio::spawn(async move {
+                    let (rtt_ms, status) = match tokio::time::timeout(
+                        connection_test::TEST_TIMEOUT,
+                        mgmt.ping(),
+                    )
+                    .await
+                    {
+                        Ok(Ok(rtt)) => (rtt.as_millis() as u64, app::HEALTH_OK.to_string()),
+                        Ok(Err(e)) => (0, e.to_string()),
+                        Err(_) => (
+                            0,
+                            format!(
+                                "no answer within {}s",
+                                connection_test::TEST_TIMEOUT.as_secs()
+                            ),
+                        ),
+                    };
+                    let _ = tx.send(BgEvent::HealthCheckResult { rtt_ms, status });
+                });
+            }
+        }
+
+        // List subscriptions (with counts) of topics that scrolled into view,
+        // a few topics per task
+        if let Some(mgmt) = app.management.clone() {
+            let pending = app.take_visible_pending_topics();
+            for batch in pending.chunks(COUNT_BATCH_SIZE) {
+                let mgmt = mgmt.clone();
+                let batch = batch.to_vec();
+                let tx = app.bg_tx.clone();
+                tokio::spawn(async move {
+                    for topic in batch {
+                        let subscriptions = mgmt
+                            .list_subscriptions_with_counts(&topic)
+                            .await
+                            .map_err(|e| e.to_string());
+                        let _ = tx.send(BgEvent::NodeCountsLoaded {
+                            topic,
+                            subscriptions,
+                        });
+                    }
+                });
+            }
+        }
+
         // Load detail when selection changes (spawned)
         if app.tree_selected != last_selected && !app.flat_nodes.is_empty() {
             last_selected = app.tree_selected;
-
-            if let Some(mgmt) = app.management.as_ref() {
+            app.sync_messages_to_selection();
+            app.sync_tab_to_dead_letter_node();
+
+            let stale = app
+                .selected_entity()
+                .map(|(p, _)| p.to_string())
+                .filter(|p| app.is_entity_stale(p));
+            if let Some(path) = stale {
+                // Already known to be gone; don't ask again until a refresh
+                app.mark_entity_gone(&path);
+            } else if let Some(mgmt) = app.management.as_ref() {
                 if let Some(node) = app.flat_nodes.get(app.tree_selected) {
                     let mgmt = mgmt.clone();
-                    let entity_type = node.entity_type.clone();
-                    let path = node.path.clone();
+                    // A dead-letter node shows its queue's or subscription's detail
+                    let (path, entity_type) = match node.entity_type {
+                        EntityType::DeadLetterQueue => {
+                            let (parent, kind) = app::dead_letter_parent(&node.path);
+                            (parent.to_string(), kind.clone())
+                        }
+                        _ => (node.path.clone(), node.entity_type.clone()),
+                    };
+                    let label = node.label.clone();
+                    let arm_namespace = app.connected_arm_namespace();
                     let tx = app.bg_tx.clone();
 
+                    // Metrics are reported per queue or topic; a subscription shows its topic's
+                    let metrics_entity = match entity_type {
+                        EntityType::Queue | EntityType::Topic => Some(path.clone()),
+                        EntityType::Subscription => entity_path::split_subscription_path(&path)
+                            .map(|(topic, _)| topic.to_string()),
+                        _ => None,
+                    };
+                    if let (Some(entity), Some((ns, credential))) =
+                        (metrics_entity, arm_namespace.clone())
+                    {
+                        let tx = tx.clone();
+                        tokio::spawn(async move {
+                            let arm =
+                                client::resource_manager::ResourceManagerClient::new(credential);
+                            // Missing permissions or no metrics: leave the panel as it is
+                            if let Ok(metrics) = arm
+                                .get_entity_metrics(
+                                    &ns.resource_id,
+                                    &entity,
+                                    std::time::Duration::from_secs(3600),
+                                )
+                                .await
+                            {
+                                let _ = tx.send(BgEvent::EntityMetricsLoaded { entity, metrics });
+                            }
+                        });
+                    }
+
                     tokio::spawn(async move {
                         let detail = match entity_type {
+                            EntityType::Namespace => {
+                                let rules = match mgmt.entity_path() {
+                                    Some(_) => None,
+                                    None => mgmt.list_namespace_authorization_rules().await.ok(),
+                                };
+                                let info = match arm_namespace {
+                                    Some((ns, credential)) => {
+                                        let arm =
+                                            client::resource_manager::ResourceManagerClient::new(
+                                                credential,
+                                            );
+                                        match ns.resource_group {
+                                            Some(ref rg) => arm
+                                                .get_namespace(&ns.subscription_id, rg, &ns.name)
+                                                .await
+                                                .ok()
+                                                .or(ns.detail),
+                                            None => ns.detail,
+                                        }
+                                    }
+                                    None => None,
+                                };
+                                Some(DetailView::Namespace(label, rules, info))
+                            }
                             EntityType::Queue => {
                                 match (
                                     mgmt.get_queue(&path).await,
@@ -382,19 +1030,29 @@ async fn run_app(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> anyho
                                 ) {
                                     (Ok(desc), Ok(rt)) => Some(DetailView::Queue(desc, Some(rt))),
                                     (Ok(desc), Err(_)) => Some(DetailView::Queue(desc, None)),
-                                    _ => None,
+                                    (Err(e), _) => {
+                                        report_if_gone(&tx, &path, &e);
+                                        None
+                                    }
                                 }
                             }
                             EntityType::Topic => {
+                                let subs = mgmt.list_subscriptions_with_counts(&path).await.ok();
+                                let routes = match subs {
+                                    Some(ref subs) => {
+                                        let names: Vec<String> =
+                                            subs.iter().map(|(s, _, _)| s.name.clone()).collect();
+                                        Some(mgmt.list_subscription_routes(&path, &names).await)
+                                    }
+                                    None => None,
+                                };
                                 match (
                                     mgmt.get_topic(&path).await,
                                     mgmt.get_topic_runtime_info(&path).await,
                                 ) {
                                     (Ok(desc), Ok(mut rt)) => {
                                         // Aggregate subscription counts
-                                        if let Ok(subs) =
-                                            mgmt.list_subscriptions_with_counts(&path).await
-                                        {
+                                        if let Some(ref subs) = subs {
                                             let (total_active, total_dlq): (i64, i64) =
                                                 subs.iter().fold(
                                                     (0, 0),
@@ -405,10 +1063,15 @@ async fn run_app(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> anyho
                                             rt.active_message_count = total_active;
                                             rt.dead_letter_message_count = total_dlq;
                                         }
-                                        Some(DetailView::Topic(desc, Some(rt)))
+                                        Some(DetailView::Topic(desc, Some(rt), routes))
+                                    }
+                                    (Ok(desc), Err(_)) => {
+                                        Some(DetailView::Topic(desc, None, routes))
+                                    }
+                                    (Err(e), _) => {
+                                        report_if_gone(&tx, &path, &e);
+                                        None
                                     }
-                                    (Ok(desc), Err(_)) => Some(DetailView::Topic(desc, None)),
-                                    _ => None,
                                 }
                             }
                             EntityType::Subscription => {
@@ -425,7 +1088,10 @@ async fn run_app(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> anyho
                                         (Ok(desc), Err(_)) => {
                                             Some(DetailView::Subscription(desc, None))
                                         }
-                                        _ => None,
+                                        (Err(e), _) => {
+                                            report_if_gone(&tx, &path, &e);
+                                            None
+                                        }
                                     }
                                 } else {
                                     None
@@ -441,11 +1107,75 @@ async fn run_app(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> anyho
             }
         }
 
+        // Test every saved connection from the connection list (spawned)
+        if app.status_message == "Testing connections..." && !app.bg_running {
+            let targets: Vec<(String, Result<client::ConnectionConfig, String>)> = app
+                .config
+                .connections
+                .iter()
+                .map(|c| (c.name.clone(), app::saved_connection_config(c)))
+                .collect();
+            app.connections_testing = targets.iter().map(|(name, _)| name.clone()).collect();
+            app.start_bg_job("Testing connections", None);
+            app.set_status(format!(
+                "Testing {} connections (Esc to cancel)...",
+                targets.len()
+            ));
+            let tx = app.bg_tx.clone();
+            let cancel = app.new_cancel_token();
+            let http = app.http.clone();
+
+            tokio::spawn(async move {
+                // Up to TEST_CONCURRENCY tests in flight; each test checks for
+                // cancellation once it gets its turn, so Esc stops the run
+                // after the tests already under way.
+                let permits = std::sync::Arc::new(tokio::sync::Semaphore::new(
+                    connection_test::TEST_CONCURRENCY,
+                ));
+                let handles: Vec<_> = targets
+                    .into_iter()
+                    .map(|(name, config)| {
+                        let permits = permits.clone();
+                        let cancel = cancel.clone();
+                        let http = http.clone();
+                        let tx = tx.clone();
+                        tokio::spawn(async move {
+                            let Ok(_permit) = permits.acquire_owned().await else {
+                                return;
+                            };
+                            if cancel.load(std::sync::atomic::Ordering::Relaxed) {
+                                return;
+                            }
+                            let outcome = match config {
+                                Ok(config) => {
+                                    connection_test::test_connection(client::ManagementClient::new(
+                                        config, http,
+                                    ))
+                                    .await
+                                }
+                                Err(detail) => connection_test::TestOutcome::Failed {
+                                    class: connection_test::FailureClass::AuthFailed,
+                                    detail,
+                                },
+                            };
+                            let _ = tx.send(BgEvent::ConnectionTested { name, outcome });
+                        })
+                    })
+                    .collect();
+                for handle in handles {
+                    let _ = handle.await;
+                }
+                let cancelled = cancel.load(std::sync::atomic::Ordering::Relaxed);
+                let _ = tx.send(BgEvent::ConnectionTestsDone { cancelled });
+            });
+        }
+
         // Namespace discovery (spawned)
         if app.status_message == "Discovering namespaces..." && !app.bg_running {
-            app.bg_running = true;
+            app.start_bg_job("Namespace discovery", None);
             let bg_tx = app.bg_tx.clone();
             let cancel = app.new_cancel_token();
+            let cache = app.usable_discovery_cache(chrono::Utc::now());
 
             tokio::spawn(async move {
                 if cancel.load(std::sync::atomic::Ordering::Relaxed) {
@@ -468,10 +1198,26 @@ async fn run_app(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> anyho
                     };
 
                 let client = client::resource_manager::ResourceManagerClient::new(credential);
-                let result = client.discover_namespaces().await;
+                // Without an identity nothing is served from or written to
+                // the cache; the discovery itself reports token failures.
+                let identity = client.identity().await.ok();
+                let cached = identity
+                    .as_ref()
+                    .and_then(|identity| cache.into_iter().find(|e| e.is_for(identity)));
+                let (result, cached_minutes) = match cached {
+                    Some(entry) => {
+                        let age = entry.age_minutes(chrono::Utc::now());
+                        (entry.result, age)
+                    }
+                    None => (client.discover_namespaces().await, None),
+                };
 
                 if !cancel.load(std::sync::atomic::Ordering::Relaxed) {
-                    let _ = bg_tx.send(BgEvent::NamespacesDiscovered { result });
+                    let _ = bg_tx.send(BgEvent::NamespacesDiscovered {
+                        identity,
+                        result,
+                        cached_minutes,
+                    });
                 }
             });
         }
@@ -479,53 +1225,117 @@ async fn run_app(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> anyho
         // Peek messages (spawned)
         if app.status_message == "Peeking messages..." && app.data_plane.is_some() {
             let dp = app.data_plane.clone().unwrap();
-            if let Some((path, entity_type)) = app.selected_entity() {
+            let stale = app
+                .selected_entity()
+                .map(|(p, _)| p.to_string())
+                .filter(|p| app.is_entity_stale(p));
+            if let Some(path) = stale {
+                app.peek_dlq = false;
+                app.peek_both = false;
+                app.pending_peek_count = None;
+                app.mark_entity_gone(&path);
+            } else if let Some((path, entity_type)) = app.selected_entity() {
                 let is_dlq = app.peek_dlq;
+                let is_both = app.peek_both;
                 let is_topic = *entity_type == EntityType::Topic;
                 let entity_path = path.to_string();
                 app.peek_dlq = false;
+                app.peek_both = false;
                 let peek_count = app
                     .pending_peek_count
                     .take()
                     .unwrap_or(app.config.settings.peek_count);
                 let tx = app.bg_tx.clone();
 
-                app.set_status("Peeking...");
+                match app.peek_clamp_note {
+                    Some(ref note) => {
+                        let status = format!("Peeking {} messages ({})...", peek_count, note);
+                        app.set_status(status);
+                    }
+                    None => app.set_status("Peeking..."),
+                }
 
-                if is_topic && is_dlq {
+                if is_both {
+                    tokio::spawn(async move {
+                        let dlq_path = format!("{}/$deadletterqueue", entity_path);
+                        let (active, dead_lettered) = tokio::join!(
+                            dp.peek_messages(&entity_path, peek_count),
+                            dp.peek_messages(&dlq_path, peek_count)
+                        );
+                        let tag = |result: client::Result<Vec<client::models::ReceivedMessage>>| {
+                            result
+                                .map(|mut msgs| {
+                                    for msg in &mut msgs {
+                                        msg.source_entity = Some(entity_path.clone());
+                                    }
+                                    msgs
+                                })
+                                .map_err(|e| e.to_string())
+                        };
+                        let active = tag(active);
+                        let dead_lettered = tag(dead_lettered);
+                        let _ = tx.send(BgEvent::PeekBothComplete {
+                            entity: entity_path,
+                            active,
+                            dead_lettered,
+                        });
+                    });
+                } else if is_topic && is_dlq {
                     let mgmt = app.management.as_ref().cloned();
+                    let concurrency = app.config.settings.peek_concurrency.max(1);
                     tokio::spawn(async move {
                         let mut all_msgs = Vec::new();
                         if let Some(mgmt) = mgmt {
                             match mgmt.list_subscriptions(&entity_path).await {
                                 Ok(subs) => {
-                                    for s in &subs {
-                                        // Management-style path for remove_from_dlq
-                                        let sub_entity =
-                                            format!("{}/Subscriptions/{}", entity_path, s.name);
-                                        let dlq_path = format!(
-                                            "{}/subscriptions/{}/$deadletterqueue",
-                                            entity_path, s.name
-                                        );
-                                        if let Ok(mut msgs) =
-                                            dp.peek_messages(&dlq_path, peek_count).await
-                                        {
-                                            for msg in &mut msgs {
-                                                msg.source_entity = Some(sub_entity.clone());
+                                    // Peek `concurrency` subscription DLQs at a time,
+                                    // collecting results in subscription order
+                                    for batch in subs.chunks(concurrency) {
+                                        let handles: Vec<_> = batch
+                                            .iter()
+                                            .map(|s| {
+                                                let dp = dp.clone();
+                                                // Management-style path for remove_from_dlq
+                                                let sub_entity = format!(
+                                                    "{}/Subscriptions/{}",
+                                                    entity_path, s.name
+                                                );
+                                                let dlq_path = format!(
+                                                    "{}/subscriptions/{}/$deadletterqueue",
+                                                    entity_path, s.name
+                                                );
+                                                tokio::spawn(async move {
+                                                    let mut msgs = dp
+                                                        .peek_messages(&dlq_path, peek_count)
+                                                        .await
+                                                        .unwrap_or_default();
+                                                    for msg in &mut msgs {
+                                                        msg.source_entity =
+                                                            Some(sub_entity.clone());
+                                                    }
+                                                    msgs
+                                                })
+                                            })
+                                            .collect();
+                                        for handle in handles {
+                                            if let Ok(msgs) = handle.await {
+                                                all_msgs.extend(msgs);
                                             }
-                                            all_msgs.extend(msgs);
                                         }
                                     }
                                 }
                                 Err(e) => {
-                                    send_failed_with(&tx, "Failed to list subscriptions", e);
+                                    if !report_if_gone(&tx, &entity_path, &e) {
+                                        send_failed_with(&tx, "Failed to list subscriptions", e);
+                                    }
                                     return;
                                 }
                             }
                         }
                         let _ = tx.send(BgEvent::PeekComplete {
+                            entity: entity_path,
                             messages: all_msgs,
-                            is_dlq: true,
+                            tab: MessageTab::DeadLetter,
                         });
                     });
                 } else {
@@ -542,13 +1352,21 @@ async fn run_app(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> anyho
                                 for msg in &mut msgs {
                                     msg.source_entity = Some(source_entity.clone());
                                 }
+                                let tab = if is_dlq {
+                                    MessageTab::DeadLetter
+                                } else {
+                                    MessageTab::Messages
+                                };
                                 let _ = tx.send(BgEvent::PeekComplete {
+                                    entity: source_entity,
                                     messages: msgs,
-                                    is_dlq,
+                                    tab,
                                 });
                             }
                             Err(e) => {
-                                send_failed_with(&tx, "Peek failed", e);
+                                if !report_if_gone(&tx, &source_entity, &e) {
+                                    send_failed_with(&tx, "Peek failed", e);
+                                }
                             }
                         }
                     });
@@ -558,38 +1376,241 @@ async fn run_app(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> anyho
             }
         }
 
+        // Peek the transfer dead-letter queue (spawned)
+        if app.status_message == "Peeking transfer DLQ..." && app.data_plane.is_some() {
+            let dp = app.data_plane.clone().unwrap();
+            if let Some((path, _)) = app.selected_entity() {
+                let source_entity = path.to_string();
+                let peek_path = format!("{}/$transfer/$deadletterqueue", path);
+                let peek_count = app.config.settings.peek_count;
+                let tx = app.bg_tx.clone();
+                app.set_status("Peeking...");
+                tokio::spawn(async move {
+                    match dp.peek_messages(&peek_path, peek_count).await {
+                        Ok(mut msgs) => {
+                            for msg in &mut msgs {
+                                msg.source_entity = Some(source_entity.clone());
+                            }
+                            let _ = tx.send(BgEvent::PeekComplete {
+                                entity: source_entity,
+                                messages: msgs,
+                                tab: MessageTab::Transferred,
+                            });
+                        }
+                        Err(e) => {
+                            if !report_if_gone(&tx, &source_entity, &e) {
+                                send_failed_with(&tx, "Peek failed", e);
+                            }
+                        }
+                    }
+                });
+            }
+        }
+
+        // Topic purge pre-flight — check each subscription before purging (spawned)
+        let is_preflight = app.status_message == "Checking subscriptions (delete)..."
+            || app.status_message == "Checking subscriptions (delete DLQ)...";
+        if is_preflight && !app.bg_running {
+            let is_dlq = app.status_message == "Checking subscriptions (delete DLQ)...";
+            match (&app.modal, app.management.clone()) {
+                (ActiveModal::ClearOptions { entity_path, .. }, Some(mgmt)) => {
+                    let entity_path = entity_path.clone();
+                    let tx = app.bg_tx.clone();
+                    app.set_status("Checking subscriptions before purging...");
+                    tokio::spawn(async move {
+                        let candidates = preflight_topic_purge(&mgmt, &entity_path, is_dlq).await;
+                        let _ = tx.send(BgEvent::PurgePreflightLoaded {
+                            entity_path,
+                            is_dlq,
+                            candidates,
+                        });
+                    });
+                }
+                _ => app.set_status("No entity selected"),
+            }
+        }
+
+        // Dry run of a purge or resend — report what it would touch (spawned)
+        let dry_run_op = match (app.status_message.as_str(), &app.modal) {
+            ("Dry run (delete)...", ActiveModal::ClearOptions { is_topic, .. }) => {
+                Some(DryRunOp::Purge {
+                    is_dlq: false,
+                    is_topic: *is_topic,
+                })
+            }
+            ("Dry run (delete DLQ)...", ActiveModal::ClearOptions { is_topic, .. }) => {
+                Some(DryRunOp::Purge {
+                    is_dlq: true,
+                    is_topic: *is_topic,
+                })
+            }
+            ("Dry run (resend)...", ActiveModal::ClearOptions { .. }) => Some(DryRunOp::ResendAll),
+            ("Dry run (bulk resend)...", ActiveModal::ConfirmBulkResend { .. }) => {
+                Some(DryRunOp::BulkResend)
+            }
+            ("Dry run (bulk delete)...", ActiveModal::ConfirmBulkDelete { .. }) => {
+                Some(DryRunOp::BulkDelete)
+            }
+            _ => None,
+        };
+        if let (Some(op), Some(dp)) = (dry_run_op, app.data_plane.clone()) {
+            if !app.bg_running {
+                // Reopened with dry run off to run the operation for real
+                app.toggle_dry_run();
+                let then = app.modal.clone();
+                let (entity_path, is_topic, is_dlq) = match &then {
+                    ActiveModal::ClearOptions {
+                        base_entity_path,
+                        is_topic,
+                        ..
+                    } => (
+                        base_entity_path.clone(),
+                        *is_topic,
+                        matches!(op, DryRunOp::Purge { is_dlq: true, .. }),
+                    ),
+                    ActiveModal::ConfirmBulkResend {
+                        entity_path,
+                        is_topic,
+                        ..
+                    } => (entity_path.clone(), *is_topic, true),
+                    ActiveModal::ConfirmBulkDelete {
+                        entity_path,
+                        is_dlq,
+                        is_topic,
+                        ..
+                    } => (entity_path.clone(), *is_topic, *is_dlq),
+                    _ => unreachable!("dry runs start from a clear or bulk confirm modal"),
+                };
+                let send_target =
+                    matches!(op, DryRunOp::ResendAll | DryRunOp::BulkResend).then(|| {
+                        app.selected_effective_send_entity()
+                            .unwrap_or_else(|| entity_path::send_target(&entity_path).to_string())
+                    });
+                let mut report = DryRunReport {
+                    op,
+                    send_target,
+                    counts: Vec::new(),
+                    sample_ids: Vec::new(),
+                    then,
+                };
+
+                if op == DryRunOp::BulkResend {
+                    // Resends exactly the peeked messages; nothing to scan
+                    let mut counts: Vec<(String, Result<u64, String>)> = Vec::new();
+                    for msg in &app.dlq_messages {
+                        let source = msg.source_entity.as_deref().unwrap_or(&entity_path);
+                        let dlq = format!("{}/$deadletterqueue", source);
+                        match counts.iter_mut().find(|(path, _)| *path == dlq) {
+                            Some((_, Ok(n))) => *n += 1,
+                            _ => counts.push((dlq, Ok(1))),
+                        }
+                    }
+                    report.counts = counts;
+                    report.sample_ids = app
+                        .dlq_messages
+                        .iter()
+                        .filter_map(|m| m.broker_properties.message_id.clone())
+                        .take(DRY_RUN_SAMPLE_IDS)
+                        .collect();
+                    app.open_dry_run_summary(report);
+                } else {
+                    let tx = app.bg_tx.clone();
+                    let cancel = app.new_cancel_token();
+                    let mgmt = app.management.as_ref().cloned();
+
+                    app.start_bg_job("Dry run", Some(entity_path.clone()));
+                    app.modal = ActiveModal::None;
+                    app.set_status("Preparing dry run...");
+
+                    tokio::spawn(async move {
+                        let paths = match op {
+                            DryRunOp::ResendAll => {
+                                let target = report.send_target.as_deref().unwrap_or_default();
+                                resolve_resend_pairs(mgmt.as_ref(), &entity_path, target, is_topic)
+                                    .await
+                                    .map(|pairs| pairs.into_iter().map(|(dlq, _)| dlq).collect())
+                            }
+                            _ => {
+                                resolve_purge_paths(mgmt.as_ref(), &entity_path, is_topic, is_dlq)
+                                    .await
+                            }
+                        };
+                        let paths: Vec<String> = match paths {
+                            Ok(p) => p,
+                            Err(e) => {
+                                send_failed(&tx, e);
+                                return;
+                            }
+                        };
+                        match dry_run_scan(mgmt.as_ref(), &dp, &paths, &cancel, &tx).await {
+                            Some((counts, sample_ids)) => {
+                                report.counts = counts;
+                                report.sample_ids = sample_ids;
+                                let _ = tx.send(BgEvent::DryRunComplete(Box::new(report)));
+                            }
+                            None => {
+                                let _ = tx.send(BgEvent::Cancelled {
+                                    message: "Dry run cancelled".to_string(),
+                                });
+                            }
+                        }
+                    });
+                }
+            }
+        }
+
         // Clear (delete / delete DLQ) — spawn background purge
         let is_clear_delete = app.status_message == "Clearing (delete)..."
             || app.status_message == "Clearing (delete DLQ)...";
         if is_clear_delete && app.data_plane.is_some() && !app.bg_running {
             let is_dlq = app.status_message == "Clearing (delete DLQ)...";
-            if let ActiveModal::ClearOptions {
-                ref entity_path,
-                is_topic,
-                ..
-            } = app.modal
-            {
-                let entity_path = entity_path.clone();
+            let target = match app.modal {
+                ActiveModal::ClearOptions {
+                    ref entity_path,
+                    is_topic,
+                    ..
+                } => Some((entity_path.clone(), is_topic)),
+                ActiveModal::PurgePreflight {
+                    ref entity_path, ..
+                } => Some((entity_path.clone(), true)),
+                _ => None,
+            };
+            if let Some((entity_path, is_topic)) = target {
                 let dp = app.data_plane.clone().unwrap();
                 let tx = app.bg_tx.clone();
                 let cancel = app.new_cancel_token();
                 let mgmt = app.management.as_ref().cloned();
-
-                app.bg_running = true;
+                let concurrency = app.config.settings.purge_concurrency;
+                let chosen = app.purge_paths.take();
+                let excluded = app.purge_candidates.iter().any(|c| !c.selected);
+                app.purge_candidates.clear();
+
+                app.start_bg_job("Purge", Some(entity_path.clone()));
+                // The entity's count includes excluded subscriptions
+                app.purge_total = if excluded {
+                    None
+                } else {
+                    app.selected_peekable_count(is_dlq).map(|n| n.max(0) as u64)
+                };
+                app.purge_done = 0;
                 app.modal = ActiveModal::None;
                 app.set_status("Preparing purge...");
 
                 tokio::spawn(async move {
-                    let paths =
-                        match resolve_purge_paths(mgmt.as_ref(), &entity_path, is_topic, is_dlq)
-                            .await
-                        {
-                            Ok(p) => p,
-                            Err(e) => {
-                                send_failed(&tx, e);
-                                return;
+                    let paths = match chosen {
+                        Some(paths) => paths,
+                        None => {
+                            match resolve_purge_paths(mgmt.as_ref(), &entity_path, is_topic, is_dlq)
+                                .await
+                            {
+                                Ok(p) => p,
+                                Err(e) => {
+                                    send_failed(&tx, e);
+                                    return;
+                                }
                             }
-                        };
+                        }
+                    };
 
                     let _ = tx.send(BgEvent::Progress(format!(
                         "Purging messages from {} path(s) (Esc to cancel)...",
@@ -604,52 +1625,38 @@ async fn run_app(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> anyho
                         while let Some(n) = progress_rx.recv().await {
                             if n >= last_reported + 50 {
                                 last_reported = n;
-                                let _ = tx2.send(BgEvent::Progress(format!(
-                                    "Deleted {} messages... (Esc to cancel)",
-                                    n
-                                )));
+                                let _ = tx2.send(BgEvent::JobProgress {
+                                    processed: n,
+                                    message: format!("Deleted {} messages... (Esc to cancel)", n),
+                                });
                             }
                         }
                     });
 
-                    let mut count = 0u64;
+                    // A failing path doesn't stop the others; each reports its own count
+                    let mut results = Vec::with_capacity(paths.len());
                     for path in &paths {
-                        match dp
+                        if cancel.load(std::sync::atomic::Ordering::Relaxed) {
+                            break;
+                        }
+                        let result = dp
                             .purge_concurrent(
                                 path,
-                                32,
+                                concurrency,
                                 Some(cancel.clone()),
                                 Some(progress_tx.clone()),
                             )
                             .await
-                        {
-                            Ok(n) => count += n,
-                            Err(e) => {
-                                if cancel.load(std::sync::atomic::Ordering::Relaxed) {
-                                    let _ = tx.send(BgEvent::Cancelled {
-                                        message: format!(
-                                            "Cancelled after deleting {} messages",
-                                            count
-                                        ),
-                                    });
-                                } else {
-                                    send_failed(
-                                        &tx,
-                                        format!("Purge failed after {} messages: {}", count, e),
-                                    );
-                                }
-                                drop(progress_tx);
-                                let _ = progress_task.await;
-                                return;
-                            }
-                        }
+                            .map_err(|e| e.to_string());
+                        results.push((path.clone(), result));
                     }
                     if cancel.load(std::sync::atomic::Ordering::Relaxed) {
+                        let count: u64 = results.iter().filter_map(|(_, r)| r.as_ref().ok()).sum();
                         let _ = tx.send(BgEvent::Cancelled {
                             message: format!("Cancelled after deleting {} messages", count),
                         });
                     } else {
-                        let _ = tx.send(BgEvent::PurgeComplete { count });
+                        let _ = tx.send(BgEvent::PurgeComplete { results });
                     }
                     drop(progress_tx);
                     let _ = progress_task.await;
@@ -675,9 +1682,11 @@ async fn run_app(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> anyho
                 let tx = app.bg_tx.clone();
                 let cancel = app.new_cancel_token();
                 let mgmt = app.management.as_ref().cloned();
-                let send_target = send_path_owned(&entity_path);
+                let send_target = app
+                    .selected_effective_send_entity()
+                    .unwrap_or_else(|| entity_path::send_target(&entity_path).to_string());
 
-                app.bg_running = true;
+                app.start_bg_job("DLQ resend", Some(entity_path.clone()));
                 app.modal = ActiveModal::None;
                 app.set_status("Preparing DLQ resend...");
 
@@ -704,7 +1713,13 @@ async fn run_app(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> anyho
 
                     match resend_dlq_loop(&dp, &pairs, None, &cancel, &tx).await {
                         Ok((resent, errors)) => {
-                            let _ = tx.send(BgEvent::ResendComplete { resent, errors });
+                            let _ = tx.send(BgEvent::ResendComplete {
+                                resent,
+                                errors,
+                                cancelled: false,
+                                scheduled: None,
+                                failures: None,
+                            });
                         }
                         Err(msg) => {
                             if cancel.load(std::sync::atomic::Ordering::Relaxed) {
@@ -761,19 +1776,21 @@ async fn run_app(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> anyho
         // Submit send message (spawned)
         if app.status_message == "Submitting..." && app.modal == ActiveModal::SendMessage {
             if let Some(dp) = app.data_plane.as_ref() {
-                if let Some((path, _)) = app.selected_entity() {
+                if let Some(path) = app.selected_effective_send_entity() {
                     let dp = dp.clone();
-                    let path = entity_path::send_target(path).to_string();
                     let msg = app.build_message_from_form();
+                    let defaults = app.send_defaults_for_message(&path, &msg);
                     let tx = app.bg_tx.clone();
 
                     app.set_status("Sending...");
+                    app.form_submitting = true;
 
                     tokio::spawn(async move {
                         match dp.send_message(&path, &msg).await {
                             Ok(_) => {
                                 let _ = tx.send(BgEvent::SendComplete {
                                     status: "Message sent successfully".to_string(),
+                                    defaults,
                                 });
                             }
                             Err(e) => {
@@ -788,27 +1805,50 @@ async fn run_app(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> anyho
         // Submit edit & resend — modal or inline (spawned)
         let is_edit_resend = app.status_message == "Submitting..."
             && (app.modal == ActiveModal::EditResend || app.detail_editing);
-        if is_edit_resend {
+        let source_busy = app
+            .edit_source_dlq_ref
+            .as_ref()
+            .is_some_and(|t| app.message_ops_in_flight.contains(t));
+        if is_edit_resend && source_busy {
+            app.set_status("An operation on this message is already in progress");
+        } else if is_edit_resend {
             let was_inline = app.detail_editing;
             if let Some(dp) = app.data_plane.as_ref() {
-                if let Some((path, _)) = app.selected_entity() {
+                if let (Some((path, _)), Some(base_path)) =
+                    (app.selected_entity(), app.selected_effective_send_entity())
+                {
                     let dp = dp.clone();
-                    let base_path = entity_path::send_target(path).to_string();
                     let entity_path = path.to_string();
                     let msg = app.build_message_from_form();
-                    let dlq_seq = app.edit_source_dlq_seq.take();
+                    let dlq_ref = app.edit_source_dlq_ref.take();
                     let tx = app.bg_tx.clone();
+                    let mgmt = app.management.clone();
+                    let window = app.form_delay().and_then(Result::ok).map(|d| {
+                        delay::DelaySpec {
+                            delay: d,
+                            spread: None,
+                        }
+                        .window(chrono::Utc::now(), 1)
+                        .unwrap_or_else(|e| e)
+                    });
 
+                    if let Some(ref target) = dlq_ref {
+                        app.begin_message_op(target);
+                    }
                     app.set_status("Resending...");
+                    app.form_submitting = true;
 
-                    tokio::spawn(async move {
+                    let tracked = dlq_ref.clone();
+                    let op_tx = tx.clone();
+                    let op = async move {
                         match dp.send_message(&base_path, &msg).await {
                             Ok(_) => {
-                                let (status, seq_removed) = if let Some(seq) = dlq_seq {
-                                    match dp.remove_from_dlq(&entity_path, seq).await {
-                                        Ok(true) => {
-                                            ("Resent and removed from DLQ".to_string(), Some(seq))
-                                        }
+                                let (status, dlq_removed) = if let Some(target) = dlq_ref {
+                                    match dp.remove_from_dlq(&entity_path, &target).await {
+                                        Ok(true) => (
+                                            "Resent and removed from DLQ".to_string(),
+                                            Some(target),
+                                        ),
                                         Ok(false) => (
                                             "Resent (DLQ message not found to remove)".to_string(),
                                             None,
@@ -820,9 +1860,17 @@ async fn run_app(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> anyho
                                 } else {
                                     ("Message resent successfully".to_string(), None)
                                 };
+                                let status = match window {
+                                    Some(window) => {
+                                        let note =
+                                            scheduled_note(mgmt.as_ref(), window, &base_path).await;
+                                        format!("{}, {}", status, note)
+                                    }
+                                    None => status,
+                                };
                                 let _ = tx.send(BgEvent::ResendSendComplete {
                                     status,
-                                    dlq_seq_removed: seq_removed,
+                                    dlq_removed,
                                     was_inline,
                                 });
                             }
@@ -830,7 +1878,13 @@ async fn run_app(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> anyho
                                 send_failed_with(&tx, "Resend failed", e);
                             }
                         }
-                    });
+                    };
+                    match tracked {
+                        Some(target) => spawn_message_op(op_tx, target, op),
+                        None => {
+                            tokio::spawn(op);
+                        }
+                    }
                 }
             }
         }
@@ -843,6 +1897,7 @@ async fn run_app(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> anyho
                 let tx = app.bg_tx.clone();
                 let name = desc.name.clone();
                 app.set_status("Creating queue...");
+                app.form_submitting = true;
 
                 spawn_entity_create(
                     tx,
@@ -861,6 +1916,7 @@ async fn run_app(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> anyho
                 let tx = app.bg_tx.clone();
                 let name = desc.name.clone();
                 app.set_status("Creating topic...");
+                app.form_submitting = true;
 
                 spawn_entity_create(
                     tx,
@@ -879,6 +1935,7 @@ async fn run_app(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> anyho
                 let tx = app.bg_tx.clone();
                 let name = desc.name.clone();
                 app.set_status("Creating subscription...");
+                app.form_submitting = true;
 
                 spawn_entity_create(tx, "Subscription", name, async move {
                     mgmt.create_subscription(&desc).await
@@ -886,6 +1943,174 @@ async fn run_app(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> anyho
             }
         }
 
+        // Submit entity property update (spawned)
+        if app.status_message == "Submitting..." {
+            if let ActiveModal::EditEntity { entity_type, path } = app.modal.clone() {
+                if let Some(mgmt) = app.management.as_ref().cloned() {
+                    let tx = app.bg_tx.clone();
+                    match app.entity_update_patches() {
+                        Some(patches) if patches.is_empty() => {
+                            app.set_status("Nothing changed");
+                            app.modal = ActiveModal::None;
+                        }
+                        Some(patches) => {
+                            app.set_status(format!("Updating '{}'...", path));
+                            app.form_submitting = true;
+                            spawn_entity_patch(tx, mgmt, &entity_type, path, patches);
+                        }
+                        None => app.set_error("Entity details are no longer loaded"),
+                    }
+                }
+            }
+        }
+
+        // Namespace-wide message search (spawned, cancellable) — peeks every
+        // queue/subscription and its DLQ up to `search_peek_cap`
+        if app.status_message == "Searching namespace..." && !app.bg_running {
+            if let (Some(dp), Some(mgmt)) = (app.data_plane.clone(), app.management.clone()) {
+                let query = app.input_buffer.trim().to_string();
+                let mut entities = app.searchable_entities();
+                // Topics not scrolled into view yet have no subscriptions in the tree
+                let unlisted = app.unlisted_topics();
+                let cap = app.config.settings.search_peek_cap;
+                let tx = app.bg_tx.clone();
+                let cancel = app.new_cancel_token();
+
+                app.start_bg_job("Search", None);
+                app.set_status(format!("Searching for '{}' (Esc to cancel)...", query));
+
+                tokio::spawn(async move {
+                    for topic in unlisted {
+                        if cancel.load(std::sync::atomic::Ordering::Relaxed) {
+                            break;
+                        }
+                        let subscriptions = mgmt
+                            .list_subscriptions_with_counts(&topic)
+                            .await
+                            .map_err(|e| e.to_string());
+                        if let Ok(ref subs) = subscriptions {
+                            entities
+                                .extend(subs.iter().map(|(s, _, _)| {
+                                    format!("{}/Subscriptions/{}", topic, s.name)
+                                }));
+                        }
+                        let _ = tx.send(BgEvent::NodeCountsLoaded {
+                            topic,
+                            subscriptions,
+                        });
+                    }
+                    let mut results = SearchResults {
+                        query,
+                        total: entities.len(),
+                        ..Default::default()
+                    };
+                    for entity in &entities {
+                        if cancel.load(std::sync::atomic::Ordering::Relaxed) {
+                            results.cancelled = true;
+                            break;
+                        }
+                        let dlq_path = format!("{}/$deadletterqueue", entity);
+                        let mut peeked_any = false;
+                        for (path, is_dlq) in [(entity.as_str(), false), (dlq_path.as_str(), true)]
+                        {
+                            if let Ok(msgs) = dp.peek_messages(path, cap).await {
+                                peeked_any = true;
+                                results.hits.extend(msgs.iter().filter_map(|m| {
+                                    SearchHit::from_match(entity, is_dlq, m, &results.query)
+                                }));
+                            }
+                        }
+                        if !peeked_any {
+                            results.failed += 1;
+                        }
+                        results.searched += 1;
+                        let _ = tx.send(BgEvent::JobProgress {
+                            processed: results.searched as u64,
+                            message: format!(
+                                "Searched {}/{} entities, {} match(es)... (Esc to cancel)",
+                                results.searched,
+                                results.total,
+                                results.hits.len()
+                            ),
+                        });
+                    }
+                    let _ = tx.send(BgEvent::SearchComplete(Box::new(results)));
+                });
+            }
+        }
+
+        // Browse sessions of a session-enabled entity (spawned, cancellable)
+        if app.status_message == "Browsing sessions..." && !app.bg_running {
+            if let (Some(entity_path), Some(dp)) =
+                (app.session_entity_path(), app.data_plane.clone())
+            {
+                let tx = app.bg_tx.clone();
+                let cancel = app.new_cancel_token();
+                let max_messages = app.config.settings.peek_max.max(1) as usize;
+
+                app.start_bg_job("Browsing sessions", Some(entity_path.clone()));
+                app.set_status(format!(
+                    "Browsing sessions on '{}' (Esc to stop)...",
+                    entity_path
+                ));
+
+                tokio::spawn(async move {
+                    match dp
+                        .browse_sessions(
+                            &entity_path,
+                            max_messages,
+                            MAX_BROWSED_SESSIONS,
+                            Some(cancel),
+                        )
+                        .await
+                    {
+                        Ok((mut messages, truncated)) => {
+                            for msg in &mut messages {
+                                msg.source_entity = Some(entity_path.clone());
+                            }
+                            let browse = SessionBrowse::new(entity_path, messages, truncated);
+                            let _ = tx.send(BgEvent::SessionsBrowsed(Box::new(browse)));
+                        }
+                        Err(e) => send_failed_with(&tx, "Session browse failed", e),
+                    }
+                });
+            }
+        }
+
+        // Submit forward-to change (spawned) — blank input clears forwarding
+        if app.status_message == "Updating forwarding..." {
+            if let ActiveModal::ForwardToInput { entity_type, path } = app.modal.clone() {
+                if let Some(mgmt) = app.management.as_ref().cloned() {
+                    let tx = app.bg_tx.clone();
+                    let target = app.input_buffer.trim().to_string();
+                    app.set_status(if target.is_empty() {
+                        format!("Clearing forwarding on '{}'...", path)
+                    } else {
+                        format!("Forwarding '{}' to '{}'...", path, target)
+                    });
+                    let patches = vec![("ForwardTo".to_string(), target)];
+                    spawn_entity_patch(tx, mgmt, &entity_type, path, patches);
+                }
+            }
+        }
+
+        // Submit entity status change (spawned) — only the Status element is
+        // sent, everything else stays as the service has it
+        if app.status_message == "Updating status..." {
+            if let ActiveModal::StatusSelect { entity_type, path } = app.modal.clone() {
+                if let Some(mgmt) = app.management.as_ref().cloned() {
+                    let tx = app.bg_tx.clone();
+                    let status = status_options(&entity_type)
+                        .get(app.status_selected)
+                        .unwrap_or(&"Active")
+                        .to_string();
+                    app.set_status(format!("Setting '{}' to {}...", path, status));
+                    let patches = vec![("Status".to_string(), status)];
+                    spawn_entity_patch(tx, mgmt, &entity_type, path, patches);
+                }
+            }
+        }
+
         // Load subscription filter rules (spawned)
         if app.status_message == "Loading subscription filters..."
             && app.management.is_some()
@@ -901,7 +2126,10 @@ async fn run_app(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> anyho
                         let mgmt = app.management.as_ref().cloned().unwrap();
                         let tx = app.bg_tx.clone();
 
-                        app.bg_running = true;
+                        app.start_bg_job(
+                            "Loading filters",
+                            Some(format!("{}/Subscriptions/{}", topic_name, sub_name)),
+                        );
                         app.set_status("Loading subscription filters...");
 
                         tokio::spawn(async move {
@@ -937,6 +2165,36 @@ async fn run_app(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> anyho
             }
         }
 
+        // Load entity authorization rules (spawned)
+        if app.status_message == "Loading authorization rules..."
+            && app.management.is_some()
+            && !app.bg_running
+        {
+            if let Some((entity_path, _)) = app.selected_entity() {
+                let entity_path = entity_path.to_string();
+                let mgmt = app.management.as_ref().cloned().unwrap();
+                let tx = app.bg_tx.clone();
+
+                app.start_bg_job("Loading SAS policies", Some(entity_path.clone()));
+                app.set_status(format!(
+                    "Loading authorization rules for '{}'...",
+                    entity_path
+                ));
+
+                tokio::spawn(async move {
+                    match mgmt.list_entity_authorization_rules(&entity_path).await {
+                        Ok(rules) => {
+                            let _ =
+                                tx.send(BgEvent::AuthorizationRulesLoaded { entity_path, rules });
+                        }
+                        Err(e) => {
+                            send_failed_with(&tx, "Failed to load authorization rules", e);
+                        }
+                    }
+                });
+            }
+        }
+
         // Submit subscription filter update (spawned)
         if app.status_message == "Submitting..." && app.modal == ActiveModal::EditSubscriptionFilter
         {
@@ -953,8 +2211,12 @@ async fn run_app(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> anyho
                                 app.build_subscription_filter_from_form();
                             let tx = app.bg_tx.clone();
 
-                            app.bg_running = true;
+                            app.start_bg_job(
+                                "Updating filter",
+                                Some(format!("{}/Subscriptions/{}", topic_name, sub_name)),
+                            );
                             app.set_status("Updating subscription filter...");
+                            app.form_submitting = true;
 
                             tokio::spawn(async move {
                                 match mgmt
@@ -995,14 +2257,21 @@ async fn run_app(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> anyho
         if app.status_message == "Loading destination entities..."
             && app.modal == ActiveModal::CopySelectEntity
         {
-            if let Some(conn_cfg) = app.copy_dest_connection_config.clone() {
+            if let (Some(conn_cfg), Some(connection)) = (
+                app.copy_dest_connection_config.clone(),
+                app.copy_dest_connection_name.clone(),
+            ) {
                 let tx = app.bg_tx.clone();
+                let http = app.http.clone();
 
-                app.bg_running = true;
+                app.start_bg_job("Loading destination entities", None);
                 tokio::spawn(async move {
-                    match App::fetch_destination_entities(conn_cfg).await {
+                    match App::fetch_destination_entities(conn_cfg, http).await {
                         Ok(entities) => {
-                            let _ = tx.send(BgEvent::DestinationEntitiesLoaded { entities });
+                            let _ = tx.send(BgEvent::DestinationEntitiesLoaded {
+                                connection,
+                                entities,
+                            });
                         }
                         Err(e) => {
                             send_failed_with(&tx, "Failed to load entities", e);
@@ -1021,14 +2290,15 @@ async fn run_app(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> anyho
             ) {
                 let msg = app.build_message_from_form();
                 let tx = app.bg_tx.clone();
+                let http = app.http.clone();
 
-                app.bg_running = true;
+                app.start_bg_job("Copy", Some(dest_entity.clone()));
                 app.modal = ActiveModal::None;
                 app.set_status("Copying...");
 
                 tokio::spawn(async move {
                     // Create temporary data plane client for destination
-                    let dest_dp = crate::client::DataPlaneClient::new(conn_cfg);
+                    let dest_dp = crate::client::DataPlaneClient::new(conn_cfg, http);
 
                     // Send to destination
                     match dest_dp.send_message(&dest_entity, &msg).await {
@@ -1055,62 +2325,116 @@ async fn run_app(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> anyho
         if app.status_message == "Bulk resending..." && app.data_plane.is_some() && !app.bg_running
         {
             if let ActiveModal::ConfirmBulkResend {
-                ref entity_path, ..
+                ref entity_path,
+                is_topic,
+                ..
             } = app.modal
             {
                 let entity_path = entity_path.clone();
                 let dp = app.data_plane.clone().unwrap();
                 let tx = app.bg_tx.clone();
                 let cancel = app.new_cancel_token();
-                let send_target = send_path_owned(&entity_path);
+                let send_target = app
+                    .selected_effective_send_entity()
+                    .unwrap_or_else(|| entity_path::send_target(&entity_path).to_string());
                 let messages = app.dlq_messages.clone();
+                let mgmt = app.management.clone();
+                let schedule = app.bulk_resend_delay.take();
+                let now = chrono::Utc::now();
 
-                app.bg_running = true;
+                app.start_bg_job("Resend", Some(entity_path.clone()));
                 app.modal = ActiveModal::None;
-                app.set_status(format!(
-                    "Resending {} peeked DLQ messages (Esc to cancel)...",
-                    messages.len()
-                ));
+                match schedule {
+                    Some(spec) => app.set_status(format!(
+                        "Resending {} peeked DLQ messages to appear {} (Esc to cancel)...",
+                        messages.len(),
+                        spec.window(now, messages.len()).unwrap_or_else(|e| e)
+                    )),
+                    None => app.set_status(format!(
+                        "Resending {} peeked DLQ messages (Esc to cancel)...",
+                        messages.len()
+                    )),
+                }
 
                 tokio::spawn(async move {
                     let mut resent = 0u32;
                     let mut errors = 0u32;
+                    let mut failures = Vec::new();
+                    let mut cancelled = false;
                     let total = messages.len();
 
-                    for msg in &messages {
+                    for (i, msg) in messages.iter().enumerate() {
+                        // Stop, but still report what failed before Esc
                         if cancel.load(std::sync::atomic::Ordering::Relaxed) {
-                            let _ = tx.send(BgEvent::Cancelled {
-                                message: format!(
-                                    "Cancelled after resending {} of {} messages ({} errors)",
-                                    resent, total, errors
-                                ),
-                            });
-                            return;
+                            cancelled = true;
+                            break;
                         }
 
-                        match dp.send_message(&send_target, &msg.to_sendable()).await {
+                        let mut outgoing = msg.to_sendable();
+                        let scheduled = match schedule {
+                            Some(spec) => spec.enqueue_time(now, i, total).map(Some),
+                            None => Ok(None),
+                        };
+                        let sent = match scheduled {
+                            Ok(at) => {
+                                outgoing.scheduled_enqueue_time =
+                                    at.map(delay::enqueue_time_header);
+                                dp.send_message(&send_target, &outgoing).await
+                            }
+                            Err(e) => Err(client::ServiceBusError::Operation(e)),
+                        };
+                        match sent {
                             Ok(_) => {
-                                // Remove original from DLQ by sequence number
+                                // Remove original from DLQ (sequence number + partition identity)
                                 let source = msg.source_entity.as_deref().unwrap_or(&entity_path);
-                                if let Some(seq) = msg.broker_properties.sequence_number {
-                                    let _ = dp.remove_from_dlq(source, seq).await;
+                                if let Some(target) = MessageRef::from_message(msg) {
+                                    let _ = dp.remove_from_dlq(source, &target).await;
                                 }
                                 resent += 1;
                             }
-                            Err(_) => {
+                            Err(e) => {
                                 errors += 1;
+                                failures.push(ResendFailure {
+                                    message: msg.clone(),
+                                    kind: e.kind(),
+                                    error: e.to_string(),
+                                });
                             }
                         }
 
                         if (resent + errors) > 1 && (resent + errors).is_multiple_of(10) {
-                            let _ = tx.send(BgEvent::Progress(format!(
-                                "Resent {}/{} messages ({} errors)... (Esc to cancel)",
-                                resent, total, errors
-                            )));
+                            let _ = tx.send(BgEvent::JobProgress {
+                                processed: (resent + errors) as u64,
+                                message: format!(
+                                    "Resent {}/{} messages ({} errors)... (Esc to cancel)",
+                                    resent, total, errors
+                                ),
+                            });
                         }
                     }
 
-                    let _ = tx.send(BgEvent::ResendComplete { resent, errors });
+                    let scheduled = match schedule {
+                        Some(spec) if resent > 0 => Some(
+                            scheduled_note(
+                                mgmt.as_ref(),
+                                spec.window(now, total).unwrap_or_else(|e| e),
+                                &send_target,
+                            )
+                            .await,
+                        ),
+                        _ => None,
+                    };
+                    let _ = tx.send(BgEvent::ResendComplete {
+                        resent,
+                        errors,
+                        cancelled,
+                        scheduled,
+                        failures: (!failures.is_empty()).then_some(ResendReport {
+                            entity_path,
+                            is_topic,
+                            failures,
+                        }),
+                    });
                 });
             }
         }
@@ -1122,6 +2446,7 @@ async fn run_app(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> anyho
                 count: _,
                 is_dlq,
                 is_topic,
+                ..
             } = app.modal
             {
                 let dp = app.data_plane.clone().unwrap();
@@ -1130,8 +2455,9 @@ async fn run_app(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> anyho
                 let tx = app.bg_tx.clone();
                 let cancel = app.new_cancel_token();
                 let mgmt = app.management.as_ref().cloned();
+                let concurrency = app.config.settings.purge_concurrency;
 
-                app.bg_running = true;
+                app.start_bg_job("Delete", Some(path.clone()));
                 app.modal = ActiveModal::None;
                 app.set_status("Purging messages...");
 
@@ -1148,7 +2474,7 @@ async fn run_app(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> anyho
                     let mut deleted = 0u64;
                     for delete_path in &paths {
                         match dp
-                            .purge_concurrent(delete_path, 32, Some(cancel.clone()), None)
+                            .purge_concurrent(delete_path, concurrency, Some(cancel.clone()), None)
                             .await
                         {
                             Ok(n) => deleted += n,
@@ -1185,5 +2511,60 @@ async fn run_app(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> anyho
         }
     }
 
+    // Finish the write in progress, then anything changed since
+    if let Some(task) = config_save.take() {
+        let _ = task.await;
+    }
+    if app.config.settings.restore_session && !app.demo {
+        let _ = session::SessionState::persist(app.session_state().as_ref());
+    }
+    // Closing the channel lets the log writer drain what is queued and stop
+    app.event_log = None;
+    if let Some(task) = log_writer {
+        let _ = task.await;
+    }
+    if let Some(config) = app.take_config_save(true) {
+        config
+            .save()
+            .map_err(|e| anyhow::anyhow!("Failed to save config: {}", e))?;
+    }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<CliArgs, String> {
+        parse_args(args.iter().map(|a| a.to_string()))
+    }
+
+    #[test]
+    fn version_and_help_flags_have_short_forms() {
+        for flag in ["-V", "--version"] {
+            let args = parse(&[flag]).unwrap();
+            assert!(args.version && !args.help, "{}", flag);
+        }
+        for flag in ["-h", "--help"] {
+            let args = parse(&[flag]).unwrap();
+            assert!(args.help && !args.version, "{}", flag);
+        }
+        let args = parse(&["--demo", "-h", "-V"]).unwrap();
+        assert!(args.demo && args.help && args.version);
+        assert!(!parse(&[]).unwrap().version);
+        assert!(version_line().starts_with(concat!(
+            "service-bus-explorer-tui ",
+            env!("CARGO_PKG_VERSION"),
+            " ("
+        )));
+    }
+
+    #[test]
+    fn options_with_values_need_them() {
+        let args = parse(&["--config", "x.toml", "--log-format=json"]).unwrap();
+        assert_eq!(args.config, Some("x.toml".into()));
+        assert!(args.json_log);
+        assert!(parse(&["--config"]).is_err());
+        assert!(parse(&["--log-format", "text"]).is_err());
+    }
+}
@@ -0,0 +1,199 @@
+use std::collections::BTreeSet;
+
+use serde_json::Value;
+
+use crate::body_format;
+use crate::client::models::ReceivedMessage;
+
+/// Above this many line pairs the LCS table gets too large; bodies are then
+/// compared line by line instead.
+const MAX_LCS_CELLS: usize = 4_000_000;
+
+/// One line of a body diff (left = first marked message).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffLine {
+    Same(String),
+    Removed(String),
+    Added(String),
+}
+
+/// A property compared across both messages; `None` where a message lacks it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PropertyDiff {
+    pub name: String,
+    pub left: Option<String>,
+    pub right: Option<String>,
+}
+
+impl PropertyDiff {
+    pub fn differs(&self) -> bool {
+        self.left != self.right
+    }
+}
+
+/// Line-level diff of two texts using a longest-common-subsequence table.
+pub fn diff_lines(left: &str, right: &str) -> Vec<DiffLine> {
+    let a: Vec<&str> = left.lines().collect();
+    let b: Vec<&str> = right.lines().collect();
+    let (n, m) = (a.len(), b.len());
+    if n.saturating_mul(m) > MAX_LCS_CELLS {
+        return positional_diff(&a, &b);
+    }
+
+    // lcs[i][j] = LCS length of a[i..] and b[j..]
+    let idx = |i: usize, j: usize| i * (m + 1) + j;
+    let mut lcs = vec![0u32; (n + 1) * (m + 1)];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[idx(i, j)] = if a[i] == b[j] {
+                lcs[idx(i + 1, j + 1)] + 1
+            } else {
+                lcs[idx(i + 1, j)].max(lcs[idx(i, j + 1)])
+            };
+        }
+    }
+
+    let mut out = Vec::with_capacity(n.max(m));
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if a[i] == b[j] {
+            out.push(DiffLine::Same(a[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if lcs[idx(i + 1, j)] >= lcs[idx(i, j + 1)] {
+            out.push(DiffLine::Removed(a[i].to_string()));
+            i += 1;
+        } else {
+            out.push(DiffLine::Added(b[j].to_string()));
+            j += 1;
+        }
+    }
+    out.extend(a[i..].iter().map(|l| DiffLine::Removed(l.to_string())));
+    out.extend(b[j..].iter().map(|l| DiffLine::Added(l.to_string())));
+    out
+}
+
+fn positional_diff(a: &[&str], b: &[&str]) -> Vec<DiffLine> {
+    let mut out = Vec::with_capacity(a.len().max(b.len()));
+    for k in 0..a.len().max(b.len()) {
+        match (a.get(k), b.get(k)) {
+            (Some(x), Some(y)) if x == y => out.push(DiffLine::Same(x.to_string())),
+            (x, y) => {
+                out.extend(x.map(|l| DiffLine::Removed(l.to_string())));
+                out.extend(y.map(|l| DiffLine::Added(l.to_string())));
+            }
+        }
+    }
+    out
+}
+
+/// Broker properties of both messages by name, skipping ones neither has.
+pub fn broker_property_diff(left: &ReceivedMessage, right: &ReceivedMessage) -> Vec<PropertyDiff> {
+    let to_map = |msg: &ReceivedMessage| match serde_json::to_value(&msg.broker_properties) {
+        Ok(Value::Object(map)) => map,
+        _ => serde_json::Map::new(),
+    };
+    let (a, b) = (to_map(left), to_map(right));
+    let names: BTreeSet<&String> = a.keys().chain(b.keys()).collect();
+    names
+        .into_iter()
+        .map(|name| PropertyDiff {
+            name: name.clone(),
+            left: a.get(name).and_then(value_text),
+            right: b.get(name).and_then(value_text),
+        })
+        .filter(|p| p.left.is_some() || p.right.is_some())
+        .collect()
+}
+
+/// Custom (application) properties of both messages aligned by key.
+pub fn custom_property_diff(left: &ReceivedMessage, right: &ReceivedMessage) -> Vec<PropertyDiff> {
+    let lookup = |msg: &ReceivedMessage, key: &str| {
+        msg.custom_properties
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.clone())
+    };
+    let keys: BTreeSet<&str> = left
+        .custom_properties
+        .iter()
+        .chain(&right.custom_properties)
+        .map(|(k, _)| k.as_str())
+        .collect();
+    keys.into_iter()
+        .map(|key| PropertyDiff {
+            name: key.to_string(),
+            left: lookup(left, key),
+            right: lookup(right, key),
+        })
+        .collect()
+}
+
+/// Body as compared: pretty-printed when it is valid JSON, otherwise as sent.
+pub fn comparable_body(msg: &ReceivedMessage) -> String {
+    body_format::format_json(&msg.body).unwrap_or_else(|_| msg.body.clone())
+}
+
+fn value_text(value: &Value) -> Option<String> {
+    match value {
+        Value::Null => None,
+        Value::String(s) => Some(s.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use DiffLine::*;
+
+    #[test]
+    fn diff_keeps_common_lines_in_order() {
+        let diff = diff_lines("a\nb\nc\nd", "a\nc\nd\ne");
+        assert_eq!(
+            diff,
+            vec![
+                Same("a".into()),
+                Removed("b".into()),
+                Same("c".into()),
+                Same("d".into()),
+                Added("e".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn diff_of_identical_and_empty_texts() {
+        assert!(diff_lines("x\ny", "x\ny")
+            .iter()
+            .all(|l| matches!(l, Same(_))));
+        assert_eq!(diff_lines("", "new"), vec![Added("new".into())]);
+    }
+
+    fn with_properties(props: &[(&str, &str)]) -> ReceivedMessage {
+        ReceivedMessage {
+            body: String::new(),
+            broker_properties: Default::default(),
+            custom_properties: props
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            lock_token_uri: None,
+            source_entity: None,
+            raw_body: None,
+        }
+    }
+
+    #[test]
+    fn custom_properties_align_by_key() {
+        let left = with_properties(&[("tenant", "a"), ("only", "x")]);
+        let right = with_properties(&[("tenant", "b")]);
+
+        let diff = custom_property_diff(&left, &right);
+        assert_eq!(diff.len(), 2);
+        assert_eq!(diff[0].name, "only");
+        assert_eq!(diff[0].right, None);
+        assert_eq!(diff[1].name, "tenant");
+        assert!(diff[1].differs());
+    }
+}
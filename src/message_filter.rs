@@ -0,0 +1,231 @@
+use serde::{Deserialize, Serialize};
+
+use crate::client::models::{BrokerProperties, ReceivedMessage};
+
+/// What part of a message a filter expression is matched against.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FilterScope {
+    Body,
+    BrokerProperties,
+    #[default]
+    CustomProperties,
+}
+
+impl FilterScope {
+    pub fn label(self) -> &'static str {
+        match self {
+            FilterScope::Body => "body",
+            FilterScope::BrokerProperties => "broker",
+            FilterScope::CustomProperties => "custom",
+        }
+    }
+
+    /// Next scope, for cycling with Tab.
+    pub fn next(self) -> Self {
+        match self {
+            FilterScope::Body => FilterScope::BrokerProperties,
+            FilterScope::BrokerProperties => FilterScope::CustomProperties,
+            FilterScope::CustomProperties => FilterScope::Body,
+        }
+    }
+}
+
+/// Quick filter over the peeked message lists.
+///
+/// For properties the expression is `name=value` (the property equals the
+/// value) or `name` (the property is set); for the body it is text the body
+/// contains. Names and values compare case-insensitively.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MessageFilter {
+    pub scope: FilterScope,
+    pub expression: String,
+}
+
+impl MessageFilter {
+    /// `custom: tenantId=contoso`
+    pub fn describe(&self) -> String {
+        format!("{}: {}", self.scope.label(), self.expression)
+    }
+
+    pub fn matches(&self, msg: &ReceivedMessage) -> bool {
+        let expression = self.expression.trim();
+        if expression.is_empty() {
+            return true;
+        }
+        if self.scope == FilterScope::Body {
+            return msg.body.to_lowercase().contains(&expression.to_lowercase());
+        }
+
+        let (name, wanted) = match expression.split_once('=') {
+            Some((name, value)) => (name.trim(), Some(value.trim())),
+            None => (expression, None),
+        };
+        let value = match self.scope {
+            FilterScope::CustomProperties => msg
+                .custom_properties
+                .iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(name))
+                .map(|(_, v)| v.clone()),
+            _ => broker_property(msg, name),
+        };
+        match (value, wanted) {
+            (Some(value), Some(wanted)) => value.eq_ignore_ascii_case(wanted),
+            (Some(_), None) => true,
+            (None, _) => false,
+        }
+    }
+}
+
+/// Text of a broker property, `None` when unset.
+type BrokerGetter = fn(&BrokerProperties) -> Option<String>;
+
+/// Broker properties by their wire name (`Label`, `DeadLetterReason`, ...).
+/// Read directly rather than by serializing every message while the list
+/// is filtered on each redraw; numbers print as serde_json would.
+const BROKER_PROPERTIES: &[(&str, BrokerGetter)] = &[
+    ("MessageId", |p| p.message_id.clone()),
+    ("CorrelationId", |p| p.correlation_id.clone()),
+    ("SessionId", |p| p.session_id.clone()),
+    ("Label", |p| p.label.clone()),
+    ("To", |p| p.to.clone()),
+    ("ReplyTo", |p| p.reply_to.clone()),
+    ("ReplyToSessionId", |p| p.reply_to_session_id.clone()),
+    ("ContentType", |p| p.content_type.clone()),
+    ("SequenceNumber", |p| {
+        p.sequence_number.map(|v| v.to_string())
+    }),
+    ("EnqueuedSequenceNumber", |p| {
+        p.enqueued_sequence_number.map(|v| v.to_string())
+    }),
+    ("EnqueuedTimeUtc", |p| p.enqueued_time_utc.clone()),
+    ("LockedUntilUtc", |p| p.locked_until_utc.clone()),
+    ("LockToken", |p| p.lock_token.clone()),
+    ("TimeToLive", |p| p.time_to_live.map(|v| format!("{:?}", v))),
+    ("DeliveryCount", |p| p.delivery_count.map(|v| v.to_string())),
+    ("DeadLetterSource", |p| p.dead_letter_source.clone()),
+    ("DeadLetterReason", |p| p.dead_letter_reason.clone()),
+    ("DeadLetterErrorDescription", |p| {
+        p.dead_letter_error_description.clone()
+    }),
+    ("State", |p| p.state.clone()),
+    ("PartitionKey", |p| p.partition_key.clone()),
+    ("ViaPartitionKey", |p| p.via_partition_key.clone()),
+    ("ScheduledEnqueueTimeUtc", |p| {
+        p.scheduled_enqueue_time_utc.clone()
+    }),
+    ("Size", |p| p.size.map(|v| v.to_string())),
+];
+
+/// Broker property `name` (case-insensitive) as text; unset properties are
+/// `None`.
+fn broker_property(msg: &ReceivedMessage, name: &str) -> Option<String> {
+    let (_, get) = BROKER_PROPERTIES
+        .iter()
+        .find(|(n, _)| n.eq_ignore_ascii_case(name))?;
+    get(&msg.broker_properties)
+}
+
+/// A named message filter kept in the config (`F` in the messages panel).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FilterPreset {
+    pub name: String,
+    #[serde(default)]
+    pub scope: FilterScope,
+    pub expression: String,
+}
+
+impl FilterPreset {
+    pub fn filter(&self) -> MessageFilter {
+        MessageFilter {
+            scope: self.scope,
+            expression: self.expression.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    fn message() -> ReceivedMessage {
+        ReceivedMessage {
+            body: r#"{"Customer":"Contoso"}"#.to_string(),
+            broker_properties: BrokerProperties {
+                label: Some("order.created".to_string()),
+                delivery_count: Some(3),
+                ..Default::default()
+            },
+            custom_properties: vec![("tenantId".to_string(), "contoso".to_string())],
+            lock_token_uri: None,
+            source_entity: None,
+            raw_body: None,
+        }
+    }
+
+    fn filter(scope: FilterScope, expression: &str) -> MessageFilter {
+        MessageFilter {
+            scope,
+            expression: expression.to_string(),
+        }
+    }
+
+    #[test]
+    fn matches_each_scope() {
+        let msg = message();
+        assert!(filter(FilterScope::CustomProperties, "TenantId = Contoso").matches(&msg));
+        assert!(filter(FilterScope::CustomProperties, "tenantId").matches(&msg));
+        assert!(!filter(FilterScope::CustomProperties, "tenantId=fabrikam").matches(&msg));
+        assert!(!filter(FilterScope::CustomProperties, "region").matches(&msg));
+
+        assert!(filter(FilterScope::BrokerProperties, "Label=order.created").matches(&msg));
+        assert!(filter(FilterScope::BrokerProperties, "deliverycount=3").matches(&msg));
+        // Unset broker properties don't match, even by name
+        assert!(!filter(FilterScope::BrokerProperties, "SessionId").matches(&msg));
+
+        assert!(filter(FilterScope::Body, "contoso").matches(&msg));
+        assert!(!filter(FilterScope::Body, "fabrikam").matches(&msg));
+        assert!(filter(FilterScope::Body, "  ").matches(&msg));
+    }
+
+    #[test]
+    fn broker_property_table_matches_the_wire_names() {
+        let props = BrokerProperties {
+            message_id: Some("m".into()),
+            correlation_id: Some("c".into()),
+            session_id: Some("s".into()),
+            label: Some("l".into()),
+            to: Some("t".into()),
+            reply_to: Some("r".into()),
+            reply_to_session_id: Some("rs".into()),
+            content_type: Some("ct".into()),
+            sequence_number: Some(1),
+            enqueued_sequence_number: Some(2),
+            enqueued_time_utc: Some("e".into()),
+            locked_until_utc: Some("lu".into()),
+            lock_token: Some("lt".into()),
+            time_to_live: Some(3600.0),
+            delivery_count: Some(3),
+            dead_letter_source: Some("ds".into()),
+            dead_letter_reason: Some("dr".into()),
+            dead_letter_error_description: Some("de".into()),
+            state: Some("Active".into()),
+            partition_key: Some("pk".into()),
+            via_partition_key: Some("vpk".into()),
+            scheduled_enqueue_time_utc: Some("se".into()),
+            size: Some(42),
+        };
+        let Ok(Value::Object(wire)) = serde_json::to_value(&props) else {
+            panic!("broker properties serialize to an object");
+        };
+        assert_eq!(wire.len(), BROKER_PROPERTIES.len());
+        for (name, get) in BROKER_PROPERTIES {
+            let expected = match &wire[*name] {
+                Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            assert_eq!(get(&props), Some(expected), "{}", name);
+        }
+    }
+}
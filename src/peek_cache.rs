@@ -0,0 +1,119 @@
+use std::time::Instant;
+
+use crate::app::MessageTab;
+use crate::client::models::ReceivedMessage;
+
+/// Most entities whose peek results are kept.
+const MAX_ENTITIES: usize = 20;
+/// Most message body bytes kept across all cached entities.
+const MAX_BODY_BYTES: usize = 50 * 1024 * 1024;
+
+/// One peeked tab of an entity that is no longer shown.
+#[derive(Debug, Clone)]
+pub struct CachedTab {
+    pub tab: MessageTab,
+    pub messages: Vec<ReceivedMessage>,
+    pub peeked_at: Instant,
+}
+
+impl CachedTab {
+    fn body_bytes(&self) -> usize {
+        self.messages.iter().map(|m| m.body_bytes().len()).sum()
+    }
+}
+
+/// Peek results per entity path, least recently used first. Entries are
+/// moved out when their entity is shown again, so nothing is held twice.
+#[derive(Debug, Default)]
+pub struct PeekCache {
+    entries: Vec<(String, Vec<CachedTab>)>,
+}
+
+impl PeekCache {
+    /// Remove and return everything cached for `path`.
+    pub fn take(&mut self, path: &str) -> Vec<CachedTab> {
+        match self.entries.iter().position(|(p, _)| p == path) {
+            Some(i) => self.entries.remove(i).1,
+            None => Vec::new(),
+        }
+    }
+
+    /// Cache one tab of `path`, replacing an older peek of the same tab, then
+    /// evict the least recently used entities over the budget.
+    pub fn insert(&mut self, path: &str, cached: CachedTab) {
+        let mut tabs = self.take(path);
+        tabs.retain(|t| t.tab != cached.tab);
+        tabs.push(cached);
+        self.entries.push((path.to_string(), tabs));
+        self.evict();
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    fn body_bytes(&self) -> usize {
+        self.entries
+            .iter()
+            .flat_map(|(_, tabs)| tabs)
+            .map(CachedTab::body_bytes)
+            .sum()
+    }
+
+    fn evict(&mut self) {
+        while self.entries.len() > MAX_ENTITIES
+            || (!self.entries.is_empty() && self.body_bytes() > MAX_BODY_BYTES)
+        {
+            self.entries.remove(0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tab(tab: MessageTab, body: &str) -> CachedTab {
+        CachedTab {
+            tab,
+            messages: vec![ReceivedMessage {
+                body: body.to_string(),
+                broker_properties: Default::default(),
+                custom_properties: Vec::new(),
+                lock_token_uri: None,
+                source_entity: None,
+                raw_body: None,
+            }],
+            peeked_at: Instant::now(),
+        }
+    }
+
+    #[test]
+    fn tabs_of_an_entity_are_kept_together() {
+        let mut cache = PeekCache::default();
+        cache.insert("orders", tab(MessageTab::Messages, "old"));
+        cache.insert("orders", tab(MessageTab::DeadLetter, "dlq"));
+        cache.insert("orders", tab(MessageTab::Messages, "new"));
+
+        let tabs = cache.take("orders");
+        assert_eq!(tabs.len(), 2);
+        assert_eq!(tabs[1].messages[0].body, "new");
+        assert!(cache.take("orders").is_empty());
+    }
+
+    #[test]
+    fn least_recently_used_entities_are_evicted() {
+        let mut cache = PeekCache::default();
+        for i in 0..=MAX_ENTITIES {
+            cache.insert(&format!("q{}", i), tab(MessageTab::Messages, "x"));
+        }
+        assert!(cache.take("q0").is_empty());
+        assert_eq!(cache.take("q1").len(), 1);
+
+        let big = "x".repeat(MAX_BODY_BYTES / 2 + 1);
+        cache.insert("a", tab(MessageTab::Messages, &big));
+        cache.insert("b", tab(MessageTab::Messages, &big));
+        assert!(cache.take("a").is_empty());
+        assert_eq!(cache.take("b").len(), 1);
+    }
+}
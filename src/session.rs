@@ -0,0 +1,110 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::app::{FocusPanel, MessageTab};
+use crate::config::{write_atomic, AppConfig};
+
+/// Where the user left off, saved on a clean exit while connected and
+/// restored on the next launch (`restore_session`, `--no-restore`).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SessionState {
+    /// Name of the saved connection that was active.
+    pub connection: String,
+    /// Entity path selected in the tree.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub selected_entity: Option<String>,
+    /// Expanded flag of each tree node, by node ID.
+    #[serde(default)]
+    pub expand_state: BTreeMap<String, bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message_tab: Option<MessageTab>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub focus: Option<FocusPanel>,
+}
+
+impl SessionState {
+    /// `session.toml`, next to the config file.
+    pub fn path() -> PathBuf {
+        AppConfig::config_path().with_file_name("session.toml")
+    }
+
+    /// The saved session, or `None` if there is none or it can't be read —
+    /// a broken session file just means a fresh start.
+    pub fn load() -> Option<Self> {
+        Self::load_from(&Self::path())
+    }
+
+    fn load_from(path: &Path) -> Option<Self> {
+        let content = std::fs::read_to_string(path).ok()?;
+        Self::parse(&content)
+    }
+
+    fn parse(content: &str) -> Option<Self> {
+        toml::from_str::<Self>(content)
+            .ok()
+            .filter(|s| !s.connection.is_empty())
+    }
+
+    /// Save `session` for the next launch. `None` (quit while disconnected)
+    /// removes the saved one, so a connection left behind isn't reopened.
+    pub fn persist(session: Option<&Self>) -> anyhow::Result<()> {
+        Self::persist_at(&Self::path(), session)
+    }
+
+    fn persist_at(path: &Path, session: Option<&Self>) -> anyhow::Result<()> {
+        match session {
+            Some(session) => write_atomic(path, &toml::to_string_pretty(session)?)?,
+            None => match std::fs::remove_file(path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+                _ => {}
+            },
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sessions_round_trip_and_bad_files_are_ignored() {
+        let session = SessionState {
+            connection: "prod".to_string(),
+            selected_entity: Some("orders/Subscriptions/audit".to_string()),
+            expand_state: [("t:orders".to_string(), true)].into_iter().collect(),
+            message_tab: Some(MessageTab::DeadLetter),
+            focus: Some(FocusPanel::Messages),
+        };
+        let text = toml::to_string_pretty(&session).unwrap();
+        assert_eq!(SessionState::parse(&text), Some(session));
+
+        assert_eq!(
+            SessionState::parse("connection = \"prod\"\nfocus = 7"),
+            None
+        );
+        assert_eq!(SessionState::parse("not toml ["), None);
+        assert_eq!(SessionState::parse("connection = \"\""), None);
+    }
+
+    #[test]
+    fn quitting_while_disconnected_removes_the_saved_session() {
+        let dir = std::env::temp_dir().join(format!("sb-session-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("session.toml");
+        let session = SessionState {
+            connection: "prod".to_string(),
+            ..Default::default()
+        };
+
+        SessionState::persist_at(&path, Some(&session)).unwrap();
+        assert_eq!(SessionState::load_from(&path), Some(session));
+        SessionState::persist_at(&path, None).unwrap();
+        assert!(!path.exists());
+        assert_eq!(SessionState::load_from(&path), None);
+        // Nothing saved to remove is fine too
+        SessionState::persist_at(&path, None).unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
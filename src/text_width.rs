@@ -0,0 +1,131 @@
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Byte offset of the `char_idx`-th character of `s` (or `s.len()` past the end).
+pub fn byte_offset(s: &str, char_idx: usize) -> usize {
+    s.char_indices()
+        .nth(char_idx)
+        .map(|(i, _)| i)
+        .unwrap_or(s.len())
+}
+
+/// Terminal columns `s` occupies (wide CJK/emoji count 2, combining marks 0).
+pub fn width(s: &str) -> usize {
+    s.width()
+}
+
+/// Column of a cursor sitting before the `char_idx`-th character of `s`.
+pub fn cursor_column(s: &str, char_idx: usize) -> usize {
+    width(&s[..byte_offset(s, char_idx)])
+}
+
+/// Byte offset of the character at display column `column` of `line`. A column
+/// inside a wide character resolves to that character's start; past the end,
+/// to `line.len()`.
+pub fn byte_at_column(line: &str, column: usize) -> usize {
+    let mut used = 0;
+    for (i, c) in line.char_indices() {
+        let w = c.width().unwrap_or(0);
+        if used + w > column {
+            return i;
+        }
+        used += w;
+    }
+    line.len()
+}
+
+/// Cut `s` to at most `max_width` columns, ending in `…` when anything was cut.
+pub fn truncate_to_width(s: &str, max_width: usize) -> String {
+    if width(s) <= max_width {
+        return s.to_string();
+    }
+    let budget = max_width.saturating_sub(1);
+    let mut out = String::new();
+    let mut used = 0;
+    for c in s.chars() {
+        let w = c.width().unwrap_or(0);
+        if used + w > budget {
+            break;
+        }
+        used += w;
+        out.push(c);
+    }
+    out.push('…');
+    out
+}
+
+/// `text` broken into lines of at most `width` columns; a character wider
+/// than that gets a line of its own, and blank lines are kept.
+pub fn wrap_to_width(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    for line in text.lines() {
+        if line.is_empty() {
+            lines.push(String::new());
+        }
+        let mut rest = line;
+        while !rest.is_empty() {
+            let mut cut = byte_at_column(rest, width);
+            if cut == 0 {
+                cut = rest.chars().next().map_or(rest.len(), char::len_utf8);
+            }
+            lines.push(rest[..cut].to_string());
+            rest = &rest[cut..];
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wide_characters_take_two_columns() {
+        assert_eq!(width("abc"), 3);
+        assert_eq!(width("日本"), 4);
+        assert_eq!(width("🚀"), 2);
+        // "e" + COMBINING ACUTE ACCENT renders as one cell
+        assert_eq!(width("e\u{301}"), 1);
+    }
+
+    #[test]
+    fn cursor_column_counts_display_cells() {
+        let s = "ns-日本🚀x";
+        assert_eq!(cursor_column(s, 3), 3);
+        assert_eq!(cursor_column(s, 5), 7);
+        assert_eq!(cursor_column(s, 6), 9);
+        assert_eq!(cursor_column(s, 99), 10);
+        assert_eq!(cursor_column("cafe\u{301}!", 5), 4);
+    }
+
+    #[test]
+    fn byte_at_column_lands_on_char_boundaries() {
+        let line = "a日b";
+        assert_eq!(byte_at_column(line, 0), 0);
+        assert_eq!(byte_at_column(line, 1), 1);
+        // Column 2 is the right half of 日
+        assert_eq!(byte_at_column(line, 2), 1);
+        assert_eq!(byte_at_column(line, 3), 4);
+        assert_eq!(byte_at_column(line, 10), line.len());
+    }
+
+    #[test]
+    fn truncate_never_splits_a_glyph() {
+        assert_eq!(truncate_to_width("short", 10), "short");
+        assert_eq!(truncate_to_width("abcdef", 4), "abc…");
+        // 日本語 is 6 columns; a 4-column budget fits one wide char plus …
+        assert_eq!(truncate_to_width("日本語", 4), "日…");
+        assert_eq!(truncate_to_width("🚀🚀🚀", 5), "🚀🚀…");
+    }
+
+    #[test]
+    fn wrap_breaks_lines_by_display_width() {
+        assert_eq!(
+            wrap_to_width("abcdef\n\ngh", 4),
+            vec!["abcd", "ef", "", "gh"]
+        );
+        assert_eq!(wrap_to_width("日本語", 4), vec!["日本", "語"]);
+        // A character wider than the column still makes progress
+        assert_eq!(wrap_to_width("日本", 1), vec!["日", "本"]);
+        assert!(wrap_to_width("", 4).is_empty());
+    }
+}
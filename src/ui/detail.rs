@@ -3,6 +3,14 @@ use ratatui::widgets::*;
 use ratatui::Frame;
 
 use crate::app::{App, DetailView, FocusPanel};
+use crate::client::models::{FieldChange, SizeUsage, SubscriptionRoute};
+
+use super::sanitize::sanitize_for_terminal;
+use crate::text_width::{truncate_to_width, wrap_to_width};
+
+/// Label and value columns of the property table.
+const COLUMN_WIDTHS: [Constraint; 2] = [Constraint::Percentage(35), Constraint::Percentage(65)];
+const COLUMN_SPACING: u16 = 1;
 
 pub fn render_detail(frame: &mut Frame, app: &App, area: Rect) {
     let is_focused = app.focus == FocusPanel::Detail;
@@ -12,11 +20,24 @@ pub fn render_detail(frame: &mut Frame, app: &App, area: Rect) {
         Style::default().fg(Color::DarkGray)
     };
 
+    let dead_letter = app
+        .selected_is_dead_letter_node()
+        .then(|| dead_letter_rows(&app.detail_view))
+        .flatten();
     let block = Block::default()
-        .title(" Properties ")
+        .title(if dead_letter.is_some() {
+            " Dead-letter Queue "
+        } else {
+            " Properties "
+        })
         .borders(Borders::ALL)
         .border_style(border_style);
 
+    if let Some(rows) = dead_letter {
+        render_table(frame, area, block, rows);
+        return;
+    }
+
     match &app.detail_view {
         DetailView::None => {
             let msg = Paragraph::new("Select an entity to view properties")
@@ -52,6 +73,9 @@ pub fn render_detail(frame: &mut Frame, app: &App, area: Rect) {
             if let Some(ref fwd) = desc.forward_dead_lettered_messages_to {
                 rows.push(make_row("Fwd DLQ To", fwd));
             }
+            rows.extend(user_metadata_row(app, &desc.user_metadata, area));
+
+            rows.extend(diff_rows(app.visible_detail_changes()));
 
             if let Some(rt) = runtime {
                 rows.push(make_row("──────────", "──────────"));
@@ -67,12 +91,13 @@ pub fn render_detail(frame: &mut Frame, app: &App, area: Rect) {
                     "Scheduled",
                     &rt.scheduled_message_count.to_string(),
                 ));
-                rows.push(make_row("Size (bytes)", &rt.size_in_bytes.to_string()));
+                rows.push(size_row(rt.size_in_bytes, desc.max_size_in_megabytes));
             }
+            rows.extend(metrics_row(app, &desc.name, "In / Out (1h)"));
 
             render_table(frame, area, block, rows);
         }
-        DetailView::Topic(desc, runtime) => {
+        DetailView::Topic(desc, runtime, routes) => {
             let mut rows = vec![
                 make_row("Name", &desc.name),
                 make_row("Status", desc.status.as_deref().unwrap_or("Active")),
@@ -83,6 +108,9 @@ pub fn render_detail(frame: &mut Frame, app: &App, area: Rect) {
                 ),
                 make_row("Partitioning", &opt_bool(desc.enable_partitioning)),
             ];
+            rows.extend(user_metadata_row(app, &desc.user_metadata, area));
+
+            rows.extend(diff_rows(app.visible_detail_changes()));
 
             if let Some(rt) = runtime {
                 rows.push(make_row("──────────", "──────────"));
@@ -102,10 +130,24 @@ pub fn render_detail(frame: &mut Frame, app: &App, area: Rect) {
                     "Scheduled",
                     &rt.scheduled_message_count.to_string(),
                 ));
-                rows.push(make_row("Size (bytes)", &rt.size_in_bytes.to_string()));
+                rows.push(size_row(rt.size_in_bytes, desc.max_size_in_megabytes));
             }
+            rows.extend(metrics_row(app, &desc.name, "In / Out (1h)"));
 
-            render_table(frame, area, block, rows);
+            match routes {
+                Some(routes) => {
+                    let inner = block.inner(area);
+                    frame.render_widget(block, area);
+                    let lines = routing_lines(&desc.name, routes, app.route_selected, is_focused);
+                    let layout = Layout::default()
+                        .direction(Direction::Vertical)
+                        .constraints([Constraint::Min(3), Constraint::Length(lines.len() as u16)])
+                        .split(inner);
+                    render_table(frame, layout[0], Block::default(), rows);
+                    frame.render_widget(Paragraph::new(lines), layout[1]);
+                }
+                None => render_table(frame, area, block, rows),
+            }
         }
         DetailView::Subscription(desc, runtime) => {
             let mut rows = vec![
@@ -126,6 +168,9 @@ pub fn render_detail(frame: &mut Frame, app: &App, area: Rect) {
             if let Some(ref fwd) = desc.forward_to {
                 rows.push(make_row("Forward To", fwd));
             }
+            rows.extend(user_metadata_row(app, &desc.user_metadata, area));
+
+            rows.extend(diff_rows(app.visible_detail_changes()));
 
             if let Some(rt) = runtime {
                 rows.push(make_row("──────────", "──────────"));
@@ -138,12 +183,235 @@ pub fn render_detail(frame: &mut Frame, app: &App, area: Rect) {
                     &rt.dead_letter_message_count.to_string(),
                 ));
             }
+            rows.extend(metrics_row(app, &desc.topic_name, "Topic In / Out (1h)"));
+
+            render_table(frame, area, block, rows);
+        }
+        DetailView::Namespace(name, rules, info) => {
+            let mut rows = vec![make_row("Namespace", name)];
+
+            if let Some(info) = info {
+                rows.push(make_row("SKU", info.sku_name.as_deref().unwrap_or("-")));
+                rows.push(make_row("Tier", info.sku_tier.as_deref().unwrap_or("-")));
+                rows.push(make_row("Capacity (MU)", &opt_i64(info.capacity)));
+                rows.push(make_row(
+                    "Provisioning",
+                    info.provisioning_state.as_deref().unwrap_or("-"),
+                ));
+                rows.push(make_row(
+                    "Created",
+                    info.created_at.as_deref().unwrap_or("-"),
+                ));
+                rows.push(make_row(
+                    "Updated",
+                    info.updated_at.as_deref().unwrap_or("-"),
+                ));
+            } else if let Some(ref ns) = app.namespace_info {
+                // Without ARM access, fall back to what $namespaceinfo reports
+                rows.push(make_row("Tier", ns.messaging_sku.as_deref().unwrap_or("-")));
+                rows.push(make_row("Messaging Units", &opt_i64(ns.messaging_units)));
+                rows.push(make_row("Created", ns.created_at.as_deref().unwrap_or("-")));
+            }
+
+            rows.push(make_row("──────────", "──────────"));
+            match rules {
+                Some(rules) if !rules.is_empty() => {
+                    for rule in rules {
+                        rows.push(make_row(&rule.key_name, &rule.rights.join(", ")));
+                    }
+                }
+                Some(_) => rows.push(make_row("SAS Policies", "none")),
+                None => rows.push(make_row("SAS Policies", "unavailable")),
+            }
 
             render_table(frame, area, block, rows);
         }
     }
 }
 
+/// Topic → subscription routing diagram:
+///
+/// ```text
+/// [Topic: orders]
+///  ├→ [Sub: payments (SQL: type='payment')]
+///  └→ [Sub: all (TrueFilter)]
+/// ```
+fn routing_lines(
+    topic: &str,
+    routes: &[SubscriptionRoute],
+    selected: usize,
+    is_focused: bool,
+) -> Vec<Line<'static>> {
+    let san = |s: &str| sanitize_for_terminal(s, false);
+    let mut lines = vec![
+        Line::from(Span::styled(
+            "── Routing (j/k select · R jump to subscription) ──",
+            Style::default().fg(Color::DarkGray),
+        )),
+        Line::from(Span::styled(
+            format!("[Topic: {}]", san(topic)),
+            Style::default().fg(Color::Cyan).bold(),
+        )),
+    ];
+    if routes.is_empty() {
+        lines.push(Line::from(Span::styled(
+            " └ (no subscriptions)",
+            Style::default().fg(Color::DarkGray),
+        )));
+        return lines;
+    }
+    for (idx, route) in routes.iter().enumerate() {
+        let branch = if idx + 1 == routes.len() {
+            " └→ "
+        } else {
+            " ├→ "
+        };
+        let filters = match route.rules {
+            Some(ref rules) if !rules.is_empty() => rules
+                .iter()
+                .map(|r| r.filter_label())
+                .collect::<Vec<_>>()
+                .join(" | "),
+            Some(_) => "no rules".to_string(),
+            None => "rules unavailable".to_string(),
+        };
+        let style = if idx == selected && is_focused {
+            Style::default().bg(Color::DarkGray).fg(Color::White)
+        } else if idx == selected {
+            Style::default().fg(Color::Cyan)
+        } else {
+            Style::default()
+        };
+        lines.push(Line::from(vec![
+            Span::styled(branch, Style::default().fg(Color::DarkGray)),
+            Span::styled(
+                format!("[Sub: {} ({})]", san(&route.name), san(&filters)),
+                style,
+            ),
+        ]));
+    }
+    lines
+}
+
+/// Highlighted "Field: before → after" rows for recently changed settings.
+/// What a "Dead-letter" node shows: its own counts and the parent settings
+/// that decide what ends up there. `None` until the parent's detail loaded.
+fn dead_letter_rows(view: &DetailView) -> Option<Vec<Row<'static>>> {
+    let (owner, max_delivery, on_expiry, forward, counts) = match view {
+        DetailView::Queue(desc, runtime) => (
+            desc.name.clone(),
+            desc.max_delivery_count,
+            desc.dead_lettering_on_message_expiration,
+            &desc.forward_dead_lettered_messages_to,
+            runtime.as_ref().map(|rt| {
+                (
+                    rt.dead_letter_message_count,
+                    rt.transfer_dead_letter_message_count,
+                )
+            }),
+        ),
+        DetailView::Subscription(desc, runtime) => (
+            format!("{}/{}", desc.topic_name, desc.name),
+            desc.max_delivery_count,
+            desc.dead_lettering_on_message_expiration,
+            &desc.forward_dead_lettered_messages_to,
+            runtime.as_ref().map(|rt| {
+                (
+                    rt.dead_letter_message_count,
+                    rt.transfer_dead_letter_message_count,
+                )
+            }),
+        ),
+        _ => return None,
+    };
+    let mut rows = vec![make_row("Dead-letter of", &owner)];
+    if let Some((dead_letter, transfer_dead_letter)) = counts {
+        rows.push(make_row("Messages", &dead_letter.to_string()));
+        rows.push(make_row("Transfer DLQ", &transfer_dead_letter.to_string()));
+    }
+    rows.push(make_row("──────────", "──────────"));
+    rows.push(make_row("Max Delivery Count", &opt_i32(max_delivery)));
+    rows.push(make_row("DLQ on Expiry", &opt_bool(on_expiry)));
+    rows.push(make_row("Fwd DLQ To", forward.as_deref().unwrap_or("-")));
+    Some(rows)
+}
+
+fn diff_rows(changes: &[FieldChange]) -> Vec<Row<'static>> {
+    if changes.is_empty() {
+        return Vec::new();
+    }
+    let style = Style::default().fg(Color::Yellow).bold();
+    let mut rows = vec![make_row("── Changed ──", "(Esc to dismiss)").style(style)];
+    rows.extend(
+        changes
+            .iter()
+            .map(|c| make_row(&c.field, &format!("{} → {}", c.before, c.after)).style(style)),
+    );
+    rows
+}
+
+/// Operator-set `UserMetadata` in yellow, apart from broker properties: one
+/// line cut to the value column, or with `U` the whole value wrapped to it.
+fn user_metadata_row(app: &App, metadata: &Option<String>, area: Rect) -> Option<Row<'static>> {
+    let value = metadata
+        .as_deref()
+        .map(str::trim)
+        .filter(|v| !v.is_empty())?;
+    let style = Style::default().fg(Color::Yellow);
+    let width = value_column_width(area);
+
+    if !app.user_metadata_expanded {
+        let flat = sanitize_for_terminal(value, false);
+        return Some(make_row("User Metadata", &truncate_to_width(&flat, width)).style(style));
+    }
+    let lines: Vec<Line> = wrap_to_width(&sanitize_for_terminal(value, true), width)
+        .into_iter()
+        .map(Line::from)
+        .collect();
+    let height = lines.len().max(1) as u16;
+    Some(
+        Row::new(vec![
+            Cell::from("User Metadata"),
+            Cell::from(Text::from(lines)),
+        ])
+        .height(height)
+        .style(style),
+    )
+}
+
+/// Width of the value column when `render_table` draws into `area`, laid out
+/// the way the table does it.
+fn value_column_width(area: Rect) -> usize {
+    let inner = Block::default().borders(Borders::ALL).inner(area);
+    let columns = Layout::horizontal(COLUMN_WIDTHS)
+        .flex(layout::Flex::Start)
+        .spacing(COLUMN_SPACING)
+        .split(inner);
+    usize::from(columns[1].width).max(1)
+}
+
+/// Size against the entity's quota, red once it is nearly full.
+fn size_row(size_in_bytes: i64, max_size_in_megabytes: Option<i64>) -> Row<'static> {
+    match SizeUsage::new(size_in_bytes, max_size_in_megabytes) {
+        Some(usage) if usage.is_near_quota() => {
+            make_row("Size", &format!("{} ⚠ nearly full", usage))
+                .style(Style::default().fg(Color::Red).bold())
+        }
+        Some(usage) => make_row("Size", &usage.to_string()),
+        None => make_row("Size (bytes)", &size_in_bytes.to_string()),
+    }
+}
+
+/// Last-hour incoming/outgoing totals of `entity`, once ARM metrics arrived.
+fn metrics_row(app: &App, entity: &str, label: &str) -> Option<Row<'static>> {
+    let metrics = app.metrics_for(entity)?;
+    let count = |v: Option<f64>| v.map(|v| format!("{:.0}", v)).unwrap_or_else(|| "-".into());
+    Some(make_row(
+        label,
+        &format!("{} / {}", count(metrics.incoming), count(metrics.outgoing)),
+    ))
+}
+
 fn make_row(label: &str, value: &str) -> Row<'static> {
     Row::new(vec![label.to_string(), value.to_string()])
 }
@@ -161,12 +429,34 @@ fn opt_bool(v: Option<bool>) -> String {
 }
 
 fn render_table(frame: &mut Frame, area: Rect, block: Block, rows: Vec<Row>) {
-    let table = Table::new(
-        rows,
-        [Constraint::Percentage(35), Constraint::Percentage(65)],
-    )
-    .block(block)
-    .column_spacing(1);
+    let table = Table::new(rows, COLUMN_WIDTHS)
+        .block(block)
+        .column_spacing(COLUMN_SPACING);
 
     frame.render_widget(table, area);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn value_column_matches_the_rendered_table() {
+        let area = Rect::new(0, 0, 62, 10);
+        let width = value_column_width(area);
+        let mut buffer = Buffer::empty(area);
+        let rows = vec![make_row("Label", &"x".repeat(100))];
+        Widget::render(
+            Table::new(rows, COLUMN_WIDTHS)
+                .block(Block::default().borders(Borders::ALL))
+                .column_spacing(COLUMN_SPACING),
+            area,
+            &mut buffer,
+        );
+        let row: String = (0..area.width)
+            .map(|x| buffer[(x, 1)].symbol().to_string())
+            .collect();
+        assert_eq!(row.matches('x').count(), width);
+        assert_eq!(value_column_width(Rect::new(0, 0, 2, 2)), 1);
+    }
+}
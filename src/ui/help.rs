@@ -11,7 +11,23 @@ pub fn render_help(frame: &mut Frame) {
         .borders(Borders::ALL)
         .border_style(Style::default().fg(Color::Cyan));
 
-    let help_text = vec![
+    let paragraph = Paragraph::new(help_lines()).block(block);
+    frame.render_widget(paragraph, area);
+}
+
+/// The key bindings as plain text, for `--help`.
+pub fn help_text() -> String {
+    help_lines()
+        .iter()
+        .map(|line| {
+            let text: String = line.spans.iter().map(|s| s.content.as_ref()).collect();
+            format!("{}\n", text.trim_end())
+        })
+        .collect()
+}
+
+fn help_lines() -> Vec<Line<'static>> {
+    vec![
         Line::from(""),
         Line::from(vec![Span::styled(
             "  Navigation",
@@ -22,59 +38,109 @@ pub fn render_help(frame: &mut Frame) {
         Line::from("  Tab/Shift+Tab  Switch panels"),
         Line::from("  Enter          Select/Expand"),
         Line::from("  g/G            First/Last item"),
+        Line::from("  3j, 10k, 5G    Count first: move that many rows / go to row 5"),
+        Line::from(Span::styled(
+            "                 (tree & messages; 20p peeks 20; Esc clears the count)",
+            Style::default().fg(Color::DarkGray),
+        )),
         Line::from(""),
         Line::from(vec![Span::styled(
             "  Connection",
             Style::default().fg(Color::Cyan).bold(),
         )]),
         Line::from("  c              Connect / Switch connection"),
+        Line::from("  R / X          Rename / delete active connection (after c)"),
+        Line::from("  G              Set group of saved connection (in list)"),
+        Line::from("  t / f          Test all saved connections / show failed only (in list)"),
+        Line::from("  Ctrl+R         Rediscover namespaces, ignoring the cache (Azure AD list)"),
         Line::from("  r / F5         Refresh entities"),
+        Line::from("  H (shift)      Health check: ping the management API"),
+        Line::from("  L (shift)      Request latency / errors per API"),
+        Line::from("  Esc            Skip restoring the last session (at launch)"),
         Line::from(""),
         Line::from(vec![Span::styled(
             "  Entity Operations",
             Style::default().fg(Color::Cyan).bold(),
         )]),
-        Line::from("  n              Create new entity"),
+        Line::from("  n              Create new entity (on a topic: sub or topic)"),
         Line::from("  x              Delete selected entity"),
+        Line::from("  D (shift)      Delete / resend messages (on a Dead-letter node)"),
         Line::from("  f              Edit selected subscription filter"),
+        Line::from("  a              View SAS policies (read-only)"),
+        Line::from("  Y              Copy entity path (or Azure Portal URL)"),
+        Line::from("  E (shift)      Edit entity properties (detail panel)"),
+        Line::from("  T (shift)      Change entity status (detail panel)"),
+        Line::from("  F (shift)      Set / clear auto-forwarding (detail panel)"),
+        Line::from("  U (shift)      Expand / collapse User Metadata (detail panel)"),
+        Line::from("  S (shift)      Browse sessions (session-enabled entity detail)"),
+        Line::from("  R (shift)      Jump to routed subscription (topic detail)"),
         Line::from(""),
         Line::from(vec![Span::styled(
             "  Message Operations",
             Style::default().fg(Color::Cyan).bold(),
         )]),
         Line::from("  p              Peek messages (prompts for count)"),
+        Line::from(Span::styled(
+            "                 (1/2/3 = 10/100/1000, a = all, c = custom)",
+            Style::default().fg(Color::DarkGray),
+        )),
         Line::from("  d              Peek dead-letter queue"),
+        Line::from("  b              Peek active + dead-letter together (tab stays)"),
         Line::from("  s              Send message"),
         Line::from("  P (shift)      Clear entity (delete all / resend DLQ)"),
         Line::from(Span::styled(
             "                 (on topics: operates across all subs)",
             Style::default().fg(Color::DarkGray),
         )),
-        Line::from("  1/2            Switch Messages/DLQ tab"),
+        Line::from("  1/2            Switch Messages/DLQ tab (digits once a count is typed)"),
+        Line::from("  4              Peek transfer DLQ (when it has messages)"),
+        Line::from("  ]              Peek one more page (5] five more; re-peeks from the head)"),
         Line::from("  Enter          View message detail"),
         Line::from("  Esc            Close message detail"),
+        Line::from("  W (shift)      Toggle body wrap (h/l scroll when off)"),
+        Line::from("  M (shift)      Cycle body format (JSON / XML / CSV / JWT / text / hex)"),
+        Line::from("  J (shift)      Decode a JWT body into header and claims"),
+        Line::from("  X (shift)      Hex + ASCII dump of the body"),
+        Line::from(
+            "  v              Browse custom properties (j/k, full value, PgUp/PgDn, y = copy)",
+        ),
+        Line::from("  m / =          Mark two messages / compare them"),
+        Line::from("  O (shift)      Order by expiry, soonest first (toggle)"),
+        Line::from("  F (shift)      Message filters: apply / save presets"),
+        Line::from("  Ctrl+O         Choose / reorder message columns"),
+        Line::from("  Ctrl+G         Forwarding graph (Enter = go to entity)"),
         Line::from(""),
         Line::from(vec![Span::styled(
             "  Bulk Operations (Messages panel)",
             Style::default().fg(Color::Cyan).bold(),
         )]),
-        Line::from("  R (shift)      Resend peeked DLQ → main entity"),
+        Line::from("  R (shift)      Resend peeked DLQ → main entity (d = delay)"),
+        Line::from("  Ctrl+E         Failures of the last bulk resend"),
+        Line::from("  S (shift)      DLQ reason breakdown / filter"),
+        Line::from("  G (shift)      Group DLQ by reason (Enter expands, Esc back)"),
         Line::from("  D (shift)      Bulk delete messages"),
         Line::from(Span::styled(
             "                 (on topics: fan-out across all subs)",
             Style::default().fg(Color::DarkGray),
         )),
+        Line::from("  t (in confirm) Dry run: count what would be touched"),
         Line::from("  e              Edit & resend (inline WYSIWYG)"),
         Line::from(vec![
             Span::styled("  C       ", Style::default().fg(Color::Yellow)),
             Span::raw("Copy message to different connection"),
         ]),
+        Line::from(Span::styled(
+            "                 (picker: type to filter, Ctrl+S same name, Ctrl+R refresh)",
+            Style::default().fg(Color::DarkGray),
+        )),
         Line::from(""),
         Line::from(vec![Span::styled(
             "  Editing (inline & forms)",
             Style::default().fg(Color::Cyan).bold(),
         )]),
         Line::from("  F2             Send / submit"),
+        Line::from("  Ctrl+F         Format JSON body"),
+        Line::from("  F3             Forget remembered send fields"),
         Line::from("  ←/→/Home/End   Move cursor in field"),
         Line::from("  Tab/↑↓         Navigate between fields"),
         Line::from("  Esc            Cancel editing"),
@@ -84,12 +150,13 @@ pub fn render_help(frame: &mut Frame) {
             Style::default().fg(Color::Cyan).bold(),
         )]),
         Line::from("  ?              Show this help"),
-        Line::from("  q / Ctrl+C     Quit"),
+        Line::from("  ,              Settings editor"),
+        Line::from("  /              Search namespace by MessageId / CorrelationId"),
+        Line::from("  q / Ctrl+C     Quit (asks first while an operation runs)"),
+        Line::from("  y              Copy details (in error dialog)"),
+        Line::from("  !              Jump to worst DLQ threshold alert"),
         Line::from(""),
-    ];
-
-    let paragraph = Paragraph::new(help_text).block(block);
-    frame.render_widget(paragraph, area);
+    ]
 }
 
 fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
@@ -0,0 +1,80 @@
+use ratatui::prelude::*;
+
+const BYTES_PER_LINE: usize = 16;
+
+fn is_printable(byte: u8) -> bool {
+    (0x20..0x7f).contains(&byte)
+}
+
+/// Classic debugger dump: offset, 16 hex bytes (split in two groups of 8) and
+/// the printable ASCII, with `.` standing in for everything else.
+/// Non-printable bytes are dimmed in the hex column; printable characters are
+/// green in the ASCII column.
+pub fn hex_dump(bytes: &[u8]) -> Vec<Line<'static>> {
+    let dim = Style::default().fg(Color::DarkGray);
+    bytes
+        .chunks(BYTES_PER_LINE)
+        .enumerate()
+        .map(|(i, chunk)| {
+            let mut spans = vec![Span::styled(
+                format!("{:08x}  ", i * BYTES_PER_LINE),
+                Style::default().fg(Color::Cyan),
+            )];
+            for col in 0..BYTES_PER_LINE {
+                let gap = if col == 7 { "  " } else { " " };
+                match chunk.get(col) {
+                    Some(&b) if is_printable(b) => {
+                        spans.push(Span::raw(format!("{:02x}{}", b, gap)))
+                    }
+                    Some(&b) => spans.push(Span::styled(format!("{:02x}{}", b, gap), dim)),
+                    None => spans.push(Span::raw(format!("  {}", gap))),
+                }
+            }
+            spans.push(Span::styled("|", dim));
+            for &b in chunk {
+                spans.push(if is_printable(b) {
+                    Span::styled((b as char).to_string(), Style::default().fg(Color::Green))
+                } else {
+                    Span::styled(".", dim)
+                });
+            }
+            spans.push(Span::styled("|", dim));
+            Line::from(spans)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(line: &Line) -> String {
+        line.spans.iter().map(|s| s.content.as_ref()).collect()
+    }
+
+    #[test]
+    fn lines_show_offset_hex_and_ascii() {
+        let bytes: Vec<u8> = b"PK\x03\x04hello, world!\x00\xffab".to_vec();
+        let lines = hex_dump(&bytes);
+        assert_eq!(lines.len(), 2);
+        assert_eq!(
+            text(&lines[0]),
+            "00000000  50 4b 03 04 68 65 6c 6c  6f 2c 20 77 6f 72 6c 64 |PK..hello, world|"
+        );
+        // A short last line is padded so the ASCII column stays aligned
+        assert_eq!(
+            text(&lines[1]),
+            "00000010  21 00 ff 61 62                                   |!..ab|"
+        );
+    }
+
+    #[test]
+    fn non_printable_bytes_are_dimmed() {
+        let line = &hex_dump(b"A\x01")[0];
+        assert_eq!(line.spans[1].style.fg, None);
+        assert_eq!(line.spans[2].style.fg, Some(Color::DarkGray));
+        let ascii: Vec<_> = line.spans.iter().rev().skip(1).take(2).collect();
+        assert_eq!(ascii[0].content, ".");
+        assert_eq!(ascii[1].style.fg, Some(Color::Green));
+    }
+}
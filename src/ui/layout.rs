@@ -2,7 +2,7 @@ use ratatui::prelude::*;
 use ratatui::widgets::*;
 use ratatui::Frame;
 
-use crate::app::{ActiveModal, App};
+use crate::app::{ActiveModal, ApiHealth, App};
 
 use super::detail::render_detail;
 use super::help::render_help;
@@ -26,13 +26,39 @@ pub fn render(frame: &mut Frame, app: &mut App) {
 
     // Title bar
     let title = if let Some(ref name) = app.connection_name {
-        format!(" Service Bus Explorer — {} ", name)
+        match app.namespace_info {
+            Some(ref info) => format!(
+                " Service Bus Explorer — {} · {} [{}] ",
+                name,
+                info.name,
+                info.messaging_sku.as_deref().unwrap_or("?")
+            ),
+            None => format!(" Service Bus Explorer — {} ", name),
+        }
     } else {
         " Service Bus Explorer — Not Connected ".to_string()
     };
-    let title_bar =
-        Paragraph::new(title).style(Style::default().bg(Color::Blue).fg(Color::White).bold());
+    let mut title_spans = vec![Span::raw(title)];
+    if !app.dlq_alerts.is_empty() {
+        let n = app.dlq_alerts.len();
+        title_spans.push(Span::styled(
+            format!(
+                " ⚠ {} {} over DLQ threshold (! to jump) ",
+                n,
+                if n == 1 { "entity" } else { "entities" }
+            ),
+            Style::default().bg(Color::Red).fg(Color::White).bold(),
+        ));
+    }
+    let title_bar = Paragraph::new(Line::from(title_spans))
+        .style(Style::default().bg(Color::Blue).fg(Color::White).bold());
     frame.render_widget(title_bar, outer[0]);
+    if let Some(indicator) = api_health_indicator(app) {
+        frame.render_widget(
+            Paragraph::new(indicator).alignment(Alignment::Right),
+            outer[0],
+        );
+    }
 
     // Body: [tree | detail+messages]
     let body = Layout::default()
@@ -56,7 +82,17 @@ pub fn render(frame: &mut Frame, app: &mut App) {
     render_tree(frame, app, body[0]);
     render_detail(frame, app, right[0]);
     render_messages(frame, app, right[1]);
-    render_status_bar(frame, app, outer[2]);
+    match app.purge_ratio() {
+        Some(ratio) => {
+            let status = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Min(20), Constraint::Length(32)])
+                .split(outer[2]);
+            render_status_bar(frame, app, status[0]);
+            render_purge_gauge(frame, app, ratio, status[1]);
+        }
+        None => render_status_bar(frame, app, outer[2]),
+    }
 
     // Render modal overlay if active
     if app.modal != ActiveModal::None {
@@ -68,3 +104,43 @@ pub fn render(frame: &mut Frame, app: &mut App) {
         render_help(frame);
     }
 }
+
+/// "● 140ms · 2% err" over the recent requests of the connection, colored
+/// by `ApiHealth`; `L` breaks it down per API.
+fn api_health_indicator(app: &App) -> Option<Line<'static>> {
+    let summary = app.connection_config.as_ref()?.request_stats().overall();
+    if summary.calls == 0 {
+        return None;
+    }
+    let color = match ApiHealth::of(&summary) {
+        ApiHealth::Good => Color::LightGreen,
+        ApiHealth::Slow => Color::Yellow,
+        ApiHealth::Poor => Color::LightRed,
+    };
+    let latency = summary
+        .p50
+        .map_or("—".to_string(), |d| format!("{}ms", d.as_millis()));
+    let style = Style::default().bg(Color::Blue).fg(Color::White);
+    Some(Line::from(vec![
+        Span::styled("●", style.fg(color).bold()),
+        Span::styled(
+            format!(" {} · {:.0}% err ", latency, summary.error_rate() * 100.0),
+            style,
+        ),
+    ]))
+}
+
+/// "1234/5000 (24%)" gauge shown next to the status bar while purging.
+fn render_purge_gauge(frame: &mut Frame, app: &App, ratio: f64, area: Rect) {
+    let label = format!(
+        "{}/{} ({:.0}%)",
+        app.purge_done,
+        app.purge_total.unwrap_or_default(),
+        ratio * 100.0
+    );
+    let gauge = Gauge::default()
+        .gauge_style(app.theme.gauge_style())
+        .ratio(ratio)
+        .label(label);
+    frame.render_widget(gauge, area);
+}
@@ -1,9 +1,18 @@
+use chrono::{DateTime, Utc};
 use ratatui::prelude::*;
 use ratatui::widgets::*;
 use ratatui::Frame;
+use std::time::Duration;
 
-use crate::app::{App, FocusPanel, MessageTab};
+use crate::app::{sorted_properties, App, FocusPanel, MessageTab, PROPERTY_VALUE_LINES};
+use crate::body_format::{parse_csv, render_body, BodyFormat};
+use crate::client::models::ReceivedMessage;
+use crate::config::MessageColumn;
+use crate::delay::format_delay;
+use crate::jwt::{decode_jwt, is_standard_claim, DecodedJwt};
+use crate::text_width;
 
+use super::hex::hex_dump;
 use super::sanitize::sanitize_for_terminal;
 
 pub fn render_messages(frame: &mut Frame, app: &mut App, area: Rect) {
@@ -26,13 +35,54 @@ pub fn render_messages(frame: &mut Frame, app: &mut App, area: Rect) {
         Style::default().fg(Color::DarkGray)
     };
 
-    let title = Line::from(vec![
+    let mut title_spans = vec![
         Span::raw(" "),
         Span::styled("[1] Messages", msg_tab_style),
         Span::raw(" | "),
         Span::styled("[2] Dead-letter", dlq_tab_style),
         Span::raw(" "),
-    ]);
+    ];
+    if app.show_transfer_dlq_tab() {
+        let transfer_tab_style = if app.message_tab == MessageTab::Transferred {
+            Style::default().fg(Color::LightRed).bold()
+        } else {
+            Style::default().fg(Color::DarkGray)
+        };
+        title_spans.push(Span::raw("| "));
+        title_spans.push(Span::styled("[4] Transfer DLQ", transfer_tab_style));
+        title_spans.push(Span::raw(" "));
+    }
+    if let Some(ref reason) = app.dlq_reason_filter {
+        title_spans.push(Span::styled(
+            format!("(reason: {}) ", sanitize_for_terminal(reason, false)),
+            Style::default().fg(Color::Yellow),
+        ));
+    }
+    if let Some(ref filter) = app.message_filter {
+        title_spans.push(Span::styled(
+            format!(
+                "(filter {}) ",
+                sanitize_for_terminal(&filter.describe(), false)
+            ),
+            Style::default().fg(Color::Yellow),
+        ));
+    }
+    if let Some(banner) = app.cached_peek_banner() {
+        title_spans.push(Span::styled(banner, Style::default().fg(Color::Yellow)));
+    }
+    if app.sort_by_expiry && app.message_tab == MessageTab::Messages {
+        title_spans.push(Span::styled(
+            "(expiring first) ",
+            Style::default().fg(Color::Yellow),
+        ));
+    }
+    if app.in_dlq_group_summary() {
+        title_spans.push(Span::styled(
+            "(grouped by reason) ",
+            Style::default().fg(Color::Yellow),
+        ));
+    }
+    let title = Line::from(title_spans);
 
     let block = Block::default()
         .title(title)
@@ -52,10 +102,33 @@ pub fn render_messages(frame: &mut Frame, app: &mut App, area: Rect) {
         return;
     }
 
-    let messages = match app.message_tab {
-        MessageTab::Messages => &app.messages,
-        MessageTab::DeadLetter => &app.dlq_messages,
-    };
+    if app.in_dlq_group_summary() && !app.dlq_messages.is_empty() {
+        render_dlq_groups(frame, app, area, block, is_focused);
+        return;
+    }
+
+    let messages = app.visible_messages();
+
+    if messages.is_empty()
+        && app.dlq_reason_filter.is_some()
+        && app.message_tab == MessageTab::DeadLetter
+    {
+        let msg = Paragraph::new(
+            "No dead-letter messages match the reason filter. Press 'S' to change it.",
+        )
+        .style(Style::default().fg(Color::DarkGray))
+        .block(block);
+        frame.render_widget(msg, area);
+        return;
+    }
+
+    if messages.is_empty() && app.message_filter.is_some() && !app.tab_messages_empty() {
+        let msg = Paragraph::new("No messages match the filter. Press 'F' to change or clear it.")
+            .style(Style::default().fg(Color::DarkGray))
+            .block(block);
+        frame.render_widget(msg, area);
+        return;
+    }
 
     if messages.is_empty() {
         let msg = Paragraph::new("No messages. Press 'p' on an entity to peek active messages or press 'd' to peek dead-letter messages.")
@@ -67,89 +140,244 @@ pub fn render_messages(frame: &mut Frame, app: &mut App, area: Rect) {
 
     let inner = block.inner(area);
 
-    // Build table rows
-    let header = Row::new(vec!["#", "Message ID", "Seq #", "Subject", "Enqueued"])
+    // Build table rows from the configured column list
+    let columns = &app.config.message_columns;
+    let header = Row::new(columns.iter().map(|c| c.title()).collect::<Vec<_>>())
         .style(Style::default().fg(Color::Yellow).bold())
         .bottom_margin(1);
 
+    // In-flight operations are flagged in the first flexible column
+    let marker_column = columns
+        .iter()
+        .position(|c| matches!(column_width(*c), Constraint::Fill(_)))
+        .unwrap_or(columns.len().saturating_sub(1));
+
+    let now = Utc::now();
+    let warn_secs = app.config.settings.expiry_warning_secs;
     let rows: Vec<Row> = messages
         .iter()
         .enumerate()
         .map(|(idx, msg)| {
+            let in_flight = app.is_message_op_in_flight(msg);
             let style = if idx == app.message_selected && is_focused {
                 Style::default().bg(Color::DarkGray).fg(Color::White)
+            } else if in_flight {
+                Style::default().fg(Color::DarkGray).italic()
+            } else if app.is_compare_marked(msg) {
+                Style::default().fg(Color::Magenta).bold()
             } else {
                 Style::default()
             };
 
+            Row::new(
+                columns
+                    .iter()
+                    .enumerate()
+                    .map(|(col, c)| {
+                        if *c == MessageColumn::Expires {
+                            let expires = app.message_expiry(msg);
+                            let soon = expires_soon(expires, now, warn_secs);
+                            let style = if soon {
+                                Style::default().fg(Color::Red).bold()
+                            } else {
+                                Style::default()
+                            };
+                            return Cell::from(expiry_label(expires, now)).style(style);
+                        }
+                        let mut cell = column_cell(*c, idx, msg);
+                        if in_flight && col == marker_column {
+                            cell.push_str(" (operation in progress)");
+                        }
+                        Cell::from(cell)
+                    })
+                    .collect::<Vec<_>>(),
+            )
+            .style(style)
+        })
+        .collect();
+
+    let widths: Vec<Constraint> = columns.iter().map(|c| column_width(*c)).collect();
+    let table = Table::new(rows, widths)
+        .header(header)
+        .block(Block::default())
+        .column_spacing(1);
+
+    // Persist scroll offset across frames for natural scrolling
+    app.message_table_state.select(Some(app.message_selected));
+
+    // Layout: table + hint bar
+    let msg_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(3), Constraint::Length(1)])
+        .split(inner);
+
+    let hint_text = if app.message_tab == MessageTab::DeadLetter && app.dlq_group_view {
+        "Esc=Back to groups  G=Ungroup  Enter=View  e=Edit & Resend"
+    } else if app.message_tab == MessageTab::Transferred {
+        "Enter=View  e=Edit & Resend  m=Mark  ==Compare"
+    } else if app.message_tab == MessageTab::DeadLetter {
+        "R=Resend All  D=Delete All  S=Reasons  G=Group  Enter=View  e=Edit & Resend  m=Mark  ==Compare"
+    } else {
+        "D=Delete All  O=Expiring first  Enter=View  e=Edit & Resend  m=Mark  ==Compare"
+    };
+    let hint = Paragraph::new(hint_text).style(Style::default().fg(Color::DarkGray));
+
+    frame.render_widget(block, area);
+    frame.render_stateful_widget(table, msg_layout[0], &mut app.message_table_state);
+    frame.render_widget(hint, msg_layout[1]);
+}
+
+/// Grouped DLQ summary: one row per dead-letter reason.
+fn render_dlq_groups(frame: &mut Frame, app: &App, area: Rect, block: Block<'_>, is_focused: bool) {
+    let inner = block.inner(area);
+    let groups = app.dlq_groups();
+    let selected = app.dlq_group_selected.min(groups.len().saturating_sub(1));
+
+    let header = Row::new(vec!["Reason", "Count", "First MessageId", "Example Body"])
+        .style(Style::default().fg(Color::Yellow).bold())
+        .bottom_margin(1);
+    let rows: Vec<Row> = groups
+        .iter()
+        .enumerate()
+        .map(|(idx, group)| {
+            let style = if idx == selected && is_focused {
+                Style::default().bg(Color::DarkGray).fg(Color::White)
+            } else {
+                Style::default()
+            };
+            let body: String = group.first.body.lines().next().unwrap_or("").to_string();
             Row::new(vec![
-                (idx + 1).to_string(),
+                sanitize_for_terminal(&group.reason, false),
+                group.count.to_string(),
                 sanitize_for_terminal(
-                    &msg.broker_properties
+                    group
+                        .first
+                        .broker_properties
                         .message_id
-                        .clone()
-                        .unwrap_or_else(|| "-".to_string()),
-                    false,
-                ),
-                msg.broker_properties
-                    .sequence_number
-                    .map(|v| v.to_string())
-                    .unwrap_or_else(|| "-".to_string()),
-                sanitize_for_terminal(
-                    &msg.broker_properties
-                        .label
-                        .clone()
-                        .unwrap_or_else(|| "-".to_string()),
-                    false,
-                ),
-                sanitize_for_terminal(
-                    &msg.broker_properties
-                        .enqueued_time_utc
-                        .clone()
-                        .unwrap_or_else(|| "-".to_string()),
+                        .as_deref()
+                        .unwrap_or("-"),
                     false,
                 ),
+                sanitize_for_terminal(&body, false),
             ])
             .style(style)
         })
         .collect();
-
     let table = Table::new(
         rows,
         [
-            Constraint::Length(4),
-            Constraint::Percentage(30),
-            Constraint::Length(10),
-            Constraint::Percentage(20),
             Constraint::Percentage(30),
+            Constraint::Length(7),
+            Constraint::Length(36),
+            Constraint::Fill(1),
         ],
     )
     .header(header)
-    .block(Block::default())
     .column_spacing(1);
 
-    // Persist scroll offset across frames for natural scrolling
-    app.message_table_state.select(Some(app.message_selected));
-
-    // Layout: table + hint bar
-    let msg_layout = Layout::default()
+    let layout = Layout::default()
         .direction(Direction::Vertical)
         .constraints([Constraint::Min(3), Constraint::Length(1)])
         .split(inner);
+    let hint = Paragraph::new("Enter=Expand group  G=Ungroup  R=Resend All  D=Delete All")
+        .style(Style::default().fg(Color::DarkGray));
 
-    let hint_text = if app.message_tab == MessageTab::DeadLetter {
-        "R=Resend All  D=Delete All  Enter=View  e=Edit & Resend"
-    } else {
-        "D=Delete All  Enter=View  e=Edit & Resend"
+    let mut state = TableState::default().with_selected(Some(selected));
+    frame.render_widget(block, area);
+    frame.render_stateful_widget(table, layout[0], &mut state);
+    frame.render_widget(hint, layout[1]);
+}
+
+fn column_cell(column: MessageColumn, idx: usize, msg: &ReceivedMessage) -> String {
+    let props = &msg.broker_properties;
+    let text = |v: &Option<String>| sanitize_for_terminal(v.as_deref().unwrap_or("-"), false);
+    let num = |v: Option<i64>| v.map(|v| v.to_string()).unwrap_or_else(|| "-".to_string());
+
+    match column {
+        MessageColumn::Index => (idx + 1).to_string(),
+        MessageColumn::MessageId => text(&props.message_id),
+        MessageColumn::SequenceNumber => num(props.sequence_number),
+        MessageColumn::Size => num(props.size),
+        MessageColumn::EnqueuedTime => text(&props.enqueued_time_utc),
+        MessageColumn::DeliveryCount => num(props.delivery_count.map(i64::from)),
+        MessageColumn::SessionId => text(&props.session_id),
+        MessageColumn::CorrelationId => text(&props.correlation_id),
+        MessageColumn::Label => text(&props.label),
+        MessageColumn::State => text(&props.state),
+        MessageColumn::Expires => "-".to_string(),
+    }
+}
+
+/// "in 4m12s", "in 2h30m", "in 3d4h" or "expired"; "-" when the message
+/// doesn't expire or its expiry is unknown.
+fn expiry_label(expires: Option<DateTime<Utc>>, now: DateTime<Utc>) -> String {
+    let Some(expires) = expires else {
+        return "-".to_string();
     };
-    let hint = Paragraph::new(hint_text).style(Style::default().fg(Color::DarkGray));
+    let Ok(left) = (expires - now).to_std() else {
+        return "expired".to_string();
+    };
+    // Two units are enough to read at a glance
+    let secs = left.as_secs();
+    let coarse = match secs {
+        0..=3599 => secs,
+        3600..=86_399 => secs / 60 * 60,
+        _ => secs / 3600 * 3600,
+    };
+    format!("in {}", format_delay(Duration::from_secs(coarse)))
+}
 
-    frame.render_widget(block, area);
-    frame.render_stateful_widget(table, msg_layout[0], &mut app.message_table_state);
-    frame.render_widget(hint, msg_layout[1]);
+/// Whether to flag a message expiring at `expires` (expired ones included),
+/// with `warn_secs` of warning; 0 turns the flag off.
+fn expires_soon(expires: Option<DateTime<Utc>>, now: DateTime<Utc>, warn_secs: u64) -> bool {
+    if warn_secs == 0 {
+        return false;
+    }
+    let warn_within = i64::try_from(warn_secs)
+        .ok()
+        .and_then(chrono::Duration::try_seconds)
+        .unwrap_or(chrono::Duration::MAX);
+    expires.is_some_and(|at| at - now < warn_within)
+}
+
+fn column_width(column: MessageColumn) -> Constraint {
+    match column {
+        MessageColumn::Index => Constraint::Length(4),
+        MessageColumn::SequenceNumber => Constraint::Length(10),
+        MessageColumn::Size => Constraint::Length(8),
+        MessageColumn::DeliveryCount => Constraint::Length(10),
+        MessageColumn::State => Constraint::Length(10),
+        MessageColumn::Expires => Constraint::Length(12),
+        MessageColumn::MessageId | MessageColumn::EnqueuedTime => Constraint::Fill(3),
+        MessageColumn::SessionId | MessageColumn::CorrelationId | MessageColumn::Label => {
+            Constraint::Fill(2)
+        }
+    }
 }
 
 fn render_detail_readonly(frame: &mut Frame, app: &mut App, inner: Rect) {
+    // The selected property's full value, wrapped by display width inside
+    // its bordered panel; PgUp/PgDn scroll it when it is longer than that.
+    let value_width = inner.width.saturating_sub(2).max(1) as usize;
+    let expanded = {
+        let custom = app.detail_properties();
+        let selected = app.property_selected.min(custom.len().saturating_sub(1));
+        custom
+            .get(selected)
+            .filter(|_| app.properties_focus)
+            .map(|(name, value)| {
+                let lines =
+                    text_width::wrap_to_width(&sanitize_for_terminal(value, true), value_width);
+                (name.clone(), value.chars().count(), lines)
+            })
+    };
+    if let Some((_, _, ref lines)) = expanded {
+        let last_top = lines.len().saturating_sub(PROPERTY_VALUE_LINES);
+        app.property_value_scroll = app
+            .property_value_scroll
+            .min(u16::try_from(last_top).unwrap_or(u16::MAX));
+    }
+
     let msg = app.selected_message_detail.as_ref().unwrap();
 
     let san = |s: &str| sanitize_for_terminal(s, false);
@@ -180,6 +408,18 @@ fn render_detail_readonly(frame: &mut Frame, app: &mut App, inner: Rect) {
     if let Some(ref label) = msg.broker_properties.label {
         props_rows.push(Row::new(vec!["Label".to_string(), san(label)]));
     }
+    if let Some(ref to) = msg.broker_properties.to {
+        props_rows.push(Row::new(vec!["To".to_string(), san(to)]));
+    }
+    if let Some(ref reply_to) = msg.broker_properties.reply_to {
+        props_rows.push(Row::new(vec!["Reply To".to_string(), san(reply_to)]));
+    }
+    if let Some(ref id) = msg.broker_properties.reply_to_session_id {
+        props_rows.push(Row::new(vec!["Reply To Session".to_string(), san(id)]));
+    }
+    if let Some(ref key) = msg.broker_properties.partition_key {
+        props_rows.push(Row::new(vec!["Partition Key".to_string(), san(key)]));
+    }
     if let Some(ref src) = msg.broker_properties.dead_letter_source {
         props_rows.push(Row::new(vec!["DLQ Source".to_string(), san(src)]));
     }
@@ -189,36 +429,135 @@ fn render_detail_readonly(frame: &mut Frame, app: &mut App, inner: Rect) {
     if let Some(ref desc) = msg.broker_properties.dead_letter_error_description {
         props_rows.push(Row::new(vec!["DLQ Error".to_string(), san(desc)]));
     }
-    for (k, v) in &msg.custom_properties {
-        props_rows.push(Row::new(vec![san(k), san(v)]));
+    let custom = sorted_properties(&msg.custom_properties);
+    let broker_rows = props_rows.len();
+    let selected = app
+        .properties_focus
+        .then(|| app.property_selected.min(custom.len().saturating_sub(1)))
+        .filter(|_| !custom.is_empty());
+    for (i, (k, v)) in custom.iter().enumerate() {
+        let row = Row::new(vec![san(k), san(v)]);
+        props_rows.push(if selected == Some(i) {
+            row.style(Style::default().bg(Color::DarkGray).fg(Color::White).bold())
+        } else {
+            row
+        });
     }
 
     let props_height = (props_rows.len() as u16 + 2).max(4); // rows + border
 
+    // The expanded value goes between table and body
+    let expanded_height = expanded.as_ref().map_or(0, |(_, _, lines)| {
+        lines.len().clamp(1, PROPERTY_VALUE_LINES) as u16 + 2
+    });
+
     let detail_layout = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([Constraint::Length(props_height), Constraint::Min(3)])
+        .constraints([
+            Constraint::Length(props_height),
+            Constraint::Length(expanded_height),
+            Constraint::Min(3),
+        ])
         .split(inner);
 
+    let props_title = if selected.is_some() {
+        " Properties (j/k = select · y = copy value · v/Esc = back) "
+    } else if custom.is_empty() {
+        " Properties (e = edit & resend · Esc = close) "
+    } else {
+        " Properties (v = browse properties · e = edit & resend · Esc = close) "
+    };
     let props_table = Table::new(
         props_rows,
         [Constraint::Percentage(30), Constraint::Percentage(70)],
     )
     .block(
         Block::default()
-            .title(" Properties (e = edit & resend · Esc = close) ")
+            .title(props_title)
             .borders(Borders::ALL)
             .border_style(Style::default().fg(Color::Yellow)),
     );
-    frame.render_widget(props_table, detail_layout[0]);
+    let mut props_state = TableState::default().with_selected(selected.map(|i| broker_rows + i));
+    frame.render_stateful_widget(props_table, detail_layout[0], &mut props_state);
+
+    if let Some((name, chars, lines)) = expanded {
+        let top = usize::from(app.property_value_scroll);
+        let title = if lines.len() > PROPERTY_VALUE_LINES {
+            format!(
+                " {} ({} chars · lines {}–{} of {} · PgUp/PgDn = scroll) ",
+                san(&name),
+                chars,
+                top + 1,
+                (top + PROPERTY_VALUE_LINES).min(lines.len()),
+                lines.len()
+            )
+        } else {
+            format!(" {} ({} chars) ", san(&name), chars)
+        };
+        let lines: Vec<Line> = lines.into_iter().map(Line::from).collect();
+        let full = Paragraph::new(lines)
+            .scroll((app.property_value_scroll, 0))
+            .block(
+                Block::default()
+                    .title(title)
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(Color::Cyan)),
+            );
+        frame.render_widget(full, detail_layout[1]);
+    }
 
-    let body = san_ml(&pretty_print_body(&msg.body));
-    let body_lines = body.lines().count() as u16;
+    let format = app.body_display_format().unwrap_or(BodyFormat::PlainText);
+    let csv_rows = match format {
+        BodyFormat::Csv => parse_csv(&msg.body),
+        _ => None,
+    };
+    let body = san_ml(&render_body(&msg.body, format));
+    let body_lines = match csv_rows {
+        Some(ref rows) => rows.len().saturating_sub(1) as u16,
+        None => body.lines().count() as u16,
+    };
+    let body_title = if app.body_wrap {
+        format!(
+            " Body · {} (j/k to scroll · M = format · J = JWT · X = hex · W = no wrap · Esc = close) ",
+            format.label()
+        )
+    } else {
+        format!(
+            " Body · {} (j/k/h/l to scroll · M = format · J = JWT · X = hex · W = wrap · Esc = close) ",
+            format.label()
+        )
+    };
     let body_inner = Block::default()
-        .title(" Body (j/k to scroll · Esc = close) ")
+        .title(body_title)
         .borders(Borders::ALL)
         .border_style(Style::default().fg(Color::Yellow));
-    let body_viewport = body_inner.inner(detail_layout[1]).height;
+    let body_area = body_inner.inner(detail_layout[2]);
+    let body_viewport = body_area.height;
+    if let Some(rows) = csv_rows {
+        render_csv_body(frame, app, rows, body_inner, detail_layout[2]);
+        return;
+    }
+    if let Some(jwt) = (format == BodyFormat::Jwt)
+        .then(|| decode_jwt(&msg.body))
+        .flatten()
+    {
+        render_jwt_body(frame, app, jwt, body_inner, detail_layout[2]);
+        return;
+    }
+    if matches!(format, BodyFormat::Binary | BodyFormat::Hex) {
+        let lines = app
+            .detail_hex_dump
+            .get_or_insert_with(|| hex_dump(msg.body_bytes()));
+        app.detail_body_scroll = app
+            .detail_body_scroll
+            .min((lines.len() as u16).saturating_sub(body_viewport));
+        // Only the visible lines are handed to the widget
+        let top = usize::from(app.detail_body_scroll).min(lines.len());
+        let end = (top + usize::from(body_viewport)).min(lines.len());
+        let dump = Paragraph::new(lines[top..end].to_vec()).block(body_inner);
+        frame.render_widget(dump, detail_layout[2]);
+        return;
+    }
     // Clamp scroll so we don't scroll past the end
     if body_lines > body_viewport {
         app.detail_body_scroll = app
@@ -227,11 +566,94 @@ fn render_detail_readonly(frame: &mut Frame, app: &mut App, inner: Rect) {
     } else {
         app.detail_body_scroll = 0;
     }
-    let body_widget = Paragraph::new(body)
-        .block(body_inner)
-        .wrap(Wrap { trim: false })
-        .scroll((app.detail_body_scroll, 0));
-    frame.render_widget(body_widget, detail_layout[1]);
+    let body_widget = if app.body_wrap {
+        Paragraph::new(body)
+            .block(body_inner)
+            .wrap(Wrap { trim: false })
+            .scroll((app.detail_body_scroll, 0))
+    } else {
+        let widest = body.lines().map(|l| l.chars().count()).max().unwrap_or(0) as u16;
+        app.body_scroll_horizontal = app
+            .body_scroll_horizontal
+            .min(widest.saturating_sub(body_area.width));
+        Paragraph::new(body)
+            .block(body_inner)
+            .scroll((app.detail_body_scroll, app.body_scroll_horizontal))
+    };
+    frame.render_widget(body_widget, detail_layout[2]);
+}
+
+/// A CSV body as a table: first row as header, the rest scrolled with j/k.
+fn render_csv_body(
+    frame: &mut Frame,
+    app: &mut App,
+    rows: Vec<Vec<String>>,
+    block: Block,
+    area: Rect,
+) {
+    let san = |s: &str| sanitize_for_terminal(s, false);
+    let viewport = block.inner(area).height.saturating_sub(1);
+    let data_rows = rows.len().saturating_sub(1) as u16;
+    app.detail_body_scroll = app
+        .detail_body_scroll
+        .min(data_rows.saturating_sub(viewport));
+
+    let widths: Vec<Constraint> = (0..rows[0].len())
+        .map(|col| {
+            let widest = rows
+                .iter()
+                .map(|r| text_width::width(&r[col]))
+                .max()
+                .unwrap_or(0);
+            Constraint::Length(widest.clamp(3, 40) as u16)
+        })
+        .collect();
+    let mut rows = rows.into_iter();
+    let header = Row::new(rows.next().unwrap_or_default().iter().map(|f| san(f)))
+        .style(Style::default().fg(Color::Cyan).bold());
+    let body = rows
+        .skip(app.detail_body_scroll as usize)
+        .map(|r| Row::new(r.iter().map(|f| san(f)).collect::<Vec<_>>()));
+    let table = Table::new(body, widths).header(header).block(block);
+    frame.render_widget(table, area);
+}
+
+/// A decoded JWT as a `[JWT Header]` / `[JWT Claims]` table, with the
+/// registered claims highlighted.
+fn render_jwt_body(frame: &mut Frame, app: &mut App, jwt: DecodedJwt, block: Block, area: Rect) {
+    let san = |s: &str| sanitize_for_terminal(s, false);
+    let section = Style::default().fg(Color::Cyan).bold();
+    let mut rows = vec![Row::new(vec!["[JWT Header]".to_string()]).style(section)];
+    rows.extend(
+        jwt.header
+            .iter()
+            .map(|(k, v)| Row::new(vec![san(k), san(v)])),
+    );
+    rows.push(Row::new(vec![String::new()]));
+    rows.push(Row::new(vec!["[JWT Claims]".to_string()]).style(section));
+    rows.extend(jwt.claims.iter().map(|(k, v)| {
+        let row = Row::new(vec![san(k), san(v)]);
+        if is_standard_claim(k) {
+            row.style(Style::default().fg(Color::Yellow).bold())
+        } else {
+            row
+        }
+    }));
+
+    let viewport = block.inner(area).height;
+    app.detail_body_scroll = app
+        .detail_body_scroll
+        .min((rows.len() as u16).saturating_sub(viewport));
+    let rows: Vec<Row> = rows
+        .into_iter()
+        .skip(app.detail_body_scroll as usize)
+        .collect();
+    let table = Table::new(
+        rows,
+        [Constraint::Percentage(30), Constraint::Percentage(70)],
+    )
+    .block(block);
+    frame.render_widget(table, area);
 }
 
 /// WYSIWYG inline edit view — fields 1..N at top, body (field 0) at bottom.
@@ -246,7 +668,11 @@ fn render_detail_edit(frame: &mut Frame, app: &mut App, inner: Rect) {
     //   4: Session ID
     //   5: Label
     //   6: TTL
-    //   7: Custom Properties
+    //   7: To
+    //   8: Reply To
+    //   9: Reply To Session ID
+    //  10: Partition Key
+    //  11: Custom Properties
     let prop_field_count = app.input_fields.len().saturating_sub(1); // fields 1..N
     let props_height = (prop_field_count as u16 * 2 + 2).max(4); // rows for prop fields + border
 
@@ -308,7 +734,11 @@ fn render_detail_edit(frame: &mut Frame, app: &mut App, inner: Rect) {
 
     // ── Editable properties (fields 1..N) ──
     let props_block = Block::default()
-        .title(" Properties (editable) ")
+        .title(if app.form_submitting {
+            " Properties (sending...) "
+        } else {
+            " Properties (editable) "
+        })
         .borders(Borders::ALL)
         .border_style(Style::default().fg(Color::Cyan));
     let props_inner = props_block.inner(props_area);
@@ -409,11 +839,15 @@ fn render_detail_edit(frame: &mut Frame, app: &mut App, inner: Rect) {
         }
     }
 
-    // ── Hint bar ──
-    let hint = Paragraph::new(
-        "Tab fields · ↑↓←→ navigate · Enter newline (body) · F2 resend · Esc cancel",
-    )
-    .style(Style::default().fg(Color::DarkGray));
+    // ── Hint bar (or the body validation error) ──
+    let hint = match app.form_error {
+        Some(ref err) => Paragraph::new(sanitize_for_terminal(err, false))
+            .style(Style::default().fg(Color::Red).bold()),
+        None => Paragraph::new(
+            "Tab fields · ↑↓←→ navigate · Enter newline (body) · Ctrl+F format · F2 resend · Esc cancel",
+        )
+        .style(Style::default().fg(Color::DarkGray)),
+    };
     frame.render_widget(hint, hint_area);
 }
 
@@ -425,3 +859,35 @@ fn pretty_print_body(body: &str) -> String {
         body.to_string()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn expiry_labels_round_to_two_units() {
+        let now = Utc.with_ymd_and_hms(2024, 5, 6, 12, 0, 0).unwrap();
+        let at = |secs: i64| Some(now + chrono::Duration::seconds(secs));
+        assert_eq!(expiry_label(None, now), "-");
+        assert_eq!(expiry_label(at(-1), now), "expired");
+        assert_eq!(expiry_label(at(0), now), "in 0s");
+        assert_eq!(expiry_label(at(95), now), "in 1m35s");
+        assert_eq!(expiry_label(at(2 * 3600 + 125), now), "in 2h2m");
+        assert_eq!(expiry_label(at(3 * 86_400 + 3700), now), "in 3d1h");
+    }
+
+    #[test]
+    fn expiry_flag_respects_the_warning_window() {
+        let now = Utc.with_ymd_and_hms(2024, 5, 6, 12, 0, 0).unwrap();
+        let at = |secs: i64| Some(now + chrono::Duration::seconds(secs));
+        assert!(expires_soon(at(60), now, 300));
+        assert!(expires_soon(at(-60), now, 300));
+        assert!(!expires_soon(at(600), now, 300));
+        assert!(!expires_soon(None, now, 300));
+        // 0 = off, expired messages included
+        assert!(!expires_soon(at(-60), now, 0));
+        // Hand-edited values too large for chrono don't panic
+        assert!(expires_soon(at(600), now, u64::MAX));
+    }
+}
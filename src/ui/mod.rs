@@ -1,8 +1,10 @@
 pub mod detail;
 pub mod help;
+pub mod hex;
 pub mod layout;
 pub mod messages;
 pub mod modals;
 pub mod sanitize;
 pub mod status_bar;
+pub mod theme;
 pub mod tree;
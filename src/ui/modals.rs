@@ -2,27 +2,51 @@ use ratatui::prelude::*;
 use ratatui::widgets::*;
 use ratatui::Frame;
 
-use crate::app::{ActiveModal, App};
+use crate::app::{status_options, ActiveModal, ApiHealth, App, DetailView, DlqStats, MessageTab};
+use crate::client::models::ReceivedMessage;
+use crate::client::stats::{Api, STATS_WINDOW};
+use crate::config::SettingField;
+use crate::connection_test::TestOutcome;
+use crate::message_diff::{broker_property_diff, custom_property_diff, DiffLine, PropertyDiff};
+use crate::message_filter::FilterScope;
+use crate::text_width::{self, truncate_to_width};
 
 use super::sanitize::sanitize_for_terminal;
 
-fn mask_secret_ascii_keep_suffix(input: &str, suffix_chars: usize) -> String {
-    if input.is_empty() {
-        return String::new();
-    }
+/// Mask all but the last `suffix_chars` characters with one `*` each, so the
+/// masked text has as many characters as the input and the cursor (a char
+/// index) lines up with it.
+fn mask_secret_keep_suffix(input: &str, suffix_chars: usize) -> String {
+    let len = input.chars().count();
+    let masked = len.saturating_sub(suffix_chars);
+    input
+        .chars()
+        .enumerate()
+        .map(|(i, c)| if i < masked { '*' } else { c })
+        .collect()
+}
+
+/// Keys whose values are secrets wherever they turn up in a connection string.
+const SECRET_KEYS: [&str; 2] = ["SharedAccessKey=", "SharedAccessSignature="];
 
-    // This app treats input_cursor as a byte offset; connection strings are ASCII.
-    // Keep output strictly ASCII to avoid cursor-position drift.
-    let len = input.len();
-    let suffix = suffix_chars.min(len);
-    let (prefix, tail) = input.split_at(len - suffix);
-    format!("{}{}", "*".repeat(prefix.len()), tail)
+/// `value` cut off at the first embedded secret, for a segment that lost its
+/// `;` separator (`Endpoint=sb://ns/SharedAccessKey=...`).
+fn strip_embedded_secret(value: &str) -> &str {
+    let lower = value.to_ascii_lowercase();
+    let cut = SECRET_KEYS
+        .iter()
+        .filter_map(|key| lower.find(&key.to_ascii_lowercase()))
+        .min()
+        .unwrap_or(value.len());
+    value[..cut].trim().trim_end_matches(';')
 }
 
 fn redact_connection_string_for_preview(conn_str: &str) -> String {
-    // Extract Endpoint and SharedAccessKeyName for a safe summary.
+    // Extract Endpoint and SharedAccessKeyName for a safe summary; every other
+    // value (the key, a SAS token, anything pasted by mistake) is dropped.
     let mut endpoint: Option<&str> = None;
     let mut key_name: Option<&str> = None;
+    let mut signature = false;
 
     for part in conn_str.split(';') {
         let part = part.trim();
@@ -30,32 +54,53 @@ fn redact_connection_string_for_preview(conn_str: &str) -> String {
             continue;
         }
         if let Some((k, v)) = part.split_once('=') {
-            match k.trim() {
-                "Endpoint" => endpoint = Some(v.trim()),
-                "SharedAccessKeyName" => key_name = Some(v.trim()),
-                _ => {}
+            let k = k.trim();
+            if k.eq_ignore_ascii_case("Endpoint") {
+                endpoint = Some(strip_embedded_secret(v));
+            } else if k.eq_ignore_ascii_case("SharedAccessKeyName") {
+                key_name = Some(strip_embedded_secret(v));
+            } else if k.eq_ignore_ascii_case("SharedAccessSignature") {
+                signature = true;
             }
         }
     }
 
+    // Anything that isn't a URL isn't an endpoint — likely a secret pasted
+    // into the wrong place.
+    let endpoint = endpoint.filter(|ep| ep.contains("://"));
+    let key_name = key_name.filter(|kn| !kn.is_empty());
+    let secret = if signature {
+        "SharedAccessSignature=***"
+    } else {
+        "SharedAccessKey=***"
+    };
     match (endpoint, key_name) {
-        (Some(ep), Some(kn)) => format!(
-            "Endpoint={}; SharedAccessKeyName={}; SharedAccessKey=***",
-            ep, kn
-        ),
-        (Some(ep), None) => format!("Endpoint={}; SharedAccessKey=***", ep),
+        (Some(ep), Some(kn)) => format!("Endpoint={}; SharedAccessKeyName={}; {}", ep, kn, secret),
+        (Some(ep), None) => format!("Endpoint={}; {}", ep, secret),
         _ => "(redacted SAS connection)".to_string(),
     }
 }
 
 pub fn render_modal(frame: &mut Frame, app: &mut App) {
     match &app.modal.clone() {
-        ActiveModal::ConnectionModeSelect => render_connection_mode_select(frame),
+        ActiveModal::ConnectionModeSelect => render_connection_mode_select(frame, app),
         ActiveModal::ConnectionInput => render_connection_input(frame, app),
         ActiveModal::ConnectionList => render_connection_list(frame, app),
+        ActiveModal::ConnectionGroupInput { connection } => {
+            render_connection_group_input(frame, app, connection.as_deref())
+        }
         ActiveModal::ConnectionSwitch => render_connection_switch(frame, app),
+        ActiveModal::ConnectionRenameInput { connection } => {
+            render_connection_rename_input(frame, app, connection)
+        }
+        ActiveModal::ConfirmDeleteConnection(name) => render_confirm_delete_connection(frame, name),
         ActiveModal::AzureAdNamespaceInput => render_azure_ad_input(frame, app),
-        ActiveModal::SendMessage => render_form(frame, app, "Send Message", "F2 to send"),
+        ActiveModal::SendMessage => render_form(
+            frame,
+            app,
+            "Send Message",
+            "F2 to send · F3 forget remembered fields",
+        ),
         ActiveModal::EditResend => render_form(frame, app, "Edit & Resend", "F2 to resend"),
         ActiveModal::CreateQueue => render_form(frame, app, "Create Queue", "F2 to create"),
         ActiveModal::CreateTopic => render_form(frame, app, "Create Topic", "F2 to create"),
@@ -68,24 +113,50 @@ pub fn render_modal(frame: &mut Frame, app: &mut App) {
             "Edit Subscription Filter",
             "F2 to update filter",
         ),
+        ActiveModal::EditEntity { path, .. } => {
+            let title = format!("Edit — {}", path);
+            render_form(frame, app, &title, "F2 to save")
+        }
         ActiveModal::ConfirmDelete(path) => render_confirm_delete(frame, path),
+        ActiveModal::ConfirmQuit => render_confirm_quit(frame, app),
         ActiveModal::ConfirmBulkResend {
-            entity_path, count, ..
+            entity_path,
+            count,
+            dry_run,
+            ..
         } => {
+            let delay = match app.bulk_resend_delay {
+                Some(spec) => format!(
+                    "Delay: {} (appear {})",
+                    spec,
+                    spec.window(chrono::Utc::now(), *count as usize)
+                        .unwrap_or_else(|e| e)
+                ),
+                None => "Delay: none ('d' to schedule)".to_string(),
+            };
+            let mut message = format!(
+                "Resend {} peeked dead-letter messages back to '{}'?\nOriginals will be removed from DLQ.\n{}",
+                count, entity_path, delay
+            );
+            if let Some(warning) = app.quota_warning(entity_path) {
+                message.push_str(&format!("\n⚠ {}", warning));
+            }
             render_confirm_bulk(
                 frame,
                 "Resend Peeked DLQ Messages",
-                &format!(
-                    "Resend {} peeked dead-letter messages back to '{}'?\nOriginals will be removed from DLQ.",
-                    count, entity_path
-                ),
+                &message,
                 Color::Yellow,
+                *dry_run,
             );
         }
+        ActiveModal::BulkResendDelayInput { count, .. } => {
+            render_bulk_resend_delay_input(frame, app, *count)
+        }
         ActiveModal::ConfirmBulkDelete {
             entity_path,
             count,
             is_dlq,
+            dry_run,
             ..
         } => {
             let target = if *is_dlq { "DLQ" } else { "main queue" };
@@ -97,12 +168,29 @@ pub fn render_modal(frame: &mut Frame, app: &mut App) {
                     count, target, entity_path
                 ),
                 Color::Red,
+                *dry_run,
             );
         }
-        ActiveModal::PeekCountInput => render_peek_count_input(frame, app),
-        ActiveModal::ClearOptions { entity_path, .. } => {
-            render_clear_options(frame, entity_path);
+        ActiveModal::PeekCountInput { custom } => render_peek_count_input(frame, app, *custom),
+        ActiveModal::ClearOptions {
+            entity_path,
+            dry_run,
+            dlq_only,
+            ..
+        } => {
+            render_clear_options(frame, entity_path, *dry_run, *dlq_only);
         }
+        ActiveModal::DryRunSummary => render_dry_run_summary(frame, app),
+        ActiveModal::ResendReport => render_resend_report(frame, app),
+        ActiveModal::ForwardGraph => render_forward_graph(frame, app),
+        ActiveModal::ApiStats => render_api_stats(frame, app),
+        ActiveModal::PurgePreflight {
+            entity_path,
+            is_dlq,
+        } => render_purge_preflight(frame, app, entity_path, *is_dlq),
+        ActiveModal::CreateOnTopic { topic } => render_create_on_topic(frame, topic),
+        ActiveModal::CopyFormat { path, .. } => render_copy_format(frame, path),
+        ActiveModal::ResumeDraft { kind } => render_resume_draft(frame, app, *kind),
         ActiveModal::NamespaceDiscovery { state } => render_namespace_discovery(frame, app, state),
         ActiveModal::CopySelectConnection => render_copy_select_connection(frame, app),
         ActiveModal::CopySelectEntity => render_copy_select_entity(frame, app),
@@ -122,6 +210,33 @@ pub fn render_modal(frame: &mut Frame, app: &mut App) {
                 "F2 to copy | Esc to cancel",
             )
         }
+        ActiveModal::AuthorizationRules { entity_path } => {
+            render_authorization_rules(frame, app, entity_path)
+        }
+        ActiveModal::ErrorDetail { .. } => render_error_detail(frame, app),
+        ActiveModal::ColumnSelector => render_column_selector(frame, app),
+        ActiveModal::DlqStats => render_dlq_stats(frame, app),
+        ActiveModal::Settings { editing } => render_settings(frame, app, *editing),
+        ActiveModal::ForwardToInput { path, .. } => render_forward_to_input(frame, app, path),
+        ActiveModal::Sessions => render_sessions(frame, app),
+        ActiveModal::FilterPresets => render_filter_presets(frame, app),
+        ActiveModal::ConfirmDeleteFilterPreset(name) => {
+            render_filter_presets(frame, app);
+            render_confirm_delete_filter_preset(frame, name);
+        }
+        ActiveModal::MessageFilterInput { scope, preset } => {
+            render_message_filter_input(frame, app, *scope, *preset)
+        }
+        ActiveModal::FilterPresetNameInput { preset } => {
+            render_filter_preset_name_input(frame, app, *preset)
+        }
+        ActiveModal::ConfirmFilterPeek => render_confirm_filter_peek(frame, app),
+        ActiveModal::MessageSearchInput => render_message_search_input(frame, app),
+        ActiveModal::SearchResults => render_search_results(frame, app),
+        ActiveModal::CompareMessages => render_compare_messages(frame, app),
+        ActiveModal::StatusSelect { entity_type, path } => {
+            render_status_select(frame, app, status_options(entity_type), path)
+        }
         ActiveModal::Help | ActiveModal::None => {}
     }
 }
@@ -182,8 +297,10 @@ fn render_popup_block(frame: &mut Frame, area: Rect, title: String, border_color
     inner
 }
 
-fn set_single_line_cursor(frame: &mut Frame, input_area: Rect, cursor: usize) {
-    let cursor_x = input_area.x + cursor as u16 + 1;
+/// Place the terminal cursor in a bordered one-line input showing `text`,
+/// before its `cursor`-th character.
+fn set_single_line_cursor(frame: &mut Frame, input_area: Rect, text: &str, cursor: usize) {
+    let cursor_x = input_area.x + text_width::cursor_column(text, cursor) as u16 + 1;
     let cursor_y = input_area.y + 1;
     frame.set_cursor_position((cursor_x, cursor_y));
 }
@@ -212,7 +329,7 @@ fn render_connection_input(frame: &mut Frame, app: &App) {
     let inner = render_popup_block(
         frame,
         area,
-        " Connect — Enter Connection String ".to_string(),
+        connect_title(app, "Connect — Enter Connection String"),
         Color::Cyan,
     );
 
@@ -228,8 +345,8 @@ fn render_connection_input(frame: &mut Frame, app: &App) {
     .style(Style::default().fg(Color::DarkGray));
     frame.render_widget(hint, layout[0]);
 
-    let masked = mask_secret_ascii_keep_suffix(app.input_buffer.as_str(), 4);
-    let input = Paragraph::new(masked)
+    let masked = mask_secret_keep_suffix(app.input_buffer.as_str(), 4);
+    let input = Paragraph::new(masked.as_str())
         .style(Style::default().fg(Color::White))
         .block(
             Block::default()
@@ -238,7 +355,7 @@ fn render_connection_input(frame: &mut Frame, app: &App) {
         );
     frame.render_widget(input, layout[1]);
 
-    set_single_line_cursor(frame, layout[1], app.input_cursor);
+    set_single_line_cursor(frame, layout[1], &masked, app.input_cursor);
 }
 
 fn render_connection_list(frame: &mut Frame, app: &App) {
@@ -246,17 +363,32 @@ fn render_connection_list(frame: &mut Frame, app: &App) {
     let inner = render_popup_block(
         frame,
         area,
-        " Saved Connections (n=new, d=delete, Enter=connect) ".to_string(),
+        format!(
+            " Saved Connections{} (n=new, G=group, d=delete, t=test all, f=failed only, Enter=connect) ",
+            if app.connection_failures_only {
+                " — failed"
+            } else {
+                ""
+            }
+        ),
         Color::Cyan,
     );
 
-    let items: Vec<ListItem> = app
-        .config
-        .connections
-        .iter()
-        .enumerate()
-        .map(|(idx, conn)| {
-            let style = if idx == app.input_field_index {
+    // Headers only once something is grouped; a flat list needs none
+    let groups = app.listed_connection_groups();
+    let show_headers = groups.iter().any(|(group, _)| group.is_some());
+    let mut items: Vec<ListItem> = Vec::new();
+    let mut position = 0;
+    for (group, indices) in groups {
+        if show_headers {
+            items.push(ListItem::new(Line::from(Span::styled(
+                format!("  {}", group.unwrap_or("(Ungrouped)")),
+                Style::default().fg(Color::DarkGray).bold(),
+            ))));
+        }
+        for idx in indices {
+            let conn = &app.config.connections[idx];
+            let style = if position == app.input_field_index {
                 Style::default().bg(Color::DarkGray).fg(Color::White).bold()
             } else {
                 Style::default()
@@ -267,26 +399,169 @@ fn render_connection_list(frame: &mut Frame, app: &App) {
                 let preview = redact_connection_string_for_preview(
                     conn.connection_string.as_deref().unwrap_or(""),
                 );
-                format!("[SAS] {}…", truncate(&preview, 55))
+                format!("[SAS] {}", truncate_to_width(&preview, 56))
             };
-            ListItem::new(Line::from(Span::styled(
-                format!("  {} — {}", conn.name, detail),
+            let indent = if show_headers { "    " } else { "  " };
+            let mut spans = vec![Span::styled(
+                format!("{}{} — {}", indent, conn.name, detail),
                 style,
-            )))
-        })
-        .collect();
+            )];
+            spans.extend(connection_test_note(app, &conn.name));
+            items.push(ListItem::new(Line::from(spans)));
+            position += 1;
+        }
+    }
+
+    // Full error of the highlighted connection's failed test
+    let failure = app
+        .selected_saved_connection()
+        .and_then(|conn| app.connection_tests.get(&conn.name))
+        .and_then(|test| match &test.outcome {
+            TestOutcome::Failed { detail, .. } => Some(detail.clone()),
+            TestOutcome::Ok { .. } => None,
+        });
+    let list_area = match failure {
+        Some(detail) => {
+            let split = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(1), Constraint::Length(1)])
+                .split(inner);
+            frame.render_widget(
+                Paragraph::new(Span::styled(
+                    format!("  {}", sanitize_for_terminal(&detail, false)),
+                    Style::default().fg(Color::Red),
+                )),
+                split[1],
+            );
+            split[0]
+        }
+        None => inner,
+    };
 
     let list = List::new(items);
-    frame.render_widget(list, inner);
+    frame.render_widget(list, list_area);
+}
+
+/// "✓ 84ms · 2m ago" / "✗ auth failed · 10s ago" after a tested connection.
+fn connection_test_note(app: &App, name: &str) -> Option<Span<'static>> {
+    if app.connections_testing.contains(name) {
+        return Some(Span::styled(
+            "  … testing",
+            Style::default().fg(Color::Yellow),
+        ));
+    }
+    let test = app.connection_tests.get(name)?;
+    let age = crate::app::short_age(test.at.elapsed());
+    Some(match &test.outcome {
+        TestOutcome::Ok { latency } => Span::styled(
+            format!("  ✓ {}ms · {} ago", latency.as_millis(), age),
+            Style::default().fg(Color::Green),
+        ),
+        TestOutcome::Failed { class, .. } => Span::styled(
+            format!("  ✗ {} · {} ago", class.label(), age),
+            Style::default().fg(Color::Red),
+        ),
+    })
+}
+
+fn render_connection_group_input(frame: &mut Frame, app: &App, connection: Option<&str>) {
+    let area = centered_rect(60, 20, frame.area());
+    let title = match connection {
+        Some(name) => format!(" Group — {} ", name),
+        None => " New Connection — Group ".to_string(),
+    };
+    let inner = render_popup_block(frame, area, title, Color::Cyan);
+
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2),
+            Constraint::Length(3),
+            Constraint::Length(1),
+        ])
+        .margin(1)
+        .split(inner);
+
+    let hint = Paragraph::new(
+        "Group to list the connection under (e.g. dev, staging, prod)
+Leave blank for no group",
+    )
+    .style(Style::default().fg(Color::DarkGray));
+    frame.render_widget(hint, layout[0]);
+
+    let input = Paragraph::new(app.input_buffer.as_str())
+        .style(Style::default().fg(Color::White))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Cyan)),
+        );
+    frame.render_widget(input, layout[1]);
+    set_single_line_cursor(frame, layout[1], &app.input_buffer, app.input_cursor);
+
+    render_shortcut_hints(
+        frame,
+        layout[2],
+        &[("Enter", " save  "), ("Esc", " cancel")],
+    );
+}
+
+fn render_connection_rename_input(frame: &mut Frame, app: &App, connection: &str) {
+    let area = centered_rect(60, 20, frame.area());
+    let title = format!(" Rename — {} ", connection);
+    let inner = render_popup_block(frame, area, title, Color::Cyan);
+
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2),
+            Constraint::Length(3),
+            Constraint::Length(1),
+        ])
+        .margin(1)
+        .split(inner);
+
+    let hint = Paragraph::new("New name for the saved connection")
+        .style(Style::default().fg(Color::DarkGray));
+    frame.render_widget(hint, layout[0]);
+
+    let input = Paragraph::new(app.input_buffer.as_str())
+        .style(Style::default().fg(Color::White))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Cyan)),
+        );
+    frame.render_widget(input, layout[1]);
+    set_single_line_cursor(frame, layout[1], &app.input_buffer, app.input_cursor);
+
+    render_shortcut_hints(
+        frame,
+        layout[2],
+        &[("Enter", " rename  "), ("Esc", " back")],
+    );
+}
+
+/// Title of the connect dialogs: names the saved connection when its
+/// credentials are being re-entered after a 401.
+fn connect_title(app: &App, default: &str) -> String {
+    match app.reauth_connection {
+        Some(ref name) => format!(" Re-authenticate: {} ", sanitize_for_terminal(name, false)),
+        None => format!(" {} ", default),
+    }
 }
 
-fn render_connection_mode_select(frame: &mut Frame) {
-    let area = centered_rect_abs_height(50, 9, frame.area());
+fn render_connection_mode_select(frame: &mut Frame, app: &App) {
+    let area = centered_rect_abs_height(50, 11, frame.area());
     let inner = render_popup_block(
         frame,
         area,
-        " Connect — Choose Auth Method ".to_string(),
-        Color::Cyan,
+        connect_title(app, "Connect — Choose Auth Method"),
+        if app.reauth_connection.is_some() {
+            Color::Yellow
+        } else {
+            Color::Cyan
+        },
     );
 
     let text = vec![
@@ -301,6 +576,11 @@ fn render_connection_mode_select(frame: &mut Frame) {
             Span::raw("Azure AD / Entra ID"),
         ]),
         Line::from(""),
+        Line::from(vec![
+            Span::styled("  [4] ", Style::default().fg(Color::Yellow).bold()),
+            Span::raw("Local Emulator"),
+        ]),
+        Line::from(""),
         Line::from(Span::styled(
             "  Esc to cancel",
             Style::default().fg(Color::DarkGray),
@@ -341,110 +621,717 @@ fn render_azure_ad_input(frame: &mut Frame, app: &App) {
         );
     frame.render_widget(input, layout[1]);
 
-    set_single_line_cursor(frame, layout[1], app.input_cursor);
+    set_single_line_cursor(frame, layout[1], &app.input_buffer, app.input_cursor);
 }
 
-fn render_connection_switch(frame: &mut Frame, app: &App) {
-    let area = centered_rect(50, 40, frame.area());
-    let inner = render_popup_block(
-        frame,
-        area,
-        " Connection Management ".to_string(),
-        Color::Cyan,
-    );
+fn render_compare_messages(frame: &mut Frame, app: &App) {
+    let [(left, left_tab), (right, right_tab)] = &app.compare_marks[..] else {
+        return;
+    };
+    let area = centered_rect(90, 85, frame.area());
+    let inner = render_popup_block(frame, area, " Compare Messages ".to_string(), Color::Cyan);
 
-    let current_conn = app.connection_name.as_deref().unwrap_or("Unknown");
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(1)])
+        .margin(1)
+        .split(inner);
 
-    let text = vec![
-        Line::from(""),
-        Line::from(vec![
-            Span::styled("Current connection: ", Style::default().fg(Color::DarkGray)),
-            Span::styled(current_conn, Style::default().fg(Color::White).bold()),
-        ]),
-        Line::from(""),
-        Line::from(""),
-        Line::from(vec![
-            Span::styled("  [D] ", Style::default().fg(Color::Yellow).bold()),
-            Span::raw("Disconnect"),
-        ]),
-        Line::from(""),
-        Line::from(vec![
-            Span::styled("  [S] ", Style::default().fg(Color::Yellow).bold()),
-            Span::raw("Switch connection"),
-        ]),
+    let san = |s: &str| sanitize_for_terminal(s, false);
+    let describe = |msg: &ReceivedMessage, tab: &MessageTab| {
+        let seq = msg
+            .broker_properties
+            .sequence_number
+            .map(|n| format!("seq {}", n))
+            .unwrap_or_else(|| "no seq".to_string());
+        let kind = match tab {
+            MessageTab::Messages => "Active",
+            MessageTab::DeadLetter => "DLQ",
+            MessageTab::Transferred => "Transfer DLQ",
+        };
+        format!(
+            "{} ({}, {})",
+            san(msg.source_entity.as_deref().unwrap_or("-")),
+            seq,
+            kind
+        )
+    };
+    let heading = |title: &str| {
+        Line::from(Span::styled(
+            title.to_string(),
+            Style::default().fg(Color::Cyan).bold(),
+        ))
+    };
+    let property_line = |p: &PropertyDiff| {
+        let style = if p.differs() {
+            Style::default().fg(Color::Yellow).bold()
+        } else {
+            Style::default().fg(Color::Gray)
+        };
+        let value = |v: &Option<String>| san(v.as_deref().unwrap_or("—"));
+        Line::from(Span::styled(
+            format!(
+                "  {:<26} {:<36} {}",
+                san(&p.name),
+                value(&p.left),
+                value(&p.right)
+            ),
+            style,
+        ))
+    };
+
+    let mut lines = vec![
+        Line::from(format!("A: {}", describe(left, left_tab))),
+        Line::from(format!("B: {}", describe(right, right_tab))),
         Line::from(""),
-        Line::from(vec![
-            Span::styled("  [C/Esc] ", Style::default().fg(Color::Yellow).bold()),
-            Span::raw("Cancel (stay connected)"),
-        ]),
+        heading("Broker properties"),
+        Line::from(Span::styled(
+            format!("  {:<26} {:<36} {}", "", "A", "B"),
+            Style::default().fg(Color::DarkGray),
+        )),
     ];
+    lines.extend(broker_property_diff(left, right).iter().map(property_line));
 
-    render_centered_lines(frame, inner, text);
-}
-
-fn render_form(frame: &mut Frame, app: &mut App, title: &str, hint: &str) {
-    let san_ml = |s: &str| sanitize_for_terminal(s, true);
-
-    // Check if the first field is a Body field (SendMessage / EditResend forms).
-    let has_body = app
-        .input_fields
-        .first()
-        .map(|(l, _)| l == "Body")
-        .unwrap_or(false);
+    lines.push(Line::from(""));
+    lines.push(heading("Custom properties"));
+    let custom = custom_property_diff(left, right);
+    if custom.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "  (none)",
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+    lines.extend(custom.iter().map(property_line));
 
-    if has_body {
-        render_form_with_body(frame, app, title, hint, &san_ml);
-    } else {
-        render_form_flat(frame, app, title, hint);
+    lines.push(Line::from(""));
+    lines.push(heading("Body (- only in A, + only in B)"));
+    for line in &app.compare_body_diff {
+        let (prefix, text, style) = match line {
+            DiffLine::Same(t) => ("  ", t, Style::default()),
+            DiffLine::Removed(t) => ("- ", t, Style::default().fg(Color::Red)),
+            DiffLine::Added(t) => ("+ ", t, Style::default().fg(Color::Green)),
+        };
+        lines.push(Line::from(Span::styled(
+            format!("{}{}", prefix, san(text)),
+            style,
+        )));
     }
-}
 
-/// Form layout for Send/EditResend: multiline body area + single-line property fields.
-fn render_form_with_body(
-    frame: &mut Frame,
-    app: &mut App,
-    title: &str,
-    hint: &str,
-    san_ml: &dyn Fn(&str) -> String,
-) {
-    // Properties = fields 1..N, each needs 2 rows (label + value).
-    let prop_count = app.input_fields.len().saturating_sub(1);
-    let props_height = (prop_count as u16) * 2;
-    // body area (bordered, min 8) + properties + hint + outer block borders (2) + margin (2)
-    let min_height = 10 + props_height + 1 + 2 + 2;
-    // Use 80% of terminal height, but at least min_height
-    let desired = (frame.area().height * 80 / 100).max(min_height);
-    let area = centered_rect_abs_height(70, desired, frame.area());
-    frame.render_widget(Clear, area);
+    frame.render_widget(
+        Paragraph::new(lines).scroll((app.compare_scroll, 0)),
+        layout[0],
+    );
+    render_shortcut_hints(
+        frame,
+        layout[1],
+        &[("↑↓/PgUp/PgDn", " scroll  "), ("Esc", " close")],
+    );
+}
 
-    let block = Block::default()
-        .title(format!(" {} ", title))
-        .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Cyan));
-    let inner = block.inner(area);
-    frame.render_widget(block, area);
+fn render_message_search_input(frame: &mut Frame, app: &App) {
+    let area = centered_rect(60, 20, frame.area());
+    let inner = render_popup_block(frame, area, " Search Namespace ".to_string(), Color::Cyan);
 
-    let form_layout = Layout::default()
+    let layout = Layout::default()
         .direction(Direction::Vertical)
-        .margin(1)
         .constraints([
-            Constraint::Min(8),               // body area (bordered)
-            Constraint::Length(props_height), // property fields
-            Constraint::Length(1),            // hint line
+            Constraint::Length(2),
+            Constraint::Length(3),
+            Constraint::Length(1),
         ])
+        .margin(1)
         .split(inner);
 
-    let body_area = form_layout[0];
-    let props_area = form_layout[1];
-    let hint_area = form_layout[2];
+    let hint = Paragraph::new(format!(
+        "MessageId or CorrelationId (exact match)\nBest-effort: peeks the first {} messages of every queue, subscription and DLQ",
+        app.config.settings.search_peek_cap
+    ))
+    .style(Style::default().fg(Color::DarkGray));
+    frame.render_widget(hint, layout[0]);
 
-    // ── Body field (index 0) ──
-    let body_is_active = app.input_field_index == 0;
-    let body_border_style = if body_is_active {
-        Style::default().fg(Color::Cyan)
-    } else {
-        Style::default().fg(Color::Yellow)
-    };
+    let input = Paragraph::new(app.input_buffer.as_str())
+        .style(Style::default().fg(Color::White))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Cyan)),
+        );
+    frame.render_widget(input, layout[1]);
+    set_single_line_cursor(frame, layout[1], &app.input_buffer, app.input_cursor);
+
+    render_shortcut_hints(
+        frame,
+        layout[2],
+        &[("Enter", " search  "), ("Esc", " cancel")],
+    );
+}
+
+fn render_filter_presets(frame: &mut Frame, app: &App) {
+    let area = centered_rect(60, 50, frame.area());
+    let inner = render_popup_block(frame, area, " Message Filters ".to_string(), Color::Cyan);
+
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2),
+            Constraint::Min(1),
+            Constraint::Length(1),
+        ])
+        .margin(1)
+        .split(inner);
+
+    let active = match app.message_filter {
+        Some(ref f) => Line::from(vec![
+            Span::styled("Active: ", Style::default().fg(Color::DarkGray)),
+            Span::styled(
+                sanitize_for_terminal(&f.describe(), false),
+                Style::default().fg(Color::Yellow),
+            ),
+        ]),
+        None => Line::from(Span::styled(
+            "No filter active",
+            Style::default().fg(Color::DarkGray),
+        )),
+    };
+    frame.render_widget(Paragraph::new(active), layout[0]);
+
+    if app.config.filter_presets.is_empty() {
+        frame.render_widget(
+            Paragraph::new("No saved presets. Press / to type a filter, then s to save it.")
+                .style(Style::default().fg(Color::DarkGray)),
+            layout[1],
+        );
+    } else {
+        let rows: Vec<Row> = app
+            .config
+            .filter_presets
+            .iter()
+            .enumerate()
+            .map(|(i, p)| {
+                let style = if i == app.filter_preset_selected {
+                    Style::default().bg(Color::DarkGray).fg(Color::White)
+                } else {
+                    Style::default()
+                };
+                Row::new(vec![
+                    Cell::from(sanitize_for_terminal(&p.name, false)),
+                    Cell::from(p.scope.label()),
+                    Cell::from(sanitize_for_terminal(&p.expression, false)),
+                ])
+                .style(style)
+            })
+            .collect();
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Percentage(30),
+                Constraint::Length(8),
+                Constraint::Min(10),
+            ],
+        )
+        .header(
+            Row::new(vec!["Name", "Scope", "Expression"])
+                .style(Style::default().fg(Color::Yellow).bold()),
+        );
+        let mut state = TableState::default().with_selected(Some(app.filter_preset_selected));
+        frame.render_stateful_widget(table, layout[1], &mut state);
+    }
+
+    render_shortcut_hints(
+        frame,
+        layout[2],
+        &[
+            ("Enter", " apply  "),
+            ("/", " new  "),
+            ("s", " save active  "),
+            ("e", " edit  "),
+            ("r", " rename  "),
+            ("d", " delete  "),
+            ("c", " clear  "),
+            ("Esc", " close"),
+        ],
+    );
+}
+
+fn render_message_filter_input(
+    frame: &mut Frame,
+    app: &App,
+    scope: FilterScope,
+    preset: Option<usize>,
+) {
+    let area = centered_rect(60, 20, frame.area());
+    let title = match preset.and_then(|i| app.config.filter_presets.get(i)) {
+        Some(p) => format!(" Edit Filter — {} ", p.name),
+        None => " Filter Messages ".to_string(),
+    };
+    let inner = render_popup_block(frame, area, title, Color::Cyan);
+
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2),
+            Constraint::Length(3),
+            Constraint::Length(1),
+        ])
+        .margin(1)
+        .split(inner);
+
+    let example = match scope {
+        FilterScope::Body => "text the body contains",
+        FilterScope::BrokerProperties => "Label=order.created, or a property name",
+        FilterScope::CustomProperties => "tenantId=contoso, or a property name",
+    };
+    let hint = Paragraph::new(vec![
+        Line::from(vec![
+            Span::styled("Match against: ", Style::default().fg(Color::DarkGray)),
+            Span::styled(scope.label(), Style::default().fg(Color::Yellow).bold()),
+        ]),
+        Line::from(Span::styled(example, Style::default().fg(Color::DarkGray))),
+    ]);
+    frame.render_widget(hint, layout[0]);
+
+    let input = Paragraph::new(app.input_buffer.as_str())
+        .style(Style::default().fg(Color::White))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Cyan)),
+        );
+    frame.render_widget(input, layout[1]);
+    set_single_line_cursor(frame, layout[1], &app.input_buffer, app.input_cursor);
+
+    let enter = if preset.is_some() {
+        " save  "
+    } else {
+        " apply  "
+    };
+    render_shortcut_hints(
+        frame,
+        layout[2],
+        &[("Enter", enter), ("Tab", " scope  "), ("Esc", " back")],
+    );
+}
+
+fn render_filter_preset_name_input(frame: &mut Frame, app: &App, preset: Option<usize>) {
+    let area = centered_rect(50, 20, frame.area());
+    let title = if preset.is_some() {
+        " Rename Filter Preset "
+    } else {
+        " Save Filter Preset "
+    };
+    let inner = render_popup_block(frame, area, title.to_string(), Color::Cyan);
+
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1),
+            Constraint::Length(3),
+            Constraint::Length(1),
+        ])
+        .margin(1)
+        .split(inner);
+
+    frame.render_widget(
+        Paragraph::new("Preset name").style(Style::default().fg(Color::DarkGray)),
+        layout[0],
+    );
+    let input = Paragraph::new(app.input_buffer.as_str())
+        .style(Style::default().fg(Color::White))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Cyan)),
+        );
+    frame.render_widget(input, layout[1]);
+    set_single_line_cursor(frame, layout[1], &app.input_buffer, app.input_cursor);
+
+    render_shortcut_hints(frame, layout[2], &[("Enter", " save  "), ("Esc", " back")]);
+}
+
+fn render_confirm_filter_peek(frame: &mut Frame, app: &App) {
+    let area = centered_rect(50, 20, frame.area());
+    let inner = render_popup_block(frame, area, " Peek First? ".to_string(), Color::Cyan);
+
+    render_centered_lines(
+        frame,
+        inner,
+        vec![
+            Line::from(""),
+            Line::from(Span::styled(
+                "This list hasn't been peeked yet.",
+                Style::default().fg(Color::White).bold(),
+            )),
+            Line::from(format!(
+                "Peek {} messages and filter them?",
+                app.config.settings.peek_count
+            )),
+            Line::from(""),
+            Line::from(Span::styled(
+                "Press 'y' to peek, 'n' or Esc to keep the empty list",
+                Style::default().fg(Color::DarkGray),
+            )),
+        ],
+    );
+}
+
+fn render_search_results(frame: &mut Frame, app: &App) {
+    let Some(ref results) = app.search_results else {
+        return;
+    };
+    let area = centered_rect(70, 60, frame.area());
+    let title = format!(
+        " Search — {} ",
+        sanitize_for_terminal(&results.query, false)
+    );
+    let inner = render_popup_block(frame, area, title, Color::Cyan);
+
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2),
+            Constraint::Min(1),
+            Constraint::Length(1),
+        ])
+        .margin(1)
+        .split(inner);
+
+    let mut summary = format!(
+        "{} match(es) in {}/{} entities",
+        results.hits.len(),
+        results.searched,
+        results.total
+    );
+    if results.cancelled {
+        summary.push_str(" (cancelled)");
+    }
+    if results.failed > 0 {
+        summary.push_str(&format!(", {} could not be peeked", results.failed));
+    }
+    let header = vec![
+        Line::from(summary),
+        Line::from(Span::styled(
+            format!(
+                "Best-effort scan of the first {} messages per entity — not an index",
+                app.config.settings.search_peek_cap
+            ),
+            Style::default().fg(Color::Yellow),
+        )),
+    ];
+    frame.render_widget(Paragraph::new(header), layout[0]);
+
+    let rows: Vec<Row> = results
+        .hits
+        .iter()
+        .enumerate()
+        .map(|(i, hit)| {
+            let style = if i == app.search_selected {
+                Style::default().bg(Color::DarkGray).fg(Color::White)
+            } else {
+                Style::default()
+            };
+            Row::new(vec![
+                hit.entity_path.clone(),
+                if hit.is_dlq { "DLQ" } else { "Active" }.to_string(),
+                hit.sequence_number
+                    .map(|n| n.to_string())
+                    .unwrap_or_else(|| "-".to_string()),
+                sanitize_for_terminal(hit.message_id.as_deref().unwrap_or("-"), false),
+            ])
+            .style(style)
+        })
+        .collect();
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Percentage(40),
+            Constraint::Length(7),
+            Constraint::Length(12),
+            Constraint::Min(10),
+        ],
+    )
+    .header(
+        Row::new(vec!["Entity", "Queue", "Seq #", "Message ID"])
+            .style(Style::default().fg(Color::Yellow).bold()),
+    );
+    let mut state = TableState::default().with_selected(Some(app.search_selected));
+    frame.render_stateful_widget(table, layout[1], &mut state);
+
+    render_shortcut_hints(
+        frame,
+        layout[2],
+        &[
+            ("↑↓/j/k", " navigate  "),
+            ("Enter", " jump to entity  "),
+            ("Esc", " close"),
+        ],
+    );
+}
+
+fn render_sessions(frame: &mut Frame, app: &App) {
+    let Some(ref browse) = app.session_browse else {
+        return;
+    };
+    let area = centered_rect(60, 60, frame.area());
+    let title = format!(" Sessions — {} ", browse.entity_path);
+    let inner = render_popup_block(frame, area, title, Color::Cyan);
+
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2),
+            Constraint::Min(1),
+            Constraint::Length(1),
+        ])
+        .margin(1)
+        .split(inner);
+
+    let mut summary = format!(
+        "{} session(s) in {} peeked message(s)",
+        browse.sessions.len(),
+        browse.messages.len()
+    );
+    if browse.truncated {
+        summary.push_str(" — stopped early, counts are lower bounds");
+    }
+    frame.render_widget(
+        Paragraph::new(summary).style(Style::default().fg(Color::DarkGray)),
+        layout[0],
+    );
+
+    let rows: Vec<Row> = browse
+        .sessions
+        .iter()
+        .enumerate()
+        .map(|(i, (session_id, count))| {
+            let style = if i == app.session_selected {
+                Style::default().bg(Color::DarkGray).fg(Color::White)
+            } else {
+                Style::default()
+            };
+            Row::new(vec![
+                sanitize_for_terminal(session_id, false),
+                count.to_string(),
+            ])
+            .style(style)
+        })
+        .collect();
+    let table = Table::new(rows, [Constraint::Min(20), Constraint::Length(10)]).header(
+        Row::new(vec!["Session ID", "Messages"]).style(Style::default().fg(Color::Yellow).bold()),
+    );
+    let mut state = TableState::default().with_selected(Some(app.session_selected));
+    frame.render_stateful_widget(table, layout[1], &mut state);
+
+    render_shortcut_hints(
+        frame,
+        layout[2],
+        &[
+            ("↑↓/j/k", " navigate  "),
+            ("Enter", " show messages  "),
+            ("Esc", " close"),
+        ],
+    );
+}
+
+fn render_forward_to_input(frame: &mut Frame, app: &App, path: &str) {
+    let area = centered_rect(60, 20, frame.area());
+    let inner = render_popup_block(frame, area, format!(" Forward To — {} ", path), Color::Cyan);
+
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2),
+            Constraint::Length(3),
+            Constraint::Length(1),
+        ])
+        .margin(1)
+        .split(inner);
+
+    // A refused chain replaces the hint until the input changes
+    let hint = match app.form_error {
+        Some(ref err) => Paragraph::new(sanitize_for_terminal(err, false))
+            .style(Style::default().fg(Color::Red).bold())
+            .wrap(Wrap { trim: true }),
+        None => Paragraph::new(
+            "Destination queue or topic for auto-forwarding
+Leave blank to stop forwarding",
+        )
+        .style(Style::default().fg(Color::DarkGray)),
+    };
+    frame.render_widget(hint, layout[0]);
+
+    let input = Paragraph::new(app.input_buffer.as_str())
+        .style(Style::default().fg(Color::White))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Cyan)),
+        );
+    frame.render_widget(input, layout[1]);
+    set_single_line_cursor(frame, layout[1], &app.input_buffer, app.input_cursor);
+
+    render_shortcut_hints(
+        frame,
+        layout[2],
+        &[("Enter", " save  "), ("Esc", " cancel")],
+    );
+}
+
+fn render_bulk_resend_delay_input(frame: &mut Frame, app: &App, count: u32) {
+    let area = centered_rect(60, 20, frame.area());
+    let inner = render_popup_block(
+        frame,
+        area,
+        format!(" Resend Delay — {} messages ", count),
+        Color::Cyan,
+    );
+
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2),
+            Constraint::Length(3),
+            Constraint::Length(1),
+        ])
+        .margin(1)
+        .split(inner);
+
+    let hint = Paragraph::new(
+        "Delay before resent messages appear, e.g. 10m or 1h over 30m (spread)
+Leave blank to resend immediately",
+    )
+    .style(Style::default().fg(Color::DarkGray));
+    frame.render_widget(hint, layout[0]);
+
+    let input = Paragraph::new(app.input_buffer.as_str())
+        .style(Style::default().fg(Color::White))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Cyan)),
+        );
+    frame.render_widget(input, layout[1]);
+    set_single_line_cursor(frame, layout[1], &app.input_buffer, app.input_cursor);
+
+    render_shortcut_hints(frame, layout[2], &[("Enter", " set  "), ("Esc", " back")]);
+}
+
+fn render_connection_switch(frame: &mut Frame, app: &App) {
+    let area = centered_rect(50, 55, frame.area());
+    let inner = render_popup_block(
+        frame,
+        area,
+        " Connection Management ".to_string(),
+        Color::Cyan,
+    );
+
+    let current_conn = app.connection_name.as_deref().unwrap_or("Unknown");
+
+    let text = vec![
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("Current connection: ", Style::default().fg(Color::DarkGray)),
+            Span::styled(current_conn, Style::default().fg(Color::White).bold()),
+        ]),
+        Line::from(""),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("  [D] ", Style::default().fg(Color::Yellow).bold()),
+            Span::raw("Disconnect"),
+        ]),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("  [S] ", Style::default().fg(Color::Yellow).bold()),
+            Span::raw("Switch connection"),
+        ]),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("  [R] ", Style::default().fg(Color::Yellow).bold()),
+            Span::raw("Rename connection"),
+        ]),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("  [X] ", Style::default().fg(Color::Yellow).bold()),
+            Span::raw("Delete connection (and disconnect)"),
+        ]),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("  [C/Esc] ", Style::default().fg(Color::Yellow).bold()),
+            Span::raw("Cancel (stay connected)"),
+        ]),
+    ];
+
+    render_centered_lines(frame, inner, text);
+}
+
+fn render_form(frame: &mut Frame, app: &mut App, title: &str, hint: &str) {
+    let san_ml = |s: &str| sanitize_for_terminal(s, true);
+    let title = if app.form_submitting {
+        format!("{} (sending...)", title)
+    } else {
+        title.to_string()
+    };
+    let title = title.as_str();
+
+    // Check if the first field is a Body field (SendMessage / EditResend forms).
+    let has_body = app
+        .input_fields
+        .first()
+        .map(|(l, _)| l == "Body")
+        .unwrap_or(false);
+
+    if has_body {
+        render_form_with_body(frame, app, title, hint, &san_ml);
+    } else {
+        render_form_flat(frame, app, title, hint);
+    }
+}
+
+/// Form layout for Send/EditResend: multiline body area + single-line property fields.
+fn render_form_with_body(
+    frame: &mut Frame,
+    app: &mut App,
+    title: &str,
+    hint: &str,
+    san_ml: &dyn Fn(&str) -> String,
+) {
+    // Properties = fields 1..N, each needs 2 rows (label + value).
+    let prop_count = app.input_fields.len().saturating_sub(1);
+    let props_height = (prop_count as u16) * 2;
+    // body area (bordered, min 8) + properties + hint + outer block borders (2) + margin (2)
+    let min_height = 10 + props_height + 1 + 2 + 2;
+    // Use 80% of terminal height, but at least min_height
+    let desired = (frame.area().height * 80 / 100).max(min_height);
+    let area = centered_rect_abs_height(70, desired, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(format!(" {} ", title))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let form_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Min(8),               // body area (bordered)
+            Constraint::Length(props_height), // property fields
+            Constraint::Length(1),            // hint line
+        ])
+        .split(inner);
+
+    let body_area = form_layout[0];
+    let props_area = form_layout[1];
+    let hint_area = form_layout[2];
+
+    // ── Body field (index 0) ──
+    let body_is_active = app.input_field_index == 0;
+    let body_border_style = if body_is_active {
+        Style::default().fg(Color::Cyan)
+    } else {
+        Style::default().fg(Color::Yellow)
+    };
     let body_block = Block::default()
         .title(if body_is_active {
             " Body (editing) "
@@ -535,12 +1422,16 @@ fn render_form_with_body(
         );
     }
 
-    // ── Hint line ──
-    let hint_widget = Paragraph::new(format!(
-        "Tab fields · ↑↓←→ navigate · Enter newline (body) · {} · Esc cancel",
-        hint
-    ))
-    .style(Style::default().fg(Color::DarkGray));
+    // ── Hint line (or the body validation error) ──
+    let hint_widget = match app.form_error {
+        Some(ref err) => Paragraph::new(sanitize_for_terminal(err, false))
+            .style(Style::default().fg(Color::Red).bold()),
+        None => Paragraph::new(format!(
+            "Tab fields · ↑↓←→ navigate · Enter newline (body) · Ctrl+F format · {} · Esc cancel",
+            hint
+        ))
+        .style(Style::default().fg(Color::DarkGray)),
+    };
     frame.render_widget(hint_widget, hint_area);
 }
 
@@ -590,7 +1481,12 @@ fn render_form_flat(frame: &mut Frame, app: &App, title: &str, hint: &str) {
             Style::default().fg(Color::DarkGray)
         };
 
-        let label_widget = Paragraph::new(format!("{}:", label)).style(label_style);
+        let label_text = if app.is_field_read_only(idx) {
+            format!("{} (read-only):", label)
+        } else {
+            format!("{}:", label)
+        };
+        let label_widget = Paragraph::new(label_text).style(label_style);
         frame.render_widget(label_widget, layout[label_idx]);
 
         let value_style = if is_active {
@@ -611,14 +1507,18 @@ fn render_form_flat(frame: &mut Frame, app: &App, title: &str, hint: &str) {
         frame.render_widget(value_widget, layout[value_idx]);
     }
 
-    // Hint line
+    // Hint line (or the forwarding chain error)
     let hint_idx = app.input_fields.len() * 2;
     if hint_idx < layout.len() {
-        let hint_widget = Paragraph::new(format!(
-            "Tab/↑↓ navigate · ←→/Home/End cursor · {} · Esc cancel",
-            hint
-        ))
-        .style(Style::default().fg(Color::DarkGray));
+        let hint_widget = match app.form_error {
+            Some(ref err) => Paragraph::new(sanitize_for_terminal(err, false))
+                .style(Style::default().fg(Color::Red).bold()),
+            None => Paragraph::new(format!(
+                "Tab/↑↓ navigate · ←→/Home/End cursor · {} · Esc cancel",
+                hint
+            ))
+            .style(Style::default().fg(Color::DarkGray)),
+        };
         frame.render_widget(hint_widget, layout[hint_idx]);
     }
 }
@@ -653,17 +1553,127 @@ fn render_confirm_delete(frame: &mut Frame, path: &str) {
     );
 }
 
-fn truncate(s: &str, max_len: usize) -> String {
-    if s.len() <= max_len {
-        s.to_string()
+fn render_confirm_delete_connection(frame: &mut Frame, name: &str) {
+    let area = centered_rect(50, 20, frame.area());
+    let inner = render_popup_block(frame, area, " Delete Connection ".to_string(), Color::Red);
+
+    render_centered_lines(
+        frame,
+        inner,
+        vec![
+            Line::from(""),
+            Line::from(Span::styled(
+                format!("Delete saved connection '{}'?", name),
+                Style::default().fg(Color::Red).bold(),
+            )),
+            Line::from("You will be disconnected."),
+            Line::from(""),
+            Line::from(Span::styled(
+                "Press 'y' to confirm, 'n' or Esc to cancel",
+                Style::default().fg(Color::DarkGray),
+            )),
+        ],
+    );
+}
+
+fn render_confirm_delete_filter_preset(frame: &mut Frame, name: &str) {
+    let area = centered_rect(50, 20, frame.area());
+    let inner = render_popup_block(
+        frame,
+        area,
+        " Delete Filter Preset ".to_string(),
+        Color::Red,
+    );
+
+    render_centered_lines(
+        frame,
+        inner,
+        vec![
+            Line::from(""),
+            Line::from(Span::styled(
+                format!(
+                    "Delete filter preset '{}'?",
+                    sanitize_for_terminal(name, false)
+                ),
+                Style::default().fg(Color::Red).bold(),
+            )),
+            Line::from(""),
+            Line::from(Span::styled(
+                "Press 'y' to confirm, 'n' or Esc to cancel",
+                Style::default().fg(Color::DarkGray),
+            )),
+        ],
+    );
+}
+
+/// `1234567` → `1,234,567`.
+fn group_digits(n: u64) -> String {
+    let digits = n.to_string();
+    let mut out = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            out.push(',');
+        }
+        out.push(c);
+    }
+    out
+}
+
+fn render_confirm_quit(frame: &mut Frame, app: &App) {
+    let area = centered_rect(55, 30, frame.area());
+    let inner = render_popup_block(frame, area, " Quit? ".to_string(), Color::Yellow);
+
+    let running = match app.bg_job {
+        Some(ref job) => match job.processed() {
+            Some(n) => format!(
+                "{} is running ({} processed so far)",
+                job.operation,
+                group_digits(n)
+            ),
+            None => format!("{} is running", job.operation),
+        },
+        None => "A background operation is running".to_string(),
+    };
+    let option = |key: &str, text: &str, color: Color| {
+        Line::from(vec![
+            Span::styled(format!("  [{}] ", key), Style::default().fg(color).bold()),
+            Span::styled(text.to_string(), Style::default().fg(Color::White)),
+        ])
+    };
+
+    let lines = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            running,
+            Style::default().fg(Color::Yellow).bold(),
+        )),
+        Line::from(""),
+        option("Q", "Quit anyway (stops it mid-way)", Color::Red),
+        option("C", "Cancel the operation, then quit", Color::Yellow),
+        option("K", "Keep running", Color::Green),
+        Line::from(""),
+        Line::from(Span::styled(
+            "Esc to keep running",
+            Style::default().fg(Color::DarkGray),
+        )),
+    ];
+
+    render_centered_lines(frame, inner, lines);
+}
+
+/// " Clear Entity [dry run] " while dry-run mode is on.
+fn dry_run_title(title: &str, dry_run: bool) -> String {
+    if dry_run {
+        format!(" {} [dry run] ", title)
     } else {
-        format!("{}…", &s[..max_len])
+        format!(" {} ", title)
     }
 }
 
-fn render_confirm_bulk(frame: &mut Frame, title: &str, message: &str, color: Color) {
+fn render_confirm_bulk(frame: &mut Frame, title: &str, message: &str, color: Color, dry_run: bool) {
     let area = centered_rect(55, 25, frame.area());
-    let inner = render_popup_block(frame, area, format!(" {} ", title), color);
+    let color = if dry_run { Color::Cyan } else { color };
+    let inner = render_popup_block(frame, area, dry_run_title(title, dry_run), color);
 
     let mut lines = vec![Line::from("")];
     for line in message.lines() {
@@ -673,21 +1683,32 @@ fn render_confirm_bulk(frame: &mut Frame, title: &str, message: &str, color: Col
         )));
     }
     lines.push(Line::from(""));
+    let hint = if dry_run {
+        "Press 'y' to scan without changing anything, 't' for the real run, 'n' or Esc to cancel"
+    } else {
+        "Press 'y' to confirm, 't' for a dry run, 'n' or Esc to cancel"
+    };
     lines.push(Line::from(Span::styled(
-        "Press 'y' to confirm, 'n' or Esc to cancel",
+        hint,
         Style::default().fg(Color::DarkGray),
     )));
 
     render_centered_lines(frame, inner, lines);
 }
 
-fn render_peek_count_input(frame: &mut Frame, app: &App) {
-    let area = centered_rect(45, 20, frame.area());
-    let inner = render_popup_block(frame, area, " Peek Messages ".to_string(), Color::Cyan);
+fn render_peek_count_input(frame: &mut Frame, app: &App, custom: bool) {
+    let area = centered_rect(50, 30, frame.area());
+    let title = if app.peek_both {
+        " Peek Messages + DLQ "
+    } else {
+        " Peek Messages "
+    };
+    let inner = render_popup_block(frame, area, title.to_string(), Color::Cyan);
 
     let layout = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
+            Constraint::Length(1),
             Constraint::Length(1),
             Constraint::Length(1),
             Constraint::Length(3),
@@ -701,25 +1722,61 @@ fn render_peek_count_input(frame: &mut Frame, app: &App) {
         Paragraph::new("How many messages to peek?").style(Style::default().fg(Color::White));
     frame.render_widget(label, layout[0]);
 
+    let all_label = match app.pending_peekable_count() {
+        Some(n) => format!("all ({})", n.min(i64::from(app.config.settings.peek_max))),
+        None => "all".to_string(),
+    };
+    let preset_style = if custom {
+        Style::default().fg(Color::DarkGray)
+    } else {
+        Style::default().fg(Color::Yellow)
+    };
+    let presets = Line::from(vec![
+        Span::styled("1", preset_style),
+        Span::raw(" 10  "),
+        Span::styled("2", preset_style),
+        Span::raw(" 100  "),
+        Span::styled("3", preset_style),
+        Span::raw(" 1000  "),
+        Span::styled("a", preset_style),
+        Span::raw(format!(" {}", all_label)),
+    ]);
+    frame.render_widget(Paragraph::new(presets), layout[2]);
+
+    let border_color = if custom {
+        Color::Yellow
+    } else {
+        Color::DarkGray
+    };
     let input = Paragraph::new(app.input_buffer.as_str())
         .style(Style::default().fg(Color::White))
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::Yellow)),
+                .border_style(Style::default().fg(border_color)),
         );
-    frame.render_widget(input, layout[2]);
+    frame.render_widget(input, layout[3]);
 
-    let hint =
-        Paragraph::new("Enter to peek · Esc to cancel").style(Style::default().fg(Color::DarkGray));
-    frame.render_widget(hint, layout[3]);
+    let hint_text = if custom {
+        format!(
+            "Enter to peek · Esc to cancel · max {}",
+            app.config.settings.peek_max
+        )
+    } else {
+        "Enter to peek last used · c custom · Esc to cancel".to_string()
+    };
+    let hint = Paragraph::new(hint_text).style(Style::default().fg(Color::DarkGray));
+    frame.render_widget(hint, layout[4]);
 
-    set_single_line_cursor(frame, layout[2], app.input_cursor);
+    if custom {
+        set_single_line_cursor(frame, layout[3], &app.input_buffer, app.input_cursor);
+    }
 }
 
-fn render_clear_options(frame: &mut Frame, entity_path: &str) {
+fn render_clear_options(frame: &mut Frame, entity_path: &str, dry_run: bool, dlq_only: bool) {
     let area = centered_rect(58, 35, frame.area());
-    let inner = render_popup_block(frame, area, " Clear Entity ".to_string(), Color::Yellow);
+    let color = if dry_run { Color::Cyan } else { Color::Yellow };
+    let inner = render_popup_block(frame, area, dry_run_title("Clear Entity", dry_run), color);
 
     let entity_display = if entity_path.len() > 40 {
         format!("...{}", &entity_path[entity_path.len() - 37..])
@@ -727,39 +1784,504 @@ fn render_clear_options(frame: &mut Frame, entity_path: &str) {
         entity_path.to_string()
     };
 
-    let lines = vec![
+    let mut lines = vec![
         Line::from(""),
         Line::from(Span::styled(
             entity_display,
             Style::default().fg(Color::White).bold(),
         )),
         Line::from(""),
+    ];
+    if !dlq_only {
+        lines.push(Line::from(vec![
+            Span::styled("  [D] ", Style::default().fg(Color::Red).bold()),
+            Span::styled(
+                "Delete ALL active messages",
+                Style::default().fg(Color::White),
+            ),
+        ]));
+        lines.push(Line::from(""));
+    }
+    lines.extend([
+        Line::from(vec![
+            Span::styled("  [L] ", Style::default().fg(Color::Red).bold()),
+            Span::styled(
+                "Delete ALL dead-letter messages",
+                Style::default().fg(Color::White),
+            ),
+        ]),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("  [R] ", Style::default().fg(Color::Yellow).bold()),
+            Span::styled(
+                "Resend ALL DLQ → main entity",
+                Style::default().fg(Color::White),
+            ),
+        ]),
+        Line::from(""),
+        Line::from(Span::styled(
+            if dry_run {
+                "Dry run: only counts what would be touched · t real run · Esc to cancel"
+            } else {
+                "t dry run · Esc to cancel"
+            },
+            Style::default().fg(Color::DarkGray),
+        )),
+    ]);
+
+    render_centered_lines(frame, inner, lines);
+}
+
+fn render_dry_run_summary(frame: &mut Frame, app: &App) {
+    let Some(ref report) = app.dry_run_report else {
+        return;
+    };
+
+    let area = centered_rect(70, 60, frame.area());
+    let inner = render_popup_block(frame, area, " Dry Run ".to_string(), Color::Cyan);
+
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2),
+            Constraint::Min(1),
+            Constraint::Length(1),
+        ])
+        .margin(1)
+        .split(inner);
+
+    frame.render_widget(
+        Paragraph::new(sanitize_for_terminal(&report.summary(), false))
+            .style(Style::default().fg(Color::White).bold())
+            .wrap(Wrap { trim: true }),
+        layout[0],
+    );
+
+    let mut lines: Vec<Line> = report
+        .counts
+        .iter()
+        .map(|(path, result)| {
+            let (count, style) = match result {
+                Ok(n) => (n.to_string(), Style::default().fg(Color::Yellow)),
+                Err(e) => (format!("failed: {}", e), Style::default().fg(Color::Red)),
+            };
+            Line::from(vec![
+                Span::raw(format!("  {}  ", sanitize_for_terminal(path, false))),
+                Span::styled(count, style),
+            ])
+        })
+        .collect();
+    if !report.sample_ids.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            "Sample message ids:",
+            Style::default().fg(Color::DarkGray),
+        )));
+        for id in &report.sample_ids {
+            lines.push(Line::from(format!(
+                "  {}",
+                sanitize_for_terminal(id, false)
+            )));
+        }
+    }
+    frame.render_widget(Paragraph::new(lines), layout[1]);
+
+    render_shortcut_hints(
+        frame,
+        layout[2],
+        &[("Enter", " run it for real  "), ("Esc", " close")],
+    );
+}
+
+fn render_resend_report(frame: &mut Frame, app: &App) {
+    let Some(ref report) = app.resend_report else {
+        return;
+    };
+
+    let area = centered_rect(75, 60, frame.area());
+    let inner = render_popup_block(frame, area, " Resend Failures ".to_string(), Color::Red);
+
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2),
+            Constraint::Min(1),
+            Constraint::Length(1),
+        ])
+        .margin(1)
+        .split(inner);
+
+    let first = usize::from(app.resend_report_scroll).min(report.failures.len());
+    let visible = usize::from(layout[1].height);
+    let shown = if report.failures.len() > visible {
+        format!(
+            " (showing {}–{})",
+            first + 1,
+            (first + visible).min(report.failures.len())
+        )
+    } else {
+        String::new()
+    };
+    frame.render_widget(
+        Paragraph::new(format!(
+            "{} message(s) could not be resent and are still in the DLQ of {}{}",
+            report.failures.len(),
+            sanitize_for_terminal(&report.entity_path, false),
+            shown
+        ))
+        .style(Style::default().fg(Color::White).bold())
+        .wrap(Wrap { trim: true }),
+        layout[0],
+    );
+
+    let lines: Vec<Line> = report
+        .failures
+        .iter()
+        .map(|failure| {
+            Line::from(vec![
+                Span::styled(
+                    format!("  {}  ", sanitize_for_terminal(&failure.describe(), false)),
+                    Style::default().fg(Color::Yellow),
+                ),
+                Span::styled(
+                    sanitize_for_terminal(&failure.error, false),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ])
+        })
+        .collect();
+    frame.render_widget(
+        Paragraph::new(lines).scroll((app.resend_report_scroll, 0)),
+        layout[1],
+    );
+
+    render_shortcut_hints(
+        frame,
+        layout[2],
+        &[
+            ("r", " retry failed  "),
+            ("↑↓/PgUp/PgDn", " scroll  "),
+            ("Esc", " close"),
+        ],
+    );
+}
+
+fn render_api_stats(frame: &mut Frame, app: &App) {
+    let Some(ref config) = app.connection_config else {
+        return;
+    };
+    let stats = config.request_stats();
+
+    let area = centered_rect(60, 40, frame.area());
+    let inner = render_popup_block(frame, area, " API Latency ".to_string(), Color::Cyan);
+
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2),
+            Constraint::Min(1),
+            Constraint::Length(1),
+        ])
+        .margin(1)
+        .split(inner);
+
+    frame.render_widget(
+        Paragraph::new(format!(
+            "Last {} requests per API; empty receives don't count toward latency",
+            STATS_WINDOW
+        ))
+        .style(Style::default().fg(Color::DarkGray))
+        .wrap(Wrap { trim: true }),
+        layout[0],
+    );
+
+    let ms = |d: Option<std::time::Duration>| {
+        d.map_or("—".to_string(), |d| format!("{}ms", d.as_millis()))
+    };
+    let rows: Vec<Row> = Api::ALL
+        .iter()
+        .map(|&api| {
+            let summary = stats.summary(api);
+            let color = if summary.calls == 0 {
+                Color::DarkGray
+            } else {
+                match ApiHealth::of(&summary) {
+                    ApiHealth::Good => Color::Green,
+                    ApiHealth::Slow => Color::Yellow,
+                    ApiHealth::Poor => Color::Red,
+                }
+            };
+            Row::new(vec![
+                Cell::from(Span::styled("●", Style::default().fg(color))),
+                Cell::from(api.label()),
+                Cell::from(summary.calls.to_string()),
+                Cell::from(ms(summary.p50)),
+                Cell::from(ms(summary.p95)),
+                Cell::from(format!("{:.0}%", summary.error_rate() * 100.0)),
+            ])
+        })
+        .collect();
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(2),
+            Constraint::Length(12),
+            Constraint::Length(7),
+            Constraint::Length(9),
+            Constraint::Length(9),
+            Constraint::Length(7),
+        ],
+    )
+    .header(
+        Row::new(vec!["", "API", "Calls", "p50", "p95", "Errors"])
+            .style(Style::default().fg(Color::Cyan).bold()),
+    );
+    frame.render_widget(table, layout[1]);
+
+    render_shortcut_hints(frame, layout[2], &[("Esc", " close")]);
+}
+
+fn render_forward_graph(frame: &mut Frame, app: &App) {
+    let area = frame.area();
+    let inner = render_popup_block(frame, area, " Forwarding Graph ".to_string(), Color::Cyan);
+
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2),
+            Constraint::Min(1),
+            Constraint::Length(1),
+        ])
+        .margin(1)
+        .split(inner);
+
+    let cycles = app.forward_graph.iter().any(|l| l.in_cycle);
+    let mut summary =
+        String::from("Auto-forwarding among loaded entities; [DLQ] = dead-letters only");
+    if cycles {
+        summary.push_str(" — cycles in red");
+    }
+    frame.render_widget(
+        Paragraph::new(summary).style(Style::default().fg(Color::DarkGray)),
+        layout[0],
+    );
+
+    let items: Vec<ListItem> = app
+        .forward_graph
+        .iter()
+        .map(|line| {
+            let style = if line.in_cycle {
+                Style::default().fg(Color::Red)
+            } else {
+                Style::default().fg(Color::White)
+            };
+            let mut spans = vec![
+                Span::styled(line.prefix.clone(), Style::default().fg(Color::DarkGray)),
+                Span::styled(sanitize_for_terminal(&line.path, false), style),
+            ];
+            if let Some(note) = line.note {
+                let note_style = if note == "cycle" {
+                    Style::default().fg(Color::Red).bold()
+                } else {
+                    Style::default().fg(Color::DarkGray)
+                };
+                spans.push(Span::styled(format!("  ({})", note), note_style));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+    let list = List::new(items).highlight_style(Style::default().bg(Color::DarkGray));
+    let mut state = ListState::default().with_selected(Some(app.forward_graph_selected));
+    frame.render_stateful_widget(list, layout[1], &mut state);
+
+    render_shortcut_hints(
+        frame,
+        layout[2],
+        &[
+            ("↑↓/j/k", " navigate  "),
+            ("Enter", " go to entity  "),
+            ("Esc", " close"),
+        ],
+    );
+}
+
+fn render_purge_preflight(frame: &mut Frame, app: &App, entity_path: &str, is_dlq: bool) {
+    let area = centered_rect(70, 60, frame.area());
+    let title = format!(" Purge — {} ", entity_path);
+    let inner = render_popup_block(frame, area, title, Color::Red);
+
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2),
+            Constraint::Min(1),
+            Constraint::Length(1),
+        ])
+        .margin(1)
+        .split(inner);
+
+    let selected = app.purge_candidates.iter().filter(|c| c.selected).count();
+    let what = if is_dlq {
+        "dead-letter messages"
+    } else {
+        "active messages"
+    };
+    frame.render_widget(
+        Paragraph::new(format!(
+            "Delete ALL {} from {} of {} subscription(s)?",
+            what,
+            selected,
+            app.purge_candidates.len()
+        ))
+        .style(Style::default().fg(Color::Red).bold()),
+        layout[0],
+    );
+
+    let rows: Vec<Row> = app
+        .purge_candidates
+        .iter()
+        .enumerate()
+        .map(|(i, c)| {
+            let style = if i == app.purge_candidate_selected {
+                Style::default().bg(Color::DarkGray).fg(Color::White)
+            } else {
+                Style::default()
+            };
+            let mark = if c.selected { "[x]" } else { "[ ]" };
+            let problem = Span::styled(
+                c.problem.clone().unwrap_or_default(),
+                Style::default().fg(Color::Yellow),
+            );
+            Row::new(vec![
+                Cell::from(mark),
+                Cell::from(sanitize_for_terminal(&c.path, false)),
+                Cell::from(problem),
+            ])
+            .style(style)
+        })
+        .collect();
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(4),
+            Constraint::Percentage(60),
+            Constraint::Min(10),
+        ],
+    )
+    .header(Row::new(vec!["", "Path", "Problem"]).style(Style::default().fg(Color::Yellow).bold()));
+    let mut state = TableState::default().with_selected(Some(app.purge_candidate_selected));
+    frame.render_stateful_widget(table, layout[1], &mut state);
+
+    render_shortcut_hints(
+        frame,
+        layout[2],
+        &[
+            ("Space", " toggle  "),
+            ("a", " all  "),
+            ("Enter", " purge  "),
+            ("Esc", " cancel"),
+        ],
+    );
+}
+
+fn render_create_on_topic(frame: &mut Frame, topic: &str) {
+    let area = centered_rect(50, 30, frame.area());
+    let inner = render_popup_block(frame, area, " Create ".to_string(), Color::Cyan);
+
+    let lines = vec![
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("  [S] ", Style::default().fg(Color::Cyan).bold()),
+            Span::styled(
+                format!("Subscription under '{}'", topic),
+                Style::default().fg(Color::White),
+            ),
+        ]),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("  [T] ", Style::default().fg(Color::Cyan).bold()),
+            Span::styled("New topic", Style::default().fg(Color::White)),
+        ]),
+        Line::from(""),
+        Line::from(Span::styled(
+            "Enter = subscription · Esc to cancel",
+            Style::default().fg(Color::DarkGray),
+        )),
+    ];
+
+    render_centered_lines(frame, inner, lines);
+}
+
+fn render_resume_draft(frame: &mut Frame, app: &App, kind: crate::app::DraftKind) {
+    let area = centered_rect(50, 30, frame.area());
+    let inner = render_popup_block(frame, area, " Unsent Draft ".to_string(), Color::Yellow);
+
+    let (entity, body_lines) = app
+        .form_drafts
+        .get(&kind)
+        .map(|d| {
+            let body = d.fields.first().map_or("", |(_, v)| v.as_str());
+            (d.entity.as_str(), body.lines().count())
+        })
+        .unwrap_or(("", 0));
+    let form = match kind {
+        crate::app::DraftKind::Send => "message",
+        crate::app::DraftKind::Edit => "edit",
+    };
+
+    let lines = vec![
+        Line::from(Span::styled(
+            format!(
+                "A {} for '{}' was closed unsent ({} body lines)",
+                form,
+                sanitize_for_terminal(entity, false),
+                body_lines
+            ),
+            Style::default().fg(Color::White),
+        )),
+        Line::from(""),
         Line::from(vec![
-            Span::styled("  [D] ", Style::default().fg(Color::Red).bold()),
+            Span::styled("  [R] ", Style::default().fg(Color::Yellow).bold()),
+            Span::styled("Resume draft", Style::default().fg(Color::White)),
+        ]),
+        Line::from(vec![
+            Span::styled("  [D] ", Style::default().fg(Color::Yellow).bold()),
             Span::styled(
-                "Delete ALL active messages",
+                "Discard it and start fresh",
                 Style::default().fg(Color::White),
             ),
         ]),
+        Line::from(""),
+        Line::from(Span::styled(
+            "Enter = resume · Esc to close (draft kept)",
+            Style::default().fg(Color::DarkGray),
+        )),
+    ];
+
+    render_centered_lines(frame, inner, lines);
+}
+
+fn render_copy_format(frame: &mut Frame, path: &str) {
+    let area = centered_rect(50, 30, frame.area());
+    let inner = render_popup_block(frame, area, " Copy ".to_string(), Color::Cyan);
+
+    let lines = vec![
         Line::from(""),
         Line::from(vec![
-            Span::styled("  [L] ", Style::default().fg(Color::Red).bold()),
+            Span::styled("  [P] ", Style::default().fg(Color::Cyan).bold()),
             Span::styled(
-                "Delete ALL dead-letter messages",
+                format!("Path '{}'", sanitize_for_terminal(path, false)),
                 Style::default().fg(Color::White),
             ),
         ]),
         Line::from(""),
         Line::from(vec![
-            Span::styled("  [R] ", Style::default().fg(Color::Yellow).bold()),
-            Span::styled(
-                "Resend ALL DLQ → main entity",
-                Style::default().fg(Color::White),
-            ),
+            Span::styled("  [U] ", Style::default().fg(Color::Cyan).bold()),
+            Span::styled("Azure Portal URL", Style::default().fg(Color::White)),
         ]),
         Line::from(""),
         Line::from(Span::styled(
-            "Esc to cancel",
+            "Enter = path · Esc to cancel",
             Style::default().fg(Color::DarkGray),
         )),
     ];
@@ -898,6 +2420,7 @@ fn render_namespace_list(frame: &mut Frame, app: &App) {
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Length(2), // header with count + warnings
+            Constraint::Length(2), // search
             Constraint::Min(3),    // namespace list
             Constraint::Length(2), // hints
         ])
@@ -905,10 +2428,24 @@ fn render_namespace_list(frame: &mut Frame, app: &App) {
         .split(inner);
 
     // Header
-    let mut header_lines = vec![Line::from(Span::styled(
-        format!("Found {} namespace(s)", app.discovered_namespaces.len()),
-        Style::default().fg(Color::Cyan),
-    ))];
+    let namespaces = app.filtered_namespaces();
+    let found = if app.namespace_search.is_empty() {
+        format!("Found {} namespace(s)", app.discovered_namespaces.len())
+    } else {
+        format!(
+            "Found {} namespace(s), {} matching",
+            app.discovered_namespaces.len(),
+            namespaces.len()
+        )
+    };
+    let mut header_spans = vec![Span::styled(found, Style::default().fg(Color::Cyan))];
+    if let Some(age) = app.discovery_cached_minutes {
+        header_spans.push(Span::styled(
+            format!("  · cached {} min ago", age),
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+    let mut header_lines = vec![Line::from(header_spans)];
 
     if !app.discovery_warnings.is_empty() {
         header_lines.push(Line::from(Span::styled(
@@ -923,88 +2460,91 @@ fn render_namespace_list(frame: &mut Frame, app: &App) {
     let header = Paragraph::new(header_lines);
     frame.render_widget(header, layout[0]);
 
-    // Namespace list
-    let mut items: Vec<ListItem> = Vec::new();
+    let search = Paragraph::new(Line::from(vec![
+        Span::styled("Search: ", Style::default().fg(Color::DarkGray)),
+        Span::styled(
+            format!("{}▏", sanitize_for_terminal(&app.namespace_search, false)),
+            Style::default().fg(Color::White),
+        ),
+    ]));
+    frame.render_widget(search, layout[1]);
 
-    // Group by subscription
-    let mut by_subscription: std::collections::HashMap<
-        String,
-        Vec<&crate::client::resource_manager::DiscoveredNamespace>,
-    > = std::collections::HashMap::new();
-
-    for ns in &app.discovered_namespaces {
-        by_subscription
-            .entry(ns.subscription_name.clone())
-            .or_default()
-            .push(ns);
+    // Namespace list, grouped by subscription (`filtered_namespaces` order)
+    if namespaces.is_empty() {
+        let empty = Paragraph::new("No namespaces match the search. Esc clears it.")
+            .style(Style::default().fg(Color::DarkGray));
+        frame.render_widget(empty, layout[2]);
     }
+    let mut items: Vec<ListItem> = Vec::new();
+    let mut current_sub: Option<&str> = None;
+    for (idx, ns) in namespaces.iter().enumerate() {
+        if current_sub != Some(ns.subscription_name.as_str()) {
+            current_sub = Some(ns.subscription_name.as_str());
+            items.push(ListItem::new(Line::from(Span::styled(
+                format!("  {}", ns.subscription_name),
+                Style::default().fg(Color::Blue).bold(),
+            ))));
+        }
+        let is_selected = idx == app.namespace_list_state;
 
-    let mut sorted_subs: Vec<_> = by_subscription.keys().collect();
-    sorted_subs.sort();
-
-    let mut idx = 0;
-    for sub_name in sorted_subs {
-        let namespaces = &by_subscription[sub_name];
-
-        // Subscription header
-        items.push(ListItem::new(Line::from(Span::styled(
-            format!("  {}", sub_name),
-            Style::default().fg(Color::Blue).bold(),
-        ))));
-
-        for ns in namespaces {
-            let is_selected = idx == app.namespace_list_state;
+        let status_icon = match ns.status.as_str() {
+            "Active" => "✓",
+            "Disabled" | "Disabling" => "✗",
+            _ => "?",
+        };
 
-            let status_icon = match ns.status.as_str() {
-                "Active" => "✓",
-                "Disabled" | "Disabling" => "✗",
-                _ => "?",
-            };
+        let status_color = match ns.status.as_str() {
+            "Active" => Color::Green,
+            "Disabled" | "Disabling" => Color::Red,
+            _ => Color::Yellow,
+        };
 
-            let status_color = match ns.status.as_str() {
-                "Active" => Color::Green,
-                "Disabled" | "Disabling" => Color::Red,
-                _ => Color::Yellow,
-            };
+        let line_style = if is_selected {
+            Style::default().bg(Color::DarkGray).fg(Color::White)
+        } else {
+            Style::default()
+        };
 
-            let line_style = if is_selected {
-                Style::default().bg(Color::DarkGray).fg(Color::White)
-            } else {
+        let line = Line::from(vec![
+            Span::styled("    ", line_style),
+            Span::styled(
+                status_icon,
                 Style::default()
-            };
+                    .fg(status_color)
+                    .add_modifier(line_style.add_modifier),
+            ),
+            Span::styled(" ", line_style),
+            Span::styled(&ns.name, line_style.fg(Color::White).bold()),
+            Span::styled("  ", line_style),
+            Span::styled(format!("[{}]", ns.location), line_style.fg(Color::DarkGray)),
+            Span::styled("  ", line_style),
+            Span::styled(
+                ns.detail
+                    .as_ref()
+                    .and_then(|d| d.sku_name.as_deref())
+                    .map(|sku| format!("{}  ", sku))
+                    .unwrap_or_default(),
+                line_style.fg(Color::DarkGray),
+            ),
+            Span::styled(&ns.status, line_style.fg(status_color)),
+        ]);
 
-            let line = Line::from(vec![
-                Span::styled("    ", line_style),
-                Span::styled(
-                    status_icon,
-                    Style::default()
-                        .fg(status_color)
-                        .add_modifier(line_style.add_modifier),
-                ),
-                Span::styled(" ", line_style),
-                Span::styled(&ns.name, line_style.fg(Color::White).bold()),
-                Span::styled("  ", line_style),
-                Span::styled(format!("[{}]", ns.location), line_style.fg(Color::DarkGray)),
-                Span::styled("  ", line_style),
-                Span::styled(&ns.status, line_style.fg(status_color)),
-            ]);
-
-            items.push(ListItem::new(line));
-            idx += 1;
-        }
+        items.push(ListItem::new(line));
     }
 
     let list = List::new(items);
-    frame.render_widget(list, layout[1]);
+    frame.render_widget(list, layout[2]);
 
     render_shortcut_hints(
         frame,
-        layout[2],
+        layout[3],
         &[
-            ("↑↓/j/k", " navigate  "),
+            ("↑↓", " navigate  "),
+            ("type", " search  "),
             ("Enter", " connect  "),
-            ("m", " manual  "),
-            ("Esc", " cancel"),
+            ("Tab", " manual  "),
+            ("^R", " refresh  "),
+            ("Esc", " clear/cancel"),
         ],
     );
 }
@@ -1093,7 +2633,7 @@ fn render_copy_select_entity(frame: &mut Frame, app: &mut App) {
     let layout = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Length(2), // header (2 lines)
+            Constraint::Length(3), // header (2 lines + filter)
             Constraint::Min(3),    // entity list
             Constraint::Length(1), // footer hints
         ])
@@ -1111,14 +2651,20 @@ fn render_copy_select_entity(frame: &mut Frame, app: &mut App) {
             Span::styled(source_entity, Style::default().fg(Color::Yellow)),
         ]),
         Line::from(Span::styled(
-            "Select destination queue or topic, or press 's' to use same entity name.",
+            "Type to filter, pick a queue or topic, or Ctrl+S to use the same entity name.",
             Style::default().fg(Color::DarkGray),
         )),
+        Line::from(vec![
+            Span::styled("Filter: ", Style::default().fg(Color::DarkGray)),
+            Span::styled(
+                format!("{}▏", sanitize_for_terminal(&app.copy_entity_filter, false)),
+                Style::default().fg(Color::White),
+            ),
+        ]),
     ]);
     frame.render_widget(header, layout[0]);
 
     // Entity list
-    // Use copy_dest_entities from app state
     let has_entities = !app.copy_dest_entities.is_empty();
 
     if !has_entities {
@@ -1128,12 +2674,11 @@ fn render_copy_select_entity(frame: &mut Frame, app: &mut App) {
         frame.render_widget(loading, layout[1]);
     } else {
         // Render entity list with type icons
-        // Use copy_dest_entities from app state
         use crate::client::models::EntityType;
 
         let items: Vec<ListItem> = app
-            .copy_dest_entities
-            .iter()
+            .copy_filtered_entities()
+            .into_iter()
             .map(|(path, entity_type)| {
                 let icon = match entity_type {
                     EntityType::Queue => "📬",
@@ -1145,7 +2690,7 @@ fn render_copy_select_entity(frame: &mut Frame, app: &mut App) {
             .collect();
 
         if items.is_empty() {
-            let empty_msg = Paragraph::new("No queues or topics found")
+            let empty_msg = Paragraph::new("No queues or topics match the filter")
                 .style(Style::default().fg(Color::DarkGray))
                 .alignment(Alignment::Center);
             frame.render_widget(empty_msg, layout[1]);
@@ -1163,10 +2708,397 @@ fn render_copy_select_entity(frame: &mut Frame, app: &mut App) {
         frame,
         layout[2],
         &[
-            ("↑↓/j/k", " navigate | "),
+            ("↑↓", " navigate | "),
             ("Enter", " select | "),
-            ("s", " use source name | "),
+            ("Ctrl+S", " use source name | "),
+            ("Ctrl+R", " refresh | "),
+            ("Esc", " clear/cancel"),
+        ],
+    );
+}
+
+fn render_authorization_rules(frame: &mut Frame, app: &App, entity_path: &str) {
+    let area = centered_rect(70, 50, frame.area());
+    let inner = render_popup_block(
+        frame,
+        area,
+        format!(" SAS Policies — {} ", entity_path),
+        Color::Cyan,
+    );
+
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(3), Constraint::Length(1)])
+        .margin(1)
+        .split(inner);
+
+    if app.authorization_rules.is_empty() {
+        let empty = Paragraph::new("No entity-level authorization rules")
+            .style(Style::default().fg(Color::DarkGray))
+            .alignment(Alignment::Center);
+        frame.render_widget(empty, layout[0]);
+    } else {
+        let header = Row::new(vec!["Name", "Rights", "Modified"])
+            .style(Style::default().fg(Color::Yellow).bold())
+            .bottom_margin(1);
+        let rows: Vec<Row> = app
+            .authorization_rules
+            .iter()
+            .map(|rule| {
+                Row::new(vec![
+                    sanitize_for_terminal(&rule.key_name, false),
+                    rule.rights.join(", "),
+                    rule.modified_time
+                        .clone()
+                        .unwrap_or_else(|| "-".to_string()),
+                ])
+            })
+            .collect();
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Percentage(35),
+                Constraint::Percentage(35),
+                Constraint::Percentage(30),
+            ],
+        )
+        .header(header)
+        .column_spacing(1);
+        frame.render_widget(table, layout[0]);
+    }
+
+    render_shortcut_hints(
+        frame,
+        layout[1],
+        &[("Esc", " close  "), ("", "Keys are never displayed")],
+    );
+}
+
+fn render_error_detail(frame: &mut Frame, app: &App) {
+    let Some(ref report) = app.error_report else {
+        return;
+    };
+
+    let area = centered_rect(80, 70, frame.area());
+    let title = if report.operation.is_empty() {
+        " Error ".to_string()
+    } else {
+        format!(" Error — {} ", report.operation)
+    };
+    let inner = render_popup_block(frame, area, title, Color::Red);
+
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(3), Constraint::Length(1)])
+        .margin(1)
+        .split(inner);
+
+    let text = sanitize_for_terminal(&report.full_text(), true);
+    let body = Paragraph::new(text)
+        .style(Style::default().fg(Color::White))
+        .wrap(Wrap { trim: false })
+        .scroll((app.error_scroll, 0));
+    frame.render_widget(body, layout[0]);
+
+    render_shortcut_hints(
+        frame,
+        layout[1],
+        &[
+            ("y", " copy  "),
+            ("↑↓/PgUp/PgDn", " scroll  "),
+            ("Esc", " close"),
+        ],
+    );
+}
+
+fn render_column_selector(frame: &mut Frame, app: &App) {
+    let height = app.column_editor.len() as u16 + 6;
+    let area = centered_rect_abs_height(45, height, frame.area());
+    let inner = render_popup_block(frame, area, " Message Columns ".to_string(), Color::Cyan);
+
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(1)])
+        .margin(1)
+        .split(inner);
+
+    let items: Vec<ListItem> = app
+        .column_editor
+        .iter()
+        .enumerate()
+        .map(|(i, (column, enabled))| {
+            let marker = if *enabled { "[x] " } else { "[ ] " };
+            let style = if i == app.column_editor_selected {
+                Style::default().bg(Color::DarkGray).fg(Color::White)
+            } else if *enabled {
+                Style::default().fg(Color::White)
+            } else {
+                Style::default().fg(Color::DarkGray)
+            };
+            ListItem::new(format!("{}{}", marker, column.title())).style(style)
+        })
+        .collect();
+    frame.render_widget(List::new(items), layout[0]);
+
+    render_shortcut_hints(
+        frame,
+        layout[1],
+        &[
+            ("Space", " toggle  "),
+            ("Shift+↑↓", " move  "),
+            ("Enter", " save  "),
+            ("Esc", " cancel"),
+        ],
+    );
+}
+
+fn render_status_select(frame: &mut Frame, app: &App, options: &[&str], path: &str) {
+    let current = match &app.detail_view {
+        DetailView::Queue(q, _) => q.status.as_deref(),
+        DetailView::Topic(t, ..) => t.status.as_deref(),
+        DetailView::Subscription(s, _) => s.status.as_deref(),
+        DetailView::Namespace(..) | DetailView::None => None,
+    }
+    .unwrap_or("Active");
+
+    let height = options.len() as u16 + 6;
+    let area = centered_rect_abs_height(50, height, frame.area());
+    let inner = render_popup_block(frame, area, format!(" Status — {} ", path), Color::Cyan);
+
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(1)])
+        .margin(1)
+        .split(inner);
+
+    let items: Vec<ListItem> = options
+        .iter()
+        .enumerate()
+        .map(|(i, status)| {
+            let marker = if *status == current { "● " } else { "  " };
+            let style = if i == app.status_selected {
+                Style::default().bg(Color::DarkGray).fg(Color::White)
+            } else {
+                Style::default().fg(Color::White)
+            };
+            ListItem::new(format!("{}{}", marker, status)).style(style)
+        })
+        .collect();
+    frame.render_widget(List::new(items), layout[0]);
+
+    render_shortcut_hints(
+        frame,
+        layout[1],
+        &[
+            ("↑↓/j/k", " navigate  "),
+            ("Enter", " apply  "),
             ("Esc", " cancel"),
         ],
     );
 }
+
+fn render_settings(frame: &mut Frame, app: &App, editing: bool) {
+    let height = SettingField::ALL.len() as u16 + 7;
+    let area = centered_rect_abs_height(60, height, frame.area());
+    let inner = render_popup_block(frame, area, " Settings ".to_string(), Color::Cyan);
+
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
+        .margin(1)
+        .split(inner);
+
+    let rows: Vec<Row> = SettingField::ALL
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            let selected = i == app.settings_selected;
+            let value = if selected && editing {
+                let mut buffer = app.input_buffer.clone();
+                buffer.insert(text_width::byte_offset(&buffer, app.input_cursor), '▏');
+                buffer
+            } else {
+                match app.config.settings.value_of(*field) {
+                    v if v.is_empty() => "off".to_string(),
+                    v => v,
+                }
+            };
+            let style = if selected && editing {
+                Style::default().fg(Color::Yellow).bold()
+            } else if selected {
+                Style::default().bg(Color::DarkGray).fg(Color::White)
+            } else {
+                Style::default()
+            };
+            Row::new(vec![field.label().to_string(), value]).style(style)
+        })
+        .collect();
+    let table = Table::new(
+        rows,
+        [Constraint::Percentage(60), Constraint::Percentage(40)],
+    );
+    frame.render_widget(table, layout[0]);
+
+    if let Some(ref err) = app.settings_error {
+        frame.render_widget(
+            Paragraph::new(err.as_str()).style(Style::default().fg(Color::Red)),
+            layout[1],
+        );
+    }
+
+    let hints: &[(&str, &str)] = if editing {
+        &[("Enter", " save  "), ("Esc", " cancel")]
+    } else {
+        &[
+            ("↑↓/j/k", " navigate  "),
+            ("Enter", " edit/toggle  "),
+            ("Esc", " close"),
+        ]
+    };
+    render_shortcut_hints(frame, layout[2], hints);
+}
+
+fn render_dlq_stats(frame: &mut Frame, app: &App) {
+    let stats = DlqStats::from_messages(&app.dlq_messages);
+
+    let area = centered_rect(70, 60, frame.area());
+    let inner = render_popup_block(
+        frame,
+        area,
+        format!(" Dead-letter Reasons — {} message(s) ", stats.total),
+        Color::Magenta,
+    );
+
+    let source_rows = stats.sources.len().clamp(1, 5) as u16;
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(3),
+            Constraint::Length(source_rows + 2),
+            Constraint::Length(1),
+        ])
+        .margin(1)
+        .split(inner);
+
+    let header = Row::new(vec!["Reason", "Count", "%"])
+        .style(Style::default().fg(Color::Yellow).bold())
+        .bottom_margin(1);
+    let rows: Vec<Row> = stats
+        .reasons
+        .iter()
+        .enumerate()
+        .map(|(i, (reason, count))| {
+            let active = app.dlq_reason_filter.as_deref() == Some(reason.as_str());
+            let style = if i == app.dlq_stats_selected {
+                Style::default().bg(Color::DarkGray).fg(Color::White)
+            } else if active {
+                Style::default().fg(Color::Yellow)
+            } else {
+                Style::default()
+            };
+            let marker = if active { "* " } else { "  " };
+            Row::new(vec![
+                format!("{}{}", marker, sanitize_for_terminal(reason, false)),
+                count.to_string(),
+                format!("{:.1}", stats.percent(*count)),
+            ])
+            .style(style)
+        })
+        .collect();
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Min(20),
+            Constraint::Length(8),
+            Constraint::Length(7),
+        ],
+    )
+    .header(header)
+    .column_spacing(1);
+    frame.render_widget(table, layout[0]);
+
+    let mut source_lines = vec![Line::from(Span::styled(
+        "Top sources (DeadLetterSource)",
+        Style::default().fg(Color::Yellow).bold(),
+    ))];
+    if stats.sources.is_empty() {
+        source_lines.push(Line::from(Span::styled(
+            "  (none recorded)",
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+    for (source, count) in stats.sources.iter().take(5) {
+        source_lines.push(Line::from(format!(
+            "  {} — {} ({:.1}%)",
+            sanitize_for_terminal(source, false),
+            count,
+            stats.percent(*count)
+        )));
+    }
+    frame.render_widget(Paragraph::new(source_lines), layout[1]);
+
+    render_shortcut_hints(
+        frame,
+        layout[2],
+        &[
+            ("↑↓", " select  "),
+            ("Enter", " filter  "),
+            ("c", " clear filter  "),
+            ("Esc", " close"),
+        ],
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn connection_preview_never_shows_secrets() {
+        let key = "abc+def/ghi==";
+        let preview = redact_connection_string_for_preview(&format!(
+            "Endpoint=sb://ns.servicebus.windows.net/;SharedAccessKeyName=Root;SharedAccessKey={}",
+            key
+        ));
+        assert_eq!(
+            preview,
+            "Endpoint=sb://ns.servicebus.windows.net/; SharedAccessKeyName=Root; SharedAccessKey=***"
+        );
+
+        // Missing separators leave the key inside another value
+        for conn in [
+            format!("Endpoint=sb://ns/SharedAccessKey={}", key),
+            format!(
+                "Endpoint=sb://ns/;SharedAccessKeyName=Root SharedAccessKey={}",
+                key
+            ),
+            format!(
+                "endpoint=sb://ns/;sharedaccesskeyname=Root;sharedaccesskey={};",
+                key
+            ),
+        ] {
+            let preview = redact_connection_string_for_preview(&conn);
+            assert!(!preview.contains("abc"), "{} -> {}", conn, preview);
+            assert!(preview.starts_with("Endpoint=sb://ns/"), "{}", preview);
+        }
+
+        let preview = redact_connection_string_for_preview(
+            "Endpoint=sb://ns/;SharedAccessSignature=SharedAccessSignature sr=x&sig=abc%3D&se=1",
+        );
+        assert_eq!(preview, "Endpoint=sb://ns/; SharedAccessSignature=***");
+
+        // A bare secret (e.g. a client secret) pasted on its own
+        assert_eq!(
+            redact_connection_string_for_preview("Q~abc=def=="),
+            "(redacted SAS connection)"
+        );
+        assert_eq!(
+            redact_connection_string_for_preview("Endpoint=abc=="),
+            "(redacted SAS connection)"
+        );
+    }
+}
@@ -4,6 +4,9 @@
 /// - prevent terminal escape injection (CSI/OSC/etc)
 /// - remove other control characters (except optional newlines)
 /// - keep output reasonably readable/debuggable via placeholders
+///
+/// CSI sequences (colors, cursor movement, erase — typical of captured log
+/// output) are dropped without a placeholder so colored logs stay readable.
 pub fn sanitize_for_terminal(input: &str, allow_newlines: bool) -> String {
     let mut out = String::with_capacity(input.len());
     let mut chars = input.chars().peekable();
@@ -16,27 +19,20 @@ pub fn sanitize_for_terminal(input: &str, allow_newlines: bool) -> String {
                     // CSI: ESC [ ... <final>
                     Some('[') => {
                         let _ = chars.next();
-                        for c in chars.by_ref() {
-                            if ('@'..='~').contains(&c) {
-                                break;
-                            }
-                        }
-                        out.push_str("[CSI]");
+                        skip_csi(&mut chars);
                     }
                     // OSC: ESC ] ... BEL | ESC \
                     Some(']') => {
                         let _ = chars.next();
-                        while let Some(c) = chars.next() {
-                            if c == '\x07' {
-                                break;
-                            }
-                            if c == '\x1b' && matches!(chars.peek().copied(), Some('\\')) {
-                                let _ = chars.next();
-                                break;
-                            }
-                        }
+                        skip_string(&mut chars, true);
                         out.push_str("[OSC]");
                     }
+                    // DCS / SOS / PM / APC: ESC P|X|^|_ ... ESC \
+                    Some('P' | 'X' | '^' | '_') => {
+                        let _ = chars.next();
+                        skip_string(&mut chars, false);
+                        out.push_str("[ESC]");
+                    }
                     // Other escape: consume a single following char if present
                     Some(_) => {
                         let _ = chars.next();
@@ -46,6 +42,9 @@ pub fn sanitize_for_terminal(input: &str, allow_newlines: bool) -> String {
                 }
             }
 
+            // 8-bit CSI
+            '\u{9b}' => skip_csi(&mut chars),
+
             // Other control characters
             c if c.is_control() => {
                 if (allow_newlines && c == '\n') || c == '\t' || c == '\r' {
@@ -62,6 +61,39 @@ pub fn sanitize_for_terminal(input: &str, allow_newlines: bool) -> String {
     out
 }
 
+/// Skip the rest of a CSI sequence: parameter bytes (`0-9:;<=>?`), then
+/// intermediate bytes (space to `/`), then one final byte (`@` to `~`).
+/// Stops early at anything else so a truncated sequence can't swallow the
+/// text after it.
+fn skip_csi(chars: &mut std::iter::Peekable<std::str::Chars<'_>>) {
+    while let Some(&c) = chars.peek() {
+        match c {
+            '0'..='?' | ' '..='/' => {
+                let _ = chars.next();
+            }
+            '@'..='~' => {
+                let _ = chars.next();
+                return;
+            }
+            _ => return,
+        }
+    }
+}
+
+/// Skip a control string up to its terminator: ESC \ (ST), or BEL when
+/// `bel_terminates` (OSC).
+fn skip_string(chars: &mut std::iter::Peekable<std::str::Chars<'_>>, bel_terminates: bool) {
+    while let Some(c) = chars.next() {
+        if bel_terminates && c == '\x07' {
+            break;
+        }
+        if c == '\x1b' && matches!(chars.peek().copied(), Some('\\')) {
+            let _ = chars.next();
+            break;
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::sanitize_for_terminal;
@@ -70,7 +102,11 @@ mod tests {
     fn strips_csi_and_osc_escape_sequences() {
         assert_eq!(
             sanitize_for_terminal("hello\x1b[31mred\x1b[0m world", false),
-            "hello[CSI]red[CSI] world"
+            "hellored world"
+        );
+        assert_eq!(
+            sanitize_for_terminal("\x1b[2J\x1b[1;1H\x1b[Kdone\x1b[38;5;196mX", false),
+            "doneX"
         );
         assert_eq!(
             sanitize_for_terminal("title\x1b]0;tab title\x07done", false),
@@ -89,4 +125,19 @@ mod tests {
         assert_eq!(sanitize_for_terminal("x\x1bz", false), "x[ESC]");
         assert_eq!(sanitize_for_terminal("x\x1b", false), "x[ESC]");
     }
+
+    #[test]
+    fn truncated_csi_keeps_following_text() {
+        assert_eq!(sanitize_for_terminal("a\x1b[12\nb", true), "a\nb");
+        assert_eq!(sanitize_for_terminal("a\x1b[", false), "a");
+        assert_eq!(sanitize_for_terminal("a\u{9b}31mb", false), "ab");
+    }
+
+    #[test]
+    fn drops_device_control_strings() {
+        assert_eq!(
+            sanitize_for_terminal("a\x1bPq#0;2;0;0;0\x1b\\b", false),
+            "a[ESC]b"
+        );
+    }
 }
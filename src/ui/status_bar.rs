@@ -2,7 +2,9 @@ use ratatui::prelude::*;
 use ratatui::widgets::*;
 use ratatui::Frame;
 
-use crate::app::App;
+use crate::app::{App, BgJob};
+
+use super::sanitize::sanitize_for_terminal;
 
 pub fn render_status_bar(frame: &mut Frame, app: &App, area: Rect) {
     let style = if app.status_is_error {
@@ -11,26 +13,38 @@ pub fn render_status_bar(frame: &mut Frame, app: &App, area: Rect) {
         Style::default().bg(Color::DarkGray).fg(Color::White)
     };
 
-    let left = Span::styled(format!(" {} ", app.status_message), style);
+    let left_text = match app.bg_job.as_ref().filter(|_| app.bg_running) {
+        Some(job) => format!(" {} | {} ", job_summary(job), app.status_message),
+        None => format!(" {} ", app.status_message),
+    };
+    let left_width = left_text.chars().count() as u16;
+    let left = Span::styled(left_text, style);
 
     let right_text = match app.focus {
         crate::app::FocusPanel::Tree => "Tree",
         crate::app::FocusPanel::Detail => "Detail",
         crate::app::FocusPanel::Messages => "Messages",
     };
+    let health = match app.health_check {
+        Some(ref check) if check.is_ok() => format!(" {}ms |", check.rtt_ms),
+        Some(_) => " unreachable |".to_string(),
+        None => String::new(),
+    };
+    let count = app
+        .pending_count
+        .map(|count| format!(" {} |", count))
+        .unwrap_or_default();
+    let right_text = format!("{}{} {} | ? Help ", count, health, right_text);
+    let right_width = right_text.chars().count() as u16;
     let right = Span::styled(
-        format!(" {} | ? Help ", right_text),
+        right_text,
         Style::default().bg(Color::DarkGray).fg(Color::Gray),
     );
 
     let bar = Line::from(vec![
         left,
         Span::styled(
-            " ".repeat(
-                area.width
-                    .saturating_sub(app.status_message.len() as u16 + right_text.len() as u16 + 12)
-                    as usize,
-            ),
+            " ".repeat(area.width.saturating_sub(left_width + right_width) as usize),
             Style::default().bg(Color::DarkGray),
         ),
         right,
@@ -38,3 +52,22 @@ pub fn render_status_bar(frame: &mut Frame, app: &App, area: Rect) {
 
     frame.render_widget(Paragraph::new(bar), area);
 }
+
+const SPINNER: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
+/// "⠹ Purge orders 01:23 · 412 msg/s" — the spinner advances with the 100ms redraw tick.
+fn job_summary(job: &BgJob) -> String {
+    let elapsed = job.elapsed();
+    let frame = SPINNER[(elapsed.as_millis() / 100) as usize % SPINNER.len()];
+    let secs = elapsed.as_secs();
+    let mut text = format!("{} {}", frame, job.operation);
+    if let Some(ref path) = job.entity_path {
+        text.push(' ');
+        text.push_str(&sanitize_for_terminal(path, false));
+    }
+    text.push_str(&format!(" {:02}:{:02}", secs / 60, secs % 60));
+    if let Some(rate) = job.rate() {
+        text.push_str(&format!(" · {:.0} msg/s", rate));
+    }
+    text
+}
@@ -0,0 +1,124 @@
+use std::io::IsTerminal;
+
+use ratatui::buffer::Buffer;
+use ratatui::style::{Color, Modifier, Style};
+
+/// How the finished frame reaches the terminal.
+///
+/// Widgets always draw with the normal colored styles; `Monochrome` then
+/// rewrites every cell so that meaning carried by color survives as
+/// bold/underline/reverse. Text content is never touched, so sanitizing is
+/// unaffected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Theme {
+    Color,
+    Monochrome,
+}
+
+impl Theme {
+    /// Monochrome when asked for (`--no-color`, the `no_color` setting) or when
+    /// the environment can't show color: `NO_COLOR` set, `TERM=dumb`, or
+    /// stdout not being a terminal.
+    pub fn detect(no_color_flag: bool, no_color_setting: bool) -> Self {
+        let no_color_env = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+        let dumb_term = std::env::var("TERM").is_ok_and(|t| t == "dumb");
+        if no_color_flag
+            || no_color_setting
+            || no_color_env
+            || dumb_term
+            || !std::io::stdout().is_terminal()
+        {
+            Theme::Monochrome
+        } else {
+            Theme::Color
+        }
+    }
+
+    pub fn apply(self, buf: &mut Buffer) {
+        if self == Theme::Color {
+            return;
+        }
+        for cell in buf.content.iter_mut() {
+            let mut modifier = cell.modifier | monochrome_modifier(cell.fg);
+            // Backgrounds mark selections and gauges
+            if cell.bg != Color::Reset {
+                modifier |= Modifier::REVERSED;
+            }
+            cell.fg = Color::Reset;
+            cell.bg = Color::Reset;
+            cell.modifier = modifier;
+        }
+    }
+}
+
+impl Theme {
+    /// Style of progress gauges. A colored gauge draws its filled part as
+    /// green blocks on black, which `apply` would reverse into an inverted
+    /// bar, so the monochrome gauge has no colors: filled cells are plain
+    /// blocks, the rest stays blank.
+    pub fn gauge_style(self) -> Style {
+        match self {
+            Theme::Color => Style::default().fg(Color::Green).bg(Color::Black),
+            Theme::Monochrome => Style::default(),
+        }
+    }
+}
+
+/// Emphasis standing in for a foreground color: red (errors, alerts) is bold
+/// and underlined, yellow/magenta (highlights, key hints) bold.
+fn monochrome_modifier(fg: Color) -> Modifier {
+    match fg {
+        Color::Red | Color::LightRed => Modifier::BOLD | Modifier::UNDERLINED,
+        Color::Yellow | Color::LightYellow | Color::Magenta | Color::LightMagenta => Modifier::BOLD,
+        _ => Modifier::empty(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::layout::Rect;
+    use ratatui::style::Style;
+
+    #[test]
+    fn monochrome_replaces_colors_with_modifiers() {
+        let mut buf = Buffer::empty(Rect::new(0, 0, 3, 1));
+        buf.set_string(0, 0, "a", Style::default().fg(Color::Red));
+        buf.set_string(1, 0, "b", Style::default().bg(Color::DarkGray));
+        buf.set_string(2, 0, "c", Style::default().fg(Color::Cyan));
+
+        Theme::Monochrome.apply(&mut buf);
+
+        let cells = &buf.content;
+        assert!(cells
+            .iter()
+            .all(|c| c.fg == Color::Reset && c.bg == Color::Reset));
+        assert!(cells[0]
+            .modifier
+            .contains(Modifier::BOLD | Modifier::UNDERLINED));
+        assert!(cells[1].modifier.contains(Modifier::REVERSED));
+        assert_eq!(cells[2].modifier, Modifier::empty());
+        assert_eq!(cells[2].symbol(), "c");
+    }
+
+    #[test]
+    fn monochrome_gauge_fills_from_the_left() {
+        use ratatui::widgets::{Gauge, Widget};
+
+        let area = Rect::new(0, 0, 10, 1);
+        let mut buf = Buffer::empty(area);
+        Gauge::default()
+            .gauge_style(Theme::Monochrome.gauge_style())
+            .ratio(0.3)
+            .label("")
+            .render(area, &mut buf);
+        Theme::Monochrome.apply(&mut buf);
+
+        let symbols: String = buf.content.iter().map(|c| c.symbol()).collect();
+        assert_eq!(symbols, "███       ");
+        assert!(buf
+            .content
+            .iter()
+            .all(|c| !c.modifier.contains(Modifier::REVERSED)));
+    }
+}
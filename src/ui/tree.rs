@@ -26,7 +26,19 @@ pub fn render_tree(frame: &mut Frame, app: &mut App, area: Rect) {
         return;
     }
 
-    let inner = block.inner(area);
+    // Footer line summarizing the selected entity, when there's room for it
+    let mut inner = block.inner(area);
+    let footer = app.selected_node_summary().filter(|_| inner.height > 3);
+    let footer_area = footer.as_ref().map(|_| {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(1), Constraint::Length(1)])
+            .split(inner);
+        inner = rows[0];
+        rows[1]
+    });
+    app.tree_viewport_height = inner.height as usize;
+    let flash_on = app.alert_flash_on();
 
     // Build list items from flat nodes
     let items: Vec<ListItem> = app
@@ -61,18 +73,38 @@ pub fn render_tree(frame: &mut Frame, app: &mut App, area: Rect) {
                     format!(" [{}] (💀{})", msg, dlq)
                 }
                 (Some(msg), _) => format!(" [{}]", msg),
+                (None, _) if node.counts_pending => " […]".to_string(),
                 _ => String::new(),
             };
 
+            let forward_str = node
+                .forward_to
+                .as_deref()
+                .map(|target| format!(" → {}", target))
+                .unwrap_or_default();
+
+            let alerting = app.is_dlq_alerting(&node.path);
+            let stale = app.is_entity_stale(&node.path);
             let line = format!(
-                "{}{}{} {}{}",
-                indent, expand_indicator, icon, node.label, count_str
+                "{}{}{} {}{}{}{}{}",
+                indent,
+                expand_indicator,
+                icon,
+                node.label,
+                count_str,
+                forward_str,
+                if alerting { " ⚠" } else { "" },
+                if stale { " (deleted)" } else { "" }
             );
 
             let style = if idx == app.tree_selected && is_focused {
                 Style::default().bg(Color::DarkGray).fg(Color::White).bold()
             } else if idx == app.tree_selected {
                 Style::default().fg(Color::Yellow)
+            } else if alerting && flash_on {
+                Style::default().bg(Color::Red).fg(Color::White).bold()
+            } else if alerting {
+                Style::default().fg(Color::Red).bold()
             } else {
                 match node.entity_type {
                     EntityType::DeadLetterQueue => Style::default().fg(Color::Red),
@@ -82,6 +114,13 @@ pub fn render_tree(frame: &mut Frame, app: &mut App, area: Rect) {
                     _ => Style::default(),
                 }
             };
+            let style = match (stale, idx == app.tree_selected) {
+                (true, true) => style.add_modifier(Modifier::CROSSED_OUT),
+                (true, false) => style
+                    .fg(Color::DarkGray)
+                    .add_modifier(Modifier::CROSSED_OUT | Modifier::DIM),
+                (false, _) => style,
+            };
 
             ListItem::new(Line::from(Span::styled(line, style)))
         })
@@ -104,4 +143,16 @@ pub fn render_tree(frame: &mut Frame, app: &mut App, area: Rect) {
 
     frame.render_widget(block, area);
     frame.render_stateful_widget(list, inner, &mut app.tree_list_state);
+
+    if let (Some(summary), Some(footer_area)) = (footer, footer_area) {
+        let style = if is_focused {
+            Style::default().fg(Color::Cyan)
+        } else {
+            Style::default().fg(Color::DarkGray)
+        };
+        frame.render_widget(
+            Paragraph::new(Line::from(Span::styled(summary, style))),
+            footer_area,
+        );
+    }
 }